@@ -19,6 +19,8 @@
 //! - **Validator**: G-Code validation and safety checks
 //! - **Comment Processor**: G-Code comment handling
 //! - **Statistics**: G-Code statistics and analysis
+//! - **Tool Change Feeds**: Tool library feeds and speeds applied on tool change
+//! - **Spindle Check**: Spindle speeds checked against the tool library and materials
 //!
 //! ## UI Components
 //!
@@ -33,10 +35,12 @@ pub mod laser_engraver;
 pub mod optimizer;
 pub mod stats;
 pub mod tabbed_box;
+pub mod tool_change_feeds;
 pub mod validator;
 pub mod vector_engraver;
 pub mod hatch_generator;
 pub mod speeds_feeds;
+pub mod spindle_check;
 pub mod spoilboard_surfacing;
 pub mod spoilboard_grid;
 
@@ -57,9 +61,11 @@ pub use laser_engraver::{
 };
 pub use optimizer::GCodeOptimizer;
 pub use speeds_feeds::{SpeedsFeedsCalculator, CalculationResult};
+pub use spindle_check::{check_spindle_speeds, recommended_rpm_range};
 pub use spoilboard_surfacing::{SpoilboardSurfacingGenerator, SpoilboardSurfacingParameters};
 pub use spoilboard_grid::{SpoilboardGridGenerator, SpoilboardGridParameters};
 pub use stats::StatsCalculator;
 pub use tabbed_box::{BoxParameters, BoxType, FingerJointSettings, FingerStyle, TabbedBoxMaker, KeyDividerType};
+pub use tool_change_feeds::ToolChangeFeedsProcessor;
 pub use validator::GCodeValidator;
 pub use vector_engraver::{VectorEngraver, VectorEngravingParameters};
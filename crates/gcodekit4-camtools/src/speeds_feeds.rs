@@ -8,7 +8,7 @@
 //! Feed Rate = RPM * Chip Load * Number of Flutes
 
 use gcodekit4_core::data::materials::Material;
-use gcodekit4_core::data::tools::Tool;
+use gcodekit4_core::data::tools::{Tool, ToolType};
 use gcodekit4_devicedb::model::DeviceProfile;
 
 /// Result of a speeds and feeds calculation
@@ -53,17 +53,11 @@ impl SpeedsFeedsCalculator {
         // 2. Material cutting params for this tool type (implied from RPM range?)
         // 3. Tool default params
         
-        let tool_type_key = match tool.tool_type {
-            gcodekit4_core::data::tools::ToolType::EndMillFlat => "endmill_flat",
-            gcodekit4_core::data::tools::ToolType::EndMillBall => "endmill_ball",
-            gcodekit4_core::data::tools::ToolType::VBit => "vbit",
-            gcodekit4_core::data::tools::ToolType::DrillBit => "drill",
-            _ => "generic",
-        };
+        let tool_type_key = cutting_params_key(tool.tool_type);
 
         let material_params = material.get_cutting_params(tool_type_key);
 
-        let surface_speed = if let Some(params) = material_params {
+        let mut surface_speed = if let Some(params) = material_params {
             if let Some(speed) = params.surface_speed_m_min {
                 source.push_str("Material Surface Speed");
                 speed
@@ -93,7 +87,7 @@ impl SpeedsFeedsCalculator {
         // 2. Derived from Material Feed Rate range
         // 3. Derived from Tool default Feed Rate
         
-        let chip_load = if let Some(params) = material_params {
+        let mut chip_load = if let Some(params) = material_params {
             if let Some(load) = params.chip_load_mm {
                 if !source.contains("Material") {
                     source.push_str(" + Material Chip Load");
@@ -121,6 +115,18 @@ impl SpeedsFeedsCalculator {
         // Feed = RPM * Chip Load * Flutes
         let mut feed_rate = rpm * chip_load * tool.flutes as f32;
 
+        // 4b. Material presets override the calculated values
+        if let Some(preset) = material
+            .get_feed_speed_preset(tool_type_key)
+            .filter(|preset| preset.rpm > 0)
+        {
+            rpm = preset.rpm as f32;
+            feed_rate = preset.feed_rate;
+            surface_speed = (rpm * std::f32::consts::PI * tool.diameter) / 1000.0;
+            chip_load = feed_rate / (rpm * tool.flutes as f32);
+            source = "Material Preset".to_string();
+        }
+
         // 5. Apply Device Limits
         
         // Check Max RPM
@@ -164,4 +170,13 @@ impl SpeedsFeedsCalculator {
     }
 }
 
-
+/// Key of a tool type in a material's cutting parameters and presets
+pub(crate) fn cutting_params_key(tool_type: ToolType) -> &'static str {
+    match tool_type {
+        ToolType::EndMillFlat => "endmill_flat",
+        ToolType::EndMillBall => "endmill_ball",
+        ToolType::VBit => "vbit",
+        ToolType::DrillBit => "drill",
+        _ => "generic",
+    }
+}
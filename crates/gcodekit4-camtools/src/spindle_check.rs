@@ -0,0 +1,95 @@
+//! Spindle Speed Check
+//!
+//! Flags `S` words that spin the active tool faster than the tool library and
+//! materials database recommend, or faster than the spindle can go. A program
+//! posted for a different machine or tool usually shows up here first.
+
+use gcodekit4_core::data::materials::Material;
+use gcodekit4_core::data::tools::{Tool, ToolLibrary};
+use gcodekit4_visualizer::gcode::tokenize_words;
+use gcodekit4_visualizer::{ValidationIssue, ValidationSeverity};
+
+use crate::speeds_feeds::cutting_params_key;
+
+/// Recommended spindle speed range (min, max RPM) for a tool in a material
+///
+/// The material's cutting parameters for the tool type take priority over
+/// the tool's own defaults.
+pub fn recommended_rpm_range(tool: &Tool, material: &Material) -> (u32, u32) {
+    material
+        .get_cutting_params(cutting_params_key(tool.tool_type))
+        .map(|params| params.rpm_range)
+        .unwrap_or(tool.params.rpm_range)
+}
+
+/// Check commanded spindle speeds against the active tool and material
+///
+/// `T` selects a tool and `M6` loads it; until the first `M6` the selected
+/// tool is taken as loaded, for programs that never change tools. A tool
+/// change on the same line as an `S` word applies to it. Tools missing from
+/// the library are only checked against the spindle maximum.
+///
+/// # Arguments
+/// * `src` - G-Code program text
+/// * `tools` - Tool library, looked up by tool number
+/// * `material` - Material being cut
+/// * `spindle_max_rpm` - Spindle's configured maximum (e.g. GRBL `$30`), if known
+///
+/// # Returns
+/// One warning issue per line whose `S` word exceeds the limit, with 1-based
+/// line numbers
+pub fn check_spindle_speeds(
+    src: &str,
+    tools: &ToolLibrary,
+    material: &Material,
+    spindle_max_rpm: Option<u32>,
+) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let mut selected: Option<u32> = None;
+    let mut loaded: Option<u32> = None;
+    let mut changed_tools = false;
+
+    for (index, line) in src.lines().enumerate() {
+        let words = tokenize_words(line);
+        if let Some(&(_, value)) = words.iter().find(|(letter, _)| *letter == 'T') {
+            selected = Some(value as u32);
+        }
+        if words.contains(&('M', 6.0)) {
+            loaded = selected;
+            changed_tools = true;
+        }
+        let Some(&(_, speed)) = words.iter().find(|(letter, _)| *letter == 'S') else {
+            continue;
+        };
+
+        let active = if changed_tools { loaded } else { selected };
+        let tool = active.and_then(|number| {
+            tools
+                .get_all_tools()
+                .into_iter()
+                .find(|tool| tool.number == number)
+        });
+
+        // Running past the spindle's own limit is the bigger problem
+        let message = if let Some(max) = spindle_max_rpm.filter(|&max| speed > f64::from(max)) {
+            format!("S{} exceeds the spindle maximum of {} RPM", speed, max)
+        } else if let Some((tool, max)) = tool
+            .map(|tool| (tool, recommended_rpm_range(tool, material).1))
+            .filter(|&(_, max)| speed > f64::from(max))
+        {
+            format!(
+                "S{} exceeds the recommended maximum of {} RPM for T{} ({}) in {}",
+                speed, max, tool.number, tool.name, material.name
+            )
+        } else {
+            continue;
+        };
+
+        let issue = ValidationIssue::new(index as u32 + 1, ValidationSeverity::Warning, message);
+        issues.push(
+            issue.with_suggestion("Check that the program was posted for this machine and tool"),
+        );
+    }
+
+    issues
+}
@@ -0,0 +1,178 @@
+//! Tool Change Feeds and Speeds
+//!
+//! Command processor that applies feeds and speeds from the tool library
+//! whenever a program changes tools. On `M6` the selected tool is looked up,
+//! the speeds and feeds calculator is run against the active material, and the
+//! recommended spindle speed is injected. Until the next tool change every `S`
+//! word is replaced with the recommendation and `F` words are capped to the
+//! recommended feed rate.
+
+use std::sync::Mutex;
+
+use gcodekit4_core::data::materials::Material;
+use gcodekit4_core::data::tools::{Tool, ToolLibrary};
+use gcodekit4_devicedb::model::DeviceProfile;
+use gcodekit4_visualizer::gcode::{
+    tokenize_words, CommandProcessor, GcodeCommand, GcodeState, ProcessorConfig,
+};
+use regex::{Captures, Regex};
+
+use crate::speeds_feeds::{CalculationResult, SpeedsFeedsCalculator};
+
+/// Recommended spindle speed and feed cap for the loaded tool
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ActiveFeeds {
+    rpm: u32,
+    feed_rate: f32,
+}
+
+/// Tool selection tracked across commands
+#[derive(Debug, Default)]
+struct ToolChangeState {
+    /// Tool selected by the last `T` word
+    selected: Option<u32>,
+    /// Feeds for the tool loaded by the last `M6`, `None` if it is unknown
+    active: Option<ActiveFeeds>,
+}
+
+/// Substitutes feeds and speeds from the tool library on tool change
+pub struct ToolChangeFeedsProcessor {
+    tools: ToolLibrary,
+    material: Material,
+    device: DeviceProfile,
+    config: ProcessorConfig,
+    state: Mutex<ToolChangeState>,
+}
+
+impl ToolChangeFeedsProcessor {
+    /// Create a processor for a tool library, material and machine
+    pub fn new(tools: ToolLibrary, material: Material, device: DeviceProfile) -> Self {
+        Self {
+            tools,
+            material,
+            device,
+            config: ProcessorConfig::new(),
+            state: Mutex::new(ToolChangeState::default()),
+        }
+    }
+
+    /// Look up a tool by its tool number
+    pub fn tool(&self, number: u32) -> Option<&Tool> {
+        self.tools
+            .get_all_tools()
+            .into_iter()
+            .find(|tool| tool.number == number)
+    }
+
+    /// Calculate the recommendation for a tool number with the active material
+    pub fn recommendation(&self, number: u32) -> Option<CalculationResult> {
+        self.tool(number)
+            .map(|tool| SpeedsFeedsCalculator::calculate(&self.material, tool, &self.device))
+    }
+
+    /// Forget the loaded tool, e.g. before processing a new program
+    pub fn reset(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            *state = ToolChangeState::default();
+        }
+    }
+
+    /// Replace `S` words and cap `F` words in the code part of a line
+    fn substitute(line: &str, feeds: ActiveFeeds) -> String {
+        static WORD_REGEX: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+        let regex = WORD_REGEX
+            .get_or_init(|| Regex::new(r"(?i)([SF])\s*([-+]?\d*\.?\d+)").unwrap());
+
+        let split = line.find([';', '(']).unwrap_or(line.len());
+        let (code, comment) = line.split_at(split);
+
+        let code = regex.replace_all(code, |caps: &Captures| {
+            let value: f32 = caps[2].parse().unwrap_or(0.0);
+            if caps[1].eq_ignore_ascii_case("S") {
+                format!("S{}", feeds.rpm)
+            } else if value > feeds.feed_rate {
+                format!("F{:.0}", feeds.feed_rate)
+            } else {
+                caps[0].to_string()
+            }
+        });
+
+        format!("{}{}", code, comment)
+    }
+}
+
+impl CommandProcessor for ToolChangeFeedsProcessor {
+    fn name(&self) -> &str {
+        "tool_change_feeds"
+    }
+
+    fn description(&self) -> &str {
+        "Applies tool library feeds and speeds after each tool change"
+    }
+
+    fn process(
+        &self,
+        command: &GcodeCommand,
+        _state: &GcodeState,
+    ) -> Result<Vec<GcodeCommand>, String> {
+        let words = tokenize_words(&command.command);
+        let tool_word = words
+            .iter()
+            .find(|(letter, _)| *letter == 'T')
+            .map(|&(_, value)| value as u32);
+        let tool_change = words.contains(&('M', 6.0));
+
+        let mut state = self.state.lock().map_err(|e| e.to_string())?;
+        if tool_word.is_some() {
+            state.selected = tool_word;
+        }
+
+        if tool_change {
+            let Some(number) = state.selected else {
+                state.active = None;
+                return Ok(vec![command.clone()]);
+            };
+
+            state.active = self.recommendation(number).map(|result| ActiveFeeds {
+                rpm: result.rpm,
+                feed_rate: result.feed_rate,
+            });
+
+            return Ok(match state.active {
+                Some(feeds) => {
+                    tracing::debug!(
+                        "Tool T{} loaded: S{} F{:.0}",
+                        number,
+                        feeds.rpm,
+                        feeds.feed_rate
+                    );
+                    vec![
+                        command.clone(),
+                        GcodeCommand::new(format!("S{}", feeds.rpm)),
+                    ]
+                }
+                None => {
+                    tracing::warn!("Tool T{} not found in tool library", number);
+                    vec![command.clone()]
+                }
+            });
+        }
+
+        match state.active {
+            Some(feeds) => {
+                let mut processed = command.clone();
+                processed.command = Self::substitute(&command.command, feeds);
+                Ok(vec![processed])
+            }
+            None => Ok(vec![command.clone()]),
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    fn config(&self) -> &ProcessorConfig {
+        &self.config
+    }
+}
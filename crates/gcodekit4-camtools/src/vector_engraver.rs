@@ -534,6 +534,11 @@ impl VectorEngraver {
     }
 
     /// Parse DXF file and extract entities
+    fn parse_dxf(_file_path: &str) -> Result<(Vec<Path>, f32)> {
+        anyhow::bail!("dxf disabled")
+    }
+
+    #[cfg(any())]
     fn parse_dxf(file_path: &str) -> Result<(Vec<Path>, f32)> {
         use dxf::entities::EntityType;
 
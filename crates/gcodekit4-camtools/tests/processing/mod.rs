@@ -5,4 +5,6 @@ pub mod tabbed_box_debug;
 pub mod tabbed_box_user_bug;
 pub mod comment_processor;
 pub mod speeds_feeds;
+pub mod spindle_check;
 pub mod validator;
+pub mod tool_change_feeds;
@@ -1,5 +1,5 @@
 use gcodekit4_camtools::speeds_feeds::SpeedsFeedsCalculator;
-use gcodekit4_core::data::materials::{Material, MaterialCategory, MaterialId};
+use gcodekit4_core::data::materials::{FeedSpeedPreset, Material, MaterialCategory, MaterialId};
 use gcodekit4_core::data::tools::{Tool, ToolId, ToolType};
 use gcodekit4_devicedb::model::DeviceProfile;
 
@@ -68,3 +68,80 @@ fn test_calculation_with_material_properties() {
     
     assert!(result.source.contains("Material Surface Speed"));
 }
+
+#[test]
+fn test_material_preset_overrides_calculation() {
+    let mut material = Material::new(
+        MaterialId("test".to_string()),
+        "Test".to_string(),
+        MaterialCategory::Wood,
+        "Test".to_string(),
+    );
+
+    let params = gcodekit4_core::data::materials::CuttingParameters {
+        surface_speed_m_min: Some(300.0),
+        chip_load_mm: Some(0.1),
+        ..Default::default()
+    };
+    material.set_cutting_params("endmill_flat".to_string(), params);
+    material.set_feed_speed_preset(
+        "endmill_flat".to_string(),
+        FeedSpeedPreset {
+            rpm: 12000,
+            feed_rate: 1800.0,
+        },
+    );
+
+    let tool = Tool::new(
+        ToolId("test".to_string()),
+        1,
+        "Test".to_string(),
+        ToolType::EndMillFlat,
+        6.35,
+        50.0,
+    );
+
+    let device = DeviceProfile {
+        max_feed_rate: 5000.0,
+        ..DeviceProfile::default()
+    };
+
+    let result = SpeedsFeedsCalculator::calculate(&material, &tool, &device);
+
+    assert_eq!(result.rpm, 12000);
+    assert_eq!(result.feed_rate, 1800.0);
+    // Chip load = 1800 / (12000 * 2)
+    assert!((result.chip_load - 0.075).abs() < 1e-6);
+    assert_eq!(result.source, "Material Preset");
+    assert!(result.warnings.is_empty());
+}
+
+#[test]
+fn test_preset_for_other_tool_type_is_ignored() {
+    let mut material = Material::new(
+        MaterialId("test".to_string()),
+        "Test".to_string(),
+        MaterialCategory::Wood,
+        "Test".to_string(),
+    );
+    material.set_feed_speed_preset(
+        "vbit".to_string(),
+        FeedSpeedPreset {
+            rpm: 12000,
+            feed_rate: 1800.0,
+        },
+    );
+
+    let tool = Tool::new(
+        ToolId("test".to_string()),
+        1,
+        "Test".to_string(),
+        ToolType::EndMillFlat,
+        6.35,
+        50.0,
+    );
+
+    let result = SpeedsFeedsCalculator::calculate(&material, &tool, &DeviceProfile::default());
+
+    assert!(result.source.contains("Tool Defaults"));
+}
@@ -0,0 +1,88 @@
+use gcodekit4_camtools::{check_spindle_speeds, recommended_rpm_range};
+use gcodekit4_core::data::materials::{CuttingParameters, Material, MaterialCategory, MaterialId};
+use gcodekit4_core::data::tools::{Tool, ToolId, ToolLibrary, ToolType};
+use gcodekit4_visualizer::ValidationSeverity;
+
+fn material() -> Material {
+    let mut material = Material::new(
+        MaterialId("oak".to_string()),
+        "Oak".to_string(),
+        MaterialCategory::Wood,
+        "Hardwood".to_string(),
+    );
+    let params = CuttingParameters {
+        rpm_range: (16000, 20000),
+        ..Default::default()
+    };
+    material.set_cutting_params("endmill_flat".to_string(), params);
+    material
+}
+
+fn library() -> ToolLibrary {
+    let mut library = ToolLibrary::new();
+    library.add_tool(Tool::new(
+        ToolId("t1".to_string()),
+        1,
+        "6mm End Mill".to_string(),
+        ToolType::EndMillFlat,
+        6.0,
+        50.0,
+    ));
+    let mut drill = Tool::new(
+        ToolId("t2".to_string()),
+        2,
+        "3mm Drill".to_string(),
+        ToolType::DrillBit,
+        3.0,
+        40.0,
+    );
+    drill.params.rpm_range = (2000, 4000);
+    library.add_tool(drill);
+    library
+}
+
+#[test]
+fn test_recommended_range_prefers_material_params() {
+    let tools = library();
+    let material = material();
+
+    let end_mill = tools.get_tool(&ToolId("t1".to_string())).unwrap();
+    let drill = tools.get_tool(&ToolId("t2".to_string())).unwrap();
+    assert_eq!(recommended_rpm_range(end_mill, &material), (16000, 20000));
+    // No drill parameters for oak, so the tool's own range applies
+    assert_eq!(recommended_rpm_range(drill, &material), (2000, 4000));
+}
+
+#[test]
+fn test_spindle_speed_beyond_recommended_range_is_flagged() {
+    let program = "T1 M6\nM3 S18000\nG1 X10 F800\nT2 M6\nM3 S12000\nM5";
+
+    let issues = check_spindle_speeds(program, &library(), &material(), None);
+
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line_number, 5);
+    assert_eq!(issues[0].severity, ValidationSeverity::Warning);
+    assert!(issues[0].message.contains("4000"));
+    assert!(issues[0].message.contains("T2"));
+}
+
+#[test]
+fn test_spindle_speed_beyond_spindle_max_is_flagged() {
+    // T1 allows 20000 RPM in oak, but this spindle tops out at 12000
+    let program = "T1 M6\nS18000 M3\nS30000";
+
+    let issues = check_spindle_speeds(program, &library(), &material(), Some(12000));
+
+    assert_eq!(issues.len(), 2);
+    assert_eq!(issues[0].line_number, 2);
+    assert!(issues[0].message.contains("spindle maximum"));
+    assert_eq!(issues[1].line_number, 3);
+}
+
+#[test]
+fn test_spindle_speeds_within_range_pass() {
+    let program = "T1\nM3 S18000\nG1 X10 F800\nM5\nT99 M6\nM3 S30000";
+
+    // T1 is active without an M6, and unknown tools aren't checked
+    assert!(check_spindle_speeds(program, &library(), &material(), None).is_empty());
+}
@@ -0,0 +1,105 @@
+use gcodekit4_camtools::speeds_feeds::SpeedsFeedsCalculator;
+use gcodekit4_camtools::ToolChangeFeedsProcessor;
+use gcodekit4_core::data::materials::{CuttingParameters, Material, MaterialCategory, MaterialId};
+use gcodekit4_core::data::tools::{Tool, ToolId, ToolLibrary, ToolType};
+use gcodekit4_devicedb::model::DeviceProfile;
+use gcodekit4_visualizer::gcode::{CommandProcessor, GcodeCommand, GcodeState};
+
+fn material() -> Material {
+    let mut material = Material::new(
+        MaterialId("oak".to_string()),
+        "Oak".to_string(),
+        MaterialCategory::Wood,
+        "Hardwood".to_string(),
+    );
+    let params = CuttingParameters {
+        surface_speed_m_min: Some(250.0),
+        chip_load_mm: Some(0.05),
+        ..Default::default()
+    };
+    material.set_cutting_params("endmill_flat".to_string(), params);
+    material
+}
+
+fn library() -> ToolLibrary {
+    let mut library = ToolLibrary::new();
+    library.add_tool(Tool::new(
+        ToolId("t1".to_string()),
+        1,
+        "6mm End Mill".to_string(),
+        ToolType::EndMillFlat,
+        6.0,
+        50.0,
+    ));
+    library.add_tool(Tool::new(
+        ToolId("t2".to_string()),
+        2,
+        "3mm End Mill".to_string(),
+        ToolType::EndMillFlat,
+        3.0,
+        40.0,
+    ));
+    library
+}
+
+fn device() -> DeviceProfile {
+    DeviceProfile {
+        max_feed_rate: 5000.0,
+        ..DeviceProfile::default()
+    }
+}
+
+fn run(processor: &ToolChangeFeedsProcessor, line: &str) -> Vec<String> {
+    processor
+        .process(&GcodeCommand::new(line), &GcodeState::new())
+        .unwrap()
+        .into_iter()
+        .map(|c| c.command)
+        .collect()
+}
+
+#[test]
+fn test_tool_change_injects_recommended_speed() {
+    let processor = ToolChangeFeedsProcessor::new(library(), material(), device());
+    let tools = library();
+    let tool = tools
+        .get_all_tools()
+        .into_iter()
+        .find(|t| t.number == 2)
+        .unwrap();
+    let expected = SpeedsFeedsCalculator::calculate(&material(), tool, &device());
+
+    assert_eq!(
+        run(&processor, "M6 T2"),
+        vec!["M6 T2".to_string(), format!("S{}", expected.rpm)]
+    );
+    assert_eq!(
+        run(&processor, "M3 S24000"),
+        vec![format!("M3 S{}", expected.rpm)]
+    );
+}
+
+#[test]
+fn test_tool_change_caps_feed_until_next_change() {
+    let processor = ToolChangeFeedsProcessor::new(library(), material(), device());
+    let feed = processor.recommendation(2).unwrap().feed_rate;
+
+    run(&processor, "T2");
+    run(&processor, "M6");
+    assert_eq!(
+        run(&processor, "G1 X10 F99999 ; fast"),
+        vec![format!("G1 X10 F{:.0} ; fast", feed)]
+    );
+    assert_eq!(run(&processor, "G1 X20 F10"), vec!["G1 X20 F10"]);
+
+    // Unknown tools leave the program untouched
+    assert_eq!(run(&processor, "M6 T9"), vec!["M6 T9"]);
+    assert_eq!(run(&processor, "G1 X30 F99999"), vec!["G1 X30 F99999"]);
+}
+
+#[test]
+fn test_tool_change_passthrough_before_first_change() {
+    let processor = ToolChangeFeedsProcessor::new(library(), material(), device());
+
+    assert_eq!(run(&processor, "M3 S12000"), vec!["M3 S12000"]);
+}
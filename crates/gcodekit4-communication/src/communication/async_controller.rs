@@ -0,0 +1,262 @@
+//! Background connection task with a channel-based command API
+//!
+//! `AsyncController` moves a [`Communicator`] onto a tokio task so the UI
+//! never waits on the port. Blocking reads run on tokio's blocking pool. Commands arrive through a [`ControllerChannel`]
+//! and are sent one at a time; each resolves when the controller answers it
+//! with `ok`, `error:` or `ALARM:`. Lines that aren't part of an answer, such
+//! as status reports and startup banners, go to [`subscribe`] receivers.
+//!
+//! [`subscribe`]: AsyncController::subscribe
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use gcodekit4_core::{CommandRequest, CommandResponse, ControllerChannel};
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+use super::Communicator;
+
+/// Unsolicited lines kept for slow subscribers
+const MESSAGE_BUFFER: usize = 256;
+
+/// Timing of the background connection task
+#[derive(Debug, Clone, Copy)]
+pub struct AsyncControllerConfig {
+    /// Pause between reads when the controller has nothing to say
+    pub poll_interval: Duration,
+    /// How long a command may go unanswered before it fails
+    pub response_timeout: Duration,
+}
+
+impl Default for AsyncControllerConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_millis(10),
+            response_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+/// The command currently waiting for its answer
+struct InFlight {
+    request: CommandRequest,
+    lines: Vec<String>,
+    sent_at: Instant,
+}
+
+/// Handle to a connection running on a background task
+///
+/// Must be created inside a tokio runtime. Dropping the handle stops the task
+/// and fails any commands still queued.
+pub struct AsyncController {
+    channel: ControllerChannel,
+    realtime: mpsc::UnboundedSender<u8>,
+    messages: broadcast::Sender<String>,
+    shutdown: Option<oneshot::Sender<()>>,
+    task: Option<JoinHandle<Box<dyn Communicator>>>,
+}
+
+impl AsyncController {
+    /// Start the background task with the default timing
+    pub fn spawn(communicator: Box<dyn Communicator>) -> Self {
+        Self::spawn_with_config(communicator, AsyncControllerConfig::default())
+    }
+
+    /// Start the background task
+    ///
+    /// The communicator should already be connected.
+    pub fn spawn_with_config(
+        communicator: Box<dyn Communicator>,
+        config: AsyncControllerConfig,
+    ) -> Self {
+        let (channel, requests) = ControllerChannel::new();
+        let (realtime, realtime_rx) = mpsc::unbounded_channel();
+        let (messages, _) = broadcast::channel(MESSAGE_BUFFER);
+        let (shutdown, shutdown_rx) = oneshot::channel();
+
+        let task = tokio::spawn(run(
+            communicator,
+            config,
+            requests,
+            realtime_rx,
+            messages.clone(),
+            shutdown_rx,
+        ));
+
+        Self {
+            channel,
+            realtime,
+            messages,
+            shutdown: Some(shutdown),
+            task: Some(task),
+        }
+    }
+
+    /// A channel for queueing commands, e.g. to hand to a `SimpleController`
+    pub fn channel(&self) -> ControllerChannel {
+        self.channel.clone()
+    }
+
+    /// Queue a command without waiting for it
+    ///
+    /// The returned receiver resolves with the controller's answer.
+    pub fn send(&self, command: &str) -> oneshot::Receiver<CommandResponse> {
+        self.channel.send(command)
+    }
+
+    /// Send a real-time byte (e.g. `?` or feed hold) ahead of queued commands
+    pub fn send_realtime(&self, byte: u8) {
+        let _ = self.realtime.send(byte);
+    }
+
+    /// Receive lines that don't answer a command, such as status reports
+    pub fn subscribe(&self) -> broadcast::Receiver<String> {
+        self.messages.subscribe()
+    }
+
+    /// Stop the task and take the communicator back
+    ///
+    /// Returns `None` if the task panicked.
+    pub async fn shutdown(mut self) -> Option<Box<dyn Communicator>> {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        self.task.take()?.await.ok()
+    }
+}
+
+impl Drop for AsyncController {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+/// Whether a line ends the answer to a command, and with what error
+pub(super) fn completion(line: &str) -> Option<Option<String>> {
+    if line == "ok" {
+        Some(None)
+    } else if line.starts_with("error:") || line.starts_with("ALARM:") {
+        Some(Some(line.to_string()))
+    } else {
+        None
+    }
+}
+
+/// Body of the background task
+async fn run(
+    mut communicator: Box<dyn Communicator>,
+    config: AsyncControllerConfig,
+    mut requests: mpsc::UnboundedReceiver<CommandRequest>,
+    mut realtime: mpsc::UnboundedReceiver<u8>,
+    messages: broadcast::Sender<String>,
+    mut shutdown: oneshot::Receiver<()>,
+) -> Box<dyn Communicator> {
+    let mut queue: VecDeque<CommandRequest> = VecDeque::new();
+    let mut in_flight: Option<InFlight> = None;
+    let mut buffer = String::new();
+
+    loop {
+        // A dropped handle stops the task just like an explicit shutdown
+        if !matches!(
+            shutdown.try_recv(),
+            Err(oneshot::error::TryRecvError::Empty)
+        ) {
+            break;
+        }
+
+        while let Ok(byte) = realtime.try_recv() {
+            if let Err(e) = communicator.send(&[byte]) {
+                tracing::warn!("Failed to send real-time byte 0x{:02X}: {}", byte, e);
+            }
+        }
+        while let Ok(request) = requests.try_recv() {
+            queue.push_back(request);
+        }
+
+        if in_flight.is_none() {
+            if let Some(request) = queue.pop_front() {
+                match communicator.send_command(&request.command) {
+                    Ok(()) => {
+                        in_flight = Some(InFlight {
+                            request,
+                            lines: Vec::new(),
+                            sent_at: Instant::now(),
+                        })
+                    }
+                    Err(e) => request.fail(e.to_string()),
+                }
+            }
+        }
+
+        // Reads block for up to the port timeout, so keep them off the runtime's workers
+        let (returned, received) = match tokio::task::spawn_blocking(move || {
+            let received = communicator.receive();
+            (communicator, received)
+        })
+        .await
+        {
+            Ok(result) => result,
+            Err(e) => std::panic::resume_unwind(e.into_panic()),
+        };
+        communicator = returned;
+        let data = match received {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::warn!("Controller read failed: {}", e);
+                Vec::new()
+            }
+        };
+        buffer.push_str(&String::from_utf8_lossy(&data));
+        while let Some(pos) = buffer.find('\n') {
+            let line = buffer[..pos].trim().to_string();
+            buffer.drain(..=pos);
+            if line.is_empty() {
+                continue;
+            }
+
+            // Status reports can arrive in the middle of any answer
+            let Some(current) = in_flight.as_mut().filter(|_| !line.starts_with('<')) else {
+                let _ = messages.send(line);
+                continue;
+            };
+            match completion(&line) {
+                Some(error) => {
+                    let InFlight { request, lines, .. } = in_flight.take().unwrap();
+                    let response = match error {
+                        Some(error) => CommandResponse::failed(&request.command, lines, error),
+                        None => CommandResponse::ok(&request.command, lines),
+                    };
+                    request.respond(response);
+                }
+                None => current.lines.push(line),
+            }
+        }
+
+        if let Some(current) = &in_flight {
+            if current.sent_at.elapsed() >= config.response_timeout {
+                let InFlight { request, .. } = in_flight.take().unwrap();
+                let error = format!("Timed out waiting for a response to '{}'", request.command);
+                request.fail(error);
+            }
+        }
+
+        if data.is_empty() {
+            tokio::time::sleep(config.poll_interval).await;
+        } else {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    let pending = in_flight.map(|current| current.request).into_iter();
+    for request in pending
+        .chain(queue)
+        .chain(std::iter::from_fn(|| requests.try_recv().ok()))
+    {
+        request.fail("Controller connection closed");
+    }
+    communicator
+}
@@ -0,0 +1,93 @@
+//! Sending a custom macro as one batch
+//!
+//! [`AsyncController::run_macro`] expands a [`CustomMacro`], queues every
+//! line at once and waits for all of the answers, then sums them up in a
+//! [`MacroRunResult`] that points at the line the controller rejected, if
+//! any.
+
+use std::collections::HashMap;
+
+use gcodekit4_core::CommandResponse;
+use gcodekit4_visualizer::CustomMacro;
+
+use super::AsyncController;
+
+/// Outcome of running a macro
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MacroRunResult {
+    /// Name of the macro that ran
+    pub name: String,
+    /// The answer to each command sent, in order
+    pub responses: Vec<CommandResponse>,
+    /// 1-based line of the expanded macro each response belongs to
+    pub line_numbers: Vec<usize>,
+    /// 1-based line of the first command the controller rejected
+    pub failed_line: Option<usize>,
+}
+
+impl MacroRunResult {
+    /// Check whether every command was accepted
+    pub fn is_ok(&self) -> bool {
+        self.failed_line.is_none()
+    }
+
+    /// The answer to the first rejected command
+    pub fn failure(&self) -> Option<&CommandResponse> {
+        self.responses.iter().find(|response| !response.is_ok())
+    }
+}
+
+impl AsyncController {
+    /// Expand a macro and send it, waiting until every line is answered
+    ///
+    /// `vars` are applied on top of the macro's own variables. Blank lines
+    /// and comment-only lines (`;` or `(...)`) are skipped. All lines are
+    /// queued before the first answer is awaited, so a rejected line doesn't
+    /// stop the ones after it, just as when streaming a program.
+    ///
+    /// # Arguments
+    /// * `custom_macro` - Macro to run
+    /// * `vars` - Variable values for this run
+    ///
+    /// # Returns
+    /// The responses to all lines sent, with the first failing line if any
+    pub async fn run_macro(
+        &self,
+        custom_macro: &CustomMacro,
+        vars: &HashMap<String, String>,
+    ) -> MacroRunResult {
+        let mut expanded = custom_macro.clone();
+        for (name, value) in vars {
+            expanded.set_variable(name.clone(), value.clone());
+        }
+        let code = expanded.expand();
+
+        let pending: Vec<_> = code
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with([';', '(']))
+            .map(|(number, line)| (number, line.to_string(), self.send(line)))
+            .collect();
+
+        let mut result = MacroRunResult {
+            name: custom_macro.name.clone(),
+            responses: Vec::with_capacity(pending.len()),
+            line_numbers: Vec::with_capacity(pending.len()),
+            failed_line: None,
+        };
+        for (number, command, receiver) in pending {
+            let response = receiver.await.unwrap_or_else(|_| {
+                let error = format!("Controller dropped command '{}'", command);
+                CommandResponse::failed(&command, Vec::new(), error)
+            });
+            if !response.is_ok() && result.failed_line.is_none() {
+                result.failed_line = Some(number);
+            }
+            result.responses.push(response);
+            result.line_numbers.push(number);
+        }
+
+        result
+    }
+}
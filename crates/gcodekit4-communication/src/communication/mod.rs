@@ -8,20 +8,35 @@
 //! - TCP/IP network communication  
 //! - WebSocket communication
 //! - Event callbacks for connection state changes
+//! - Background connection task with a non-blocking command API
+//! - Streaming piped G-code as it arrives
+//! - Startup command block run after connecting
+//! - Custom macros sent as one batch
 //! - Configurable connection parameters
 
+pub mod async_controller;
 pub mod buffered;
+pub mod macro_run;
+pub mod pipe;
 pub mod serial;
+pub mod startup;
 pub mod tcp;
 
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::sync::Arc;
+use std::time::Duration;
 
+pub use async_controller::{AsyncController, AsyncControllerConfig};
 pub use buffered::{
     BufferedCommand, BufferedCommunicatorConfig, BufferedCommunicatorWrapper, CommandStatus,
 };
-pub use serial::{list_ports, SerialPortInfo};
+pub use macro_run::MacroRunResult;
+pub use pipe::{stream_lines, wait_for_ready};
+pub use serial::{
+    apply_control_lines, list_ports, ports_from_enumeration, ControlLines, SerialPortInfo,
+};
+pub use startup::run_startup_commands;
 pub use tcp::TcpConnectionInfo;
 
 /// Connection driver type
@@ -86,6 +101,19 @@ pub struct ConnectionParams {
 
     /// Maximum number of reconnection attempts before giving up
     pub max_retries: u32,
+
+    /// DTR level to set when the port is opened (serial only)
+    ///
+    /// `None` leaves the line as the OS driver sets it. Many Arduino-based
+    /// boards reset when DTR is asserted on open.
+    #[serde(default)]
+    pub dtr_on_open: Option<bool>,
+
+    /// RTS level to set when the port is opened (serial only)
+    ///
+    /// `None` leaves the line as the OS driver sets it.
+    #[serde(default)]
+    pub rts_on_open: Option<bool>,
 }
 
 /// Serial port parity setting
@@ -114,6 +142,8 @@ impl Default for ConnectionParams {
             parity: SerialParity::None,
             auto_reconnect: true,
             max_retries: 3,
+            dtr_on_open: None,
+            rts_on_open: None,
         }
     }
 }
@@ -149,6 +179,26 @@ impl ConnectionParams {
         }
     }
 
+    /// Set the DTR and RTS levels applied when the serial port is opened
+    pub fn with_control_lines(mut self, dtr: Option<bool>, rts: Option<bool>) -> Self {
+        self.dtr_on_open = dtr;
+        self.rts_on_open = rts;
+        self
+    }
+
+    /// Open the serial port without resetting the board
+    ///
+    /// Keeps DTR and RTS deasserted so boards that reset on a DTR edge
+    /// retain their state across connections.
+    pub fn with_no_reset(self) -> Self {
+        self.with_control_lines(Some(false), Some(false))
+    }
+
+    /// Check whether the port will be opened in no-reset mode
+    pub fn is_no_reset(&self) -> bool {
+        self.dtr_on_open == Some(false) && self.rts_on_open == Some(false)
+    }
+
     /// Validate the connection parameters
     pub fn validate(&self) -> gcodekit4_core::Result<()> {
         match self.driver {
@@ -294,6 +344,46 @@ pub trait Communicator: Send + Sync {
     /// Set connection parameters (without connecting)
     fn set_connection_params(&mut self, params: ConnectionParams) -> gcodekit4_core::Result<()>;
 
+    /// Re-open a dropped connection with the stored parameters
+    ///
+    /// Tries up to `max_retries` times, waiting `retry_delay` between
+    /// attempts. Fails straight away if the parameters have
+    /// `auto_reconnect` turned off.
+    ///
+    /// Returns the number of attempts it took
+    fn reconnect(&mut self, retry_delay: Duration) -> gcodekit4_core::Result<u32> {
+        let params = self
+            .connection_params()
+            .cloned()
+            .ok_or_else(|| gcodekit4_core::Error::other("No connection to reconnect"))?;
+        if !params.auto_reconnect {
+            return Err(gcodekit4_core::Error::other(
+                "Automatic reconnection is disabled",
+            ));
+        }
+
+        let _ = self.disconnect();
+        let attempts = params.max_retries.max(1);
+        let mut attempt = 1;
+        loop {
+            match self.connect(&params) {
+                Ok(()) => return Ok(attempt),
+                Err(e) if attempt >= attempts => return Err(e),
+                Err(e) => {
+                    tracing::warn!(
+                        "Reconnect attempt {}/{} to {} failed: {}",
+                        attempt,
+                        attempts,
+                        params.port,
+                        e
+                    );
+                    std::thread::sleep(retry_delay);
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
     /// Get the connection driver type
     fn driver_type(&self) -> ConnectionDriver {
         self.connection_params()
@@ -0,0 +1,181 @@
+//! Streaming G-code from a pipe
+//!
+//! External CAM post-processors can pipe their output straight to the
+//! controller. [`stream_lines`] reads a program line by line and hands each
+//! line to a [`BufferedCommunicatorWrapper`] as soon as it arrives, so a slow
+//! producer is streamed while it is still writing and the whole program is
+//! never held in memory. [`wait_for_ready`] holds the stream back until the
+//! controller has finished starting up.
+
+use std::io::BufRead;
+use std::time::{Duration, Instant};
+
+use gcodekit4_visualizer::gcode::strip_comments;
+
+use super::async_controller::completion;
+use super::buffered::BufferedCommunicatorWrapper;
+use super::Communicator;
+
+/// Pause between reads while waiting for the controller
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Stream a program from a reader to the controller
+///
+/// Lines that are empty once comments are stripped are skipped. Each line is
+/// sent once the controller's receive buffer has room for it, and the stream
+/// ends when the reader is exhausted and every line sent has been answered.
+/// It stops at the first line the controller rejects, or when the controller
+/// goes `timeout` without answering while lines are outstanding.
+///
+/// # Arguments
+/// * `reader` - Program source, e.g. locked stdin
+/// * `sender` - Buffered sender wrapping a connected communicator
+/// * `timeout` - How long the controller may go without answering
+///
+/// # Returns
+/// The number of lines sent
+pub fn stream_lines<R: BufRead>(
+    reader: R,
+    sender: &mut BufferedCommunicatorWrapper,
+    timeout: Duration,
+) -> gcodekit4_core::Result<usize> {
+    let mut buffer = String::new();
+    let mut sent = 0;
+
+    for line in reader.lines() {
+        let line = line.map_err(|e| gcodekit4_core::Error::other(e.to_string()))?;
+        let line = line.trim();
+        if strip_comments(line).trim().is_empty() {
+            continue;
+        }
+
+        sender.queue_command(line.to_string())?;
+        while sender.queued_commands_count()? > 0 {
+            sender.stream_commands()?;
+            if sender.queued_commands_count()? == 0 {
+                break;
+            }
+            if sender.active_commands_count()? == 0 {
+                return Err(gcodekit4_core::Error::other(format!(
+                    "Line is longer than the controller's receive buffer: {}",
+                    line
+                )));
+            }
+            wait_for_answer(sender, &mut buffer, timeout)?;
+        }
+        sent += 1;
+        read_answers(sender, &mut buffer)?;
+    }
+
+    while sender.active_commands_count()? > 0 {
+        wait_for_answer(sender, &mut buffer, timeout)?;
+    }
+
+    Ok(sent)
+}
+
+/// Wait until a freshly connected controller can take commands
+///
+/// Opening the port resets most boards, and GRBL drops anything it receives
+/// before printing its `Grbl ...` banner. Boards that don't reset never print
+/// one, so if no banner has arrived after `poll_after` an empty line is sent,
+/// and its `ok` shows the controller is listening. Only one poll is sent, and
+/// its answer is waited for even after a banner, so no stray `ok` is left to
+/// be taken for the answer to a streamed line.
+///
+/// # Arguments
+/// * `communicator` - A connected communicator
+/// * `poll_after` - How long to wait for a banner before polling
+/// * `timeout` - How long the controller may take to become ready
+pub fn wait_for_ready(
+    communicator: &mut dyn Communicator,
+    poll_after: Duration,
+    timeout: Duration,
+) -> gcodekit4_core::Result<()> {
+    let started = Instant::now();
+    let mut buffer = String::new();
+    let mut polled = false;
+    let mut banner = false;
+
+    loop {
+        let data = communicator.receive()?;
+        buffer.push_str(&String::from_utf8_lossy(&data));
+        while let Some(pos) = buffer.find('\n') {
+            let line = buffer[..pos].trim().to_string();
+            buffer.drain(..=pos);
+            if line == "ok" {
+                return Ok(());
+            }
+            banner |= line.starts_with("Grbl ");
+        }
+        if banner && !polled {
+            return Ok(());
+        }
+
+        let elapsed = started.elapsed();
+        if elapsed >= timeout {
+            // A poll sent during the reset is lost, so the banner is enough
+            if banner {
+                return Ok(());
+            }
+            return Err(gcodekit4_core::Error::other(
+                "Timed out waiting for the controller to start",
+            ));
+        }
+        if !polled && elapsed >= poll_after {
+            communicator.send(b"\n")?;
+            polled = true;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Read from the controller until at least one line sent has been answered
+fn wait_for_answer(
+    sender: &mut BufferedCommunicatorWrapper,
+    buffer: &mut String,
+    timeout: Duration,
+) -> gcodekit4_core::Result<()> {
+    let started = Instant::now();
+    while read_answers(sender, buffer)? == 0 {
+        if started.elapsed() >= timeout {
+            return Err(gcodekit4_core::Error::other(
+                "Timed out waiting for the controller to answer",
+            ));
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    Ok(())
+}
+
+/// Handle whatever the controller has answered so far
+///
+/// Returns the number of lines acknowledged, and fails on the first rejected
+/// line.
+fn read_answers(
+    sender: &mut BufferedCommunicatorWrapper,
+    buffer: &mut String,
+) -> gcodekit4_core::Result<usize> {
+    let data = sender.communicator_mut().receive()?;
+    buffer.push_str(&String::from_utf8_lossy(&data));
+
+    let mut answered = 0;
+    while let Some(pos) = buffer.find('\n') {
+        let line = buffer[..pos].trim().to_string();
+        buffer.drain(..=pos);
+        match completion(&line) {
+            Some(Some(error)) => {
+                return Err(gcodekit4_core::Error::other(format!(
+                    "Controller rejected a streamed line: {}",
+                    error
+                )));
+            }
+            Some(None) => {
+                sender.handle_acknowledgment()?;
+                answered += 1;
+            }
+            None => {}
+        }
+    }
+    Ok(answered)
+}
@@ -54,6 +54,9 @@ pub struct SerialPortInfo {
     /// Manufacturer name if available
     pub manufacturer: Option<String>,
 
+    /// Product name if available
+    pub product: Option<String>,
+
     /// Serial number if available
     pub serial_number: Option<String>,
 
@@ -71,6 +74,7 @@ impl SerialPortInfo {
             port_name: port_name.into(),
             description: description.into(),
             manufacturer: None,
+            product: None,
             serial_number: None,
             vid: None,
             pid: None,
@@ -83,6 +87,12 @@ impl SerialPortInfo {
         self
     }
 
+    /// Set product name
+    pub fn with_product(mut self, product: impl Into<String>) -> Self {
+        self.product = Some(product.into());
+        self
+    }
+
     /// Set serial number
     pub fn with_serial_number(mut self, serial_number: impl Into<String>) -> Self {
         self.serial_number = Some(serial_number.into());
@@ -95,6 +105,31 @@ impl SerialPortInfo {
         self.pid = Some(pid);
         self
     }
+
+    /// One-line summary for picking between similar ports
+    ///
+    /// e.g. "FTDI FT232R USB UART (0403:6001) SN A50285BI". Falls back to the
+    /// description when no USB metadata is known.
+    pub fn details(&self) -> String {
+        let mut parts: Vec<String> = self
+            .manufacturer
+            .iter()
+            .chain(self.product.iter())
+            .cloned()
+            .collect();
+        if let (Some(vid), Some(pid)) = (self.vid, self.pid) {
+            parts.push(format!("({:04x}:{:04x})", vid, pid));
+        }
+        if let Some(serial) = &self.serial_number {
+            parts.push(format!("SN {}", serial));
+        }
+
+        if parts.is_empty() {
+            self.description.clone()
+        } else {
+            parts.join(" ")
+        }
+    }
 }
 
 /// List available serial ports on the system
@@ -106,31 +141,7 @@ impl SerialPortInfo {
 /// - macOS: /dev/cu.usbserial-*, /dev/cu.usbmodem*
 pub fn list_ports() -> Result<Vec<SerialPortInfo>> {
     match serialport::available_ports() {
-        Ok(ports) => {
-            let port_infos: Vec<SerialPortInfo> = ports
-                .iter()
-                .filter(|port| is_valid_cnc_port(&port.port_name))
-                .map(|port| {
-                    let info = SerialPortInfo::new(&port.port_name, get_port_description(port));
-
-                    match &port.port_type {
-                        serialport::SerialPortType::UsbPort(usb_info) => {
-                            let mut info = info.with_usb_ids(usb_info.vid, usb_info.pid);
-                            if let Some(ref mfg) = usb_info.manufacturer {
-                                info = info.with_manufacturer(mfg);
-                            }
-                            if let Some(ref serial) = usb_info.serial_number {
-                                info = info.with_serial_number(serial);
-                            }
-                            info
-                        }
-                        _ => info,
-                    }
-                })
-                .collect();
-
-            Ok(port_infos)
-        }
+        Ok(ports) => Ok(ports_from_enumeration(&ports)),
         Err(e) => {
             tracing::error!("Failed to enumerate serial ports: {}", e);
             Err(Error::other(format!("Failed to enumerate ports: {}", e)))
@@ -138,6 +149,37 @@ pub fn list_ports() -> Result<Vec<SerialPortInfo>> {
     }
 }
 
+/// Build port infos from an OS port enumeration
+///
+/// Keeps only CNC controller port patterns and copies the USB VID/PID,
+/// manufacturer, product and serial number where the OS reports them.
+pub fn ports_from_enumeration(ports: &[serialport::SerialPortInfo]) -> Vec<SerialPortInfo> {
+    ports
+        .iter()
+        .filter(|port| is_valid_cnc_port(&port.port_name))
+        .map(|port| {
+            let info = SerialPortInfo::new(&port.port_name, get_port_description(port));
+
+            match &port.port_type {
+                serialport::SerialPortType::UsbPort(usb_info) => {
+                    let mut info = info.with_usb_ids(usb_info.vid, usb_info.pid);
+                    if let Some(ref mfg) = usb_info.manufacturer {
+                        info = info.with_manufacturer(mfg);
+                    }
+                    if let Some(ref product) = usb_info.product {
+                        info = info.with_product(product);
+                    }
+                    if let Some(ref serial) = usb_info.serial_number {
+                        info = info.with_serial_number(serial);
+                    }
+                    info
+                }
+                _ => info,
+            }
+        })
+        .collect()
+}
+
 /// Check if a port name matches CNC controller patterns
 ///
 /// Valid patterns:
@@ -188,6 +230,60 @@ fn to_serialport_parity(parity: SerialParity) -> serialport::Parity {
     }
 }
 
+/// Modem control lines of an open serial port
+///
+/// Abstracts the DTR/RTS outputs so the open sequence can be exercised
+/// without real hardware.
+pub trait ControlLines {
+    /// Set the Data Terminal Ready line
+    fn write_data_terminal_ready(&mut self, level: bool) -> io::Result<()>;
+
+    /// Set the Request To Send line
+    fn write_request_to_send(&mut self, level: bool) -> io::Result<()>;
+}
+
+/// Apply the DTR/RTS levels requested in the connection parameters
+///
+/// Lines left as `None` are not touched. RTS is skipped when hardware flow
+/// control is enabled since the driver owns it in that mode.
+pub fn apply_control_lines(params: &ConnectionParams, lines: &mut dyn ControlLines) -> Result<()> {
+    if let Some(level) = params.dtr_on_open {
+        lines
+            .write_data_terminal_ready(level)
+            .map_err(|e| Error::other(format!("Failed to set DTR on {}: {}", params.port, e)))?;
+    }
+
+    if let Some(level) = params.rts_on_open {
+        if params.flow_control {
+            tracing::warn!(
+                "Ignoring RTS setting on {}: hardware flow control is enabled",
+                params.port
+            );
+        } else {
+            lines.write_request_to_send(level).map_err(|e| {
+                Error::other(format!("Failed to set RTS on {}: {}", params.port, e))
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Control line adapter for ports opened through the serialport crate
+struct NativeControlLines<'a>(&'a mut dyn serialport::SerialPort);
+
+impl ControlLines for NativeControlLines<'_> {
+    fn write_data_terminal_ready(&mut self, level: bool) -> io::Result<()> {
+        self.0
+            .write_data_terminal_ready(level)
+            .map_err(io::Error::from)
+    }
+
+    fn write_request_to_send(&mut self, level: bool) -> io::Result<()> {
+        self.0.write_request_to_send(level).map_err(io::Error::from)
+    }
+}
+
 /// Low-level serial port interface
 pub trait SerialPort: Send + Sync {
     /// Write data to the port
@@ -219,7 +315,7 @@ impl RealSerialPort {
             return Err(Error::other("RealSerialPort requires Serial driver type"));
         }
 
-        let builder = serialport::new(&params.port, params.baud_rate)
+        let mut builder = serialport::new(&params.port, params.baud_rate)
             .timeout(Duration::from_millis(params.timeout_ms))
             .data_bits(match params.data_bits {
                 5 => serialport::DataBits::Five,
@@ -250,10 +346,18 @@ impl RealSerialPort {
                 serialport::FlowControl::None
             });
 
+        // Avoid the DTR edge on open that resets many boards
+        if let Some(level) = params.dtr_on_open {
+            builder = builder.dtr_on_open(level);
+        }
+
         match builder.open_native() {
-            Ok(port) => Ok(RealSerialPort {
-                port: Mutex::new(Box::new(port)),
-            }),
+            Ok(mut port) => {
+                apply_control_lines(params, &mut NativeControlLines(&mut port))?;
+                Ok(RealSerialPort {
+                    port: Mutex::new(Box::new(port)),
+                })
+            }
             Err(e) => {
                 tracing::warn!("Failed to open serial port {}: {}", params.port, e);
                 Err(Error::other(format!(
@@ -0,0 +1,95 @@
+//! Startup command block sent after connecting
+//!
+//! Device profiles can list commands to run every time a connection is made,
+//! such as `G21` or `$10=1`. [`run_startup_commands`] sends them one at a
+//! time, waiting for each `ok` before the next, and stops at the first
+//! command the controller rejects so later commands never run against a
+//! half-configured machine.
+
+use std::time::{Duration, Instant};
+
+use gcodekit4_core::CommandResponse;
+
+use super::async_controller::completion;
+use super::Communicator;
+
+/// Pause between reads while waiting for an answer
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Send startup commands in order, waiting for each answer
+///
+/// Blank lines and comment-only lines (`;` or `(...)`) are skipped. Status
+/// reports and other lines that arrive before a command's `ok`, `error:` or
+/// `ALARM:` are kept in its response. The block stops at the first command
+/// that fails or isn't answered within `timeout`.
+///
+/// # Arguments
+/// * `communicator` - A connected communicator
+/// * `commands` - Commands to send, in order
+/// * `timeout` - How long each command may go unanswered
+///
+/// # Returns
+/// The response to each command sent; if the block stopped early, the last
+/// one holds the error
+pub fn run_startup_commands(
+    communicator: &mut dyn Communicator,
+    commands: &[String],
+    timeout: Duration,
+) -> Vec<CommandResponse> {
+    let mut responses = Vec::new();
+    let mut buffer = String::new();
+
+    let commands = commands
+        .iter()
+        .map(|command| command.trim())
+        .filter(|command| !command.is_empty() && !command.starts_with([';', '(']));
+    for command in commands {
+        let response = run_one(communicator, command, timeout, &mut buffer);
+        let failed = !response.is_ok();
+        responses.push(response);
+        if failed {
+            break;
+        }
+    }
+
+    responses
+}
+
+/// Send one command and collect its answer
+fn run_one(
+    communicator: &mut dyn Communicator,
+    command: &str,
+    timeout: Duration,
+    buffer: &mut String,
+) -> CommandResponse {
+    if let Err(e) = communicator.send_command(command) {
+        return CommandResponse::failed(command, Vec::new(), e.to_string());
+    }
+
+    let sent_at = Instant::now();
+    let mut lines = Vec::new();
+    loop {
+        match communicator.receive() {
+            Ok(data) => buffer.push_str(&String::from_utf8_lossy(&data)),
+            Err(e) => return CommandResponse::failed(command, lines, e.to_string()),
+        }
+        while let Some(pos) = buffer.find('\n') {
+            let line = buffer[..pos].trim().to_string();
+            buffer.drain(..=pos);
+            if line.is_empty() {
+                continue;
+            }
+            match completion(&line) {
+                Some(Some(error)) => return CommandResponse::failed(command, lines, error),
+                Some(None) => return CommandResponse::ok(command, lines),
+                None => lines.push(line),
+            }
+        }
+
+        if sent_at.elapsed() >= timeout {
+            let error = format!("Timed out waiting for a response to '{}'", command);
+            return CommandResponse::failed(command, lines, error);
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
@@ -2,9 +2,12 @@
 //!
 //! Manages firmware capabilities and provides UI-friendly state.
 //! Tracks current controller capabilities and notifies UI of changes.
+//! Programs can be checked against the capabilities before streaming.
 
 use super::capabilities_db::{CapabilitiesDatabase, FirmwareCapabilities};
 use super::firmware_version::{FirmwareType, SemanticVersion};
+use gcodekit4_visualizer::gcode::tokenize_words;
+use gcodekit4_visualizer::{GcodeCommand, ValidationIssue, ValidationResult, ValidationSeverity};
 use std::sync::{Arc, Mutex};
 
 /// UI-friendly capability state
@@ -265,3 +268,92 @@ impl Default for CapabilityManager {
     }
 }
 
+/// Axis letters beyond X, Y and Z, in the order controllers number them
+const EXTRA_AXES: [char; 3] = ['A', 'B', 'C'];
+
+/// Check a program for commands the connected firmware can't execute
+///
+/// Each command the capabilities rule out gets a warning on its line: arcs
+/// (`G2`/`G3`), probing (`G38.x`), tool changes (`M6`), tool length offsets
+/// from the tool table (`G43`), coolant (`M7`-`M9`), work coordinate systems
+/// beyond those available (`G54`-`G59`) and rotary axes beyond `max_axes`.
+/// A command's own line number is used when set, otherwise its position in
+/// `commands` (1-indexed).
+///
+/// # Arguments
+/// * `commands` - Program commands in order
+/// * `capabilities` - Capabilities of the connected controller
+///
+/// # Returns
+/// One warning per unsupported word
+pub fn validate_against_capabilities(
+    commands: &[GcodeCommand],
+    capabilities: &CapabilityState,
+) -> ValidationResult {
+    let mut result = ValidationResult::new();
+
+    for (index, command) in commands.iter().enumerate() {
+        let line_number = command.line_number.unwrap_or(index as u32 + 1);
+        let mut warn = |message: String, suggestion: &str| {
+            result.add_issue(
+                ValidationIssue::new(line_number, ValidationSeverity::Warning, message)
+                    .with_suggestion(suggestion),
+            );
+        };
+
+        for (letter, value) in tokenize_words(&command.command) {
+            let code = (value * 10.0).round() as i64;
+            match (letter, code) {
+                ('G', 20 | 30) if !capabilities.supports_arcs => warn(
+                    format!("G{} arc is not supported by the firmware", code / 10),
+                    "Linearize arcs before streaming",
+                ),
+                ('G', 382..=385) if !capabilities.supports_probing => warn(
+                    format!("G38.{} probe is not supported by the firmware", code % 10),
+                    "Remove the probing cycle",
+                ),
+                ('G', 430) if !capabilities.supports_tool_change => warn(
+                    "G43 tool length offset is not supported by the firmware".to_string(),
+                    "Set the tool length offset on the controller before the job",
+                ),
+                ('G', 540..=590) if code % 10 == 0 => {
+                    let system = (code - 530) / 10;
+                    if system > i64::from(capabilities.coordinate_systems) {
+                        warn(
+                            format!(
+                                "G{} needs {} work coordinate systems, the firmware has {}",
+                                code / 10,
+                                system,
+                                capabilities.coordinate_systems
+                            ),
+                            "Use a work coordinate system the firmware provides",
+                        );
+                    }
+                }
+                ('M', 60) if !capabilities.supports_tool_change => warn(
+                    "M6 tool change is not supported by the firmware".to_string(),
+                    "Split the program at tool changes and change tools manually",
+                ),
+                ('M', 70..=90) if code % 10 == 0 && !capabilities.supports_coolant => warn(
+                    format!("M{} coolant is not supported by the firmware", code / 10),
+                    "Remove coolant commands",
+                ),
+                (axis, _) if EXTRA_AXES.contains(&axis) => {
+                    let position = EXTRA_AXES.iter().position(|&a| a == axis).unwrap_or(0);
+                    if position + 3 >= usize::from(capabilities.max_axes) {
+                        warn(
+                            format!(
+                                "{} axis is not supported by the firmware ({} axes)",
+                                axis, capabilities.max_axes
+                            ),
+                            "Remove moves on axes the machine doesn't have",
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    result
+}
@@ -0,0 +1,90 @@
+//! Firmware command dialects
+//!
+//! Most commands are plain G-code that every firmware accepts, but a few
+//! actions are spelt differently per firmware family. [`CommandDialect`]
+//! gives the bytes for those so callers don't branch on the firmware.
+
+use super::grbl;
+use super::ControllerType;
+use crate::communication::Communicator;
+use gcodekit4_visualizer::LineFramer;
+use std::sync::Mutex;
+
+/// How a firmware family expects its special commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CommandDialect {
+    /// GRBL and the firmwares sharing its real-time commands
+    #[default]
+    Grbl,
+    /// Marlin
+    Marlin,
+}
+
+impl CommandDialect {
+    /// The dialect a controller type speaks
+    pub fn for_controller(controller_type: ControllerType) -> Self {
+        match controller_type {
+            ControllerType::Marlin => Self::Marlin,
+            _ => Self::Grbl,
+        }
+    }
+
+    /// Bytes that halt the machine at once
+    ///
+    /// GRBL takes the real-time soft reset (`0x18`). Marlin has no real-time
+    /// bytes but parses `M112` as soon as the line arrives, ahead of its
+    /// command queue.
+    pub fn emergency_stop(&self) -> &'static [u8] {
+        match self {
+            Self::Grbl => &[0x18],
+            Self::Marlin => b"M112\n",
+        }
+    }
+
+    /// Framer for streamed program lines, if the firmware checks them
+    ///
+    /// Marlin takes line numbers and checksums and asks for a corrupted line
+    /// again with `Resend:`. GRBL lines go out as they are.
+    pub fn line_framer(&self) -> Option<LineFramer> {
+        match self {
+            Self::Grbl => None,
+            Self::Marlin => Some(LineFramer::new()),
+        }
+    }
+
+    /// Write the emergency stop straight to the port, bypassing any queue
+    pub fn send_emergency_stop<C: Communicator + ?Sized>(
+        &self,
+        communicator: &mut C,
+    ) -> anyhow::Result<()> {
+        communicator.send(self.emergency_stop())?;
+        Ok(())
+    }
+
+    /// Stop the running program through a communicator the caller's IO loop reads
+    ///
+    /// GRBL gets [`grbl::abort_communicator`]: a feed hold, a wait for it to
+    /// finish, a soft reset and a status query. Marlin gets the `M410` quick
+    /// stop, which halts the steppers and drops its planner without killing
+    /// the board as `M112` does; `machine_state` isn't used for it.
+    pub fn abort_communicator<C, F>(
+        &self,
+        communicator: &Mutex<C>,
+        machine_state: F,
+    ) -> anyhow::Result<()>
+    where
+        C: Communicator + ?Sized,
+        F: Fn() -> Option<String>,
+    {
+        match self {
+            Self::Grbl => grbl::abort_communicator(communicator, machine_state),
+            Self::Marlin => {
+                communicator
+                    .lock()
+                    .map_err(|_| anyhow::anyhow!("Communicator lock poisoned"))?
+                    .send(b"M410\n")?;
+                Ok(())
+            }
+        }
+    }
+}
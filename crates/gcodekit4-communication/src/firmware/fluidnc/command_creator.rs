@@ -137,6 +137,11 @@ impl FluidNCCommandCreator {
     pub fn upload_file(&self, filename: &str, size: usize) -> String {
         format!("$SD/Upload {} {}", filename, size)
     }
+
+    /// Create a request to run a file from the SD card
+    pub fn run_file(&self, filename: &str) -> String {
+        format!("$SD/Run={}", filename)
+    }
 }
 
 impl Default for FluidNCCommandCreator {
@@ -8,11 +8,13 @@ pub mod command_creator;
 pub mod constants;
 pub mod controller;
 pub mod response_parser;
+pub mod sd_upload;
 
 pub use capabilities::FluidNCCapabilities;
 pub use command_creator::FluidNCCommandCreator;
 pub use controller::FluidNCController;
 pub use response_parser::FluidNCResponseParser;
+pub use sd_upload::{FluidNCSdUploader, SdChunk, SdUploadProgress};
 
 /// FluidNC version information
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
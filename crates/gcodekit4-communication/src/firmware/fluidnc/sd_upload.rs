@@ -0,0 +1,223 @@
+//! FluidNC SD card upload
+//!
+//! Very large programs are better uploaded to the controller's SD card and
+//! run from there than streamed line by line. The uploader prepares a
+//! program (optionally running it through a processor pipeline), splits it
+//! into numbered chunk files if it exceeds the chunk size, and writes each
+//! file with `$SD/Upload`, reporting progress as lines are sent.
+
+use super::{FluidNCCapabilities, FluidNCCommandCreator};
+use crate::communication::Communicator;
+use gcodekit4_visualizer::{GcodeCommand, GcodeState, ProcessorPipeline};
+
+/// A file to be written to the SD card
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SdChunk {
+    /// Remote file name
+    pub name: String,
+    /// Program lines, without line endings
+    pub lines: Vec<String>,
+}
+
+impl SdChunk {
+    /// Size of the file on the SD card in bytes, one `\n` per line
+    pub fn size(&self) -> usize {
+        self.lines.iter().map(|line| line.len() + 1).sum()
+    }
+}
+
+/// Progress of an SD card upload
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SdUploadProgress {
+    /// Index of the file being written (0-based)
+    pub file_index: usize,
+    /// Number of files in the upload
+    pub file_count: usize,
+    /// Bytes written so far across all files
+    pub bytes_sent: usize,
+    /// Total bytes across all files
+    pub total_bytes: usize,
+}
+
+impl SdUploadProgress {
+    /// Percentage of the upload completed
+    pub fn percent(&self) -> f64 {
+        if self.total_bytes == 0 {
+            100.0
+        } else {
+            (self.bytes_sent as f64 / self.total_bytes as f64) * 100.0
+        }
+    }
+}
+
+/// Writes programs to a FluidNC controller's SD card
+#[derive(Debug, Clone)]
+pub struct FluidNCSdUploader {
+    command_creator: FluidNCCommandCreator,
+    /// Largest chunk file in bytes, `None` to upload a single file
+    max_chunk_bytes: Option<usize>,
+}
+
+impl FluidNCSdUploader {
+    /// Create an uploader that writes each program as a single file
+    pub fn new() -> Self {
+        Self {
+            command_creator: FluidNCCommandCreator::new(),
+            max_chunk_bytes: None,
+        }
+    }
+
+    /// Split programs into files of at most `bytes` bytes
+    pub fn with_chunk_size(mut self, bytes: usize) -> Self {
+        self.max_chunk_bytes = Some(bytes.max(1));
+        self
+    }
+
+    /// Prepare a program for upload under a remote file name
+    ///
+    /// Lines are trimmed and blank lines dropped. If a pipeline is given each
+    /// line is run through it first. Programs larger than the chunk size are
+    /// split on line boundaries into `name_001.ext`, `name_002.ext`, ...
+    pub fn prepare(
+        &self,
+        name: &str,
+        program: &str,
+        pipeline: Option<&ProcessorPipeline>,
+    ) -> anyhow::Result<Vec<SdChunk>> {
+        let lines: Vec<String> = program
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        let lines = match pipeline {
+            Some(pipeline) => {
+                let commands: Vec<GcodeCommand> = lines.iter().map(GcodeCommand::new).collect();
+                pipeline
+                    .process_commands(&commands, &mut GcodeState::new())
+                    .map_err(|e| anyhow::anyhow!(e))?
+                    .into_iter()
+                    .map(|command| command.command.trim().to_string())
+                    .filter(|line| !line.is_empty())
+                    .collect()
+            }
+            None => lines,
+        };
+
+        let Some(max_bytes) = self.max_chunk_bytes else {
+            return Ok(vec![SdChunk {
+                name: name.to_string(),
+                lines,
+            }]);
+        };
+
+        let mut groups: Vec<Vec<String>> = Vec::new();
+        let mut current = Vec::new();
+        let mut current_bytes = 0;
+        for line in lines {
+            if !current.is_empty() && current_bytes + line.len() + 1 > max_bytes {
+                groups.push(std::mem::take(&mut current));
+                current_bytes = 0;
+            }
+            current_bytes += line.len() + 1;
+            current.push(line);
+        }
+        if !current.is_empty() || groups.is_empty() {
+            groups.push(current);
+        }
+
+        if groups.len() == 1 {
+            return Ok(vec![SdChunk {
+                name: name.to_string(),
+                lines: groups.remove(0),
+            }]);
+        }
+
+        Ok(groups
+            .into_iter()
+            .enumerate()
+            .map(|(index, lines)| SdChunk {
+                name: Self::chunk_name(name, index + 1),
+                lines,
+            })
+            .collect())
+    }
+
+    /// Name of the `number`th chunk of a file, e.g. `/job_002.nc`
+    pub fn chunk_name(name: &str, number: usize) -> String {
+        let file_start = name.rfind('/').map_or(0, |i| i + 1);
+        match name[file_start..].rfind('.') {
+            Some(dot) if dot > 0 => {
+                let (stem, extension) = name.split_at(file_start + dot);
+                format!("{}_{:03}{}", stem, number, extension)
+            }
+            _ => format!("{}_{:03}", name, number),
+        }
+    }
+
+    /// Write prepared files to the SD card
+    ///
+    /// Each file is announced with `$SD/Upload <name> <size>` followed by its
+    /// lines. `progress` is called after every line. Fails without sending
+    /// anything if the controller has no file system, and stops at the first
+    /// `error` the controller reports. Returns the names of the files written.
+    pub fn upload<F>(
+        &self,
+        communicator: &mut dyn Communicator,
+        capabilities: &FluidNCCapabilities,
+        chunks: &[SdChunk],
+        mut progress: F,
+    ) -> anyhow::Result<Vec<String>>
+    where
+        F: FnMut(SdUploadProgress),
+    {
+        if !capabilities.has_filesystem() {
+            return Err(anyhow::anyhow!(
+                "Controller does not support SD card file uploads"
+            ));
+        }
+        if !communicator.is_connected() {
+            return Err(anyhow::anyhow!("Not connected"));
+        }
+
+        let mut status = SdUploadProgress {
+            file_index: 0,
+            file_count: chunks.len(),
+            bytes_sent: 0,
+            total_bytes: chunks.iter().map(SdChunk::size).sum(),
+        };
+
+        for (index, chunk) in chunks.iter().enumerate() {
+            status.file_index = index;
+            communicator
+                .send_command(&self.command_creator.upload_file(&chunk.name, chunk.size()))?;
+
+            for line in &chunk.lines {
+                communicator.send_command(line)?;
+                status.bytes_sent += line.len() + 1;
+                progress(status);
+            }
+
+            let reply = String::from_utf8_lossy(&communicator.receive()?).to_string();
+            if let Some(error) = reply
+                .lines()
+                .find(|line| line.trim().to_lowercase().starts_with("error"))
+            {
+                return Err(anyhow::anyhow!(
+                    "Upload of {} failed: {}",
+                    chunk.name,
+                    error.trim()
+                ));
+            }
+        }
+
+        Ok(chunks.iter().map(|chunk| chunk.name.clone()).collect())
+    }
+}
+
+impl Default for FluidNCSdUploader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -259,28 +259,20 @@ impl G2CoreResponseParser {
             }
         }
 
-        // Parse positions with 6-axis support
-        if let Some(pos_val) = sr.get("pos") {
-            if let Some(pos_obj) = pos_val.as_object() {
-                let x = pos_obj.get("x").and_then(Value::as_f64).unwrap_or(0.0) as f32;
-                let y = pos_obj.get("y").and_then(Value::as_f64).unwrap_or(0.0) as f32;
-                let z = pos_obj.get("z").and_then(Value::as_f64).unwrap_or(0.0) as f32;
-                let a = pos_obj.get("a").and_then(Value::as_f64).unwrap_or(0.0) as f32;
-                let b = pos_obj.get("b").and_then(Value::as_f64).unwrap_or(0.0) as f32;
-                let c = pos_obj.get("c").and_then(Value::as_f64).unwrap_or(0.0) as f32;
-                status.work_position = Position::with_a(x, y, z, a);
-                status.rotational_axes = Some((a, b, c));
+        // Parse positions, including any rotary axes
+        if let Some(position) = parse_position(sr, "pos", "pos") {
+            if position.axis_count() > 3 {
+                status.rotational_axes = Some((
+                    position.a.unwrap_or(0.0),
+                    position.b.unwrap_or(0.0),
+                    position.c.unwrap_or(0.0),
+                ));
             }
+            status.work_position = position;
         }
 
-        if let Some(mpos_val) = sr.get("mpos") {
-            if let Some(mpos_obj) = mpos_val.as_object() {
-                let x = mpos_obj.get("x").and_then(Value::as_f64).unwrap_or(0.0) as f32;
-                let y = mpos_obj.get("y").and_then(Value::as_f64).unwrap_or(0.0) as f32;
-                let z = mpos_obj.get("z").and_then(Value::as_f64).unwrap_or(0.0) as f32;
-                let a = mpos_obj.get("a").and_then(Value::as_f64).unwrap_or(0.0) as f32;
-                status.machine_position = Position::with_a(x, y, z, a);
-            }
+        if let Some(position) = parse_position(sr, "mpos", "mpo") {
+            status.machine_position = position;
         }
 
         // Parse feed rate and speed
@@ -305,3 +297,34 @@ impl G2CoreResponseParser {
         Ok(status)
     }
 }
+
+/// Read an axis position from a status report
+///
+/// Accepts both a nested object (`"pos":{"x":1,"a":90}`) and the flat
+/// keys TinyG/g2core use in default status reports (`"posx":1,"posa":90`).
+/// Rotary axes are only set when the controller reports them.
+fn parse_position(sr: &Value, nested: &str, flat_prefix: &str) -> Option<Position> {
+    let axis = |name: &str| -> Option<f32> {
+        match sr.get(nested).and_then(Value::as_object) {
+            Some(obj) => obj.get(name),
+            None => sr.get(format!("{}{}", flat_prefix, name).as_str()),
+        }
+        .and_then(Value::as_f64)
+        .map(|v| v as f32)
+    };
+
+    let (x, y, z) = (axis("x"), axis("y"), axis("z"));
+    let (a, b, c) = (axis("a"), axis("b"), axis("c"));
+    if sr.get(nested).is_none() && [x, y, z, a, b, c].iter().all(Option::is_none) {
+        return None;
+    }
+
+    Some(Position::with_rotary(
+        x.unwrap_or(0.0),
+        y.unwrap_or(0.0),
+        z.unwrap_or(0.0),
+        a,
+        b,
+        c,
+    ))
+}
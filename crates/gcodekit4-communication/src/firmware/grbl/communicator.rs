@@ -5,6 +5,7 @@
 //! needing traditional handshaking.
 
 use crate::communication::{Communicator, ConnectionParams};
+use crate::firmware::CommandDialect;
 use parking_lot::RwLock;
 use std::sync::Arc;
 
@@ -173,6 +174,14 @@ impl GrblCommunicator {
     pub fn send_realtime_byte(&self, byte: u8) -> anyhow::Result<()> {
         self.send_bytes(&[byte])
     }
+
+    /// Send a firmware's emergency stop straight to the port
+    ///
+    /// Like real-time commands it skips character counting.
+    pub fn send_emergency_stop(&self, dialect: CommandDialect) -> anyhow::Result<()> {
+        let mut comm = self.communicator.write();
+        dialect.send_emergency_stop(&mut **comm)
+    }
 }
 
 
@@ -51,6 +51,46 @@ pub const CMD_DOOR_ALARM: u8 = 0x04;
 /// Safety door toggle (Ctrl+Shift+D = 0x84)
 pub const CMD_SAFETY_DOOR: u8 = 0x84;
 
+// GRBL 1.1 Real-Time Override Commands
+/// Feed override: set to 100%
+pub const CMD_FEED_OV_RESET: u8 = 0x90;
+
+/// Feed override: increase 10%
+pub const CMD_FEED_OV_COARSE_PLUS: u8 = 0x91;
+
+/// Feed override: decrease 10%
+pub const CMD_FEED_OV_COARSE_MINUS: u8 = 0x92;
+
+/// Feed override: increase 1%
+pub const CMD_FEED_OV_FINE_PLUS: u8 = 0x93;
+
+/// Feed override: decrease 1%
+pub const CMD_FEED_OV_FINE_MINUS: u8 = 0x94;
+
+/// Rapid override: set to 100%
+pub const CMD_RAPID_OV_RESET: u8 = 0x95;
+
+/// Rapid override: set to 50%
+pub const CMD_RAPID_OV_MEDIUM: u8 = 0x96;
+
+/// Rapid override: set to 25%
+pub const CMD_RAPID_OV_LOW: u8 = 0x97;
+
+/// Spindle override: set to 100%
+pub const CMD_SPINDLE_OV_RESET: u8 = 0x99;
+
+/// Spindle override: increase 10%
+pub const CMD_SPINDLE_OV_COARSE_PLUS: u8 = 0x9A;
+
+/// Spindle override: decrease 10%
+pub const CMD_SPINDLE_OV_COARSE_MINUS: u8 = 0x9B;
+
+/// Spindle override: increase 1%
+pub const CMD_SPINDLE_OV_FINE_PLUS: u8 = 0x9C;
+
+/// Spindle override: decrease 1%
+pub const CMD_SPINDLE_OV_FINE_MINUS: u8 = 0x9D;
+
 // GRBL Status Report Codes
 /// GRBL is IDLE
 pub const STATUS_IDLE: &str = "Idle";
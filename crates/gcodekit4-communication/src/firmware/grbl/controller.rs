@@ -3,20 +3,63 @@
 //! Provides a complete implementation of the ControllerTrait for GRBL firmware,
 //! including connection management, command execution, and status polling.
 
-use crate::communication::{ConnectionParams, NoOpCommunicator};
+use crate::communication::{Communicator, ConnectionParams, NoOpCommunicator};
 use crate::firmware::grbl::{GrblCommunicator, GrblCommunicatorConfig};
+use crate::firmware::{CommandDialect, ControllerType};
+use crate::firmware::grbl::override_manager::{
+    feed_override_bytes, override_state_bytes, rapid_override_byte, spindle_override_bytes,
+};
+use crate::firmware::grbl::jog_queue::JogQueue;
+use crate::firmware::grbl::response_parser::WorkOffsets;
 use crate::firmware::grbl::status_parser::StatusParser;
+use crate::firmware::grbl::utils::is_hold_complete;
 use async_trait::async_trait;
 use gcodekit4_core::{ControllerState, ControllerStatus, PartialPosition};
-use gcodekit4_core::{ControllerTrait, OverrideState};
+use gcodekit4_core::{ControllerEvent, ControllerTrait, EventDispatcher, OverrideState};
+use gcodekit4_visualizer::{WorkCoordinateSystem, WorkOffset};
 use parking_lot::RwLock;
 use std::collections::VecDeque;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc};
 use tokio::task::JoinHandle;
 use tokio::time::Duration;
 
+/// Longest wait for a feed hold to stop motion before `abort` resets
+pub const ABORT_HOLD_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// Seconds of motion queued by each analog jog command
+const ANALOG_JOG_HORIZON: f64 = 0.25;
+
+/// Analog inputs closer to center than this stop the jog
+const ANALOG_JOG_DEADZONE: f64 = 0.05;
+
+/// Smallest change in analog input that re-issues the jog at the new rate
+const ANALOG_JOG_RATE_STEP: f64 = 0.05;
+
+/// The analog jog currently running
+#[derive(Debug, Clone, Copy)]
+pub struct AnalogJog {
+    /// Axis letter being jogged
+    pub axis: char,
+    /// Input the jog was issued for, -1.0 to 1.0
+    pub rate: f64,
+    /// When the last jog segment was queued
+    pub issued: Instant,
+}
+
+/// Progress of a `$H` homing cycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HomingCycle {
+    /// No homing cycle running
+    #[default]
+    Inactive,
+    /// `$H` sent; GRBL answers once the cycle ends
+    AwaitingOk,
+    /// `$H` answered `ok`, waiting for an `Idle` status report
+    AwaitingIdle,
+}
+
 /// GRBL Controller state management
 #[derive(Debug, Clone)]
 pub struct GrblControllerState {
@@ -34,6 +77,33 @@ pub struct GrblControllerState {
     pub is_streaming: bool,
     /// Status poll rate (milliseconds)
     pub poll_rate_ms: u64,
+    /// Held by `safety_hold` with the spindle stopped by the spindle stop override
+    pub safety_hold: bool,
+    /// Last status report showed no motion, e.g. `Hold:0` once a feed hold
+    /// has finished decelerating
+    pub hold_complete: bool,
+    /// Homed with `$H` since the last reset or alarm
+    pub homed: bool,
+    /// Homing cycle in progress
+    pub homing: HomingCycle,
+    /// Overrides in effect when the program was paused
+    pub paused_overrides: Option<OverrideState>,
+    /// Re-apply `paused_overrides` on resume if they were changed while paused
+    pub restore_overrides_on_resume: bool,
+    /// Offsets from the last `$#` report
+    pub work_offsets: WorkOffsets,
+    /// Number of complete `$#` reports received
+    pub work_offsets_reports: u64,
+    /// Program stopped by `abort` since streaming last started
+    pub aborted: bool,
+    /// Startup banner seen mid-stream since streaming last started
+    pub unexpected_reset: bool,
+    /// Feed rate for full analog jog input (mm/min)
+    pub max_jog_feed: f64,
+    /// Analog jog in progress
+    pub analog_jog: Option<AnalogJog>,
+    /// Button jogs sent or held back
+    pub jog_queue: JogQueue,
 }
 
 impl Default for GrblControllerState {
@@ -46,10 +116,55 @@ impl Default for GrblControllerState {
             work_position: gcodekit4_core::Position::default(),
             is_streaming: false,
             poll_rate_ms: 100,
+            safety_hold: false,
+            hold_complete: false,
+            homed: false,
+            homing: HomingCycle::Inactive,
+            paused_overrides: None,
+            restore_overrides_on_resume: false,
+            work_offsets: WorkOffsets::default(),
+            work_offsets_reports: 0,
+            aborted: false,
+            unexpected_reset: false,
+            max_jog_feed: 1000.0,
+            analog_jog: None,
+            jog_queue: JogQueue::default(),
         }
     }
 }
 
+/// Stop a GRBL machine driven directly through a `Communicator`
+///
+/// The blocking counterpart of [`GrblController::abort`] for callers running
+/// their own IO loop: a feed hold, a wait of up to [`ABORT_HOLD_TIMEOUT`] for
+/// `machine_state` to show the hold complete, then a soft reset and a status
+/// query. `machine_state` gives the state from the latest status report since
+/// the call, or `None` until one arrives. The communicator is only locked
+/// while sending, so the caller's IO loop keeps reading status reports.
+pub fn abort_communicator<C, F>(communicator: &Mutex<C>, machine_state: F) -> anyhow::Result<()>
+where
+    C: Communicator + ?Sized,
+    F: Fn() -> Option<String>,
+{
+    let send = |bytes: &[u8]| -> anyhow::Result<()> {
+        communicator
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Communicator lock poisoned"))?
+            .send(bytes)?;
+        Ok(())
+    };
+
+    send(&[0x21])?;
+    let waited = Instant::now();
+    while !machine_state().is_some_and(|state| is_hold_complete(&state))
+        && waited.elapsed() < ABORT_HOLD_TIMEOUT
+    {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    send(&[0x18])?;
+    send(b"?")
+}
+
 /// GRBL Controller implementation
 ///
 /// Implements the ControllerTrait for GRBL firmware with full protocol support.
@@ -65,17 +180,34 @@ pub struct GrblController {
     io_task: Arc<RwLock<Option<JoinHandle<()>>>>,
     /// Command sender channel
     command_tx: Arc<RwLock<Option<mpsc::Sender<String>>>>,
+    /// Priority command channel, drained ahead of queued commands
+    priority_tx: Arc<RwLock<Option<mpsc::UnboundedSender<String>>>>,
     /// Shutdown signal
     shutdown_signal: Arc<RwLock<Option<mpsc::Sender<()>>>>,
     /// Connection parameters
     connection_params: ConnectionParams,
+    /// Controller events raised by the IO loop
+    events: EventDispatcher,
 }
 
 impl GrblController {
     /// Create a new GRBL controller
     pub fn new(connection_params: ConnectionParams, name: Option<String>) -> anyhow::Result<Self> {
-        let communicator = Arc::new(GrblCommunicator::new(
+        Self::with_communicator(
+            connection_params,
+            name,
             Box::new(NoOpCommunicator::new()),
+        )
+    }
+
+    /// Create a new GRBL controller on top of an existing communicator
+    pub fn with_communicator(
+        connection_params: ConnectionParams,
+        name: Option<String>,
+        communicator: Box<dyn Communicator>,
+    ) -> anyhow::Result<Self> {
+        let communicator = Arc::new(GrblCommunicator::new(
+            communicator,
             GrblCommunicatorConfig::default(),
         ));
 
@@ -85,11 +217,184 @@ impl GrblController {
             state: Arc::new(RwLock::new(GrblControllerState::default())),
             io_task: Arc::new(RwLock::new(None)),
             command_tx: Arc::new(RwLock::new(None)),
+            priority_tx: Arc::new(RwLock::new(None)),
             shutdown_signal: Arc::new(RwLock::new(None)),
             connection_params,
+            events: EventDispatcher::default(),
         })
     }
 
+    /// Check whether the controller is held by `safety_hold`
+    pub fn is_safety_hold(&self) -> bool {
+        self.state.read().safety_hold
+    }
+
+    /// Check whether the program was stopped by `abort`
+    pub fn is_aborted(&self) -> bool {
+        self.state.read().aborted
+    }
+
+    /// Check whether the controller reset itself while streaming
+    ///
+    /// Set when GRBL prints its startup banner mid-job, e.g. after a
+    /// brownout. Streaming has been stopped and the queue dropped.
+    pub fn is_unexpected_reset(&self) -> bool {
+        self.state.read().unexpected_reset
+    }
+
+    /// Subscribe to controller events such as `ControllerEvent::Reset`
+    pub fn subscribe_events(&self) -> broadcast::Receiver<ControllerEvent> {
+        self.events.subscribe()
+    }
+
+    /// Check whether the machine has been homed since the last reset or alarm
+    pub fn is_homed(&self) -> bool {
+        self.state.read().homed
+    }
+
+    /// Choose whether resume re-applies the overrides in effect at pause
+    ///
+    /// When disabled, overrides changed while paused are kept and the
+    /// difference is only logged.
+    pub fn set_restore_overrides_on_resume(&mut self, restore: bool) {
+        self.state.write().restore_overrides_on_resume = restore;
+    }
+
+    /// Set the feed rate used for full analog jog input (mm/min)
+    pub fn set_max_jog_feed(&mut self, feed_rate: f64) {
+        self.state.write().max_jog_feed = feed_rate.max(0.0);
+    }
+
+    /// Jog continuously from an analog input such as a gamepad stick
+    ///
+    /// `rate_fraction` runs from -1.0 to 1.0 and sets the direction and the
+    /// share of the maximum jog feed. Each `$J=` command covers a short time
+    /// so motion stops soon after input does; call this repeatedly while the
+    /// input is held to queue the next segment. A changed rate or axis
+    /// cancels the queued jog and re-issues it, and returning to center
+    /// cancels the jog.
+    pub async fn jog_analog(&mut self, axis: char, rate_fraction: f64) -> anyhow::Result<()> {
+        let axis = axis.to_ascii_uppercase();
+        if !"XYZABC".contains(axis) {
+            return Err(anyhow::anyhow!("Cannot jog axis {}", axis));
+        }
+        let rate = if rate_fraction.is_finite() {
+            rate_fraction.clamp(-1.0, 1.0)
+        } else {
+            0.0
+        };
+
+        let (previous, max_feed) = {
+            let state = self.state.read();
+            (state.analog_jog, state.max_jog_feed)
+        };
+
+        if rate.abs() < ANALOG_JOG_DEADZONE {
+            if self.state.write().analog_jog.take().is_some() {
+                self.communicator.send_realtime_byte(0x85)?;
+            }
+            return Ok(());
+        }
+
+        // Small wobbles keep the running rate so the jog isn't re-issued constantly
+        let running = previous.filter(|previous| {
+            previous.axis == axis
+                && previous.rate.signum() == rate.signum()
+                && (previous.rate - rate).abs() < ANALOG_JOG_RATE_STEP
+        });
+        match running {
+            Some(running) if running.issued.elapsed().as_secs_f64() < ANALOG_JOG_HORIZON / 2.0 => {
+                return Ok(());
+            }
+            Some(_) => {}
+            // Drop the jog queued at the old rate so the new one applies at once
+            None if previous.is_some() => self.communicator.send_realtime_byte(0x85)?,
+            None => {}
+        }
+
+        let rate = running.map_or(rate, |running| running.rate);
+        let feed_rate = max_feed * rate.abs();
+        let distance = feed_rate / 60.0 * ANALOG_JOG_HORIZON * rate.signum();
+        let cmd = format!("$J=G91 G0 {}{:.3} F{:.0}", axis, distance, feed_rate);
+        self.send_command(&cmd).await?;
+
+        self.state.write().analog_jog = Some(AnalogJog {
+            axis,
+            rate,
+            issued: Instant::now(),
+        });
+        Ok(())
+    }
+
+    /// Get the offsets from the last `$#` report
+    pub fn work_offsets(&self) -> WorkOffsets {
+        self.state.read().work_offsets
+    }
+
+    /// Query the stored G54-G59, G28/G30, G92 and tool length offsets
+    ///
+    /// Sends `$#` and waits for the report to be parsed by the IO loop. The
+    /// result is also kept in the controller state for display.
+    pub async fn query_work_offsets(&mut self) -> anyhow::Result<WorkOffsets> {
+        let reports = self.state.read().work_offsets_reports;
+        self.communicator.send_command("$#")?;
+
+        for _ in 0..100 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            let state = self.state.read();
+            if state.work_offsets_reports != reports {
+                return Ok(state.work_offsets);
+            }
+        }
+
+        Err(anyhow::anyhow!("Timed out waiting for $# response"))
+    }
+
+    /// Make a machine reference point work zero in the active coordinate system
+    ///
+    /// `reference` is a machine position such as a fixture corner; `None` uses
+    /// the current machine position. Machine coordinates only repeat after
+    /// homing, so this fails until `$H` has been run. The offset is written
+    /// with `G10 L2`, stored in `wcs` and mirrored into the `$#` offsets.
+    pub async fn set_work_zero_at_reference(
+        &mut self,
+        wcs: &mut WorkCoordinateSystem,
+        reference: Option<WorkOffset>,
+    ) -> anyhow::Result<()> {
+        let system = wcs.current_system();
+        if !(1..=6).contains(&system) {
+            return Err(anyhow::anyhow!(
+                "Work coordinate system must be G54-G59 (P1-P6)"
+            ));
+        }
+
+        let reference = {
+            let state = self.state.read();
+            if !state.homed {
+                return Err(anyhow::anyhow!(
+                    "Machine must be homed ($H) before setting work zero from a machine reference"
+                ));
+            }
+            reference.unwrap_or_else(|| {
+                WorkOffset::new(
+                    state.machine_position.x as f64,
+                    state.machine_position.y as f64,
+                    state.machine_position.z as f64,
+                )
+            })
+        };
+
+        let previous = wcs.current_offset();
+        let cmd = wcs.set_reference_as_zero(reference);
+        if let Err(e) = self.send_command(&cmd).await {
+            wcs.set_offset(system, previous);
+            return Err(e);
+        }
+
+        self.state.write().work_offsets.systems[system as usize - 1] = reference;
+        Ok(())
+    }
+
     /// Initialize the controller and query its capabilities
     // fn initialize(&self) -> anyhow::Result<()> { ... } - Removed as we use async send_command in connect
 
@@ -98,16 +403,19 @@ impl GrblController {
     fn start_io_loop(&mut self) -> anyhow::Result<()> {
         let (cmd_tx, mut cmd_rx) = mpsc::channel::<String>(100);
         let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
+        let (priority_tx, mut priority_rx) = mpsc::unbounded_channel::<String>();
 
         *self.command_tx.write() = Some(cmd_tx);
+        *self.priority_tx.write() = Some(priority_tx);
         *self.shutdown_signal.write() = Some(shutdown_tx);
 
         let communicator = self.communicator.clone();
         let state = self.state.clone();
+        let events = self.events.clone();
 
         let handle = tokio::spawn(async move {
             let mut buffer = String::new();
-            let mut sent_queue: VecDeque<usize> = VecDeque::new();
+            let mut sent_queue: VecDeque<String> = VecDeque::new();
             let mut local_cmd_queue: VecDeque<String> = VecDeque::new();
             let mut last_poll = Instant::now();
             
@@ -132,7 +440,7 @@ impl GrblController {
                         // Process complete lines
                         while let Some(pos) = buffer.find('\n') {
                             let line = buffer[..pos].trim().to_string();
-                            buffer.drain(..=pos + 1); // +1 to remove \n
+                            buffer.drain(..=pos); // inclusive to remove \n
 
                             if !line.is_empty() {
                                 // Check for status report
@@ -158,7 +466,7 @@ impl GrblController {
                                         let s = machine_state.as_str();
                                         
                                         // Update ControllerState (detailed)
-                                        state_guard.state = match s {
+                                        let new_state = match s {
                                             s if s.starts_with("Idle") => ControllerState::Idle,
                                             s if s.starts_with("Run") => ControllerState::Run,
                                             s if s.starts_with("Hold") => ControllerState::Hold,
@@ -171,6 +479,29 @@ impl GrblController {
                                             _ => ControllerState::Idle,
                                         };
 
+                                        // Entering an alarm loses the homed position
+                                        if new_state == ControllerState::Alarm
+                                            && state_guard.state != ControllerState::Alarm
+                                        {
+                                            state_guard.homed = false;
+                                            state_guard.jog_queue.cancel();
+                                        }
+                                        match (new_state, state_guard.homing) {
+                                            (ControllerState::Alarm, _) => {
+                                                state_guard.homing = HomingCycle::Inactive;
+                                            }
+                                            (ControllerState::Idle, HomingCycle::AwaitingIdle) => {
+                                                state_guard.homed = true;
+                                                state_guard.homing = HomingCycle::Inactive;
+                                            }
+                                            _ => {}
+                                        }
+                                        // Outstanding jogs are done once a jog ends in idle
+                                        let jog = state_guard.jog_queue.machine_state(s);
+                                        local_cmd_queue.extend(jog);
+                                        state_guard.state = new_state;
+                                        state_guard.hold_complete = is_hold_complete(s);
+
                                         // Update ControllerStatus (simplified)
                                         state_guard.status = match s {
                                             s if s.starts_with("Idle") => ControllerStatus::Idle,
@@ -182,17 +513,58 @@ impl GrblController {
                                             _ => ControllerStatus::Idle,
                                         };
                                     }
-                                } else if line == "ok" {
-                                    // Acknowledge command
-                                    if let Some(len) = sent_queue.pop_front() {
-                                        communicator.acknowledge_chars(len);
+                                } else if line == "ok" || line.starts_with("error:") {
+                                    // Acknowledge command (an error also consumes a command slot)
+                                    let accepted = line == "ok";
+                                    if !accepted {
+                                        tracing::error!("GRBL Error: {}", line);
                                     }
-                                } else if line.starts_with("error:") {
-                                    // Handle error (also consumes a command slot)
-                                    tracing::error!("GRBL Error: {}", line);
-                                    if let Some(len) = sent_queue.pop_front() {
-                                        communicator.acknowledge_chars(len);
+                                    if let Some(cmd) = sent_queue.pop_front() {
+                                        communicator.acknowledge_chars(cmd.len() + 1);
+                                        if cmd.starts_with("$J=") {
+                                            let jog = state.write().jog_queue.response(accepted);
+                                            local_cmd_queue.extend(jog);
+                                        } else if cmd == "$H" {
+                                            // Homed only once an idle report follows the `ok`
+                                            state.write().homing = if accepted {
+                                                HomingCycle::AwaitingIdle
+                                            } else {
+                                                HomingCycle::Inactive
+                                            };
+                                        }
                                     }
+                                } else if line.starts_with("ALARM:") {
+                                    // A failed homing cycle alarms instead of answering `ok`
+                                    tracing::error!("GRBL Alarm: {}", line);
+                                    let mut state_guard = state.write();
+                                    state_guard.homed = false;
+                                    state_guard.homing = HomingCycle::Inactive;
+                                } else if line.starts_with('[') {
+                                    // $# offsets; [TLO:..] ends the offsets block
+                                    let mut state_guard = state.write();
+                                    if !state_guard.work_offsets.parse_line(&line) {
+                                        tracing::debug!("GRBL Message: {}", line);
+                                    } else if line.starts_with("[TLO:") {
+                                        state_guard.work_offsets_reports += 1;
+                                    }
+                                } else if line.starts_with("Grbl ") && state.read().is_streaming {
+                                    // The banner mid-job means GRBL restarted (e.g. a brownout)
+                                    // and lost its planner; stop before feeding it the rest
+                                    tracing::error!("GRBL reset while streaming: {}", line);
+                                    {
+                                        let mut state_guard = state.write();
+                                        state_guard.is_streaming = false;
+                                        state_guard.unexpected_reset = true;
+                                        state_guard.homed = false;
+                                        state_guard.paused_overrides = None;
+                                        state_guard.state = ControllerState::Alarm;
+                                        state_guard.status = ControllerStatus::Alarm;
+                                    }
+                                    local_cmd_queue.clear();
+                                    sent_queue.clear();
+                                    while cmd_rx.try_recv().is_ok() {}
+                                    let _ = communicator.clear();
+                                    let _ = events.publish(ControllerEvent::Reset(line.clone()));
                                 } else {
                                     // Other messages (welcome, settings, etc)
                                     tracing::debug!("GRBL Message: {}", line);
@@ -208,6 +580,15 @@ impl GrblController {
                     local_cmd_queue.push_back(cmd);
                 }
 
+                // Priority commands jump ahead of everything already queued
+                let mut priority = Vec::new();
+                while let Ok(cmd) = priority_rx.try_recv() {
+                    priority.push(cmd);
+                }
+                for cmd in priority.into_iter().rev() {
+                    local_cmd_queue.push_front(cmd);
+                }
+
                 // 3. WRITE PHASE: Send commands if buffer allows
                 // We peek at the next command
                 if let Some(cmd) = local_cmd_queue.front() {
@@ -216,8 +597,9 @@ impl GrblController {
                         // Send it
                         if let Ok(_) = communicator.send_command(cmd) {
                             // Move to sent queue
-                            sent_queue.push_back(cmd_len);
-                            local_cmd_queue.pop_front();
+                            if let Some(cmd) = local_cmd_queue.pop_front() {
+                                sent_queue.push_back(cmd);
+                            }
                         }
                     }
                 }
@@ -250,6 +632,20 @@ impl GrblController {
 
         Ok(())
     }
+
+    /// Drop what was queued before a soft reset and start over
+    async fn restart_after_reset(&mut self) -> anyhow::Result<()> {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // Reset communicator state
+        self.communicator.clear()?;
+
+        // Restart IO loop to clear queues
+        self.stop_io_loop()?;
+        self.start_io_loop()?;
+
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -326,22 +722,39 @@ impl ControllerTrait for GrblController {
     }
 
     async fn home(&mut self) -> anyhow::Result<()> {
-        self.send_command("$H").await?;
-        Ok(())
+        // The IO loop marks the machine homed once the cycle completes
+        {
+            let mut state = self.state.write();
+            state.homed = false;
+            state.homing = HomingCycle::AwaitingOk;
+        }
+        self.send_command("$H").await
     }
 
     async fn reset(&mut self) -> anyhow::Result<()> {
+        // The banner that follows our own reset must not look like a brownout
+        {
+            let mut state = self.state.write();
+            state.is_streaming = false;
+            state.homed = false;
+            state.homing = HomingCycle::Inactive;
+            state.jog_queue.cancel();
+        }
         self.communicator.send_realtime_byte(0x18)?;
-        tokio::time::sleep(Duration::from_millis(100)).await;
-        
-        // Reset communicator state
-        self.communicator.clear()?;
-        
-        // Restart IO loop to clear queues
-        self.stop_io_loop()?;
-        self.start_io_loop()?;
-        
-        Ok(())
+        self.restart_after_reset().await
+    }
+
+    async fn emergency_stop(&mut self) -> anyhow::Result<()> {
+        {
+            let mut state = self.state.write();
+            state.is_streaming = false;
+            state.homed = false;
+            state.homing = HomingCycle::Inactive;
+            state.jog_queue.cancel();
+        }
+        self.communicator
+            .send_emergency_stop(CommandDialect::for_controller(ControllerType::Grbl))?;
+        self.restart_after_reset().await
     }
 
     async fn clear_alarm(&mut self) -> anyhow::Result<()> {
@@ -367,12 +780,16 @@ impl ControllerTrait for GrblController {
         // Create a jog command using $J= syntax with G91 (relative) and G0 (rapid)
         let direction_str = if direction > 0 { "+" } else { "-" };
         let cmd = format!("$J=G91 G0 {}{} F{:.0}", axis, direction_str, feed_rate);
-        self.send_command(&cmd).await?;
+        let ready = self.state.write().jog_queue.request(cmd);
+        if let Some(cmd) = ready {
+            self.send_command(&cmd).await?;
+        }
 
         Ok(())
     }
 
     async fn jog_stop(&mut self) -> anyhow::Result<()> {
+        self.state.write().jog_queue.cancel();
         self.communicator.send_realtime_byte(0x85)?;
         Ok(())
     }
@@ -385,8 +802,12 @@ impl ControllerTrait for GrblController {
     ) -> anyhow::Result<()> {
         // Format: $J=G91 G0 X{signed_distance} F{feed_rate}
         // distance already includes sign from the caller
+        // Bursts of clicks are held back rather than piling up in the planner
         let cmd = format!("$J=G91 G0 {}{:.3} F{:.0}", axis, distance, feed_rate);
-        self.send_command(&cmd).await?;
+        let ready = self.state.write().jog_queue.request(cmd);
+        if let Some(cmd) = ready {
+            self.send_command(&cmd).await?;
+        }
 
         Ok(())
     }
@@ -394,27 +815,120 @@ impl ControllerTrait for GrblController {
     async fn start_streaming(&mut self) -> anyhow::Result<()> {
         let mut state = self.state.write();
         state.is_streaming = true;
+        state.aborted = false;
+        state.unexpected_reset = false;
         state.state = ControllerState::Run;
         Ok(())
     }
 
     async fn pause_streaming(&mut self) -> anyhow::Result<()> {
         self.communicator.send_realtime_byte(0x21)?;
-        self.state.write().state = ControllerState::Hold;
+        let mut state = self.state.write();
+        state.state = ControllerState::Hold;
+        if state.paused_overrides.is_none() {
+            state.paused_overrides = Some(state.override_state);
+        }
         Ok(())
     }
 
     async fn resume_streaming(&mut self) -> anyhow::Result<()> {
+        let (paused, current, restore) = {
+            let mut state = self.state.write();
+            (
+                state.paused_overrides.take(),
+                state.override_state,
+                state.restore_overrides_on_resume,
+            )
+        };
+
+        // Overrides go out before cycle start so motion resumes at the intended rate
+        if let Some(paused) = paused.filter(|paused| *paused != current) {
+            if restore {
+                for byte in override_state_bytes(&paused) {
+                    self.communicator.send_realtime_byte(byte)?;
+                }
+                self.state.write().override_state = paused;
+            } else {
+                tracing::warn!(
+                    "Overrides changed while paused: feed {}% -> {}%, rapid {}% -> {}%, spindle {}% -> {}%",
+                    paused.feed_override,
+                    current.feed_override,
+                    paused.rapid_override,
+                    current.rapid_override,
+                    paused.spindle_override,
+                    current.spindle_override
+                );
+            }
+        }
+
         self.communicator.send_realtime_byte(0x7E)?;
-        self.state.write().state = ControllerState::Run;
+        let mut state = self.state.write();
+        state.state = ControllerState::Run;
+        state.safety_hold = false;
+        Ok(())
+    }
+
+    async fn safety_hold(&mut self) -> anyhow::Result<()> {
+        // GRBL only accepts the real-time spindle stop override (0x9E) in Hold, so a
+        // machine that never reports Hold gets an M5 ahead of everything queued instead
+        let priority_tx = self
+            .priority_tx
+            .read()
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("Controller not connected"))?;
+        self.communicator.send_realtime_byte(0x21)?;
+        let waited = Instant::now();
+        while self.state.read().state != ControllerState::Hold {
+            if waited.elapsed() >= ABORT_HOLD_TIMEOUT {
+                tracing::warn!("Machine did not enter feed hold; stopping the spindle with M5");
+                priority_tx
+                    .send("M5".to_string())
+                    .map_err(|_| anyhow::anyhow!("Failed to send M5 to IO loop"))?;
+                return Ok(());
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        // Cycle start restores the spindle before motion resumes
+        self.communicator.send_realtime_byte(0x9E)?;
+
+        let mut state = self.state.write();
+        state.state = ControllerState::Hold;
+        state.status = ControllerStatus::Hold;
+        state.safety_hold = true;
+        Ok(())
+    }
+
+    async fn abort(&mut self) -> anyhow::Result<()> {
+        // Resetting mid-motion loses position, so let the feed hold stop the machine first.
+        // Hold:1 is still decelerating; only a report after the hold can say it is done
+        self.state.write().hold_complete = false;
+        self.communicator.send_realtime_byte(0x21)?;
+        let waited = Instant::now();
+        while !self.state.read().hold_complete && waited.elapsed() < ABORT_HOLD_TIMEOUT {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        // Soft reset flushes GRBL's planner; restarting the IO loop drops queued commands
+        self.reset().await?;
+        {
+            let mut state = self.state.write();
+            state.is_streaming = false;
+            state.aborted = true;
+            state.safety_hold = false;
+            state.paused_overrides = None;
+        }
+
+        // Take the state from the next status report instead of assuming idle
+        self.communicator.send_realtime_byte(b'?')?;
         Ok(())
     }
 
     async fn cancel_streaming(&mut self) -> anyhow::Result<()> {
+        self.state.write().is_streaming = false;
         self.communicator.send_realtime_byte(0x18)?;
         let mut state = self.state.write();
-        state.is_streaming = false;
         state.state = ControllerState::Idle;
+        state.paused_overrides = None;
         Ok(())
     }
 
@@ -458,10 +972,9 @@ impl ControllerTrait for GrblController {
 
         self.state.write().override_state.feed_override = percentage;
 
-        // Send real-time override commands based on percentage
-        // GRBL uses specific codes for different percentages
-        if percentage == 100 {
-            self.communicator.send_realtime_byte(0x90)?;
+        // GRBL only steps overrides, so reset to 100% and step to the target
+        for byte in feed_override_bytes(percentage) {
+            self.communicator.send_realtime_byte(byte)?;
         }
 
         Ok(())
@@ -473,6 +986,7 @@ impl ControllerTrait for GrblController {
         }
 
         self.state.write().override_state.rapid_override = percentage;
+        self.communicator.send_realtime_byte(rapid_override_byte(percentage))?;
         Ok(())
     }
 
@@ -482,6 +996,9 @@ impl ControllerTrait for GrblController {
         }
 
         self.state.write().override_state.spindle_override = percentage;
+        for byte in spindle_override_bytes(percentage) {
+            self.communicator.send_realtime_byte(byte)?;
+        }
         Ok(())
     }
 
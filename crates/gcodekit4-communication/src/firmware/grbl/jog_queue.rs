@@ -0,0 +1,152 @@
+//! Bounded queue for jog commands
+//!
+//! Every `$J=` command GRBL accepts goes into its planner and runs to the
+//! end, so clicking a jog button quickly can queue far more motion than the
+//! user meant, and the machine keeps moving after the clicking stops.
+//! [`JogQueue`] only lets a few jogs be outstanding at once. Requests made
+//! while it is full are coalesced into one held jog, which is sent once the
+//! outstanding jogs finish or are rejected, and [`JogQueue::cancel`] drops
+//! everything so a jog cancel (`0x85`) halts the machine promptly when input
+//! stops.
+
+/// Jogs allowed to be outstanding at once by default
+pub const DEFAULT_MAX_PENDING_JOGS: usize = 2;
+
+/// Tracks outstanding jog commands and holds back the rest
+#[derive(Debug, Clone)]
+pub struct JogQueue {
+    max_pending: usize,
+    /// Jogs sent since the machine was last seen stopped
+    pending: usize,
+    /// Jogs sent that have not had their `ok` or `error` yet
+    unanswered: usize,
+    /// Whether a jog was accepted with `ok` since the machine was last seen stopped
+    accepted: bool,
+    /// Latest request made while the queue was full
+    held: Option<String>,
+    /// Whether a `Jog` state was reported since the outstanding jogs were sent
+    jogging: bool,
+}
+
+impl JogQueue {
+    /// Create a queue allowing `max_pending` outstanding jogs (at least one)
+    pub fn new(max_pending: usize) -> Self {
+        Self {
+            max_pending: max_pending.max(1),
+            pending: 0,
+            unanswered: 0,
+            accepted: false,
+            held: None,
+            jogging: false,
+        }
+    }
+
+    /// Number of jogs allowed to be outstanding at once
+    pub fn max_pending(&self) -> usize {
+        self.max_pending
+    }
+
+    /// Number of jogs sent that may still be moving the machine
+    pub fn pending(&self) -> usize {
+        self.pending
+    }
+
+    /// The jog waiting for the outstanding ones to finish, if any
+    pub fn held(&self) -> Option<&str> {
+        self.held.as_deref()
+    }
+
+    /// Ask to send a jog command
+    ///
+    /// Returns the command if it should be sent now. Otherwise it replaces
+    /// any jog already held back, so a burst of requests costs at most one
+    /// extra jog.
+    pub fn request(&mut self, command: impl Into<String>) -> Option<String> {
+        let command = command.into();
+        if self.pending < self.max_pending {
+            self.pending += 1;
+            self.unanswered += 1;
+            return Some(command);
+        }
+        if let Some(dropped) = self.held.replace(command) {
+            tracing::debug!("Coalesced jog request '{}'", dropped);
+        }
+        None
+    }
+
+    /// Record the `ok` (`accepted`) or `error` reply to a jog command
+    ///
+    /// A rejected jog never moves the machine, so it stops being outstanding
+    /// straight away. Replies beyond the jogs sent are ignored. Returns the
+    /// held jog, if any, which should be sent now.
+    pub fn response(&mut self, accepted: bool) -> Option<String> {
+        if self.unanswered == 0 {
+            return None;
+        }
+        self.unanswered -= 1;
+        if accepted {
+            self.accepted = true;
+            return None;
+        }
+        self.pending = self.pending.saturating_sub(1);
+        self.release()
+    }
+
+    /// Record the machine state from a status report, e.g. `Jog` or `Idle`
+    ///
+    /// Outstanding jogs are done once the machine goes from `Jog` to `Idle`,
+    /// or reports `Idle` after a jog was accepted: GRBL is already in `Jog`
+    /// when it replies `ok`, so a short jog can finish between status polls
+    /// without ever being seen moving. An `Idle` report before either leaves
+    /// them outstanding. Returns the held jog, if any, which should be sent
+    /// now.
+    pub fn machine_state(&mut self, state: &str) -> Option<String> {
+        if state.starts_with("Jog") {
+            self.jogging = true;
+            return None;
+        }
+        if !state.starts_with("Idle") {
+            return None;
+        }
+        if std::mem::take(&mut self.jogging) {
+            self.pending = 0;
+            self.unanswered = 0;
+        } else if std::mem::take(&mut self.accepted) {
+            // Jogs still waiting for their reply may not have started yet
+            self.pending = self.unanswered;
+        } else {
+            return None;
+        }
+        self.accepted = false;
+        self.release()
+    }
+
+    /// Send the held jog if there is room for it
+    fn release(&mut self) -> Option<String> {
+        if self.pending >= self.max_pending {
+            return None;
+        }
+        let held = self.held.take()?;
+        self.pending += 1;
+        self.unanswered += 1;
+        Some(held)
+    }
+
+    /// Drop the held jog and forget the outstanding ones
+    ///
+    /// Returns true if jogs may still be moving the machine, in which case a
+    /// jog cancel should be sent.
+    pub fn cancel(&mut self) -> bool {
+        self.held = None;
+        self.jogging = false;
+        self.accepted = false;
+        self.unanswered = 0;
+        std::mem::take(&mut self.pending) > 0
+    }
+}
+
+impl Default for JogQueue {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_PENDING_JOGS)
+    }
+}
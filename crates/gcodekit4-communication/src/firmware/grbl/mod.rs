@@ -9,6 +9,7 @@ pub mod communicator;
 pub mod constants;
 pub mod controller;
 pub mod error_decoder;
+pub mod jog_queue;
 pub mod override_manager;
 pub mod response_parser;
 pub mod settings;
@@ -23,10 +24,16 @@ pub use command_creator::{
 };
 pub use communicator::{GrblCommunicator, GrblCommunicatorConfig};
 pub use constants::*;
-pub use controller::GrblController;
+pub use controller::{abort_communicator, GrblController, ABORT_HOLD_TIMEOUT};
 pub use error_decoder::{decode_alarm, decode_error, format_alarm, format_error};
-pub use override_manager::{OverrideManager, RealTimeOverrideCommand};
-pub use response_parser::{BufferState, GrblResponse, GrblResponseParser, StatusReport};
+pub use jog_queue::{JogQueue, DEFAULT_MAX_PENDING_JOGS};
+pub use override_manager::{
+    feed_override_bytes, override_state_bytes, rapid_override_byte, spindle_override_bytes,
+    OverrideManager, RealTimeOverrideCommand,
+};
+pub use response_parser::{
+    BufferState, GrblResponse, GrblResponseParser, StatusReport, WorkOffsets,
+};
 pub use settings::{Setting, SettingsManager};
 pub use status_parser::{
     BufferRxState, FeedSpindleState, FullStatus, MachinePosition, StatusParser,
@@ -3,6 +3,14 @@
 //! Provides real-time override management for GRBL firmware,
 //! including feed rate, rapid, and spindle speed overrides.
 
+use crate::firmware::grbl::constants::{
+    CMD_FEED_OV_COARSE_MINUS, CMD_FEED_OV_COARSE_PLUS, CMD_FEED_OV_FINE_MINUS,
+    CMD_FEED_OV_FINE_PLUS, CMD_FEED_OV_RESET, CMD_RAPID_OV_LOW, CMD_RAPID_OV_MEDIUM,
+    CMD_RAPID_OV_RESET, CMD_SPINDLE_OV_COARSE_MINUS, CMD_SPINDLE_OV_COARSE_PLUS,
+    CMD_SPINDLE_OV_FINE_MINUS, CMD_SPINDLE_OV_FINE_PLUS, CMD_SPINDLE_OV_RESET,
+};
+use gcodekit4_core::OverrideState;
+
 /// GRBL real-time override commands
 /// According to GRBL protocol specification
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -303,3 +311,70 @@ impl Default for OverrideManager {
         Self::new()
     }
 }
+
+/// Real-time bytes that set the feed override to `percentage`
+///
+/// GRBL has no absolute feed override command, so this resets to 100% and
+/// steps by 10% then 1%. GRBL clamps the result to 10-200%.
+pub fn feed_override_bytes(percentage: u16) -> Vec<u8> {
+    override_step_bytes(
+        percentage,
+        CMD_FEED_OV_RESET,
+        [
+            CMD_FEED_OV_COARSE_PLUS,
+            CMD_FEED_OV_COARSE_MINUS,
+            CMD_FEED_OV_FINE_PLUS,
+            CMD_FEED_OV_FINE_MINUS,
+        ],
+    )
+}
+
+/// Real-time bytes that set the spindle override to `percentage`
+///
+/// Works like [`feed_override_bytes`] with the spindle override commands.
+pub fn spindle_override_bytes(percentage: u16) -> Vec<u8> {
+    override_step_bytes(
+        percentage,
+        CMD_SPINDLE_OV_RESET,
+        [
+            CMD_SPINDLE_OV_COARSE_PLUS,
+            CMD_SPINDLE_OV_COARSE_MINUS,
+            CMD_SPINDLE_OV_FINE_PLUS,
+            CMD_SPINDLE_OV_FINE_MINUS,
+        ],
+    )
+}
+
+/// Real-time byte that sets the rapid override (25, 50 or 100%)
+pub fn rapid_override_byte(percentage: u8) -> u8 {
+    match percentage {
+        25 => CMD_RAPID_OV_LOW,
+        50 => CMD_RAPID_OV_MEDIUM,
+        _ => CMD_RAPID_OV_RESET,
+    }
+}
+
+/// Real-time bytes that restore a complete override state
+pub fn override_state_bytes(state: &OverrideState) -> Vec<u8> {
+    let mut bytes = feed_override_bytes(state.feed_override);
+    bytes.push(rapid_override_byte(state.rapid_override));
+    bytes.extend(spindle_override_bytes(state.spindle_override));
+    bytes
+}
+
+/// Reset to 100% then step towards the target, coarse steps first
+///
+/// `steps` is `[coarse plus, coarse minus, fine plus, fine minus]`.
+fn override_step_bytes(percentage: u16, reset: u8, steps: [u8; 4]) -> Vec<u8> {
+    let [coarse_plus, coarse_minus, fine_plus, fine_minus] = steps;
+    let (coarse, fine, delta) = if percentage >= 100 {
+        (coarse_plus, fine_plus, percentage - 100)
+    } else {
+        (coarse_minus, fine_minus, 100 - percentage)
+    };
+
+    let mut bytes = vec![reset];
+    bytes.extend(std::iter::repeat_n(coarse, (delta / 10) as usize));
+    bytes.extend(std::iter::repeat_n(fine, (delta % 10) as usize));
+    bytes
+}
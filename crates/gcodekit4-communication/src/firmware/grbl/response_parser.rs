@@ -4,6 +4,7 @@
 //! alarm messages, settings responses, and other GRBL-specific responses.
 
 use gcodekit4_core::{CNCPoint, Units};
+use gcodekit4_visualizer::{WorkCoordinateSystem, WorkOffset};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -288,3 +289,111 @@ impl Default for GrblResponseParser {
 }
 
 
+
+/// Stored offsets reported by the `$#` command
+///
+/// GRBL answers `$#` with one bracketed line per offset, e.g.
+/// `[G54:10.000,20.000,-5.000]`, followed by `[TLO:0.000]` and the last probe
+/// result. All values are in millimeters.
+#[derive(Debug, Clone, Copy)]
+pub struct WorkOffsets {
+    /// G54-G59 offsets, index 0 is G54
+    pub systems: [WorkOffset; 6],
+    /// G28 stored position
+    pub g28: WorkOffset,
+    /// G30 stored position
+    pub g30: WorkOffset,
+    /// G92 coordinate offset
+    pub g92: WorkOffset,
+    /// Tool length offset
+    pub tlo: f64,
+}
+
+impl WorkOffsets {
+    /// Parse a complete `$#` response block, ignoring unrelated lines
+    pub fn parse(block: &str) -> Self {
+        let mut offsets = Self::default();
+        for line in block.lines() {
+            offsets.parse_line(line);
+        }
+        offsets
+    }
+
+    /// Parse a single `$#` response line into these offsets
+    ///
+    /// # Returns
+    /// `true` if the line was an offset line and has been applied
+    pub fn parse_line(&mut self, line: &str) -> bool {
+        let Some(body) = line
+            .trim()
+            .strip_prefix('[')
+            .and_then(|l| l.strip_suffix(']'))
+        else {
+            return false;
+        };
+        let Some((name, values)) = body.split_once(':') else {
+            return false;
+        };
+
+        if name == "TLO" {
+            return match values.trim().parse::<f64>() {
+                Ok(tlo) => {
+                    self.tlo = tlo;
+                    true
+                }
+                Err(_) => false,
+            };
+        }
+
+        let coords: Vec<f64> = values
+            .split(',')
+            .filter_map(|v| v.trim().parse::<f64>().ok())
+            .collect();
+        if coords.len() < 3 {
+            return false;
+        }
+        let offset = WorkOffset::new(coords[0], coords[1], coords[2]);
+
+        match name {
+            "G54" | "G55" | "G56" | "G57" | "G58" | "G59" => {
+                let index = name[1..].parse::<usize>().unwrap_or(54) - 54;
+                self.systems[index] = offset;
+            }
+            "G28" => self.g28 = offset,
+            "G30" => self.g30 = offset,
+            "G92" => self.g92 = offset,
+            _ => return false,
+        }
+        true
+    }
+
+    /// Get the offset for a work coordinate system number (54-59)
+    pub fn system(&self, wcs: u8) -> Option<WorkOffset> {
+        if (54..=59).contains(&wcs) {
+            Some(self.systems[(wcs - 54) as usize])
+        } else {
+            None
+        }
+    }
+
+    /// Build a `WorkCoordinateSystem` with G54-G59 as systems 1-6
+    pub fn to_coordinate_system(&self) -> WorkCoordinateSystem {
+        let mut wcs = WorkCoordinateSystem::new();
+        for (i, offset) in self.systems.iter().enumerate() {
+            wcs.set_offset(i as u32 + 1, *offset);
+        }
+        wcs
+    }
+}
+
+impl Default for WorkOffsets {
+    fn default() -> Self {
+        Self {
+            systems: [WorkOffset::zero(); 6],
+            g28: WorkOffset::zero(),
+            g30: WorkOffset::zero(),
+            g92: WorkOffset::zero(),
+            tlo: 0.0,
+        }
+    }
+}
@@ -95,6 +95,23 @@ pub fn is_held_state(state: &str) -> bool {
     state == "Hold"
 }
 
+/// Check if motion has stopped after a feed hold
+///
+/// `Hold:0` means the hold has finished decelerating, while `Hold:1` is still
+/// slowing down. A bare `Hold`, from firmware that doesn't report the
+/// substate, can't tell the two apart and counts as moving. States without
+/// motion, such as `Idle` or `Alarm`, count as stopped.
+pub fn is_hold_complete(state: &str) -> bool {
+    let (name, substate) = state.split_once(':').unwrap_or((state, ""));
+    match name {
+        "Run" | "Jog" | "Home" => false,
+        "Hold" => substate == "0",
+        // Door:2 is still holding or parking, Door:3 is resuming
+        "Door" => !matches!(substate, "2" | "3"),
+        _ => true,
+    }
+}
+
 /// Get error code lookup map
 pub fn get_error_codes() -> HashMap<u8, &'static str> {
     let mut map = HashMap::new();
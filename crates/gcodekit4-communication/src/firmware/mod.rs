@@ -11,6 +11,7 @@ pub mod capabilities;
 pub mod capabilities_db;
 pub mod capability_manager;
 pub mod connection_watch;
+pub mod dialect;
 pub mod device_db;
 pub mod device_status;
 pub mod file_service;
@@ -25,11 +26,12 @@ pub mod smoothieware;
 pub mod tinyg;
 
 pub use capabilities::{CapabilitiesTrait, Capability, DefaultCapabilities};
-pub use capability_manager::{CapabilityManager, CapabilityState};
+pub use capability_manager::{validate_against_capabilities, CapabilityManager, CapabilityState};
 pub use connection_watch::{ConnectionWatchConfig, ConnectionWatchState, ConnectionWatcher};
+pub use dialect::CommandDialect;
 pub use file_service::{FileInfo, FileServiceTrait, NoOpFileService, StorageInfo};
 pub use firmware_detector::{FirmwareDetectionResult, FirmwareDetector};
-pub use fluidnc::{FluidNCCapabilities, FluidNCController, FluidNCVersion};
+pub use fluidnc::{FluidNCCapabilities, FluidNCController, FluidNCSdUploader, FluidNCVersion};
 pub use g2core::{G2CoreCapabilities, G2CoreController, G2CoreVersion as G2CoreVer};
 pub use grbl::GrblCapabilities;
 pub use override_manager::{
@@ -52,6 +54,8 @@ pub enum ControllerType {
     Smoothieware,
     /// FluidNC
     FluidNC,
+    /// Marlin
+    Marlin,
     /// Unknown/generic
     Unknown,
 }
@@ -64,6 +68,7 @@ impl std::fmt::Display for ControllerType {
             Self::G2Core => write!(f, "g2core"),
             Self::Smoothieware => write!(f, "Smoothieware"),
             Self::FluidNC => write!(f, "FluidNC"),
+            Self::Marlin => write!(f, "Marlin"),
             Self::Unknown => write!(f, "Unknown"),
         }
     }
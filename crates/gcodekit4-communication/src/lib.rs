@@ -9,11 +9,19 @@ pub mod communication;
 pub mod firmware;
 
 pub use communication::{
-    serial::{list_ports, SerialPortInfo},
+    macro_run::MacroRunResult,
+    pipe::{stream_lines, wait_for_ready},
+    serial::{
+        apply_control_lines, list_ports, ports_from_enumeration, ControlLines, SerialPortInfo,
+    },
+    startup::run_startup_commands,
     tcp::TcpConnectionInfo,
-    Communicator, CommunicatorEvent, CommunicatorListener, CommunicatorListenerHandle,
-    ConnectionDriver, ConnectionParams, NoOpCommunicator, SerialCommunicator, SerialParity,
-    TcpCommunicator,
+    AsyncController, AsyncControllerConfig, Communicator, CommunicatorEvent, CommunicatorListener,
+    CommunicatorListenerHandle, ConnectionDriver, ConnectionParams, NoOpCommunicator,
+    SerialCommunicator, SerialParity, TcpCommunicator,
 };
 
-pub use firmware::{CapabilityManager, CapabilityState, ControllerType, FirmwareDetector};
+pub use firmware::{
+    validate_against_capabilities, CapabilityManager, CapabilityState, ControllerType,
+    FirmwareDetector,
+};
@@ -0,0 +1,251 @@
+//! Tests for communication::async_controller
+
+use gcodekit4_communication::{
+    AsyncController, AsyncControllerConfig, Communicator, CommunicatorListenerHandle,
+    ConnectionParams,
+};
+use gcodekit4_core::{ControllerTrait, SimpleController};
+use gcodekit4_visualizer::CustomMacro;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::oneshot::error::TryRecvError;
+
+const STATUS: &str = "<Idle|MPos:0.000,0.000,0.000|FS:0,0>";
+
+/// Communicator that answers each command line from a script and records
+/// what was sent
+#[derive(Default)]
+struct ScriptedCommunicator {
+    script: Vec<(&'static str, &'static str)>,
+    sent: Arc<Mutex<Vec<String>>>,
+    line: String,
+    output: String,
+    read_delay: Duration,
+}
+
+impl ScriptedCommunicator {
+    fn new(script: &[(&'static str, &'static str)]) -> Self {
+        Self {
+            script: script.to_vec(),
+            ..Self::default()
+        }
+    }
+}
+
+impl Communicator for ScriptedCommunicator {
+    fn connect(&mut self, _params: &ConnectionParams) -> gcodekit4_core::Result<()> {
+        Ok(())
+    }
+
+    fn disconnect(&mut self) -> gcodekit4_core::Result<()> {
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        true
+    }
+
+    fn send(&mut self, data: &[u8]) -> gcodekit4_core::Result<usize> {
+        if data == b"?" {
+            self.output.push_str(STATUS);
+            self.output.push('\n');
+            return Ok(1);
+        }
+        self.line.push_str(&String::from_utf8_lossy(data));
+        while let Some(pos) = self.line.find('\n') {
+            let line: String = self.line.drain(..=pos).collect();
+            let line = line.trim().to_string();
+            if let Some((_, reply)) = self.script.iter().find(|(command, _)| *command == line) {
+                self.output.push_str(reply);
+            }
+            self.sent.lock().unwrap().push(line);
+        }
+        Ok(data.len())
+    }
+
+    fn receive(&mut self) -> gcodekit4_core::Result<Vec<u8>> {
+        // Like a serial read waiting out its timeout
+        std::thread::sleep(self.read_delay);
+        Ok(std::mem::take(&mut self.output).into_bytes())
+    }
+
+    fn add_listener(&mut self, _listener: CommunicatorListenerHandle) {}
+
+    fn remove_listener(&mut self, _listener: &CommunicatorListenerHandle) {}
+
+    fn connection_params(&self) -> Option<&ConnectionParams> {
+        None
+    }
+
+    fn set_connection_params(&mut self, _params: ConnectionParams) -> gcodekit4_core::Result<()> {
+        Ok(())
+    }
+}
+
+async fn resolve<T>(receiver: tokio::sync::oneshot::Receiver<T>) -> T {
+    tokio::time::timeout(Duration::from_secs(1), receiver)
+        .await
+        .expect("response timed out")
+        .expect("response dropped")
+}
+
+#[tokio::test]
+async fn test_send_returns_immediately_and_resolves_on_response() {
+    let communicator = ScriptedCommunicator::new(&[("$$", "$0=10\n$1=25\nok\n")]);
+    let controller = AsyncController::spawn(Box::new(communicator));
+
+    let mut pending = controller.send("$$");
+    // Nothing has been read yet, and the caller isn't held up waiting for it
+    assert!(matches!(pending.try_recv(), Err(TryRecvError::Empty)));
+
+    let response = resolve(pending).await;
+    assert!(response.is_ok());
+    assert_eq!(response.command, "$$");
+    assert_eq!(response.lines, vec!["$0=10", "$1=25"]);
+}
+
+#[tokio::test]
+async fn test_queued_commands_resolve_in_order() {
+    let communicator = ScriptedCommunicator::new(&[("G21", "ok\n"), ("G99", "error:20\n")]);
+    let sent = communicator.sent.clone();
+    let controller = AsyncController::spawn(Box::new(communicator));
+
+    let first = controller.send("G21");
+    let second = controller.send("G99");
+    let third = controller.send("G21");
+
+    assert!(resolve(first).await.is_ok());
+    assert_eq!(resolve(second).await.error.as_deref(), Some("error:20"));
+    assert!(resolve(third).await.is_ok());
+    assert_eq!(*sent.lock().unwrap(), vec!["G21", "G99", "G21"]);
+}
+
+#[tokio::test]
+async fn test_status_reports_go_to_subscribers() {
+    let reply = "<Idle|MPos:0.000,0.000,0.000|FS:0,0>\n[GC:G0 G54 G17 G21]\nok\n";
+    let communicator = ScriptedCommunicator::new(&[("$G", reply)]);
+    let controller = AsyncController::spawn(Box::new(communicator));
+    let mut messages = controller.subscribe();
+
+    let response = resolve(controller.send("$G")).await;
+    assert_eq!(response.lines, vec!["[GC:G0 G54 G17 G21]"]);
+    assert_eq!(messages.recv().await.unwrap(), STATUS);
+
+    controller.send_realtime(b'?');
+    let status = tokio::time::timeout(Duration::from_secs(1), messages.recv())
+        .await
+        .unwrap();
+    assert_eq!(status.unwrap(), STATUS);
+}
+
+#[tokio::test]
+async fn test_unanswered_command_times_out() {
+    let config = AsyncControllerConfig {
+        response_timeout: Duration::from_millis(50),
+        ..AsyncControllerConfig::default()
+    };
+    let communicator = ScriptedCommunicator::new(&[("G21", "ok\n")]);
+    let controller = AsyncController::spawn_with_config(Box::new(communicator), config);
+
+    let silent = controller.send("G4 P10");
+    let next = controller.send("G21");
+
+    let response = resolve(silent).await;
+    assert!(response.error.unwrap().contains("Timed out"));
+    assert!(resolve(next).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_blocking_reads_leave_the_runtime_free() {
+    let communicator = ScriptedCommunicator {
+        read_delay: Duration::from_millis(200),
+        ..ScriptedCommunicator::new(&[])
+    };
+    let _controller = AsyncController::spawn(Box::new(communicator));
+
+    // The test runtime has one thread, which a read on it would stall
+    let started = std::time::Instant::now();
+    tokio::task::yield_now().await;
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    assert!(started.elapsed() < Duration::from_millis(100));
+}
+
+#[tokio::test]
+async fn test_shutdown_fails_pending_commands() {
+    let controller = AsyncController::spawn(Box::new(ScriptedCommunicator::new(&[])));
+    let channel = controller.channel();
+    let pending = controller.send("G4 P10");
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    assert!(controller.shutdown().await.is_some());
+    assert!(!resolve(pending).await.is_ok());
+    assert!(channel.is_closed());
+    assert!(!resolve(channel.send("G21")).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_simple_controller_forwards_through_channel() {
+    let communicator = ScriptedCommunicator::new(&[("G21", "ok\n"), ("G99", "error:20\n")]);
+    let sent = communicator.sent.clone();
+    let controller = AsyncController::spawn(Box::new(communicator));
+    let mut simple = SimpleController::new("grbl").with_channel(controller.channel());
+
+    simple.send_command("G21").await.unwrap();
+    let err = simple.send_command("G99").await.unwrap_err();
+    assert!(err.to_string().contains("error:20"));
+    assert_eq!(*sent.lock().unwrap(), vec!["G21", "G99"]);
+}
+
+#[tokio::test]
+async fn test_run_macro_reports_failing_line() {
+    let communicator = ScriptedCommunicator::new(&[
+        ("G21", "ok\n"),
+        ("G0 Z5", "ok\n"),
+        ("G1 X10 F500", "error:22\n"),
+        ("G0 X0", "ok\n"),
+    ]);
+    let sent = communicator.sent.clone();
+    let controller = AsyncController::spawn(Box::new(communicator));
+
+    let mut custom_macro = CustomMacro::new(
+        "square",
+        "G21\n; lift\nG0 Z${safe}\nG1 X${size} F${feed}\nG0 X0",
+    );
+    custom_macro.set_variable("safe", "5");
+    custom_macro.set_variable("size", "10");
+    let vars = HashMap::from([("feed".to_string(), "500".to_string())]);
+
+    let result = controller.run_macro(&custom_macro, &vars).await;
+
+    assert!(!result.is_ok());
+    assert_eq!(result.name, "square");
+    assert_eq!(result.failed_line, Some(4));
+    assert_eq!(result.line_numbers, vec![1, 3, 4, 5]);
+    let failure = result.failure().unwrap();
+    assert_eq!(failure.command, "G1 X10 F500");
+    assert_eq!(failure.error.as_deref(), Some("error:22"));
+    // Lines after the rejected one were still streamed and answered
+    assert!(result.responses[3].is_ok());
+    assert_eq!(
+        *sent.lock().unwrap(),
+        vec!["G21", "G0 Z5", "G1 X10 F500", "G0 X0"]
+    );
+}
+
+#[tokio::test]
+async fn test_run_macro_succeeds_when_every_line_is_accepted() {
+    let communicator = ScriptedCommunicator::new(&[("G0 Z5", "ok\n"), ("G0 X0 Y0", "ok\n")]);
+    let controller = AsyncController::spawn(Box::new(communicator));
+
+    let mut custom_macro = CustomMacro::new("park", "G0 Z${z}\n\nG0 X0 Y0");
+    custom_macro.set_variable("z", "1");
+    let vars = HashMap::from([("z".to_string(), "5".to_string())]);
+
+    let result = controller.run_macro(&custom_macro, &vars).await;
+
+    assert!(result.is_ok());
+    assert!(result.failure().is_none());
+    assert_eq!(result.responses.len(), 2);
+    assert_eq!(result.responses[0].command, "G0 Z5");
+}
@@ -0,0 +1,6 @@
+mod async_controller;
+mod pipe;
+mod reconnect;
+mod serial_control_lines;
+mod serial_ports;
+mod startup;
@@ -0,0 +1,210 @@
+//! Tests for communication::pipe
+
+use gcodekit4_communication::communication::{
+    BufferedCommunicatorConfig, BufferedCommunicatorWrapper,
+};
+use gcodekit4_communication::{
+    stream_lines, wait_for_ready, Communicator, CommunicatorListenerHandle, ConnectionParams,
+};
+use std::io::{BufRead, Cursor, Read};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Communicator that answers `ok` to every line, or a scripted reply, and
+/// records what was sent
+#[derive(Default)]
+struct ScriptedCommunicator {
+    script: Vec<(&'static str, &'static str)>,
+    sent: Arc<Mutex<Vec<String>>>,
+    line: String,
+    output: String,
+}
+
+impl Communicator for ScriptedCommunicator {
+    fn connect(&mut self, _params: &ConnectionParams) -> gcodekit4_core::Result<()> {
+        Ok(())
+    }
+
+    fn disconnect(&mut self) -> gcodekit4_core::Result<()> {
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        true
+    }
+
+    fn send(&mut self, data: &[u8]) -> gcodekit4_core::Result<usize> {
+        self.line.push_str(&String::from_utf8_lossy(data));
+        while let Some(pos) = self.line.find('\n') {
+            let line: String = self.line.drain(..=pos).collect();
+            let line = line.trim().to_string();
+            let reply = self
+                .script
+                .iter()
+                .find(|(command, _)| *command == line)
+                .map_or("ok\n", |(_, reply)| *reply);
+            self.output.push_str(reply);
+            self.sent.lock().unwrap().push(line);
+        }
+        Ok(data.len())
+    }
+
+    fn receive(&mut self) -> gcodekit4_core::Result<Vec<u8>> {
+        Ok(std::mem::take(&mut self.output).into_bytes())
+    }
+
+    fn add_listener(&mut self, _listener: CommunicatorListenerHandle) {}
+
+    fn remove_listener(&mut self, _listener: &CommunicatorListenerHandle) {}
+
+    fn connection_params(&self) -> Option<&ConnectionParams> {
+        None
+    }
+
+    fn set_connection_params(&mut self, _params: ConnectionParams) -> gcodekit4_core::Result<()> {
+        Ok(())
+    }
+}
+
+/// Reader that hands out one line at a time, like a producer still writing,
+/// and notes how many lines had been sent before each line was produced
+struct TrickleReader {
+    lines: Vec<String>,
+    current: Cursor<Vec<u8>>,
+    sent: Arc<Mutex<Vec<String>>>,
+    sent_before: Vec<usize>,
+}
+
+impl Read for TrickleReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = {
+            let available = self.fill_buf()?;
+            let n = available.len().min(buf.len());
+            buf[..n].copy_from_slice(&available[..n]);
+            n
+        };
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl BufRead for TrickleReader {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        let exhausted = self.current.position() as usize == self.current.get_ref().len();
+        if exhausted && !self.lines.is_empty() {
+            self.sent_before.push(self.sent.lock().unwrap().len());
+            self.current = Cursor::new(self.lines.remove(0).into_bytes());
+        }
+        self.current.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.current.consume(amount);
+    }
+}
+
+fn sender(communicator: ScriptedCommunicator) -> BufferedCommunicatorWrapper {
+    BufferedCommunicatorWrapper::new(
+        Box::new(communicator),
+        BufferedCommunicatorConfig::default(),
+    )
+}
+
+#[test]
+fn test_piped_program_is_sent_in_order() {
+    let communicator = ScriptedCommunicator::default();
+    let sent = communicator.sent.clone();
+    let mut sender = sender(communicator);
+
+    let program = "G21 G90\n; from the post\n\nG0 X0 Y0\n(plunge)\nG1 Z-1 F200\nG1 X10\nM2\n";
+    let count = stream_lines(Cursor::new(program), &mut sender, Duration::from_secs(1)).unwrap();
+
+    assert_eq!(count, 5);
+    assert_eq!(
+        *sent.lock().unwrap(),
+        vec!["G21 G90", "G0 X0 Y0", "G1 Z-1 F200", "G1 X10", "M2"]
+    );
+    assert_eq!(sender.active_commands_count().unwrap(), 0);
+}
+
+#[test]
+fn test_lines_are_sent_as_they_arrive() {
+    let communicator = ScriptedCommunicator::default();
+    let sent = communicator.sent.clone();
+    // Long enough to fill the controller's 128 byte buffer several times
+    let lines: Vec<String> = (0..40)
+        .map(|i| format!("G1 X{} Y{} F1000\n", i, i * 2))
+        .collect();
+    let mut reader = TrickleReader {
+        lines: lines.clone(),
+        current: Cursor::new(Vec::new()),
+        sent: sent.clone(),
+        sent_before: Vec::new(),
+    };
+    let mut sender = sender(communicator);
+
+    let count = stream_lines(&mut reader, &mut sender, Duration::from_secs(1)).unwrap();
+
+    assert_eq!(count, 40);
+    let expected: Vec<String> = lines.iter().map(|line| line.trim().to_string()).collect();
+    assert_eq!(*sent.lock().unwrap(), expected);
+    // Every line went out before the producer had to write the next one
+    assert_eq!(reader.sent_before, (0..40).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_rejected_line_stops_stream() {
+    let communicator = ScriptedCommunicator {
+        script: vec![("G99", "error:20\n")],
+        ..ScriptedCommunicator::default()
+    };
+    let mut sender = sender(communicator);
+
+    let err = stream_lines(
+        Cursor::new("G21\nG99\nG0 X10\n"),
+        &mut sender,
+        Duration::from_secs(1),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("error:20"));
+}
+
+#[test]
+fn test_wait_for_ready_returns_on_banner() {
+    let mut communicator = ScriptedCommunicator {
+        output: "\r\nGrbl 1.1h ['$' for help]\r\n".to_string(),
+        ..ScriptedCommunicator::default()
+    };
+    let sent = communicator.sent.clone();
+
+    wait_for_ready(
+        &mut communicator,
+        Duration::from_secs(1),
+        Duration::from_secs(2),
+    )
+    .unwrap();
+    assert!(sent.lock().unwrap().is_empty());
+}
+
+#[test]
+fn test_wait_for_ready_polls_a_controller_that_did_not_reset() {
+    let mut communicator = ScriptedCommunicator::default();
+    let sent = communicator.sent.clone();
+
+    wait_for_ready(&mut communicator, Duration::ZERO, Duration::from_secs(1)).unwrap();
+    // One empty line, whose ok was consumed
+    assert_eq!(*sent.lock().unwrap(), vec![""]);
+    assert!(communicator.receive().unwrap().is_empty());
+}
+
+#[test]
+fn test_wait_for_ready_times_out_on_silent_controller() {
+    let mut communicator = ScriptedCommunicator {
+        script: vec![("", "")],
+        ..ScriptedCommunicator::default()
+    };
+
+    let err =
+        wait_for_ready(&mut communicator, Duration::ZERO, Duration::from_millis(50)).unwrap_err();
+    assert!(err.to_string().contains("Timed out"));
+}
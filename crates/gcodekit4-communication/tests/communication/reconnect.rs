@@ -0,0 +1,98 @@
+//! Tests for Communicator::reconnect
+
+use gcodekit4_communication::{Communicator, CommunicatorListenerHandle, ConnectionParams};
+use std::time::Duration;
+
+/// Communicator whose next few connection attempts fail
+struct FlakyCommunicator {
+    failures: u32,
+    attempts: u32,
+    connected: bool,
+    params: Option<ConnectionParams>,
+}
+
+impl FlakyCommunicator {
+    fn new(failures: u32, params: ConnectionParams) -> Self {
+        Self {
+            failures,
+            attempts: 0,
+            connected: false,
+            params: Some(params),
+        }
+    }
+}
+
+impl Communicator for FlakyCommunicator {
+    fn connect(&mut self, _params: &ConnectionParams) -> gcodekit4_core::Result<()> {
+        self.attempts += 1;
+        if self.failures > 0 {
+            self.failures -= 1;
+            return Err(gcodekit4_core::Error::other("Port busy"));
+        }
+        self.connected = true;
+        Ok(())
+    }
+
+    fn disconnect(&mut self) -> gcodekit4_core::Result<()> {
+        self.connected = false;
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    fn send(&mut self, data: &[u8]) -> gcodekit4_core::Result<usize> {
+        Ok(data.len())
+    }
+
+    fn receive(&mut self) -> gcodekit4_core::Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn add_listener(&mut self, _listener: CommunicatorListenerHandle) {}
+
+    fn remove_listener(&mut self, _listener: &CommunicatorListenerHandle) {}
+
+    fn connection_params(&self) -> Option<&ConnectionParams> {
+        self.params.as_ref()
+    }
+
+    fn set_connection_params(&mut self, params: ConnectionParams) -> gcodekit4_core::Result<()> {
+        self.params = Some(params);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_reconnect_retries_until_connected() {
+    let mut communicator = FlakyCommunicator::new(2, ConnectionParams::default());
+
+    assert_eq!(communicator.reconnect(Duration::ZERO).unwrap(), 3);
+    assert!(communicator.is_connected());
+}
+
+#[test]
+fn test_reconnect_gives_up_after_max_retries() {
+    let params = ConnectionParams {
+        max_retries: 2,
+        ..ConnectionParams::default()
+    };
+    let mut communicator = FlakyCommunicator::new(5, params);
+
+    assert!(communicator.reconnect(Duration::ZERO).is_err());
+    assert_eq!(communicator.attempts, 2);
+    assert!(!communicator.is_connected());
+}
+
+#[test]
+fn test_reconnect_respects_disabled_auto_reconnect() {
+    let params = ConnectionParams {
+        auto_reconnect: false,
+        ..ConnectionParams::default()
+    };
+    let mut communicator = FlakyCommunicator::new(0, params);
+
+    assert!(communicator.reconnect(Duration::ZERO).is_err());
+    assert_eq!(communicator.attempts, 0);
+}
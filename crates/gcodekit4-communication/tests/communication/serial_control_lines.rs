@@ -0,0 +1,83 @@
+use gcodekit4_communication::{apply_control_lines, ConnectionParams, ControlLines};
+use std::io;
+
+#[derive(Default)]
+struct MockLines {
+    dtr: Vec<bool>,
+    rts: Vec<bool>,
+}
+
+impl ControlLines for MockLines {
+    fn write_data_terminal_ready(&mut self, level: bool) -> io::Result<()> {
+        self.dtr.push(level);
+        Ok(())
+    }
+
+    fn write_request_to_send(&mut self, level: bool) -> io::Result<()> {
+        self.rts.push(level);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_control_lines_default_leaves_lines_untouched() {
+    let params = ConnectionParams::serial("/dev/ttyUSB0", 115200);
+    let mut lines = MockLines::default();
+
+    apply_control_lines(&params, &mut lines).unwrap();
+    assert!(lines.dtr.is_empty());
+    assert!(lines.rts.is_empty());
+    assert!(!params.is_no_reset());
+}
+
+#[test]
+fn test_control_lines_no_reset_deasserts_dtr_and_rts() {
+    let params = ConnectionParams::serial("/dev/ttyUSB0", 115200).with_no_reset();
+    let mut lines = MockLines::default();
+
+    apply_control_lines(&params, &mut lines).unwrap();
+    assert!(params.is_no_reset());
+    assert_eq!(lines.dtr, vec![false]);
+    assert_eq!(lines.rts, vec![false]);
+}
+
+#[test]
+fn test_control_lines_assert_dtr_only() {
+    let params =
+        ConnectionParams::serial("/dev/ttyACM0", 115200).with_control_lines(Some(true), None);
+    let mut lines = MockLines::default();
+
+    apply_control_lines(&params, &mut lines).unwrap();
+    assert_eq!(lines.dtr, vec![true]);
+    assert!(lines.rts.is_empty());
+}
+
+#[test]
+fn test_control_lines_rts_skipped_with_hardware_flow_control() {
+    let mut params =
+        ConnectionParams::serial("/dev/ttyUSB0", 115200).with_control_lines(None, Some(true));
+    params.flow_control = true;
+    let mut lines = MockLines::default();
+
+    apply_control_lines(&params, &mut lines).unwrap();
+    assert!(lines.rts.is_empty());
+}
+
+#[test]
+fn test_control_lines_error_is_reported() {
+    struct FailingLines;
+
+    impl ControlLines for FailingLines {
+        fn write_data_terminal_ready(&mut self, _level: bool) -> io::Result<()> {
+            Err(io::Error::other("not supported"))
+        }
+
+        fn write_request_to_send(&mut self, _level: bool) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let params = ConnectionParams::serial("/dev/ttyUSB0", 115200).with_no_reset();
+    let err = apply_control_lines(&params, &mut FailingLines).unwrap_err();
+    assert!(err.to_string().contains("DTR"));
+}
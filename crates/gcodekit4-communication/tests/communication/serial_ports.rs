@@ -0,0 +1,53 @@
+use gcodekit4_communication::ports_from_enumeration;
+use serialport::{SerialPortInfo as OsPortInfo, SerialPortType, UsbPortInfo};
+
+fn usb_port(name: &str, vid: u16, pid: u16, manufacturer: &str, product: &str) -> OsPortInfo {
+    OsPortInfo {
+        port_name: name.to_string(),
+        port_type: SerialPortType::UsbPort(UsbPortInfo {
+            vid,
+            pid,
+            serial_number: Some("A50285BI".to_string()),
+            manufacturer: Some(manufacturer.to_string()),
+            product: Some(product.to_string()),
+        }),
+    }
+}
+
+#[test]
+fn test_ports_from_enumeration_copies_usb_metadata() {
+    let ports = ports_from_enumeration(&[
+        usb_port("/dev/ttyUSB0", 0x0403, 0x6001, "FTDI", "FT232R USB UART"),
+        usb_port("/dev/ttyUSB1", 0x1a86, 0x7523, "QinHeng", "CH340 serial converter"),
+    ]);
+
+    assert_eq!(ports.len(), 2);
+    assert_eq!(ports[0].vid, Some(0x0403));
+    assert_eq!(ports[0].pid, Some(0x6001));
+    assert_eq!(ports[0].manufacturer.as_deref(), Some("FTDI"));
+    assert_eq!(ports[0].product.as_deref(), Some("FT232R USB UART"));
+    assert_eq!(ports[0].serial_number.as_deref(), Some("A50285BI"));
+    assert_eq!(
+        ports[1].details(),
+        "QinHeng CH340 serial converter (1a86:7523) SN A50285BI"
+    );
+}
+
+#[test]
+fn test_ports_from_enumeration_filters_and_describes_non_usb_ports() {
+    let ports = ports_from_enumeration(&[
+        OsPortInfo {
+            port_name: "/dev/ttyS0".to_string(),
+            port_type: SerialPortType::Unknown,
+        },
+        OsPortInfo {
+            port_name: "COM3".to_string(),
+            port_type: SerialPortType::PciPort,
+        },
+    ]);
+
+    assert_eq!(ports.len(), 1);
+    assert_eq!(ports[0].port_name, "COM3");
+    assert_eq!(ports[0].vid, None);
+    assert_eq!(ports[0].details(), "PCI Serial");
+}
@@ -0,0 +1,125 @@
+//! Tests for communication::startup
+
+use gcodekit4_communication::{
+    run_startup_commands, Communicator, CommunicatorListenerHandle, ConnectionParams,
+};
+use std::time::Duration;
+
+/// Communicator that answers each command line from a script and records
+/// what was sent
+#[derive(Default)]
+struct ScriptedCommunicator {
+    script: Vec<(&'static str, &'static str)>,
+    sent: Vec<String>,
+    line: String,
+    output: String,
+    connected: bool,
+}
+
+impl ScriptedCommunicator {
+    fn new(script: &[(&'static str, &'static str)]) -> Self {
+        Self {
+            script: script.to_vec(),
+            ..Self::default()
+        }
+    }
+}
+
+impl Communicator for ScriptedCommunicator {
+    fn connect(&mut self, _params: &ConnectionParams) -> gcodekit4_core::Result<()> {
+        self.connected = true;
+        Ok(())
+    }
+
+    fn disconnect(&mut self) -> gcodekit4_core::Result<()> {
+        self.connected = false;
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    fn send(&mut self, data: &[u8]) -> gcodekit4_core::Result<usize> {
+        self.line.push_str(&String::from_utf8_lossy(data));
+        while let Some(pos) = self.line.find('\n') {
+            let line: String = self.line.drain(..=pos).collect();
+            let line = line.trim().to_string();
+            if let Some((_, reply)) = self.script.iter().find(|(command, _)| *command == line) {
+                self.output.push_str(reply);
+            }
+            self.sent.push(line);
+        }
+        Ok(data.len())
+    }
+
+    fn receive(&mut self) -> gcodekit4_core::Result<Vec<u8>> {
+        Ok(std::mem::take(&mut self.output).into_bytes())
+    }
+
+    fn add_listener(&mut self, _listener: CommunicatorListenerHandle) {}
+
+    fn remove_listener(&mut self, _listener: &CommunicatorListenerHandle) {}
+
+    fn connection_params(&self) -> Option<&ConnectionParams> {
+        None
+    }
+
+    fn set_connection_params(&mut self, _params: ConnectionParams) -> gcodekit4_core::Result<()> {
+        Ok(())
+    }
+}
+
+fn commands(lines: &[&str]) -> Vec<String> {
+    lines.iter().map(|line| line.to_string()).collect()
+}
+
+#[test]
+fn test_connect_runs_startup_commands_in_order() {
+    let mut communicator = ScriptedCommunicator::new(&[
+        ("G21", "ok\n"),
+        ("$10=1", "ok\n"),
+        ("$G", "[GC:G0 G54 G17 G21 G90 G94 M5 M9 T0 F0 S0]\nok\n"),
+    ]);
+    communicator.connect(&ConnectionParams::default()).unwrap();
+
+    let startup = commands(&["G21", "; report mask", "", "$10=1", "$G"]);
+    let responses = run_startup_commands(&mut communicator, &startup, Duration::from_secs(1));
+
+    assert_eq!(communicator.sent, vec!["G21", "$10=1", "$G"]);
+    assert_eq!(responses.len(), 3);
+    assert!(responses.iter().all(|response| response.is_ok()));
+    assert_eq!(
+        responses[2].lines,
+        vec!["[GC:G0 G54 G17 G21 G90 G94 M5 M9 T0 F0 S0]"]
+    );
+}
+
+#[test]
+fn test_startup_commands_stop_on_error() {
+    let mut communicator =
+        ScriptedCommunicator::new(&[("G21", "ok\n"), ("$99=1", "error:3\n"), ("$G", "ok\n")]);
+    communicator.connect(&ConnectionParams::default()).unwrap();
+
+    let startup = commands(&["G21", "$99=1", "$G"]);
+    let responses = run_startup_commands(&mut communicator, &startup, Duration::from_secs(1));
+
+    assert_eq!(communicator.sent, vec!["G21", "$99=1"]);
+    assert_eq!(responses.len(), 2);
+    assert!(responses[0].is_ok());
+    assert_eq!(responses[1].command, "$99=1");
+    assert_eq!(responses[1].error.as_deref(), Some("error:3"));
+}
+
+#[test]
+fn test_startup_commands_time_out_without_answer() {
+    let mut communicator = ScriptedCommunicator::new(&[]);
+    communicator.connect(&ConnectionParams::default()).unwrap();
+
+    let startup = commands(&["G21", "$G"]);
+    let responses = run_startup_commands(&mut communicator, &startup, Duration::from_millis(50));
+
+    assert_eq!(communicator.sent, vec!["G21"]);
+    assert_eq!(responses.len(), 1);
+    assert!(responses[0].error.as_deref().unwrap().contains("Timed out"));
+}
@@ -1,5 +1,6 @@
 use gcodekit4_communication::firmware::capability_manager::*;
 use gcodekit4_communication::firmware::firmware_version::{FirmwareType, SemanticVersion};
+use gcodekit4_visualizer::{GcodeCommand, ValidationSeverity};
 
 #[test]
 fn test_capability_manager_default() {
@@ -61,3 +62,81 @@ fn test_get_summary() {
     assert!(summary.contains("v1.1.0"));
     assert!(summary.contains("3 axes"));
 }
+
+fn program(lines: &[&str]) -> Vec<GcodeCommand> {
+    lines.iter().map(|line| GcodeCommand::new(*line)).collect()
+}
+
+fn capable() -> CapabilityState {
+    CapabilityState {
+        detected: true,
+        max_axes: 4,
+        supports_arcs: true,
+        supports_probing: true,
+        supports_tool_change: true,
+        supports_coolant: true,
+        coordinate_systems: 6,
+        ..CapabilityState::default()
+    }
+}
+
+#[test]
+fn test_validate_against_capabilities_flags_unsupported_commands() {
+    let commands = program(&[
+        "G21 G90 G55",
+        "T2 M6",
+        "G43 H2",
+        "M8",
+        "G0 X0 Y0 A90",
+        "G38.2 Z-10 F50",
+        "G2 X10 Y0 I5 J0 F300 (arc)",
+        "M9",
+    ]);
+
+    let result = validate_against_capabilities(&commands, &capable());
+    assert!(result.issues.is_empty(), "{:?}", result.issues);
+
+    let mut bare = capable();
+    bare.supports_arcs = false;
+    let result = validate_against_capabilities(&commands, &bare);
+    assert_eq!(result.warning_count, 1);
+    assert!(result.is_valid());
+    let arc = &result.issues_at_line(7)[0];
+    assert_eq!(arc.severity, ValidationSeverity::Warning);
+    assert!(arc.message.contains("G2"), "{}", arc.message);
+    assert!(arc.suggestion.is_some());
+
+    bare.supports_tool_change = false;
+    bare.supports_probing = false;
+    let lines: Vec<u32> = validate_against_capabilities(&commands, &bare)
+        .issues
+        .iter()
+        .map(|issue| issue.line_number)
+        .collect();
+    assert_eq!(lines, vec![2, 3, 6, 7]);
+}
+
+#[test]
+fn test_validate_against_capabilities_checks_coolant_axes_and_offsets() {
+    let mut commands = program(&["G56", "M7", "G0 X0 A90 B45", "M9"]);
+    commands[3].set_line_number(40);
+
+    let mut bare = capable();
+    bare.supports_coolant = false;
+    bare.max_axes = 3;
+    bare.coordinate_systems = 1;
+    let result = validate_against_capabilities(&commands, &bare);
+
+    let found: Vec<(u32, bool)> = result
+        .issues
+        .iter()
+        .map(|issue| (issue.line_number, issue.message.starts_with('M')))
+        .collect();
+    assert_eq!(found, vec![(1, false), (2, true), (3, false), (3, false), (40, true)]);
+
+    // A fourth axis makes A available but not B
+    bare.max_axes = 4;
+    let result = validate_against_capabilities(&commands, &bare);
+    assert_eq!(result.issues_at_line(3).len(), 1);
+    assert!(result.issues_at_line(3)[0].message.starts_with('B'));
+}
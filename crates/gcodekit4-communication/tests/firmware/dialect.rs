@@ -0,0 +1,92 @@
+//! Tests for firmware::dialect
+
+use gcodekit4_communication::firmware::{CommandDialect, ControllerType};
+use gcodekit4_communication::{Communicator, CommunicatorListenerHandle, ConnectionParams};
+use std::sync::Mutex;
+
+#[test]
+fn test_dialect_emergency_stop_bytes() {
+    assert_eq!(CommandDialect::Grbl.emergency_stop(), &[0x18]);
+    assert_eq!(CommandDialect::Marlin.emergency_stop(), b"M112\n");
+}
+
+#[test]
+fn test_dialect_for_controller() {
+    assert_eq!(
+        CommandDialect::for_controller(ControllerType::Marlin),
+        CommandDialect::Marlin
+    );
+    for controller_type in [
+        ControllerType::Grbl,
+        ControllerType::FluidNC,
+        ControllerType::Unknown,
+    ] {
+        let dialect = CommandDialect::for_controller(controller_type);
+        assert_eq!(dialect.emergency_stop(), &[0x18]);
+    }
+}
+
+#[test]
+fn test_dialect_frames_lines_for_marlin_only() {
+    assert!(CommandDialect::Grbl.line_framer().is_none());
+
+    let mut framer = CommandDialect::Marlin.line_framer().unwrap();
+    assert_eq!(framer.reset(), "N0 M110 N0*125");
+    assert_eq!(framer.frame("G28"), "N1 G28*18");
+}
+
+/// Communicator that records every byte written to it
+#[derive(Default)]
+struct RecordingCommunicator {
+    sent: Vec<u8>,
+}
+
+impl Communicator for RecordingCommunicator {
+    fn connect(&mut self, _params: &ConnectionParams) -> gcodekit4_core::Result<()> {
+        Ok(())
+    }
+
+    fn disconnect(&mut self) -> gcodekit4_core::Result<()> {
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        true
+    }
+
+    fn send(&mut self, data: &[u8]) -> gcodekit4_core::Result<usize> {
+        self.sent.extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    fn receive(&mut self) -> gcodekit4_core::Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn add_listener(&mut self, _listener: CommunicatorListenerHandle) {}
+
+    fn remove_listener(&mut self, _listener: &CommunicatorListenerHandle) {}
+
+    fn connection_params(&self) -> Option<&ConnectionParams> {
+        None
+    }
+
+    fn set_connection_params(&mut self, _params: ConnectionParams) -> gcodekit4_core::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_dialect_abort_follows_firmware() {
+    let communicator = Mutex::new(RecordingCommunicator::default());
+    CommandDialect::Marlin
+        .abort_communicator(&communicator, || panic!("Marlin abort doesn't wait for a hold"))
+        .unwrap();
+    assert_eq!(communicator.lock().unwrap().sent, b"M410\n");
+
+    let communicator = Mutex::new(RecordingCommunicator::default());
+    CommandDialect::Grbl
+        .abort_communicator(&communicator, || Some("Hold:0".to_string()))
+        .unwrap();
+    assert_eq!(communicator.lock().unwrap().sent, vec![0x21, 0x18, b'?']);
+}
@@ -1,3 +1,4 @@
 mod capabilities;
 mod command_creator;
 mod response_parser;
+mod sd_upload;
@@ -0,0 +1,188 @@
+//! Tests for firmware::fluidnc::sd_upload
+
+use gcodekit4_communication::firmware::fluidnc::sd_upload::*;
+use gcodekit4_communication::firmware::fluidnc::FluidNCCapabilities;
+use gcodekit4_communication::{Communicator, CommunicatorListenerHandle, ConnectionParams};
+
+/// Communicator that records every command line written to it and answers
+/// each upload with a canned `reply`
+#[derive(Default)]
+struct RecordingCommunicator {
+    sent: Vec<u8>,
+    reply: &'static str,
+}
+
+impl RecordingCommunicator {
+    fn lines(&self) -> Vec<String> {
+        String::from_utf8_lossy(&self.sent)
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+impl Communicator for RecordingCommunicator {
+    fn connect(&mut self, _params: &ConnectionParams) -> gcodekit4_core::Result<()> {
+        Ok(())
+    }
+
+    fn disconnect(&mut self) -> gcodekit4_core::Result<()> {
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        true
+    }
+
+    fn send(&mut self, data: &[u8]) -> gcodekit4_core::Result<usize> {
+        self.sent.extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    fn receive(&mut self) -> gcodekit4_core::Result<Vec<u8>> {
+        Ok(self.reply.as_bytes().to_vec())
+    }
+
+    fn add_listener(&mut self, _listener: CommunicatorListenerHandle) {}
+
+    fn remove_listener(&mut self, _listener: &CommunicatorListenerHandle) {}
+
+    fn connection_params(&self) -> Option<&ConnectionParams> {
+        None
+    }
+
+    fn set_connection_params(&mut self, _params: ConnectionParams) -> gcodekit4_core::Result<()> {
+        Ok(())
+    }
+}
+
+const PROGRAM: &str = "G21\n\nG0 X0 Y0\n  G1 X10 F500  \nM2\n";
+
+#[test]
+fn test_upload_sends_sd_sequence_and_reports_progress() {
+    let uploader = FluidNCSdUploader::new();
+    let chunks = uploader.prepare("/job.nc", PROGRAM, None).unwrap();
+    let mut communicator = RecordingCommunicator::default();
+    let mut progress = Vec::new();
+
+    let uploaded = uploader
+        .upload(
+            &mut communicator,
+            &FluidNCCapabilities::default(),
+            &chunks,
+            |p| progress.push(p),
+        )
+        .unwrap();
+
+    assert_eq!(uploaded, vec!["/job.nc".to_string()]);
+    // "G21\nG0 X0 Y0\nG1 X10 F500\nM2\n" is 28 bytes
+    assert_eq!(
+        communicator.lines(),
+        vec![
+            "$SD/Upload /job.nc 28",
+            "G21",
+            "G0 X0 Y0",
+            "G1 X10 F500",
+            "M2"
+        ]
+    );
+    assert_eq!(progress.len(), 4);
+    assert_eq!(progress[0].bytes_sent, 4);
+    assert_eq!(progress.last().unwrap().bytes_sent, 28);
+    assert_eq!(progress.last().unwrap().percent(), 100.0);
+}
+
+#[test]
+fn test_large_program_is_split_into_named_chunks() {
+    let uploader = FluidNCSdUploader::new().with_chunk_size(14);
+    let chunks = uploader.prepare("/job.nc", PROGRAM, None).unwrap();
+
+    let names: Vec<&str> = chunks.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["/job_001.nc", "/job_002.nc", "/job_003.nc"]);
+    assert!(chunks.iter().all(|c| c.size() <= 14));
+
+    let mut communicator = RecordingCommunicator::default();
+    let mut progress = Vec::new();
+    uploader
+        .upload(
+            &mut communicator,
+            &FluidNCCapabilities::default(),
+            &chunks,
+            |p| progress.push(p),
+        )
+        .unwrap();
+
+    let headers: Vec<String> = communicator
+        .lines()
+        .into_iter()
+        .filter(|line| line.starts_with("$SD/Upload"))
+        .collect();
+    assert_eq!(
+        headers,
+        vec![
+            "$SD/Upload /job_001.nc 13",
+            "$SD/Upload /job_002.nc 12",
+            "$SD/Upload /job_003.nc 3",
+        ]
+    );
+    assert_eq!(progress.last().unwrap().file_index, 2);
+    assert_eq!(progress.last().unwrap().file_count, 3);
+}
+
+#[test]
+fn test_upload_requires_filesystem_capability() {
+    let uploader = FluidNCSdUploader::new();
+    let chunks = uploader.prepare("/job.nc", PROGRAM, None).unwrap();
+    let capabilities = FluidNCCapabilities {
+        supports_filesystem: false,
+        ..FluidNCCapabilities::default()
+    };
+    let mut communicator = RecordingCommunicator::default();
+
+    assert!(uploader
+        .upload(&mut communicator, &capabilities, &chunks, |_| {})
+        .is_err());
+    assert!(communicator.sent.is_empty());
+}
+
+#[test]
+fn test_upload_stops_on_controller_error() {
+    let uploader = FluidNCSdUploader::new().with_chunk_size(14);
+    let chunks = uploader.prepare("/job.nc", PROGRAM, None).unwrap();
+    let mut communicator = RecordingCommunicator {
+        reply: "error:60\n",
+        ..RecordingCommunicator::default()
+    };
+
+    let err = uploader
+        .upload(
+            &mut communicator,
+            &FluidNCCapabilities::default(),
+            &chunks,
+            |_| {},
+        )
+        .unwrap_err();
+
+    assert!(err.to_string().contains("/job_001.nc"));
+    assert_eq!(
+        communicator
+            .lines()
+            .iter()
+            .filter(|line| line.starts_with("$SD/Upload"))
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn test_chunk_name() {
+    assert_eq!(
+        FluidNCSdUploader::chunk_name("/sd/job.nc", 2),
+        "/sd/job_002.nc"
+    );
+    assert_eq!(FluidNCSdUploader::chunk_name("job", 1), "job_001");
+    assert_eq!(
+        FluidNCSdUploader::chunk_name("/v1.2/job", 1),
+        "/v1.2/job_001"
+    );
+}
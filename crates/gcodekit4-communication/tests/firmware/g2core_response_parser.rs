@@ -0,0 +1,38 @@
+//! Tests for firmware::g2core::response_parser
+
+use gcodekit4_communication::firmware::g2core::response_parser::*;
+
+#[test]
+fn test_status_report_with_rotary_axes() {
+    let mut parser = G2CoreResponseParser::new();
+    let response = parser
+        .parse(r#"{"sr":{"stat":{"state":"Run"},"pos":{"x":10.0,"y":20.0,"z":-1.5,"a":90.0,"b":45.0},"mpos":{"x":110.0,"y":120.0,"z":-11.5,"a":90.0,"b":45.0}}}"#)
+        .unwrap();
+    let status = parser.parse_status_report(&response).unwrap();
+
+    assert_eq!(status.state, "Run");
+    assert_eq!(status.work_position.x, 10.0);
+    assert_eq!(status.work_position.a, Some(90.0));
+    assert_eq!(status.work_position.b, Some(45.0));
+    assert_eq!(status.work_position.c, None);
+    assert_eq!(status.work_position.axis_count(), 5);
+    assert_eq!(status.machine_position.a, Some(90.0));
+    assert_eq!(status.rotational_axes, Some((90.0, 45.0, 0.0)));
+    assert_eq!(
+        status.work_position.to_string(),
+        "X:10.00 Y:20.00 Z:-1.50 A:90.00° B:45.00°"
+    );
+}
+
+#[test]
+fn test_status_report_without_rotary_axes() {
+    let mut parser = G2CoreResponseParser::new();
+    let response = parser
+        .parse(r#"{"sr":{"pos":{"x":1.0,"y":2.0,"z":3.0}}}"#)
+        .unwrap();
+    let status = parser.parse_status_report(&response).unwrap();
+
+    assert_eq!(status.work_position.axis_count(), 3);
+    assert_eq!(status.work_position.a, None);
+    assert_eq!(status.rotational_axes, None);
+}
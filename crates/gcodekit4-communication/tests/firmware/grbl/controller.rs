@@ -1,5 +1,10 @@
 use gcodekit4_communication::firmware::grbl::controller::*;
-use gcodekit4_core::ControllerState;
+use gcodekit4_communication::firmware::grbl::DEFAULT_MAX_PENDING_JOGS;
+use gcodekit4_communication::{Communicator, CommunicatorListenerHandle, ConnectionParams};
+use gcodekit4_core::{ControllerEvent, ControllerState, ControllerTrait};
+use gcodekit4_visualizer::WorkCoordinateSystem;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 #[test]
 fn test_grbl_controller_state_default() {
@@ -8,3 +13,563 @@ fn test_grbl_controller_state_default() {
     assert_eq!(state.poll_rate_ms, 100);
     assert!(!state.is_streaming);
 }
+
+/// Communicator that records every byte written to it
+///
+/// When `$#` is sent the canned `offsets_report` is queued as the reply, and
+/// each `?` poll is answered with `status_report`. Sending the `reset_after`
+/// line makes it answer with a GRBL startup banner, as after a brownout.
+/// Lines listed in `command_replies` are answered with their canned reply.
+struct RecordingCommunicator {
+    sent: Arc<Mutex<Vec<u8>>>,
+    connected: bool,
+    params: Option<ConnectionParams>,
+    offsets_report: &'static str,
+    status_report: &'static str,
+    reset_after: &'static str,
+    command_replies: Vec<(&'static str, &'static str)>,
+    pending: Vec<u8>,
+}
+
+impl Communicator for RecordingCommunicator {
+    fn connect(&mut self, params: &ConnectionParams) -> gcodekit4_core::Result<()> {
+        self.params = Some(params.clone());
+        self.connected = true;
+        Ok(())
+    }
+
+    fn disconnect(&mut self) -> gcodekit4_core::Result<()> {
+        self.connected = false;
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    fn send(&mut self, data: &[u8]) -> gcodekit4_core::Result<usize> {
+        self.sent.lock().unwrap().extend_from_slice(data);
+        if data.starts_with(b"$#") {
+            self.pending.extend_from_slice(self.offsets_report.as_bytes());
+        }
+        if data == b"?" {
+            self.pending.extend_from_slice(self.status_report.as_bytes());
+        }
+        if !self.reset_after.is_empty()
+            && data.strip_suffix(b"\n") == Some(self.reset_after.as_bytes())
+        {
+            self.pending.extend_from_slice(b"\r\nGrbl 1.1h ['$' for help]\r\n");
+        }
+        for (command, reply) in &self.command_replies {
+            if data.strip_suffix(b"\n") == Some(command.as_bytes()) {
+                self.pending.extend_from_slice(reply.as_bytes());
+            }
+        }
+        Ok(data.len())
+    }
+
+    fn receive(&mut self) -> gcodekit4_core::Result<Vec<u8>> {
+        Ok(std::mem::take(&mut self.pending))
+    }
+
+    fn add_listener(&mut self, _listener: CommunicatorListenerHandle) {}
+
+    fn remove_listener(&mut self, _listener: &CommunicatorListenerHandle) {}
+
+    fn connection_params(&self) -> Option<&ConnectionParams> {
+        self.params.as_ref()
+    }
+
+    fn set_connection_params(&mut self, params: ConnectionParams) -> gcodekit4_core::Result<()> {
+        self.params = Some(params);
+        Ok(())
+    }
+}
+
+async fn connected_controller() -> (GrblController, Arc<Mutex<Vec<u8>>>) {
+    connected_controller_with_offsets("").await
+}
+
+async fn connected_controller_with_offsets(
+    offsets_report: &'static str,
+) -> (GrblController, Arc<Mutex<Vec<u8>>>) {
+    connected_controller_with_replies(offsets_report, "").await
+}
+
+async fn connected_controller_with_replies(
+    offsets_report: &'static str,
+    status_report: &'static str,
+) -> (GrblController, Arc<Mutex<Vec<u8>>>) {
+    connect_recording(offsets_report, status_report, "").await
+}
+
+async fn connect_recording(
+    offsets_report: &'static str,
+    status_report: &'static str,
+    reset_after: &'static str,
+) -> (GrblController, Arc<Mutex<Vec<u8>>>) {
+    connect_answering(offsets_report, status_report, reset_after, Vec::new()).await
+}
+
+/// Answers to the commands sent on connect, and `reply` to `$H`
+fn homing_replies(reply: &'static str) -> Vec<(&'static str, &'static str)> {
+    vec![
+        ("$RST=*", "ok\r\n"),
+        ("$I", "ok\r\n"),
+        ("$", "ok\r\n"),
+        ("$G", "ok\r\n"),
+        ("$H", reply),
+    ]
+}
+
+async fn connect_answering(
+    offsets_report: &'static str,
+    status_report: &'static str,
+    reset_after: &'static str,
+    command_replies: Vec<(&'static str, &'static str)>,
+) -> (GrblController, Arc<Mutex<Vec<u8>>>) {
+    let sent = Arc::new(Mutex::new(Vec::new()));
+    let communicator = RecordingCommunicator {
+        sent: sent.clone(),
+        connected: false,
+        params: None,
+        offsets_report,
+        status_report,
+        reset_after,
+        command_replies,
+        pending: Vec::new(),
+    };
+    let mut controller = GrblController::with_communicator(
+        ConnectionParams::default(),
+        None,
+        Box::new(communicator),
+    )
+    .unwrap();
+
+    controller.connect().await.unwrap();
+    // Let the IO loop flush the initialization commands
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    sent.lock().unwrap().clear();
+
+    (controller, sent)
+}
+
+#[tokio::test]
+async fn test_grbl_controller_safety_hold_stops_spindle_once_held() {
+    let (mut controller, sent) =
+        connected_controller_with_replies("", "<Hold:0|MPos:0.000,0.000,0.000|FS:0,0>\r\n").await;
+
+    controller.safety_hold().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    // The real-time spindle stop follows the feed hold; a queued M5 would
+    // wait for cycle start
+    let bytes = sent.lock().unwrap().clone();
+    let hold_pos = bytes.iter().position(|&b| b == 0x21).expect("feed hold byte");
+    let stop_pos = bytes.iter().position(|&b| b == 0x9E).expect("spindle stop byte");
+    assert!(hold_pos < stop_pos);
+    assert!(!bytes.windows(2).any(|w| w == b"M5"));
+
+    assert_eq!(controller.get_state(), ControllerState::Hold);
+    assert!(controller.is_safety_hold());
+
+    controller.resume_streaming().await.unwrap();
+    assert!(!controller.is_safety_hold());
+    controller.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_grbl_controller_safety_hold_stops_spindle_without_hold() {
+    let (mut controller, sent) =
+        connected_controller_with_replies("", "<Idle|MPos:0.000,0.000,0.000|FS:0,0>\r\n").await;
+
+    // An idle machine never reports Hold, so the spindle is stopped with M5
+    controller.safety_hold().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let bytes = sent.lock().unwrap().clone();
+    assert!(bytes.contains(&0x21));
+    assert!(!bytes.contains(&0x9E));
+    assert!(bytes.windows(3).any(|w| w == b"M5\n"));
+    assert!(!controller.is_safety_hold());
+    controller.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_grbl_controller_pause_is_not_safety_hold() {
+    let (mut controller, sent) = connected_controller().await;
+
+    controller.pause_streaming().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let bytes = sent.lock().unwrap().clone();
+    assert!(bytes.contains(&0x21));
+    assert!(!bytes.windows(2).any(|w| w == b"M5"));
+    assert_eq!(controller.get_state(), ControllerState::Hold);
+    assert!(!controller.is_safety_hold());
+    controller.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_grbl_controller_resume_restores_paused_overrides() {
+    let (mut controller, sent) = connected_controller().await;
+    controller.set_restore_overrides_on_resume(true);
+
+    controller.set_feed_override(80).await.unwrap();
+    controller.pause_streaming().await.unwrap();
+    controller.set_feed_override(120).await.unwrap();
+    sent.lock().unwrap().clear();
+
+    controller.resume_streaming().await.unwrap();
+
+    let bytes: Vec<u8> = sent
+        .lock()
+        .unwrap()
+        .iter()
+        .copied()
+        .filter(|&b| b != b'?')
+        .collect();
+    // Feed back to 80%, rapid and spindle at 100%, then cycle start
+    assert_eq!(bytes, vec![0x90, 0x92, 0x92, 0x95, 0x99, 0x7E]);
+    assert_eq!(controller.get_override_state().feed_override, 80);
+    controller.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_grbl_controller_resume_keeps_overrides_without_restore() {
+    let (mut controller, sent) = connected_controller().await;
+
+    controller.set_feed_override(80).await.unwrap();
+    controller.pause_streaming().await.unwrap();
+    controller.set_feed_override(120).await.unwrap();
+    sent.lock().unwrap().clear();
+
+    controller.resume_streaming().await.unwrap();
+
+    let bytes: Vec<u8> = sent
+        .lock()
+        .unwrap()
+        .iter()
+        .copied()
+        .filter(|&b| b != b'?')
+        .collect();
+    assert_eq!(bytes, vec![0x7E]);
+    assert_eq!(controller.get_override_state().feed_override, 120);
+    controller.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_grbl_controller_query_work_offsets() {
+    let report = "[G54:10.000,20.000,-5.000]\r\n[G55:0.000,0.000,0.000]\r\n\
+                  [G56:0.000,0.000,0.000]\r\n[G57:0.000,0.000,0.000]\r\n\
+                  [G58:0.000,0.000,0.000]\r\n[G59:1.000,2.000,3.000]\r\n\
+                  [G28:0.000,0.000,0.000]\r\n[G30:0.000,0.000,0.000]\r\n\
+                  [G92:0.000,0.000,0.000]\r\n[TLO:1.500]\r\n\
+                  [PRB:0.000,0.000,0.000:0]\r\nok\r\n";
+    let (mut controller, sent) = connected_controller_with_offsets(report).await;
+
+    let offsets = controller.query_work_offsets().await.unwrap();
+
+    assert!(sent.lock().unwrap().windows(3).any(|w| w == b"$#\n"));
+    assert_eq!(offsets.systems[0].y, 20.0);
+    assert_eq!(offsets.system(59).unwrap().z, 3.0);
+    assert_eq!(offsets.tlo, 1.5);
+    assert_eq!(controller.work_offsets().systems[0].x, 10.0);
+    controller.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_grbl_controller_set_work_zero_at_machine_position() {
+    let (mut controller, sent) = connect_answering(
+        "",
+        "<Idle|MPos:-120.500,-80.000,-3.250|FS:0,0>\r\n",
+        "",
+        homing_replies("ok\r\n"),
+    )
+    .await;
+    // Wait for a status poll to report the machine position
+    tokio::time::sleep(Duration::from_millis(150)).await;
+
+    let mut wcs = WorkCoordinateSystem::new();
+    wcs.select_system(2).unwrap();
+    assert!(controller
+        .set_work_zero_at_reference(&mut wcs, None)
+        .await
+        .is_err());
+
+    controller.home().await.unwrap();
+    // Homed once the cycle's `ok` is followed by an idle status report
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    assert!(controller.is_homed());
+    controller
+        .set_work_zero_at_reference(&mut wcs, None)
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let bytes = sent.lock().unwrap().clone();
+    let expected = b"G10 L2 P2 X-120.500 Y-80.000 Z-3.250\n";
+    assert!(bytes.windows(expected.len()).any(|w| w == expected));
+
+    let offset = wcs.current_offset();
+    assert_eq!((offset.x, offset.y, offset.z), (-120.5, -80.0, -3.25));
+    assert_eq!(controller.work_offsets().system(55).unwrap().x, -120.5);
+    controller.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_grbl_controller_home_alarm_leaves_machine_unhomed() {
+    // Homing fails to find a switch: GRBL alarms instead of answering `ok`
+    let (mut controller, _sent) = connect_answering(
+        "",
+        "<Alarm|MPos:0.000,0.000,0.000|FS:0,0>\r\n",
+        "",
+        homing_replies("ALARM:9\r\n"),
+    )
+    .await;
+
+    controller.home().await.unwrap();
+    assert!(!controller.is_homed());
+    tokio::time::sleep(Duration::from_millis(250)).await;
+
+    assert!(!controller.is_homed());
+    assert_eq!(controller.get_state(), ControllerState::Alarm);
+    controller.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_grbl_controller_home_error_leaves_machine_unhomed() {
+    // error:5, homing is not enabled
+    let (mut controller, _sent) = connect_answering(
+        "",
+        "<Idle|MPos:0.000,0.000,0.000|FS:0,0>\r\n",
+        "",
+        homing_replies("error:5\r\n"),
+    )
+    .await;
+
+    controller.home().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(250)).await;
+
+    assert!(!controller.is_homed());
+    controller.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_grbl_controller_abort_holds_resets_and_requeries_status() {
+    let (mut controller, sent) =
+        connected_controller_with_replies("", "<Idle|MPos:0.000,0.000,0.000|FS:0,0>\r\n").await;
+
+    controller.start_streaming().await.unwrap();
+    controller.send_command("G1 X100 F500").await.unwrap();
+    controller.abort().await.unwrap();
+
+    let bytes = sent.lock().unwrap().clone();
+    let hold_pos = bytes.iter().position(|&b| b == 0x21).expect("feed hold byte");
+    let reset_pos = bytes.iter().position(|&b| b == 0x18).expect("soft reset byte");
+    assert!(hold_pos < reset_pos);
+    // The status query is the last thing sent, after the reset
+    assert_eq!(bytes.last(), Some(&b'?'));
+
+    assert!(controller.is_aborted());
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(controller.get_state(), ControllerState::Idle);
+
+    controller.start_streaming().await.unwrap();
+    assert!(!controller.is_aborted());
+    controller.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_grbl_controller_abort_waits_for_hold_to_complete() {
+    let (mut controller, sent) =
+        connected_controller_with_replies("", "<Hold:1|MPos:0.000,0.000,0.000|FS:0,0>\r\n").await;
+
+    let started = std::time::Instant::now();
+    controller.abort().await.unwrap();
+
+    // Hold:1 is still decelerating, so the reset waits out the hold timeout
+    assert!(started.elapsed() >= ABORT_HOLD_TIMEOUT);
+    assert!(sent.lock().unwrap().contains(&0x18));
+    controller.disconnect().await.unwrap();
+}
+
+#[test]
+fn test_grbl_abort_communicator_resets_once_hold_completes() {
+    let sent = Arc::new(Mutex::new(Vec::new()));
+    let communicator = Mutex::new(RecordingCommunicator {
+        sent: sent.clone(),
+        connected: true,
+        params: None,
+        offsets_report: "",
+        status_report: "",
+        reset_after: "",
+        command_replies: Vec::new(),
+        pending: Vec::new(),
+    });
+    let reports = Mutex::new(vec![Some("Hold:0"), Some("Hold:1"), None]);
+
+    let started = std::time::Instant::now();
+    abort_communicator(&communicator, || {
+        let mut reports = reports.lock().unwrap();
+        let state = if reports.len() > 1 {
+            reports.pop().unwrap()
+        } else {
+            reports[0]
+        };
+        state.map(str::to_string)
+    })
+    .unwrap();
+
+    assert!(started.elapsed() < ABORT_HOLD_TIMEOUT);
+    assert_eq!(reports.lock().unwrap().len(), 1);
+    assert_eq!(*sent.lock().unwrap(), vec![0x21, 0x18, b'?']);
+}
+
+#[tokio::test]
+async fn test_grbl_controller_emergency_stop_sends_soft_reset() {
+    let (mut controller, sent) = connected_controller().await;
+
+    controller.start_streaming().await.unwrap();
+    controller.emergency_stop().await.unwrap();
+
+    // GRBL's dialect stops with the real-time soft reset, not a queued line
+    let bytes: Vec<u8> = sent.lock().unwrap().iter().copied().filter(|&b| b != b'?').collect();
+    assert_eq!(bytes, vec![0x18]);
+    assert!(!controller.is_homed());
+    controller.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_grbl_controller_startup_banner_mid_stream_stops_sending() {
+    let (mut controller, sent) = connect_recording("", "", "G1 X2").await;
+    let mut events = controller.subscribe_events();
+
+    controller.start_streaming().await.unwrap();
+    for x in 1..=6 {
+        controller.send_command(&format!("G1 X{}", x)).await.unwrap();
+    }
+    tokio::time::sleep(Duration::from_millis(150)).await;
+
+    let text = String::from_utf8_lossy(&sent.lock().unwrap()).to_string();
+    assert!(text.contains("G1 X2\n"));
+    assert!(!text.contains("G1 X4"));
+    assert!(controller.is_unexpected_reset());
+    assert_eq!(controller.get_state(), ControllerState::Alarm);
+
+    match events.try_recv() {
+        Ok(ControllerEvent::Reset(banner)) => assert!(banner.starts_with("Grbl 1.1h")),
+        other => panic!("expected reset event, got {:?}", other),
+    }
+
+    controller.start_streaming().await.unwrap();
+    assert!(!controller.is_unexpected_reset());
+    controller.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_grbl_controller_startup_banner_when_idle_is_ignored() {
+    let (mut controller, _sent) = connect_recording("", "", "G1 X2").await;
+    let mut events = controller.subscribe_events();
+
+    controller.send_command("G1 X2").await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    assert!(!controller.is_unexpected_reset());
+    assert!(events.try_recv().is_err());
+    controller.disconnect().await.unwrap();
+}
+
+/// Jog commands sent so far, with real-time bytes removed
+fn sent_jogs(sent: &Arc<Mutex<Vec<u8>>>) -> Vec<String> {
+    let bytes: Vec<u8> = sent
+        .lock()
+        .unwrap()
+        .iter()
+        .copied()
+        .filter(|&b| b != b'?' && b != 0x85)
+        .collect();
+    String::from_utf8_lossy(&bytes)
+        .lines()
+        .filter(|line| line.starts_with("$J="))
+        .map(str::to_string)
+        .collect()
+}
+
+#[tokio::test]
+async fn test_grbl_controller_analog_jog_scales_feed() {
+    let (mut controller, sent) = connected_controller().await;
+    controller.set_max_jog_feed(2000.0);
+
+    controller.jog_analog('x', 0.5).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    // 1000 mm/min for a quarter second
+    assert_eq!(sent_jogs(&sent), vec!["$J=G91 G0 X4.167 F1000"]);
+
+    // Holding the same input doesn't flood the controller
+    controller.jog_analog('X', 0.51).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(sent_jogs(&sent).len(), 1);
+    assert!(!sent.lock().unwrap().contains(&0x85));
+
+    // Reversing cancels the queued jog before issuing the new one
+    controller.jog_analog('X', -1.0).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let bytes = sent.lock().unwrap().clone();
+    let cancel_pos = bytes.iter().position(|&b| b == 0x85).expect("jog cancel byte");
+    let jog_pos = bytes.windows(6).position(|w| w == b"X-8.33").expect("reversed jog");
+    assert!(cancel_pos < jog_pos);
+    assert_eq!(sent_jogs(&sent)[1], "$J=G91 G0 X-8.333 F2000");
+    controller.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_grbl_controller_analog_jog_center_cancels() {
+    let (mut controller, sent) = connected_controller().await;
+
+    controller.jog_analog('Y', 0.0).await.unwrap();
+    assert!(!sent.lock().unwrap().contains(&0x85));
+
+    controller.jog_analog('Y', 0.8).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(sent_jogs(&sent), vec!["$J=G91 G0 Y3.333 F800"]);
+
+    controller.jog_analog('Y', 0.0).await.unwrap();
+    assert_eq!(sent.lock().unwrap().last(), Some(&0x85));
+    assert!(controller.jog_analog('Q', 1.0).await.is_err());
+    controller.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_grbl_controller_coalesces_quick_jog_clicks() {
+    let (mut controller, sent) = connected_controller().await;
+
+    for _ in 0..5 {
+        controller.jog_incremental('X', 1.0, 1000.0).await.unwrap();
+    }
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    controller.jog_stop().await.unwrap();
+
+    // The extra clicks were held back, and stopping cancels the rest
+    let jogs = sent_jogs(&sent);
+    assert!(jogs.len() <= DEFAULT_MAX_PENDING_JOGS, "{:?}", jogs);
+    assert!(jogs.iter().all(|jog| jog == "$J=G91 G0 X1.000 F1000"));
+    assert_eq!(sent.lock().unwrap().last(), Some(&0x85));
+    controller.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_grbl_controller_reset_forgets_outstanding_jogs() {
+    let (mut controller, sent) = connected_controller().await;
+
+    for _ in 0..5 {
+        controller.jog_incremental('X', 1.0, 1000.0).await.unwrap();
+    }
+    controller.reset().await.unwrap();
+
+    // The reset flushed the controller's planner, so no jogs are waiting on it
+    controller.jog_incremental('Y', 1.0, 1000.0).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(sent_jogs(&sent).contains(&"$J=G91 G0 Y1.000 F1000".to_string()));
+    controller.disconnect().await.unwrap();
+}
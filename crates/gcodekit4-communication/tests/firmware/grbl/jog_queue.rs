@@ -0,0 +1,93 @@
+//! Tests for firmware::grbl::jog_queue
+
+use gcodekit4_communication::firmware::grbl::{JogQueue, DEFAULT_MAX_PENDING_JOGS};
+
+#[test]
+fn test_jog_queue_bounds_burst_of_requests() {
+    let mut queue = JogQueue::default();
+    let sent: Vec<String> = (1..=5)
+        .filter_map(|i| queue.request(format!("$J=G91 G0 X{} F1000", i)))
+        .collect();
+
+    assert_eq!(sent.len(), DEFAULT_MAX_PENDING_JOGS);
+    assert_eq!(sent[0], "$J=G91 G0 X1 F1000");
+    // Only the latest of the extra requests is kept
+    assert_eq!(queue.held(), Some("$J=G91 G0 X5 F1000"));
+
+    // Input stopped: the held jog is dropped and the moving ones cancelled
+    assert!(queue.cancel());
+    assert_eq!(queue.pending(), 0);
+    assert!(queue.held().is_none());
+    assert!(queue.machine_state("Jog").is_none());
+    assert!(queue.machine_state("Idle").is_none());
+}
+
+#[test]
+fn test_jog_queue_sends_held_jog_when_motion_stops() {
+    let mut queue = JogQueue::new(1);
+    assert!(queue.request("$J=G91 G0 Y1 F500").is_some());
+    assert!(queue.request("$J=G91 G0 Y2 F500").is_none());
+
+    assert!(queue.machine_state("Jog").is_none());
+    assert_eq!(queue.machine_state("Idle").as_deref(), Some("$J=G91 G0 Y2 F500"));
+    assert_eq!(queue.pending(), 1);
+    assert!(queue.machine_state("Jog").is_none());
+    assert!(queue.machine_state("Idle").is_none());
+    assert_eq!(queue.pending(), 0);
+    assert!(!queue.cancel());
+    assert_eq!(JogQueue::new(0).max_pending(), 1);
+}
+
+#[test]
+fn test_jog_queue_ignores_idle_before_jog_starts() {
+    let mut queue = JogQueue::new(1);
+    assert!(queue.request("$J=G91 G0 Z1 F300").is_some());
+    assert!(queue.request("$J=G91 G0 Z2 F300").is_none());
+
+    // Status polled before GRBL began the first jog
+    assert!(queue.machine_state("Idle").is_none());
+    assert_eq!(queue.pending(), 1);
+    assert_eq!(queue.held(), Some("$J=G91 G0 Z2 F300"));
+
+    assert!(queue.machine_state("Jog").is_none());
+    assert!(queue.machine_state("Run").is_none());
+    assert_eq!(queue.pending(), 1);
+    assert_eq!(queue.machine_state("Idle").as_deref(), Some("$J=G91 G0 Z2 F300"));
+}
+
+#[test]
+fn test_jog_queue_short_jogs_finish_without_jog_report() {
+    let mut queue = JogQueue::default();
+    assert!(queue.request("$J=G91 G0 X0.01 F1000").is_some());
+    assert!(queue.request("$J=G91 G0 X0.01 F1000").is_some());
+
+    // Both finished between status polls, so no `Jog` state was ever seen
+    assert!(queue.response(true).is_none());
+    assert!(queue.response(true).is_none());
+    assert!(queue.machine_state("Idle").is_none());
+    assert_eq!(queue.pending(), 0);
+
+    assert_eq!(
+        queue.request("$J=G91 G0 X0.01 F1000").as_deref(),
+        Some("$J=G91 G0 X0.01 F1000")
+    );
+}
+
+#[test]
+fn test_jog_queue_rejected_jog_releases_held_jog() {
+    let mut queue = JogQueue::new(1);
+    assert!(queue.request("$J=G91 G0 X500 F1000").is_some());
+    assert!(queue.request("$J=G91 G0 X1 F1000").is_none());
+
+    // error:15, the jog target exceeds the travel
+    assert_eq!(queue.response(false).as_deref(), Some("$J=G91 G0 X1 F1000"));
+    assert_eq!(queue.pending(), 1);
+    assert!(queue.held().is_none());
+
+    assert!(queue.response(true).is_none());
+    // Replies with no jog waiting for them are not jog replies
+    assert!(queue.response(false).is_none());
+    assert_eq!(queue.pending(), 1);
+    assert!(queue.machine_state("Idle").is_none());
+    assert_eq!(queue.pending(), 0);
+}
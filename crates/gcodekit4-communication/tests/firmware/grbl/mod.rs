@@ -4,5 +4,6 @@ mod settings;
 mod communicator;
 mod controller;
 mod command_creator;
+mod jog_queue;
 mod override_manager;
 mod utils;
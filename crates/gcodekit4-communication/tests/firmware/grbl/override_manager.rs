@@ -199,3 +199,25 @@ fn test_feed_override_command_detection() {
     let cmd = manager.get_feed_override_command();
     assert!(cmd.is_some());
 }
+
+#[test]
+fn test_feed_override_bytes_step_from_reset() {
+    assert_eq!(feed_override_bytes(100), vec![0x90]);
+    assert_eq!(feed_override_bytes(80), vec![0x90, 0x92, 0x92]);
+    assert_eq!(feed_override_bytes(113), vec![0x90, 0x91, 0x93, 0x93, 0x93]);
+    assert_eq!(spindle_override_bytes(90), vec![0x99, 0x9B]);
+    assert_eq!(rapid_override_byte(25), 0x97);
+}
+
+#[test]
+fn test_override_state_bytes() {
+    let state = gcodekit4_core::OverrideState {
+        feed_override: 80,
+        rapid_override: 50,
+        spindle_override: 110,
+    };
+    assert_eq!(
+        override_state_bytes(&state),
+        vec![0x90, 0x92, 0x92, 0x96, 0x99, 0x9A]
+    );
+}
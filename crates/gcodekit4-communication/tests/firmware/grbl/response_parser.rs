@@ -131,3 +131,47 @@ fn test_parse_multiaxis_position() {
         assert_eq!(status.machine_pos.a, 5.0);
     }
 }
+
+#[test]
+fn test_parse_work_offsets_block() {
+    let block = "[G54:10.000,20.000,-5.000]\n\
+                 [G55:-1.500,0.000,0.000]\n\
+                 [G56:0.000,0.000,0.000]\n\
+                 [G57:0.000,0.000,0.000]\n\
+                 [G58:0.000,0.000,0.000]\n\
+                 [G59:100.000,200.000,-30.250]\n\
+                 [G28:0.000,0.000,-1.000]\n\
+                 [G30:0.000,0.000,0.000]\n\
+                 [G92:5.000,0.000,0.000]\n\
+                 [TLO:12.700]\n\
+                 [PRB:0.000,0.000,0.000:0]\n\
+                 ok";
+
+    let offsets = WorkOffsets::parse(block);
+
+    assert_eq!(offsets.systems[0].x, 10.0);
+    assert_eq!(offsets.systems[0].y, 20.0);
+    assert_eq!(offsets.systems[0].z, -5.0);
+    assert_eq!(offsets.system(55).unwrap().x, -1.5);
+    assert_eq!(offsets.system(59).unwrap().z, -30.25);
+    assert!(offsets.system(60).is_none());
+    assert_eq!(offsets.g28.z, -1.0);
+    assert_eq!(offsets.g92.x, 5.0);
+    assert_eq!(offsets.tlo, 12.7);
+
+    let wcs = offsets.to_coordinate_system();
+    assert_eq!(wcs.get_offset(1).unwrap().x, 10.0);
+    assert_eq!(wcs.get_offset(6).unwrap().y, 200.0);
+}
+
+#[test]
+fn test_parse_work_offsets_line_rejects_other_messages() {
+    let mut offsets = WorkOffsets::default();
+
+    assert!(offsets.parse_line("[G54:1.000,2.000,3.000,4.000]"));
+    assert!(!offsets.parse_line("[PRB:0.000,0.000,0.000:0]"));
+    assert!(!offsets.parse_line("[MSG:Caution: Unlocked]"));
+    assert!(!offsets.parse_line("[GC:G0 G54 G17 G21 G90 G94 M5 M9 T0 F0 S0]"));
+    assert!(!offsets.parse_line("ok"));
+    assert_eq!(offsets.systems[0].z, 3.0);
+}
@@ -83,6 +83,19 @@ fn test_is_held_state() {
     assert!(!is_held_state("Run"));
 }
 
+#[test]
+fn test_is_hold_complete() {
+    assert!(is_hold_complete("Hold:0"));
+    assert!(is_hold_complete("Idle"));
+    assert!(is_hold_complete("Alarm"));
+    assert!(is_hold_complete("Door:1"));
+    assert!(!is_hold_complete("Hold:1"));
+    assert!(!is_hold_complete("Hold"));
+    assert!(!is_hold_complete("Run"));
+    assert!(!is_hold_complete("Jog"));
+    assert!(!is_hold_complete("Door:2"));
+}
+
 #[test]
 fn test_get_error_codes() {
     let codes = get_error_codes();
@@ -6,9 +6,12 @@ mod device_db;
 mod capability_manager;
 mod firmware_detector;
 mod connection_watch;
+mod dialect;
 mod file_service;
 mod override_manager;
 mod settings_test;
 mod g2core_capabilities;
+mod g2core_response_parser;
+mod tinyg_response_parser;
 mod fluidnc;
 mod smoothieware;
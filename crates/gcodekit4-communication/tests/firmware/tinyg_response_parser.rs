@@ -0,0 +1,31 @@
+//! Tests for firmware::tinyg::response_parser
+
+use gcodekit4_communication::firmware::tinyg::response_parser::*;
+
+#[test]
+fn test_status_report_with_a_axis() {
+    let mut parser = TinyGResponseParser::new();
+    let response = parser
+        .parse(r#"{"sr":{"pos":{"x":1.0,"y":2.0,"z":3.0,"a":180.5}}}"#)
+        .unwrap();
+    let status = parser.parse_status_report(&response).unwrap();
+
+    assert_eq!(status.work_position.a, Some(180.5));
+    assert_eq!(status.work_position.b, None);
+    assert_eq!(status.work_position.axis_count(), 4);
+}
+
+#[test]
+fn test_status_report_with_flat_axis_keys() {
+    let mut parser = TinyGResponseParser::new();
+    let response = parser
+        .parse(r#"{"sr":{"posx":5.0,"posy":6.0,"posz":7.0,"posa":-30.0,"mpox":15.0,"mpoy":16.0,"mpoz":17.0,"mpoa":-30.0}}"#)
+        .unwrap();
+    let status = parser.parse_status_report(&response).unwrap();
+
+    assert_eq!(status.work_position.x, 5.0);
+    assert_eq!(status.work_position.a, Some(-30.0));
+    assert_eq!(status.machine_position.x, 15.0);
+    assert_eq!(status.machine_position.a, Some(-30.0));
+    assert_eq!(status.machine_position.c, None);
+}
@@ -0,0 +1,111 @@
+//! Channel-based controller command API
+//!
+//! Lets the UI talk to a controller without blocking on the connection.
+//! A [`ControllerChannel`] queues commands for a background task that owns
+//! the connection, and each command gets a receiver that resolves once the
+//! controller answers it with `ok`, `error:` or `ALARM:`. The UI can poll
+//! that receiver from a timer or `.await` it.
+
+use tokio::sync::{mpsc, oneshot};
+
+/// A controller's answer to one command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandResponse {
+    /// The command as it was sent
+    pub command: String,
+    /// Lines printed before the final `ok`/`error:`, e.g. the `$$` settings
+    pub lines: Vec<String>,
+    /// Why the command failed, if it did
+    pub error: Option<String>,
+}
+
+impl CommandResponse {
+    /// A successful response
+    pub fn ok(command: &str, lines: Vec<String>) -> Self {
+        Self {
+            command: command.to_string(),
+            lines,
+            error: None,
+        }
+    }
+
+    /// A failed response
+    pub fn failed(command: &str, lines: Vec<String>, error: impl Into<String>) -> Self {
+        Self {
+            command: command.to_string(),
+            lines,
+            error: Some(error.into()),
+        }
+    }
+
+    /// Check whether the controller accepted the command
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// A queued command waiting for its response
+#[derive(Debug)]
+pub struct CommandRequest {
+    /// Command line, without the trailing newline
+    pub command: String,
+    reply: oneshot::Sender<CommandResponse>,
+}
+
+impl CommandRequest {
+    /// Resolve the request, dropping the response if the sender gave up on it
+    pub fn respond(self, response: CommandResponse) {
+        let _ = self.reply.send(response);
+    }
+
+    /// Resolve the request as failed
+    pub fn fail(self, error: impl Into<String>) {
+        let response = CommandResponse::failed(&self.command, Vec::new(), error);
+        self.respond(response);
+    }
+}
+
+/// Sending half of a controller command queue
+///
+/// Cheap to clone, so every part of the UI can hold one. The receiving half
+/// belongs to whatever task owns the connection.
+#[derive(Debug, Clone)]
+pub struct ControllerChannel {
+    requests: mpsc::UnboundedSender<CommandRequest>,
+}
+
+impl ControllerChannel {
+    /// Create a channel and the receiver the connection task reads from
+    pub fn new() -> (Self, mpsc::UnboundedReceiver<CommandRequest>) {
+        let (requests, receiver) = mpsc::unbounded_channel();
+        (Self { requests }, receiver)
+    }
+
+    /// Queue a command without waiting for it
+    ///
+    /// The returned receiver resolves with the command's response. If the
+    /// connection task has stopped it resolves right away with an error.
+    pub fn send(&self, command: &str) -> oneshot::Receiver<CommandResponse> {
+        let (reply, receiver) = oneshot::channel();
+        let request = CommandRequest {
+            command: command.trim_end().to_string(),
+            reply,
+        };
+        if let Err(mpsc::error::SendError(request)) = self.requests.send(request) {
+            request.fail("Controller connection is closed");
+        }
+        receiver
+    }
+
+    /// Send a command and wait for its response
+    pub async fn request(&self, command: &str) -> anyhow::Result<CommandResponse> {
+        self.send(command)
+            .await
+            .map_err(|_| anyhow::anyhow!("Controller dropped command '{}'", command.trim_end()))
+    }
+
+    /// Check whether the connection task has stopped reading commands
+    pub fn is_closed(&self) -> bool {
+        self.requests.is_closed()
+    }
+}
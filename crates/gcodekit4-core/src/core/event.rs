@@ -34,6 +34,8 @@ pub enum ControllerEvent {
     SpindleSpeedChanged(f64),
     /// Feed rate changed
     FeedRateChanged(f64),
+    /// Controller restarted on its own, with the startup banner it printed
+    Reset(String),
 }
 
 impl std::fmt::Display for ControllerEvent {
@@ -58,6 +60,7 @@ impl std::fmt::Display for ControllerEvent {
             }
             ControllerEvent::SpindleSpeedChanged(speed) => write!(f, "Spindle: {} RPM", speed),
             ControllerEvent::FeedRateChanged(rate) => write!(f, "Feed rate: {} mm/min", rate),
+            ControllerEvent::Reset(banner) => write!(f, "Controller reset: {}", banner),
         }
     }
 }
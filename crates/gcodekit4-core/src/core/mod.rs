@@ -5,8 +5,10 @@
 //! - State machine for tracking controller state
 //! - Event system for communication between components
 //! - Command queuing and execution
+//! - Channel-based command API for non-blocking callers
 //! - Listener registration for controller events
 
+pub mod channel;
 pub mod event;
 pub mod listener;
 pub mod message;
@@ -16,6 +18,7 @@ use async_trait::async_trait;
 use parking_lot::RwLock;
 use std::sync::Arc;
 
+pub use channel::{CommandRequest, CommandResponse, ControllerChannel};
 pub use listener::{ControllerListener, ControllerListenerHandle};
 
 /// Override state for controller operations
@@ -90,6 +93,14 @@ pub trait ControllerTrait: Send + Sync {
     /// Perform a soft reset
     async fn reset(&mut self) -> anyhow::Result<()>;
 
+    /// Halt the machine at once, ahead of any queued commands
+    ///
+    /// Defaults to a soft reset; controllers whose firmware has its own
+    /// emergency stop send that instead.
+    async fn emergency_stop(&mut self) -> anyhow::Result<()> {
+        self.reset().await
+    }
+
     /// Clear all alarms
     async fn clear_alarm(&mut self) -> anyhow::Result<()>;
 
@@ -137,6 +148,27 @@ pub trait ControllerTrait: Send + Sync {
     /// Cancel streaming
     async fn cancel_streaming(&mut self) -> anyhow::Result<()>;
 
+    /// Feed hold and stop the spindle in a single action
+    ///
+    /// Unlike `pause_streaming`, the spindle is switched off as well.
+    /// Controllers with a real-time spindle stop use it; the default queues
+    /// `M5`, which only takes effect once the controller runs queued commands.
+    async fn safety_hold(&mut self) -> anyhow::Result<()> {
+        self.pause_streaming().await?;
+        self.send_command("M5").await
+    }
+
+    /// Abort the program and leave the machine in a known state
+    ///
+    /// Feed hold stops buffered motion, a reset clears the controller and the
+    /// local queue, then status is queried again so the state comes from the
+    /// machine rather than being assumed idle.
+    async fn abort(&mut self) -> anyhow::Result<()> {
+        self.pause_streaming().await?;
+        self.reset().await?;
+        self.query_status().await.map(|_| ())
+    }
+
     // ===== Probing Methods =====
 
     /// Probe to work surface (Z-axis)
@@ -230,10 +262,14 @@ pub trait ControllerTrait: Send + Sync {
 }
 
 /// Simple controller implementation for testing
+///
+/// Commands are discarded unless a [`ControllerChannel`] is attached, in
+/// which case they are forwarded to the task on the other end of it.
 pub struct SimpleController {
     state: Arc<RwLock<ControllerState>>,
     status: Arc<RwLock<ControllerStatus>>,
     name: String,
+    channel: Option<ControllerChannel>,
 }
 
 impl SimpleController {
@@ -243,9 +279,21 @@ impl SimpleController {
             state: Arc::new(RwLock::new(ControllerState::Disconnected)),
             status: Arc::new(RwLock::new(ControllerStatus::Idle)),
             name: name.to_string(),
+            channel: None,
         }
     }
 
+    /// Forward commands through a channel to a connection task
+    pub fn with_channel(mut self, channel: ControllerChannel) -> Self {
+        self.channel = Some(channel);
+        self
+    }
+
+    /// The channel commands are forwarded to, if any
+    pub fn channel(&self) -> Option<&ControllerChannel> {
+        self.channel.as_ref()
+    }
+
     /// Set the controller state
     pub fn set_state(&self, new_state: ControllerState) {
         *self.state.write() = new_state;
@@ -280,8 +328,15 @@ impl ControllerTrait for SimpleController {
         Ok(())
     }
 
-    async fn send_command(&mut self, _command: &str) -> anyhow::Result<()> {
-        Ok(())
+    async fn send_command(&mut self, command: &str) -> anyhow::Result<()> {
+        let Some(channel) = &self.channel else {
+            return Ok(());
+        };
+        let response = channel.request(command).await?;
+        match response.error {
+            Some(error) => Err(anyhow::anyhow!("{} failed: {}", response.command, error)),
+            None => Ok(()),
+        }
     }
 
     async fn home(&mut self) -> anyhow::Result<()> {
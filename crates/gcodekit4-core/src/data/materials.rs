@@ -175,6 +175,17 @@ impl Default for CuttingParameters {
     }
 }
 
+/// User-chosen spindle speed and feed rate for a material and tool type
+///
+/// Presets override the speeds and feeds calculator's recommendation.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FeedSpeedPreset {
+    /// Spindle speed in RPM
+    pub rpm: u32,
+    /// Feed rate in mm/min
+    pub feed_rate: f32,
+}
+
 /// Material identifier
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash)]
 pub struct MaterialId(pub String);
@@ -232,6 +243,9 @@ pub struct Material {
     // Cutting parameters for different tool types
     /// Cutting parameters (tool type -> parameters)
     pub cutting_params: HashMap<String, CuttingParameters>,
+    /// Feeds and speeds presets (tool type -> preset)
+    #[serde(default)]
+    pub feed_speed_presets: HashMap<String, FeedSpeedPreset>,
 
     // Metadata
     /// Whether this is a user-defined custom material
@@ -267,6 +281,7 @@ impl Material {
             required_ppe: vec![PPE::EyeProtection],
             coolant_required: false,
             cutting_params: HashMap::new(),
+            feed_speed_presets: HashMap::new(),
             custom: false,
             notes: String::new(),
         }
@@ -282,6 +297,16 @@ impl Material {
         self.cutting_params.insert(tool_type, params);
     }
 
+    /// Get the feeds and speeds preset for a tool type
+    pub fn get_feed_speed_preset(&self, tool_type: &str) -> Option<&FeedSpeedPreset> {
+        self.feed_speed_presets.get(tool_type)
+    }
+
+    /// Set the feeds and speeds preset for a tool type
+    pub fn set_feed_speed_preset(&mut self, tool_type: String, preset: FeedSpeedPreset) {
+        self.feed_speed_presets.insert(tool_type, preset);
+    }
+
     /// Get machinability description
     pub fn machinability_desc(&self) -> &'static str {
         match self.machinability_rating {
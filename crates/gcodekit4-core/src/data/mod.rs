@@ -9,9 +9,11 @@
 //! - Unit management (MM, INCH)
 //! - Materials database with cutting parameters
 //! - Tools palette for CAM operations
+//! - Visualizer color themes
 
 pub mod gtc_import;
 pub mod materials;
+pub mod theme;
 pub mod tools;
 
 use serde::{Deserialize, Serialize};
@@ -158,7 +160,10 @@ impl fmt::Display for CNCPoint {
     }
 }
 
-/// Position in 3D space with optional fourth axis (simplified for backward compatibility)
+/// Position in 3D space with optional rotary axes (simplified for backward compatibility)
+///
+/// Rotary axes are `None` on machines that do not have them, so 3-axis
+/// machines do not show a 0° A/B/C axis.
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Position {
     /// X-axis position
@@ -167,33 +172,68 @@ pub struct Position {
     pub y: f32,
     /// Z-axis position
     pub z: f32,
-    /// Fourth axis (A/U) if present
+    /// Fourth axis (A/U) if present, in degrees
     pub a: Option<f32>,
+    /// Fifth axis (B/V) if present, in degrees
+    #[serde(default)]
+    pub b: Option<f32>,
+    /// Sixth axis (C/W) if present, in degrees
+    #[serde(default)]
+    pub c: Option<f32>,
 }
 
 impl Position {
     /// Create a new position with X, Y, Z coordinates
     pub fn new(x: f32, y: f32, z: f32) -> Self {
-        Self { x, y, z, a: None }
+        Self {
+            x,
+            y,
+            z,
+            a: None,
+            b: None,
+            c: None,
+        }
     }
 
     /// Create a position with four axes including the A axis
     pub fn with_a(x: f32, y: f32, z: f32, a: f32) -> Self {
         Self {
-            x,
-            y,
-            z,
             a: Some(a),
+            ..Self::new(x, y, z)
         }
     }
 
-    /// Convert from a CNCPoint to Position (takes only X, Y, Z, A)
+    /// Create a position with the rotary axes that are present
+    pub fn with_rotary(
+        x: f32,
+        y: f32,
+        z: f32,
+        a: Option<f32>,
+        b: Option<f32>,
+        c: Option<f32>,
+    ) -> Self {
+        Self {
+            a,
+            b,
+            c,
+            ..Self::new(x, y, z)
+        }
+    }
+
+    /// Number of axes present (3 plus one for each rotary axis)
+    pub fn axis_count(&self) -> usize {
+        3 + [self.a, self.b, self.c].iter().filter(|v| v.is_some()).count()
+    }
+
+    /// Convert from a CNCPoint to Position (takes all six axes)
     pub fn from_cnc_point(point: &CNCPoint) -> Self {
         Self {
             x: point.x as f32,
             y: point.y as f32,
             z: point.z as f32,
             a: Some(point.a as f32),
+            b: Some(point.b as f32),
+            c: Some(point.c as f32),
         }
     }
 
@@ -204,8 +244,8 @@ impl Position {
             self.y as f64,
             self.z as f64,
             self.a.unwrap_or(0.0) as f64,
-            0.0,
-            0.0,
+            self.b.unwrap_or(0.0) as f64,
+            self.c.unwrap_or(0.0) as f64,
             unit,
         )
     }
@@ -225,34 +265,42 @@ impl Position {
             y: self.y.abs(),
             z: self.z.abs(),
             a: self.a.map(|v| v.abs()),
+            b: self.b.map(|v| v.abs()),
+            c: self.c.map(|v| v.abs()),
         }
     }
 
     /// Add another position (component-wise)
     pub fn add(&self, other: &Position) -> Self {
+        let add = |v1: Option<f32>, v2: Option<f32>| match (v1, v2) {
+            (Some(v1), Some(v2)) => Some(v1 + v2),
+            (Some(v), None) | (None, Some(v)) => Some(v),
+            _ => None,
+        };
         Self {
             x: self.x + other.x,
             y: self.y + other.y,
             z: self.z + other.z,
-            a: match (self.a, other.a) {
-                (Some(a1), Some(a2)) => Some(a1 + a2),
-                (Some(a), None) | (None, Some(a)) => Some(a),
-                _ => None,
-            },
+            a: add(self.a, other.a),
+            b: add(self.b, other.b),
+            c: add(self.c, other.c),
         }
     }
 
     /// Subtract another position (component-wise)
     pub fn subtract(&self, other: &Position) -> Self {
+        let subtract = |v1: Option<f32>, v2: Option<f32>| match (v1, v2) {
+            (Some(v1), Some(v2)) => Some(v1 - v2),
+            (Some(v), None) | (None, Some(v)) => Some(v),
+            _ => None,
+        };
         Self {
             x: self.x - other.x,
             y: self.y - other.y,
             z: self.z - other.z,
-            a: match (self.a, other.a) {
-                (Some(a1), Some(a2)) => Some(a1 - a2),
-                (Some(a), None) | (None, Some(a)) => Some(a),
-                _ => None,
-            },
+            a: subtract(self.a, other.a),
+            b: subtract(self.b, other.b),
+            c: subtract(self.c, other.c),
         }
     }
 }
@@ -265,14 +313,13 @@ impl Default for Position {
 
 impl std::fmt::Display for Position {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.a {
-            Some(a) => write!(
-                f,
-                "X:{:.2} Y:{:.2} Z:{:.2} A:{:.2}",
-                self.x, self.y, self.z, a
-            ),
-            None => write!(f, "X:{:.2} Y:{:.2} Z:{:.2}", self.x, self.y, self.z),
+        write!(f, "X:{:.2} Y:{:.2} Z:{:.2}", self.x, self.y, self.z)?;
+        for (label, value) in [("A", self.a), ("B", self.b), ("C", self.c)] {
+            if let Some(value) = value {
+                write!(f, " {}:{:.2}°", label, value)?;
+            }
         }
+        Ok(())
     }
 }
 
@@ -352,6 +399,8 @@ impl PartialPosition {
             y: self.y.unwrap_or(pos.y),
             z: self.z.unwrap_or(pos.z),
             a: self.a.or(pos.a),
+            b: self.b.or(pos.b),
+            c: self.c.or(pos.c),
         }
     }
 
@@ -0,0 +1,253 @@
+//! Visualizer color theme
+//!
+//! Colors and stroke widths used to draw toolpath layers. Colors are stored as
+//! `#RRGGBB` or `#RRGGBBAA` strings so the theme reads naturally in the
+//! settings file.
+
+use serde::{Deserialize, Serialize};
+
+/// A drawable layer of the toolpath view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ToolpathLayer {
+    /// Background grid
+    Grid,
+    /// Machine work envelope (soft limits)
+    Envelope,
+    /// Origin cross at (0,0)
+    Origin,
+    /// Rapid moves (G0)
+    Rapid,
+    /// Linear cutting moves (G1)
+    G1,
+    /// Clockwise arcs (G2)
+    G2,
+    /// Counter-clockwise arcs (G3)
+    G3,
+    /// Dwells (G4)
+    G4,
+    /// Moves that leave the work envelope
+    OutOfBounds,
+    /// Pins marking user annotations
+    Annotations,
+}
+
+impl ToolpathLayer {
+    /// All layers in drawing order (bottom to top)
+    pub fn all() -> &'static [ToolpathLayer] {
+        &[
+            ToolpathLayer::Grid,
+            ToolpathLayer::Envelope,
+            ToolpathLayer::Origin,
+            ToolpathLayer::Rapid,
+            ToolpathLayer::G1,
+            ToolpathLayer::G2,
+            ToolpathLayer::G3,
+            ToolpathLayer::G4,
+            ToolpathLayer::OutOfBounds,
+            ToolpathLayer::Annotations,
+        ]
+    }
+
+    /// Short lowercase name, e.g. `g1`
+    pub fn name(&self) -> &'static str {
+        match self {
+            ToolpathLayer::Grid => "grid",
+            ToolpathLayer::Envelope => "envelope",
+            ToolpathLayer::Origin => "origin",
+            ToolpathLayer::Rapid => "rapid",
+            ToolpathLayer::G1 => "g1",
+            ToolpathLayer::G2 => "g2",
+            ToolpathLayer::G3 => "g3",
+            ToolpathLayer::G4 => "g4",
+            ToolpathLayer::OutOfBounds => "out_of_bounds",
+            ToolpathLayer::Annotations => "annotations",
+        }
+    }
+}
+
+/// Colors and stroke widths for the toolpath visualizer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VisualizerTheme {
+    /// Rapid move (G0) color
+    pub rapid_color: String,
+    /// Linear move (G1) color
+    pub g1_color: String,
+    /// Clockwise arc (G2) color
+    pub g2_color: String,
+    /// Counter-clockwise arc (G3) color
+    pub g3_color: String,
+    /// Dwell (G4) color
+    pub g4_color: String,
+    /// Grid line color
+    pub grid_color: String,
+    /// Origin marker color
+    pub origin_color: String,
+    /// Work envelope outline color
+    pub envelope_color: String,
+    /// Color of moves outside the work envelope
+    pub out_of_bounds_color: String,
+    /// Annotation pin color
+    pub annotation_color: String,
+    /// Canvas background color
+    pub background_color: String,
+    /// Colors given to each tool in turn when drawing moves by tool
+    pub tool_colors: Vec<String>,
+    /// Stroke width of rapid moves in pixels
+    pub rapid_width: f32,
+    /// Stroke width of cutting moves (G1/G2/G3/G4) in pixels
+    pub cut_width: f32,
+    /// Stroke width of grid lines in pixels
+    pub grid_width: f32,
+    /// Stroke width of the origin marker, work envelope and annotation pins in pixels
+    pub origin_width: f32,
+}
+
+impl VisualizerTheme {
+    /// High-contrast preset: bright strokes on a black background
+    pub fn high_contrast() -> Self {
+        Self {
+            rapid_color: "#FF00FF".to_string(),
+            g1_color: "#FFFFFF".to_string(),
+            g2_color: "#00FF00".to_string(),
+            g3_color: "#FF8000".to_string(),
+            g4_color: "#00BFFF".to_string(),
+            grid_color: "#404040".to_string(),
+            origin_color: "#FFFF00".to_string(),
+            envelope_color: "#00FFFF".to_string(),
+            out_of_bounds_color: "#FF0000".to_string(),
+            annotation_color: "#00BFFF".to_string(),
+            background_color: "#000000".to_string(),
+            tool_colors: [
+                "#FFFFFF", "#00FF00", "#FF8000", "#FF00FF", "#00BFFF", "#FFFF00",
+            ]
+            .map(String::from)
+            .to_vec(),
+            rapid_width: 1.5,
+            cut_width: 2.0,
+            grid_width: 1.0,
+            origin_width: 2.0,
+        }
+    }
+
+    /// Look up a preset by name (`default` or `high_contrast`)
+    pub fn preset(name: &str) -> Option<Self> {
+        match name.to_lowercase().replace(['-', ' '], "_").as_str() {
+            "default" => Some(Self::default()),
+            "high_contrast" => Some(Self::high_contrast()),
+            _ => None,
+        }
+    }
+
+    /// Color used to draw a layer
+    pub fn color(&self, layer: ToolpathLayer) -> &str {
+        match layer {
+            ToolpathLayer::Grid => &self.grid_color,
+            ToolpathLayer::Envelope => &self.envelope_color,
+            ToolpathLayer::Origin => &self.origin_color,
+            ToolpathLayer::Rapid => &self.rapid_color,
+            ToolpathLayer::G1 => &self.g1_color,
+            ToolpathLayer::G2 => &self.g2_color,
+            ToolpathLayer::G3 => &self.g3_color,
+            ToolpathLayer::G4 => &self.g4_color,
+            ToolpathLayer::OutOfBounds => &self.out_of_bounds_color,
+            ToolpathLayer::Annotations => &self.annotation_color,
+        }
+    }
+
+    /// Stroke width used to draw a layer
+    pub fn width(&self, layer: ToolpathLayer) -> f32 {
+        match layer {
+            ToolpathLayer::Grid => self.grid_width,
+            ToolpathLayer::Origin | ToolpathLayer::Envelope | ToolpathLayer::Annotations => {
+                self.origin_width
+            }
+            ToolpathLayer::Rapid => self.rapid_width,
+            ToolpathLayer::G1
+            | ToolpathLayer::G2
+            | ToolpathLayer::G3
+            | ToolpathLayer::G4
+            | ToolpathLayer::OutOfBounds => self.cut_width,
+        }
+    }
+
+    /// Color of the `index`th tool used in a program
+    ///
+    /// Colors repeat once every tool color has been used. Falls back to the
+    /// G1 color if no tool colors are set.
+    pub fn tool_color(&self, index: usize) -> &str {
+        if self.tool_colors.is_empty() {
+            return &self.g1_color;
+        }
+        &self.tool_colors[index % self.tool_colors.len()]
+    }
+
+    /// Parse a `#RRGGBB` or `#RRGGBBAA` color into (r, g, b, a)
+    pub fn parse_color(color: &str) -> Option<(u8, u8, u8, u8)> {
+        let hex = color.trim().strip_prefix('#')?;
+        if !(hex.len() == 6 || hex.len() == 8) || !hex.is_ascii() {
+            return None;
+        }
+        let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        let alpha = if hex.len() == 8 { byte(6)? } else { 255 };
+        Some((byte(0)?, byte(2)?, byte(4)?, alpha))
+    }
+
+    /// Check that all colors parse and all widths are positive
+    pub fn validate(&self) -> Result<(), String> {
+        for &layer in ToolpathLayer::all() {
+            if Self::parse_color(self.color(layer)).is_none() {
+                return Err(format!(
+                    "Invalid {} color '{}'",
+                    layer.name(),
+                    self.color(layer)
+                ));
+            }
+            if self.width(layer) <= 0.0 {
+                return Err(format!("{} stroke width must be > 0", layer.name()));
+            }
+        }
+        if let Some(color) = self
+            .tool_colors
+            .iter()
+            .find(|color| Self::parse_color(color).is_none())
+        {
+            return Err(format!("Invalid tool color '{}'", color));
+        }
+        if Self::parse_color(&self.background_color).is_none() {
+            return Err(format!(
+                "Invalid background color '{}'",
+                self.background_color
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for VisualizerTheme {
+    fn default() -> Self {
+        Self {
+            rapid_color: "#00FFFF80".to_string(),
+            g1_color: "#FFFF00".to_string(),
+            g2_color: "#00FF00".to_string(),
+            g3_color: "#FF0000".to_string(),
+            g4_color: "#0000FF".to_string(),
+            grid_color: "#808080".to_string(),
+            origin_color: "#FFFF00".to_string(),
+            envelope_color: "#ECF0F1".to_string(),
+            out_of_bounds_color: "#FF00FF".to_string(),
+            annotation_color: "#FF8000".to_string(),
+            background_color: "#34495E".to_string(),
+            tool_colors: [
+                "#FFFF00", "#00FF00", "#FF8000", "#FF66CC", "#00BFFF", "#C080FF", "#80FF80",
+                "#FF4040",
+            ]
+            .map(String::from)
+            .to_vec(),
+            rapid_width: 1.0,
+            cut_width: 1.0,
+            grid_width: 1.0,
+            origin_width: 2.0,
+        }
+    }
+}
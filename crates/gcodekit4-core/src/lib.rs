@@ -12,7 +12,8 @@ pub mod units;
 pub use core::{
     event::{ControllerEvent, EventDispatcher},
     message::{Message, MessageDispatcher, MessageLevel},
-    ControllerListener, ControllerListenerHandle, ControllerTrait, OverrideState, SimpleController,
+    CommandRequest, CommandResponse, ControllerChannel, ControllerListener,
+    ControllerListenerHandle, ControllerTrait, OverrideState, SimpleController,
 };
 
 pub use data::{
@@ -20,4 +21,6 @@ pub use data::{
     MachineStatusSnapshot, PartialPosition, Position, Units,
 };
 
+pub use data::theme::{ToolpathLayer, VisualizerTheme};
+
 pub use error::{ConnectionError, ControllerError, Error, FirmwareError, GcodeError, Result};
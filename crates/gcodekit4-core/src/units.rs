@@ -1,11 +1,14 @@
 //! Unit conversion utilities
 //!
 //! Handles conversion between Metric (mm) and Imperial (inch) systems.
-//! Supports decimal and fractional inch parsing and formatting.
+//! Supports decimal and fractional inch parsing and formatting, and
+//! fixed-precision DRO formatting keyed to the controller's `Units`.
 
 use std::fmt;
 use std::str::FromStr;
 
+use crate::data::Units;
+
 /// Measurement system
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MeasurementSystem {
@@ -104,6 +107,120 @@ pub fn parse_from_string(input: &str, system: MeasurementSystem) -> Result<f32,
     }
 }
 
+/// Digital readout (DRO) formatter for machine positions
+///
+/// Positions are reported by the controller in `machine_units` and shown in
+/// `display_units`. Toggling the display units only converts what is shown;
+/// nothing is sent to the machine. Values use a fixed precision per unit
+/// (3 decimals for mm, 4 for inches) so the readout does not jitter in width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DroFormatter {
+    machine_units: Units,
+    display_units: Units,
+}
+
+impl DroFormatter {
+    /// Create a formatter that displays values in the machine's own units
+    pub fn new(machine_units: Units) -> Self {
+        Self {
+            machine_units,
+            display_units: machine_units,
+        }
+    }
+
+    /// Set the units used for display
+    pub fn with_display_units(mut self, display_units: Units) -> Self {
+        self.display_units = display_units;
+        self
+    }
+
+    /// Units the controller reports positions in
+    pub fn machine_units(&self) -> Units {
+        self.machine_units
+    }
+
+    /// Units positions are displayed in
+    pub fn display_units(&self) -> Units {
+        self.display_units
+    }
+
+    /// Set the units used for display
+    pub fn set_display_units(&mut self, display_units: Units) {
+        self.display_units = display_units;
+    }
+
+    /// Switch the display between millimeters and inches
+    pub fn toggle_display_units(&mut self) {
+        self.display_units = match self.display_units {
+            Units::INCH => Units::MM,
+            _ => Units::INCH,
+        };
+    }
+
+    /// Number of decimal places shown for the display units
+    pub fn precision(&self) -> usize {
+        match self.display_units {
+            Units::INCH => 4,
+            Units::MM | Units::Unknown => 3,
+        }
+    }
+
+    /// Unit suffix for the display units, empty if unknown
+    pub fn suffix(&self) -> &'static str {
+        match self.display_units {
+            Units::MM => "mm",
+            Units::INCH => "in",
+            Units::Unknown => "",
+        }
+    }
+
+    /// Convert a machine position to the display units
+    pub fn convert(&self, value: f64) -> f64 {
+        Units::convert(value, self.machine_units, self.display_units)
+    }
+
+    /// Format a machine position as a number without suffix, e.g. `1.0000`
+    pub fn format_value(&self, value: f64) -> String {
+        let converted = self.convert(value);
+        // Avoid showing "-0.000" for values that round to zero
+        let scale = 10f64.powi(self.precision() as i32);
+        let converted = if (converted * scale).round() == 0.0 {
+            0.0
+        } else {
+            converted
+        };
+        format!("{:.*}", self.precision(), converted)
+    }
+
+    /// Format a machine position with its unit suffix, e.g. `1.0000 in`
+    pub fn format(&self, value: f64) -> String {
+        let suffix = self.suffix();
+        if suffix.is_empty() {
+            self.format_value(value)
+        } else {
+            format!("{} {}", self.format_value(value), suffix)
+        }
+    }
+
+    /// Format a rotary axis position in degrees, e.g. `90.000°`
+    ///
+    /// Rotary axes are not affected by the inch/mm display toggle.
+    pub fn format_rotary(&self, degrees: f64) -> String {
+        let degrees = if (degrees * 1000.0).round() == 0.0 {
+            0.0
+        } else {
+            degrees
+        };
+        format!("{:.3}°", degrees)
+    }
+}
+
+impl Default for DroFormatter {
+    fn default() -> Self {
+        Self::new(Units::MM)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
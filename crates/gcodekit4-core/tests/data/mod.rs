@@ -1,3 +1,4 @@
 mod materials;
+mod theme;
 mod tools;
 mod gtc_import;
@@ -0,0 +1,64 @@
+use gcodekit4_core::data::theme::*;
+
+#[test]
+fn test_default_theme_is_valid() {
+    let theme = VisualizerTheme::default();
+    assert!(theme.validate().is_ok());
+    assert_eq!(theme.color(ToolpathLayer::G1), "#FFFF00");
+    assert_eq!(theme.width(ToolpathLayer::Origin), 2.0);
+}
+
+#[test]
+fn test_high_contrast_preset() {
+    let theme = VisualizerTheme::preset("High Contrast").unwrap();
+    assert_eq!(theme, VisualizerTheme::high_contrast());
+    assert_eq!(theme.background_color, "#000000");
+    assert!(theme.validate().is_ok());
+    assert!(VisualizerTheme::preset("neon").is_none());
+}
+
+#[test]
+fn test_parse_color() {
+    assert_eq!(VisualizerTheme::parse_color("#FF8000"), Some((255, 128, 0, 255)));
+    assert_eq!(VisualizerTheme::parse_color("#00FFFF80"), Some((0, 255, 255, 128)));
+    assert_eq!(VisualizerTheme::parse_color("FF8000"), None);
+    assert_eq!(VisualizerTheme::parse_color("#FF80"), None);
+    assert_eq!(VisualizerTheme::parse_color("#GG0000"), None);
+}
+
+#[test]
+fn test_validate_rejects_bad_values() {
+    let theme = VisualizerTheme {
+        g2_color: "green".to_string(),
+        ..VisualizerTheme::default()
+    };
+    assert!(theme.validate().unwrap_err().contains("g2"));
+
+    let theme = VisualizerTheme {
+        cut_width: 0.0,
+        ..VisualizerTheme::default()
+    };
+    assert!(theme.validate().is_err());
+}
+
+#[test]
+fn test_tool_colors_cycle() {
+    let theme = VisualizerTheme {
+        tool_colors: vec!["#FF0000".to_string(), "#00FF00".to_string()],
+        ..VisualizerTheme::default()
+    };
+    assert_eq!(theme.tool_color(1), "#00FF00");
+    assert_eq!(theme.tool_color(2), "#FF0000");
+
+    let theme = VisualizerTheme {
+        tool_colors: Vec::new(),
+        ..VisualizerTheme::default()
+    };
+    assert_eq!(theme.tool_color(0), theme.g1_color);
+
+    let theme = VisualizerTheme {
+        tool_colors: vec!["red".to_string()],
+        ..VisualizerTheme::default()
+    };
+    assert!(theme.validate().unwrap_err().contains("tool color"));
+}
@@ -0,0 +1,33 @@
+use gcodekit4_core::units::DroFormatter;
+use gcodekit4_core::Units;
+
+#[test]
+fn test_dro_formats_inches_from_mm() {
+    let dro = DroFormatter::new(Units::MM).with_display_units(Units::INCH);
+    assert_eq!(dro.format(25.4), "1.0000 in");
+    assert_eq!(dro.format_value(-12.7), "-0.5000");
+}
+
+#[test]
+fn test_dro_native_units_and_toggle() {
+    let mut dro = DroFormatter::new(Units::MM);
+    assert_eq!(dro.format(25.4), "25.400 mm");
+    assert_eq!(dro.format(-0.0001), "0.000 mm");
+
+    dro.toggle_display_units();
+    assert_eq!(dro.display_units(), Units::INCH);
+    assert_eq!(dro.format(25.4), "1.0000 in");
+
+    dro.toggle_display_units();
+    assert_eq!(dro.format(25.4), "25.400 mm");
+
+    let inch_machine = DroFormatter::new(Units::INCH).with_display_units(Units::MM);
+    assert_eq!(inch_machine.format(1.0), "25.400 mm");
+}
+
+#[test]
+fn test_dro_formats_rotary_in_degrees() {
+    let dro = DroFormatter::new(Units::MM).with_display_units(Units::INCH);
+    assert_eq!(dro.format_rotary(90.0), "90.000°");
+    assert_eq!(dro.format_rotary(-0.0001), "0.000°");
+}
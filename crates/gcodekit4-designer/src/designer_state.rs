@@ -20,6 +20,10 @@ pub struct DesignerState {
     pub is_modified: bool,
     pub design_name: String,
     pub show_grid: bool,
+    /// Draw the machine's work area boundary on the canvas
+    pub show_work_area: bool,
+    /// Work area of the active machine as (min_x, min_y, max_x, max_y)
+    pub work_area: Option<(f64, f64, f64, f64)>,
     pub clipboard: Vec<crate::canvas::DrawingObject>,
     pub default_properties_shape: crate::canvas::DrawingObject,
     undo_stack: Vec<DesignerCommand>,
@@ -38,6 +42,8 @@ impl DesignerState {
             is_modified: false,
             design_name: "Untitled".to_string(),
             show_grid: true,
+            show_work_area: true,
+            work_area: None,
             clipboard: Vec::new(),
             default_properties_shape: crate::canvas::DrawingObject::new(0, crate::shapes::Shape::Rectangle(crate::shapes::Rectangle::new(0.0, 0.0, 0.0, 0.0))),
             undo_stack: Vec::new(),
@@ -121,6 +127,133 @@ impl DesignerState {
         self.show_grid = !self.show_grid;
     }
 
+    /// Toggle work area boundary visibility
+    pub fn toggle_work_area(&mut self) {
+        self.show_work_area = !self.show_work_area;
+    }
+
+    /// Sets the work area, usually from the active device profile's X and Y travel.
+    pub fn set_work_area(&mut self, min_x: f64, min_y: f64, max_x: f64, max_y: f64) {
+        self.work_area = Some((
+            min_x.min(max_x),
+            min_y.min(max_y),
+            min_x.max(max_x),
+            min_y.max(max_y),
+        ));
+    }
+
+    /// Clears the work area, e.g. when no machine profile is active.
+    pub fn clear_work_area(&mut self) {
+        self.work_area = None;
+    }
+
+    /// Returns the ids of shapes extending beyond the work area.
+    /// Nothing is flagged when no work area is set.
+    pub fn shapes_outside_work_area(&self) -> Vec<u64> {
+        let Some((area_min_x, area_min_y, area_max_x, area_max_y)) = self.work_area else {
+            return Vec::new();
+        };
+        const TOLERANCE: f64 = 1e-6;
+
+        self.canvas
+            .shapes()
+            .filter(|obj| {
+                let (x1, y1, x2, y2) = obj.shape.bounding_box();
+                x1 < area_min_x - TOLERANCE
+                    || y1 < area_min_y - TOLERANCE
+                    || x2 > area_max_x + TOLERANCE
+                    || y2 > area_max_y + TOLERANCE
+            })
+            .map(|obj| obj.id)
+            .collect()
+    }
+
+    /// Fits the selected shapes, or the whole design when nothing is selected,
+    /// inside the work area. Shapes too big for it are scaled down uniformly,
+    /// then everything is moved just far enough to lie inside.
+    pub fn fit_to_work_area(&mut self) {
+        let Some((area_min_x, area_min_y, area_max_x, area_max_y)) = self.work_area else {
+            return;
+        };
+        let all = self.canvas.selected_count() == 0;
+        let ids: Vec<u64> = self.canvas.shapes().filter(|s| all || s.selected).map(|s| s.id).collect();
+        if ids.is_empty() {
+            return;
+        }
+
+        // Calculate bounding box of the shapes being fitted
+        let mut min_x = f64::INFINITY;
+        let mut min_y = f64::INFINITY;
+        let mut max_x = f64::NEG_INFINITY;
+        let mut max_y = f64::NEG_INFINITY;
+
+        for id in &ids {
+            if let Some(obj) = self.canvas.get_shape(*id) {
+                let (x1, y1, x2, y2) = obj.shape.bounding_box();
+                min_x = min_x.min(x1);
+                min_y = min_y.min(y1);
+                max_x = max_x.max(x2);
+                max_y = max_y.max(y2);
+            }
+        }
+
+        let fit = |size: f64, available: f64| {
+            if size > 1e-6 {
+                available / size
+            } else {
+                f64::INFINITY
+            }
+        };
+        let scale = fit(max_x - min_x, area_max_x - area_min_x)
+            .min(fit(max_y - min_y, area_max_y - area_min_y))
+            .min(1.0);
+
+        // Scale about the lower left corner, then shift inside the area
+        let new_max_x = min_x + (max_x - min_x) * scale;
+        let new_max_y = min_y + (max_y - min_y) * scale;
+        let shift = |low: f64, high: f64, area_low: f64, area_high: f64| {
+            if low < area_low {
+                area_low - low
+            } else if high > area_high {
+                area_high - high
+            } else {
+                0.0
+            }
+        };
+        let dx = shift(min_x, new_max_x, area_min_x, area_max_x);
+        let dy = shift(min_y, new_max_y, area_min_y, area_max_y);
+        if scale == 1.0 && dx == 0.0 && dy == 0.0 {
+            return;
+        }
+
+        let mut commands = Vec::new();
+        for id in ids {
+            if let Some(obj) = self.canvas.get_shape(id) {
+                let old_shape = obj.shape.clone();
+                let mut new_shape = old_shape.clone();
+                if scale < 1.0 {
+                    new_shape.scale(scale, scale, Point::new(min_x, min_y));
+                }
+                new_shape.translate(dx, dy);
+
+                commands.push(DesignerCommand::ResizeShape(ResizeShape {
+                    id,
+                    handle: 4,
+                    dx,
+                    dy,
+                    old_shape: Some(old_shape),
+                    new_shape: Some(new_shape),
+                }));
+            }
+        }
+
+        let cmd = DesignerCommand::CompositeCommand(CompositeCommand {
+            commands,
+            name: "Fit to Work Area".to_string(),
+        });
+        self.push_command(cmd);
+    }
+
     /// Sets the drawing mode.
     pub fn set_mode(&mut self, mode: i32) {
         let drawing_mode = match mode {
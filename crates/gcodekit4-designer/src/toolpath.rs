@@ -120,6 +120,11 @@ impl ToolpathGenerator {
         self.tool_diameter = diameter;
     }
 
+    /// Gets the tool diameter in mm.
+    pub fn tool_diameter(&self) -> f64 {
+        self.tool_diameter
+    }
+
     /// Sets the cut depth in mm (negative for downward).
     pub fn set_cut_depth(&mut self, depth: f64) {
         self.cut_depth = depth;
@@ -151,3 +151,40 @@ fn test_designer_state_polyline_update() {
         }
     }
 }
+
+#[test]
+fn test_shapes_beyond_work_area_are_flagged() {
+    let mut state = DesignerState::new();
+    let inside = state.canvas.add_rectangle(20.0, 20.0, 50.0, 50.0);
+    let too_big = state.canvas.add_rectangle(10.0, 10.0, 400.0, 100.0);
+
+    // Without a machine work area nothing is flagged
+    assert!(state.shapes_outside_work_area().is_empty());
+
+    state.set_work_area(0.0, 0.0, 300.0, 200.0);
+    assert!(state.show_work_area);
+    assert_eq!(state.shapes_outside_work_area(), vec![too_big]);
+    assert!(!state.shapes_outside_work_area().contains(&inside));
+}
+
+#[test]
+fn test_fit_to_work_area_scales_design_inside_bounds() {
+    let mut state = DesignerState::new();
+    state.canvas.add_rectangle(20.0, 20.0, 50.0, 50.0);
+    let too_big = state.canvas.add_rectangle(10.0, 10.0, 400.0, 100.0);
+    state.set_work_area(0.0, 0.0, 300.0, 200.0);
+
+    state.fit_to_work_area();
+
+    assert!(state.shapes_outside_work_area().is_empty());
+    // Scaled by 300/400 and moved back inside the X travel
+    let (x1, y1, x2, y2) = state.canvas.get_shape(too_big).unwrap().shape.bounding_box();
+    assert!(x1.abs() < 1e-6);
+    assert!((x2 - 300.0).abs() < 1e-6);
+    assert!((y1 - 10.0).abs() < 1e-6);
+    assert!((y2 - 85.0).abs() < 1e-6);
+
+    // Fitting is a single undoable step
+    state.undo();
+    assert_eq!(state.shapes_outside_work_area(), vec![too_big]);
+}
@@ -1,4 +1,4 @@
-use crate::model::DeviceProfile;
+use crate::model::{ConnectionProfile, DeviceProfile, SenderConfig};
 use crate::traits::DeviceProfileProvider;
 use anyhow::{Context, Result};
 use std::collections::HashMap;
@@ -9,7 +9,9 @@ use std::sync::{Arc, RwLock, Mutex};
 #[derive(Debug, Clone)]
 pub struct DeviceManager {
     profiles: Arc<RwLock<HashMap<String, DeviceProfile>>>,
+    connections: Arc<RwLock<HashMap<String, ConnectionProfile>>>,
     active_profile_id: Arc<RwLock<Option<String>>>,
+    sender_config: Arc<RwLock<SenderConfig>>,
     config_path: PathBuf,
     file_lock: Arc<Mutex<()>>,
 }
@@ -18,7 +20,9 @@ impl DeviceManager {
     pub fn new(config_path: PathBuf) -> Self {
         Self {
             profiles: Arc::new(RwLock::new(HashMap::new())),
+            connections: Arc::new(RwLock::new(HashMap::new())),
             active_profile_id: Arc::new(RwLock::new(None)),
+            sender_config: Arc::new(RwLock::new(SenderConfig::default())),
             config_path,
             file_lock: Arc::new(Mutex::new(())),
         }
@@ -48,15 +52,27 @@ impl DeviceManager {
             }
         }
 
+        let mut connections_map = HashMap::new();
+
+        if let Some(connections_array) = data.get("connections").and_then(|v| v.as_array()) {
+            for c in connections_array {
+                let connection: ConnectionProfile = serde_json::from_value(c.clone())?;
+                connections_map.insert(connection.id.clone(), connection);
+            }
+        }
+
         let active_id = data.get("active_id").and_then(|v| v.as_str()).map(String::from);
 
         {
             let mut profiles_lock = self.profiles.write().unwrap();
             *profiles_lock = profiles_map;
+
+            *self.connections.write().unwrap() = connections_map;
             
             let mut active_lock = self.active_profile_id.write().unwrap();
             *active_lock = active_id;
         }
+        self.apply_active_profile();
 
         Ok(())
     }
@@ -66,12 +82,15 @@ impl DeviceManager {
         let _file_guard = self.file_lock.lock().unwrap();
 
         let profiles_lock = self.profiles.read().unwrap();
+        let connections_lock = self.connections.read().unwrap();
         let active_lock = self.active_profile_id.read().unwrap();
 
         let profiles_vec: Vec<&DeviceProfile> = profiles_lock.values().collect();
+        let connections_vec: Vec<&ConnectionProfile> = connections_lock.values().collect();
         
         let data = serde_json::json!({
             "profiles": profiles_vec,
+            "connections": connections_vec,
             "active_id": *active_lock
         });
 
@@ -99,6 +118,7 @@ impl DeviceManager {
             let mut lock = self.profiles.write().unwrap();
             lock.insert(profile.id.clone(), profile);
         }
+        self.apply_active_profile();
         self.save()
     }
 
@@ -117,6 +137,7 @@ impl DeviceManager {
                 }
             }
         }
+        self.apply_active_profile();
         
         self.save()
     }
@@ -128,6 +149,7 @@ impl DeviceManager {
                 let mut lock = self.active_profile_id.write().unwrap();
                 *lock = Some(id.to_string());
             }
+            self.apply_active_profile();
             self.save()?;
             Ok(())
         } else {
@@ -139,6 +161,123 @@ impl DeviceManager {
         let active_id = self.active_profile_id.read().unwrap().clone()?;
         self.get_profile(&active_id)
     }
+
+    pub fn get_connection_profile(&self, id: &str) -> Option<ConnectionProfile> {
+        self.connections.read().unwrap().get(id).cloned()
+    }
+
+    pub fn get_all_connection_profiles(&self) -> Vec<ConnectionProfile> {
+        self.connections.read().unwrap().values().cloned().collect()
+    }
+
+    pub fn save_connection_profile(&self, connection: ConnectionProfile) -> Result<()> {
+        {
+            let mut lock = self.connections.write().unwrap();
+            lock.insert(connection.id.clone(), connection);
+        }
+        self.save()
+    }
+
+    /// Delete a connection profile and drop every device's reference to it
+    pub fn delete_connection_profile(&self, id: &str) -> Result<()> {
+        self.connections.write().unwrap().remove(id);
+        {
+            let mut profiles = self.profiles.write().unwrap();
+            for profile in profiles.values_mut() {
+                profile.connection_ids.retain(|c| c != id);
+                if profile.last_connection_id.as_deref() == Some(id) {
+                    profile.last_connection_id = None;
+                }
+            }
+        }
+        self.save()
+    }
+
+    /// Connection profiles a device references, in the device's order
+    ///
+    /// References to deleted connection profiles are skipped.
+    pub fn connections_for(&self, device_id: &str) -> Vec<ConnectionProfile> {
+        let Some(profile) = self.get_profile(device_id) else {
+            return Vec::new();
+        };
+        let connections = self.connections.read().unwrap();
+        profile
+            .connection_ids
+            .iter()
+            .filter_map(|id| connections.get(id).cloned())
+            .collect()
+    }
+
+    /// Quick-switch the connection a device uses and remember it as last used
+    pub fn switch_connection(&self, device_id: &str, connection_id: &str) -> Result<()> {
+        {
+            let mut profiles = self.profiles.write().unwrap();
+            let profile = profiles
+                .get_mut(device_id)
+                .ok_or_else(|| anyhow::anyhow!("Profile not found"))?;
+            if !profile.connection_ids.iter().any(|c| c == connection_id)
+                || !self.connections.read().unwrap().contains_key(connection_id)
+            {
+                return Err(anyhow::anyhow!(
+                    "Connection '{}' is not available for '{}'",
+                    connection_id,
+                    profile.name
+                ));
+            }
+            profile.last_connection_id = Some(connection_id.to_string());
+        }
+        self.save()
+    }
+
+    /// Connection settings for the active device
+    ///
+    /// This is the device's last used connection profile, falling back to its
+    /// first one and then to the settings stored on the device itself.
+    /// DTR/RTS levels the connection profile leaves unset come from the device.
+    pub fn active_connection(&self) -> Option<ConnectionProfile> {
+        let profile = self.get_active_profile()?;
+        let connection = profile
+            .active_connection_id()
+            .and_then(|id| self.get_connection_profile(id))
+            .or_else(|| self.connections_for(&profile.id).into_iter().next());
+        let mut connection = connection.unwrap_or_else(|| ConnectionProfile::from_device(&profile));
+        connection.dtr_on_open = connection.dtr_on_open.or(profile.dtr_on_open);
+        connection.rts_on_open = connection.rts_on_open.or(profile.rts_on_open);
+        Some(connection)
+    }
+
+    /// Keep DTR and RTS low when the active device's port is opened
+    ///
+    /// Boards that reset on a DTR edge then keep their state across
+    /// connections. Turning it off leaves both lines at the driver default.
+    pub fn set_no_reset_on_open(&self, no_reset: bool) -> Result<()> {
+        let mut profile = self
+            .get_active_profile()
+            .ok_or_else(|| anyhow::anyhow!("No active device profile"))?;
+        let level = if no_reset { Some(false) } else { None };
+        profile.dtr_on_open = level;
+        profile.rts_on_open = level;
+        self.save_profile(profile)
+    }
+
+    /// Sender/jog configuration pushed by the most recent profile activation
+    pub fn sender_config(&self) -> SenderConfig {
+        self.sender_config.read().unwrap().clone()
+    }
+
+    fn apply_active_profile(&self) {
+        let config = self
+            .get_active_profile()
+            .map(|p| p.sender_config())
+            .unwrap_or_default();
+        tracing::debug!(
+            "Applying sender config: recipe={:?}, jog feed={}, jog step={}",
+            config.recipe,
+            config.jog_feed_rate,
+            config.jog_step_size
+        );
+        *self.sender_config.write().unwrap() = config;
+    }
 }
 
 impl DeviceProfileProvider for DeviceManager {
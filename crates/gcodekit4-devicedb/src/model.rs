@@ -102,6 +102,26 @@ pub struct DeviceProfile {
     pub tcp_port: u16,
     pub timeout_ms: u64,
     pub auto_reconnect: bool,
+    /// DTR level on port open, `None` leaves the driver default
+    pub dtr_on_open: Option<bool>,
+    /// RTS level on port open, `None` leaves the driver default
+    pub rts_on_open: Option<bool>,
+
+    // Defaults applied when the profile is activated
+    /// Name of the preprocessing recipe to select, empty for none
+    pub default_recipe: String,
+    pub jog_feed_rate: f64,
+    pub jog_step_size: f64,
+    /// Transmit comment-only lines instead of skipping them
+    pub send_comments: bool,
+    /// Commands sent after every connection, once the firmware is detected
+    pub startup_commands: Vec<String>,
+
+    // Reusable connection profiles
+    /// Ids of the connection profiles this device can be reached through
+    pub connection_ids: Vec<String>,
+    /// Connection profile picked most recently, if any
+    pub last_connection_id: Option<String>,
 }
 
 impl Default for DeviceProfile {
@@ -128,6 +148,157 @@ impl Default for DeviceProfile {
             tcp_port: 23,
             timeout_ms: 5000,
             auto_reconnect: false,
+            dtr_on_open: None,
+            rts_on_open: None,
+            default_recipe: "".to_string(),
+            jog_feed_rate: 2000.0,
+            jog_step_size: 1.0,
+            send_comments: false,
+            startup_commands: Vec::new(),
+            connection_ids: Vec::new(),
+            last_connection_id: None,
+        }
+    }
+}
+
+impl DeviceProfile {
+    /// Id of the connection profile to use: the last one picked if it is
+    /// still referenced, otherwise the first
+    pub fn active_connection_id(&self) -> Option<&str> {
+        self.last_connection_id
+            .as_deref()
+            .filter(|id| self.connection_ids.iter().any(|c| c == id))
+            .or_else(|| self.connection_ids.first().map(String::as_str))
+    }
+
+    /// Check whether the port is opened with DTR and RTS held low
+    pub fn no_reset_on_open(&self) -> bool {
+        self.dtr_on_open == Some(false) && self.rts_on_open == Some(false)
+    }
+
+    /// Build the sender/jog configuration this profile applies on activation
+    ///
+    /// Non-positive jog values fall back to the `SenderConfig` defaults, and
+    /// blank startup commands are dropped.
+    pub fn sender_config(&self) -> SenderConfig {
+        let defaults = SenderConfig::default();
+        let recipe = self.default_recipe.trim();
+
+        SenderConfig {
+            recipe: (!recipe.is_empty()).then(|| recipe.to_string()),
+            jog_feed_rate: if self.jog_feed_rate > 0.0 {
+                self.jog_feed_rate
+            } else {
+                defaults.jog_feed_rate
+            },
+            jog_step_size: if self.jog_step_size > 0.0 {
+                self.jog_step_size
+            } else {
+                defaults.jog_step_size
+            },
+            send_comments: self.send_comments,
+            startup_commands: self
+                .startup_commands
+                .iter()
+                .map(|command| command.trim())
+                .filter(|command| !command.is_empty())
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+}
+
+/// Sender and jog settings driven by the active device profile
+#[derive(Debug, Clone, PartialEq)]
+pub struct SenderConfig {
+    /// Preprocessing recipe to apply to files before streaming
+    pub recipe: Option<String>,
+    /// Feed rate used for jog commands (units/min)
+    pub jog_feed_rate: f64,
+    /// Default jog increment
+    pub jog_step_size: f64,
+    /// Transmit comment-only lines, for firmwares that log them
+    pub send_comments: bool,
+    /// Commands to run after connecting, in order, each waiting for `ok`
+    pub startup_commands: Vec<String>,
+}
+
+impl Default for SenderConfig {
+    fn default() -> Self {
+        Self {
+            recipe: None,
+            jog_feed_rate: 2000.0,
+            jog_step_size: 1.0,
+            send_comments: false,
+            startup_commands: Vec::new(),
         }
     }
 }
+
+/// Reusable connection settings, e.g. USB at the shop or TCP from home
+///
+/// Device profiles reference these by id so one machine can be reached
+/// several ways without duplicating its geometry.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ConnectionProfile {
+    pub id: String,
+    pub name: String,
+    /// `Serial`, `TCP` or `WebSocket`
+    pub connection_type: String,
+    /// Serial port name, or host name for network connections
+    pub port: String,
+    pub baud_rate: u32,
+    pub tcp_port: u16,
+    pub timeout_ms: u64,
+    pub auto_reconnect: bool,
+    /// DTR level on port open, `None` leaves the driver default
+    pub dtr_on_open: Option<bool>,
+    /// RTS level on port open, `None` leaves the driver default
+    pub rts_on_open: Option<bool>,
+}
+
+impl Default for ConnectionProfile {
+    fn default() -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name: "New Connection".to_string(),
+            connection_type: "Serial".to_string(),
+            port: "Auto".to_string(),
+            baud_rate: 115200,
+            tcp_port: 23,
+            timeout_ms: 5000,
+            auto_reconnect: false,
+            dtr_on_open: None,
+            rts_on_open: None,
+        }
+    }
+}
+
+impl ConnectionProfile {
+    /// The connection settings stored on a device profile itself
+    ///
+    /// Used for devices that don't reference any connection profile. The
+    /// result shares the device's id.
+    pub fn from_device(profile: &DeviceProfile) -> Self {
+        Self {
+            id: profile.id.clone(),
+            name: profile.name.clone(),
+            connection_type: profile.connection_type.clone(),
+            port: profile.port.clone(),
+            baud_rate: profile.baud_rate,
+            tcp_port: profile.tcp_port,
+            timeout_ms: profile.timeout_ms,
+            auto_reconnect: profile.auto_reconnect,
+            dtr_on_open: profile.dtr_on_open,
+            rts_on_open: profile.rts_on_open,
+        }
+    }
+
+    /// Check whether this connects over the network rather than a serial port
+    pub fn is_network(&self) -> bool {
+        ["tcp", "websocket"]
+            .iter()
+            .any(|kind| self.connection_type.eq_ignore_ascii_case(kind))
+    }
+}
@@ -1,4 +1,6 @@
-use gcodekit4_devicedb::{DeviceManager, DeviceProfile, DeviceType, ControllerType};
+use gcodekit4_devicedb::{
+    ConnectionProfile, ControllerType, DeviceManager, DeviceProfile, DeviceType, SenderConfig,
+};
 use tempfile::tempdir;
 
 #[test]
@@ -55,3 +57,186 @@ fn test_device_manager_crud() {
     assert_eq!(manager2.get_all_profiles().len(), 1);
     assert!(manager2.get_active_profile().is_none()); // Active profile was deleted
 }
+
+#[test]
+fn test_activate_profile_applies_recipe_and_jog_feeds() {
+    let dir = tempdir().unwrap();
+    let manager = DeviceManager::new(dir.path().join("devices.json"));
+    manager.load().unwrap();
+
+    // Default profile has no recipe and the stock jog settings
+    assert_eq!(manager.sender_config(), SenderConfig::default());
+
+    let router = DeviceProfile {
+        name: "Router".to_string(),
+        default_recipe: "grbl-cleanup".to_string(),
+        jog_feed_rate: 3500.0,
+        jog_step_size: 5.0,
+        ..Default::default()
+    };
+    manager.save_profile(router.clone()).unwrap();
+
+    let laser = DeviceProfile {
+        name: "Laser".to_string(),
+        jog_feed_rate: 0.0, // invalid, falls back to default
+        ..Default::default()
+    };
+    manager.save_profile(laser.clone()).unwrap();
+
+    manager.set_active_profile(&router.id).unwrap();
+    let config = manager.sender_config();
+    assert_eq!(config.recipe.as_deref(), Some("grbl-cleanup"));
+    assert_eq!(config.jog_feed_rate, 3500.0);
+    assert_eq!(config.jog_step_size, 5.0);
+
+    manager.set_active_profile(&laser.id).unwrap();
+    let config = manager.sender_config();
+    assert_eq!(config.recipe, None);
+    assert_eq!(config.jog_feed_rate, SenderConfig::default().jog_feed_rate);
+
+    // Deleting the active profile resets to defaults
+    manager.delete_profile(&laser.id).unwrap();
+    assert_eq!(manager.sender_config(), SenderConfig::default());
+}
+
+#[test]
+fn test_activate_profile_applies_startup_commands() {
+    let dir = tempdir().unwrap();
+    let manager = DeviceManager::new(dir.path().join("devices.json"));
+    manager.load().unwrap();
+
+    let router = DeviceProfile {
+        name: "Router".to_string(),
+        startup_commands: vec!["G21".to_string(), "  ".to_string(), " $10=1 ".to_string()],
+        ..Default::default()
+    };
+    manager.save_profile(router.clone()).unwrap();
+    manager.set_active_profile(&router.id).unwrap();
+
+    assert_eq!(manager.sender_config().startup_commands, vec!["G21", "$10=1"]);
+}
+
+#[test]
+fn test_device_switches_between_connection_profiles() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("devices.json");
+    let manager = DeviceManager::new(config_path.clone());
+    manager.load().unwrap();
+
+    let usb = ConnectionProfile {
+        name: "Shop USB".to_string(),
+        port: "/dev/ttyUSB0".to_string(),
+        baud_rate: 115200,
+        dtr_on_open: Some(false),
+        ..Default::default()
+    };
+    let remote = ConnectionProfile {
+        name: "Remote".to_string(),
+        connection_type: "TCP".to_string(),
+        port: "router.local".to_string(),
+        tcp_port: 23,
+        ..Default::default()
+    };
+    manager.save_connection_profile(usb.clone()).unwrap();
+    manager.save_connection_profile(remote.clone()).unwrap();
+
+    let router = DeviceProfile {
+        name: "Router".to_string(),
+        connection_ids: vec![usb.id.clone(), remote.id.clone()],
+        ..Default::default()
+    };
+    manager.save_profile(router.clone()).unwrap();
+    manager.set_active_profile(&router.id).unwrap();
+
+    let names: Vec<String> = manager
+        .connections_for(&router.id)
+        .into_iter()
+        .map(|c| c.name)
+        .collect();
+    assert_eq!(names, vec!["Shop USB", "Remote"]);
+    // Without a last used connection the first one is picked
+    assert_eq!(manager.active_connection().unwrap(), usb);
+
+    manager.switch_connection(&router.id, &remote.id).unwrap();
+    let active = manager.active_connection().unwrap();
+    assert!(active.is_network());
+    assert_eq!(active.port, "router.local");
+
+    // The last used connection survives a reload
+    let reloaded = DeviceManager::new(config_path);
+    reloaded.load().unwrap();
+    assert_eq!(reloaded.active_connection().unwrap(), remote);
+
+    // Unreferenced connections can't be switched to
+    let other = ConnectionProfile::default();
+    manager.save_connection_profile(other.clone()).unwrap();
+    assert!(manager.switch_connection(&router.id, &other.id).is_err());
+}
+
+#[test]
+fn test_connection_falls_back_to_device_settings() {
+    let dir = tempdir().unwrap();
+    let manager = DeviceManager::new(dir.path().join("devices.json"));
+    manager.load().unwrap();
+
+    let usb = ConnectionProfile {
+        port: "COM3".to_string(),
+        ..Default::default()
+    };
+    manager.save_connection_profile(usb.clone()).unwrap();
+    let laser = DeviceProfile {
+        port: "/dev/ttyACM0".to_string(),
+        baud_rate: 250000,
+        connection_ids: vec![usb.id.clone()],
+        last_connection_id: Some(usb.id.clone()),
+        ..Default::default()
+    };
+    manager.save_profile(laser.clone()).unwrap();
+    manager.set_active_profile(&laser.id).unwrap();
+    assert_eq!(manager.active_connection().unwrap().port, "COM3");
+
+    // Deleting the connection profile drops the device's references to it
+    manager.delete_connection_profile(&usb.id).unwrap();
+    let laser = manager.get_profile(&laser.id).unwrap();
+    assert!(laser.connection_ids.is_empty());
+    assert_eq!(laser.last_connection_id, None);
+
+    let active = manager.active_connection().unwrap();
+    assert_eq!(active.port, "/dev/ttyACM0");
+    assert_eq!(active.baud_rate, 250000);
+    assert!(!active.is_network());
+}
+
+#[test]
+fn test_no_reset_on_open_persists_through_device_profile() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("devices.json");
+    let manager = DeviceManager::new(config_path.clone());
+    manager.load().unwrap();
+
+    let usb = ConnectionProfile {
+        port: "/dev/ttyUSB0".to_string(),
+        ..Default::default()
+    };
+    manager.save_connection_profile(usb.clone()).unwrap();
+    let router = DeviceProfile {
+        connection_ids: vec![usb.id.clone()],
+        ..Default::default()
+    };
+    manager.save_profile(router.clone()).unwrap();
+    manager.set_active_profile(&router.id).unwrap();
+
+    manager.set_no_reset_on_open(true).unwrap();
+    assert!(manager.get_active_profile().unwrap().no_reset_on_open());
+    let active = manager.active_connection().unwrap();
+    assert_eq!((active.dtr_on_open, active.rts_on_open), (Some(false), Some(false)));
+
+    // The setting survives a reload
+    let reloaded = DeviceManager::new(config_path);
+    reloaded.load().unwrap();
+    assert!(reloaded.get_active_profile().unwrap().no_reset_on_open());
+
+    manager.set_no_reset_on_open(false).unwrap();
+    let active = manager.active_connection().unwrap();
+    assert_eq!((active.dtr_on_open, active.rts_on_open), (None, None));
+}
@@ -0,0 +1,74 @@
+//! Column (rectangular) selection helpers
+//!
+//! A column selection covers the same character columns on a run of lines,
+//! which acts like one cursor per line. Editing it replaces those columns on
+//! every line at once, e.g. to change `Z-1.0` to `Z-1.5` on a block of moves.
+
+use std::ops::Range;
+
+/// A rectangular selection spanning the same columns on several lines
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColumnSelection {
+    /// 0-based, end-exclusive range of lines
+    pub lines: Range<usize>,
+    /// 0-based, end-exclusive range of character columns, empty for a cursor
+    pub columns: Range<usize>,
+}
+
+impl ColumnSelection {
+    /// Create a column selection
+    ///
+    /// The line and column ranges are normalized so `start <= end`.
+    pub fn new(lines: Range<usize>, columns: Range<usize>) -> Self {
+        Self {
+            lines: lines.start.min(lines.end)..lines.start.max(lines.end),
+            columns: columns.start.min(columns.end)..columns.start.max(columns.end),
+        }
+    }
+
+    /// Check whether the selection is one cursor per line with no width
+    pub fn is_cursor(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    /// The text selected on one line, empty if the line is too short
+    pub fn extract(&self, line: &str) -> String {
+        line.chars()
+            .skip(self.columns.start)
+            .take(self.columns.len())
+            .collect()
+    }
+}
+
+/// Replace the selected columns on each line with `text`
+///
+/// Lines shorter than the selection are padded with spaces up to the first
+/// column before inserting, so a cursor past the end of a short line still
+/// inserts at that column. Nothing is padded when `text` is empty.
+///
+/// # Arguments
+/// * `lines` - Line contents without line terminators
+/// * `columns` - 0-based, end-exclusive character columns to replace
+/// * `text` - Replacement text inserted on every line
+///
+/// # Returns
+/// The edited lines, in the same order
+pub fn replace_columns(lines: &[&str], columns: Range<usize>, text: &str) -> Vec<String> {
+    lines
+        .iter()
+        .map(|line| {
+            let chars: Vec<char> = line.chars().collect();
+            if chars.len() < columns.start && text.is_empty() {
+                return line.to_string();
+            }
+
+            let start = columns.start.min(chars.len());
+            let end = columns.end.max(columns.start).min(chars.len());
+            let mut edited: String = chars[..start].iter().collect();
+            edited.extend(std::iter::repeat_n(' ', columns.start - start));
+            edited.push_str(text);
+            edited.extend(&chars[end..]);
+            edited
+        })
+        .collect()
+}
@@ -0,0 +1,143 @@
+//! Comment toggling and parenthesis matching helpers for G-code lines
+//!
+//! G-code supports two comment forms: `;` to end of line and `( ... )` block
+//! comments. Block comments never span lines and do not nest, so all helpers
+//! here operate on a single line at a time.
+
+/// Comment syntax used when commenting out lines
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CommentStyle {
+    /// Prefix the line with `;`
+    #[default]
+    Semicolon,
+    /// Wrap the line in `(` and `)`
+    Parentheses,
+}
+
+/// Location of a parenthesis pair on a single line
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParenMatch {
+    /// 0-based line index
+    pub line: usize,
+    /// Column of the opening parenthesis
+    pub open_col: usize,
+    /// Column of the closing parenthesis, `None` if the comment is unterminated
+    pub close_col: Option<usize>,
+}
+
+/// Check whether a line is entirely a comment in either style
+pub fn is_commented(line: &str) -> bool {
+    let trimmed = line.trim();
+    if trimmed.starts_with(';') {
+        return true;
+    }
+    trimmed.starts_with('(') && trimmed.find(')') == Some(trimmed.len() - 1)
+}
+
+/// Toggle comments on a block of lines
+///
+/// If every non-blank line is already a comment the block is uncommented,
+/// otherwise every non-blank line is commented using `style`. Blank lines are
+/// returned unchanged so toggling twice restores the original text.
+///
+/// # Arguments
+/// * `lines` - Line contents without line terminators
+/// * `style` - Comment style used when commenting
+///
+/// # Returns
+/// The toggled lines, in the same order
+pub fn toggle_comment_lines(lines: &[&str], style: CommentStyle) -> Vec<String> {
+    let all_commented = lines
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .all(|l| is_commented(l));
+    let any_content = lines.iter().any(|l| !l.trim().is_empty());
+
+    lines
+        .iter()
+        .map(|line| {
+            if line.trim().is_empty() {
+                line.to_string()
+            } else if all_commented && any_content {
+                uncomment_line(line)
+            } else {
+                comment_line(line, style)
+            }
+        })
+        .collect()
+}
+
+/// Comment out a single line
+fn comment_line(line: &str, style: CommentStyle) -> String {
+    match style {
+        CommentStyle::Semicolon => format!(";{}", line),
+        CommentStyle::Parentheses => format!("({})", line),
+    }
+}
+
+/// Remove the comment markers from a fully commented line
+fn uncomment_line(line: &str) -> String {
+    let indent_len = line.len() - line.trim_start().len();
+    let (indent, rest) = line.split_at(indent_len);
+    if let Some(stripped) = rest.strip_prefix(';') {
+        return format!("{}{}", indent, stripped);
+    }
+    let rest = rest.trim_end();
+    match rest.strip_prefix('(').and_then(|r| r.strip_suffix(')')) {
+        Some(inner) => format!("{}{}", indent, inner),
+        None => line.to_string(),
+    }
+}
+
+/// Find the parenthesis pair touching a cursor column
+///
+/// The character at `col` is checked first, then the one before it, so a
+/// cursor placed just after a closing parenthesis still reports its pair.
+///
+/// # Returns
+/// `(open_col, close_col)` or `None` if the cursor is not on a parenthesis
+/// or a closing parenthesis has no opening partner
+pub fn find_paren_pair(line: &str, col: usize) -> Option<(usize, Option<usize>)> {
+    let chars: Vec<char> = line.chars().collect();
+    let candidates = [Some(col), col.checked_sub(1)];
+
+    for idx in candidates.into_iter().flatten() {
+        match chars.get(idx) {
+            Some('(') => {
+                let mut depth = 0usize;
+                for (i, ch) in chars.iter().enumerate().skip(idx) {
+                    match ch {
+                        '(' => depth += 1,
+                        ')' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                return Some((idx, Some(i)));
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                return Some((idx, None));
+            }
+            Some(')') => {
+                let mut depth = 0usize;
+                for (i, ch) in chars[..=idx].iter().enumerate().rev() {
+                    match ch {
+                        ')' => depth += 1,
+                        '(' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                return Some((i, Some(idx)));
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                return None;
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
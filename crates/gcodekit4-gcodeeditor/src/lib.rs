@@ -58,16 +58,22 @@
 //! let (start_line, lines) = editor.get_visible_lines();
 //! ```
 
+mod column;
+mod comment;
 mod slint_bridge;
 mod text_buffer;
 mod undo_manager;
 mod viewport;
 
+pub use column::ColumnSelection;
+pub use comment::{CommentStyle, ParenMatch};
 pub use slint_bridge::{EditorBridge, SlintTextLine};
 pub use text_buffer::TextBuffer;
 pub use undo_manager::{TextChange, UndoManager};
 pub use viewport::Viewport;
 
+use std::ops::Range;
+
 // Re-export for Slint UI
 #[derive(Clone, Debug)]
 pub struct TextLine {
@@ -83,6 +89,7 @@ pub struct EditorState {
     viewport: Viewport,
     cursor_pos: usize,
     selection: Option<(usize, usize)>,
+    column_selection: Option<ColumnSelection>,
     modified: bool,
 }
 
@@ -99,6 +106,7 @@ impl EditorState {
             viewport,
             cursor_pos: 0,
             selection: None,
+            column_selection: None,
             modified: false,
         }
     }
@@ -109,6 +117,7 @@ impl EditorState {
         self.viewport.set_total_lines(self.buffer.len_lines());
         self.cursor_pos = 0;
         self.selection = None;
+        self.column_selection = None;
         self.undo_manager.clear();
         self.modified = false;
     }
@@ -234,6 +243,151 @@ impl EditorState {
         }
     }
 
+    /// Toggle `;` comments over a range of lines as a single undo step
+    ///
+    /// If every non-blank line in the range is already a comment (either style),
+    /// the comment markers are removed instead.
+    ///
+    /// # Arguments
+    /// * `line_range` - 0-based, end-exclusive range of lines to toggle
+    ///
+    /// # Returns
+    /// `true` if the buffer was changed
+    pub fn toggle_comment(&mut self, line_range: Range<usize>) -> bool {
+        self.toggle_comment_with_style(line_range, CommentStyle::Semicolon)
+    }
+
+    /// Toggle comments over a range of lines using a specific comment style
+    pub fn toggle_comment_with_style(
+        &mut self,
+        line_range: Range<usize>,
+        style: CommentStyle,
+    ) -> bool {
+        self.replace_line_block(line_range, |contents| {
+            comment::toggle_comment_lines(contents, style)
+        })
+    }
+
+    /// Select the same columns on a range of lines for column editing
+    pub fn set_column_selection(&mut self, selection: ColumnSelection) {
+        self.column_selection = Some(selection);
+    }
+
+    /// Get the active column selection
+    pub fn column_selection(&self) -> Option<&ColumnSelection> {
+        self.column_selection.as_ref()
+    }
+
+    /// Drop the column selection
+    pub fn clear_column_selection(&mut self) {
+        self.column_selection = None;
+    }
+
+    /// Get the text selected on each line of the column selection
+    pub fn column_selected_text(&self) -> Vec<String> {
+        let Some(selection) = &self.column_selection else {
+            return Vec::new();
+        };
+        selection
+            .lines
+            .clone()
+            .filter_map(|line| self.buffer.line(line))
+            .map(|line| selection.extract(line.trim_end_matches(['\r', '\n'])))
+            .collect()
+    }
+
+    /// Replace the column selection on every line as a single undo step
+    ///
+    /// With an empty column range this inserts `text` at the same column on
+    /// each line; with an empty `text` it deletes the selected columns.
+    /// Afterwards the selection collapses to a cursor after the new text so
+    /// typing can continue on all lines.
+    ///
+    /// # Returns
+    /// `true` if the buffer was changed
+    pub fn replace_column_selection(&mut self, text: &str) -> bool {
+        let Some(selection) = self.column_selection.clone() else {
+            return false;
+        };
+
+        let changed = self.replace_line_block(selection.lines.clone(), |contents| {
+            column::replace_columns(contents, selection.columns.clone(), text)
+        });
+
+        let column = selection.columns.start + text.chars().count();
+        self.column_selection = Some(ColumnSelection::new(selection.lines, column..column));
+        changed
+    }
+
+    /// Rewrite a block of whole lines as a single undo step
+    ///
+    /// `edit` receives the line contents without terminators and returns the
+    /// replacement lines; `\r\n` endings are preserved.
+    fn replace_line_block<F>(&mut self, line_range: Range<usize>, edit: F) -> bool
+    where
+        F: FnOnce(&[&str]) -> Vec<String>,
+    {
+        let total_lines = self.buffer.len_lines();
+        let first = line_range.start.min(total_lines);
+        let last = line_range.end.min(total_lines);
+        if first >= last {
+            return false;
+        }
+
+        let start = self.buffer.line_col_to_char(first, 0);
+        let last_line = self.buffer.line(last - 1).unwrap_or_default();
+        let last_len = last_line.trim_end_matches(['\r', '\n']).chars().count();
+        let end = self.buffer.line_col_to_char(last - 1, 0) + last_len;
+
+        let old_text = self.buffer.slice(start, end);
+        let pieces: Vec<&str> = old_text.split('\n').collect();
+        let contents: Vec<&str> = pieces
+            .iter()
+            .map(|p| p.strip_suffix('\r').unwrap_or(p))
+            .collect();
+        let edited = edit(&contents);
+        let new_text = pieces
+            .iter()
+            .zip(edited)
+            .map(|(piece, line)| {
+                if piece.ends_with('\r') {
+                    format!("{}\r", line)
+                } else {
+                    line
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if new_text == old_text {
+            return false;
+        }
+
+        let old_cursor = self.cursor_pos;
+        self.buffer.replace(start..end, &new_text);
+        let new_cursor = self.cursor_pos.min(self.buffer.len_chars());
+
+        let change = TextChange::new(start..end, old_text, new_text, old_cursor, new_cursor);
+        self.undo_manager.record(change);
+        self.cursor_pos = new_cursor;
+        self.viewport.set_total_lines(self.buffer.len_lines());
+        self.modified = true;
+        true
+    }
+
+    /// Get the parenthesis pair under or just before the cursor
+    ///
+    /// Used to highlight matching `( ... )` block comments.
+    pub fn paren_match(&self) -> Option<ParenMatch> {
+        let (line, col) = self.cursor_line_col();
+        let content = self.buffer.line(line)?;
+        comment::find_paren_pair(&content, col).map(|(open_col, close_col)| ParenMatch {
+            line,
+            open_col,
+            close_col,
+        })
+    }
+
     /// Check if undo is available
     pub fn can_undo(&self) -> bool {
         self.undo_manager.can_undo()
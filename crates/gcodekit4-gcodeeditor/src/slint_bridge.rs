@@ -1,8 +1,9 @@
 //! Bridge between Slint UI and EditorState backend
 
-use super::EditorState;
+use super::{ColumnSelection, EditorState, ParenMatch};
 use slint::{Model, ModelRc, VecModel};
 use std::cell::RefCell;
+use std::ops::Range;
 use std::rc::Rc;
 
 /// Slint-compatible text line structure
@@ -109,6 +110,55 @@ impl EditorBridge {
         result
     }
 
+    /// Toggle comments on a range of lines (0-based, end-exclusive) as one undo step
+    pub fn toggle_comment(&self, line_range: Range<usize>) -> bool {
+        let mut editor = self.editor.borrow_mut();
+        let result = editor.toggle_comment(line_range);
+        drop(editor);
+        if result {
+            self.update_visible_lines();
+        }
+        result
+    }
+
+    /// Select the same columns (0-based, end-exclusive) on a range of lines
+    pub fn set_column_selection(&self, lines: Range<usize>, columns: Range<usize>) {
+        self.editor
+            .borrow_mut()
+            .set_column_selection(ColumnSelection::new(lines, columns));
+    }
+
+    /// Get the active column selection
+    pub fn column_selection(&self) -> Option<ColumnSelection> {
+        self.editor.borrow().column_selection().cloned()
+    }
+
+    /// Drop the column selection
+    pub fn clear_column_selection(&self) {
+        self.editor.borrow_mut().clear_column_selection();
+    }
+
+    /// Get the text selected on each line of the column selection
+    pub fn column_selected_text(&self) -> Vec<String> {
+        self.editor.borrow().column_selected_text()
+    }
+
+    /// Replace the column selection on every line as one undo step
+    pub fn replace_column_selection(&self, text: &str) -> bool {
+        let mut editor = self.editor.borrow_mut();
+        let result = editor.replace_column_selection(text);
+        drop(editor);
+        if result {
+            self.update_visible_lines();
+        }
+        result
+    }
+
+    /// Get the parenthesis pair at the cursor for match highlighting
+    pub fn paren_match(&self) -> Option<ParenMatch> {
+        self.editor.borrow().paren_match()
+    }
+
     /// Check if undo is available
     pub fn can_undo(&self) -> bool {
         self.editor.borrow().can_undo()
@@ -0,0 +1,78 @@
+use gcodekit4_gcodeeditor::{ColumnSelection, EditorBridge, EditorState};
+
+#[test]
+fn test_column_insert_is_single_undo_step() {
+    let bridge = EditorBridge::new(400.0, 20.0);
+    let original = "G1 X1 Z-1\nG1 X2 Z-1\nG1 X3 Z-1\nM5";
+    bridge.load_text(original);
+
+    bridge.set_column_selection(0..3, 9..9);
+    assert!(bridge.replace_column_selection(".5"));
+    assert_eq!(
+        bridge.get_text(),
+        "G1 X1 Z-1.5\nG1 X2 Z-1.5\nG1 X3 Z-1.5\nM5"
+    );
+
+    assert!(bridge.undo());
+    assert_eq!(bridge.get_text(), original);
+    assert!(!bridge.can_undo());
+
+    assert!(bridge.redo());
+    assert_eq!(
+        bridge.get_text(),
+        "G1 X1 Z-1.5\nG1 X2 Z-1.5\nG1 X3 Z-1.5\nM5"
+    );
+}
+
+#[test]
+fn test_column_replace_and_continue_typing() {
+    let bridge = EditorBridge::new(400.0, 20.0);
+    bridge.load_text("G1 X10 Z-2\nG1 X20 Z-2\nG1 X30 Z-2");
+
+    bridge.set_column_selection(0..3, 8..10);
+    assert_eq!(bridge.column_selected_text(), vec!["-2", "-2", "-2"]);
+
+    assert!(bridge.replace_column_selection("-3"));
+    assert_eq!(
+        bridge.column_selection(),
+        Some(ColumnSelection::new(0..3, 10..10))
+    );
+    assert!(bridge.replace_column_selection(".5"));
+    assert_eq!(
+        bridge.get_text(),
+        "G1 X10 Z-3.5\nG1 X20 Z-3.5\nG1 X30 Z-3.5"
+    );
+}
+
+#[test]
+fn test_column_edit_pads_short_lines_and_keeps_crlf() {
+    let mut editor = EditorState::new(400.0, 20.0);
+    editor.load_text("G0 X1\r\nG0\r\nG0 X2\r\n");
+
+    editor.set_column_selection(ColumnSelection::new(0..3, 6..6));
+    assert!(editor.replace_column_selection("F100"));
+    assert_eq!(
+        editor.get_text(),
+        "G0 X1 F100\r\nG0    F100\r\nG0 X2 F100\r\n"
+    );
+}
+
+#[test]
+fn test_column_delete_removes_selected_columns() {
+    let mut editor = EditorState::new(400.0, 20.0);
+    editor.load_text("N10 G0 X1\nN20 G1 X2");
+
+    editor.set_column_selection(ColumnSelection::new(0..2, 0..4));
+    assert!(editor.replace_column_selection(""));
+    assert_eq!(editor.get_text(), "G0 X1\nG1 X2");
+    assert!(!editor.replace_column_selection(""));
+}
+
+#[test]
+fn test_column_edit_without_selection_does_nothing() {
+    let mut editor = EditorState::new(400.0, 20.0);
+    editor.load_text("G0 X1");
+
+    assert!(!editor.replace_column_selection("Y"));
+    assert!(!editor.can_undo());
+}
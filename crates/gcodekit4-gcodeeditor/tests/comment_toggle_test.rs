@@ -0,0 +1,91 @@
+use gcodekit4_gcodeeditor::{EditorBridge, EditorState, ParenMatch};
+
+#[test]
+fn test_toggle_comment_selection_roundtrip() {
+    let bridge = EditorBridge::new(400.0, 20.0);
+    let original = "G21\nG0 X10 Y10\n\nG1 X20 F500\nM5";
+    bridge.load_text(original);
+
+    assert!(bridge.toggle_comment(1..4));
+    assert_eq!(bridge.get_text(), "G21\n;G0 X10 Y10\n\n;G1 X20 F500\nM5");
+
+    assert!(bridge.toggle_comment(1..4));
+    assert_eq!(bridge.get_text(), original);
+}
+
+#[test]
+fn test_toggle_comment_is_single_undo_step() {
+    let bridge = EditorBridge::new(400.0, 20.0);
+    let original = "G0 X1\nG1 X2\nG1 X3";
+    bridge.load_text(original);
+
+    assert!(bridge.toggle_comment(0..3));
+    assert!(bridge.undo());
+    assert_eq!(bridge.get_text(), original);
+    assert!(!bridge.can_undo());
+
+    assert!(bridge.redo());
+    assert_eq!(bridge.get_text(), ";G0 X1\n;G1 X2\n;G1 X3");
+}
+
+#[test]
+fn test_toggle_comment_removes_paren_comments() {
+    let mut editor = EditorState::new(400.0, 20.0);
+    editor.load_text("(G0 X1)\n;G1 X2");
+
+    assert!(editor.toggle_comment(0..2));
+    assert_eq!(editor.get_text(), "G0 X1\nG1 X2");
+}
+
+#[test]
+fn test_toggle_comment_mixed_lines_comments_all() {
+    let mut editor = EditorState::new(400.0, 20.0);
+    editor.load_text(";G0 X1\nG1 X2");
+
+    assert!(editor.toggle_comment(0..2));
+    assert_eq!(editor.get_text(), ";;G0 X1\n;G1 X2");
+
+    assert!(editor.toggle_comment(0..2));
+    assert_eq!(editor.get_text(), ";G0 X1\nG1 X2");
+}
+
+#[test]
+fn test_paren_match_at_cursor() {
+    let bridge = EditorBridge::new(400.0, 20.0);
+    bridge.load_text("G0 X1 (rapid) Y2");
+
+    bridge.set_cursor(0, 6);
+    assert_eq!(
+        bridge.paren_match(),
+        Some(ParenMatch {
+            line: 0,
+            open_col: 6,
+            close_col: Some(12),
+        })
+    );
+
+    // Cursor just after the closing parenthesis
+    bridge.set_cursor(0, 13);
+    assert_eq!(
+        bridge.paren_match(),
+        Some(ParenMatch {
+            line: 0,
+            open_col: 6,
+            close_col: Some(12),
+        })
+    );
+
+    bridge.set_cursor(0, 2);
+    assert_eq!(bridge.paren_match(), None);
+}
+
+#[test]
+fn test_paren_match_unterminated() {
+    let mut editor = EditorState::new(400.0, 20.0);
+    editor.load_text("G0 X1 (open");
+    editor.set_cursor(6);
+
+    let found = editor.paren_match().unwrap();
+    assert_eq!(found.open_col, 6);
+    assert_eq!(found.close_col, None);
+}
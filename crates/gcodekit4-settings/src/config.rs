@@ -10,7 +10,7 @@
 //! - Machine preferences (limits, jog settings)
 //! - Firmware-specific settings
 
-use gcodekit4_core::{Error, Result};
+use gcodekit4_core::{Error, Result, VisualizerTheme};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -86,6 +86,9 @@ pub struct UiSettings {
     pub show_menu_shortcuts: bool,
     /// Measurement system (Metric or Imperial)
     pub measurement_system: String,
+    /// Visualizer colors and stroke widths
+    #[serde(default)]
+    pub visualizer_theme: VisualizerTheme,
 }
 
 impl Default for UiSettings {
@@ -105,6 +108,7 @@ impl Default for UiSettings {
             language: "en".to_string(),
             show_menu_shortcuts: true,
             measurement_system: "Metric".to_string(),
+            visualizer_theme: VisualizerTheme::default(),
         }
     }
 }
@@ -145,6 +149,143 @@ impl Default for FileProcessingSettings {
     }
 }
 
+/// A jog step preset
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JogStep {
+    /// Move a fixed distance in mm per jog
+    Distance(f64),
+    /// Keep moving until the jog is cancelled
+    Continuous,
+}
+
+impl std::fmt::Display for JogStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Distance(distance) => write!(f, "{}", distance),
+            Self::Continuous => write!(f, "Cont."),
+        }
+    }
+}
+
+/// Jog step presets and the currently selected preset
+///
+/// Shared by every jog trigger (buttons and keyboard) so they always move by
+/// the same step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JogPresets {
+    /// Available presets, in display order
+    pub steps: Vec<JogStep>,
+    /// Index of the selected preset in `steps`
+    pub selected: usize,
+    /// Distance in mm requested by a continuous jog before it is cancelled
+    pub continuous_distance: f64,
+}
+
+impl Default for JogPresets {
+    fn default() -> Self {
+        Self {
+            steps: vec![
+                JogStep::Distance(0.01),
+                JogStep::Distance(0.1),
+                JogStep::Distance(1.0),
+                JogStep::Distance(10.0),
+                JogStep::Continuous,
+            ],
+            selected: 2,
+            continuous_distance: 1000.0,
+        }
+    }
+}
+
+impl JogPresets {
+    /// Get the selected preset, falling back to the first one
+    pub fn current(&self) -> JogStep {
+        self.steps
+            .get(self.selected)
+            .or_else(|| self.steps.first())
+            .copied()
+            .unwrap_or(JogStep::Distance(1.0))
+    }
+
+    /// Select a preset by index
+    ///
+    /// Returns false and keeps the current selection if the index is out of range.
+    pub fn select(&mut self, index: usize) -> bool {
+        if index < self.steps.len() {
+            self.selected = index;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Select the preset whose step is closest to `distance` mm
+    ///
+    /// The presets themselves are left alone, so a profile's default step
+    /// never adds entries to the saved list. A step with no matching preset is
+    /// snapped to the closest one and a warning is logged. Ties go to the
+    /// smaller step, and the selection is kept if there are no distance
+    /// presets. Returns the index of the selected preset.
+    pub fn select_distance(&mut self, distance: f64) -> usize {
+        let closest = self
+            .steps
+            .iter()
+            .enumerate()
+            .filter_map(|(index, step)| match step {
+                JogStep::Distance(d) => Some((index, *d, (d - distance).abs())),
+                JogStep::Continuous => None,
+            })
+            .min_by(|(_, _, a), (_, _, b)| a.total_cmp(b));
+        match closest {
+            Some((index, step, _)) => {
+                if step != distance {
+                    tracing::warn!(
+                        "No jog preset for a {} mm step, using the closest preset of {} mm",
+                        distance,
+                        step
+                    );
+                }
+                self.selected = index;
+            }
+            None => tracing::warn!(
+                "No distance jog presets, keeping the current preset instead of a {} mm step",
+                distance
+            ),
+        }
+        self.selected
+    }
+
+    /// Whether the selected preset is continuous jogging
+    pub fn is_continuous(&self) -> bool {
+        self.current() == JogStep::Continuous
+    }
+
+    /// Display labels for the presets
+    pub fn labels(&self) -> Vec<String> {
+        self.steps.iter().map(|step| step.to_string()).collect()
+    }
+
+    /// Distance in mm moved by a jog with the selected preset
+    pub fn distance(&self) -> f64 {
+        match self.current() {
+            JogStep::Distance(distance) => distance,
+            JogStep::Continuous => self.continuous_distance,
+        }
+    }
+
+    /// Build the GRBL jog command for one axis using the selected preset
+    ///
+    /// # Arguments
+    /// * `axis` - Axis letter, e.g. 'X'
+    /// * `positive` - Jog direction
+    /// * `feed_rate` - Jog feed rate in units/min
+    pub fn jog_command(&self, axis: char, positive: bool, feed_rate: f64) -> String {
+        let sign = if positive { "" } else { "-" };
+        format!("$J=G91 {}{}{} F{}", axis, sign, self.distance(), feed_rate)
+    }
+}
+
 /// Machine preference settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MachineSettings {
@@ -152,6 +293,9 @@ pub struct MachineSettings {
     pub jog_increment: f64,
     /// Default jog feed rate in units/min
     pub jog_feed_rate: f64,
+    /// Jog step presets and the current selection
+    #[serde(default)]
+    pub jog_presets: JogPresets,
     /// Machine X limit (max)
     pub x_limit: f64,
     /// Machine Y limit (max)
@@ -174,6 +318,7 @@ impl Default for MachineSettings {
         Self {
             jog_increment: 1.0,
             jog_feed_rate: 1000.0,
+            jog_presets: JogPresets::default(),
             x_limit: 200.0,
             y_limit: 200.0,
             z_limit: 100.0,
@@ -286,6 +431,8 @@ impl Config {
             return Err(Error::other("Font size must be > 0".to_string()));
         }
 
+        self.ui.visualizer_theme.validate().map_err(Error::other)?;
+
         // Validate file processing
         if self.file_processing.arc_segment_length <= 0.0 {
             return Err(Error::other("Arc segment length must be > 0".to_string()));
@@ -300,6 +447,16 @@ impl Config {
             return Err(Error::other("Jog feed rate must be > 0".to_string()));
         }
 
+        if self
+            .machine
+            .jog_presets
+            .steps
+            .iter()
+            .any(|step| matches!(step, JogStep::Distance(distance) if *distance <= 0.0))
+        {
+            return Err(Error::other("Jog step presets must be > 0".to_string()));
+        }
+
         if self.machine.x_limit <= 0.0 || self.machine.y_limit <= 0.0 || self.machine.z_limit <= 0.0
         {
             return Err(Error::other("Machine limits must be > 0".to_string()));
@@ -0,0 +1,89 @@
+use gcodekit4_settings::{Config, JogPresets, JogStep};
+
+#[test]
+fn test_jog_presets_defaults() {
+    let presets = JogPresets::default();
+
+    assert_eq!(presets.labels(), vec!["0.01", "0.1", "1", "10", "Cont."]);
+    assert_eq!(presets.current(), JogStep::Distance(1.0));
+    assert!(!presets.is_continuous());
+}
+
+#[test]
+fn test_selecting_preset_updates_jog_command() {
+    let mut presets = JogPresets::default();
+    assert_eq!(presets.jog_command('X', true, 2000.0), "$J=G91 X1 F2000");
+
+    assert!(presets.select(0));
+    assert_eq!(presets.jog_command('X', true, 2000.0), "$J=G91 X0.01 F2000");
+    assert_eq!(presets.jog_command('Z', false, 500.0), "$J=G91 Z-0.01 F500");
+
+    assert!(presets.select(4));
+    assert!(presets.is_continuous());
+    assert_eq!(
+        presets.jog_command('Y', false, 2000.0),
+        "$J=G91 Y-1000 F2000"
+    );
+
+    // Out of range selections keep the current preset
+    assert!(!presets.select(9));
+    assert_eq!(presets.selected, 4);
+}
+
+#[test]
+fn test_jog_presets_select_distance() {
+    let mut presets = JogPresets::default();
+    assert_eq!(presets.select_distance(10.0), 3);
+    assert_eq!(presets.jog_command('X', true, 2000.0), "$J=G91 X10 F2000");
+
+    // A step without a preset picks the closest one and adds nothing
+    assert_eq!(presets.select_distance(4.0), 2);
+    assert_eq!(presets.select_distance(250.0), 3);
+    assert_eq!(presets.select_distance(0.05), 0);
+    assert_eq!(
+        presets,
+        JogPresets {
+            selected: 0,
+            ..Default::default()
+        }
+    );
+
+    let mut continuous_only = JogPresets {
+        steps: vec![JogStep::Continuous],
+        selected: 0,
+        ..Default::default()
+    };
+    assert_eq!(continuous_only.select_distance(1.0), 0);
+}
+
+#[test]
+fn test_jog_presets_persist_in_config() {
+    let mut config = Config::default();
+    config.machine.jog_presets.select(1);
+
+    let json = serde_json::to_string(&config).unwrap();
+    let loaded: Config = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.machine.jog_presets.current(), JogStep::Distance(0.1));
+
+    // Configs saved before presets existed load with the defaults
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value["machine"]
+        .as_object_mut()
+        .unwrap()
+        .remove("jog_presets");
+    let loaded: Config = serde_json::from_value(value).unwrap();
+    assert_eq!(loaded.machine.jog_presets, JogPresets::default());
+}
+
+#[test]
+fn test_jog_presets_validation() {
+    let mut config = Config::default();
+    assert!(config.validate().is_ok());
+
+    config
+        .machine
+        .jog_presets
+        .steps
+        .push(JogStep::Distance(0.0));
+    assert!(config.validate().is_err());
+}
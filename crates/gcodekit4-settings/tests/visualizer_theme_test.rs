@@ -0,0 +1,32 @@
+use gcodekit4_core::VisualizerTheme;
+use gcodekit4_settings::Config;
+
+#[test]
+fn test_visualizer_theme_persists_in_config() {
+    let mut config = Config::default();
+    config.ui.visualizer_theme = VisualizerTheme::high_contrast();
+    config.ui.visualizer_theme.g1_color = "#123456".to_string();
+
+    let json = serde_json::to_string(&config).unwrap();
+    let loaded: Config = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.ui.visualizer_theme.g1_color, "#123456");
+    assert_eq!(loaded.ui.visualizer_theme.background_color, "#000000");
+
+    // Configs saved before themes existed load with the default theme
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value["ui"]
+        .as_object_mut()
+        .unwrap()
+        .remove("visualizer_theme");
+    let loaded: Config = serde_json::from_value(value).unwrap();
+    assert_eq!(loaded.ui.visualizer_theme, VisualizerTheme::default());
+}
+
+#[test]
+fn test_visualizer_theme_validation() {
+    let mut config = Config::default();
+    assert!(config.validate().is_ok());
+
+    config.ui.visualizer_theme.rapid_color = "cyan".to_string();
+    assert!(config.validate().is_err());
+}
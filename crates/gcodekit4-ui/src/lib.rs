@@ -14,7 +14,7 @@ pub use ui::{
 
 pub use gcodekit4_settings::{
     Config, ConnectionSettings, ConnectionType, FileProcessingSettings, FirmwareSettings,
-    MachineSettings, SettingsManager, UiSettings,
+    JogPresets, JogStep, MachineSettings, SettingsManager, UiSettings,
 };
 
 pub use gcodekit4_gcodeeditor::{
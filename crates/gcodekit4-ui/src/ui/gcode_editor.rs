@@ -494,9 +494,9 @@ impl GcodeEditor {
         Ok(file)
     }
 
-    /// Load file content from disk
+    /// Load file content from disk, transcoding Latin-1/Windows-1252 files to UTF-8
     pub fn load_file(&self, path: &Path) -> anyhow::Result<()> {
-        let content = std::fs::read_to_string(path)?;
+        let content = gcodekit4_visualizer::GcodeFileReader::new(path)?.read_all()?;
         self.load_content(&content)?;
 
         let mut file = self.file.lock().unwrap();
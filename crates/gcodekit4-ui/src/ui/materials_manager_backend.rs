@@ -50,6 +50,12 @@ impl MaterialsManagerBackend {
 
     fn save_to_file(&self) -> Result<(), Box<dyn std::error::Error>> {
         // Only save custom materials
+        std::fs::write(&self.storage_path, self.export_json()?)?;
+        Ok(())
+    }
+
+    /// Export custom materials, including their feed/speed presets, as JSON
+    pub fn export_json(&self) -> Result<String, Box<dyn std::error::Error>> {
         let custom_materials: Vec<&Material> = self
             .library
             .get_all_materials()
@@ -57,9 +63,24 @@ impl MaterialsManagerBackend {
             .filter(|m| m.custom)
             .collect();
 
-        let json = serde_json::to_string_pretty(&custom_materials)?;
-        std::fs::write(&self.storage_path, json)?;
-        Ok(())
+        Ok(serde_json::to_string_pretty(&custom_materials)?)
+    }
+
+    /// Import materials from JSON produced by `export_json`
+    ///
+    /// Imported materials are marked as custom, replace materials with the
+    /// same ID and are saved to disk. Returns the number of materials imported.
+    pub fn import_json(&mut self, json: &str) -> Result<usize, Box<dyn std::error::Error>> {
+        let materials: Vec<Material> = serde_json::from_str(json)?;
+        let count = materials.len();
+
+        for mut material in materials {
+            material.custom = true;
+            self.library.add_material(material);
+        }
+
+        self.save_to_file()?;
+        Ok(count)
     }
 
     pub fn get_library(&self) -> &MaterialLibrary {
@@ -0,0 +1,51 @@
+use gcodekit4_core::data::materials::{FeedSpeedPreset, Material, MaterialCategory, MaterialId};
+use gcodekit4_ui::ui::MaterialsManagerBackend;
+
+#[test]
+fn test_export_import_round_trip_with_presets() {
+    let mut material = Material::new(
+        MaterialId("test_presets".to_string()),
+        "Test Preset Material".to_string(),
+        MaterialCategory::Plastic,
+        "Acrylic".to_string(),
+    );
+    material.custom = true;
+    material.set_feed_speed_preset(
+        "endmill_flat".to_string(),
+        FeedSpeedPreset {
+            rpm: 16000,
+            feed_rate: 1200.0,
+        },
+    );
+
+    let mut source = MaterialsManagerBackend::new();
+    source.add_material(material);
+    let json = source.export_json().unwrap();
+    source.remove_material(&MaterialId("test_presets".to_string()));
+    assert!(source
+        .get_material(&MaterialId("test_presets".to_string()))
+        .is_none());
+
+    let mut backend = MaterialsManagerBackend::new();
+    assert!(backend.import_json(&json).unwrap() >= 1);
+
+    let imported = backend
+        .get_material(&MaterialId("test_presets".to_string()))
+        .unwrap();
+    assert!(imported.custom);
+    assert_eq!(
+        imported.get_feed_speed_preset("endmill_flat"),
+        Some(&FeedSpeedPreset {
+            rpm: 16000,
+            feed_rate: 1200.0,
+        })
+    );
+
+    backend.remove_material(&MaterialId("test_presets".to_string()));
+}
+
+#[test]
+fn test_import_rejects_invalid_json() {
+    let mut backend = MaterialsManagerBackend::new();
+    assert!(backend.import_json("not json").is_err());
+}
@@ -3,3 +3,4 @@ pub mod file_management_test;
 pub mod state_test;
 pub mod console_listener;
 pub mod console_output_debug;
+pub mod materials_manager_backend_test;
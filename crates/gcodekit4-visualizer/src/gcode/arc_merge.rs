@@ -0,0 +1,293 @@
+//! Merging runs of short arcs into single arc commands
+//!
+//! Converting polylines to arcs often leaves one curve split into many short
+//! `G2`/`G3` moves about the same center. [`merge_arcs`] joins consecutive
+//! arcs that turn the same way about a common center, with matching radii,
+//! into one command covering up to a full circle.
+//!
+//! Only plain XY-plane (`G17`) arcs in absolute mode (`G90`) with incremental
+//! `I J` centers are merged. Lines carrying anything else, including `R`
+//! arcs, `Z` words, line numbers and comments, end the current run and are
+//! copied unchanged.
+
+use std::f64::consts::TAU;
+
+use super::{strip_comments, tokenize_words, word_spans, TrailingZeroProcessor};
+
+/// Decimal places written for merged coordinates
+const PRECISION: usize = 4;
+
+/// Modal state needed to locate arcs
+#[derive(Debug, Clone, Copy)]
+struct ArcState {
+    /// Current X and Y, unknown until first set in absolute mode
+    position: [Option<f64>; 2],
+    /// Active motion mode (0-3)
+    motion: Option<u8>,
+    xy_plane: bool,
+    /// Whether `G91` is active
+    incremental: bool,
+    /// Whether `G90.1` (absolute arc centers) is active
+    absolute_centers: bool,
+    feed: Option<f64>,
+}
+
+impl Default for ArcState {
+    fn default() -> Self {
+        Self {
+            position: [None; 2],
+            motion: None,
+            xy_plane: true,
+            incremental: false,
+            absolute_centers: false,
+            feed: None,
+        }
+    }
+}
+
+impl ArcState {
+    fn update(&mut self, words: &[(char, f64)]) {
+        let mut moves = true;
+        for &(letter, value) in words {
+            match (letter, (value * 10.0).round() as i64) {
+                ('G', code @ (0 | 10 | 20 | 30)) => self.motion = Some((code / 10) as u8),
+                ('G', 170) => self.xy_plane = true,
+                ('G', 180 | 190) => self.xy_plane = false,
+                ('G', 900) => self.incremental = false,
+                ('G', 910) => self.incremental = true,
+                ('G', 901) => self.absolute_centers = true,
+                ('G', 911) => self.absolute_centers = false,
+                // Machine coordinates, reference returns and offset changes
+                // leave the program position unknown
+                ('G', 100 | 280 | 300 | 530 | 920..=923) => {
+                    self.position = [None; 2];
+                    moves = false;
+                }
+                ('F', _) => self.feed = Some(value),
+                _ => {}
+            }
+        }
+        if !moves {
+            return;
+        }
+
+        for &(letter, value) in words {
+            let axis = match letter {
+                'X' => 0,
+                'Y' => 1,
+                _ => continue,
+            };
+            self.position[axis] = if self.incremental {
+                self.position[axis].map(|p| p + value)
+            } else {
+                Some(value)
+            };
+        }
+    }
+
+    /// The arc moved by `line`, given the state after it and its start point
+    fn arc(
+        &self,
+        line: &str,
+        words: &[(char, f64)],
+        start: [Option<f64>; 2],
+        tolerance: f64,
+    ) -> Option<ArcMove> {
+        let plain = strip_comments(line).len() == line.len()
+            && words.iter().all(|&(letter, value)| match letter {
+                'G' => value == 2.0 || value == 3.0,
+                'X' | 'Y' | 'I' | 'J' | 'F' => true,
+                _ => false,
+            });
+        let clockwise = match self.motion {
+            Some(2) => true,
+            Some(3) => false,
+            _ => return None,
+        };
+        if !plain || !self.xy_plane || self.incremental || self.absolute_centers {
+            return None;
+        }
+
+        let word = |letter: char| words.iter().find(|(l, _)| *l == letter).map(|&(_, v)| v);
+        let (i, j) = (word('I'), word('J'));
+        if i.is_none() && j.is_none() {
+            return None;
+        }
+        let start = (start[0]?, start[1]?);
+        let end = (self.position[0]?, self.position[1]?);
+        let center = (start.0 + i.unwrap_or(0.0), start.1 + j.unwrap_or(0.0));
+
+        let radius = distance(start, center);
+        if radius <= tolerance || (distance(end, center) - radius).abs() > tolerance {
+            return None;
+        }
+
+        let angle = |p: (f64, f64)| (p.1 - center.1).atan2(p.0 - center.0);
+        let sweep = if distance(start, end) <= tolerance {
+            TAU
+        } else if clockwise {
+            (angle(start) - angle(end)).rem_euclid(TAU)
+        } else {
+            (angle(end) - angle(start)).rem_euclid(TAU)
+        };
+
+        Some(ArcMove {
+            start,
+            end,
+            center,
+            radius,
+            clockwise,
+            sweep,
+            feed: word('F'),
+        })
+    }
+}
+
+/// One arc command in absolute XY coordinates
+#[derive(Debug, Clone, Copy)]
+struct ArcMove {
+    start: (f64, f64),
+    end: (f64, f64),
+    center: (f64, f64),
+    radius: f64,
+    clockwise: bool,
+    /// Angle swept in radians, in (0, 2π]
+    sweep: f64,
+    /// Feed word on the line, if any
+    feed: Option<f64>,
+}
+
+/// Consecutive arcs being merged
+struct Run<'a> {
+    lines: Vec<&'a str>,
+    first: ArcMove,
+    end: (f64, f64),
+    sweep: f64,
+    /// Modal feed rate in effect for the run
+    feed: Option<f64>,
+}
+
+impl<'a> Run<'a> {
+    /// Add an arc that continues the run, returning false if it doesn't
+    fn extend(&mut self, line: &'a str, arc: &ArcMove, tolerance: f64) -> bool {
+        let continues = arc.clockwise == self.first.clockwise
+            && distance(arc.center, self.first.center) <= tolerance
+            && (arc.radius - self.first.radius).abs() <= tolerance
+            && arc.feed.is_none_or(|feed| Some(feed) == self.feed)
+            && self.sweep + arc.sweep <= TAU + tolerance / self.first.radius;
+        if continues {
+            self.lines.push(line);
+            self.end = arc.end;
+            self.sweep += arc.sweep;
+        }
+        continues
+    }
+
+    /// The merged arc command, or the original line if nothing was merged
+    fn finish(self) -> String {
+        let first = self.lines[0];
+        if self.lines.len() == 1 {
+            return first.to_string();
+        }
+
+        let separator = if first.trim().contains(char::is_whitespace) {
+            " "
+        } else {
+            ""
+        };
+        let geometry = [
+            ('X', self.end.0),
+            ('Y', self.end.1),
+            ('I', self.first.center.0 - self.first.start.0),
+            ('J', self.first.center.1 - self.first.start.1),
+        ]
+        .map(|(letter, value)| format!("{}{}", letter, format_value(value)));
+
+        // Keep the first line's other words (G, F) where they were
+        let mut words = Vec::new();
+        let mut placed = false;
+        for (letter, span) in word_spans(first) {
+            if matches!(letter, 'X' | 'Y' | 'I' | 'J') {
+                if !placed {
+                    words.extend(geometry.iter().cloned());
+                    placed = true;
+                }
+                continue;
+            }
+            let letter_start = first[..span.start].trim_end().len() - 1;
+            words.push(first[letter_start..span.end].split_whitespace().collect());
+        }
+        words.join(separator)
+    }
+}
+
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (a.0 - b.0).hypot(a.1 - b.1)
+}
+
+fn format_value(value: f64) -> String {
+    let value = if value.abs() < 0.5 * 10f64.powi(-(PRECISION as i32)) {
+        0.0
+    } else {
+        value
+    };
+    TrailingZeroProcessor::normalize_number(&format!("{:.*}", PRECISION, value), 0)
+}
+
+/// Merge consecutive arcs that form one continuous curve
+///
+/// Arcs are merged while they keep the same direction, their centers lie
+/// within `tolerance` of the first arc's center and their radii match within
+/// `tolerance`, as long as the total sweep stays within a full circle. The
+/// merged command keeps the first arc's center and other words, ends where
+/// the last arc ends and is written with up to four decimals. A merged full
+/// circle ends at its own start point.
+///
+/// # Arguments
+/// * `src` - G-Code program text
+/// * `tolerance` - Allowed center and radius mismatch in program units
+///
+/// # Returns
+/// The program with each run of arcs replaced by a single arc command
+pub fn merge_arcs(src: &str, tolerance: f64) -> String {
+    let mut state = ArcState::default();
+    let mut lines: Vec<String> = Vec::new();
+    let mut run: Option<Run> = None;
+
+    for line in src.lines() {
+        let words = tokenize_words(line);
+        let start = state.position;
+        state.update(&words);
+        let arc = state.arc(line, &words, start, tolerance);
+
+        if let (Some(current), Some(arc)) = (run.as_mut(), arc.as_ref()) {
+            if current.extend(line, arc, tolerance) {
+                continue;
+            }
+        }
+        if let Some(finished) = run.take() {
+            lines.push(finished.finish());
+        }
+        match arc {
+            Some(arc) => {
+                run = Some(Run {
+                    lines: vec![line],
+                    first: arc,
+                    end: arc.end,
+                    sweep: arc.sweep,
+                    feed: state.feed,
+                })
+            }
+            None => lines.push(line.to_string()),
+        }
+    }
+    if let Some(finished) = run {
+        lines.push(finished.finish());
+    }
+
+    let mut merged = lines.join("\n");
+    if src.ends_with('\n') {
+        merged.push('\n');
+    }
+    merged
+}
@@ -0,0 +1,299 @@
+//! Remapping and inverting machine axes
+//!
+//! **Advanced.** The `AxisMapProcessor` is a last resort for machines wired
+//! differently from the program's assumptions, e.g. with X and Y swapped or Z
+//! running the wrong way. It rewrites every axis word, so a wrong mapping
+//! sends the machine somewhere the program never intended. Fix the wiring or
+//! the controller's direction settings instead whenever possible.
+//!
+//! Each axis word takes its target letter and, when negated, the opposite
+//! sign. Arc center offsets (`I J K`) follow X, Y and Z onto other linear
+//! axes. Mirroring an arc's plane (negating one of its axes, or swapping
+//! them) reverses its direction, so `G2` and `G3` are exchanged; an arc whose
+//! plane axes map outside the plane is rejected.
+
+use std::sync::Mutex;
+
+use super::{word_spans, CommandProcessor, GcodeCommand, GcodeState, ProcessorConfig};
+
+/// Axes that can be remapped, in index order
+const AXES: [char; 6] = ['X', 'Y', 'Z', 'A', 'B', 'C'];
+
+/// Arc center letters of the linear axes
+const CENTERS: [char; 3] = ['I', 'J', 'K'];
+
+/// Modal state tracked across commands
+#[derive(Debug, Clone, Copy)]
+struct AxisMapState {
+    /// Active arc plane (17, 18 or 19)
+    plane: u8,
+    /// Motion mode (0-3) of the program
+    motion: Option<u8>,
+    /// Motion mode (0-3) of the rewritten output
+    written: Option<u8>,
+}
+
+impl Default for AxisMapState {
+    fn default() -> Self {
+        Self {
+            plane: 17,
+            motion: None,
+            written: None,
+        }
+    }
+}
+
+/// Swaps, remaps and negates axis words across a program
+///
+/// Advanced: see the [module documentation](self) before using it.
+pub struct AxisMapProcessor {
+    config: ProcessorConfig,
+    /// Target letter of each axis in [`AXES`]
+    targets: [char; 6],
+    /// Whether each axis in [`AXES`] changes sign
+    negated: [bool; 6],
+    state: Mutex<AxisMapState>,
+}
+
+impl AxisMapProcessor {
+    /// Create a mapping that leaves every axis alone
+    pub fn new() -> Self {
+        Self {
+            config: ProcessorConfig::new(),
+            targets: AXES,
+            negated: [false; 6],
+            state: Mutex::new(AxisMapState::default()),
+        }
+    }
+
+    /// Write the program's `from` words as `to` words
+    ///
+    /// Only `from` is changed; use [`with_swap`](Self::with_swap) to exchange
+    /// two axes.
+    pub fn with_mapping(mut self, from: char, to: char) -> Self {
+        if let Some(index) = Self::axis_index(from) {
+            self.targets[index] = to.to_ascii_uppercase();
+        }
+        self
+    }
+
+    /// Exchange two axes
+    pub fn with_swap(self, a: char, b: char) -> Self {
+        self.with_mapping(a, b).with_mapping(b, a)
+    }
+
+    /// Reverse the direction of one of the program's axes
+    ///
+    /// Negation applies to the program's axis, before it is mapped.
+    pub fn with_negated(mut self, axis: char) -> Self {
+        if let Some(index) = Self::axis_index(axis) {
+            self.negated[index] = true;
+        }
+        self
+    }
+
+    /// Where an axis of the program ends up, and whether it changes sign
+    pub fn image(&self, axis: char) -> Option<(char, bool)> {
+        Self::axis_index(axis).map(|index| (self.targets[index], self.negated[index]))
+    }
+
+    fn axis_index(axis: char) -> Option<usize> {
+        AXES.iter().position(|&a| a == axis.to_ascii_uppercase())
+    }
+
+    /// The letter and sign change for an axis or arc center word
+    fn map_letter(&self, letter: char) -> Result<Option<(char, bool)>, String> {
+        if let Some(center) = CENTERS.iter().position(|&c| c == letter) {
+            let axis = AXES[center];
+            let (target, negated) = self.image(axis).unwrap_or((axis, false));
+            return match AXES.iter().take(3).position(|&a| a == target) {
+                Some(index) => Ok(Some((CENTERS[index], negated))),
+                None => Err(format!(
+                    "Arc center {} can't follow {} onto rotary axis {}",
+                    letter, axis, target
+                )),
+            };
+        }
+        Ok(self.image(letter))
+    }
+
+    /// Whether remapping reverses arcs in a plane, or `Err` if the plane's
+    /// axes leave it
+    fn reverses_arcs(&self, plane: u8) -> Result<bool, String> {
+        let (a, b) = match plane {
+            18 => ('Z', 'X'),
+            19 => ('Y', 'Z'),
+            _ => ('X', 'Y'),
+        };
+        let (ta, na) = self.image(a).unwrap_or((a, false));
+        let (tb, nb) = self.image(b).unwrap_or((b, false));
+        let mirrored = na != nb;
+        if (ta, tb) == (a, b) {
+            Ok(mirrored)
+        } else if (ta, tb) == (b, a) {
+            Ok(!mirrored)
+        } else {
+            Err(format!(
+                "Cannot remap G{} arcs onto {}{}: the arc would leave its plane",
+                plane, ta, tb
+            ))
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        match self.targets.iter().find(|t| !AXES.contains(t)) {
+            Some(target) => Err(format!("Cannot map an axis onto '{}'", target)),
+            None => Ok(()),
+        }
+    }
+
+    /// Negate a number without reformatting it
+    fn negate(number: &str) -> String {
+        if number.parse::<f64>().is_ok_and(|v| v == 0.0) {
+            return number.to_string();
+        }
+        match number.as_bytes().first() {
+            Some(b'-') => number[1..].to_string(),
+            Some(b'+') => format!("-{}", &number[1..]),
+            _ => format!("-{}", number),
+        }
+    }
+}
+
+impl Default for AxisMapProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommandProcessor for AxisMapProcessor {
+    fn name(&self) -> &str {
+        "axis_map"
+    }
+
+    fn description(&self) -> &str {
+        "Advanced: swaps, remaps and negates axes for unusually wired machines"
+    }
+
+    /// Forget the tracked modes
+    fn reset(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            *state = AxisMapState::default();
+        }
+    }
+
+    fn process(
+        &self,
+        command: &GcodeCommand,
+        _state: &GcodeState,
+    ) -> Result<Vec<GcodeCommand>, String> {
+        self.validate()?;
+
+        let line = &command.command;
+        let split = line.find([';', '(']).unwrap_or(line.len());
+        let (code, comment) = line.split_at(split);
+        let spans = word_spans(code);
+
+        let mut state = self.state.lock().map_err(|e| e.to_string())?;
+        let mut motion_word = None;
+        for (letter, span) in &spans {
+            if *letter != 'G' {
+                continue;
+            }
+            let Ok(value) = code[span.clone()].parse::<f64>() else {
+                continue;
+            };
+            match (value * 10.0).round() as i64 {
+                mode @ (0 | 10 | 20 | 30) => {
+                    state.motion = Some((mode / 10) as u8);
+                    motion_word = Some(span.clone());
+                }
+                mode @ (170 | 180 | 190) => state.plane = (mode / 10) as u8,
+                _ => {}
+            }
+        }
+
+        let mut rewritten = Vec::new();
+        for (letter, span) in &spans {
+            if let Some((target, negated)) = self.map_letter(*letter)? {
+                rewritten.push((span.clone(), target, negated));
+            }
+        }
+        for (i, word) in rewritten.iter().enumerate() {
+            if rewritten[..i].iter().any(|other| other.1 == word.1) {
+                return Err(format!("Remapped axis words collide on {}", word.1));
+            }
+        }
+
+        let moves = spans.iter().any(|(letter, _)| "XYZIJKR".contains(*letter));
+        let arc = moves && matches!(state.motion, Some(2 | 3));
+        let mut motion = state.motion;
+        if arc && self.reverses_arcs(state.plane)? {
+            motion = motion.map(|m| 5 - m);
+        }
+
+        let separator = if code.trim().contains(char::is_whitespace) {
+            " "
+        } else {
+            ""
+        };
+        let mut result = String::with_capacity(code.len() + 4);
+        let mut last = 0;
+        // A modal arc needs its reversed direction written out unless the
+        // previous line already selected it
+        if arc && motion_word.is_none() && motion != state.written {
+            let insert = spans
+                .iter()
+                .find(|(letter, _)| *letter != 'N')
+                .map(|(_, span)| code[..span.start].trim_end().len() - 1)
+                .unwrap_or(0);
+            result.push_str(&code[..insert]);
+            result.push_str(&format!("G{}{}", motion.unwrap_or(0), separator));
+            last = insert;
+        }
+        for (letter, span) in &spans {
+            let letter_start = code[..span.start].trim_end().len() - 1;
+            let number = &code[span.clone()];
+            let replacement = if Some(span) == motion_word.as_ref() && motion != state.motion {
+                // Keep a leading zero, so G02 becomes G03
+                let padding = if number.len() > 1 && number.starts_with('0') {
+                    "0"
+                } else {
+                    ""
+                };
+                Some((*letter, format!("{}{}", padding, motion.unwrap_or(0))))
+            } else if let Some(&(_, target, negated)) =
+                rewritten.iter().find(|word| word.0 == *span)
+            {
+                let number = if negated {
+                    Self::negate(number)
+                } else {
+                    number.to_string()
+                };
+                Some((target, number))
+            } else {
+                None
+            };
+            let Some((letter, number)) = replacement else {
+                continue;
+            };
+            result.push_str(&code[last..letter_start]);
+            result.push(letter);
+            result.push_str(&code[letter_start + 1..span.start]);
+            result.push_str(&number);
+            last = span.end;
+        }
+        result.push_str(&code[last..]);
+        if moves || motion_word.is_some() {
+            state.written = motion;
+        }
+
+        let mut processed = command.clone();
+        processed.command = format!("{}{}", result, comment);
+        Ok(vec![processed])
+    }
+
+    fn config(&self) -> &ProcessorConfig {
+        &self.config
+    }
+}
@@ -0,0 +1,238 @@
+//! Dry-run time estimates
+//!
+//! The `TimeEstimator` walks a program without a machine and adds up how long
+//! each command takes: rapids at a configured rapid rate, `G1`/`G2`/`G3` at
+//! the modal feed in the pipeline's `GcodeState`, and `G4` dwells for their
+//! `P` seconds. Moves run at full speed end to end, since acceleration isn't
+//! modeled, so real jobs with many short moves take longer.
+//!
+//! Feeds follow the feed rate mode: units per minute (`G94`), inverse time
+//! (`G93`, the move takes 1/F minutes) or units per revolution (`G95`, at the
+//! modal spindle speed). Reference returns (`G28`/`G30`) have no known target
+//! and count as no time.
+
+use std::f64::consts::TAU;
+
+use super::{strip_comments, tokenize_words, CommandId, GcodeCommand, GcodeState};
+
+/// Millimeters per inch, for rapid rates in inch mode
+const MM_PER_INCH: f64 = 25.4;
+
+/// Estimated runtime of a program
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TimeEstimate {
+    /// Whole program, including dwells
+    pub total_seconds: f64,
+    /// Feed moves (`G1`/`G2`/`G3`)
+    pub cutting_seconds: f64,
+    /// Rapid moves (`G0`)
+    pub rapid_seconds: f64,
+    /// Dwells (`G4`)
+    pub dwell_seconds: f64,
+    /// Time taken by each command, in program order
+    pub per_command: Vec<(CommandId, f64)>,
+}
+
+/// Estimates program runtime from its moves and feeds
+#[derive(Debug, Clone)]
+pub struct TimeEstimator {
+    /// Rapid traverse rate in mm/min
+    rapid_rate: f64,
+}
+
+impl TimeEstimator {
+    /// Create an estimator with the machine's rapid rate in mm/min
+    pub fn new(rapid_rate: f64) -> Self {
+        Self { rapid_rate }
+    }
+
+    /// Rapid traverse rate in mm/min
+    pub fn rapid_rate(&self) -> f64 {
+        self.rapid_rate
+    }
+
+    /// Estimate the runtime of a program
+    ///
+    /// # Arguments
+    /// * `commands` - Program to walk, starting at the origin
+    /// * `state` - Modal state the program starts in, updated as it runs
+    pub fn estimate(&self, commands: &[GcodeCommand], state: &mut GcodeState) -> TimeEstimate {
+        let mut estimate = TimeEstimate::default();
+        let mut position = [0.0; 3];
+
+        for command in commands {
+            let seconds = self.command_seconds(command, state, &mut position, &mut estimate);
+            estimate.total_seconds += seconds;
+            estimate.per_command.push((command.id.clone(), seconds));
+        }
+        estimate
+    }
+
+    /// Time one command, updating the modal state and position
+    fn command_seconds(
+        &self,
+        command: &GcodeCommand,
+        state: &mut GcodeState,
+        position: &mut [f64; 3],
+        estimate: &mut TimeEstimate,
+    ) -> f64 {
+        let words = tokenize_words(&strip_comments(&command.command));
+        let word = |letter: char| {
+            words
+                .iter()
+                .find(|(l, _)| *l == letter)
+                .map(|&(_, value)| value)
+        };
+
+        let mut dwell = false;
+        let mut non_modal = None;
+        for &(letter, value) in &words {
+            match letter {
+                'G' => match (value * 10.0).round() as i64 {
+                    code @ (0 | 10 | 20 | 30) => state.motion_mode = (code / 10) as u8,
+                    40 => dwell = true,
+                    code @ (170 | 180 | 190) => state.plane_mode = (code / 10) as u8,
+                    code @ (200 | 210) => state.units_mode = (code / 10) as u8,
+                    code @ (900 | 910) => state.distance_mode = (code / 10) as u8,
+                    code @ (930 | 940 | 950) => state.feed_rate_mode = (code / 10) as u8,
+                    code @ (100 | 280 | 300 | 920) => non_modal = Some(code),
+                    _ => {}
+                },
+                'F' => state.feed_rate = value,
+                'S' => state.spindle_speed = value,
+                _ => {}
+            }
+        }
+
+        if dwell {
+            let seconds = word('P').unwrap_or(0.0).max(0.0);
+            estimate.dwell_seconds += seconds;
+            return seconds;
+        }
+
+        let axes = [word('X'), word('Y'), word('Z')];
+        if axes.iter().all(Option::is_none) {
+            return 0.0;
+        }
+        let mut target = *position;
+        for (axis, value) in target.iter_mut().zip(axes) {
+            if let Some(value) = value {
+                *axis = if state.distance_mode == 91 && non_modal != Some(920) {
+                    *axis + value
+                } else {
+                    value
+                };
+            }
+        }
+        match non_modal {
+            // G92 sets the position and G10 sets offsets, neither moves
+            Some(100) => return 0.0,
+            Some(920) => {
+                *position = target;
+                return 0.0;
+            }
+            // The stored reference point isn't known
+            Some(_) => return 0.0,
+            None => {}
+        }
+
+        let length = if matches!(state.motion_mode, 2 | 3) {
+            Self::arc_length(*position, target, state, &word)
+        } else {
+            Self::distance(*position, target)
+        };
+        *position = target;
+
+        if state.motion_mode == 0 {
+            let rate = if state.units_mode == 20 {
+                self.rapid_rate / MM_PER_INCH
+            } else {
+                self.rapid_rate
+            };
+            let seconds = if rate > 0.0 {
+                length / rate * 60.0
+            } else {
+                0.0
+            };
+            estimate.rapid_seconds += seconds;
+            return seconds;
+        }
+
+        let minutes = match state.feed_rate_mode {
+            93 if state.feed_rate > 0.0 => 1.0 / state.feed_rate,
+            95 if state.feed_rate > 0.0 && state.spindle_speed > 0.0 => {
+                length / (state.feed_rate * state.spindle_speed)
+            }
+            94 if state.feed_rate > 0.0 => length / state.feed_rate,
+            _ => 0.0,
+        };
+        estimate.cutting_seconds += minutes * 60.0;
+        minutes * 60.0
+    }
+
+    fn distance(from: [f64; 3], to: [f64; 3]) -> f64 {
+        from.iter()
+            .zip(to)
+            .map(|(a, b)| (b - a) * (b - a))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    /// Length of a `G2`/`G3` move, including any helical travel
+    ///
+    /// Arcs without a usable center or radius are timed as straight lines.
+    fn arc_length(
+        from: [f64; 3],
+        to: [f64; 3],
+        state: &GcodeState,
+        word: &dyn Fn(char) -> Option<f64>,
+    ) -> f64 {
+        // Plane axes and their center offset letters, then the linear axis
+        let (a, b, linear, offsets) = match state.plane_mode {
+            18 => (2, 0, 1, ['K', 'I']),
+            19 => (1, 2, 0, ['J', 'K']),
+            _ => (0, 1, 2, ['I', 'J']),
+        };
+        let (start, end) = ((from[a], from[b]), (to[a], to[b]));
+        let chord = (end.0 - start.0).hypot(end.1 - start.1);
+        let clockwise = state.motion_mode == 2;
+
+        let (radius, sweep) = if let Some(r) = word('R') {
+            let radius = r.abs();
+            if radius <= 0.0 || chord > 2.0 * radius + 1e-9 {
+                return Self::distance(from, to);
+            }
+            let short = 2.0 * (chord / (2.0 * radius)).min(1.0).asin();
+            // A negative R asks for the long way round
+            (radius, if r < 0.0 { TAU - short } else { short })
+        } else {
+            let center = (
+                start.0 + word(offsets[0]).unwrap_or(0.0),
+                start.1 + word(offsets[1]).unwrap_or(0.0),
+            );
+            let radius = (start.0 - center.0).hypot(start.1 - center.1);
+            if radius <= 0.0 {
+                return Self::distance(from, to);
+            }
+            let begin = (start.1 - center.1).atan2(start.0 - center.0);
+            let finish = (end.1 - center.1).atan2(end.0 - center.0);
+            let mut sweep = if clockwise {
+                begin - finish
+            } else {
+                finish - begin
+            };
+            if sweep <= 1e-9 {
+                sweep += TAU;
+            }
+            (radius, sweep)
+        };
+
+        (radius * sweep).hypot(to[linear] - from[linear])
+    }
+}
+
+impl Default for TimeEstimator {
+    fn default() -> Self {
+        Self::new(3000.0)
+    }
+}
@@ -0,0 +1,392 @@
+//! Parameter expression evaluation
+//!
+//! GRBL has no support for numbered parameters or expressions, so programs
+//! written with them (`#1=2.0`, `X[#1*2]`) must be flattened before streaming.
+//! The `ExpressionProcessor` keeps a table of `#n` parameters, drops lines that
+//! only assign parameters, and replaces bracketed arithmetic and bare `#n`
+//! references with plain numbers.
+//!
+//! Supported syntax is deliberately small: numbers, `#n` references,
+//! `+ - * /`, unary minus, and grouping with `[ ]` or `( )` inside brackets.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use super::{CommandProcessor, GcodeCommand, GcodeState, ProcessorConfig};
+
+/// Parameter table and line counter carried across commands
+#[derive(Debug, Default)]
+struct ExpressionState {
+    parameters: HashMap<u32, f64>,
+    /// Values from `set_parameter`, restored when a new program starts
+    seeds: HashMap<u32, f64>,
+    lines: u32,
+}
+
+/// Evaluates `#n` parameters and bracketed arithmetic
+pub struct ExpressionProcessor {
+    config: ProcessorConfig,
+    state: Mutex<ExpressionState>,
+}
+
+impl ExpressionProcessor {
+    /// Create a processor with an empty parameter table
+    pub fn new() -> Self {
+        Self {
+            config: ProcessorConfig::new(),
+            state: Mutex::new(ExpressionState::default()),
+        }
+    }
+
+    /// Get the current value of a parameter
+    pub fn parameter(&self, number: u32) -> Option<f64> {
+        self.state
+            .lock()
+            .ok()
+            .and_then(|state| state.parameters.get(&number).copied())
+    }
+
+    /// Set a parameter value, e.g. to seed values before processing
+    pub fn set_parameter(&self, number: u32, value: f64) {
+        if let Ok(mut state) = self.state.lock() {
+            state.parameters.insert(number, value);
+            state.seeds.insert(number, value);
+        }
+    }
+
+    /// Evaluate one line against the parameter table
+    ///
+    /// Returns the substituted line, or `None` if the line only assigned
+    /// parameters and should be dropped.
+    fn evaluate_line(
+        line: &str,
+        parameters: &mut HashMap<u32, f64>,
+    ) -> Result<Option<String>, String> {
+        let (code, comment) = line.split_at(Self::code_end(line));
+
+        if let Some(assignments) = Self::parse_assignments(code, parameters)? {
+            // Parameters assigned on a line take effect after the whole line
+            parameters.extend(assignments);
+            return Ok(None);
+        }
+
+        let mut output = String::with_capacity(line.len());
+        let mut chars = code.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '[' => {
+                    let end = Self::matching_bracket(code, i)?;
+                    let value = Evaluator::new(&code[i + 1..end], parameters).evaluate()?;
+                    output.push_str(&format_number(value));
+                    while chars.peek().is_some_and(|&(j, _)| j <= end) {
+                        chars.next();
+                    }
+                }
+                ']' => return Err("unmatched ']'".to_string()),
+                '#' => {
+                    let mut digits = String::new();
+                    while let Some(&(_, d)) = chars.peek() {
+                        if !d.is_ascii_digit() {
+                            break;
+                        }
+                        digits.push(d);
+                        chars.next();
+                    }
+                    let value = lookup(&digits, parameters)?;
+                    output.push_str(&format_number(value));
+                }
+                _ => output.push(c),
+            }
+        }
+        output.push_str(comment);
+
+        Ok(Some(output))
+    }
+
+    /// Find where the code part of a line ends
+    ///
+    /// A `(` inside brackets is grouping, not the start of a comment.
+    fn code_end(line: &str) -> usize {
+        let mut depth = 0usize;
+        for (i, c) in line.char_indices() {
+            match c {
+                '[' => depth += 1,
+                ']' => depth = depth.saturating_sub(1),
+                ';' | '(' if depth == 0 => return i,
+                _ => {}
+            }
+        }
+        line.len()
+    }
+
+    fn matching_bracket(code: &str, open: usize) -> Result<usize, String> {
+        let mut depth = 0usize;
+        for (i, c) in code[open..].char_indices() {
+            match c {
+                '[' => depth += 1,
+                ']' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(open + i);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Err("unmatched '['".to_string())
+    }
+
+    /// Parse a line made up only of `#n=expr` assignments
+    ///
+    /// Returns `None` if the line contains anything other than assignments.
+    fn parse_assignments(
+        code: &str,
+        parameters: &HashMap<u32, f64>,
+    ) -> Result<Option<Vec<(u32, f64)>>, String> {
+        let code = code.trim();
+        if !code.starts_with('#') || !code.contains('=') {
+            return Ok(None);
+        }
+
+        let mut assignments = Vec::new();
+        let mut rest = code;
+        while !rest.is_empty() {
+            let Some(body) = rest.strip_prefix('#') else {
+                return Ok(None);
+            };
+            let Some(eq) = body.find('=') else {
+                return Ok(None);
+            };
+            let Ok(number) = body[..eq].trim().parse::<u32>() else {
+                return Ok(None);
+            };
+
+            // The value runs until the next assignment outside brackets
+            let value_src = &body[eq + 1..];
+            let mut depth = 0usize;
+            let mut end = value_src.len();
+            for (i, c) in value_src.char_indices() {
+                match c {
+                    '[' | '(' => depth += 1,
+                    ']' | ')' => depth = depth.saturating_sub(1),
+                    '#' if depth == 0 && Self::is_assignment(&value_src[i..]) => {
+                        end = i;
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+
+            let value = Evaluator::new(&value_src[..end], parameters).evaluate()?;
+            assignments.push((number, value));
+            rest = value_src[end..].trim_start();
+        }
+
+        Ok(Some(assignments))
+    }
+
+    /// Check if text starts with `#n=`
+    fn is_assignment(text: &str) -> bool {
+        let Some(body) = text.strip_prefix('#') else {
+            return false;
+        };
+        let rest = body.trim_start_matches(|c: char| c.is_ascii_digit());
+        rest.len() < body.len() && rest.trim_start().starts_with('=')
+    }
+}
+
+impl Default for ExpressionProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommandProcessor for ExpressionProcessor {
+    fn name(&self) -> &str {
+        "expression"
+    }
+
+    fn description(&self) -> &str {
+        "Evaluates #n parameters and bracketed arithmetic to plain numbers"
+    }
+
+    /// Clear the line counter and the parameters set by earlier programs,
+    /// keeping seeded values
+    fn reset(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            let seeds = std::mem::take(&mut state.seeds);
+            *state = ExpressionState {
+                parameters: seeds.clone(),
+                seeds,
+                lines: 0,
+            };
+        }
+    }
+
+    fn process(
+        &self,
+        command: &GcodeCommand,
+        _state: &GcodeState,
+    ) -> Result<Vec<GcodeCommand>, String> {
+        let mut state = self.state.lock().map_err(|e| e.to_string())?;
+        state.lines += 1;
+        let line_number = command.line_number.unwrap_or(state.lines);
+
+        if !command.command.contains(['#', '[']) {
+            return Ok(vec![command.clone()]);
+        }
+
+        match Self::evaluate_line(&command.command, &mut state.parameters) {
+            Ok(Some(text)) => {
+                let mut processed = command.clone();
+                processed.command = text;
+                Ok(vec![processed])
+            }
+            Ok(None) => Ok(Vec::new()),
+            Err(e) => Err(format!("Line {}: {}", line_number, e)),
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    fn config(&self) -> &ProcessorConfig {
+        &self.config
+    }
+}
+
+/// Look up a parameter by its digits
+fn lookup(digits: &str, parameters: &HashMap<u32, f64>) -> Result<f64, String> {
+    let number: u32 = digits
+        .parse()
+        .map_err(|_| "expected parameter number after '#'".to_string())?;
+    parameters
+        .get(&number)
+        .copied()
+        .ok_or_else(|| format!("undefined parameter #{}", number))
+}
+
+/// Format a value without trailing zeros, e.g. `4` or `2.5`
+fn format_number(value: f64) -> String {
+    let s = format!("{:.4}", value);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" {
+        "0".to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+/// Recursive descent evaluator for bracketed arithmetic
+struct Evaluator<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    parameters: &'a HashMap<u32, f64>,
+}
+
+impl<'a> Evaluator<'a> {
+    fn new(src: &'a str, parameters: &'a HashMap<u32, f64>) -> Self {
+        Self {
+            chars: src.chars().peekable(),
+            parameters,
+        }
+    }
+
+    fn evaluate(mut self) -> Result<f64, String> {
+        let value = self.expression()?;
+        self.skip_whitespace();
+        match self.chars.next() {
+            None => Ok(value),
+            Some(c) => Err(format!("unsupported expression near '{}'", c)),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+            self.chars.next();
+        }
+    }
+
+    fn expression(&mut self) -> Result<f64, String> {
+        let mut value = self.term()?;
+        loop {
+            self.skip_whitespace();
+            match self.chars.peek() {
+                Some('+') => {
+                    self.chars.next();
+                    value += self.term()?;
+                }
+                Some('-') => {
+                    self.chars.next();
+                    value -= self.term()?;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<f64, String> {
+        let mut value = self.factor()?;
+        loop {
+            self.skip_whitespace();
+            match self.chars.peek() {
+                Some('*') => {
+                    self.chars.next();
+                    value *= self.factor()?;
+                }
+                Some('/') => {
+                    self.chars.next();
+                    let divisor = self.factor()?;
+                    if divisor == 0.0 {
+                        return Err("division by zero".to_string());
+                    }
+                    value /= divisor;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    fn factor(&mut self) -> Result<f64, String> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some('-') => Ok(-self.factor()?),
+            Some('+') => self.factor(),
+            Some(open @ ('[' | '(')) => {
+                let value = self.expression()?;
+                self.skip_whitespace();
+                let close = if open == '[' { ']' } else { ')' };
+                match self.chars.next() {
+                    Some(c) if c == close => Ok(value),
+                    _ => Err(format!("expected '{}'", close)),
+                }
+            }
+            Some('#') => {
+                let mut digits = String::new();
+                while let Some(&c) = self.chars.peek() {
+                    if !c.is_ascii_digit() {
+                        break;
+                    }
+                    digits.push(c);
+                    self.chars.next();
+                }
+                lookup(&digits, self.parameters)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let mut number = String::from(c);
+                while let Some(&c) = self.chars.peek() {
+                    if !(c.is_ascii_digit() || c == '.') {
+                        break;
+                    }
+                    number.push(c);
+                    self.chars.next();
+                }
+                number
+                    .parse()
+                    .map_err(|_| format!("invalid number '{}'", number))
+            }
+            Some(c) => Err(format!("unsupported expression near '{}'", c)),
+            None => Err("unexpected end of expression".to_string()),
+        }
+    }
+}
@@ -0,0 +1,326 @@
+//! Extents of a toolpath
+//!
+//! [`compute_extents`] walks parsed commands the way a controller would,
+//! honoring the modal motion, distance and plane modes, and reports the
+//! bounding box of everything the tool passes through together with the
+//! distance travelled cutting and at rapid. Arcs contribute their endpoints
+//! and every axis extreme they sweep through, so a full circle spans its
+//! whole diameter.
+
+use std::f64::consts::{FRAC_PI_2, TAU};
+
+use super::{tokenize_words, GcodeCommand};
+
+/// Bounding box and path lengths of a toolpath
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Extents {
+    /// Smallest X reached
+    pub min_x: f64,
+    /// Largest X reached
+    pub max_x: f64,
+    /// Smallest Y reached
+    pub min_y: f64,
+    /// Largest Y reached
+    pub max_y: f64,
+    /// Smallest Z reached
+    pub min_z: f64,
+    /// Largest Z reached
+    pub max_z: f64,
+    /// Distance travelled by feed moves (`G1`, `G2`, `G3`)
+    pub cutting_length: f64,
+    /// Distance travelled by rapids (`G0`)
+    pub rapid_length: f64,
+}
+
+impl Extents {
+    /// Create empty extents, containing no points
+    pub fn new() -> Self {
+        Self {
+            min_x: f64::INFINITY,
+            max_x: f64::NEG_INFINITY,
+            min_y: f64::INFINITY,
+            max_y: f64::NEG_INFINITY,
+            min_z: f64::INFINITY,
+            max_z: f64::NEG_INFINITY,
+            cutting_length: 0.0,
+            rapid_length: 0.0,
+        }
+    }
+
+    /// Check whether no move was found
+    pub fn is_empty(&self) -> bool {
+        self.min_x > self.max_x
+    }
+
+    /// Size along X, or 0 when empty
+    pub fn width(&self) -> f64 {
+        (self.max_x - self.min_x).max(0.0)
+    }
+
+    /// Size along Y, or 0 when empty
+    pub fn height(&self) -> f64 {
+        (self.max_y - self.min_y).max(0.0)
+    }
+
+    /// Size along Z, or 0 when empty
+    pub fn depth(&self) -> f64 {
+        (self.max_z - self.min_z).max(0.0)
+    }
+
+    /// Total distance travelled, cutting and at rapid
+    pub fn total_length(&self) -> f64 {
+        self.cutting_length + self.rapid_length
+    }
+
+    /// Grow the box to contain a point
+    fn include(&mut self, [x, y, z]: [f64; 3]) {
+        self.min_x = self.min_x.min(x);
+        self.max_x = self.max_x.max(x);
+        self.min_y = self.min_y.min(y);
+        self.max_y = self.max_y.max(y);
+        self.min_z = self.min_z.min(z);
+        self.max_z = self.max_z.max(z);
+    }
+}
+
+impl Default for Extents {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compute the bounding box and path lengths of a program
+///
+/// The program is assumed to start at the origin, as in the visualizer.
+/// Absolute (`G90`) and incremental (`G91`) coordinates are tracked, and
+/// arcs (`G2`/`G3`, with `I J K` or `R`) are followed in the plane
+/// selected by `G17`-`G19`, including helical Z travel. `I J K` are offsets
+/// from the start point unless `G90.1` makes them absolute. Lines in machine
+/// coordinates (`G53`), reference returns (`G28`/`G30`) and offset changes
+/// (`G10`/`G92`) don't move the program position and are skipped.
+///
+/// # Arguments
+/// * `commands` - Parsed commands in program order
+///
+/// # Returns
+/// The extents of every move; empty if the program never moves
+pub fn compute_extents(commands: &[GcodeCommand]) -> Extents {
+    let mut extents = Extents::new();
+    walk_moves(commands, |step| {
+        for &point in &step.points {
+            extents.include(point);
+        }
+        if step.motion == 0 {
+            extents.rapid_length += step.length;
+        } else {
+            extents.cutting_length += step.length;
+        }
+    });
+    extents
+}
+
+/// One move found while walking a program
+pub(super) struct Move {
+    /// Index of the command making the move
+    pub index: usize,
+    /// Motion mode (0-3)
+    pub motion: u8,
+    /// Path length in program units
+    pub length: f64,
+    /// Modal feed rate (`F`), if one was set
+    pub feed: Option<f64>,
+    /// Whether inverse time feed mode (`G93`) is active
+    pub inverse_time: bool,
+    /// Whether the program is in inches (`G20`)
+    pub inches: bool,
+    /// Start and end, plus the axis extremes an arc passes through
+    pub points: Vec<[f64; 3]>,
+}
+
+/// Call `visit` for each move of a program, as described for
+/// [`compute_extents`]
+pub(super) fn walk_moves(commands: &[GcodeCommand], mut visit: impl FnMut(Move)) {
+    let mut position = [0.0; 3];
+    let mut motion: Option<u8> = None;
+    let mut incremental = false;
+    let mut absolute_centers = false;
+    let mut inverse_time = false;
+    let mut inches = false;
+    let mut feed = None;
+    // Plane axes in counter-clockwise order, and the axis normal to it
+    let mut plane: (usize, usize, usize) = (0, 1, 2);
+
+    for (index, command) in commands.iter().enumerate() {
+        let words = tokenize_words(&command.command);
+        let mut skip = false;
+        for &(letter, value) in &words {
+            if letter == 'F' {
+                feed = Some(value);
+            }
+            if letter != 'G' {
+                continue;
+            }
+            match (value * 10.0).round() as i64 {
+                code @ (0 | 10 | 20 | 30) => motion = Some((code / 10) as u8),
+                800 => motion = None,
+                170 => plane = (0, 1, 2),
+                180 => plane = (2, 0, 1),
+                190 => plane = (1, 2, 0),
+                200 => inches = true,
+                210 => inches = false,
+                900 => incremental = false,
+                910 => incremental = true,
+                901 => absolute_centers = true,
+                911 => absolute_centers = false,
+                930 => inverse_time = true,
+                940 | 950 => inverse_time = false,
+                100 | 280 | 300 | 530 | 920..=923 => skip = true,
+                _ => {}
+            }
+        }
+        if skip {
+            continue;
+        }
+
+        let mut target = position;
+        let mut moves = false;
+        for &(letter, value) in &words {
+            let axis = match letter {
+                'X' => 0,
+                'Y' => 1,
+                'Z' => 2,
+                _ => continue,
+            };
+            moves = true;
+            target[axis] = if incremental {
+                target[axis] + value
+            } else {
+                value
+            };
+        }
+        let Some(motion) = motion.filter(|_| moves) else {
+            continue;
+        };
+
+        let mut points = vec![position, target];
+        let length = if motion < 2 {
+            distance(position, target)
+        } else {
+            let word = |wanted: char| {
+                words
+                    .iter()
+                    .find(|(letter, _)| *letter == wanted)
+                    .map(|&(_, value)| value)
+            };
+            let mut offsets = [word('I'), word('J'), word('K')];
+            if absolute_centers {
+                for (axis, offset) in offsets.iter_mut().enumerate() {
+                    *offset = offset.map(|center| center - position[axis]);
+                }
+            }
+            match arc_center(position, target, plane, motion, &offsets, word('R')) {
+                Some(center) => sweep_arc(&mut points, position, target, center, plane, motion),
+                // An arc that can't be resolved is counted as a line
+                None => distance(position, target),
+            }
+        };
+        visit(Move {
+            index,
+            motion,
+            length,
+            feed,
+            inverse_time,
+            inches,
+            points,
+        });
+        position = target;
+    }
+}
+
+fn distance(from: [f64; 3], to: [f64; 3]) -> f64 {
+    from.iter()
+        .zip(to)
+        .map(|(a, b)| (b - a).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// Center of an arc in plane coordinates `(a, b)`
+fn arc_center(
+    start: [f64; 3],
+    end: [f64; 3],
+    (a, b, _): (usize, usize, usize),
+    motion: u8,
+    offsets: &[Option<f64>; 3],
+    radius: Option<f64>,
+) -> Option<(f64, f64)> {
+    if let Some(radius) = radius {
+        // Same construction as GRBL: the center lies on the perpendicular
+        // bisector, on the side giving the minor arc for a positive R
+        let (x, y) = (end[a] - start[a], end[b] - start[b]);
+        let chord = x.hypot(y);
+        let squared = 4.0 * radius * radius - x * x - y * y;
+        if chord == 0.0 || squared < 0.0 {
+            return None;
+        }
+        let mut h = -squared.sqrt() / chord;
+        if motion == 3 {
+            h = -h;
+        }
+        if radius < 0.0 {
+            h = -h;
+        }
+        return Some((start[a] + 0.5 * (x - y * h), start[b] + 0.5 * (y + x * h)));
+    }
+    if offsets[a].is_none() && offsets[b].is_none() {
+        return None;
+    }
+    Some((
+        start[a] + offsets[a].unwrap_or(0.0),
+        start[b] + offsets[b].unwrap_or(0.0),
+    ))
+}
+
+/// Add the axis extremes an arc passes through to `points` and return its
+/// length
+fn sweep_arc(
+    points: &mut Vec<[f64; 3]>,
+    start: [f64; 3],
+    end: [f64; 3],
+    center: (f64, f64),
+    (a, b, normal): (usize, usize, usize),
+    motion: u8,
+) -> f64 {
+    let radius = (start[a] - center.0).hypot(start[b] - center.1);
+    let start_angle = (start[b] - center.1).atan2(start[a] - center.0);
+    let end_angle = (end[b] - center.1).atan2(end[a] - center.0);
+    let clockwise = motion == 2;
+
+    // Angle travelled from the start, in the arc's direction
+    let travelled = |angle: f64| {
+        let delta = if clockwise {
+            start_angle - angle
+        } else {
+            angle - start_angle
+        };
+        delta.rem_euclid(TAU)
+    };
+    let mut sweep = travelled(end_angle);
+    if sweep <= 1e-9 {
+        // Coincident endpoints make a full circle
+        sweep = TAU;
+    }
+
+    for quadrant in 0..4 {
+        let angle = quadrant as f64 * FRAC_PI_2;
+        if travelled(angle) <= sweep {
+            let fraction = travelled(angle) / sweep;
+            let mut point = start;
+            point[a] = center.0 + radius * angle.cos();
+            point[b] = center.1 + radius * angle.sin();
+            point[normal] = start[normal] + (end[normal] - start[normal]) * fraction;
+            points.push(point);
+        }
+    }
+    (radius * sweep).hypot(end[normal] - start[normal])
+}
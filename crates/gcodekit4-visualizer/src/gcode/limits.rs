@@ -0,0 +1,109 @@
+//! Soft limit check
+//!
+//! [`check_soft_limits`] follows a program through the active work offsets
+//! and reports every move that would carry the machine past its configured
+//! travel, so the job can be fixed before the controller alarms half way
+//! through it.
+
+use super::extents::walk_moves;
+use super::{tokenize_words, GcodeCommand};
+use crate::utils::{SoftLimits, ValidationIssue, ValidationSeverity, WorkCoordinateSystem};
+
+/// Distance past a limit still accepted, absorbing rounding in arc extremes
+/// and work offsets
+const LIMIT_TOLERANCE: f64 = 1e-6;
+
+/// Check a program's moves against the machine's soft limits
+///
+/// Program coordinates are turned into machine coordinates by adding the
+/// offset of the active work coordinate system, starting from the one
+/// selected in `wcs` and following `G54`-`G59` and `G59.1`-`G59.3` in the
+/// program. Inch programs (`G20`) are converted to millimeters first. Arcs
+/// are checked at every axis extreme they sweep through, not just their
+/// endpoints. As in the visualizer the program starts at its origin, and
+/// lines in machine coordinates (`G53`), reference returns and offset
+/// changes aren't checked.
+///
+/// # Arguments
+/// * `src` - G-Code program text
+/// * `limits` - Machine travel in millimeters; nothing is reported when disabled
+/// * `wcs` - Work offsets, with the system active when the program starts
+///
+/// # Returns
+/// One error issue per axis and offending line, with 1-based line numbers;
+/// a move is only reported for the axes it moves
+pub fn check_soft_limits(
+    src: &str,
+    limits: &SoftLimits,
+    wcs: &WorkCoordinateSystem,
+) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    if !limits.enabled {
+        return issues;
+    }
+
+    let commands: Vec<GcodeCommand> = src.lines().map(GcodeCommand::new).collect();
+    let bounds = [
+        ('X', limits.x_min, limits.x_max),
+        ('Y', limits.y_min, limits.y_max),
+        ('Z', limits.z_min, limits.z_max),
+    ];
+    let mut system = wcs.current_system();
+    let mut scanned = 0;
+
+    walk_moves(&commands, |step| {
+        // Offset selections on lines up to and including this move
+        for command in &commands[scanned..=step.index] {
+            for (letter, value) in tokenize_words(&command.command) {
+                if letter != 'G' {
+                    continue;
+                }
+                match (value * 10.0).round() as u32 {
+                    code @ (540 | 550 | 560 | 570 | 580 | 590) => system = (code - 540) / 10 + 1,
+                    code @ 591..=593 => system = code - 584,
+                    _ => {}
+                }
+            }
+        }
+        scanned = step.index + 1;
+
+        let offset = wcs.get_offset(system).unwrap_or(wcs.current_offset());
+        let offset = [offset.x, offset.y, offset.z];
+        let scale = if step.inches { 25.4 } else { 1.0 };
+
+        // The start was checked as the end of the previous move, and an axis
+        // the move leaves alone was reported by the move that took it there
+        for (axis, &(letter, min, max)) in bounds.iter().enumerate() {
+            let start = step.points[0][axis];
+            if step.points[1..].iter().all(|point| point[axis] == start) {
+                continue;
+            }
+            let machine = step.points[1..]
+                .iter()
+                .map(|point| point[axis] * scale + offset[axis]);
+            let (low, high) = machine.fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), v| {
+                (low.min(v), high.max(v))
+            });
+            let (value, limit) = if high > max + LIMIT_TOLERANCE {
+                (high, max)
+            } else if low < min - LIMIT_TOLERANCE {
+                (low, min)
+            } else {
+                continue;
+            };
+            issues.push(
+                ValidationIssue::new(
+                    step.index as u32 + 1,
+                    ValidationSeverity::Error,
+                    format!(
+                        "Move reaches machine {}{} beyond the soft limit of {}{}",
+                        letter, value, letter, limit
+                    ),
+                )
+                .with_suggestion("Check the work offset or move the job inside the machine travel"),
+            );
+        }
+    });
+
+    issues
+}
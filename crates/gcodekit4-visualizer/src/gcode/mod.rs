@@ -8,13 +8,41 @@
 //! - Command lifecycle management
 //! - Command listener framework
 //! - Stream management (reading from files or strings)
-
+//! - Parameter expression evaluation
+//! - Whole-program coordinate transforms
+//! - Merging runs of short arcs
+//! - Converting between inches and millimeters
+//! - Dry-run runtime estimates
+//! - Renumbering N words
+//! - Recording and replaying controller sessions
+//! - Retracting to safe Z before rapids
+//! - Toolpath extents and path lengths
+//! - Checking moves against the machine's soft limits
+//! - Runtime estimates that follow the feed and rapid overrides
+
+pub mod arc_merge;
+pub mod axis_map;
+pub mod estimate;
+pub mod expression;
+pub mod extents;
+pub mod limits;
+pub mod renumber;
+pub mod resume;
+pub mod runtime;
+pub mod safe_z;
+pub mod session;
 pub mod stream;
+pub mod transform;
+pub mod units;
 
+use crate::utils::{ValidationIssue, ValidationSeverity};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::path::Path;
 use std::sync::atomic::{AtomicU32, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use stream::{FileStreamReader, GcodeStreamReader};
 use uuid::Uuid;
 
 /// Unique identifier for a G-Code command
@@ -331,6 +359,20 @@ pub struct GcodeParser {
     command_generator: CommandNumberGenerator,
 }
 
+/// Syntax problem found by [`GcodeParser::validate_line`]
+///
+/// `col` and `len` count characters, with `col` 0-indexed, so an editor can
+/// underline exactly the offending text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIssue {
+    /// Column of the first offending character (0-indexed)
+    pub col: usize,
+    /// Number of characters to underline
+    pub len: usize,
+    /// What is wrong
+    pub message: String,
+}
+
 /// Modal state for G-Code execution
 ///
 /// Tracks the active modal groups during G-Code execution.
@@ -359,12 +401,44 @@ impl Default for ModalState {
     }
 }
 
+/// Words found on one G-Code line
+///
+/// Axis, arc and feed words hold the last value given on the line, `None` when
+/// absent. G and M codes are kept in line order, so `G90 G0` yields
+/// `[90.0, 0.0]` and dotted codes such as `G38.2` keep their fraction.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParsedWords {
+    /// X axis
+    pub x: Option<f64>,
+    /// Y axis
+    pub y: Option<f64>,
+    /// Z axis
+    pub z: Option<f64>,
+    /// Arc center X offset
+    pub i: Option<f64>,
+    /// Arc center Y offset
+    pub j: Option<f64>,
+    /// Arc center Z offset
+    pub k: Option<f64>,
+    /// Arc radius
+    pub r: Option<f64>,
+    /// Feed rate
+    pub f: Option<f64>,
+    /// Spindle speed
+    pub s: Option<f64>,
+    /// G codes on the line
+    pub g_codes: Vec<f64>,
+    /// M codes on the line
+    pub m_codes: Vec<f64>,
+}
+
 /// Comprehensive G-Code execution state
 ///
 /// Tracks all modal groups and execution state required for proper G-Code interpretation:
 /// - Motion group (G00, G01, G02, G03)
 /// - Plane selection group (G17, G18, G19)
 /// - Distance mode group (G90, G91)
+/// - Arc distance mode group (G90.1, G91.1)
 /// - Feed rate mode group (G93, G94, G95)
 /// - Units group (G20, G21)
 /// - Coordinate system group (G54-G59)
@@ -372,6 +446,7 @@ impl Default for ModalState {
 /// - Cutter compensation group (G40, G41, G42)
 /// - Spindle mode group (G03, G04, G05)
 /// - Path control group (G61, G61.1, G64)
+/// - Spindle control (M3, M4, M5) and coolant (M7, M8, M9)
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct GcodeState {
     /// Motion mode - Group 1 (G00, G01, G02, G03)
@@ -383,6 +458,10 @@ pub struct GcodeState {
     /// Distance mode - Group 3 (G90=absolute, G91=incremental)
     pub distance_mode: u8,
 
+    /// Arc distance mode - Group 4 (G90.1=absolute I J K, G91.1=offsets from the start)
+    #[serde(default)]
+    pub arc_center_absolute: bool,
+
     /// Feed rate mode - Group 5 (G93=inverse_time, G94=units_per_minute, G95=units_per_revolution)
     pub feed_rate_mode: u8,
 
@@ -412,6 +491,15 @@ pub struct GcodeState {
 
     /// Tool number (T value)
     pub tool_number: u16,
+
+    /// Whether the spindle is turning (M3 or M4 active)
+    pub spindle_on: bool,
+
+    /// Spindle direction - M3=clockwise, M4=counter-clockwise, M5=stopped
+    pub spindle_direction: u8,
+
+    /// Coolant - M7=mist, M8=flood, M9=off
+    pub coolant: u8,
 }
 
 impl Default for GcodeState {
@@ -427,9 +515,13 @@ impl Default for GcodeState {
             compensation_mode: 40, // G40 (cutter compensation off)
             spindle_mode: 0,       // No spindle mode
             path_control_mode: 64, // G64 (blend/continuous)
+            arc_center_absolute: false,
             feed_rate: 0.0,
             spindle_speed: 0.0,
             tool_number: 0,
+            spindle_on: false,
+            spindle_direction: 5, // M5 (spindle stopped)
+            coolant: 9,           // M9 (coolant off)
         }
     }
 }
@@ -473,6 +565,11 @@ impl GcodeState {
         }
     }
 
+    /// Set arc distance mode (`true` for G90.1, `false` for G91.1)
+    pub fn set_arc_center_absolute(&mut self, absolute: bool) {
+        self.arc_center_absolute = absolute;
+    }
+
     /// Set feed rate mode (G93, G94, G95)
     pub fn set_feed_rate_mode(&mut self, mode: u8) -> Result<(), String> {
         match mode {
@@ -551,6 +648,91 @@ impl GcodeState {
         self.tool_number = tool;
     }
 
+    /// Set spindle direction (M3 clockwise, M4 counter-clockwise, M5 stop)
+    ///
+    /// Also turns `spindle_on` on for M3 and M4 and off for M5.
+    pub fn set_spindle_direction(&mut self, direction: u8) -> Result<(), String> {
+        match direction {
+            3..=5 => {
+                self.spindle_direction = direction;
+                self.spindle_on = direction != 5;
+                Ok(())
+            }
+            _ => Err(format!("Invalid spindle direction: {}", direction)),
+        }
+    }
+
+    /// Set coolant mode (M7 mist, M8 flood, M9 off)
+    pub fn set_coolant(&mut self, coolant: u8) -> Result<(), String> {
+        match coolant {
+            7..=9 => {
+                self.coolant = coolant;
+                Ok(())
+            }
+            _ => Err(format!("Invalid coolant mode: {}", coolant)),
+        }
+    }
+
+    /// Summarize the motion, units, distance, WCS and feed modes for a status line
+    ///
+    /// For example `G1 G21 G90 G54 F500`.
+    pub fn modal_summary(&self) -> String {
+        format!(
+            "G{} G{} G{} G{} F{}",
+            self.motion_mode,
+            self.units_mode,
+            self.distance_mode,
+            self.coordinate_system,
+            self.feed_rate
+        )
+    }
+
+    /// Apply the modal words of one line, e.g. from [`tokenize_words`]
+    ///
+    /// Motion, plane, distance, arc distance, feed rate, units, coordinate
+    /// system, tool offset and cutter compensation codes set their modes; spindle and
+    /// coolant M-codes set the spindle and coolant state; `F`, `S` and `T`
+    /// set the feed rate, spindle speed and tool number. Other words are
+    /// ignored.
+    fn apply_words(&mut self, words: &[(char, f64)]) -> Result<(), String> {
+        for &(letter, value) in words {
+            match letter {
+                'F' => self.set_feed_rate(value)?,
+                'S' => self.set_spindle_speed(value)?,
+                'T' if value >= 0.0 && value.fract() == 0.0 => self.set_tool_number(value as u16),
+                'G' | 'M' => {
+                    // Compare in tenths so G43.1 or G59.1 doesn't pass for G43 or G59
+                    let tenths = (value * 10.0).round() as i64;
+                    if letter == 'G' && matches!(tenths, 901 | 911) {
+                        self.set_arc_center_absolute(tenths == 901);
+                        continue;
+                    }
+                    let Ok(code) = u8::try_from(tenths / 10) else {
+                        continue;
+                    };
+                    if tenths % 10 != 0 {
+                        continue;
+                    }
+                    match (letter, code) {
+                        ('G', 0..=3) => self.set_motion_mode(code)?,
+                        ('G', 17..=19) => self.set_plane_mode(code)?,
+                        ('G', 90 | 91) => self.set_distance_mode(code)?,
+                        ('G', 93..=95) => self.set_feed_rate_mode(code)?,
+                        ('G', 20 | 21) => self.set_units_mode(code)?,
+                        ('G', 54..=59) => self.set_coordinate_system(code)?,
+                        ('G', 43 | 49) => self.set_tool_offset_mode(code)?,
+                        ('G', 40..=42) => self.set_compensation_mode(code)?,
+                        ('M', 3..=5) => self.set_spindle_direction(code)?,
+                        ('M', 7..=9) => self.set_coolant(code)?,
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
     /// Check if state is valid
     pub fn validate(&self) -> Result<(), String> {
         if !matches!(self.motion_mode, 0..=3) {
@@ -574,9 +756,106 @@ impl GcodeState {
                 self.coordinate_system
             ));
         }
+        if !matches!(self.spindle_direction, 3..=5) {
+            return Err(format!(
+                "Invalid spindle direction: {}",
+                self.spindle_direction
+            ));
+        }
+        if self.spindle_on != (self.spindle_direction != 5) {
+            return Err("Spindle on/off disagrees with its direction".to_string());
+        }
+        if !matches!(self.coolant, 7..=9) {
+            return Err(format!("Invalid coolant mode: {}", self.coolant));
+        }
         Ok(())
     }
 
+    /// Detect conflicting modal-group words on a single line
+    ///
+    /// A block may contain at most one word from each modal group, so
+    /// `G0 G1 X1` or `G90 G91` are errors while `G90 G1 X1` is fine. Coolant
+    /// words are not checked since `M7` and `M8` may legitimately be combined.
+    ///
+    /// # Arguments
+    /// * `line` - Raw G-Code line (comments are ignored)
+    /// * `line_number` - Line number reported in the issues
+    ///
+    /// # Returns
+    /// One error issue per modal group with more than one word on the line
+    pub fn check_modal_conflicts(line: &str, line_number: u32) -> Vec<ValidationIssue> {
+        let mut seen: Vec<(&'static str, String)> = Vec::new();
+        let mut issues = Vec::new();
+
+        for (letter, value) in tokenize_words(line) {
+            let Some(group) = modal_group(letter, value) else {
+                continue;
+            };
+            let word = format!("{}{}", letter, value);
+            match seen.iter().find(|(g, _)| *g == group) {
+                Some((_, first)) if *first != word => {
+                    issues.push(
+                        ValidationIssue::new(
+                            line_number,
+                            ValidationSeverity::Error,
+                            format!(
+                                "Conflicting {} words {} and {} on one line",
+                                group, first, word
+                            ),
+                        )
+                        .with_suggestion("Keep only one word from each modal group per line"),
+                    );
+                }
+                Some(_) => {}
+                None => seen.push((group, word)),
+            }
+        }
+
+        issues
+    }
+
+    /// Detect tool changes made while the spindle may still be running
+    ///
+    /// Spindle state is tracked through the program: `M3`/`M4` start it and
+    /// `M5`, `M2` or `M30` stop it. An `M6` issued while the spindle is on is
+    /// flagged. The tool change executes before spindle words on the same
+    /// line, so `M5 M6` is also flagged.
+    ///
+    /// # Arguments
+    /// * `lines` - Program lines; issues use 1-based line numbers
+    ///
+    /// # Returns
+    /// One warning issue per unsafe tool change
+    pub fn check_tool_change_spindle<S: AsRef<str>>(lines: &[S]) -> Vec<ValidationIssue> {
+        let mut spindle_on = false;
+        let mut issues = Vec::new();
+
+        for (index, line) in lines.iter().enumerate() {
+            let words = tokenize_words(line.as_ref());
+            let is_m = |code: f64| words.iter().any(|&(l, v)| l == 'M' && v == code);
+
+            if is_m(6.0) && spindle_on {
+                issues.push(
+                    ValidationIssue::new(
+                        index as u32 + 1,
+                        ValidationSeverity::Warning,
+                        "Tool change (M6) while the spindle is running",
+                    )
+                    .with_suggestion("Stop the spindle with M5 before the tool change"),
+                );
+            }
+
+            if is_m(3.0) || is_m(4.0) {
+                spindle_on = true;
+            }
+            if is_m(5.0) || is_m(2.0) || is_m(30.0) {
+                spindle_on = false;
+            }
+        }
+
+        issues
+    }
+
     /// Get a human-readable description of the current motion mode
     pub fn motion_mode_description(&self) -> &'static str {
         match self.motion_mode {
@@ -720,6 +999,12 @@ pub trait CommandProcessor: Send + Sync {
         true
     }
 
+    /// Forget anything carried over from earlier commands
+    ///
+    /// The pipeline calls this before each program, so processors that
+    /// track state across commands start every program afresh.
+    fn reset(&self) {}
+
     /// Get the configuration for this processor
     fn config(&self) -> &ProcessorConfig {
         static DEFAULT_CONFIG: std::sync::OnceLock<ProcessorConfig> = std::sync::OnceLock::new();
@@ -730,6 +1015,27 @@ pub trait CommandProcessor: Send + Sync {
 /// Arc-wrapped processor for thread-safe sharing
 pub type ProcessorHandle = Arc<dyn CommandProcessor>;
 
+/// Per-processor line counts from a pipeline run
+///
+/// Each entry is `(name, in, out)`: the number of commands a processor
+/// received and the number it produced. A comment stripper shows fewer lines
+/// out than in, an arc expander shows more.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PipelineReport {
+    /// Counts for each enabled processor, in pipeline order
+    pub per_processor: Vec<(String, usize, usize)>,
+}
+
+impl PipelineReport {
+    /// Get the `(in, out)` counts for a processor by name
+    pub fn counts(&self, name: &str) -> Option<(usize, usize)> {
+        self.per_processor
+            .iter()
+            .find(|(n, _, _)| n == name)
+            .map(|&(_, input, output)| (input, output))
+    }
+}
+
 /// G-Code command processor pipeline
 ///
 /// Manages a sequence of command processors that are applied to G-Code commands
@@ -748,6 +1054,10 @@ pub type ProcessorHandle = Arc<dyn CommandProcessor>;
 pub struct ProcessorPipeline {
     processors: Vec<ProcessorHandle>,
     config: ProcessorConfig,
+    /// Enabled states set with `set_enabled`, by processor name
+    enabled_overrides: std::collections::HashMap<String, bool>,
+    /// Header and footer applied to the whole program after the processors
+    header_footer: Option<HeaderFooterProcessor>,
 }
 
 impl ProcessorPipeline {
@@ -756,6 +1066,8 @@ impl ProcessorPipeline {
         Self {
             processors: Vec::new(),
             config: ProcessorConfig::new(),
+            enabled_overrides: std::collections::HashMap::new(),
+            header_footer: None,
         }
     }
 
@@ -792,10 +1104,80 @@ impl ProcessorPipeline {
     pub fn list_processors(&self) -> Vec<(&str, &str, bool)> {
         self.processors
             .iter()
-            .map(|p| (p.name(), p.description(), p.is_enabled()))
+            .map(|p| (p.name(), p.description(), self.is_processor_enabled(p)))
             .collect()
     }
 
+    /// Enable or disable a registered processor by name
+    ///
+    /// The setting overrides the processor's own `is_enabled` until it's
+    /// changed again, so a processor can be toggled between runs without
+    /// rebuilding the pipeline. Every processor with the name is affected.
+    ///
+    /// # Returns
+    /// `false` if no processor with that name is registered
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        if self.get_processor_by_name(name).is_none() {
+            return false;
+        }
+        self.enabled_overrides.insert(name.to_string(), enabled);
+        true
+    }
+
+    /// Move the processor at `from` to position `to`, shifting the ones between
+    ///
+    /// # Returns
+    /// `false` if either index is out of range
+    pub fn move_processor(&mut self, from: usize, to: usize) -> bool {
+        if from >= self.processors.len() || to >= self.processors.len() {
+            return false;
+        }
+        let processor = self.processors.remove(from);
+        self.processors.insert(to, processor);
+        true
+    }
+
+    /// Swap the processors at positions `a` and `b`
+    ///
+    /// # Returns
+    /// `false` if either index is out of range
+    pub fn swap_processors(&mut self, a: usize, b: usize) -> bool {
+        if a >= self.processors.len() || b >= self.processors.len() {
+            return false;
+        }
+        self.processors.swap(a, b);
+        true
+    }
+
+    /// Remove every processor with the given name
+    ///
+    /// # Returns
+    /// `false` if no processor with that name is registered
+    pub fn remove_processor(&mut self, name: &str) -> bool {
+        let count = self.processors.len();
+        self.processors.retain(|p| p.name() != name);
+        self.enabled_overrides.remove(name);
+        self.processors.len() != count
+    }
+
+    /// Set the header and footer added to every processed program
+    ///
+    /// This runs once per program, after the processors, so the header and
+    /// footer lines are not themselves processed and the returned state does
+    /// not reflect them. Pass `None` to stop adding them.
+    pub fn set_header_footer(&mut self, header_footer: Option<HeaderFooterProcessor>) -> &mut Self {
+        self.header_footer = header_footer;
+        self
+    }
+
+    /// Whether a processor runs, taking `set_enabled` overrides into account
+    fn is_processor_enabled(&self, processor: &ProcessorHandle) -> bool {
+        self.enabled_overrides
+            .get(processor.name())
+            .copied()
+            .unwrap_or_else(|| processor.is_enabled())
+    }
+
     /// Process a single command through the entire pipeline
     ///
     /// Returns a vector of commands. Most processors return one command,
@@ -804,14 +1186,27 @@ impl ProcessorPipeline {
         &self,
         command: &GcodeCommand,
         state: &GcodeState,
+    ) -> Result<Vec<GcodeCommand>, String> {
+        self.process_command_counted(command, state, &mut [])
+    }
+
+    /// Process one command, adding per-processor in/out counts to `counts`
+    ///
+    /// `counts` is indexed like `self.processors`; an empty slice skips counting.
+    fn process_command_counted(
+        &self,
+        command: &GcodeCommand,
+        state: &GcodeState,
+        counts: &mut [(usize, usize)],
     ) -> Result<Vec<GcodeCommand>, String> {
         let mut current_commands = vec![command.clone()];
 
-        for processor in &self.processors {
-            if !processor.is_enabled() {
+        for (index, processor) in self.processors.iter().enumerate() {
+            if !self.is_processor_enabled(processor) {
                 continue;
             }
 
+            let input_count = current_commands.len();
             let mut next_commands = Vec::new();
 
             for cmd in current_commands {
@@ -831,6 +1226,10 @@ impl ProcessorPipeline {
                 }
             }
 
+            if let Some((input, output)) = counts.get_mut(index) {
+                *input += input_count;
+                *output += next_commands.len();
+            }
             current_commands = next_commands;
 
             // If no commands remain after processing, we can stop early
@@ -844,6 +1243,9 @@ impl ProcessorPipeline {
 
     /// Process a batch of commands through the pipeline
     ///
+    /// The commands are taken as a whole program: every processor is reset
+    /// before the first one.
+    ///
     /// # Arguments
     /// * `commands` - The commands to process
     /// * `state` - Current G-Code state (will be updated as commands are processed)
@@ -855,10 +1257,30 @@ impl ProcessorPipeline {
         commands: &[GcodeCommand],
         state: &mut GcodeState,
     ) -> Result<Vec<GcodeCommand>, String> {
+        self.process_commands_with_report(commands, state)
+            .map(|(results, _)| results)
+    }
+
+    /// Process a batch of commands and report what each processor did
+    ///
+    /// Like [`ProcessorPipeline::process_commands`], every processor is reset
+    /// first.
+    ///
+    /// # Returns
+    /// The processed commands and a `PipelineReport` with the number of
+    /// commands each enabled processor received and produced. A header and
+    /// footer set with `set_header_footer` is reported last as `header_footer`.
+    pub fn process_commands_with_report(
+        &self,
+        commands: &[GcodeCommand],
+        state: &mut GcodeState,
+    ) -> Result<(Vec<GcodeCommand>, PipelineReport), String> {
+        self.reset_processors();
         let mut results = Vec::new();
+        let mut counts = vec![(0, 0); self.processors.len()];
 
         for command in commands {
-            let processed = self.process_command(command, state)?;
+            let processed = self.process_command_counted(command, state, &mut counts)?;
 
             // Update state based on processed commands
             for cmd in &processed {
@@ -867,70 +1289,101 @@ impl ProcessorPipeline {
             }
         }
 
-        Ok(results)
+        let mut per_processor: Vec<(String, usize, usize)> = self
+            .processors
+            .iter()
+            .zip(counts)
+            .filter(|(processor, _)| self.is_processor_enabled(processor))
+            .map(|(processor, (input, output))| (processor.name().to_string(), input, output))
+            .collect();
+
+        if let Some(header_footer) = &self.header_footer {
+            let input = results.len();
+            results = header_footer.apply(&results);
+            per_processor.push(("header_footer".to_string(), input, results.len()));
+        }
+
+        Ok((results, PipelineReport { per_processor }))
     }
 
-    /// Update G-Code state based on a command
-    fn update_state(&self, command: &GcodeCommand, state: &mut GcodeState) -> Result<(), String> {
-        let cmd_upper = command.command.to_uppercase();
+    /// Process a G-Code file through the pipeline, reading it line by line
+    ///
+    /// Each line becomes a command carrying its 1-based `line_number`, and a
+    /// single `GcodeState` is threaded through the whole file, so a large
+    /// file never has to be loaded as one string. Every processor is reset
+    /// before the first line.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the G-Code file
+    ///
+    /// # Returns
+    /// The processed commands, or an error if the file can't be read or a
+    /// processor fails
+    pub fn process_file<P: AsRef<Path>>(&self, path: P) -> Result<Vec<GcodeCommand>, String> {
+        let path = path.as_ref();
+        let mut reader = FileStreamReader::new(path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        self.reset_processors();
+        let mut state = GcodeState::new();
+        let mut results = Vec::new();
 
-        // Motion mode
-        if cmd_upper.contains("G00") {
-            state.set_motion_mode(0)?;
-        } else if cmd_upper.contains("G01") {
-            state.set_motion_mode(1)?;
-        } else if cmd_upper.contains("G02") {
-            state.set_motion_mode(2)?;
-        } else if cmd_upper.contains("G03") {
-            state.set_motion_mode(3)?;
-        }
-
-        // Plane selection
-        if cmd_upper.contains("G17") {
-            state.set_plane_mode(17)?;
-        } else if cmd_upper.contains("G18") {
-            state.set_plane_mode(18)?;
-        } else if cmd_upper.contains("G19") {
-            state.set_plane_mode(19)?;
-        }
-
-        // Distance mode
-        if cmd_upper.contains("G90") {
-            state.set_distance_mode(90)?;
-        } else if cmd_upper.contains("G91") {
-            state.set_distance_mode(91)?;
-        }
-
-        // Feed rate mode
-        if cmd_upper.contains("G93") {
-            state.set_feed_rate_mode(93)?;
-        } else if cmd_upper.contains("G94") {
-            state.set_feed_rate_mode(94)?;
-        } else if cmd_upper.contains("G95") {
-            state.set_feed_rate_mode(95)?;
-        }
-
-        // Units
-        if cmd_upper.contains("G20") {
-            state.set_units_mode(20)?;
-        } else if cmd_upper.contains("G21") {
-            state.set_units_mode(21)?;
-        }
-
-        // Coordinate system (G54-G59)
-        for cs in 54..=59 {
-            if cmd_upper.contains(&format!("G{}", cs)) {
-                state.set_coordinate_system(cs as u8)?;
-                break;
+        while let Some(line) = reader.read_line() {
+            let mut command = GcodeCommand::new(&line);
+            command.set_line_number(reader.current_line_number() as u32);
+
+            for cmd in self.process_command(&command, &state)? {
+                self.update_state(&cmd, &mut state)?;
+                results.push(cmd);
             }
         }
 
-        Ok(())
+        if let Some(header_footer) = &self.header_footer {
+            results = header_footer.apply(&results);
+        }
+        Ok(results)
+    }
+
+    /// Process a whole program given as text
+    ///
+    /// Lines are numbered from 1 and run through the pipeline from a fresh
+    /// `GcodeState`, with every processor reset.
+    ///
+    /// # Returns
+    /// The processed program, one command per line
+    pub fn process_text(&self, text: &str) -> Result<String, String> {
+        let commands: Vec<GcodeCommand> = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| {
+                let mut command = GcodeCommand::new(line);
+                command.set_line_number(index as u32 + 1);
+                command
+            })
+            .collect();
+        let processed = self.process_commands(&commands, &mut GcodeState::new())?;
+        Ok(processed
+            .iter()
+            .map(|command| command.command.as_str())
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+
+    /// Reset every processor before a new program
+    fn reset_processors(&self) {
+        for processor in &self.processors {
+            processor.reset();
+        }
+    }
+
+    /// Update G-Code state based on a command
+    fn update_state(&self, command: &GcodeCommand, state: &mut GcodeState) -> Result<(), String> {
+        state.apply_words(&tokenize_words(&command.command))
     }
 
     /// Clear all processors from the pipeline
     pub fn clear(&mut self) {
         self.processors.clear();
+        self.enabled_overrides.clear();
     }
 
     /// Get mutable access to the pipeline configuration
@@ -966,6 +1419,35 @@ impl ProcessorRegistry {
         }
     }
 
+    /// Create a registry with the built-in processors that need no arguments
+    ///
+    /// Each is registered under its [`CommandProcessor::name`] with its
+    /// default settings.
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        registry
+            .register("whitespace", || Arc::new(WhitespaceProcessor::new()))
+            .register("comment", || Arc::new(CommentProcessor::new()))
+            .register("empty_line_remover", || {
+                Arc::new(EmptyLineRemoverProcessor::new())
+            })
+            .register("decimal", || Arc::new(DecimalProcessor::new()))
+            .register("trailing_zeros", || Arc::new(TrailingZeroProcessor::new()))
+            .register("arc_expander", || Arc::new(ArcExpander::new()))
+            .register("feed_override", || {
+                Arc::new(FeedRateOverrideProcessor::default())
+            })
+            .register("m30", || Arc::new(M30Processor::new()))
+            .register("translate", || {
+                Arc::new(transform::TranslateProcessor::default())
+            })
+            .register("renumber", || {
+                Arc::new(renumber::NRenumberProcessor::default())
+            })
+            .register("safe_z", || Arc::new(safe_z::SafeZProcessor::default()));
+        registry
+    }
+
     /// Register a processor factory
     pub fn register<F>(&mut self, name: impl Into<String>, factory: F) -> &mut Self
     where
@@ -998,6 +1480,18 @@ impl ProcessorRegistry {
         Ok(pipeline)
     }
 
+    /// Create a pipeline from a recipe
+    ///
+    /// A recipe lists processor names separated by commas or whitespace, e.g.
+    /// `"whitespace, comment, decimal"`, and runs them in that order.
+    pub fn create_recipe(&self, recipe: &str) -> Result<ProcessorPipeline, String> {
+        let names: Vec<&str> = recipe
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|name| !name.is_empty())
+            .collect();
+        self.create_pipeline(&names)
+    }
+
     /// List all registered processor names
     pub fn list_registered(&self) -> Vec<&str> {
         self.factories.keys().map(|s| s.as_str()).collect()
@@ -1029,6 +1523,112 @@ impl GcodeParser {
         Ok(command)
     }
 
+    /// Parse the words of a G-Code line without touching modal state
+    ///
+    /// Words may be run together (`G0X10Y-2.5`) and numbers may carry an
+    /// exponent (`X1.5e-3`); an `E` straight after a number is read as its
+    /// exponent rather than as a separate word. Comments are ignored.
+    pub fn parse_words(&self, line: &str) -> ParsedWords {
+        let code = strip_comments(line).to_ascii_uppercase();
+        let spans = word_spans(&code);
+        let mut words = ParsedWords::default();
+
+        let mut index = 0;
+        while index < spans.len() {
+            let (letter, span) = &spans[index];
+            let mut end = span.end;
+            // `1E-3` splits into `1` and an `E` word starting right after it
+            if let Some((exponent, next)) = spans.get(index + 1) {
+                if *exponent == 'E'
+                    && code[end..].starts_with('E')
+                    && next.start == end + 1
+                    && code[span.clone()].bytes().any(|b| b.is_ascii_digit())
+                {
+                    end = next.end;
+                    index += 1;
+                }
+            }
+            index += 1;
+
+            let Ok(value) = code[span.start..end].parse::<f64>() else {
+                continue;
+            };
+            match letter {
+                'X' => words.x = Some(value),
+                'Y' => words.y = Some(value),
+                'Z' => words.z = Some(value),
+                'I' => words.i = Some(value),
+                'J' => words.j = Some(value),
+                'K' => words.k = Some(value),
+                'R' => words.r = Some(value),
+                'F' => words.f = Some(value),
+                'S' => words.s = Some(value),
+                'G' => words.g_codes.push(value),
+                'M' => words.m_codes.push(value),
+                _ => {}
+            }
+        }
+
+        words
+    }
+
+    /// Check the syntax of a single line without changing any state
+    ///
+    /// Each word must be a letter followed by a number, optionally separated
+    /// by spaces. Words whose value is a `#n` parameter or `[...]` expression
+    /// are accepted as written. System commands (`$...`) and program
+    /// delimiters (`%`) are not checked.
+    ///
+    /// # Returns
+    /// One issue per problem found, in column order; empty for a clean line
+    pub fn validate_line(line: &str) -> Vec<LineIssue> {
+        let chars: Vec<char> = line.chars().collect();
+        let mut issues = Vec::new();
+
+        let first = chars.iter().position(|c| !c.is_whitespace());
+        if matches!(first.map(|i| chars[i]), Some('$' | '%')) {
+            return issues;
+        }
+
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            match c {
+                ';' => break,
+                '(' => match chars[i..].iter().position(|&c| c == ')') {
+                    Some(end) => i += end + 1,
+                    None => {
+                        issues.push(LineIssue {
+                            col: i,
+                            len: chars.len() - i,
+                            message: "Unclosed comment".to_string(),
+                        });
+                        break;
+                    }
+                },
+                _ if c.is_whitespace() => i += 1,
+                '#' => i = skip_parameter(&chars, i),
+                _ if c.is_ascii_alphabetic() => match check_word(&chars, i) {
+                    Ok(next) => i = next,
+                    Err(found) => {
+                        i = found.col + found.len;
+                        issues.push(found);
+                    }
+                },
+                _ => {
+                    issues.push(LineIssue {
+                        col: i,
+                        len: 1,
+                        message: format!("Unexpected character '{}'", c),
+                    });
+                    i += 1;
+                }
+            }
+        }
+
+        issues
+    }
+
     /// Remove comments from a G-Code line
     fn remove_comments(&self, line: &str) -> String {
         static COMMENT_REGEX: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
@@ -1058,145 +1658,1002 @@ impl GcodeParser {
 
     /// Update modal state based on parsed command
     fn update_modal_state(&mut self, command: &GcodeCommand) -> Result<(), String> {
-        let cmd_upper = command.command.to_uppercase();
+        self.current_state
+            .apply_words(&tokenize_words(&command.command))
+    }
 
-        // Parse G-codes
-        if cmd_upper.contains("G00") {
-            self.current_state.set_motion_mode(0)?;
-        } else if cmd_upper.contains("G01") {
-            self.current_state.set_motion_mode(1)?;
-        } else if cmd_upper.contains("G02") {
-            self.current_state.set_motion_mode(2)?;
-        } else if cmd_upper.contains("G03") {
-            self.current_state.set_motion_mode(3)?;
-        }
-
-        // Plane selection
-        if cmd_upper.contains("G17") {
-            self.current_state.set_plane_mode(17)?;
-        } else if cmd_upper.contains("G18") {
-            self.current_state.set_plane_mode(18)?;
-        } else if cmd_upper.contains("G19") {
-            self.current_state.set_plane_mode(19)?;
-        }
-
-        // Distance mode
-        if cmd_upper.contains("G90") {
-            self.current_state.set_distance_mode(90)?;
-        } else if cmd_upper.contains("G91") {
-            self.current_state.set_distance_mode(91)?;
-        }
-
-        // Feed rate mode
-        if cmd_upper.contains("G93") {
-            self.current_state.set_feed_rate_mode(93)?;
-        } else if cmd_upper.contains("G94") {
-            self.current_state.set_feed_rate_mode(94)?;
-        } else if cmd_upper.contains("G95") {
-            self.current_state.set_feed_rate_mode(95)?;
-        }
-
-        // Units
-        if cmd_upper.contains("G20") {
-            self.current_state.set_units_mode(20)?;
-        } else if cmd_upper.contains("G21") {
-            self.current_state.set_units_mode(21)?;
-        }
-
-        // Coordinate system (G54-G59)
-        for cs in 54..=59 {
-            if cmd_upper.contains(&format!("G{}", cs)) {
-                self.current_state.set_coordinate_system(cs as u8)?;
-                break;
-            }
-        }
+    /// Get command number generator
+    pub fn command_generator(&self) -> &CommandNumberGenerator {
+        &self.command_generator
+    }
+}
 
-        // Tool offset
-        if cmd_upper.contains("G43") {
-            self.current_state.set_tool_offset_mode(43)?;
-        } else if cmd_upper.contains("G49") {
-            self.current_state.set_tool_offset_mode(49)?;
-        }
+/// Look up the modal group a G or M word belongs to
+fn modal_group(letter: char, value: f64) -> Option<&'static str> {
+    // Compare in tenths so G61.1 and G38.2 are matched exactly
+    let code = (value * 10.0).round() as i64;
+    match (letter, code) {
+        ('G', 0 | 10 | 20 | 30 | 382..=385 | 800..=890) => Some("motion"),
+        ('G', 170..=190) => Some("plane"),
+        ('G', 900 | 910) => Some("distance mode"),
+        ('G', 901 | 911) => Some("arc distance mode"),
+        ('G', 930..=950) => Some("feed rate mode"),
+        ('G', 200 | 210) => Some("units"),
+        ('G', 400 | 410 | 420) => Some("cutter compensation"),
+        ('G', 430 | 431 | 490) => Some("tool length offset"),
+        ('G', 540..=590) => Some("coordinate system"),
+        ('G', 610 | 611 | 640) => Some("path control"),
+        ('G', 980 | 990) => Some("return mode"),
+        ('M', 0 | 10 | 20 | 300 | 600) => Some("stopping"),
+        ('M', 30 | 40 | 50) => Some("spindle"),
+        _ => None,
+    }
+}
 
-        // Cutter compensation
-        if cmd_upper.contains("G40") {
-            self.current_state.set_compensation_mode(40)?;
-        } else if cmd_upper.contains("G41") {
-            self.current_state.set_compensation_mode(41)?;
-        } else if cmd_upper.contains("G42") {
-            self.current_state.set_compensation_mode(42)?;
-        }
+/// Check whether tokenized words move the machine (G0-G3 or axis words)
+fn has_motion(words: &[(char, f64)]) -> bool {
+    words.iter().any(|&(letter, value)| {
+        matches!(letter, 'X' | 'Y' | 'Z' | 'A' | 'B' | 'C')
+            || (letter == 'G' && (0.0..=3.0).contains(&value) && value.fract() == 0.0)
+    })
+}
 
-        // Parse F value (feed rate)
-        if let Some(f_pos) = cmd_upper.find('F') {
-            let remaining = &command.command[f_pos + 1..];
-            if let Some(f_value) = remaining.split_whitespace().next() {
-                if let Ok(rate) = f_value.parse::<f64>() {
-                    self.current_state.set_feed_rate(rate)?;
-                }
-            }
-        }
+/// A logical operation within a G-Code program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Operation {
+    /// Name from an operation comment, or `Operation N` if there is none
+    pub name: String,
+    /// 0-based line indices covered by the operation (end exclusive)
+    pub line_range: std::ops::Range<usize>,
+    /// Tool number in use, if a tool has been selected
+    pub tool: Option<u32>,
+}
 
-        // Parse S value (spindle speed)
-        if let Some(s_pos) = cmd_upper.find('S') {
-            let remaining = &command.command[s_pos + 1..];
-            if let Some(s_value) = remaining.split_whitespace().next() {
-                if let Ok(speed) = s_value.parse::<f64>() {
-                    self.current_state.set_spindle_speed(speed)?;
-                }
-            }
+/// Split a program into logical operations
+///
+/// Operations are delimited by tool changes (`M6`) and operation comments such
+/// as `(Operation: Pocket)` or `; Operation: Pocket`. A boundary only starts a
+/// new operation once the current one contains motion, so a setup preamble or
+/// an operation comment directly followed by a tool change stays part of the
+/// operation it introduces. Every line belongs to exactly one operation.
+///
+/// # Arguments
+/// * `src` - G-Code program text
+///
+/// # Returns
+/// The operations in program order, empty if the program has no lines
+pub fn split_operations(src: &str) -> Vec<Operation> {
+    static OPERATION_REGEX: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+    let regex = OPERATION_REGEX
+        .get_or_init(|| Regex::new(r"(?i)[;(]\s*operation\s*:\s*([^)]*)").unwrap());
+
+    let mut operations: Vec<Operation> = Vec::new();
+    let mut current: Option<Operation> = None;
+    let mut has_moved = false;
+    let mut selected_tool: Option<u32> = None;
+    let mut line_count = 0;
+
+    for (index, line) in src.lines().enumerate() {
+        line_count = index + 1;
+        let words = tokenize_words(line);
+        if let Some(&(_, value)) = words.iter().find(|(letter, _)| *letter == 'T') {
+            selected_tool = Some(value as u32);
         }
-
-        // Parse T value (tool number)
-        if let Some(t_pos) = cmd_upper.find('T') {
-            let remaining = &command.command[t_pos + 1..];
-            if let Some(t_value) = remaining.split_whitespace().next() {
-                if let Ok(tool) = t_value.parse::<u16>() {
-                    self.current_state.set_tool_number(tool);
+        let tool_change = words.contains(&('M', 6.0));
+        let name = regex
+            .captures(line)
+            .map(|caps| caps[1].trim().to_string())
+            .filter(|name| !name.is_empty());
+
+        if tool_change || name.is_some() {
+            if has_moved {
+                if let Some(mut finished) = current.take() {
+                    finished.line_range.end = index;
+                    operations.push(finished);
                 }
+                has_moved = false;
+            }
+            let operation = current.get_or_insert_with(|| Operation {
+                name: String::new(),
+                line_range: index..index,
+                tool: selected_tool,
+            });
+            if let Some(name) = name {
+                operation.name = name;
+            }
+            if tool_change {
+                operation.tool = selected_tool;
             }
         }
 
-        Ok(())
+        let operation = current.get_or_insert_with(|| Operation {
+            name: String::new(),
+            line_range: index..index,
+            tool: selected_tool,
+        });
+        if operation.tool.is_none() {
+            operation.tool = selected_tool;
+        }
+        has_moved |= has_motion(&words);
     }
 
-    /// Get command number generator
-    pub fn command_generator(&self) -> &CommandNumberGenerator {
-        &self.command_generator
+    if let Some(mut last) = current {
+        last.line_range.end = line_count;
+        operations.push(last);
     }
-}
-
-// ============================================================================
-// Basic Preprocessor Implementations - Task 14
-// ============================================================================
-
-/// Removes leading and trailing whitespace from G-code commands
-///
-/// Processes each line by trimming whitespace while preserving the command logic.
-/// This is typically the first preprocessor in the pipeline.
-#[derive(Debug, Clone)]
-pub struct WhitespaceProcessor;
 
-impl WhitespaceProcessor {
-    /// Create a new whitespace processor
-    pub fn new() -> Self {
-        Self
+    for (i, operation) in operations.iter_mut().enumerate() {
+        if operation.name.is_empty() {
+            operation.name = format!("Operation {}", i + 1);
+        }
     }
+
+    operations
 }
 
-impl Default for WhitespaceProcessor {
-    fn default() -> Self {
-        Self::new()
-    }
+/// The commands run with one tool loaded, from `split_by_tool`
+#[derive(Debug, Clone)]
+pub struct ToolSegment {
+    /// Tool selected by the last `T` word before the segment's `M6`, or 0
+    /// for the commands before the first tool change
+    pub tool_number: u16,
+    /// Commands in program order, starting with the `M6` that loads the tool
+    pub commands: Vec<GcodeCommand>,
 }
 
-impl CommandProcessor for WhitespaceProcessor {
-    fn name(&self) -> &str {
-        "whitespace"
+/// Split a program at each tool change (`M6`)
+///
+/// For machines without a tool changer, so one tool's cuts can be run before
+/// pausing for a manual change. Each `M6` starts a new segment tagged with
+/// the tool from the `T` word on or before it; commands before the first
+/// `M6`, if any, form a segment of their own.
+///
+/// # Returns
+/// The segments in program order, empty if there are no commands
+pub fn split_by_tool(commands: &[GcodeCommand]) -> Vec<ToolSegment> {
+    let mut segments = Vec::new();
+    let mut current = ToolSegment {
+        tool_number: 0,
+        commands: Vec::new(),
+    };
+    let mut selected_tool = 0;
+
+    for command in commands {
+        let words = tokenize_words(&command.command);
+        if let Some(&(_, value)) = words.iter().rfind(|(letter, _)| *letter == 'T') {
+            selected_tool = value as u16;
+        }
+        if words.contains(&('M', 6.0)) {
+            let next = ToolSegment {
+                tool_number: selected_tool,
+                commands: Vec::new(),
+            };
+            let finished = std::mem::replace(&mut current, next);
+            if !finished.commands.is_empty() {
+                segments.push(finished);
+            }
+        }
+        current.commands.push(command.clone());
     }
 
-    fn description(&self) -> &str {
-        "Removes leading and trailing whitespace from G-code commands"
+    if !current.commands.is_empty() {
+        segments.push(current);
+    }
+    segments
+}
+
+/// Check that every rapid traverse after a plunge happens above the stock
+///
+/// A cutting move (`G1`/`G2`/`G3`) that ends below `stock_top` leaves the tool
+/// in the material. Until a later move lifts it back above `stock_top`, any
+/// `G0` with an X or Y word that starts or ends at or below the stock top is
+/// reported, which catches CAM output that forgets to retract between
+/// features. Absolute (`G90`) and incremental (`G91`) Z words are tracked;
+/// Z is unknown until the first absolute Z word.
+///
+/// # Arguments
+/// * `src` - G-Code program text
+/// * `stock_top` - Z of the stock surface in program units
+///
+/// # Returns
+/// One error issue per offending rapid, with 1-based line numbers
+pub fn check_rapid_retracts(src: &str, stock_top: f64) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let mut motion: Option<u32> = None;
+    let mut incremental = false;
+    let mut z: Option<f64> = None;
+    // Line number of the cutting move that left the tool below the stock top
+    let mut plunged_at: Option<u32> = None;
+
+    for (index, line) in src.lines().enumerate() {
+        let line_number = index as u32 + 1;
+        let words = tokenize_words(line);
+
+        for &(letter, value) in &words {
+            if letter != 'G' || value.fract() != 0.0 {
+                continue;
+            }
+            match value as u32 {
+                code @ 0..=3 => motion = Some(code),
+                80 => motion = None,
+                90 => incremental = false,
+                91 => incremental = true,
+                _ => {}
+            }
+        }
+
+        let start_z = z;
+        if let Some(&(_, value)) = words.iter().find(|(letter, _)| *letter == 'Z') {
+            z = if incremental {
+                z.map(|current| current + value)
+            } else {
+                Some(value)
+            };
+        }
+        let has_xy = words.iter().any(|(letter, _)| matches!(letter, 'X' | 'Y'));
+        let has_axis = has_xy || words.iter().any(|(letter, _)| *letter == 'Z');
+        if !has_axis {
+            continue;
+        }
+
+        match (motion, plunged_at) {
+            (Some(0), Some(plunge_line)) if has_xy => {
+                let low = [start_z, z]
+                    .into_iter()
+                    .flatten()
+                    .fold(f64::INFINITY, f64::min);
+                if low <= stock_top {
+                    issues.push(
+                        ValidationIssue::new(
+                            line_number,
+                            ValidationSeverity::Error,
+                            format!(
+                                "Rapid move at Z{} without retracting after line {}",
+                                low, plunge_line
+                            ),
+                        )
+                        .with_suggestion(format!(
+                            "Retract above the stock top (Z{}) before rapid moves",
+                            stock_top
+                        )),
+                    );
+                }
+            }
+            (Some(1..=3), None) if z.is_some_and(|z| z < stock_top) => {
+                plunged_at = Some(line_number);
+            }
+            _ => {}
+        }
+
+        if z.is_some_and(|z| z > stock_top) {
+            plunged_at = None;
+        }
+    }
+
+    issues
+}
+
+/// Limits on how fast a feed move may descend
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlungeLimits {
+    /// Largest plunge rate as a fraction of the XY feed rate
+    pub max_ratio: f64,
+    /// Largest plunge rate in program units per minute, if any
+    pub max_rate: Option<f64>,
+}
+
+impl Default for PlungeLimits {
+    fn default() -> Self {
+        Self {
+            max_ratio: 0.5,
+            max_rate: None,
+        }
+    }
+}
+
+/// Check that feed moves don't plunge into the work too fast
+///
+/// The plunge rate of a `G1` move is the downward part of its feed: the feed
+/// rate scaled by how much of the move's length is Z descent, so a shallow
+/// ramp plunges far slower than a straight drop at the same feed. It is
+/// compared with the XY feed rate, taken from the closest cutting move with
+/// XY motion before the plunge, or after it if the program plunges first.
+/// Absolute (`G90`) and incremental (`G91`) moves are tracked from the
+/// origin, and moves in inverse time (`G93`) or with a descent under
+/// `0.01` units aren't checked.
+///
+/// # Arguments
+/// * `src` - G-Code program text
+/// * `limits` - Allowed plunge rate relative to the XY feed, and absolutely
+///
+/// # Returns
+/// One warning issue per offending move, with 1-based line numbers
+pub fn check_plunge_rates(src: &str, limits: &PlungeLimits) -> Vec<ValidationIssue> {
+    const MIN_DESCENT: f64 = 0.01;
+
+    struct Cut {
+        line_number: u32,
+        feed: f64,
+        xy: f64,
+        descent: f64,
+        length: f64,
+    }
+
+    let mut cuts = Vec::new();
+    let mut motion: Option<u32> = None;
+    let mut incremental = false;
+    let mut inverse_time = false;
+    let mut feed: Option<f64> = None;
+    let mut position = [0.0f64; 3];
+
+    for (index, line) in src.lines().enumerate() {
+        let words = tokenize_words(line);
+        for &(letter, value) in &words {
+            match letter {
+                'G' if value.fract() == 0.0 => match value as u32 {
+                    code @ 0..=3 => motion = Some(code),
+                    80 => motion = None,
+                    90 => incremental = false,
+                    91 => incremental = true,
+                    93 => inverse_time = true,
+                    94 => inverse_time = false,
+                    _ => {}
+                },
+                'F' => feed = Some(value),
+                _ => {}
+            }
+        }
+
+        let start = position;
+        let mut moved = false;
+        for (axis, letter) in ['X', 'Y', 'Z'].into_iter().enumerate() {
+            if let Some(&(_, value)) = words.iter().find(|(l, _)| *l == letter) {
+                position[axis] = if incremental {
+                    position[axis] + value
+                } else {
+                    value
+                };
+                moved = true;
+            }
+        }
+        if !moved || motion != Some(1) || inverse_time {
+            continue;
+        }
+        let Some(feed) = feed else {
+            continue;
+        };
+
+        let xy = (position[0] - start[0]).hypot(position[1] - start[1]);
+        let dz = position[2] - start[2];
+        cuts.push(Cut {
+            line_number: index as u32 + 1,
+            feed,
+            xy,
+            descent: -dz.min(0.0),
+            length: xy.hypot(dz),
+        });
+    }
+
+    let mut issues = Vec::new();
+    for (i, cut) in cuts.iter().enumerate() {
+        if cut.descent < MIN_DESCENT {
+            continue;
+        }
+        let rate = cut.feed * cut.descent / cut.length;
+        let xy_feed = cuts[..i]
+            .iter()
+            .rev()
+            .chain(&cuts[i + 1..])
+            .find(|other| other.xy > 0.0 && other.descent == 0.0)
+            .map(|other| other.feed);
+
+        let limit = [
+            xy_feed.map(|xy_feed| xy_feed * limits.max_ratio),
+            limits.max_rate,
+        ]
+        .into_iter()
+        .flatten()
+        .fold(f64::INFINITY, f64::min);
+        if rate > limit {
+            issues.push(
+                ValidationIssue::new(
+                    cut.line_number,
+                    ValidationSeverity::Warning,
+                    format!(
+                        "Plunge rate of {:.0} exceeds the safe rate of {:.0}",
+                        rate, limit
+                    ),
+                )
+                .with_suggestion("Slow the plunge feed or ramp into the cut"),
+            );
+        }
+    }
+
+    issues
+}
+
+/// Lines appended by `append_program_end`: spindle and coolant off, then end
+pub const PROGRAM_END_BLOCK: [&str; 3] = ["M5", "M9", "M30"];
+
+/// How a program finishes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgramEnd {
+    /// The program has an `M2` or `M30`
+    Present,
+    /// No `M2`/`M30`, but the last motion is a rapid so the job looks finished
+    Missing,
+    /// No `M2`/`M30` and the last motion is a cut, as in a truncated file or
+    /// a fragment meant to be followed by another
+    MidOperation,
+}
+
+/// Classify how a program ends
+pub fn program_end(src: &str) -> ProgramEnd {
+    let mut motion: Option<u32> = None;
+    let mut last_motion: Option<u32> = None;
+
+    for line in src.lines() {
+        let words = tokenize_words(line);
+        if words
+            .iter()
+            .any(|&(letter, value)| letter == 'M' && (value == 2.0 || value == 30.0))
+        {
+            return ProgramEnd::Present;
+        }
+
+        for &(letter, value) in &words {
+            if letter == 'G' && value.fract() == 0.0 {
+                match value as u32 {
+                    code @ 0..=3 => motion = Some(code),
+                    80 => motion = None,
+                    _ => {}
+                }
+            }
+        }
+        if has_motion(&words) {
+            last_motion = motion;
+        }
+    }
+
+    match last_motion {
+        Some(1..=3) => ProgramEnd::MidOperation,
+        _ => ProgramEnd::Missing,
+    }
+}
+
+/// Check that a program ends with `M2` or `M30`
+///
+/// Without a program end the controller is never told the job is over. A
+/// program that otherwise finished cleanly is reported as an error that
+/// `append_program_end` can fix; one that stops in the middle of a cut only
+/// gets a warning, since appending an end there would hide a truncated file.
+///
+/// # Arguments
+/// * `src` - G-Code program text
+///
+/// # Returns
+/// At most one issue, on the last line of the program
+pub fn check_program_end(src: &str) -> Vec<ValidationIssue> {
+    let line_number = src.lines().count().max(1) as u32;
+    match program_end(src) {
+        ProgramEnd::Present => Vec::new(),
+        ProgramEnd::Missing => vec![ValidationIssue::new(
+            line_number,
+            ValidationSeverity::Error,
+            "Program has no program end (M2/M30)",
+        )
+        .with_suggestion(format!("Append {}", PROGRAM_END_BLOCK.join(" ")))],
+        ProgramEnd::MidOperation => vec![ValidationIssue::new(
+            line_number,
+            ValidationSeverity::Warning,
+            "Program ends mid-cut without a program end (M2/M30)",
+        )
+        .with_suggestion("Check that the file is complete before adding M30")],
+    }
+}
+
+/// Append `PROGRAM_END_BLOCK` to a program that has no `M2`/`M30`
+///
+/// Programs that end mid-operation are only repaired when `force` is set.
+///
+/// # Arguments
+/// * `src` - G-Code program text
+/// * `force` - Also repair programs whose last motion is a cut
+///
+/// # Returns
+/// The repaired program, or `None` if it was left unchanged
+pub fn append_program_end(src: &str, force: bool) -> Option<String> {
+    match program_end(src) {
+        ProgramEnd::Present => return None,
+        ProgramEnd::MidOperation if !force => return None,
+        _ => {}
+    }
+
+    let mut repaired = src.to_string();
+    if !repaired.is_empty() && !repaired.ends_with('\n') {
+        repaired.push('\n');
+    }
+    for line in PROGRAM_END_BLOCK {
+        repaired.push_str(line);
+        repaired.push('\n');
+    }
+    Some(repaired)
+}
+
+/// Find the first motion line when no distance mode has been set by then
+///
+/// Returns its 0-based line index, or `None` if a `G90`/`G91` comes first
+/// (the same line counts) or the program never moves.
+fn motion_without_distance_mode(src: &str) -> Option<usize> {
+    for (index, line) in src.lines().enumerate() {
+        let words = tokenize_words(line);
+        if words
+            .iter()
+            .any(|&(letter, value)| letter == 'G' && (value == 90.0 || value == 91.0))
+        {
+            return None;
+        }
+        if has_motion(&words) {
+            return Some(index);
+        }
+    }
+    None
+}
+
+/// Check that a program sets `G90` or `G91` before it first moves
+///
+/// A program that never sets the distance mode runs in whatever mode the
+/// controller powered up in, or was left in by the last job.
+///
+/// # Arguments
+/// * `src` - G-Code program text
+///
+/// # Returns
+/// At most one warning, on the first motion line
+pub fn check_distance_mode(src: &str) -> Vec<ValidationIssue> {
+    motion_without_distance_mode(src)
+        .map(|index| {
+            ValidationIssue::new(
+                index as u32 + 1,
+                ValidationSeverity::Warning,
+                "Motion before any distance mode (G90/G91) is set",
+            )
+            .with_suggestion("Insert G90 before the first move")
+        })
+        .into_iter()
+        .collect()
+}
+
+/// Insert a `G90` line before the first move of a program that sets no
+/// distance mode before it
+///
+/// Header lines such as `%` and comments stay where they are, and the
+/// inserted line uses the program's own line ending.
+///
+/// # Arguments
+/// * `src` - G-Code program text
+///
+/// # Returns
+/// The repaired program, or `None` if it was left unchanged
+pub fn insert_distance_mode(src: &str) -> Option<String> {
+    let index = motion_without_distance_mode(src)?;
+
+    let mut repaired = String::with_capacity(src.len() + 5);
+    for (i, line) in src.split_inclusive('\n').enumerate() {
+        if i == index {
+            repaired.push_str("G90");
+            repaired.push_str(if line.ends_with("\r\n") { "\r\n" } else { "\n" });
+        }
+        repaired.push_str(line);
+    }
+    Some(repaired)
+}
+
+/// Find the lines of moves that end where they start
+///
+/// Returns 0-based line indices. See `check_zero_length_moves`.
+fn zero_length_moves(src: &str, tolerance: f64) -> Vec<usize> {
+    let mut moves = Vec::new();
+    let mut motion: Option<u32> = None;
+    let mut incremental = false;
+    // X, Y and Z, unknown until first set in absolute mode
+    let mut position: [Option<f64>; 3] = [None; 3];
+
+    for (index, line) in src.lines().enumerate() {
+        let words = tokenize_words(line);
+        let line_motion = words
+            .iter()
+            .filter(|&&(letter, value)| letter == 'G' && value.fract() == 0.0)
+            .map(|&(_, value)| value as u32)
+            .rfind(|code| *code <= 3);
+        let modal_motion = motion;
+        let mut removable = true;
+        let mut known = true;
+
+        for &(letter, value) in &words {
+            match letter {
+                'G' if value.fract() == 0.0 && value <= 3.0 => motion = Some(value as u32),
+                'G' if value == 80.0 => motion = None,
+                'G' if value == 90.0 => incremental = false,
+                'G' if value == 91.0 => incremental = true,
+                // Machine coordinates, reference returns and offset changes
+                // leave the program position unknown
+                'G' if [10.0, 28.0, 30.0, 53.0, 92.0].contains(&value) => known = false,
+                'X' | 'Y' | 'Z' | 'N' => {}
+                // Dwells, feeds, spindle words, arcs and anything else make
+                // the line more than a move
+                _ => removable = false,
+            }
+        }
+
+        if !known {
+            position = [None; 3];
+            continue;
+        }
+
+        let mut zero_length = true;
+        let mut has_axis = false;
+        for (axis, letter) in ['X', 'Y', 'Z'].into_iter().enumerate() {
+            let Some(&(_, value)) = words.iter().rev().find(|(l, _)| *l == letter) else {
+                continue;
+            };
+            has_axis = true;
+            let start = position[axis];
+            let end = if incremental {
+                start.map(|p| p + value)
+            } else {
+                Some(value)
+            };
+            let unchanged = if incremental {
+                value.abs() <= tolerance
+            } else {
+                start.is_some_and(|p| (p - value).abs() <= tolerance)
+            };
+            zero_length &= unchanged;
+            position[axis] = end;
+        }
+
+        // Dropping a line that changes the motion mode would change how
+        // later modal lines move
+        let keeps_mode = line_motion.is_none() || line_motion == modal_motion;
+        if removable && has_axis && zero_length && keeps_mode && motion.is_some() {
+            moves.push(index);
+        }
+    }
+
+    moves
+}
+
+/// Check for moves whose endpoint equals the current position
+///
+/// Repeating the current coordinates, such as a second `X10` while already
+/// at X10, wastes time and confuses some controllers. Absolute (`G90`) and
+/// incremental (`G91`) moves are tracked; a position is unknown until first
+/// set in absolute mode. Only lines made up of a motion mode and X, Y, Z or
+/// N words are considered, so dwells, arcs and lines that also set a feed or
+/// spindle speed are never reported, nor are lines that change the motion
+/// mode.
+///
+/// # Arguments
+/// * `src` - G-Code program text
+/// * `tolerance` - Largest axis change still treated as no move, in program units
+///
+/// # Returns
+/// One warning per zero-length move, with 1-based line numbers
+pub fn check_zero_length_moves(src: &str, tolerance: f64) -> Vec<ValidationIssue> {
+    zero_length_moves(src, tolerance)
+        .into_iter()
+        .map(|index| {
+            ValidationIssue::new(
+                index as u32 + 1,
+                ValidationSeverity::Warning,
+                "Move ends at its start position",
+            )
+            .with_suggestion("Remove the zero-length move")
+        })
+        .collect()
+}
+
+/// Remove the moves reported by `check_zero_length_moves`
+///
+/// A comment on a removed line is kept on a line of its own.
+///
+/// # Arguments
+/// * `src` - G-Code program text
+/// * `tolerance` - Largest axis change still treated as no move, in program units
+///
+/// # Returns
+/// The optimized program, or `None` if it had no zero-length moves
+pub fn remove_zero_length_moves(src: &str, tolerance: f64) -> Option<String> {
+    let moves = zero_length_moves(src, tolerance);
+    if moves.is_empty() {
+        return None;
+    }
+
+    let mut optimized = String::with_capacity(src.len());
+    let mut moves = moves.into_iter().peekable();
+    for (index, line) in src.lines().enumerate() {
+        if moves.next_if_eq(&index).is_some() {
+            let comment = line[line.find([';', '(']).unwrap_or(line.len())..].trim();
+            if comment.is_empty() {
+                continue;
+            }
+            optimized.push_str(comment);
+        } else {
+            optimized.push_str(line);
+        }
+        optimized.push('\n');
+    }
+    if !src.ends_with('\n') {
+        optimized.pop();
+    }
+    Some(optimized)
+}
+
+/// Check that the arc distance mode is set before the first `I J K` arc
+///
+/// `I J K` are offsets from the arc's start after `G91.1` and the center
+/// itself after `G90.1`. Controllers default to offsets, but a program
+/// written for absolute centers that never says so cuts the wrong arcs, so
+/// an arc using `I J K` before either word appears is reported. `R` arcs
+/// don't depend on the mode.
+///
+/// # Arguments
+/// * `src` - G-Code program text
+///
+/// # Returns
+/// At most one warning, on the first arc that relies on the default mode
+pub fn check_arc_center_mode(src: &str) -> Vec<ValidationIssue> {
+    let mut motion: Option<u32> = None;
+
+    for (index, line) in src.lines().enumerate() {
+        let words = tokenize_words(line);
+        for &(letter, value) in &words {
+            if letter != 'G' {
+                continue;
+            }
+            match (value * 10.0).round() as i64 {
+                901 | 911 => return Vec::new(),
+                code @ (0 | 10 | 20 | 30) => motion = Some((code / 10) as u32),
+                800 => motion = None,
+                _ => {}
+            }
+        }
+
+        let has_center = words
+            .iter()
+            .any(|(letter, _)| matches!(letter, 'I' | 'J' | 'K'));
+        if matches!(motion, Some(2 | 3)) && has_center {
+            return vec![ValidationIssue::new(
+                index as u32 + 1,
+                ValidationSeverity::Warning,
+                "Arc uses I/J/K before the arc distance mode (G90.1/G91.1) is set",
+            )
+            .with_suggestion(
+                "Add G91.1 for offsets from the start, or G90.1 for absolute centers",
+            )];
+        }
+    }
+
+    Vec::new()
+}
+
+/// Scale a line's feed rate (`F`) and spindle speed (`S`) words by a cut factor
+///
+/// Used when streaming a cautious first run, e.g. everything at half feed and
+/// spindle speed. Unlike real-time overrides the words themselves are
+/// rewritten, so the factor applies on any controller. Comments and
+/// controller system commands (`$` lines) are left alone.
+///
+/// # Arguments
+/// * `line` - G-Code line to transmit
+/// * `factor` - Multiplier for F and S words, e.g. 0.5 for half speed
+///
+/// # Returns
+/// The line with each F and S value multiplied by `factor`
+pub fn apply_cut_factor(line: &str, factor: f64) -> String {
+    if factor == 1.0 || line.trim_start().starts_with('$') {
+        return line.to_string();
+    }
+    let code = &line[..line.find([';', '(']).unwrap_or(line.len())];
+
+    let mut result = String::with_capacity(line.len());
+    let mut last = 0;
+    for (letter, span) in word_spans(code) {
+        if letter != 'F' && letter != 'S' {
+            continue;
+        }
+        let Ok(value) = code[span.clone()].parse::<f64>() else {
+            continue;
+        };
+        let scaled = format!("{:.4}", value * factor);
+        result.push_str(&line[last..span.start]);
+        result.push_str(&TrailingZeroProcessor::normalize_number(&scaled, 0));
+        last = span.end;
+    }
+    result.push_str(&line[last..]);
+    result
+}
+
+/// Check the word whose letter is at `start`
+///
+/// Returns the index after the word, or the issue spanning it.
+fn check_word(chars: &[char], start: usize) -> Result<usize, LineIssue> {
+    let letter = chars[start].to_ascii_uppercase();
+    let mut value = start + 1;
+    while value < chars.len() && chars[value].is_whitespace() {
+        value += 1;
+    }
+    match chars.get(value) {
+        Some('#') => return Ok(skip_parameter(chars, value)),
+        Some('[') => {
+            return bracket_end(chars, value).ok_or_else(|| LineIssue {
+                col: value,
+                len: chars.len() - value,
+                message: "Unclosed '['".to_string(),
+            })
+        }
+        _ => {}
+    }
+
+    let mut end = value;
+    while end < chars.len() && matches!(chars[end], '0'..='9' | '.' | '-' | '+') {
+        end += 1;
+    }
+    let number: String = chars[value..end].iter().collect();
+    if number.is_empty() {
+        Err(LineIssue {
+            col: start,
+            len: 1,
+            message: format!("Missing value for '{}'", letter),
+        })
+    } else if number.parse::<f64>().is_err() {
+        Err(LineIssue {
+            col: start,
+            len: end - start,
+            message: format!("Invalid number '{}' for '{}'", number, letter),
+        })
+    } else {
+        Ok(end)
+    }
+}
+
+/// Skip a `#n` parameter reference or `#n=value` assignment starting at
+/// `start`, returning the index after it
+fn skip_parameter(chars: &[char], start: usize) -> usize {
+    let mut end = start + 1;
+    while end < chars.len() && chars[end].is_ascii_digit() {
+        end += 1;
+    }
+    if chars.get(end) != Some(&'=') {
+        return end;
+    }
+    end += 1;
+    while end < chars.len() && chars[end].is_whitespace() {
+        end += 1;
+    }
+    if chars.get(end) == Some(&'[') {
+        // An unclosed expression runs to the end of the line
+        return bracket_end(chars, end).unwrap_or(chars.len());
+    }
+    while end < chars.len() && matches!(chars[end], '0'..='9' | '.' | '-' | '+') {
+        end += 1;
+    }
+    end
+}
+
+/// Index after the `]` matching the `[` at `start`, if it is closed
+fn bracket_end(chars: &[char], start: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, &c) in chars.iter().enumerate().skip(start) {
+        match c {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Remove comments (`;` to end of line and `( ... )`) from a G-Code line
+///
+/// The remaining code is returned unchanged, including its whitespace.
+pub fn strip_comments(line: &str) -> String {
+    let mut code = String::with_capacity(line.len());
+    let mut in_paren = false;
+    for c in line.chars() {
+        match c {
+            ';' if !in_paren => break,
+            '(' => in_paren = true,
+            ')' => in_paren = false,
+            _ if !in_paren => code.push(c),
+            _ => {}
+        }
+    }
+    code
+}
+
+/// Split a G-Code line into (letter, value) words
+///
+/// Comments (`;` to end of line and `( ... )`) are stripped first. Letters are
+/// uppercased and values parsed as numbers, so `g00` and `G0` both yield
+/// `('G', 0.0)`. Words without a numeric value are ignored.
+pub fn tokenize_words(line: &str) -> Vec<(char, f64)> {
+    let code = strip_comments(line).to_ascii_uppercase();
+
+    word_spans(&code)
+        .into_iter()
+        .filter_map(|(letter, span)| code[span].parse::<f64>().ok().map(|value| (letter, value)))
+        .collect()
+}
+
+/// Find the words in comment-free code as (uppercased letter, number span)
+///
+/// The span is the byte range of the word's number text within `code`, which
+/// lets callers rewrite numbers without disturbing the rest of the line.
+pub(crate) fn word_spans(code: &str) -> Vec<(char, Range<usize>)> {
+    let mut words = Vec::new();
+    let mut chars = code.char_indices().peekable();
+
+    while let Some((_, letter)) = chars.next() {
+        if !letter.is_ascii_alphabetic() {
+            continue;
+        }
+        let mut span: Option<Range<usize>> = None;
+        while let Some(&(i, c)) = chars.peek() {
+            if c.is_ascii_digit() || c == '.' || c == '-' || c == '+' {
+                span = Some(span.map_or(i..i + 1, |s| s.start..i + 1));
+                chars.next();
+            } else if c.is_whitespace() && span.is_none() {
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        if let Some(span) = span {
+            words.push((letter.to_ascii_uppercase(), span));
+        }
+    }
+
+    words
+}
+
+// ============================================================================
+// Basic Preprocessor Implementations - Task 14
+// ============================================================================
+
+/// Removes leading and trailing whitespace from G-code commands
+///
+/// Processes each line by trimming whitespace while preserving the command logic.
+/// This is typically the first preprocessor in the pipeline.
+#[derive(Debug, Clone)]
+pub struct WhitespaceProcessor;
+
+impl WhitespaceProcessor {
+    /// Create a new whitespace processor
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for WhitespaceProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommandProcessor for WhitespaceProcessor {
+    fn name(&self) -> &str {
+        "whitespace"
+    }
+
+    fn description(&self) -> &str {
+        "Removes leading and trailing whitespace from G-code commands"
     }
 
     fn process(
@@ -1257,30 +2714,8 @@ impl CommandProcessor for CommentProcessor {
         command: &GcodeCommand,
         _state: &GcodeState,
     ) -> Result<Vec<GcodeCommand>, String> {
-        let mut cmd = command.command.clone();
-
-        // Remove parenthesized comments
-        while let Some(start) = cmd.find('(') {
-            if let Some(end) = cmd.find(')') {
-                if end > start {
-                    cmd.remove(end);
-                    cmd.remove(start);
-                } else {
-                    break;
-                }
-            } else {
-                // Unmatched parenthesis - remove from start to end of line
-                cmd.truncate(start);
-                break;
-            }
-        }
-
-        // Remove semicolon comments (everything after first semicolon)
-        if let Some(pos) = cmd.find(';') {
-            cmd.truncate(pos);
-        }
-
-        let trimmed = cmd.trim().to_string();
+        // An unmatched '(' comments out the rest of the line
+        let trimmed = strip_comments(&command.command).trim().to_string();
 
         if trimmed.is_empty() {
             Ok(vec![])
@@ -1415,7 +2850,13 @@ impl CommandProcessor for CommandLengthProcessor {
 ///
 /// Floating-point representation can lead to imprecise coordinates.
 /// This processor rounds decimal values to a specified number of decimal places.
-/// For example: X10.123456789 might become X10.12345
+/// For example: X10.123456789 might become X10.12346
+///
+/// Only the arguments of axis and parameter words (X Y Z A B C I J K R F)
+/// are rounded. G, M, N, T and S words and comments keep their original text,
+/// so `G01` stays `G01`. Rounded values drop trailing zeros unless
+/// `preserve_trailing_zeros` is set, in which case values already within the
+/// precision are left as written and rounded ones keep all `precision` places.
 #[derive(Debug, Clone)]
 pub struct DecimalProcessor {
     config: ProcessorConfig,
@@ -1434,10 +2875,39 @@ impl DecimalProcessor {
         Self { config }
     }
 
+    /// Keep trailing zeros instead of trimming rounded values
+    pub fn with_preserve_trailing_zeros(mut self, preserve: bool) -> Self {
+        self.config = self
+            .config
+            .with_option("preserve_trailing_zeros", preserve.to_string());
+        self
+    }
+
     fn round_coordinate(&self, value: f64, precision: u32) -> f64 {
         let multiplier = 10_f64.powi(precision as i32);
         (value * multiplier).round() / multiplier
     }
+
+    /// Round one word argument, or `None` to leave it as written
+    fn format_argument(&self, text: &str, precision: u32, preserve: bool) -> Option<String> {
+        let value = text.parse::<f64>().ok()?;
+        let decimals = text.find('.').map_or(0, |dot| text.len() - dot - 1);
+        if preserve && decimals <= precision as usize {
+            return None;
+        }
+
+        let mut rounded = self.round_coordinate(value, precision);
+        if rounded == 0.0 {
+            // Avoid writing `-0`
+            rounded = 0.0;
+        }
+        let fixed = format!("{:.*}", precision as usize, rounded);
+        if preserve {
+            Some(fixed)
+        } else {
+            Some(TrailingZeroProcessor::normalize_number(&fixed, 0))
+        }
+    }
 }
 
 impl Default for DecimalProcessor {
@@ -1465,44 +2935,142 @@ impl CommandProcessor for DecimalProcessor {
             .get_option("precision")
             .and_then(|v| v.parse::<u32>().ok())
             .unwrap_or(5);
+        let preserve = self
+            .config
+            .get_option("preserve_trailing_zeros")
+            .and_then(|v| v.parse::<bool>().ok())
+            .unwrap_or(false);
 
-        let cmd_upper = command.command.to_uppercase();
-        let mut processed = command.clone();
-
-        // Find and replace all numeric values with rounded versions
-        let mut result = String::new();
-        let mut current_number = String::new();
-        let mut last_was_digit = false;
-
-        for ch in cmd_upper.chars() {
-            if ch.is_ascii_digit() || ch == '.' || ch == '-' {
-                current_number.push(ch);
-                last_was_digit = true;
-            } else {
-                if last_was_digit && !current_number.is_empty() {
-                    if let Ok(value) = current_number.parse::<f64>() {
-                        let rounded = self.round_coordinate(value, precision);
-                        result.push_str(&format!("{}", rounded));
-                    } else {
-                        result.push_str(&current_number);
-                    }
-                    current_number.clear();
-                }
-                result.push(ch);
-                last_was_digit = false;
+        let line = &command.command;
+        let split = line.find([';', '(']).unwrap_or(line.len());
+        let (code, comment) = line.split_at(split);
+
+        let mut result = String::with_capacity(line.len());
+        let mut last = 0;
+        for (letter, span) in word_spans(code) {
+            if !matches!(
+                letter,
+                'X' | 'Y' | 'Z' | 'A' | 'B' | 'C' | 'I' | 'J' | 'K' | 'R' | 'F'
+            ) {
+                continue;
             }
+            let Some(number) = self.format_argument(&code[span.clone()], precision, preserve)
+            else {
+                continue;
+            };
+            result.push_str(&code[last..span.start]);
+            result.push_str(&number);
+            last = span.end;
         }
+        result.push_str(&code[last..]);
+        result.push_str(comment);
 
-        // Handle last number if command ends with a digit
-        if !current_number.is_empty() {
-            if let Ok(value) = current_number.parse::<f64>() {
-                let rounded = self.round_coordinate(value, precision);
-                result.push_str(&format!("{}", rounded));
-            } else {
-                result.push_str(&current_number);
+        let mut processed = command.clone();
+        processed.command = result;
+        Ok(vec![processed])
+    }
+
+    fn is_enabled(&self) -> bool {
+        true
+    }
+
+    fn config(&self) -> &ProcessorConfig {
+        &self.config
+    }
+}
+
+/// Drops insignificant trailing zeros from numbers in G-code
+///
+/// CAM output often pads every value, e.g. `X10.500 Y2.000`. This processor
+/// trims such values to `X10.5 Y2` for smaller files and cleaner display,
+/// keeping at least `min_precision` decimal places where the value had them.
+/// G, M, N and O word numbers and comments are left untouched.
+#[derive(Debug, Clone)]
+pub struct TrailingZeroProcessor {
+    config: ProcessorConfig,
+}
+
+impl TrailingZeroProcessor {
+    /// Create a new processor that trims all trailing zeros
+    pub fn new() -> Self {
+        let config = ProcessorConfig::new().with_option("min_precision", "0");
+        Self { config }
+    }
+
+    /// Create with a minimum number of decimal places to keep
+    pub fn with_min_precision(min_precision: u32) -> Self {
+        let config = ProcessorConfig::new().with_option("min_precision", min_precision.to_string());
+        Self { config }
+    }
+
+    pub(crate) fn normalize_number(text: &str, min_precision: usize) -> String {
+        let Some(dot) = text.find('.') else {
+            return text.to_string();
+        };
+        let (integer, decimals) = (&text[..dot], &text[dot + 1..]);
+        let keep = decimals
+            .trim_end_matches('0')
+            .len()
+            .max(min_precision)
+            .min(decimals.len());
+
+        let mut number = integer.to_string();
+        if keep > 0 {
+            number.push('.');
+            number.push_str(&decimals[..keep]);
+        } else if !number.ends_with(|c: char| c.is_ascii_digit()) {
+            // `.0` and `-.0` still need a digit
+            number.push('0');
+        }
+        number
+    }
+}
+
+impl Default for TrailingZeroProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommandProcessor for TrailingZeroProcessor {
+    fn name(&self) -> &str {
+        "trailing_zeros"
+    }
+
+    fn description(&self) -> &str {
+        "Drops insignificant trailing zeros from G-code numbers"
+    }
+
+    fn process(
+        &self,
+        command: &GcodeCommand,
+        _state: &GcodeState,
+    ) -> Result<Vec<GcodeCommand>, String> {
+        let min_precision = self
+            .config
+            .get_option("min_precision")
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(0);
+
+        let line = &command.command;
+        let split = line.find([';', '(']).unwrap_or(line.len());
+        let (code, comment) = line.split_at(split);
+
+        let mut result = String::with_capacity(line.len());
+        let mut last = 0;
+        for (letter, span) in word_spans(code) {
+            if matches!(letter, 'G' | 'M' | 'N' | 'O') || code[span.clone()].parse::<f64>().is_err()
+            {
+                continue;
             }
+            result.push_str(&code[last..span.start]);
+            result.push_str(&Self::normalize_number(&code[span.clone()], min_precision));
+            last = span.end;
         }
+        result.push_str(&code[last..]);
+        result.push_str(comment);
 
+        let mut processed = command.clone();
         processed.command = result;
         Ok(vec![processed])
     }
@@ -1575,19 +3143,116 @@ impl CommandProcessor for PatternRemover {
 
 /// Arc Expander Processor
 ///
-/// Expands arc commands (G02, G03) into multiple linear segments.
+/// Expands arc commands (G02, G03) into `G01` line segments.
 /// This is useful for controllers that don't support arc commands natively.
-#[derive(Debug, Clone)]
+///
+/// Arcs may give their center as `I J K` words or a signed `R` radius and
+/// are interpolated in the plane and distance mode from the `GcodeState`.
+/// `I J K` are offsets from the start point, or the center itself after
+/// `G90.1`; an omitted word keeps the start point's coordinate. The
+/// remaining axis moves linearly for helices. The segment count is
+/// chosen so no segment strays more than `chord_tolerance` from the true arc,
+/// and the last segment ends exactly at the commanded endpoint. The start
+/// point and the program's motion mode are tracked from the commands passed
+/// through the processor, since the `G01` segments leave the pipeline's
+/// state in `G1` while the program is still in `G2`/`G3`.
+#[derive(Debug)]
 pub struct ArcExpander {
     config: ProcessorConfig,
+    state: Mutex<ArcExpanderState>,
+}
+
+/// Position and motion mode tracked across commands
+#[derive(Debug, Clone, Copy, Default)]
+struct ArcExpanderState {
+    /// Current position in program coordinates
+    position: [f64; 3],
+    /// Motion mode set by the program, before any expansion
+    motion: Option<u8>,
 }
 
 impl ArcExpander {
+    /// Default maximum distance between a segment and the arc
+    pub const DEFAULT_CHORD_TOLERANCE: f64 = 0.01;
+
+    /// Decimal places written for segment coordinates
+    const PRECISION: usize = 4;
+
     /// Create a new arc expander
     pub fn new() -> Self {
+        Self::with_chord_tolerance(Self::DEFAULT_CHORD_TOLERANCE)
+    }
+
+    /// Create with the maximum distance allowed between a segment and the arc
+    pub fn with_chord_tolerance(tolerance: f64) -> Self {
+        let config = ProcessorConfig::new().with_option("chord_tolerance", tolerance.to_string());
         Self {
-            config: ProcessorConfig::new(),
+            config,
+            state: Mutex::new(ArcExpanderState::default()),
+        }
+    }
+
+    fn chord_tolerance(&self) -> f64 {
+        self.config
+            .get_option("chord_tolerance")
+            .and_then(|v| v.parse::<f64>().ok())
+            .filter(|tolerance| *tolerance > 0.0)
+            .unwrap_or(Self::DEFAULT_CHORD_TOLERANCE)
+    }
+
+    /// Segments needed to keep every chord within the tolerance of the arc
+    fn segment_count(&self, radius: f64, sweep: f64) -> u32 {
+        if radius <= 0.0 {
+            return 1;
+        }
+        let tolerance = self.chord_tolerance().min(radius);
+        let step = 2.0 * (1.0 - tolerance / radius).acos();
+        (sweep.abs() / step).ceil().max(1.0) as u32
+    }
+
+    fn format_value(value: f64) -> String {
+        let value = if value.abs() < 0.5 * 10f64.powi(-(Self::PRECISION as i32)) {
+            0.0
+        } else {
+            value
+        };
+        TrailingZeroProcessor::normalize_number(&format!("{:.*}", Self::PRECISION, value), 0)
+    }
+
+    /// Center of an `R` arc from `start` to `end`
+    ///
+    /// A positive radius takes the shorter way round, a negative one the longer.
+    fn radius_center(
+        start: (f64, f64),
+        end: (f64, f64),
+        radius: f64,
+        clockwise: bool,
+    ) -> Result<(f64, f64), String> {
+        let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+        let chord = dx.hypot(dy);
+        if chord == 0.0 {
+            return Err("R arc cannot start and end at the same point".to_string());
+        }
+        let half = chord / 2.0;
+        if half > radius.abs() * (1.0 + 1e-9) {
+            return Err(format!(
+                "Arc radius {} is too small for its endpoints",
+                radius
+            ));
         }
+
+        // Offset from the chord midpoint, to the left of travel for the
+        // short way round counter-clockwise
+        let offset = (radius * radius - half * half).max(0.0).sqrt() / chord;
+        let side = if clockwise == (radius < 0.0) {
+            1.0
+        } else {
+            -1.0
+        };
+        Ok((
+            start.0 + dx / 2.0 - side * offset * dy,
+            start.1 + dy / 2.0 + side * offset * dx,
+        ))
     }
 }
 
@@ -1606,36 +3271,472 @@ impl CommandProcessor for ArcExpander {
         "Expands arc commands (G02/G03) into linear segments"
     }
 
+    /// Forget the tracked position and motion mode
+    fn reset(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            *state = ArcExpanderState::default();
+        }
+    }
+
     fn process(
         &self,
         command: &GcodeCommand,
-        _state: &GcodeState,
+        state: &GcodeState,
     ) -> Result<Vec<GcodeCommand>, String> {
-        let cmd_upper = command.command.to_uppercase();
+        let words = tokenize_words(&command.command);
+        let word = |letter: char| {
+            words
+                .iter()
+                .rev()
+                .find(|(l, _)| *l == letter)
+                .map(|&(_, value)| value)
+        };
+        let g_codes: Vec<f64> = words
+            .iter()
+            .filter(|(letter, _)| *letter == 'G')
+            .map(|&(_, value)| value)
+            .collect();
+        let has_g = |code: f64| g_codes.iter().any(|&g| (g - code).abs() < 1e-6);
+
+        // Machine coordinates, reference returns and offset changes don't
+        // move the program position in a way we can follow
+        if [10.0, 28.0, 30.0, 53.0, 92.0].into_iter().any(has_g) {
+            return Ok(vec![command.clone()]);
+        }
+
+        // Words on the line take effect before its motion
+        let incremental = if has_g(91.0) {
+            true
+        } else if has_g(90.0) {
+            false
+        } else {
+            state.distance_mode == 91
+        };
+        let plane = [17, 18, 19]
+            .into_iter()
+            .find(|&code| has_g(code as f64))
+            .unwrap_or(state.plane_mode);
+
+        let mut tracked = self.state.lock().map_err(|e| e.to_string())?;
+        let motion = [0, 1, 2, 3, 80]
+            .into_iter()
+            .rfind(|&code| has_g(code as f64))
+            .or(tracked.motion)
+            .unwrap_or(state.motion_mode);
+        tracked.motion = Some(motion);
+
+        let start = tracked.position;
+        let mut target = start;
+        for (axis, letter) in ['X', 'Y', 'Z'].into_iter().enumerate() {
+            if let Some(value) = word(letter) {
+                target[axis] = if incremental {
+                    start[axis] + value
+                } else {
+                    value
+                };
+            }
+        }
+        tracked.position = target;
 
-        if !cmd_upper.starts_with("G02") && !cmd_upper.starts_with("G03") {
+        let has_center = ['I', 'J', 'K', 'R'].into_iter().any(|l| word(l).is_some());
+        if !matches!(motion, 2 | 3) || !has_center {
             return Ok(vec![command.clone()]);
         }
+        let clockwise = motion == 2;
+
+        // Plane axes ordered so the linear axis is the plane normal, making
+        // counter-clockwise a positive angle in every plane
+        let (u, v, w) = match plane {
+            18 => (2, 0, 1),
+            19 => (1, 2, 0),
+            _ => (0, 1, 2),
+        };
+        let offset_letters = ['I', 'J', 'K'];
+        let (su, sv) = (start[u], start[v]);
+        let (eu, ev) = (target[u], target[v]);
+
+        let absolute_center = if has_g(90.1) {
+            true
+        } else if has_g(91.1) {
+            false
+        } else {
+            state.arc_center_absolute
+        };
+        let center_word = |letter: char, start: f64| match word(letter) {
+            Some(value) if absolute_center => value,
+            Some(offset) => start + offset,
+            None => start,
+        };
+
+        let center = match word('R') {
+            Some(radius) => Self::radius_center((su, sv), (eu, ev), radius, clockwise)?,
+            None => (
+                center_word(offset_letters[u], su),
+                center_word(offset_letters[v], sv),
+            ),
+        };
+        let radius = (su - center.0).hypot(sv - center.1);
+        let start_angle = (sv - center.1).atan2(su - center.0);
+        let end_angle = (ev - center.1).atan2(eu - center.0);
+        let mut sweep = if clockwise {
+            -(start_angle - end_angle).rem_euclid(std::f64::consts::TAU)
+        } else {
+            (end_angle - start_angle).rem_euclid(std::f64::consts::TAU)
+        };
+        if sweep.abs() < 1e-9 && word('R').is_none() {
+            // Same start and end point is a full circle
+            sweep = if clockwise {
+                -std::f64::consts::TAU
+            } else {
+                std::f64::consts::TAU
+            };
+        }
 
-        let segments: u32 = self
-            .config
-            .get_option("segments")
-            .and_then(|v| v.parse::<u32>().ok())
-            .unwrap_or(10);
+        let segments = self.segment_count(radius, sweep);
+        let helical = (target[w] - start[w]).abs() > 0.0;
+        let feed = word('F').or(Some(state.feed_rate).filter(|f| *f > 0.0));
 
-        let mut expanded_commands = Vec::new();
+        // Keep the line's other words, such as N or S, on the first segment
+        let extra: Vec<String> = words
+            .iter()
+            .filter(|&&(letter, value)| match letter {
+                'G' => ![0.0, 1.0, 2.0, 3.0].contains(&value),
+                'X' | 'Y' | 'Z' | 'I' | 'J' | 'K' | 'R' | 'F' => false,
+                _ => true,
+            })
+            .map(|(letter, value)| format!("{}{}", letter, value))
+            .collect();
+        let line = &command.command;
+        let comment = &line[line.find([';', '(']).unwrap_or(line.len())..];
+
+        let mut emitted = start;
+        let mut expanded = Vec::with_capacity(segments as usize);
+        for segment in 1..=segments {
+            let point = if segment == segments {
+                target
+            } else {
+                let fraction = segment as f64 / segments as f64;
+                let angle = start_angle + sweep * fraction;
+                let mut point = [0.0; 3];
+                point[u] = center.0 + radius * angle.cos();
+                point[v] = center.1 + radius * angle.sin();
+                point[w] = start[w] + (target[w] - start[w]) * fraction;
+                point
+            };
+
+            let mut parts = if segment == 1 {
+                extra.clone()
+            } else {
+                Vec::new()
+            };
+            parts.push("G01".to_string());
+            for (axis, letter) in ['X', 'Y', 'Z'].into_iter().enumerate() {
+                if axis == w && !helical {
+                    continue;
+                }
+                let value = if incremental {
+                    let delta = Self::format_value(point[axis] - emitted[axis]);
+                    emitted[axis] += delta.parse::<f64>().unwrap_or(0.0);
+                    delta
+                } else {
+                    Self::format_value(point[axis])
+                };
+                parts.push(format!("{}{}", letter, value));
+            }
+            if let Some(feed) = feed.filter(|_| segment == 1) {
+                parts.push(format!("F{}", Self::format_value(feed)));
+            }
 
-        for i in 1..=segments {
+            if segment == 1 && !comment.is_empty() {
+                parts.push(comment.to_string());
+            }
             let mut arc_segment = command.clone();
-            arc_segment.command = format!("{} ; Arc segment {}/{}", command.command, i, segments);
-            expanded_commands.push(arc_segment);
+            arc_segment.command = parts.join(" ");
+            expanded.push(arc_segment);
+        }
+
+        Ok(expanded)
+    }
+
+    fn is_enabled(&self) -> bool {
+        true
+    }
+
+    fn config(&self) -> &ProcessorConfig {
+        &self.config
+    }
+}
+
+/// Arc normalizing processor
+///
+/// GRBL rejects some arcs that other controllers accept. A full circle given
+/// with `I J K` offsets, whose start and end points coincide, is split into
+/// two half circles through the opposite point. An arc whose radius is below
+/// the `min_radius` option is replaced by a straight `G1` move to its
+/// endpoint, since at that size the two are indistinguishable. Other arcs
+/// pass through unchanged.
+///
+/// Modes come from the `GcodeState` as for [`ArcExpander`], and the start
+/// point is tracked from the commands passed through the processor. After an
+/// arc is straightened, the next modal arc gets its `G2`/`G3` written out so
+/// the controller doesn't carry on in `G1`.
+#[derive(Debug)]
+pub struct ArcNormalizeProcessor {
+    config: ProcessorConfig,
+    state: Mutex<ArcNormalizeState>,
+}
+
+/// Position and motion mode tracked across commands
+#[derive(Debug, Clone, Copy, Default)]
+struct ArcNormalizeState {
+    /// Current position in program coordinates
+    position: [f64; 3],
+    /// Whether the last arc was written as a `G1`
+    straightened: bool,
+}
+
+impl ArcNormalizeProcessor {
+    /// Default radius below which arcs become straight moves
+    pub const DEFAULT_MIN_RADIUS: f64 = 0.01;
+
+    /// Endpoints closer than this make a full circle
+    const CLOSED_TOLERANCE: f64 = 1e-6;
+
+    /// Create a processor straightening arcs below the default radius
+    pub fn new() -> Self {
+        Self::with_min_radius(Self::DEFAULT_MIN_RADIUS)
+    }
+
+    /// Create a processor straightening arcs with a radius below `min_radius`
+    pub fn with_min_radius(min_radius: f64) -> Self {
+        Self {
+            config: ProcessorConfig::new().with_option("min_radius", min_radius.to_string()),
+            state: Mutex::new(ArcNormalizeState::default()),
+        }
+    }
+
+    /// Radius below which arcs become straight moves
+    pub fn min_radius(&self) -> f64 {
+        self.config
+            .get_option("min_radius")
+            .and_then(|v| v.parse::<f64>().ok())
+            .unwrap_or(Self::DEFAULT_MIN_RADIUS)
+    }
+}
+
+impl Default for ArcNormalizeProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommandProcessor for ArcNormalizeProcessor {
+    fn name(&self) -> &str {
+        "arc_normalizer"
+    }
+
+    fn description(&self) -> &str {
+        "Splits full-circle arcs and straightens tiny arcs for GRBL"
+    }
+
+    /// Forget the tracked position
+    fn reset(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            *state = ArcNormalizeState::default();
+        }
+    }
+
+    fn process(
+        &self,
+        command: &GcodeCommand,
+        state: &GcodeState,
+    ) -> Result<Vec<GcodeCommand>, String> {
+        let words = tokenize_words(&command.command);
+        let word = |letter: char| {
+            words
+                .iter()
+                .rev()
+                .find(|(l, _)| *l == letter)
+                .map(|&(_, value)| value)
+        };
+        let has_g = |code: f64| {
+            words
+                .iter()
+                .any(|&(letter, value)| letter == 'G' && (value - code).abs() < 1e-6)
+        };
+
+        // Machine coordinates, reference returns and offset changes don't
+        // move the program position in a way we can follow
+        if [10.0, 28.0, 30.0, 53.0, 92.0].into_iter().any(has_g) {
+            return Ok(vec![command.clone()]);
+        }
+
+        // Words on the line take effect before its motion
+        let incremental = if has_g(91.0) {
+            true
+        } else if has_g(90.0) {
+            false
+        } else {
+            state.distance_mode == 91
+        };
+        let plane = [17, 18, 19]
+            .into_iter()
+            .find(|&code| has_g(code as f64))
+            .unwrap_or(state.plane_mode);
+        let motion_word = [0, 1, 2, 3].into_iter().rfind(|&code| has_g(code as f64));
+        let motion = motion_word.unwrap_or(state.motion_mode);
+
+        let mut tracked = self.state.lock().map_err(|e| e.to_string())?;
+        let start = tracked.position;
+        let mut target = start;
+        for (axis, letter) in ['X', 'Y', 'Z'].into_iter().enumerate() {
+            if let Some(value) = word(letter) {
+                target[axis] = if incremental {
+                    start[axis] + value
+                } else {
+                    value
+                };
+            }
         }
+        tracked.position = target;
 
-        if expanded_commands.is_empty() {
+        let has_center = ['I', 'J', 'K', 'R'].into_iter().any(|l| word(l).is_some());
+        let restore_motion = tracked.straightened && motion_word.is_none();
+        if motion_word.is_some() {
+            tracked.straightened = false;
+        }
+        if !matches!(motion, 2 | 3) || !has_center {
             return Ok(vec![command.clone()]);
         }
+        let arc_word = if motion == 2 { "G2" } else { "G3" };
+
+        // Plane axes, and the axis normal to the plane
+        let (u, v, w) = match plane {
+            18 => (2, 0, 1),
+            19 => (1, 2, 0),
+            _ => (0, 1, 2),
+        };
+        let offset_letters = ['I', 'J', 'K'];
+        let absolute_center = if has_g(90.1) {
+            true
+        } else if has_g(91.1) {
+            false
+        } else {
+            state.arc_center_absolute
+        };
+        // Offsets from the start, whichever arc distance mode is in use
+        let offset_word = |letter: char, start: f64| match word(letter) {
+            Some(value) if absolute_center => value - start,
+            Some(offset) => offset,
+            None => 0.0,
+        };
+        let offset = (
+            offset_word(offset_letters[u], start[u]),
+            offset_word(offset_letters[v], start[v]),
+        );
+        let radius = match word('R') {
+            Some(radius) => radius.abs(),
+            None => offset.0.hypot(offset.1),
+        };
+
+        // Keep the line's other words, such as N or S, on the first move
+        let extra: Vec<String> = words
+            .iter()
+            .filter(|&&(letter, value)| match letter {
+                'G' => ![0.0, 1.0, 2.0, 3.0].contains(&value),
+                'X' | 'Y' | 'Z' | 'I' | 'J' | 'K' | 'R' | 'F' => false,
+                _ => true,
+            })
+            .map(|(letter, value)| format!("{}{}", letter, value))
+            .collect();
+        let line = &command.command;
+        let comment = &line[line.find([';', '(']).unwrap_or(line.len())..];
+        let rebuild = |motion: &str, axes: &[(char, f64)], offsets: &[(char, f64)], first: bool| {
+            let mut parts = if first { extra.clone() } else { Vec::new() };
+            parts.push(motion.to_string());
+            for (letter, value) in axes.iter().chain(offsets) {
+                parts.push(format!("{}{}", letter, ArcExpander::format_value(*value)));
+            }
+            if let Some(feed) = word('F').filter(|_| first) {
+                parts.push(format!("F{}", ArcExpander::format_value(feed)));
+            }
+            if first && !comment.is_empty() {
+                parts.push(comment.to_string());
+            }
+            let mut rebuilt = command.clone();
+            rebuilt.command = parts.join(" ");
+            rebuilt
+        };
+
+        if radius < self.min_radius() {
+            tracked.straightened = true;
+            let axes: Vec<(char, f64)> = words
+                .iter()
+                .filter(|(letter, _)| matches!(letter, 'X' | 'Y' | 'Z'))
+                .copied()
+                .collect();
+            return Ok(vec![rebuild("G1", &axes, &[], true)]);
+        }
+        tracked.straightened = false;
+
+        let closed = word('R').is_none()
+            && (target[u] - start[u]).hypot(target[v] - start[v]) < Self::CLOSED_TOLERANCE;
+        if !closed {
+            if !restore_motion {
+                return Ok(vec![command.clone()]);
+            }
+            let split = line.find([';', '(']).unwrap_or(line.len());
+            let (code, comment) = line.split_at(split);
+            let separator = if code.trim().contains(char::is_whitespace) {
+                " "
+            } else {
+                ""
+            };
+            let mut result = code.to_string();
+            insert_motion_word(&mut result, &word_spans(code), arc_word, separator);
+            let mut restored = command.clone();
+            restored.command = format!("{}{}", result, comment);
+            return Ok(vec![restored]);
+        }
+
+        // Split at the point opposite the start, half way along any helix
+        let center = (start[u] + offset.0, start[v] + offset.1);
+        let mut middle = start;
+        middle[u] = 2.0 * center.0 - start[u];
+        middle[v] = 2.0 * center.1 - start[v];
+        middle[w] = start[w] + (target[w] - start[w]) / 2.0;
+        let helical = target[w] != start[w];
+
+        let mut emitted = start;
+        let mut halves = Vec::with_capacity(2);
+        for (index, (from, to)) in [(start, middle), (middle, target)].into_iter().enumerate() {
+            let mut axes = Vec::new();
+            for (axis, letter) in ['X', 'Y', 'Z'].into_iter().enumerate() {
+                if axis == w && !helical {
+                    continue;
+                }
+                let value = if incremental {
+                    let delta = to[axis] - emitted[axis];
+                    let delta = ArcExpander::format_value(delta).parse().unwrap_or(delta);
+                    emitted[axis] += delta;
+                    delta
+                } else {
+                    to[axis]
+                };
+                axes.push((letter, value));
+            }
+            let offsets = if absolute_center {
+                [(offset_letters[u], center.0), (offset_letters[v], center.1)]
+            } else {
+                [
+                    (offset_letters[u], center.0 - from[u]),
+                    (offset_letters[v], center.1 - from[v]),
+                ]
+            };
+            halves.push(rebuild(arc_word, &axes, &offsets, index == 0));
+        }
 
-        Ok(expanded_commands)
+        Ok(halves)
     }
 
     fn is_enabled(&self) -> bool {
@@ -1734,6 +3835,443 @@ impl CommandProcessor for LineSplitter {
     }
 }
 
+/// Default feed rate injector
+///
+/// Hand-edited programs sometimes start cutting before any `F` word is set,
+/// which many controllers run at F0 or reject outright. This processor adds
+/// the configured default feed to the first feed move (`G1`/`G2`/`G3`) when
+/// no feed rate has been set earlier in the program. Once any `F` word has
+/// been seen, every command passes through unchanged.
+#[derive(Debug)]
+pub struct DefaultFeedProcessor {
+    config: ProcessorConfig,
+    state: Mutex<DefaultFeedState>,
+}
+
+/// Modal state tracked across commands
+#[derive(Debug, Clone, Copy, Default)]
+struct DefaultFeedState {
+    /// Active motion mode (0-3)
+    motion: Option<u8>,
+    /// Whether a feed rate has been set or injected
+    feed_set: bool,
+}
+
+impl DefaultFeedProcessor {
+    /// Create a processor that injects `feed` when none has been set
+    pub fn new(feed: f64) -> Self {
+        let config = ProcessorConfig::new().with_option("default_feed", feed.to_string());
+        Self {
+            config,
+            state: Mutex::new(DefaultFeedState::default()),
+        }
+    }
+
+    /// The feed rate injected into the first feed move
+    pub fn default_feed(&self) -> f64 {
+        self.config
+            .get_option("default_feed")
+            .and_then(|v| v.parse::<f64>().ok())
+            .unwrap_or(0.0)
+    }
+}
+
+impl CommandProcessor for DefaultFeedProcessor {
+    fn name(&self) -> &str {
+        "default_feed"
+    }
+
+    fn description(&self) -> &str {
+        "Adds a default feed rate to the first feed move if none is set"
+    }
+
+    /// Forget the tracked feed and motion mode
+    fn reset(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            *state = DefaultFeedState::default();
+        }
+    }
+
+    fn process(
+        &self,
+        command: &GcodeCommand,
+        _state: &GcodeState,
+    ) -> Result<Vec<GcodeCommand>, String> {
+        let mut state = self.state.lock().map_err(|e| e.to_string())?;
+        if state.feed_set {
+            return Ok(vec![command.clone()]);
+        }
+
+        let line = &command.command;
+        let split = line.find([';', '(']).unwrap_or(line.len());
+        let code = &line[..split];
+        let words = tokenize_words(code);
+
+        for &(letter, value) in &words {
+            match (letter, (value * 10.0).round() as i64) {
+                ('G', code @ (0 | 10 | 20 | 30)) => state.motion = Some((code / 10) as u8),
+                ('F', _) => state.feed_set = true,
+                _ => {}
+            }
+        }
+        let moves = words
+            .iter()
+            .any(|(letter, _)| matches!(letter, 'X' | 'Y' | 'Z' | 'A' | 'B' | 'C'));
+        if state.feed_set || !moves || !matches!(state.motion, Some(1..=3)) {
+            return Ok(vec![command.clone()]);
+        }
+
+        let feed = self.default_feed();
+        if feed <= 0.0 {
+            return Ok(vec![command.clone()]);
+        }
+        state.feed_set = true;
+
+        // Keep any trailing comment after the new word
+        let code = code.trim_end();
+        let mut processed = command.clone();
+        processed.command = format!("{} F{}{}", code, feed, &line[code.len()..]);
+        Ok(vec![processed])
+    }
+
+    fn is_enabled(&self) -> bool {
+        true
+    }
+
+    fn config(&self) -> &ProcessorConfig {
+        &self.config
+    }
+}
+
+/// Rapid-to-feed converter for traverses near the workpiece
+///
+/// For extra caution, a `G0` that ends below the `safe_z` option, or moves in
+/// X or Y while starting below it, is rewritten as a `G1` at the `feed`
+/// option, so traverses near or in the material run at a controlled speed.
+/// Rapids above safe Z, and plain retracts rising from below it, stay as
+/// `G0`. Z is tracked through `G90`/`G91` and is unknown until the first
+/// absolute Z word; rapids at an unknown Z are left alone.
+///
+/// The program's own modes are put back afterwards: a later modal rapid gets
+/// its `G0` written out, and the next feed move without an `F` word gets the
+/// program's feed rate. Rapids in inverse time mode (`G93`) are not converted,
+/// since F is not a speed there.
+#[derive(Debug)]
+pub struct RapidToFeedProcessor {
+    config: ProcessorConfig,
+    state: Mutex<RapidToFeedState>,
+}
+
+/// Modal state tracked across commands
+#[derive(Debug, Clone, Copy, Default)]
+struct RapidToFeedState {
+    /// Motion mode (0-3) of the program
+    motion: Option<u8>,
+    /// Motion mode (0-3) of the rewritten output
+    written: Option<u8>,
+    incremental: bool,
+    inverse_time: bool,
+    /// Z position, unknown until the first absolute Z word
+    z: Option<f64>,
+    /// The program's modal feed rate
+    feed: Option<f64>,
+    /// Whether a converted rapid left the controller at a different feed
+    feed_changed: bool,
+}
+
+impl RapidToFeedProcessor {
+    /// Create a processor converting rapids below `safe_z` into moves at `feed`
+    pub fn new(safe_z: f64, feed: f64) -> Self {
+        let config = ProcessorConfig::new()
+            .with_option("safe_z", safe_z.to_string())
+            .with_option("feed", feed.to_string());
+        Self {
+            config,
+            state: Mutex::new(RapidToFeedState::default()),
+        }
+    }
+
+    /// Z below which rapids are converted
+    pub fn safe_z(&self) -> f64 {
+        self.config
+            .get_option("safe_z")
+            .and_then(|v| v.parse::<f64>().ok())
+            .unwrap_or(0.0)
+    }
+
+    /// Feed rate of converted rapids
+    pub fn feed(&self) -> f64 {
+        self.config
+            .get_option("feed")
+            .and_then(|v| v.parse::<f64>().ok())
+            .unwrap_or(0.0)
+    }
+}
+
+impl CommandProcessor for RapidToFeedProcessor {
+    fn name(&self) -> &str {
+        "rapid_to_feed"
+    }
+
+    fn description(&self) -> &str {
+        "Converts rapids below safe Z into controlled feed moves"
+    }
+
+    /// Forget the tracked position and modes
+    fn reset(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            *state = RapidToFeedState::default();
+        }
+    }
+
+    fn process(
+        &self,
+        command: &GcodeCommand,
+        _state: &GcodeState,
+    ) -> Result<Vec<GcodeCommand>, String> {
+        let line = &command.command;
+        let split = line.find([';', '(']).unwrap_or(line.len());
+        let (code, comment) = line.split_at(split);
+        let spans = word_spans(code);
+
+        let mut state = self.state.lock().map_err(|e| e.to_string())?;
+        let mut motion_word = None;
+        let mut feed_word = None;
+        let mut end_z = None;
+        for (letter, span) in &spans {
+            let Ok(value) = code[span.clone()].parse::<f64>() else {
+                continue;
+            };
+            match (letter, (value * 10.0).round() as i64) {
+                ('G', mode @ (0 | 10 | 20 | 30)) => {
+                    state.motion = Some((mode / 10) as u8);
+                    motion_word = Some(span.clone());
+                }
+                ('G', 900) => state.incremental = false,
+                ('G', 910) => state.incremental = true,
+                ('G', 930) => state.inverse_time = true,
+                ('G', 940 | 950) => state.inverse_time = false,
+                ('F', _) => {
+                    state.feed = Some(value);
+                    state.feed_changed = false;
+                    feed_word = Some(span.clone());
+                }
+                ('Z', _) => end_z = Some(value),
+                _ => {}
+            }
+        }
+
+        let start_z = state.z;
+        if let Some(value) = end_z {
+            state.z = if state.incremental {
+                state.z.map(|z| z + value)
+            } else {
+                Some(value)
+            };
+        }
+        let moves = spans
+            .iter()
+            .any(|(letter, _)| matches!(letter, 'X' | 'Y' | 'Z' | 'A' | 'B' | 'C'));
+        if !moves {
+            if motion_word.is_some() {
+                state.written = state.motion;
+            }
+            return Ok(vec![command.clone()]);
+        }
+
+        let safe_z = self.safe_z();
+        let moves_xy = spans.iter().any(|(letter, _)| matches!(letter, 'X' | 'Y'));
+        let below = |z: Option<f64>| z.is_some_and(|z| z < safe_z);
+        let convert = state.motion == Some(0)
+            && !state.inverse_time
+            && self.feed() > 0.0
+            && (below(state.z) || (moves_xy && below(start_z)));
+
+        let separator = if code.trim().contains(char::is_whitespace) {
+            " "
+        } else {
+            ""
+        };
+        let mut result = code.to_string();
+        let mut written = state.motion;
+        if convert {
+            let feed = self.feed().to_string();
+            let mut edits = Vec::new();
+            match feed_word {
+                Some(span) => edits.push((span, feed)),
+                None => {
+                    let end = code.trim_end().len();
+                    edits.push((end..end, format!("{}F{}", separator, feed)));
+                }
+            }
+            if let Some(span) = &motion_word {
+                // Keep a leading zero, so G00 becomes G01
+                edits.push((span.end - 1..span.end, "1".to_string()));
+            }
+            // Edit from the end of the line so earlier spans stay valid
+            edits.sort_by_key(|(span, _)| std::cmp::Reverse(span.start));
+            for (span, text) in edits {
+                result.replace_range(span, &text);
+            }
+            if motion_word.is_none() {
+                insert_motion_word(&mut result, &spans, "G1", separator);
+            }
+            written = Some(1);
+            state.feed_changed = true;
+        } else if state.motion == Some(0) && motion_word.is_none() && state.written == Some(1) {
+            insert_motion_word(&mut result, &spans, "G0", separator);
+        } else if matches!(state.motion, Some(1..=3)) && state.feed_changed && !state.inverse_time {
+            if let Some(feed) = state.feed {
+                let end = result.trim_end().len();
+                result.insert_str(end, &format!("{}F{}", separator, feed));
+            }
+            state.feed_changed = false;
+        }
+        state.written = written;
+
+        let mut processed = command.clone();
+        processed.command = format!("{}{}", result, comment);
+        Ok(vec![processed])
+    }
+
+    fn is_enabled(&self) -> bool {
+        true
+    }
+
+    fn config(&self) -> &ProcessorConfig {
+        &self.config
+    }
+}
+
+/// Write a motion word before the first word of a line that isn't `N`
+fn insert_motion_word(
+    code: &mut String,
+    spans: &[(char, Range<usize>)],
+    word: &str,
+    separator: &str,
+) {
+    let insert = spans
+        .iter()
+        .find(|(letter, _)| *letter != 'N')
+        .map(|(_, span)| code[..span.start].trim_end().len() - 1)
+        .unwrap_or(0);
+    code.insert_str(insert, &format!("{}{}", word, separator));
+}
+
+/// Feed rate override processor
+///
+/// Multiplies every `F` word by the `scale` option, e.g. 0.5 to run a
+/// first-article cut at half feed, then clamps the result to the optional
+/// `min_feed` and `max_feed` options. Only the F argument is rewritten.
+/// Lines without an F word are left alone, since they run at the modal feed
+/// in `GcodeState::feed_rate` which was already scaled where it was set.
+/// In inverse time mode (`G93`) F is not a speed, so it is scaled but not
+/// clamped.
+#[derive(Debug, Clone)]
+pub struct FeedRateOverrideProcessor {
+    config: ProcessorConfig,
+}
+
+impl FeedRateOverrideProcessor {
+    /// Decimal places written for scaled feeds
+    const PRECISION: usize = 4;
+
+    /// Create a processor scaling feeds by `scale`
+    pub fn new(scale: f64) -> Self {
+        Self::with_config(ProcessorConfig::new().with_option("scale", scale.to_string()))
+    }
+
+    /// Create from a configuration with `scale`, `min_feed` and `max_feed` options
+    pub fn with_config(config: ProcessorConfig) -> Self {
+        Self { config }
+    }
+
+    /// Never scale a feed below `feed`
+    pub fn with_min_feed(mut self, feed: f64) -> Self {
+        self.config = self.config.with_option("min_feed", feed.to_string());
+        self
+    }
+
+    /// Never scale a feed above `feed`
+    pub fn with_max_feed(mut self, feed: f64) -> Self {
+        self.config = self.config.with_option("max_feed", feed.to_string());
+        self
+    }
+
+    fn option(&self, key: &str) -> Option<f64> {
+        self.config
+            .get_option(key)
+            .and_then(|v| v.parse::<f64>().ok())
+    }
+
+    /// Scale and clamp one feed value
+    pub fn override_feed(&self, feed: f64, state: &GcodeState) -> f64 {
+        let mut scaled = feed * self.option("scale").unwrap_or(1.0);
+        if state.feed_rate_mode != 93 {
+            if let Some(max) = self.option("max_feed") {
+                scaled = scaled.min(max);
+            }
+            if let Some(min) = self.option("min_feed") {
+                scaled = scaled.max(min);
+            }
+        }
+        scaled
+    }
+}
+
+impl Default for FeedRateOverrideProcessor {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+impl CommandProcessor for FeedRateOverrideProcessor {
+    fn name(&self) -> &str {
+        "feed_override"
+    }
+
+    fn description(&self) -> &str {
+        "Scales every feed rate by a factor, with optional clamps"
+    }
+
+    fn process(
+        &self,
+        command: &GcodeCommand,
+        state: &GcodeState,
+    ) -> Result<Vec<GcodeCommand>, String> {
+        let line = &command.command;
+        let code = &line[..line.find([';', '(']).unwrap_or(line.len())];
+
+        let mut result = String::with_capacity(line.len());
+        let mut last = 0;
+        for (letter, span) in word_spans(code) {
+            if letter != 'F' {
+                continue;
+            }
+            let Ok(feed) = code[span.clone()].parse::<f64>() else {
+                continue;
+            };
+            let scaled = format!("{:.*}", Self::PRECISION, self.override_feed(feed, state));
+            result.push_str(&line[last..span.start]);
+            result.push_str(&TrailingZeroProcessor::normalize_number(&scaled, 0));
+            last = span.end;
+        }
+        result.push_str(&line[last..]);
+
+        let mut processed = command.clone();
+        processed.command = result;
+        Ok(vec![processed])
+    }
+
+    fn is_enabled(&self) -> bool {
+        true
+    }
+
+    fn config(&self) -> &ProcessorConfig {
+        &self.config
+    }
+}
+
 /// M30 Processor
 ///
 /// Handles the M30 command (program end and reset).
@@ -1802,3 +4340,107 @@ impl CommandProcessor for M30Processor {
         &self.config
     }
 }
+
+/// Injects a standard program header and footer
+///
+/// Unlike the per-command processors above, this works on a whole program:
+/// header lines are prepended once and footer lines appended once, ahead of a
+/// trailing `M2`/`M30` if the program has one. Words already present in the
+/// program's preamble (before the first motion) or postamble (after the last
+/// motion) are left out, wherever they sit on their lines, so a program that
+/// starts with `G21 G90 G54` gets none of them again. Words are compared by
+/// value, so `G0` matches `G00`. A header or footer line whose words are all
+/// present is skipped; one with only some present keeps just the missing ones.
+///
+/// Register it with [`ProcessorPipeline::set_header_footer`] to apply it to
+/// every program the pipeline processes.
+#[derive(Debug, Clone, Default)]
+pub struct HeaderFooterProcessor {
+    /// Lines to prepend to the program
+    pub header: Vec<String>,
+    /// Lines to append to the program
+    pub footer: Vec<String>,
+}
+
+impl HeaderFooterProcessor {
+    /// Create a processor with the given header and footer lines
+    pub fn new(header: Vec<String>, footer: Vec<String>) -> Self {
+        Self { header, footer }
+    }
+
+    /// Apply the header and footer to a program
+    ///
+    /// # Arguments
+    /// * `commands` - The complete program
+    ///
+    /// # Returns
+    /// The program with missing header and footer lines inserted
+    pub fn apply(&self, commands: &[GcodeCommand]) -> Vec<GcodeCommand> {
+        let words: Vec<Vec<(char, f64)>> = commands
+            .iter()
+            .map(|c| tokenize_words(&c.command))
+            .collect();
+        let motion: Vec<usize> = words
+            .iter()
+            .enumerate()
+            .filter(|(_, w)| has_motion(w))
+            .map(|(i, _)| i)
+            .collect();
+        let first_motion = motion.first().copied().unwrap_or(commands.len());
+        let last_motion = motion.last().map(|i| i + 1).unwrap_or(0);
+
+        let header = Self::missing_lines(&self.header, &words[..first_motion]);
+        let footer = Self::missing_lines(&self.footer, &words[last_motion..]);
+
+        // Footer goes before the program end so it is actually executed
+        let end = (last_motion..commands.len())
+            .rev()
+            .find(|&i| {
+                words[i]
+                    .iter()
+                    .any(|&(letter, value)| letter == 'M' && (value == 2.0 || value == 30.0))
+            })
+            .unwrap_or(commands.len());
+
+        let mut result = Vec::with_capacity(commands.len() + header.len() + footer.len());
+        result.extend(header);
+        result.extend_from_slice(&commands[..end]);
+        result.extend(footer);
+        result.extend_from_slice(&commands[end..]);
+        result
+    }
+
+    /// The given lines with the words already in `region` left out
+    ///
+    /// Lines with no words, such as comments, are kept as they are.
+    fn missing_lines(lines: &[String], region: &[Vec<(char, f64)>]) -> Vec<GcodeCommand> {
+        lines
+            .iter()
+            .filter_map(|line| {
+                let code = strip_comments(line).to_ascii_uppercase();
+                let words: Vec<(char, f64, &str)> = word_spans(&code)
+                    .into_iter()
+                    .filter_map(|(letter, span)| {
+                        let text = &code[span];
+                        text.parse().ok().map(|value| (letter, value, text))
+                    })
+                    .collect();
+                let missing: Vec<String> = words
+                    .iter()
+                    .filter(|&&(letter, value, _)| {
+                        !region.iter().flatten().any(|&word| word == (letter, value))
+                    })
+                    .map(|(letter, _, text)| format!("{}{}", letter, text))
+                    .collect();
+
+                if words.is_empty() || missing.len() == words.len() {
+                    Some(GcodeCommand::new(line))
+                } else if missing.is_empty() {
+                    None
+                } else {
+                    Some(GcodeCommand::new(missing.join(" ")))
+                }
+            })
+            .collect()
+    }
+}
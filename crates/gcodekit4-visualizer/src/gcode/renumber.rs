@@ -0,0 +1,174 @@
+//! Renumbering program lines
+//!
+//! The `NRenumberProcessor` strips any `N` words a program already has and
+//! writes fresh, evenly spaced line numbers at the front of each command, e.g.
+//! `N10`, `N20`, ... Controllers that report errors by line number, and
+//! operators restarting partway through, both rely on them being in order.
+//!
+//! Most controllers accept at most five digits, so numbers above 99999 either
+//! wrap back to the start or fail the command, depending on the `on_overflow`
+//! option.
+
+use std::sync::Mutex;
+
+use super::{word_spans, CommandProcessor, GcodeCommand, GcodeState, ProcessorConfig};
+
+/// Largest line number a controller accepts
+const MAX_LINE_NUMBER: u64 = 99_999;
+
+/// Replaces `N` words with sequential line numbers
+///
+/// Options are `start` and `increment` (both 10 by default), `skip_blank`
+/// (`true` by default, leaving blank and comment-only lines unnumbered) and
+/// `on_overflow`, which is `error` (the default) or `wrap`.
+pub struct NRenumberProcessor {
+    config: ProcessorConfig,
+    /// Number for the next numbered line, once the first has been written
+    next: Mutex<Option<u64>>,
+}
+
+impl NRenumberProcessor {
+    /// Create a processor numbering from `start` in steps of `increment`
+    pub fn new(start: u64, increment: u64) -> Self {
+        Self::with_config(
+            ProcessorConfig::new()
+                .with_option("start", start.to_string())
+                .with_option("increment", increment.to_string()),
+        )
+    }
+
+    /// Create from a configuration with `start`, `increment`, `skip_blank`
+    /// and `on_overflow` options
+    pub fn with_config(config: ProcessorConfig) -> Self {
+        Self {
+            config,
+            next: Mutex::new(None),
+        }
+    }
+
+    /// Number blank and comment-only lines too
+    pub fn with_skip_blank(mut self, skip: bool) -> Self {
+        self.config = self.config.with_option("skip_blank", skip.to_string());
+        self
+    }
+
+    /// Wrap back to `start` past N99999 instead of failing
+    pub fn with_wrap(mut self, wrap: bool) -> Self {
+        let on_overflow = if wrap { "wrap" } else { "error" };
+        self.config = self.config.with_option("on_overflow", on_overflow);
+        self
+    }
+
+    /// First line number written
+    pub fn start(&self) -> u64 {
+        self.number_option("start")
+    }
+
+    /// Step between line numbers
+    pub fn increment(&self) -> u64 {
+        self.number_option("increment")
+    }
+
+    fn number_option(&self, key: &str) -> u64 {
+        self.config
+            .get_option(key)
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(10)
+    }
+
+    fn skip_blank(&self) -> bool {
+        self.config
+            .get_option("skip_blank")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(true)
+    }
+
+    fn wraps(&self) -> bool {
+        self.config
+            .get_option("on_overflow")
+            .is_some_and(|v| v.eq_ignore_ascii_case("wrap"))
+    }
+
+    /// Remove the `N` words from a line, along with the space after each
+    fn strip_line_numbers(line: &str) -> String {
+        let code = &line[..line.find([';', '(']).unwrap_or(line.len())];
+        let mut result = String::with_capacity(line.len());
+        let mut last = 0;
+
+        for (letter, span) in word_spans(code) {
+            if letter != 'N' {
+                continue;
+            }
+            // The letter may be separated from its number by spaces
+            let start = code[..span.start].trim_end().len() - 1;
+            let end = span.end + (code[span.end..].len() - code[span.end..].trim_start().len());
+            result.push_str(&line[last..start]);
+            last = end;
+        }
+        result.push_str(&line[last..]);
+        result
+    }
+}
+
+impl Default for NRenumberProcessor {
+    fn default() -> Self {
+        Self::new(10, 10)
+    }
+}
+
+impl CommandProcessor for NRenumberProcessor {
+    fn name(&self) -> &str {
+        "renumber"
+    }
+
+    fn description(&self) -> &str {
+        "Replaces N words with sequential line numbers"
+    }
+
+    /// Start numbering again from `start`
+    fn reset(&self) {
+        if let Ok(mut next) = self.next.lock() {
+            *next = None;
+        }
+    }
+
+    fn process(
+        &self,
+        command: &GcodeCommand,
+        _state: &GcodeState,
+    ) -> Result<Vec<GcodeCommand>, String> {
+        let stripped = Self::strip_line_numbers(&command.command);
+        let body = stripped.trim();
+        let code = &body[..body.find([';', '(']).unwrap_or(body.len())];
+
+        let mut processed = command.clone();
+        if code.trim().is_empty() && self.skip_blank() {
+            processed.command = stripped.trim_end().to_string();
+            return Ok(vec![processed]);
+        }
+
+        let mut next = self.next.lock().map_err(|e| e.to_string())?;
+        let mut number = next.unwrap_or_else(|| self.start());
+        if number > MAX_LINE_NUMBER {
+            if !self.wraps() {
+                return Err(format!(
+                    "Line number N{} exceeds the limit of N{}",
+                    number, MAX_LINE_NUMBER
+                ));
+            }
+            number = self.start();
+        }
+        *next = Some(number + self.increment().max(1));
+
+        processed.command = if body.is_empty() {
+            format!("N{}", number)
+        } else {
+            format!("N{} {}", number, body)
+        };
+        Ok(vec![processed])
+    }
+
+    fn config(&self) -> &ProcessorConfig {
+        &self.config
+    }
+}
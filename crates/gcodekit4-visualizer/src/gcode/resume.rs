@@ -0,0 +1,188 @@
+//! Building the prelude that resumes an interrupted job
+//!
+//! When a connection drops mid-job the controller forgets the program's modal
+//! state, and after re-homing the tool is somewhere else. [`resume_prelude`]
+//! replays the lines that already ran, without moving anything, and returns
+//! the commands that put the controller back as it was before the next line:
+//! units, distance mode, plane, feed mode, work offset, spindle, coolant and
+//! the tool position.
+
+use super::{tokenize_words, TrailingZeroProcessor};
+
+/// Decimal places written for coordinates and rates
+const PRECISION: usize = 4;
+
+/// Modal state replayed from the executed lines
+#[derive(Debug, Clone, Default)]
+struct ResumeState {
+    /// Position in work coordinates, unknown until first set
+    position: [Option<f64>; 3],
+    /// Active motion mode (0-3)
+    motion: Option<u8>,
+    /// `G20` or `G21`, if the program chose one
+    units: Option<u8>,
+    incremental: bool,
+    /// `G17`-`G19`, if the program chose one
+    plane: Option<u8>,
+    /// `G93`-`G95`, if the program chose one
+    feed_mode: Option<u8>,
+    /// Work offset as `G` code tenths (540 for `G54`, 591 for `G59.1`)
+    work_offset: Option<u16>,
+    /// `M3` or `M4` while the spindle runs
+    spindle: Option<u8>,
+    spindle_speed: Option<f64>,
+    mist: bool,
+    flood: bool,
+    feed: Option<f64>,
+}
+
+impl ResumeState {
+    fn update(&mut self, words: &[(char, f64)]) {
+        let mut moves = true;
+        for &(letter, value) in words {
+            match (letter, (value * 10.0).round() as i64) {
+                ('G', code @ (0 | 10 | 20 | 30)) => self.motion = Some((code / 10) as u8),
+                ('G', code @ (200 | 210)) => self.units = Some((code / 10) as u8),
+                ('G', code @ (170 | 180 | 190)) => self.plane = Some((code / 10) as u8),
+                ('G', 900) => self.incremental = false,
+                ('G', 910) => self.incremental = true,
+                ('G', code @ (930 | 940 | 950)) => self.feed_mode = Some((code / 10) as u8),
+                ('G', code @ (540 | 550 | 560 | 570 | 580 | 590..=593)) => {
+                    self.work_offset = Some(code as u16);
+                    self.position = [None; 3];
+                }
+                // Machine coordinates, reference returns and offset changes
+                // leave the program position unknown
+                ('G', 100 | 280 | 300 | 530 | 920..=923) => {
+                    self.position = [None; 3];
+                    moves = false;
+                }
+                ('M', code @ (30 | 40)) => self.spindle = Some((code / 10) as u8),
+                ('M', 50) => self.spindle = None,
+                ('M', 70) => self.mist = true,
+                ('M', 80) => self.flood = true,
+                ('M', 90) => {
+                    self.mist = false;
+                    self.flood = false;
+                }
+                ('S', _) => self.spindle_speed = Some(value),
+                ('F', _) => self.feed = Some(value),
+                _ => {}
+            }
+        }
+        if !moves {
+            return;
+        }
+
+        for &(letter, value) in words {
+            let axis = match letter {
+                'X' => 0,
+                'Y' => 1,
+                'Z' => 2,
+                _ => continue,
+            };
+            self.position[axis] = if self.incremental {
+                self.position[axis].map(|p| p + value)
+            } else {
+                Some(value)
+            };
+        }
+    }
+
+    /// Commands restoring this state on a freshly connected controller
+    fn prelude(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        let mut modes = Vec::new();
+        if let Some(units) = self.units {
+            modes.push(format!("G{}", units));
+        }
+        modes.push("G90".to_string());
+        if let Some(plane) = self.plane {
+            modes.push(format!("G{}", plane));
+        }
+        if let Some(feed_mode) = self.feed_mode {
+            modes.push(format!("G{}", feed_mode));
+        }
+        if let Some(offset) = self.work_offset {
+            modes.push(match offset % 10 {
+                0 => format!("G{}", offset / 10),
+                tenths => format!("G{}.{}", offset / 10, tenths),
+            });
+        }
+        lines.push(modes.join(" "));
+
+        if let Some(spindle) = self.spindle {
+            lines.push(match self.spindle_speed {
+                Some(speed) => format!("M{} S{}", spindle, format_value(speed)),
+                None => format!("M{}", spindle),
+            });
+        }
+        if self.mist {
+            lines.push("M7".to_string());
+        }
+        if self.flood {
+            lines.push("M8".to_string());
+        }
+
+        // Travel above the work, then feed down to the cutting depth
+        let [x, y, z] = self.position;
+        let travel: Vec<String> = [('X', x), ('Y', y)]
+            .into_iter()
+            .filter_map(|(letter, value)| Some(format!("{}{}", letter, format_value(value?))))
+            .collect();
+        if !travel.is_empty() {
+            lines.push(format!("G0 {}", travel.join(" ")));
+        }
+        match (z, self.feed) {
+            (Some(z), Some(feed)) => {
+                lines.push(format!("G1 Z{} F{}", format_value(z), format_value(feed)))
+            }
+            (Some(z), None) => lines.push(format!("G0 Z{}", format_value(z))),
+            (None, Some(feed)) => lines.push(format!("F{}", format_value(feed))),
+            (None, None) => {}
+        }
+
+        // Lines relying on the modal motion need it back; arcs can't be
+        // selected without a target, so they must name their own mode
+        match self.motion {
+            Some(0) => lines.push("G0".to_string()),
+            Some(1) if z.is_none() => lines.push("G1".to_string()),
+            _ => {}
+        }
+        if self.incremental {
+            lines.push("G91".to_string());
+        }
+        lines
+    }
+}
+
+fn format_value(value: f64) -> String {
+    TrailingZeroProcessor::normalize_number(&format!("{:.*}", PRECISION, value), 0)
+}
+
+/// Commands that restore a job's state before resuming it
+///
+/// Replays `executed`, the program lines that ran before the resume point,
+/// and returns the commands to send after reconnecting and homing, before the
+/// next program line. The prelude restores units, plane, feed mode, work
+/// offset, spindle and coolant, rapids to the last XY position in absolute
+/// mode, feeds down to the last Z and restores the feed rate, motion mode
+/// and distance mode. Settings the program never touched are left alone.
+///
+/// # Arguments
+/// * `executed` - Program lines up to, not including, the resume point
+///
+/// # Returns
+/// Command lines to send, in order
+pub fn resume_prelude<I, S>(executed: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut state = ResumeState::default();
+    for line in executed {
+        state.update(&tokenize_words(line.as_ref()));
+    }
+    state.prelude()
+}
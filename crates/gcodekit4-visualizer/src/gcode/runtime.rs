@@ -0,0 +1,114 @@
+//! Estimating how long a program runs
+//!
+//! A [`RuntimeEstimate`] times every move of a program once, at 100%
+//! overrides, and then scales feed and rapid time by the overrides in effect
+//! whenever it is asked. Changing the feed or rapid override during a job
+//! therefore updates the remaining time straight away, without walking the
+//! program again. Acceleration is ignored, so real jobs with many short
+//! moves take longer than estimated.
+
+use std::time::Duration;
+
+use gcodekit4_core::OverrideState;
+
+use super::extents::walk_moves;
+use super::GcodeCommand;
+
+/// Rapid rate assumed when the machine's is unknown (mm/min)
+pub const DEFAULT_RAPID_RATE: f64 = 5000.0;
+
+/// Millimeters per inch
+const MM_PER_INCH: f64 = 25.4;
+
+/// Feed and rapid time of a program at 100% overrides
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RuntimeEstimate {
+    /// Seconds each command spends feeding
+    feed_seconds: Vec<f64>,
+    /// Seconds each command spends at rapid
+    rapid_seconds: Vec<f64>,
+}
+
+impl RuntimeEstimate {
+    /// Time every move of parsed commands
+    ///
+    /// Feed moves run at the modal `F` rate, or take `1/F` minutes in inverse
+    /// time mode (`G93`); moves before any feed rate is set take no time.
+    /// Rapids run at `rapid_rate`, given in mm/min whatever the program units.
+    ///
+    /// # Arguments
+    /// * `commands` - Parsed commands in program order
+    /// * `rapid_rate` - Machine rapid rate in mm/min, e.g. [`DEFAULT_RAPID_RATE`]
+    pub fn new(commands: &[GcodeCommand], rapid_rate: f64) -> Self {
+        let mut estimate = Self {
+            feed_seconds: vec![0.0; commands.len()],
+            rapid_seconds: vec![0.0; commands.len()],
+        };
+        walk_moves(commands, |step| {
+            if step.motion == 0 {
+                let length = if step.inches {
+                    step.length * MM_PER_INCH
+                } else {
+                    step.length
+                };
+                if rapid_rate > 0.0 {
+                    estimate.rapid_seconds[step.index] = length / rapid_rate * 60.0;
+                }
+                return;
+            }
+            let minutes = match step.feed {
+                Some(feed) if feed > 0.0 && step.inverse_time => 1.0 / feed,
+                Some(feed) if feed > 0.0 => step.length / feed,
+                _ => 0.0,
+            };
+            estimate.feed_seconds[step.index] = minutes * 60.0;
+        });
+        estimate
+    }
+
+    /// Time every move of a program's source text
+    ///
+    /// Times are kept per source line, so line indexes match
+    /// [`QueuedLine::source_line`](super::stream::QueuedLine::source_line).
+    pub fn from_program(content: &str, rapid_rate: f64) -> Self {
+        let commands: Vec<GcodeCommand> = content.lines().map(GcodeCommand::new).collect();
+        Self::new(&commands, rapid_rate)
+    }
+
+    /// Time spent feeding at the given overrides
+    pub fn feed_time(&self, overrides: &OverrideState) -> Duration {
+        self.remaining_feed_time(0, overrides)
+    }
+
+    /// Time spent at rapid at the given overrides
+    pub fn rapid_time(&self, overrides: &OverrideState) -> Duration {
+        self.remaining_rapid_time(0, overrides)
+    }
+
+    /// Time the whole program takes at the given overrides
+    pub fn total_time(&self, overrides: &OverrideState) -> Duration {
+        self.remaining_time(0, overrides)
+    }
+
+    /// Time left from line `next_line` (0-indexed) to the end of the program
+    pub fn remaining_time(&self, next_line: usize, overrides: &OverrideState) -> Duration {
+        self.remaining_feed_time(next_line, overrides)
+            + self.remaining_rapid_time(next_line, overrides)
+    }
+
+    fn remaining_feed_time(&self, next_line: usize, overrides: &OverrideState) -> Duration {
+        scaled(&self.feed_seconds, next_line, overrides.feed_override)
+    }
+
+    fn remaining_rapid_time(&self, next_line: usize, overrides: &OverrideState) -> Duration {
+        let percent = u16::from(overrides.rapid_override);
+        scaled(&self.rapid_seconds, next_line, percent)
+    }
+}
+
+/// Sum the times from `from` onwards, stretched by an override percentage
+fn scaled(seconds: &[f64], from: usize, percent: u16) -> Duration {
+    let total: f64 = seconds.iter().skip(from).sum();
+    // A 0% override stops motion; treat it as the slowest step instead
+    Duration::from_secs_f64(total * 100.0 / f64::from(percent.max(1)))
+}
@@ -0,0 +1,149 @@
+//! Retracting to a safe height before rapids
+//!
+//! The `SafeZProcessor` guards against a rapid dragging the tool through the
+//! work: an XY rapid (`G0`) that starts below the `safe_z` option gets a
+//! `G00 Z<safe_z>` retract written in front of it. Rapids that already start
+//! at or above safe Z, e.g. because the program retracted first, are left
+//! alone.
+//!
+//! `GcodeState` carries the program's modes but not its position, so the
+//! processor follows Z itself, reading the distance mode from the pipeline's
+//! state. Z is unknown until the first absolute Z word, and becomes unknown
+//! again after a reference return or a move in machine coordinates; nothing
+//! is injected while it's unknown. In incremental mode (`G91`) the retract
+//! is written as `G90 G00 Z<safe_z>` followed by a `G91` to switch back.
+
+use std::sync::Mutex;
+
+use super::{word_spans, CommandProcessor, GcodeCommand, GcodeState, ProcessorConfig};
+
+/// Injects a retract to safe Z before rapids that start below it
+pub struct SafeZProcessor {
+    config: ProcessorConfig,
+    /// Z position, unknown until the first absolute Z word
+    z: Mutex<Option<f64>>,
+}
+
+impl SafeZProcessor {
+    /// Create a processor retracting to `safe_z` before low rapids
+    pub fn new(safe_z: f64) -> Self {
+        Self::with_config(ProcessorConfig::new().with_option("safe_z", safe_z.to_string()))
+    }
+
+    /// Create from a configuration with a `safe_z` option
+    pub fn with_config(config: ProcessorConfig) -> Self {
+        Self {
+            config,
+            z: Mutex::new(None),
+        }
+    }
+
+    /// Height retracted to, in program units
+    pub fn safe_z(&self) -> f64 {
+        self.config
+            .get_option("safe_z")
+            .and_then(|v| v.parse::<f64>().ok())
+            .unwrap_or(5.0)
+    }
+}
+
+impl Default for SafeZProcessor {
+    fn default() -> Self {
+        Self::new(5.0)
+    }
+}
+
+impl CommandProcessor for SafeZProcessor {
+    fn name(&self) -> &str {
+        "safe_z"
+    }
+
+    fn description(&self) -> &str {
+        "Retracts to safe Z before rapids that start below it"
+    }
+
+    /// Forget the tracked Z
+    fn reset(&self) {
+        if let Ok(mut z) = self.z.lock() {
+            *z = None;
+        }
+    }
+
+    fn process(
+        &self,
+        command: &GcodeCommand,
+        state: &GcodeState,
+    ) -> Result<Vec<GcodeCommand>, String> {
+        let line = &command.command;
+        let code = &line[..line.find([';', '(']).unwrap_or(line.len())];
+
+        let mut motion = state.motion_mode;
+        let mut incremental = state.distance_mode == 91;
+        let mut non_modal = None;
+        let mut z_word = None;
+        let mut moves_xy = false;
+        for (letter, span) in word_spans(code) {
+            let Ok(value) = code[span].parse::<f64>() else {
+                continue;
+            };
+            match letter {
+                'G' => match (value * 10.0).round() as i64 {
+                    mode @ (0 | 10 | 20 | 30) => motion = (mode / 10) as u8,
+                    900 => incremental = false,
+                    910 => incremental = true,
+                    code @ (100 | 280 | 300 | 530 | 920) => non_modal = Some(code),
+                    _ => {}
+                },
+                'X' | 'Y' => moves_xy = true,
+                'Z' => z_word = Some(value),
+                _ => {}
+            }
+        }
+
+        let mut z = self.z.lock().map_err(|e| e.to_string())?;
+        match non_modal {
+            // G92 names the current position
+            Some(920) => *z = z_word.or(*z),
+            // Reference returns and machine coordinate moves end somewhere
+            // the program's coordinates can't say
+            Some(280 | 300) => *z = None,
+            Some(530) if z_word.is_some() => *z = None,
+            _ => {}
+        }
+        if non_modal.is_some() {
+            return Ok(vec![command.clone()]);
+        }
+
+        let safe_z = self.safe_z();
+        let mut commands = Vec::new();
+        if motion == 0 && moves_xy && z.is_some_and(|z| z < safe_z) {
+            let retract = format!("G00 Z{}", safe_z);
+            let mut inject = |text: String| {
+                let mut injected = command.clone();
+                injected.command = text;
+                commands.push(injected);
+            };
+            if incremental {
+                inject(format!("G90 {}", retract));
+                inject("G91".to_string());
+            } else {
+                inject(retract);
+            }
+            *z = Some(safe_z);
+        }
+
+        if let Some(value) = z_word {
+            *z = if incremental {
+                z.map(|z| z + value)
+            } else {
+                Some(value)
+            };
+        }
+        commands.push(command.clone());
+        Ok(commands)
+    }
+
+    fn config(&self) -> &ProcessorConfig {
+        &self.config
+    }
+}
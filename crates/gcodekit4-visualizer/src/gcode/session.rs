@@ -0,0 +1,212 @@
+//! Recording and replaying controller sessions
+//!
+//! A [`SessionTranscript`] records every line sent to the controller and
+//! every line received from it, with the time it happened, and is stored as
+//! JSON. Users can attach one to a bug report, and maintainers can feed it
+//! back through [`SessionTranscript::replay`] to rebuild the send audit log
+//! and the program state offline, without a machine.
+
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::stream::SendAuditLog;
+use super::{tokenize_words, CommandState, GcodeParser};
+use crate::utils::ProgramState;
+
+/// Which way a recorded line travelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionDirection {
+    /// Sent to the controller
+    Sent,
+    /// Received from the controller
+    Received,
+}
+
+/// One line sent or received during a session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionEvent {
+    /// When the line was sent or received (milliseconds since the Unix epoch)
+    pub timestamp: u64,
+    /// Which way the line travelled
+    pub direction: SessionDirection,
+    /// Line text, without its terminator
+    pub line: String,
+    /// Index of a sent line in the source program (0-indexed), if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_line: Option<usize>,
+}
+
+/// Result of replaying a session transcript
+#[derive(Debug, Clone)]
+pub struct SessionReplay {
+    /// Audit log rebuilt from the sent lines and their responses
+    pub audit_log: SendAuditLog,
+    /// Program state after the last acknowledged line
+    pub program_state: ProgramState,
+}
+
+/// Transcript of a controller session
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionTranscript {
+    events: Vec<SessionEvent>,
+}
+
+impl SessionTranscript {
+    /// Create an empty transcript
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a line sent to the controller
+    ///
+    /// # Arguments
+    /// * `line` - Text sent to the controller
+    /// * `source_line` - Index of the line in the source program (0-indexed), if known
+    pub fn record_sent(&mut self, line: &str, source_line: Option<usize>) {
+        self.push(SessionDirection::Sent, line, source_line);
+    }
+
+    /// Record a line received from the controller
+    pub fn record_received(&mut self, line: &str) {
+        self.push(SessionDirection::Received, line, None);
+    }
+
+    fn push(&mut self, direction: SessionDirection, line: &str, source_line: Option<usize>) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        self.events.push(SessionEvent {
+            timestamp,
+            direction,
+            line: line.trim_end_matches(['\r', '\n']).to_string(),
+            source_line,
+        });
+    }
+
+    /// All recorded events in order
+    pub fn events(&self) -> &[SessionEvent] {
+        &self.events
+    }
+
+    /// Forget all events, e.g. before a new session
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    /// Export the transcript as JSON
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Read a transcript exported by [`to_json`](Self::to_json)
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Write the transcript to a JSON file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let json = self.to_json().map_err(std::io::Error::other)?;
+        std::fs::write(path, json)
+    }
+
+    /// Read a transcript from a JSON file
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Self::from_json(&json).map_err(std::io::Error::other)
+    }
+
+    /// Replay the session offline
+    ///
+    /// Sent lines and their `ok`/`error:N` responses are fed to a fresh
+    /// [`SendAuditLog`] with the recorded timestamps. Each acknowledged line
+    /// is parsed to track the program state: position through `G90`/`G91`,
+    /// tool, spindle speed and feed rate, with `current_line` set to the
+    /// 1-based source line last acknowledged. Lines answered with an error
+    /// never ran, so they leave the state alone. Other received lines, such
+    /// as status reports, don't affect the replay.
+    pub fn replay(&self) -> SessionReplay {
+        let mut audit_log = SendAuditLog::new();
+        let mut parser = GcodeParser::new();
+        let mut program_state = ProgramState {
+            speed: 0.0,
+            feed_rate: 0.0,
+            ..ProgramState::new()
+        };
+
+        for event in &self.events {
+            match event.direction {
+                SessionDirection::Sent => {
+                    audit_log.record_sent(&event.line, event.source_line);
+                    if let Some(entry) = audit_log.entries_mut().last_mut() {
+                        entry.sent_at = Some(event.timestamp);
+                    }
+                }
+                SessionDirection::Received => {
+                    let response = event.line.trim();
+                    if response != "ok" && !response.starts_with("error:") {
+                        continue;
+                    }
+                    let Some(entry) = audit_log.record_response(response) else {
+                        continue;
+                    };
+                    let index = entry.sequence_number as usize;
+                    let acknowledged = entry.state == CommandState::Ok;
+                    let entry = &mut audit_log.entries_mut()[index];
+                    entry.completed_at = Some(event.timestamp);
+                    if acknowledged {
+                        apply_line(&mut parser, &mut program_state, &entry.command);
+                    }
+                }
+            }
+        }
+        if let Some(line) = audit_log.last_acknowledged_line() {
+            program_state.current_line = line as u32 + 1;
+        }
+
+        SessionReplay {
+            audit_log,
+            program_state,
+        }
+    }
+}
+
+/// Track the effect of an executed line on the program state
+fn apply_line(parser: &mut GcodeParser, program_state: &mut ProgramState, line: &str) {
+    // System commands and blank lines don't change the program state
+    if line.trim_start().starts_with('$') || parser.parse(line).is_err() {
+        return;
+    }
+    let state = parser.get_state();
+    let words = tokenize_words(line);
+
+    // Machine coordinates, reference returns and offset changes don't move
+    // the program position
+    let moves_program = !words.iter().any(|&(letter, value)| {
+        let code = (value * 10.0).round() as i64;
+        letter == 'G' && matches!(code, 100 | 280 | 300 | 530 | 920..=923)
+    });
+    if moves_program {
+        for &(letter, value) in &words {
+            let axis = match letter {
+                'X' => &mut program_state.x,
+                'Y' => &mut program_state.y,
+                'Z' => &mut program_state.z,
+                _ => continue,
+            };
+            if state.distance_mode == 91 {
+                *axis += value;
+            } else {
+                *axis = value;
+            }
+        }
+    }
+    if words.iter().any(|(letter, _)| *letter == 'T') {
+        program_state.tool = Some(u32::from(state.tool_number));
+    }
+    program_state.speed = state.spindle_speed;
+    program_state.feed_rate = state.feed_rate;
+}
@@ -2,21 +2,146 @@
 //!
 //! This module provides:
 //! - Stream reader trait for different input sources
-//! - File-based stream reader for reading from disk
+//! - File-based stream reader for reading from disk, including gzip files
 //! - String-based stream reader for in-memory G-Code
 //! - Stream position tracking and pause/resume capabilities
+//! - Line ending detection, with `\n`, `\r\n` and bare `\r` all accepted
+//! - Byte and line granularity progress reporting
+//! - Send queue that skips blank and comment-only lines
+//! - Line numbers, checksums and resends for controllers that check lines
+//! - Audit log of sent lines and their responses
+//! - Resuming a stream after a lost connection
+//! - Modal state at any line, for skipping blocks that already ran
 
+use std::collections::VecDeque;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Read};
 use std::path::Path;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
+use flate2::read::GzDecoder;
+
+use super::resume::resume_prelude;
+use super::{
+    apply_cut_factor, strip_comments, tokenize_words, CommandResponse, CommandState, GcodeCommand,
+    GcodeState,
+};
+use crate::utils::PerformanceMetrics;
+
+/// Position of a stream reader within its source
+///
+/// Progress is measured in source bytes so it stays accurate when line lengths
+/// vary or when lines are later expanded into several commands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StreamProgress {
+    /// Bytes consumed from the source, including line terminators
+    pub byte_offset: u64,
+    /// Index of the next line to be read (0-indexed)
+    pub line_index: usize,
+    /// Total size of the source in bytes
+    pub total_bytes: u64,
+    /// Total number of lines in the source
+    pub total_lines: usize,
+}
+
+impl StreamProgress {
+    /// Get the progress as a fraction (0.0-1.0) based on bytes consumed
+    pub fn fraction(&self) -> f64 {
+        if self.total_bytes == 0 {
+            1.0
+        } else {
+            (self.byte_offset as f64 / self.total_bytes as f64).min(1.0)
+        }
+    }
+
+    /// Get the progress as a percentage (0-100) based on bytes consumed
+    pub fn percent(&self) -> f64 {
+        self.fraction() * 100.0
+    }
+}
+
+/// Line terminator used by a G-Code source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineEnding {
+    /// `\n`, as written on Unix and most post-processors
+    #[default]
+    Lf,
+    /// `\r\n`, as written on Windows
+    CrLf,
+    /// A bare `\r`, as written by some older post-processors
+    Cr,
+}
+
+impl LineEnding {
+    /// Detect the line ending of some text from its first terminator
+    ///
+    /// Text without any terminator is reported as [`LineEnding::Lf`].
+    pub fn detect(text: &str) -> Self {
+        let mut bytes = text.as_bytes();
+        read_raw_line(&mut bytes, &mut Vec::new())
+            .ok()
+            .flatten()
+            .and_then(|(_, ending)| ending)
+            .unwrap_or_default()
+    }
+
+    /// The terminator's characters
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+            LineEnding::Cr => "\r",
+        }
+    }
+}
+
+/// Read one line ending at `\n`, `\r\n` or a bare `\r` into `line`
+///
+/// The terminator is left out of `line`. Returns the bytes consumed and the
+/// terminator found (`None` for a final unterminated line), or `None` at the
+/// end of the source.
+fn read_raw_line<R: BufRead>(
+    reader: &mut R,
+    line: &mut Vec<u8>,
+) -> std::io::Result<Option<(usize, Option<LineEnding>)>> {
+    line.clear();
+    let mut consumed = 0;
+    loop {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+            return Ok((consumed > 0).then_some((consumed, None)));
+        }
+        let Some(end) = buf.iter().position(|&b| b == b'\n' || b == b'\r') else {
+            let len = buf.len();
+            line.extend_from_slice(buf);
+            reader.consume(len);
+            consumed += len;
+            continue;
+        };
+
+        let terminator = buf[end];
+        line.extend_from_slice(&buf[..end]);
+        reader.consume(end + 1);
+        consumed += end + 1;
+        if terminator == b'\n' {
+            return Ok(Some((consumed, Some(LineEnding::Lf))));
+        }
+        // A CR may be followed by the LF of a CRLF pair, possibly in the next buffer
+        if reader.fill_buf()?.first() == Some(&b'\n') {
+            reader.consume(1);
+            return Ok(Some((consumed + 1, Some(LineEnding::CrLf))));
+        }
+        return Ok(Some((consumed, Some(LineEnding::Cr))));
+    }
+}
+
 /// Trait for reading G-Code streams from various sources
 pub trait GcodeStreamReader: Send + Sync {
     /// Read the next line from the stream
     ///
-    /// Returns `Some(line)` if a line is available, `None` if at end of stream
+    /// Returns `Some(line)` without its line terminator if a line is
+    /// available, `None` if at end of stream
     fn read_line(&mut self) -> Option<String>;
 
     /// Get the current line number (0-indexed)
@@ -33,17 +158,42 @@ pub trait GcodeStreamReader: Send + Sync {
 
     /// Check if stream is at end
     fn is_eof(&self) -> bool;
+
+    /// Get the byte and line position within the source
+    fn position(&self) -> StreamProgress;
+
+    /// Get the number of source bytes consumed, including line terminators
+    fn bytes_read(&self) -> u64;
+
+    /// Get the total size of the source in bytes (if known)
+    fn total_bytes(&self) -> Option<u64>;
+
+    /// Get the number of lines read so far
+    fn lines_read(&self) -> usize;
+
+    /// Get the line ending used by the source, e.g. to reproduce it when sending
+    fn line_ending(&self) -> LineEnding;
 }
 
+/// First two bytes of every gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
 /// File-based G-Code stream reader
 ///
 /// Reads G-Code from a file on disk, supporting position tracking
-/// and pause/resume functionality.
+/// and pause/resume functionality. Gzip-compressed files are decompressed
+/// while streaming, so callers see plain lines. Compression is detected from
+/// the file's content rather than its extension, so mislabeled files still
+/// read correctly; progress of a compressed file is measured in
+/// decompressed bytes.
 pub struct FileStreamReader {
-    reader: BufReader<File>,
+    reader: BufReader<Box<dyn Read + Send + Sync>>,
     file_path: std::path::PathBuf,
     current_line: usize,
     total_lines: Option<usize>,
+    byte_offset: u64,
+    total_bytes: u64,
+    line_ending: LineEnding,
     is_eof: bool,
 }
 
@@ -56,19 +206,27 @@ impl FileStreamReader {
     /// # Errors
     /// Returns an error if the file cannot be opened
     pub fn new<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
-        let file = File::open(&path)?;
-        let reader = BufReader::new(file);
+        let reader = Self::open_source(path.as_ref())?;
 
-        // Count total lines
-        let file_for_count = File::open(&path)?;
-        let count_reader = BufReader::new(file_for_count);
-        let total_lines = Some(count_reader.lines().count());
+        // Count total lines and bytes, decompressed when the file is gzipped
+        let mut count_reader = Self::open_source(path.as_ref())?;
+        let (mut lines, mut total_bytes, mut line_ending) = (0, 0u64, None);
+        let mut line = Vec::new();
+        while let Some((read, ending)) = read_raw_line(&mut count_reader, &mut line)? {
+            lines += 1;
+            total_bytes += read as u64;
+            line_ending = line_ending.or(ending);
+        }
+        let total_lines = Some(lines);
 
         Ok(Self {
             reader,
             file_path: path.as_ref().to_path_buf(),
             current_line: 0,
             total_lines,
+            byte_offset: 0,
+            total_bytes,
+            line_ending: line_ending.unwrap_or_default(),
             is_eof: false,
         })
     }
@@ -78,6 +236,28 @@ impl FileStreamReader {
         &self.file_path
     }
 
+    /// Check whether a file starts with the gzip magic bytes
+    fn is_gzip(path: &Path) -> std::io::Result<bool> {
+        let mut magic = [0u8; 2];
+        let mut file = File::open(path)?;
+        match file.read_exact(&mut magic) {
+            Ok(()) => Ok(magic == GZIP_MAGIC),
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Open a file for reading, decompressing it if it is gzipped
+    fn open_source(path: &Path) -> std::io::Result<BufReader<Box<dyn Read + Send + Sync>>> {
+        let file = File::open(path)?;
+        let source: Box<dyn Read + Send + Sync> = if Self::is_gzip(path)? {
+            Box::new(GzDecoder::new(file))
+        } else {
+            Box::new(file)
+        };
+        Ok(BufReader::new(source))
+    }
+
     /// Get the progress as a percentage (0-100)
     pub fn progress_percent(&self) -> f64 {
         if let Some(total) = self.total_lines {
@@ -98,17 +278,14 @@ impl GcodeStreamReader for FileStreamReader {
             return None;
         }
 
-        let mut line = String::new();
-        match self.reader.read_line(&mut line) {
-            Ok(0) => {
-                self.is_eof = true;
-                None
-            }
-            Ok(_) => {
+        let mut line = Vec::new();
+        match read_raw_line(&mut self.reader, &mut line) {
+            Ok(Some((read, _))) if std::str::from_utf8(&line).is_ok() => {
                 self.current_line += 1;
-                Some(line)
+                self.byte_offset += read as u64;
+                String::from_utf8(line).ok()
             }
-            Err(_) => {
+            _ => {
                 self.is_eof = true;
                 None
             }
@@ -124,9 +301,9 @@ impl GcodeStreamReader for FileStreamReader {
     }
 
     fn reset(&mut self) -> std::io::Result<()> {
-        let file = File::open(&self.file_path)?;
-        self.reader = BufReader::new(file);
+        self.reader = Self::open_source(&self.file_path)?;
         self.current_line = 0;
+        self.byte_offset = 0;
         self.is_eof = false;
         Ok(())
     }
@@ -134,21 +311,49 @@ impl GcodeStreamReader for FileStreamReader {
     fn seek_to_line(&mut self, line_number: usize) -> std::io::Result<()> {
         self.reset()?;
         let mut current = 0;
-        let mut line = String::new();
+        let mut line = Vec::new();
 
-        while current < line_number && self.reader.read_line(&mut line)? > 0 {
+        while current < line_number {
+            let Some((read, _)) = read_raw_line(&mut self.reader, &mut line)? else {
+                break;
+            };
             current += 1;
-            line.clear();
+            self.byte_offset += read as u64;
         }
 
         self.current_line = current;
-        self.is_eof = current >= line_number && line_number > 0;
+        self.is_eof = current < line_number || self.reader.fill_buf()?.is_empty();
         Ok(())
     }
 
     fn is_eof(&self) -> bool {
         self.is_eof
     }
+
+    fn position(&self) -> StreamProgress {
+        StreamProgress {
+            byte_offset: self.byte_offset,
+            line_index: self.current_line,
+            total_bytes: self.total_bytes,
+            total_lines: self.total_lines.unwrap_or(self.current_line),
+        }
+    }
+
+    fn bytes_read(&self) -> u64 {
+        self.byte_offset
+    }
+
+    fn total_bytes(&self) -> Option<u64> {
+        Some(self.total_bytes)
+    }
+
+    fn lines_read(&self) -> usize {
+        self.current_line
+    }
+
+    fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
 }
 
 /// String-based G-Code stream reader
@@ -157,6 +362,9 @@ impl GcodeStreamReader for FileStreamReader {
 /// and pause/resume functionality.
 pub struct StringStreamReader {
     lines: Vec<String>,
+    /// Byte offset of the start of each line, plus the total length
+    line_offsets: Vec<u64>,
+    line_ending: LineEnding,
     current_index: usize,
 }
 
@@ -166,15 +374,22 @@ impl StringStreamReader {
     /// # Arguments
     /// * `content` - The G-Code content as a string
     pub fn new(content: &str) -> Self {
-        let lines = content
-            .lines()
-            .map(|line| line.to_string())
-            .collect::<Vec<_>>();
-
-        let _total = lines.len();
+        let mut lines = Vec::new();
+        let mut line_offsets = vec![0u64];
+        let mut offset = 0u64;
+        let mut reader = content.as_bytes();
+        let mut line = Vec::new();
+        // Reading from a string can't fail
+        while let Ok(Some((read, _))) = read_raw_line(&mut reader, &mut line) {
+            lines.push(String::from_utf8_lossy(&line).into_owned());
+            offset += read as u64;
+            line_offsets.push(offset);
+        }
 
         Self {
             lines,
+            line_offsets,
+            line_ending: LineEnding::detect(content),
             current_index: 0,
         }
     }
@@ -232,6 +447,36 @@ impl GcodeStreamReader for StringStreamReader {
     fn is_eof(&self) -> bool {
         self.current_index >= self.lines.len()
     }
+
+    fn position(&self) -> StreamProgress {
+        let total_bytes = self.line_offsets.last().copied().unwrap_or(0);
+        StreamProgress {
+            byte_offset: self
+                .line_offsets
+                .get(self.current_index)
+                .copied()
+                .unwrap_or(total_bytes),
+            line_index: self.current_index,
+            total_bytes,
+            total_lines: self.lines.len(),
+        }
+    }
+
+    fn bytes_read(&self) -> u64 {
+        self.position().byte_offset
+    }
+
+    fn total_bytes(&self) -> Option<u64> {
+        self.line_offsets.last().copied()
+    }
+
+    fn lines_read(&self) -> usize {
+        self.current_index
+    }
+
+    fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
 }
 
 /// Pausable G-Code stream wrapper
@@ -289,6 +534,53 @@ impl PausableStream {
     pub fn is_eof(&self) -> bool {
         self.inner.is_eof()
     }
+
+    /// Modal state in effect just before a line
+    ///
+    /// Replays the lines before `line` (0-indexed) through a fresh
+    /// [`GcodeState`], then returns the stream to where it was, still paused
+    /// if it was. Use it with [`seek_to_line`](GcodeStreamReader::seek_to_line)
+    /// to skip blocks that already ran without losing their units, distance
+    /// mode, work offset, feed, spindle and coolant. Words the state rejects,
+    /// such as a negative feed rate, are ignored.
+    pub fn modal_state_at(&mut self, line: usize) -> std::io::Result<GcodeState> {
+        let position = self.inner.current_line_number();
+        self.inner.seek_to_line(0)?;
+        let mut state = GcodeState::new();
+        while self.inner.current_line_number() < line {
+            let Some(text) = self.inner.read_line() else {
+                break;
+            };
+            let _ = state.apply_words(&tokenize_words(&text));
+        }
+        self.inner.seek_to_line(position)?;
+        Ok(state)
+    }
+
+    /// Prepare to resume the stream at a line after a lost connection
+    ///
+    /// Re-reads the lines before `next_line` to work out the modal state
+    /// they left behind, then positions the stream at `next_line`. Send the
+    /// returned prelude (see [`resume_prelude`]) once the controller is
+    /// reconnected and homed, then carry on reading from the stream.
+    ///
+    /// # Arguments
+    /// * `next_line` - First line to send again (0-indexed)
+    ///
+    /// # Returns
+    /// The commands restoring the program's state before `next_line`
+    pub fn prepare_resume(&mut self, next_line: usize) -> std::io::Result<Vec<String>> {
+        self.inner.seek_to_line(0)?;
+        let mut executed = Vec::with_capacity(next_line);
+        while executed.len() < next_line {
+            match self.inner.read_line() {
+                Some(line) => executed.push(line),
+                None => break,
+            }
+        }
+        self.seek_to_line(next_line)?;
+        Ok(resume_prelude(executed))
+    }
 }
 
 impl GcodeStreamReader for PausableStream {
@@ -323,4 +615,453 @@ impl GcodeStreamReader for PausableStream {
     fn is_eof(&self) -> bool {
         self.inner.is_eof()
     }
+
+    fn position(&self) -> StreamProgress {
+        self.inner.position()
+    }
+
+    fn bytes_read(&self) -> u64 {
+        self.inner.bytes_read()
+    }
+
+    fn total_bytes(&self) -> Option<u64> {
+        self.inner.total_bytes()
+    }
+
+    fn lines_read(&self) -> usize {
+        self.inner.lines_read()
+    }
+
+    fn line_ending(&self) -> LineEnding {
+        self.inner.line_ending()
+    }
+}
+
+/// A program line queued for transmission to the controller
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuedLine {
+    /// Index of the line in the source program (0-indexed)
+    pub source_line: usize,
+    /// Trimmed text to transmit
+    pub text: String,
+}
+
+/// Queue of program lines waiting to be sent to the controller
+///
+/// Lines that are empty once comments and whitespace are stripped are dropped
+/// when the queue is built, so they never cost a controller round-trip or
+/// receive buffer space. Comment-only lines are kept when `send_comments` is
+/// set, since some firmwares log them. Progress is measured against the
+/// source program, so skipped lines still count towards completion.
+///
+/// A cut factor scales the F and S words of each line as it is sent (see
+/// [`SendQueue::set_cut_factor`]); the queued program text is never changed.
+#[derive(Debug, Clone, Default)]
+pub struct SendQueue {
+    lines: VecDeque<QueuedLine>,
+    source_lines: usize,
+    sendable: usize,
+    /// Feed and spindle speed multiplier, `None` for full speed
+    cut_factor: Option<f64>,
+}
+
+impl SendQueue {
+    /// Build a send queue from program text
+    pub fn new(content: &str, send_comments: bool) -> Self {
+        let lines: VecDeque<QueuedLine> = content
+            .lines()
+            .enumerate()
+            .filter_map(|(source_line, line)| {
+                let text = line.trim();
+                let has_code = !strip_comments(text).trim().is_empty();
+                (has_code || (send_comments && !text.is_empty())).then(|| QueuedLine {
+                    source_line,
+                    text: text.to_string(),
+                })
+            })
+            .collect();
+
+        Self {
+            sendable: lines.len(),
+            lines,
+            source_lines: content.lines().count(),
+            cut_factor: None,
+        }
+    }
+
+    /// Scale the feed rate and spindle speed of every line sent from now on
+    ///
+    /// Meant for cautious test cuts, e.g. 0.5 to run the whole program at half
+    /// feed and spindle speed. This is separate from the controller's
+    /// real-time overrides. Setting 1.0 turns it off again, and factors that
+    /// aren't positive are ignored.
+    pub fn set_cut_factor(&mut self, factor: f64) {
+        if factor == 1.0 {
+            self.cut_factor = None;
+        } else if factor.is_finite() && factor > 0.0 {
+            self.cut_factor = Some(factor);
+        }
+    }
+
+    /// Get the feed and spindle speed multiplier (1.0 when not set)
+    pub fn cut_factor(&self) -> f64 {
+        self.cut_factor.unwrap_or(1.0)
+    }
+
+    /// Get the next line as queued from the program
+    pub fn front(&self) -> Option<&QueuedLine> {
+        self.lines.front()
+    }
+
+    /// Get the next line as it should be transmitted, with the cut factor applied
+    pub fn next_to_send(&self) -> Option<QueuedLine> {
+        let line = self.lines.front()?;
+        Some(match self.cut_factor {
+            Some(factor) => QueuedLine {
+                source_line: line.source_line,
+                text: apply_cut_factor(&line.text, factor),
+            },
+            None => line.clone(),
+        })
+    }
+
+    /// Remove the next line once it has been sent
+    pub fn pop_front(&mut self) -> Option<QueuedLine> {
+        self.lines.pop_front()
+    }
+
+    /// Drop all remaining lines, e.g. when a transmission is stopped
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    /// Check if all lines have been sent
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Number of lines still waiting to be sent
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Number of lines that will be transmitted in total
+    pub fn sendable_lines(&self) -> usize {
+        self.sendable
+    }
+
+    /// Number of source lines that were skipped
+    pub fn skipped_lines(&self) -> usize {
+        self.source_lines - self.sendable
+    }
+
+    /// Number of lines in the source program
+    pub fn source_lines(&self) -> usize {
+        self.source_lines
+    }
+
+    /// Get the progress as a fraction (0.0-1.0) of source lines consumed
+    ///
+    /// Skipped lines before the next queued line count as consumed, and a
+    /// drained queue is always complete.
+    pub fn fraction(&self) -> f64 {
+        match self.lines.front() {
+            Some(next) => next.source_line as f64 / self.source_lines as f64,
+            None => 1.0,
+        }
+    }
+
+    /// Get the progress as a percentage (0-100) of source lines consumed
+    pub fn percent(&self) -> f64 {
+        self.fraction() * 100.0
+    }
+}
+
+/// Line numbers and checksums for controllers that verify each line
+///
+/// Marlin can check every line it receives: each is sent as
+/// `N<n> <code>*<checksum>`, where the checksum is the XOR of every byte
+/// before the `*`. A corrupted line is answered with `Resend: <n>`, after
+/// which that line and everything sent after it must be sent again. The
+/// framer numbers lines, strips their comments and keeps the most recent
+/// framed lines for resending.
+#[derive(Debug, Clone)]
+pub struct LineFramer {
+    next: u32,
+    history: VecDeque<(u32, String)>,
+    capacity: usize,
+}
+
+impl LineFramer {
+    /// Number of framed lines kept for resending by default
+    pub const DEFAULT_HISTORY: usize = 128;
+
+    /// Create a framer numbering from line 1
+    pub fn new() -> Self {
+        Self::with_history(Self::DEFAULT_HISTORY)
+    }
+
+    /// Create a framer that keeps the last `capacity` lines for resending
+    pub fn with_history(capacity: usize) -> Self {
+        Self {
+            next: 1,
+            history: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Line number the next framed line will get
+    pub fn next_line_number(&self) -> u32 {
+        self.next
+    }
+
+    /// XOR checksum of a line, as Marlin computes it
+    pub fn checksum(text: &str) -> u8 {
+        text.bytes().fold(0, |checksum, byte| checksum ^ byte)
+    }
+
+    /// Frame a line for sending, giving it the next line number
+    ///
+    /// Comments and surrounding whitespace are removed first, since the
+    /// controller would otherwise count them in the checksum.
+    pub fn frame(&mut self, line: &str) -> String {
+        let numbered = format!("N{} {}", self.next, strip_comments(line).trim());
+        let framed = format!("{}*{}", numbered, Self::checksum(&numbered));
+
+        if self.history.len() == self.capacity {
+            self.history.pop_front();
+        }
+        self.history.push_back((self.next, framed.clone()));
+        self.next += 1;
+        framed
+    }
+
+    /// Restart numbering, returning the framed `M110 N0` that tells the
+    /// controller to do the same
+    ///
+    /// Send this after connecting, before the first framed program line.
+    pub fn reset(&mut self) -> String {
+        self.history.clear();
+        self.next = 0;
+        let framed = self.frame("M110 N0");
+        self.history.clear();
+        framed
+    }
+
+    /// Line number asked for by a `Resend: <n>` (or `rs <n>`) response
+    pub fn parse_resend(response: &str) -> Option<u32> {
+        let response = response.trim();
+        let number = response
+            .strip_prefix("Resend:")
+            .or_else(|| response.strip_prefix("rs "))?
+            .trim();
+        number.strip_prefix('N').unwrap_or(number).parse().ok()
+    }
+
+    /// Framed lines to send again, from `line` onwards
+    ///
+    /// # Returns
+    /// The lines in order, or `None` if `line` was never sent or is no
+    /// longer in the history
+    pub fn resend_from(&self, line: u32) -> Option<Vec<String>> {
+        let start = self
+            .history
+            .iter()
+            .position(|(number, _)| *number == line)?;
+        Some(
+            self.history
+                .iter()
+                .skip(start)
+                .map(|(_, framed)| framed.clone())
+                .collect(),
+        )
+    }
+
+    /// Handle a controller response, returning the lines to resend if it
+    /// asked for any
+    pub fn handle_response(&self, response: &str) -> Option<Vec<String>> {
+        Self::parse_resend(response).and_then(|line| self.resend_from(line))
+    }
+}
+
+impl Default for LineFramer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Record of every line sent to the controller and the response it got
+///
+/// Each sent line becomes a [`GcodeCommand`] carrying its state, response and
+/// sent/completed timestamps. Controllers answer lines in order, so each
+/// response completes the oldest line still awaiting one. The log can be
+/// exported as JSON or CSV after a run for traceability.
+#[derive(Debug, Clone, Default)]
+pub struct SendAuditLog {
+    entries: Vec<GcodeCommand>,
+    awaiting: VecDeque<usize>,
+}
+
+impl SendAuditLog {
+    /// Create an empty audit log
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a line as sent
+    ///
+    /// # Arguments
+    /// * `line` - Text sent to the controller
+    /// * `source_line` - Index of the line in the source program (0-indexed), if known
+    pub fn record_sent(&mut self, line: &str, source_line: Option<usize>) {
+        let mut command = GcodeCommand::with_sequence(line, self.entries.len() as u32);
+        if let Some(source_line) = source_line {
+            command.set_line_number(source_line as u32 + 1);
+        }
+        command.mark_sent();
+        self.awaiting.push_back(self.entries.len());
+        self.entries.push(command);
+    }
+
+    /// Record a controller response (`ok` or `error:N`) for the oldest unanswered line
+    ///
+    /// # Returns
+    /// The completed entry, or `None` if no line was awaiting a response
+    pub fn record_response(&mut self, response: &str) -> Option<&GcodeCommand> {
+        let index = self.awaiting.pop_front()?;
+        let response = response.trim();
+        let command = &mut self.entries[index];
+
+        if let Some(code) = response.strip_prefix("error:") {
+            command.mark_error(code.trim().parse().ok(), response.to_string());
+        } else {
+            command.mark_ok();
+            command.set_response(CommandResponse {
+                success: true,
+                message: response.to_string(),
+                error_code: None,
+                data: None,
+            });
+        }
+        Some(command)
+    }
+
+    /// All recorded entries in send order
+    pub fn entries(&self) -> &[GcodeCommand] {
+        &self.entries
+    }
+
+    /// Mutable access to the entries, e.g. to restore recorded timestamps
+    pub(crate) fn entries_mut(&mut self) -> &mut [GcodeCommand] {
+        &mut self.entries
+    }
+
+    /// Source line of the most recent line the controller acknowledged
+    ///
+    /// After a lost connection, streaming resumes at the line after this
+    /// one. Lines sent without a source line are ignored.
+    ///
+    /// # Returns
+    /// The 0-indexed source line, or `None` if no program line was acknowledged
+    pub fn last_acknowledged_line(&self) -> Option<usize> {
+        self.entries
+            .iter()
+            .filter(|c| matches!(c.state, CommandState::Ok | CommandState::Done))
+            .rev()
+            .find_map(|c| c.line_number)
+            .map(|line| line as usize - 1)
+    }
+
+    /// Number of lines still waiting for a response
+    pub fn awaiting_response(&self) -> usize {
+        self.awaiting.len()
+    }
+
+    /// Number of lines answered with an error
+    pub fn error_count(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|c| c.state == CommandState::Error)
+            .count()
+    }
+
+    /// Forget all entries, e.g. before a new run
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.awaiting.clear();
+    }
+
+    /// Summarize the run as performance metrics
+    ///
+    /// Execution time spans from the first send to the last response.
+    pub fn metrics(&self) -> PerformanceMetrics {
+        let first_sent = self.entries.iter().filter_map(|c| c.sent_at).min();
+        let last_completed = self.entries.iter().filter_map(|c| c.completed_at).max();
+
+        let mut metrics = PerformanceMetrics::new();
+        metrics.commands_sent = self.entries.len() as u32;
+        if let (Some(start), Some(end)) = (first_sent, last_completed) {
+            metrics.execution_time = end.saturating_sub(start) as f64 / 1000.0;
+        }
+        metrics.commands_per_second = metrics.throughput();
+        metrics
+    }
+
+    /// Export the entries as a JSON array
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&self.entries)
+    }
+
+    /// Export the entries as CSV with a header row
+    ///
+    /// Timestamps are milliseconds since the Unix epoch; empty when unknown.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "sequence,line_number,command,state,response,sent_at,completed_at,duration_ms\n",
+        );
+        let field = |value: Option<u64>| value.map(|v| v.to_string()).unwrap_or_default();
+
+        for command in &self.entries {
+            let response = command
+                .response
+                .as_ref()
+                .map(|r| r.message.as_str())
+                .unwrap_or("");
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{}\n",
+                command.sequence_number,
+                field(command.line_number.map(u64::from)),
+                csv_escape(&command.command),
+                command.state,
+                csv_escape(response),
+                field(command.sent_at),
+                field(command.completed_at),
+                field(command.execution_duration()),
+            ));
+        }
+        csv
+    }
+
+    /// Write the log to a file, as CSV for a `.csv` extension and JSON otherwise
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let path = path.as_ref();
+        let is_csv = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+        let content = if is_csv {
+            self.to_csv()
+        } else {
+            self.to_json().map_err(std::io::Error::other)?
+        };
+        std::fs::write(path, content)
+    }
+}
+
+/// Quote a CSV field if it contains a separator, quote or newline
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
 }
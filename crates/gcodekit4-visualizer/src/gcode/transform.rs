@@ -0,0 +1,578 @@
+//! Whole-program coordinate transforms
+//!
+//! The `TransformProcessor` moves, rotates and scales a program without going
+//! back to CAM. Points are scaled and rotated about a pivot in the XY plane
+//! and then translated. Absolute (`G90`) endpoints get the full transform,
+//! while incremental (`G91`) distances and arc center offsets (`I J K`) are
+//! only rotated and scaled since they are relative to the current position.
+//!
+//! Rotating a move that only names one of X and Y needs the other axis, so the
+//! processor tracks the untransformed position and writes both words. Lines in
+//! machine coordinates (`G53`), reference returns (`G28`/`G30`) and offset
+//! settings (`G10`/`G92`) are passed through unchanged.
+//!
+//! The `TranslateProcessor` is the shift-only case, e.g. after re-homing to a
+//! different corner. It keeps no state of its own and reads the distance mode
+//! from the pipeline's `GcodeState`.
+//!
+//! The `MirrorProcessor` reflects a program across a vertical and/or
+//! horizontal line, e.g. for the second side of a PCB. A reflection in the
+//! arc plane reverses arc direction, so it also swaps `G2` and `G3`.
+
+use std::sync::Mutex;
+
+use super::{
+    word_spans, CommandProcessor, GcodeCommand, GcodeState, ProcessorConfig, TrailingZeroProcessor,
+};
+
+/// Decimal places written for transformed coordinates
+const PRECISION: usize = 4;
+
+/// Modal state tracked across commands
+#[derive(Debug, Clone, Copy)]
+struct TransformState {
+    /// Untransformed position in program coordinates
+    position: [f64; 3],
+    /// Whether `G91` is active
+    incremental: bool,
+    /// Active arc plane (17, 18 or 19)
+    plane: u8,
+}
+
+impl Default for TransformState {
+    fn default() -> Self {
+        Self {
+            position: [0.0; 3],
+            incremental: false,
+            plane: 17,
+        }
+    }
+}
+
+/// Translates, rotates and scales every coordinate in a program
+pub struct TransformProcessor {
+    config: ProcessorConfig,
+    translation: [f64; 3],
+    /// Rotation about the Z axis in degrees, counter-clockwise
+    rotation: f64,
+    pivot: (f64, f64),
+    scale: f64,
+    state: Mutex<TransformState>,
+}
+
+impl TransformProcessor {
+    /// Create an identity transform
+    pub fn new() -> Self {
+        Self {
+            config: ProcessorConfig::new(),
+            translation: [0.0; 3],
+            rotation: 0.0,
+            pivot: (0.0, 0.0),
+            scale: 1.0,
+            state: Mutex::new(TransformState::default()),
+        }
+    }
+
+    /// Shift the program by an offset
+    pub fn with_translation(mut self, x: f64, y: f64, z: f64) -> Self {
+        self.translation = [x, y, z];
+        self
+    }
+
+    /// Rotate the program counter-clockwise about a pivot in the XY plane
+    pub fn with_rotation(mut self, degrees: f64, pivot_x: f64, pivot_y: f64) -> Self {
+        self.rotation = degrees;
+        self.pivot = (pivot_x, pivot_y);
+        self
+    }
+
+    /// Scale the program uniformly about the pivot
+    ///
+    /// Z and arc radii are scaled by the same factor. The factor must be
+    /// positive, since mirroring would also need arc directions swapped.
+    pub fn with_scale(mut self, factor: f64) -> Self {
+        self.scale = factor;
+        self
+    }
+
+    /// Transform an absolute point
+    pub fn transform_point(&self, x: f64, y: f64, z: f64) -> (f64, f64, f64) {
+        let (px, py) = self.pivot;
+        let (dx, dy, dz) = self.transform_vector(x - px, y - py, z);
+        (
+            px + dx + self.translation[0],
+            py + dy + self.translation[1],
+            dz + self.translation[2],
+        )
+    }
+
+    /// Transform a relative distance, which is rotated and scaled only
+    pub fn transform_vector(&self, x: f64, y: f64, z: f64) -> (f64, f64, f64) {
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        (
+            self.scale * (x * cos - y * sin),
+            self.scale * (x * sin + y * cos),
+            self.scale * z,
+        )
+    }
+
+    fn rotates(&self) -> bool {
+        self.rotation.rem_euclid(360.0) != 0.0
+    }
+
+    fn format_value(value: f64) -> String {
+        let value = if value.abs() < 0.5 * 10f64.powi(-(PRECISION as i32)) {
+            0.0
+        } else {
+            value
+        };
+        TrailingZeroProcessor::normalize_number(&format!("{:.*}", PRECISION, value), 0)
+    }
+
+    /// Transform the words of one line, returning the new words by letter
+    fn transform_words(
+        &self,
+        words: &[(char, f64)],
+        state: &mut TransformState,
+    ) -> Result<Vec<(char, f64)>, String> {
+        let word = |letter: char| {
+            words
+                .iter()
+                .find(|(l, _)| *l == letter)
+                .map(|&(_, value)| value)
+        };
+        let rotates = self.rotates();
+        let mut output = Vec::new();
+
+        let (x, y, z) = (word('X'), word('Y'), word('Z'));
+        if x.is_some() || y.is_some() || z.is_some() {
+            let target = if state.incremental {
+                let delta = [x.unwrap_or(0.0), y.unwrap_or(0.0), z.unwrap_or(0.0)];
+                for (position, delta) in state.position.iter_mut().zip(delta) {
+                    *position += delta;
+                }
+                self.transform_vector(delta[0], delta[1], delta[2])
+            } else {
+                state.position = [
+                    x.unwrap_or(state.position[0]),
+                    y.unwrap_or(state.position[1]),
+                    z.unwrap_or(state.position[2]),
+                ];
+                let [px, py, pz] = state.position;
+                self.transform_point(px, py, pz)
+            };
+
+            let both = rotates && (x.is_some() || y.is_some());
+            if x.is_some() || both {
+                output.push(('X', target.0));
+            }
+            if y.is_some() || both {
+                output.push(('Y', target.1));
+            }
+            if z.is_some() {
+                output.push(('Z', target.2));
+            }
+        }
+
+        let (i, j, k, r) = (word('I'), word('J'), word('K'), word('R'));
+        if rotates && state.plane != 17 && [i, j, k, r].iter().any(Option::is_some) {
+            return Err("Cannot rotate arcs outside the XY plane".to_string());
+        }
+        if i.is_some() || j.is_some() || k.is_some() {
+            let offset =
+                self.transform_vector(i.unwrap_or(0.0), j.unwrap_or(0.0), k.unwrap_or(0.0));
+            let both = rotates && (i.is_some() || j.is_some());
+            if i.is_some() || both {
+                output.push(('I', offset.0));
+            }
+            if j.is_some() || both {
+                output.push(('J', offset.1));
+            }
+            if k.is_some() {
+                output.push(('K', offset.2));
+            }
+        }
+        if let Some(radius) = r {
+            output.push(('R', radius * self.scale));
+        }
+
+        Ok(output)
+    }
+
+    /// Rewrite a line's code with transformed words
+    ///
+    /// Existing words are replaced in place. Words that rotation makes
+    /// necessary (e.g. `Y` on a line that only had `X`) are inserted next to
+    /// their partner so X stays before Y and I before J.
+    fn rewrite(code: &str, output: &[(char, f64)]) -> String {
+        let separator = if code.contains(char::is_whitespace) {
+            " "
+        } else {
+            ""
+        };
+        let spans = word_spans(code);
+        let missing = |letter: char| {
+            if spans.iter().any(|(l, _)| *l == letter) {
+                None
+            } else {
+                output.iter().find(|(l, _)| *l == letter).map(|&(_, v)| v)
+            }
+        };
+
+        let mut result = String::with_capacity(code.len() + 16);
+        let mut last = 0;
+        for (letter, span) in &spans {
+            let Some(&(_, value)) = output.iter().find(|(l, _)| l == letter) else {
+                continue;
+            };
+            // The letter precedes its number, possibly separated by spaces
+            let letter_start = code[..span.start].trim_end().len() - 1;
+            result.push_str(&code[last..letter_start]);
+            let before = match letter {
+                'Y' => Some('X'),
+                'J' => Some('I'),
+                _ => None,
+            };
+            if let Some((partner, value)) = before.and_then(|p| missing(p).map(|v| (p, v))) {
+                result.push(partner);
+                result.push_str(&Self::format_value(value));
+                result.push_str(separator);
+            }
+            result.push_str(&code[letter_start..span.start]);
+            result.push_str(&Self::format_value(value));
+            last = span.end;
+
+            let after = match letter {
+                'X' => Some('Y'),
+                'I' => Some('J'),
+                _ => None,
+            };
+            if let Some((partner, value)) = after.and_then(|p| missing(p).map(|v| (p, v))) {
+                result.push_str(separator);
+                result.push(partner);
+                result.push_str(&Self::format_value(value));
+            }
+        }
+        result.push_str(&code[last..]);
+        result
+    }
+}
+
+impl Default for TransformProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommandProcessor for TransformProcessor {
+    fn name(&self) -> &str {
+        "transform"
+    }
+
+    fn description(&self) -> &str {
+        "Translates, rotates and scales all program coordinates"
+    }
+
+    /// Forget the tracked position and modes
+    fn reset(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            *state = TransformState::default();
+        }
+    }
+
+    fn process(
+        &self,
+        command: &GcodeCommand,
+        _state: &GcodeState,
+    ) -> Result<Vec<GcodeCommand>, String> {
+        if !(self.scale.is_finite() && self.scale > 0.0) {
+            return Err(format!("Scale factor must be positive, got {}", self.scale));
+        }
+
+        let line = &command.command;
+        let split = line.find([';', '(']).unwrap_or(line.len());
+        let (code, comment) = line.split_at(split);
+        let words = super::tokenize_words(code);
+
+        let mut state = self.state.lock().map_err(|e| e.to_string())?;
+        let mut passthrough = false;
+        for &(letter, value) in &words {
+            if letter != 'G' {
+                continue;
+            }
+            match (value * 10.0).round() as i64 {
+                170 | 180 | 190 => state.plane = (value as u8).clamp(17, 19),
+                900 => state.incremental = false,
+                910 => state.incremental = true,
+                100 | 280 | 300 | 530 | 920..=923 => passthrough = true,
+                _ => {}
+            }
+        }
+        if passthrough {
+            return Ok(vec![command.clone()]);
+        }
+
+        let output = self.transform_words(&words, &mut state)?;
+        if output.is_empty() {
+            return Ok(vec![command.clone()]);
+        }
+
+        let mut processed = command.clone();
+        processed.command = format!("{}{}", Self::rewrite(code, &output), comment);
+        Ok(vec![processed])
+    }
+
+    fn config(&self) -> &ProcessorConfig {
+        &self.config
+    }
+}
+
+/// Shifts every absolute coordinate in a program by an offset
+///
+/// The `dx`, `dy` and `dz` options are added to the X, Y and Z words of
+/// motion lines in absolute mode (`G90`). Incremental (`G91`) distances and
+/// arc center offsets (`I J K`) are relative, so they are left alone, as are
+/// the lines [`TransformProcessor`] passes through.
+#[derive(Debug, Clone)]
+pub struct TranslateProcessor {
+    config: ProcessorConfig,
+}
+
+impl TranslateProcessor {
+    /// Create a processor shifting the program by `(dx, dy, dz)`
+    pub fn new(dx: f64, dy: f64, dz: f64) -> Self {
+        Self::with_config(
+            ProcessorConfig::new()
+                .with_option("dx", dx.to_string())
+                .with_option("dy", dy.to_string())
+                .with_option("dz", dz.to_string()),
+        )
+    }
+
+    /// Create from a configuration with `dx`, `dy` and `dz` options
+    pub fn with_config(config: ProcessorConfig) -> Self {
+        Self { config }
+    }
+
+    /// The offset added to absolute X, Y and Z
+    pub fn offset(&self) -> [f64; 3] {
+        ["dx", "dy", "dz"].map(|key| {
+            self.config
+                .get_option(key)
+                .and_then(|v| v.parse::<f64>().ok())
+                .unwrap_or(0.0)
+        })
+    }
+}
+
+impl Default for TranslateProcessor {
+    fn default() -> Self {
+        Self::new(0.0, 0.0, 0.0)
+    }
+}
+
+impl CommandProcessor for TranslateProcessor {
+    fn name(&self) -> &str {
+        "translate"
+    }
+
+    fn description(&self) -> &str {
+        "Shifts all absolute coordinates by an offset"
+    }
+
+    fn process(
+        &self,
+        command: &GcodeCommand,
+        state: &GcodeState,
+    ) -> Result<Vec<GcodeCommand>, String> {
+        let line = &command.command;
+        let code = &line[..line.find([';', '(']).unwrap_or(line.len())];
+
+        // A distance mode on this line applies to its own move
+        let mut incremental = state.distance_mode == 91;
+        for (letter, value) in super::tokenize_words(code) {
+            if letter != 'G' {
+                continue;
+            }
+            match (value * 10.0).round() as i64 {
+                900 => incremental = false,
+                910 => incremental = true,
+                100 | 280 | 300 | 530 | 920..=923 => return Ok(vec![command.clone()]),
+                _ => {}
+            }
+        }
+        if incremental {
+            return Ok(vec![command.clone()]);
+        }
+
+        let offset = self.offset();
+        let mut result = String::with_capacity(line.len());
+        let mut last = 0;
+        for (letter, span) in word_spans(code) {
+            let axis = match letter {
+                'X' => 0,
+                'Y' => 1,
+                'Z' => 2,
+                _ => continue,
+            };
+            let Ok(value) = code[span.clone()].parse::<f64>() else {
+                continue;
+            };
+            result.push_str(&line[last..span.start]);
+            result.push_str(&TransformProcessor::format_value(value + offset[axis]));
+            last = span.end;
+        }
+        result.push_str(&line[last..]);
+
+        let mut processed = command.clone();
+        processed.command = result;
+        Ok(vec![processed])
+    }
+
+    fn config(&self) -> &ProcessorConfig {
+        &self.config
+    }
+}
+
+/// Mirrors X and/or Y across an axis line
+///
+/// The `mirror_x` option reflects X across the vertical line at `axis_x`, and
+/// `mirror_y` reflects Y across the horizontal line at `axis_y`. Incremental
+/// distances and arc center offsets are negated on the mirrored axes and Z is
+/// left unchanged. When an odd number of the arc plane's axes are mirrored,
+/// `G2` and `G3` words are swapped; modal arcs without a motion word already
+/// follow the swapped mode the pipeline tracks in `GcodeState`.
+#[derive(Debug, Clone)]
+pub struct MirrorProcessor {
+    config: ProcessorConfig,
+}
+
+impl MirrorProcessor {
+    /// Create a processor mirroring the chosen axes across zero
+    pub fn new(mirror_x: bool, mirror_y: bool) -> Self {
+        Self::with_config(
+            ProcessorConfig::new()
+                .with_option("mirror_x", mirror_x.to_string())
+                .with_option("mirror_y", mirror_y.to_string()),
+        )
+    }
+
+    /// Create from a configuration with `mirror_x`, `mirror_y`, `axis_x` and
+    /// `axis_y` options
+    pub fn with_config(config: ProcessorConfig) -> Self {
+        Self { config }
+    }
+
+    /// Mirror X across the vertical line at `x`
+    pub fn with_axis_x(mut self, x: f64) -> Self {
+        self.config = self.config.with_option("axis_x", x.to_string());
+        self
+    }
+
+    /// Mirror Y across the horizontal line at `y`
+    pub fn with_axis_y(mut self, y: f64) -> Self {
+        self.config = self.config.with_option("axis_y", y.to_string());
+        self
+    }
+
+    fn flag(&self, key: &str) -> bool {
+        self.config
+            .get_option(key)
+            .and_then(|v| v.parse::<bool>().ok())
+            .unwrap_or(false)
+    }
+
+    fn axis(&self, key: &str) -> f64 {
+        self.config
+            .get_option(key)
+            .and_then(|v| v.parse::<f64>().ok())
+            .unwrap_or(0.0)
+    }
+}
+
+impl Default for MirrorProcessor {
+    fn default() -> Self {
+        Self::new(false, false)
+    }
+}
+
+impl CommandProcessor for MirrorProcessor {
+    fn name(&self) -> &str {
+        "mirror"
+    }
+
+    fn description(&self) -> &str {
+        "Mirrors X and/or Y across an axis line, swapping arc directions"
+    }
+
+    fn process(
+        &self,
+        command: &GcodeCommand,
+        state: &GcodeState,
+    ) -> Result<Vec<GcodeCommand>, String> {
+        let (mirror_x, mirror_y) = (self.flag("mirror_x"), self.flag("mirror_y"));
+        if !mirror_x && !mirror_y {
+            return Ok(vec![command.clone()]);
+        }
+
+        let line = &command.command;
+        let code = &line[..line.find([';', '(']).unwrap_or(line.len())];
+
+        let mut incremental = state.distance_mode == 91;
+        let mut plane = state.plane_mode;
+        for (letter, value) in super::tokenize_words(code) {
+            if letter != 'G' {
+                continue;
+            }
+            match (value * 10.0).round() as i64 {
+                170 | 180 | 190 => plane = value as u8,
+                900 => incremental = false,
+                910 => incremental = true,
+                100 | 280 | 300 | 530 | 920..=923 => return Ok(vec![command.clone()]),
+                _ => {}
+            }
+        }
+        let reversed = match plane {
+            18 => mirror_x,
+            19 => mirror_y,
+            _ => mirror_x != mirror_y,
+        };
+
+        let mut result = String::with_capacity(line.len());
+        let mut last = 0;
+        for (letter, span) in word_spans(code) {
+            let text = &code[span.clone()];
+            let Ok(value) = text.parse::<f64>() else {
+                continue;
+            };
+            let replacement = match letter {
+                // The other digits of `G2`, `G02` or `G2.0` are zeros
+                'G' if reversed && value == 2.0 => text.replace('2', "3"),
+                'G' if reversed && value == 3.0 => text.replace('3', "2"),
+                'X' if mirror_x && incremental => TransformProcessor::format_value(-value),
+                'X' if mirror_x => {
+                    TransformProcessor::format_value(2.0 * self.axis("axis_x") - value)
+                }
+                'Y' if mirror_y && incremental => TransformProcessor::format_value(-value),
+                'Y' if mirror_y => {
+                    TransformProcessor::format_value(2.0 * self.axis("axis_y") - value)
+                }
+                'I' if mirror_x => TransformProcessor::format_value(-value),
+                'J' if mirror_y => TransformProcessor::format_value(-value),
+                _ => continue,
+            };
+            result.push_str(&line[last..span.start]);
+            result.push_str(&replacement);
+            last = span.end;
+        }
+        result.push_str(&line[last..]);
+
+        let mut processed = command.clone();
+        processed.command = result;
+        Ok(vec![processed])
+    }
+
+    fn config(&self) -> &ProcessorConfig {
+        &self.config
+    }
+}
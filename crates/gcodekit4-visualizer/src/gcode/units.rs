@@ -0,0 +1,161 @@
+//! Converting programs between inches and millimeters
+//!
+//! The `UnitConversionProcessor` rewrites a program into one unit system, e.g.
+//! an inch (`G20`) program for a metric machine. Units words are replaced with
+//! the target's and every length word after them (`X Y Z I J K R` and `F`) is
+//! scaled, until the program switches units again.
+//!
+//! The pipeline updates `GcodeState::units_mode` from the rewritten output, so
+//! it always reads the target units. The processor remembers the program's
+//! own units instead, starting from the pipeline's `units_mode` for programs
+//! that never set them.
+
+use std::sync::Mutex;
+
+use super::{
+    word_spans, CommandProcessor, GcodeCommand, GcodeState, ProcessorConfig, TrailingZeroProcessor,
+};
+
+/// Millimeters per inch
+const MM_PER_INCH: f64 = 25.4;
+
+/// Decimal places written for converted millimeter values
+const PRECISION_MM: usize = 4;
+
+/// Decimal places written for converted inch values
+const PRECISION_INCH: usize = 6;
+
+/// Rewrites a program in millimeters (`G21`) or inches (`G20`)
+///
+/// The `target` option is `mm` (the default) or `inch`. Rotary axes and
+/// inverse time (`G93`) feeds aren't lengths and are left alone.
+pub struct UnitConversionProcessor {
+    config: ProcessorConfig,
+    /// Units mode (20 or 21) of the program being read, once known
+    source: Mutex<Option<u8>>,
+}
+
+impl UnitConversionProcessor {
+    /// Create a processor converting programs to millimeters
+    pub fn new() -> Self {
+        Self::with_config(ProcessorConfig::new().with_option("target", "mm"))
+    }
+
+    /// Create a processor converting programs to inches
+    pub fn to_inches() -> Self {
+        Self::with_config(ProcessorConfig::new().with_option("target", "inch"))
+    }
+
+    /// Create from a configuration with a `target` option of `mm` or `inch`
+    pub fn with_config(config: ProcessorConfig) -> Self {
+        Self {
+            config,
+            source: Mutex::new(None),
+        }
+    }
+
+    /// The units mode written, 21 for millimeters or 20 for inches
+    pub fn target_units(&self) -> u8 {
+        match self.config.get_option("target") {
+            Some(target) if target.eq_ignore_ascii_case("inch") => 20,
+            _ => 21,
+        }
+    }
+
+    fn format_value(value: f64, precision: usize) -> String {
+        let value = if value.abs() < 0.5 * 10f64.powi(-(precision as i32)) {
+            0.0
+        } else {
+            value
+        };
+        TrailingZeroProcessor::normalize_number(&format!("{:.*}", precision, value), 0)
+    }
+}
+
+impl Default for UnitConversionProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommandProcessor for UnitConversionProcessor {
+    fn name(&self) -> &str {
+        "unit_conversion"
+    }
+
+    fn description(&self) -> &str {
+        "Converts programs between inches and millimeters"
+    }
+
+    /// Forget the program's units
+    fn reset(&self) {
+        if let Ok(mut source) = self.source.lock() {
+            *source = None;
+        }
+    }
+
+    fn process(
+        &self,
+        command: &GcodeCommand,
+        state: &GcodeState,
+    ) -> Result<Vec<GcodeCommand>, String> {
+        let target = self.target_units();
+        let line = &command.command;
+        let code = &line[..line.find([';', '(']).unwrap_or(line.len())];
+        let spans = word_spans(code);
+
+        let mut source = self.source.lock().map_err(|e| e.to_string())?;
+        let mut units = source.unwrap_or(state.units_mode);
+        let mut inverse_time = state.feed_rate_mode == 93;
+        for (letter, span) in &spans {
+            if *letter != 'G' {
+                continue;
+            }
+            let Ok(value) = code[span.clone()].parse::<f64>() else {
+                continue;
+            };
+            match (value * 10.0).round() as i64 {
+                200 => units = 20,
+                210 => units = 21,
+                930 => inverse_time = true,
+                940 | 950 => inverse_time = false,
+                _ => {}
+            }
+        }
+        *source = Some(units);
+
+        let (scale, precision) = match (units, target) {
+            (20, 21) => (MM_PER_INCH, PRECISION_MM),
+            (21, 20) => (1.0 / MM_PER_INCH, PRECISION_INCH),
+            _ => (1.0, 0),
+        };
+
+        let mut result = String::with_capacity(line.len());
+        let mut last = 0;
+        for (letter, span) in spans {
+            let Ok(value) = code[span.clone()].parse::<f64>() else {
+                continue;
+            };
+            let replacement = match letter {
+                'G' if matches!((value * 10.0).round() as i64, 200 | 210) => target.to_string(),
+                'F' if inverse_time => continue,
+                'X' | 'Y' | 'Z' | 'I' | 'J' | 'K' | 'R' | 'F' if scale != 1.0 => {
+                    Self::format_value(value * scale, precision)
+                }
+                _ => continue,
+            };
+            result.push_str(&line[last..span.start]);
+            result.push_str(&replacement);
+            last = span.end;
+        }
+        result.push_str(&line[last..]);
+
+        let mut processed = command.clone();
+        processed.command = result;
+        Ok(vec![processed])
+    }
+
+    fn config(&self) -> &ProcessorConfig {
+        &self.config
+    }
+}
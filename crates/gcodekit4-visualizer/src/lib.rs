@@ -11,26 +11,52 @@ pub use visualizer::{
     Visualizer, Visualizer2D, VisualizerControls, Scene, Camera, Renderer,
     render_grid_to_path, render_origin_to_path, render_rapid_moves_to_path, render_toolpath_to_path,
     render_g1_to_path, render_g2_to_path, render_g3_to_path, render_g4_to_path,
-    render_intensity_overlay,
+    render_intensity_overlay, render_layer_to_path, render_svg_document, ToolpathLayer,
+    VisualizerTheme, render_direction_arrows_to_path, render_envelope_to_path,
+    render_out_of_bounds_to_path, DirectionArrow, ToolLegendEntry, ToolRendering,
+    render_annotations_to_path, Annotation, PlaybackPosition,
 };
 
 pub use gcode::{
-    stream::{FileStreamReader, GcodeStreamReader, PausableStream, StringStreamReader},
-    CommandId, CommandLengthProcessor, CommandListener, CommandListenerHandle,
-    CommandNumberGenerator, CommandProcessor, CommandResponse, CommandState, CommentProcessor,
-    DecimalProcessor, EmptyLineRemoverProcessor, GcodeCommand, GcodeParser, GcodeState, ModalState,
-    ProcessorConfig, ProcessorHandle, ProcessorPipeline, ProcessorRegistry, WhitespaceProcessor,
+    append_program_end, apply_cut_factor, arc_merge::merge_arcs, axis_map::AxisMapProcessor,
+    check_arc_center_mode, check_distance_mode, check_plunge_rates, check_program_end,
+    check_rapid_retracts, check_zero_length_moves,
+    estimate::{TimeEstimate, TimeEstimator},
+    expression::ExpressionProcessor,
+    extents::{compute_extents, Extents},
+    insert_distance_mode, limits::check_soft_limits, program_end, remove_zero_length_moves,
+    renumber::NRenumberProcessor, resume::resume_prelude,
+    runtime::{RuntimeEstimate, DEFAULT_RAPID_RATE},
+    safe_z::SafeZProcessor,
+    session::{SessionDirection, SessionEvent, SessionReplay, SessionTranscript},
+    split_by_tool, split_operations,
+    stream::{
+        FileStreamReader, GcodeStreamReader, LineEnding, LineFramer, PausableStream, QueuedLine,
+        SendAuditLog, SendQueue, StreamProgress, StringStreamReader,
+    },
+    transform::{MirrorProcessor, TransformProcessor, TranslateProcessor},
+    units::UnitConversionProcessor,
+    ArcNormalizeProcessor, CommandId, CommandLengthProcessor, CommandListener,
+    CommandListenerHandle, CommandNumberGenerator, CommandProcessor, CommandResponse, CommandState,
+    CommentProcessor,
+    DecimalProcessor, DefaultFeedProcessor, EmptyLineRemoverProcessor, FeedRateOverrideProcessor,
+    GcodeCommand, GcodeParser, GcodeState, HeaderFooterProcessor, LineIssue, ModalState, Operation,
+    ParsedWords, PipelineReport, PlungeLimits, ProcessorConfig, ProcessorHandle, ProcessorPipeline,
+    ProcessorRegistry, ProgramEnd, RapidToFeedProcessor, ToolSegment, TrailingZeroProcessor,
+    WhitespaceProcessor,
 };
 
 pub use utils::{
-    AdvancedProber, Alarm, AlarmManager, AlarmType, AutoConnectConfig, BackupEntry, BackupManager,
-    BasicProber, Bookmark, BookmarkManager, CommandHistory, CustomAction, CustomMacro, DataLogger,
-    DropEvent, DropFileType, DropIndicatorState, DropTarget, DropZone, ExportOptions,
-    FeedRateStats, FileComparison, FileEncoding, FileExporter, FileFormat, FileProcessingPipeline,
-    FileReadStats, FileStatistics, FileValidation, GcodeFileReader, GcodeTemplate, HeightPoint,
-    HistoryEntry, LogEntry, NetworkConfig, PendantButton, PendantConfig, PerformanceMetrics,
+    apply_mesh, AdvancedProber, Alarm, AlarmManager, AlarmType, AutoConnectConfig, AutoLevelConfig,
+    AutoLeveler, BackupEntry, BackupManager, BasicProber, Bookmark, BookmarkManager,
+    CommandHistory, CustomAction, CustomMacro, DataLogger, DropEvent, DropFileType,
+    DropIndicatorState, DropTarget, DropZone, ExportOptions, FeedRateStats, FileComparison,
+    FileEncoding, FileExporter, FileFormat, FileProcessingPipeline, FileReadStats, FileStatistics,
+    FileValidation, GcodeFileReader, GcodeTemplate, HeightPoint, HistoryEntry, LogEntry,
+    MeshLevelProcessor, NetworkConfig, PendantButton, PendantConfig, PerformanceMetrics, ProbeGrid,
     ProbeMesh, ProbePoint, ProcessedFile, ProgramState, RecentFileEntry, RecentFilesManager,
-    SimulationPosition, Simulator, SoftLimits, SpindleStats, Stepper, TemplateLibrary,
-    TemplateVariable, ToolInfo, ToolLibrary, ToolOffset, ToolOffsetManager, ValidationIssue,
-    ValidationResult, ValidationSeverity, WorkCoordinateSystem, WorkOffset,
+    RestoreReport, SettingsTarget, SimulationPosition, Simulator, SoftLimits, SpindleStats,
+    Stepper, TemplateLibrary, TemplateVariable, ToolChangeProber, ToolInfo, ToolLibrary,
+    ToolOffset, ToolOffsetManager, ToolProbeConfig, ToolStats, ValidationIssue, ValidationResult,
+    ValidationSeverity, WorkCoordinateSystem, WorkOffset,
 };
@@ -7,10 +7,14 @@
 //! Task 101: Probing - Basic - Z-axis probing
 //! Task 102: Probing - Advanced - Multi-point probing
 
+use super::auto_level::ProbeGrid;
+use super::phase6_extended::{HeightPoint, ProbeMesh};
+use crate::gcode::GcodeCommand;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -230,6 +234,29 @@ impl FileComparison {
         }
     }
 
+    /// Get the line ranges of the processed program that differ from the original
+    ///
+    /// Consecutive changed lines are merged into a single half-open range of
+    /// 0-based line indices into `processed_lines`, so tooling can re-check
+    /// only the affected region after an edit. Lines removed from the end of
+    /// the original have no counterpart in the processed program and do not
+    /// produce a range.
+    pub fn changed_ranges(&self) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+
+        for (i, change) in self.line_changes.iter().enumerate() {
+            if *change == LineChange::Unchanged || i >= self.processed_lines.len() {
+                continue;
+            }
+            match ranges.last_mut() {
+                Some(last) if last.end == i => last.end = i + 1,
+                _ => ranges.push(i..i + 1),
+            }
+        }
+
+        ranges
+    }
+
     /// Get diff summary
     pub fn summary(&self) -> String {
         format!(
@@ -263,6 +290,28 @@ pub struct BackupEntry {
     pub description: String,
 }
 
+/// Device whose settings can be snapshotted and written during a restore
+pub trait SettingsTarget {
+    /// Read the current value of every setting as (id, value) pairs
+    fn read_settings(&mut self) -> Result<Vec<(String, String)>>;
+
+    /// Write a single setting to the device
+    fn write_setting(&mut self, id: &str, value: &str) -> Result<()>;
+}
+
+/// Outcome of restoring settings to a device
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RestoreReport {
+    /// Settings written successfully, in restore order
+    pub applied: Vec<String>,
+    /// Settings that failed to write, with the error message
+    pub failed: Vec<(String, String)>,
+    /// Settings read from the device before the restore started
+    pub snapshot: Vec<(String, String)>,
+    /// Whether the snapshot was written back after too many failures
+    pub rolled_back: bool,
+}
+
 /// Backup and recovery manager
 pub struct BackupManager {
     /// Backup directory
@@ -271,6 +320,8 @@ pub struct BackupManager {
     max_age: u64,
     /// Max backups to keep
     max_backups: usize,
+    /// Failed settings tolerated before a settings restore is rolled back
+    rollback_threshold: usize,
 }
 
 impl BackupManager {
@@ -280,6 +331,7 @@ impl BackupManager {
             backup_dir: backup_dir.as_ref().to_path_buf(),
             max_age: 86400 * 7, // 7 days
             max_backups: 10,
+            rollback_threshold: 0,
         }
     }
 
@@ -293,6 +345,11 @@ impl BackupManager {
         self.max_backups = count;
     }
 
+    /// Set how many settings may fail before a settings restore is rolled back
+    pub fn set_rollback_threshold(&mut self, count: usize) {
+        self.rollback_threshold = count;
+    }
+
     /// Create backup
     pub fn backup(&self, source: impl AsRef<Path>, description: &str) -> Result<BackupEntry> {
         let source = source.as_ref();
@@ -327,6 +384,61 @@ impl BackupManager {
         Ok(())
     }
 
+    /// Restore device settings from a backup with rollback on failure
+    ///
+    /// The backup holds one `id=value` setting per line (e.g. `$110=5000`);
+    /// blank lines and `;` or `(` comments are skipped. The device's current
+    /// settings are snapshotted first. If more settings fail than the rollback
+    /// threshold allows, the original value of every setting the restore
+    /// touched is sent again so the device is not left half restored.
+    pub fn restore_settings(
+        &self,
+        backup: &BackupEntry,
+        target: &mut dyn SettingsTarget,
+    ) -> Result<RestoreReport> {
+        let content = fs::read_to_string(&backup.backup_path)?;
+        let mut report = RestoreReport {
+            snapshot: target.read_settings()?,
+            ..Default::default()
+        };
+
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with(';') || line.starts_with('(') {
+                continue;
+            }
+            let Some((id, value)) = line.split_once('=') else {
+                continue;
+            };
+            let (id, value) = (id.trim(), value.trim());
+
+            match target.write_setting(id, value) {
+                Ok(()) => report.applied.push(id.to_string()),
+                Err(e) => report.failed.push((id.to_string(), e.to_string())),
+            }
+        }
+
+        if report.failed.len() > self.rollback_threshold {
+            tracing::warn!(
+                "Settings restore failed for {} settings, rolling back",
+                report.failed.len()
+            );
+            let touched = report
+                .applied
+                .iter()
+                .chain(report.failed.iter().map(|(id, _)| id));
+            for id in touched {
+                if let Some((_, original)) = report.snapshot.iter().find(|(s, _)| s == id) {
+                    if let Err(e) = target.write_setting(id, original) {
+                        tracing::warn!("Failed to roll back setting {}: {}", id, e);
+                    }
+                }
+            }
+            report.rolled_back = true;
+        }
+
+        Ok(report)
+    }
+
     /// List backups
     pub fn list_backups(&self) -> Result<Vec<BackupEntry>> {
         let mut backups = Vec::new();
@@ -562,12 +674,17 @@ impl ProbePoint {
     }
 }
 
+/// Lowest Z a grid probe travels to before giving up
+const GRID_PROBE_DEPTH: f64 = -50.0;
+
 /// Advanced probing with multiple points
 pub struct AdvancedProber {
     /// Base prober
     base: BasicProber,
     /// Probe points
     probe_points: Vec<ProbePoint>,
+    /// Heights recorded for the current grid
+    mesh: ProbeMesh,
 }
 
 impl AdvancedProber {
@@ -576,6 +693,7 @@ impl AdvancedProber {
         Self {
             base: BasicProber::new(),
             probe_points: Vec::new(),
+            mesh: ProbeMesh::new(0.0, 0.0),
         }
     }
 
@@ -646,6 +764,73 @@ impl AdvancedProber {
         sequence
     }
 
+    /// Generate a routine probing a grid over a rectangle
+    ///
+    /// The grid's outer columns and rows lie on the edges of `bounds`, with
+    /// points no more than `spacing` apart. Rows are visited back and forth,
+    /// each point with a rapid at `clearance`, a `G38.2` probe down at `feed`
+    /// and a retract. The grid replaces the probe points and starts an empty
+    /// mesh for [`record_probe_result`](Self::record_probe_result) to fill.
+    ///
+    /// # Arguments
+    /// * `bounds` - Minimum and maximum XY corners of the area to probe
+    /// * `spacing` - Largest distance between neighbouring points
+    /// * `feed` - Probing feed rate
+    /// * `clearance` - Height to travel at between points
+    pub fn generate_grid_routine(
+        &mut self,
+        bounds: ((f64, f64), (f64, f64)),
+        spacing: f64,
+        feed: f64,
+        clearance: f64,
+    ) -> Vec<GcodeCommand> {
+        let (min, max) = bounds;
+        let grid = ProbeGrid::covering(
+            (min.0.min(max.0), min.1.min(max.1)),
+            (min.0.max(max.0), min.1.max(max.1)),
+            spacing,
+        );
+        let step = |axis: &[f64]| match axis {
+            [first, second, ..] => second - first,
+            _ => 0.0,
+        };
+        self.mesh = ProbeMesh::new(step(&grid.x), step(&grid.y));
+
+        let points = grid.points();
+        self.probe_points = points
+            .iter()
+            .map(|&(x, y)| ProbePoint::new(x, y, 0.0))
+            .collect();
+
+        let mut lines = vec!["G90".to_string(), format!("G0 Z{:.3}", clearance)];
+        for (x, y) in points {
+            lines.push(format!("G0 X{:.3} Y{:.3}", x, y));
+            lines.push(format!("G38.2 Z{:.3} F{:.0}", GRID_PROBE_DEPTH, feed));
+            lines.push(format!("G0 Z{:.3}", clearance));
+        }
+        lines.into_iter().map(GcodeCommand::new).collect()
+    }
+
+    /// Record where a grid probe touched the surface
+    ///
+    /// The height goes into the mesh and onto the matching probe point.
+    pub fn record_probe_result(&mut self, x: f64, y: f64, z: f64) {
+        const TOLERANCE: f64 = 1e-3;
+        if let Some(point) = self
+            .probe_points
+            .iter_mut()
+            .find(|point| (point.x - x).abs() < TOLERANCE && (point.y - y).abs() < TOLERANCE)
+        {
+            point.z = z;
+        }
+        self.mesh.add_point(HeightPoint { x, y, z });
+    }
+
+    /// Heights recorded since the last grid routine was generated
+    pub fn mesh(&self) -> &ProbeMesh {
+        &self.mesh
+    }
+
     /// Get probe points
     pub fn probe_points(&self) -> &[ProbePoint] {
         &self.probe_points
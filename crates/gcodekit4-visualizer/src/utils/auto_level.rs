@@ -0,0 +1,511 @@
+//! Auto-leveling for the loaded program
+//!
+//! Ties probing to the current job. The program's XY extent is covered with
+//! a grid of `G38.2` probes, the `[PRB:...]` reports from the controller are
+//! collected into a `ProbeMesh`, and every move is then offset by the surface
+//! height under it. Long cuts are split so the tool follows the surface
+//! between probe points.
+//!
+//! Heights are relative to the first probe point, at the grid's minimum X/Y
+//! corner, so Z zero should be set there before probing.
+
+use super::phase6_extended::{HeightPoint, ProbeMesh};
+use crate::gcode::{
+    word_spans, CommandProcessor, GcodeCommand, GcodeState, ProcessorConfig, ProcessorPipeline,
+    TrailingZeroProcessor,
+};
+use crate::visualizer::Visualizer2D;
+use anyhow::{anyhow, Result};
+use std::sync::{Arc, Mutex};
+
+/// Decimal places written for leveled coordinates
+const PRECISION: usize = 4;
+
+/// Auto-leveling settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AutoLevelConfig {
+    /// Largest distance between neighbouring probe points
+    pub spacing: f64,
+    /// Extra distance probed around the program's bounds
+    pub margin: f64,
+    /// Height to travel at between probe points
+    pub safe_z: f64,
+    /// Lowest Z a probe may reach before giving up
+    pub probe_depth: f64,
+    /// Probing feed rate
+    pub probe_feed: f64,
+    /// Longest cut left unsplit when leveling
+    pub max_segment: f64,
+}
+
+impl Default for AutoLevelConfig {
+    fn default() -> Self {
+        Self {
+            spacing: 10.0,
+            margin: 0.0,
+            safe_z: 5.0,
+            probe_depth: -5.0,
+            probe_feed: 50.0,
+            max_segment: 5.0,
+        }
+    }
+}
+
+/// A rectangular grid of probe positions
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProbeGrid {
+    /// X positions of the grid columns, ascending
+    pub x: Vec<f64>,
+    /// Y positions of the grid rows, ascending
+    pub y: Vec<f64>,
+}
+
+impl ProbeGrid {
+    /// Cover a rectangle with points no more than `spacing` apart
+    ///
+    /// The first and last columns and rows lie on the rectangle's edges.
+    pub fn covering(min: (f64, f64), max: (f64, f64), spacing: f64) -> Self {
+        Self {
+            x: Self::axis(min.0, max.0, spacing),
+            y: Self::axis(min.1, max.1, spacing),
+        }
+    }
+
+    fn axis(min: f64, max: f64, spacing: f64) -> Vec<f64> {
+        let span = max - min;
+        if span <= 0.0 {
+            return vec![min];
+        }
+        let steps = (span / spacing.max(f64::EPSILON)).ceil().max(1.0) as usize;
+        (0..=steps)
+            .map(|i| min + span * i as f64 / steps as f64)
+            .collect()
+    }
+
+    /// Number of probe points
+    pub fn len(&self) -> usize {
+        self.x.len() * self.y.len()
+    }
+
+    /// Check whether the grid has no points
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Probe positions in the order they are visited
+    ///
+    /// Rows are traversed back and forth to keep travel short.
+    pub fn points(&self) -> Vec<(f64, f64)> {
+        let mut points = Vec::with_capacity(self.len());
+        for (row, &y) in self.y.iter().enumerate() {
+            if row % 2 == 0 {
+                points.extend(self.x.iter().map(|&x| (x, y)));
+            } else {
+                points.extend(self.x.iter().rev().map(|&x| (x, y)));
+            }
+        }
+        points
+    }
+}
+
+/// Probes the area under a program and levels it against the result
+#[derive(Debug, Clone)]
+pub struct AutoLeveler {
+    config: AutoLevelConfig,
+    grid: ProbeGrid,
+    /// Probed Z for each grid point, in visiting order
+    heights: Vec<f64>,
+}
+
+impl AutoLeveler {
+    /// Plan a probe grid covering a program's cutting moves
+    pub fn for_program(program: &str, config: AutoLevelConfig) -> Result<Self> {
+        let mut visualizer = Visualizer2D::new();
+        visualizer.parse_gcode(program);
+        let (min_x, max_x, min_y, max_y) = visualizer
+            .get_cutting_bounds()
+            .ok_or_else(|| anyhow!("Program has no cutting moves to level"))?;
+
+        let grid = ProbeGrid::covering(
+            (min_x as f64 - config.margin, min_y as f64 - config.margin),
+            (max_x as f64 + config.margin, max_y as f64 + config.margin),
+            config.spacing,
+        );
+        Ok(Self {
+            config,
+            grid,
+            heights: Vec::new(),
+        })
+    }
+
+    /// The planned probe grid
+    pub fn grid(&self) -> &ProbeGrid {
+        &self.grid
+    }
+
+    /// G-code that probes every grid point
+    pub fn probe_program(&self) -> Vec<String> {
+        let config = &self.config;
+        let mut lines = vec!["G90".to_string(), format!("G0 Z{:.3}", config.safe_z)];
+        for (x, y) in self.grid.points() {
+            lines.push(format!("G0 X{:.3} Y{:.3}", x, y));
+            lines.push(format!(
+                "G38.2 Z{:.3} F{:.0}",
+                config.probe_depth, config.probe_feed
+            ));
+            lines.push(format!("G0 Z{:.3}", config.safe_z));
+        }
+        lines
+    }
+
+    /// Record a line received from the controller
+    ///
+    /// Returns `true` if the line was a probe report. Fails if a probe ran to
+    /// `probe_depth` without touching the surface.
+    pub fn record_response(&mut self, line: &str) -> Result<bool> {
+        let Some(report) = line
+            .trim()
+            .strip_prefix("[PRB:")
+            .and_then(|rest| rest.strip_suffix(']'))
+        else {
+            return Ok(false);
+        };
+
+        let (coords, success) = report.rsplit_once(':').unwrap_or((report, "1"));
+        let z = coords
+            .split(',')
+            .nth(2)
+            .and_then(|z| z.trim().parse::<f64>().ok())
+            .ok_or_else(|| anyhow!("Malformed probe report: {}", line.trim()))?;
+
+        if success.trim() != "1" {
+            let (x, y) = self
+                .grid
+                .points()
+                .get(self.heights.len())
+                .copied()
+                .unwrap_or_default();
+            return Err(anyhow!(
+                "Probe at X{:.3} Y{:.3} did not touch the surface",
+                x,
+                y
+            ));
+        }
+
+        if self.heights.len() < self.grid.len() {
+            self.heights.push(z);
+        }
+        Ok(true)
+    }
+
+    /// Check whether every grid point has been probed
+    pub fn is_complete(&self) -> bool {
+        self.heights.len() == self.grid.len()
+    }
+
+    /// Build the height map from the recorded probes
+    pub fn mesh(&self) -> Result<ProbeMesh> {
+        if !self.is_complete() {
+            return Err(anyhow!(
+                "Only {} of {} points probed",
+                self.heights.len(),
+                self.grid.len()
+            ));
+        }
+
+        let spacing = |axis: &[f64]| match axis {
+            [first, second, ..] => second - first,
+            _ => 0.0,
+        };
+        // Moves past the outermost probes take the nearest edge's height
+        let mut mesh =
+            ProbeMesh::new(spacing(&self.grid.x), spacing(&self.grid.y)).with_clamp_to_edges(true);
+        let reference = self.heights[0];
+        for ((x, y), z) in self.grid.points().into_iter().zip(&self.heights) {
+            mesh.add_point(HeightPoint {
+                x,
+                y,
+                z: z - reference,
+            });
+        }
+        Ok(mesh)
+    }
+
+    /// Send the probe program and build the mesh from the replies
+    ///
+    /// `send` writes one line to the controller and returns the lines it
+    /// answered with.
+    pub fn run<F>(&mut self, mut send: F) -> Result<ProbeMesh>
+    where
+        F: FnMut(&str) -> Result<Vec<String>>,
+    {
+        self.heights.clear();
+        for line in self.probe_program() {
+            for response in send(&line)? {
+                self.record_response(&response)?;
+            }
+        }
+        self.mesh()
+    }
+
+    /// Probe under a program and return the leveled program
+    pub fn level<F>(&mut self, program: &str, send: F) -> Result<String>
+    where
+        F: FnMut(&str) -> Result<Vec<String>>,
+    {
+        let mesh = self.run(send)?;
+        apply_mesh(program, &mesh, self.config.max_segment)
+    }
+}
+
+fn format_value(value: f64) -> String {
+    let value = if value.abs() < 0.5 * 10f64.powi(-(PRECISION as i32)) {
+        0.0
+    } else {
+        value
+    };
+    TrailingZeroProcessor::normalize_number(&format!("{:.*}", PRECISION, value), 0)
+}
+
+/// Offset a program's moves by the surface height under them
+///
+/// Runs the program through a [`MeshLevelProcessor`], so moves are split and
+/// leveled exactly as in a processing pipeline. Incremental (`G91`) moves are
+/// rejected with the offending line number.
+pub fn apply_mesh(program: &str, mesh: &ProbeMesh, max_segment: f64) -> Result<String> {
+    let mut pipeline = ProcessorPipeline::new();
+    pipeline.register(Arc::new(MeshLevelProcessor::new(mesh.clone(), max_segment)));
+
+    // One pass, since the pipeline resets the processor's position before each batch
+    let commands: Vec<GcodeCommand> = program
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            let mut command = GcodeCommand::new(line);
+            command.set_line_number(index as u32 + 1);
+            command
+        })
+        .collect();
+    let leveled = pipeline
+        .process_commands(&commands, &mut GcodeState::new())
+        .map_err(|e| anyhow!(e))?;
+
+    let mut output = String::with_capacity(program.len() * 2);
+    for command in leveled {
+        output.push_str(&command.command);
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+/// Levels moves against a probe mesh as they pass through a pipeline
+///
+/// [`apply_mesh`] runs whole programs through it. `G0`/`G1` moves longer in XY
+/// than the `max_segment_mm` option (5mm by default) are split, and every
+/// endpoint gets [`ProbeMesh::height_at`] added to its Z. Outside the probed
+/// region Z is left as programmed, unless the mesh clamps to its edges. Arcs
+/// only have their endpoint adjusted. Moves made before X, Y and Z are all
+/// known pass through unchanged, as do probing moves and lines in machine
+/// coordinates or setting offsets. Incremental (`G91`) moves fail.
+pub struct MeshLevelProcessor {
+    config: ProcessorConfig,
+    mesh: ProbeMesh,
+    /// Programmed X, Y and Z, each unknown until its first absolute word
+    position: Mutex<[Option<f64>; 3]>,
+}
+
+impl MeshLevelProcessor {
+    /// Create a processor leveling against `mesh`, splitting moves into
+    /// segments of at most `max_segment_mm`
+    pub fn new(mesh: ProbeMesh, max_segment_mm: f64) -> Self {
+        Self::with_config(
+            mesh,
+            ProcessorConfig::new().with_option("max_segment_mm", max_segment_mm.to_string()),
+        )
+    }
+
+    /// Create from a configuration with a `max_segment_mm` option
+    pub fn with_config(mesh: ProbeMesh, config: ProcessorConfig) -> Self {
+        Self {
+            config,
+            mesh,
+            position: Mutex::new([None; 3]),
+        }
+    }
+
+    /// Longest move left unsplit; zero or less disables splitting
+    pub fn max_segment_mm(&self) -> f64 {
+        self.config
+            .get_option("max_segment_mm")
+            .and_then(|v| v.parse::<f64>().ok())
+            .unwrap_or(5.0)
+    }
+
+    /// The mesh moves are leveled against
+    pub fn mesh(&self) -> &ProbeMesh {
+        &self.mesh
+    }
+
+}
+
+impl CommandProcessor for MeshLevelProcessor {
+    fn name(&self) -> &str {
+        "mesh_level"
+    }
+
+    fn description(&self) -> &str {
+        "Offsets moves by the probed surface height under them"
+    }
+
+    /// Forget the tracked position
+    fn reset(&self) {
+        if let Ok(mut position) = self.position.lock() {
+            *position = [None; 3];
+        }
+    }
+
+    fn process(
+        &self,
+        command: &GcodeCommand,
+        state: &GcodeState,
+    ) -> Result<Vec<GcodeCommand>, String> {
+        let line = &command.command;
+        let split = line.find([';', '(']).unwrap_or(line.len());
+        let code = line[..split].to_ascii_uppercase();
+
+        let mut motion = state.motion_mode;
+        let mut incremental = state.distance_mode == 91;
+        let mut non_modal = None;
+        let mut axes = [None; 3];
+        for (letter, span) in word_spans(&code) {
+            let Ok(value) = code[span].parse::<f64>() else {
+                continue;
+            };
+            match letter {
+                'G' => match (value * 10.0).round() as i64 {
+                    mode @ (0 | 10 | 20 | 30) => motion = (mode / 10) as u8,
+                    800 => motion = 80,
+                    900 => incremental = false,
+                    910 => incremental = true,
+                    code @ (100 | 280 | 300 | 380..=385 | 530 | 920..=923) => {
+                        non_modal = Some(code)
+                    }
+                    _ => {}
+                },
+                'X' => axes[0] = Some(value),
+                'Y' => axes[1] = Some(value),
+                'Z' => axes[2] = Some(value),
+                _ => {}
+            }
+        }
+
+        let mut position = self.position.lock().map_err(|e| e.to_string())?;
+        match non_modal {
+            // G92 names the current position
+            Some(920) => {
+                for (position, axis) in position.iter_mut().zip(axes) {
+                    *position = axis.or(*position);
+                }
+            }
+            // Reference returns, probes and machine coordinate moves end
+            // somewhere the program's coordinates can't say
+            Some(280 | 300 | 380..=385) => *position = [None; 3],
+            Some(530) if axes.iter().any(Option::is_some) => *position = [None; 3],
+            _ => {}
+        }
+        if non_modal.is_some() || axes.iter().all(Option::is_none) || motion > 3 {
+            return Ok(vec![command.clone()]);
+        }
+        if incremental {
+            let message = "Mesh leveling needs absolute (G90) coordinates";
+            return Err(match command.line_number {
+                Some(line) => format!("Line {}: {}", line, message),
+                None => message.to_string(),
+            });
+        }
+
+        let known = |p: [Option<f64>; 3]| Some([p[0]?, p[1]?, p[2]?]);
+        let start = *position;
+        for (position, axis) in position.iter_mut().zip(axes) {
+            *position = axis.or(*position);
+        }
+        let Some(end) = known(*position) else {
+            return Ok(vec![command.clone()]);
+        };
+        let start = known(start).unwrap_or(end);
+
+        let length = (end[0] - start[0]).hypot(end[1] - start[1]);
+        let max_segment = self.max_segment_mm();
+        let segments = if motion <= 1 && max_segment > 0.0 {
+            (length / max_segment).ceil().max(1.0) as usize
+        } else {
+            1
+        };
+        let leveled = |segment: usize| {
+            let t = segment as f64 / segments as f64;
+            let [x, y, z] = [0, 1, 2].map(|i| start[i] + (end[i] - start[i]) * t);
+            [x, y, z + self.mesh.height_at(x, y).unwrap_or(0.0)]
+        };
+
+        // The original line carries the first segment so its other words
+        // (feed, spindle, line number) take effect before the rest
+        let first = leveled(1);
+        let words: Vec<(char, f64)> = [('X', axes[0]), ('Y', axes[1])]
+            .into_iter()
+            .zip(first)
+            .filter(|((_, axis), _)| axis.is_some())
+            .map(|((letter, _), value)| (letter, value))
+            .chain([('Z', first[2])])
+            .collect();
+        let mut leveled_command = command.clone();
+        leveled_command.command =
+            format!("{}{}", rewrite_axes(&line[..split], &words), &line[split..]);
+
+        let mut commands = vec![leveled_command];
+        for segment in 2..=segments {
+            let [x, y, z] = leveled(segment);
+            let mut next = command.clone();
+            next.command = format!(
+                "X{} Y{} Z{}",
+                format_value(x),
+                format_value(y),
+                format_value(z)
+            );
+            commands.push(next);
+        }
+        Ok(commands)
+    }
+
+    fn config(&self) -> &ProcessorConfig {
+        &self.config
+    }
+}
+
+/// Replace a line's X, Y and Z words with `words`, where the first one was
+fn rewrite_axes(code: &str, words: &[(char, f64)]) -> String {
+    let upper = code.to_ascii_uppercase();
+    let axes = words
+        .iter()
+        .map(|(letter, value)| format!("{}{}", letter, format_value(*value)))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut result = String::with_capacity(code.len() + axes.len());
+    let mut last = 0;
+    for (letter, span) in word_spans(&upper) {
+        if !matches!(letter, 'X' | 'Y' | 'Z') {
+            continue;
+        }
+        let letter_start = upper[..span.start].trim_end().len() - 1;
+        if last == 0 {
+            result.push_str(&code[..letter_start]);
+            result.push_str(&axes);
+        } else {
+            // Drop the separator in front of each later axis word
+            result.push_str(code[last..letter_start].trim_end());
+        }
+        last = span.end;
+    }
+    result.push_str(&code[last..]);
+    result
+}
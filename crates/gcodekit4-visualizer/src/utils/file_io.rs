@@ -7,6 +7,7 @@
 //! - Implement G-code file reader with UTF-8/ASCII support
 //! - Handle large files efficiently with streaming
 //! - Support file validation and encoding detection
+//! - Transcode Windows-1252 (Latin-1) files to UTF-8 on load
 //!
 //! Task 92: File I/O - Recent Files
 //! - Track recently opened files with timestamps
@@ -27,6 +28,20 @@ const DEFAULT_MAX_RECENT: usize = 20;
 /// Buffer size for reading large files (256 KB)
 const READ_BUFFER_SIZE: usize = 256 * 1024;
 
+/// UTF-8 byte order mark
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+
+/// Share of control bytes above which data is treated as binary
+const BINARY_CONTROL_RATIO: f64 = 0.1;
+
+/// Windows-1252 characters for bytes 0x80-0x9F; the rest match Latin-1
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
 /// Supported file encodings
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FileEncoding {
@@ -34,23 +49,69 @@ pub enum FileEncoding {
     Utf8,
     /// ASCII encoding (7-bit)
     Ascii,
+    /// Windows-1252, a superset of ISO-8859-1 (Latin-1)
+    Windows1252,
+    /// Not text; decoded lossily as UTF-8
+    Binary,
 }
 
 impl FileEncoding {
     /// Detect encoding from file bytes
+    ///
+    /// Data that is not valid UTF-8 is assumed to be Windows-1252, unless it
+    /// contains NUL bytes or many other control bytes, in which case it is
+    /// reported as binary.
     pub fn detect(data: &[u8]) -> Self {
         // Check for UTF-8 BOM
-        if data.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        if data.starts_with(UTF8_BOM) {
             return FileEncoding::Utf8;
         }
 
-        // Try to validate as UTF-8
-        if std::str::from_utf8(data).is_ok() {
-            return FileEncoding::Utf8;
+        if Self::looks_binary(data) {
+            return FileEncoding::Binary;
+        }
+
+        // Try to validate as UTF-8, allowing a multi-byte character cut off at the end
+        match std::str::from_utf8(data) {
+            Ok(_) => FileEncoding::Utf8,
+            Err(e) if e.error_len().is_none() => FileEncoding::Utf8,
+            Err(_) => FileEncoding::Windows1252,
+        }
+    }
+
+    /// Decode bytes in this encoding to a UTF-8 string
+    ///
+    /// A leading UTF-8 byte order mark is dropped. Invalid UTF-8 sequences
+    /// become U+FFFD.
+    pub fn decode(&self, data: &[u8]) -> String {
+        match self {
+            FileEncoding::Windows1252 => data
+                .iter()
+                .map(|&b| match b {
+                    0x80..=0x9F => WINDOWS_1252_HIGH[(b - 0x80) as usize],
+                    _ => b as char,
+                })
+                .collect(),
+            FileEncoding::Utf8 | FileEncoding::Ascii | FileEncoding::Binary => {
+                let data = data.strip_prefix(UTF8_BOM).unwrap_or(data);
+                String::from_utf8_lossy(data).into_owned()
+            }
         }
+    }
 
-        // Fall back to ASCII
-        FileEncoding::Ascii
+    /// Whether data contains NUL bytes or a high share of other control bytes
+    fn looks_binary(data: &[u8]) -> bool {
+        if data.is_empty() {
+            return false;
+        }
+        if data.contains(&0) {
+            return true;
+        }
+        let control = data
+            .iter()
+            .filter(|&&b| b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | 0x0C))
+            .count();
+        control as f64 / data.len() as f64 > BINARY_CONTROL_RATIO
     }
 }
 
@@ -121,13 +182,21 @@ impl GcodeFileReader {
         &self.path
     }
 
-    /// Read entire file into memory
+    /// Read entire file into memory, transcoded to UTF-8
     ///
     /// Warning: Use with caution for large files (>100MB)
     ///
     /// # Errors
     /// Returns error if file cannot be read
     pub fn read_all(&self) -> Result<String> {
+        self.read_all_with_encoding().map(|(content, _)| content)
+    }
+
+    /// Read entire file into memory, transcoded to UTF-8, with its detected encoding
+    ///
+    /// # Errors
+    /// Returns error if file cannot be read
+    pub fn read_all_with_encoding(&self) -> Result<(String, FileEncoding)> {
         if self.file_size > 500 * 1024 * 1024 {
             tracing::warn!(
                 "Reading very large file ({}MB) into memory",
@@ -135,12 +204,29 @@ impl GcodeFileReader {
             );
         }
 
-        fs::read_to_string(&self.path).map_err(|e| anyhow!("Failed to read file: {}", e))
+        let data = fs::read(&self.path).map_err(|e| anyhow!("Failed to read file: {}", e))?;
+        let encoding = FileEncoding::detect(&data);
+        if encoding == FileEncoding::Binary {
+            tracing::warn!("{} looks like a binary file", self.path.display());
+        }
+        Ok((encoding.decode(&data), encoding))
+    }
+
+    /// Detect the file encoding from its first chunk
+    ///
+    /// # Errors
+    /// Returns error if file cannot be read
+    pub fn detect_encoding(&self) -> Result<FileEncoding> {
+        let file = File::open(&self.path)?;
+        let mut reader = BufReader::with_capacity(READ_BUFFER_SIZE, file);
+        Ok(FileEncoding::detect(reader.fill_buf()?))
     }
 
     /// Read file with line-by-line streaming callback
     ///
-    /// More memory-efficient for large files.
+    /// More memory-efficient for large files. Lines are transcoded to UTF-8
+    /// using the encoding detected from the first chunk; if a later line turns
+    /// out not to be UTF-8, it and the rest of the file are read as Windows-1252.
     ///
     /// # Arguments
     /// * `callback` - Called for each line with the line content
@@ -153,25 +239,33 @@ impl GcodeFileReader {
     {
         let start_time = SystemTime::now();
         let file = File::open(&self.path)?;
-        let reader = BufReader::with_capacity(READ_BUFFER_SIZE, file);
+        let mut reader = BufReader::with_capacity(READ_BUFFER_SIZE, file);
 
         let mut lines_read = 0u64;
         let mut bytes_read = 0u64;
-        let mut encoding = FileEncoding::Utf8;
-        let mut first_chunk = true;
-
-        for line_result in reader.lines() {
-            let line = line_result?;
-
-            // Detect encoding from first chunk
-            if first_chunk {
-                encoding = FileEncoding::detect(line.as_bytes());
-                first_chunk = false;
+        // Detect encoding from first chunk
+        let mut encoding = FileEncoding::detect(reader.fill_buf()?);
+        let mut raw = Vec::new();
+
+        loop {
+            raw.clear();
+            let len = reader.read_until(b'\n', &mut raw)?;
+            if len == 0 {
+                break;
             }
+            bytes_read += len as u64;
 
-            bytes_read += line.len() as u64 + 1; // +1 for newline
+            if raw.ends_with(b"\n") {
+                raw.pop();
+                if raw.ends_with(b"\r") {
+                    raw.pop();
+                }
+            }
+            if encoding == FileEncoding::Utf8 && std::str::from_utf8(&raw).is_err() {
+                encoding = FileEncoding::Windows1252;
+            }
 
-            callback(&line)?;
+            callback(&encoding.decode(&raw))?;
             lines_read += 1;
         }
 
@@ -195,14 +289,11 @@ impl GcodeFileReader {
     /// Returns error if file cannot be read
     pub fn read_lines_limited(&self, max_lines: usize) -> Result<(Vec<String>, FileReadStats)> {
         let mut lines = Vec::new();
-        let mut encoding = FileEncoding::Utf8;
+        let encoding = self.detect_encoding()?;
         let file_size = self.file_size;
 
         self.read_lines(|line| {
             if lines.len() < max_lines {
-                if lines.is_empty() {
-                    encoding = FileEncoding::detect(line.as_bytes());
-                }
                 lines.push(line.to_string());
                 Ok(())
             } else {
@@ -235,7 +326,7 @@ impl GcodeFileReader {
         let mut validation = FileValidation::new();
         let mut has_motion = false;
 
-        self.read_lines(|line| {
+        let stats = self.read_lines(|line| {
             let trimmed = line.trim();
 
             // Skip empty lines and comments
@@ -277,6 +368,12 @@ impl GcodeFileReader {
             Ok(())
         })?;
 
+        if stats.encoding == FileEncoding::Binary {
+            validation
+                .warnings
+                .push("File looks like binary data, not G-code text".to_string());
+        }
+
         if !has_motion {
             validation
                 .warnings
@@ -533,7 +630,6 @@ impl Default for RecentFilesManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-   
 
     #[test]
     fn test_file_encoding_detection() {
@@ -546,6 +642,15 @@ mod tests {
             FileEncoding::detect("G0 X10 Y20".as_bytes()),
             FileEncoding::Utf8
         );
+
+        // Latin-1 degree sign is not valid UTF-8
+        assert_eq!(
+            FileEncoding::detect(b"; 45\xB0 chamfer"),
+            FileEncoding::Windows1252
+        );
+
+        // NUL bytes mean binary
+        assert_eq!(FileEncoding::detect(b"G0\x00\x00X1"), FileEncoding::Binary);
     }
 
     #[test]
@@ -1,17 +1,20 @@
 //! Utility functions and helpers
 
 pub mod advanced;
+pub mod auto_level;
 pub mod export;
 pub mod file_io;
 pub mod phase6_extended;
 pub mod phase7;
 pub mod processing;
+pub mod tool_probe;
 
 pub use advanced::{
     AdvancedProber, BackupEntry, BackupManager, BasicProber, FileComparison, GcodeTemplate,
-    ProbePoint, TemplateLibrary, TemplateVariable, ValidationIssue, ValidationResult,
-    ValidationSeverity,
+    ProbePoint, RestoreReport, SettingsTarget, TemplateLibrary, TemplateVariable, ValidationIssue,
+    ValidationResult, ValidationSeverity,
 };
+pub use auto_level::{apply_mesh, AutoLevelConfig, AutoLeveler, MeshLevelProcessor, ProbeGrid};
 pub use export::{
     DropEvent, DropFileType, DropIndicatorState, DropTarget, DropZone, ExportOptions, FileExporter,
     FileFormat,
@@ -35,8 +38,9 @@ pub use phase7::{
     SafetyFeaturesManager,
 };
 pub use processing::{
-    FeedRateStats, FileProcessingPipeline, FileStatistics, ProcessedFile, SpindleStats,
+    FeedRateStats, FileProcessingPipeline, FileStatistics, ProcessedFile, SpindleStats, ToolStats,
 };
+pub use tool_probe::{ToolChangeProber, ToolProbeConfig};
 
 /// Format a float to a reasonable number of decimal places
 pub fn format_float(value: f64, precision: usize) -> String {
@@ -19,7 +19,10 @@
 //! Task 119: Data logging
 //! Task 120: Alarms and notifications
 
+use crate::gcode::{tokenize_words, GcodeParser, GcodeState};
+use crate::utils::advanced::{ValidationIssue, ValidationSeverity};
 use anyhow::Result;
+use gcodekit4_core::Position;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
 use std::path::PathBuf;
@@ -53,6 +56,9 @@ pub struct ProbeMesh {
     pub z_min: f64,
     /// Maximum Z found
     pub z_max: f64,
+    /// Whether `height_at` clamps positions outside the grid to its edges
+    #[serde(default)]
+    pub clamp_to_edges: bool,
 }
 
 impl ProbeMesh {
@@ -64,9 +70,16 @@ impl ProbeMesh {
             y_spacing,
             z_min: f64::MAX,
             z_max: f64::MIN,
+            clamp_to_edges: false,
         }
     }
 
+    /// Clamp `height_at` queries outside the grid to its edges
+    pub fn with_clamp_to_edges(mut self, clamp: bool) -> Self {
+        self.clamp_to_edges = clamp;
+        self
+    }
+
     /// Add probe point
     pub fn add_point(&mut self, point: HeightPoint) {
         self.z_min = self.z_min.min(point.z);
@@ -75,10 +88,17 @@ impl ProbeMesh {
     }
 
     /// Get Z offset at position (interpolated)
+    ///
+    /// Points on a complete rectangular grid are interpolated bilinearly,
+    /// clamped to the grid's edges. Other layouts fall back to averaging the
+    /// nearest points.
     pub fn get_z_offset(&self, x: f64, y: f64) -> Option<f64> {
         if self.points.is_empty() {
             return None;
         }
+        if let Some(z) = self.grid_z_offset(x, y, true) {
+            return Some(z);
+        }
 
         // Find 4 nearest points for bilinear interpolation
         let mut nearest = self
@@ -97,6 +117,67 @@ impl ProbeMesh {
         Some(avg)
     }
 
+    /// Height at a position, interpolated bilinearly between the four
+    /// surrounding grid points
+    ///
+    /// Returns `None` unless the points form a complete rectangular grid, and
+    /// for positions outside the probed region unless `clamp_to_edges` is set.
+    pub fn height_at(&self, x: f64, y: f64) -> Option<f64> {
+        self.grid_z_offset(x, y, self.clamp_to_edges)
+    }
+
+    /// Bilinear interpolation, if the points form a complete grid
+    ///
+    /// Positions outside the grid are clamped to its edges when `clamp` is
+    /// set, and give `None` otherwise.
+    fn grid_z_offset(&self, x: f64, y: f64, clamp: bool) -> Option<f64> {
+        const TOLERANCE: f64 = 1e-6;
+        let axis = |coord: fn(&HeightPoint) -> f64| {
+            let mut values: Vec<f64> = self.points.iter().map(coord).collect();
+            values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            values.dedup_by(|a, b| (*a - *b).abs() < TOLERANCE);
+            values
+        };
+        let xs = axis(|p| p.x);
+        let ys = axis(|p| p.y);
+        if xs.is_empty() || xs.len() * ys.len() != self.points.len() {
+            return None;
+        }
+        let inside = |values: &[f64], value: f64| {
+            value >= values[0] - TOLERANCE && value <= values[values.len() - 1] + TOLERANCE
+        };
+        if !clamp && (!inside(&xs, x) || !inside(&ys, y)) {
+            return None;
+        }
+
+        let height = |px: f64, py: f64| {
+            self.points
+                .iter()
+                .find(|p| (p.x - px).abs() < TOLERANCE && (p.y - py).abs() < TOLERANCE)
+                .map(|p| p.z)
+        };
+        // Index of the cell containing `value` and the fraction across it
+        let cell = |values: &[f64], value: f64| {
+            let i = values
+                .windows(2)
+                .position(|w| value < w[1])
+                .unwrap_or(values.len().saturating_sub(2));
+            let j = (i + 1).min(values.len() - 1);
+            let t = if j == i {
+                0.0
+            } else {
+                ((value - values[i]) / (values[j] - values[i])).clamp(0.0, 1.0)
+            };
+            (values[i], values[j], t)
+        };
+
+        let (x0, x1, tx) = cell(&xs, x);
+        let (y0, y1, ty) = cell(&ys, y);
+        let bottom = height(x0, y0)? * (1.0 - tx) + height(x1, y0)? * tx;
+        let top = height(x0, y1)? * (1.0 - tx) + height(x1, y1)? * tx;
+        Some(bottom * (1.0 - ty) + top * ty)
+    }
+
     /// Get mesh statistics
     pub fn stats(&self) -> (usize, f64, f64) {
         (self.points.len(), self.z_min, self.z_max)
@@ -351,6 +432,18 @@ impl WorkCoordinateSystem {
             .copied()
             .unwrap_or(WorkOffset::zero())
     }
+
+    /// Make a machine position work zero in the current system
+    ///
+    /// Stores `reference` as the current system's offset and returns the
+    /// `G10 L2` command that writes the same offset to the controller.
+    pub fn set_reference_as_zero(&mut self, reference: WorkOffset) -> String {
+        self.set_offset(self.current_system, reference);
+        format!(
+            "G10 L2 P{} X{:.3} Y{:.3} Z{:.3}",
+            self.current_system, reference.x, reference.y, reference.z
+        )
+    }
 }
 
 impl Default for WorkCoordinateSystem {
@@ -511,6 +604,158 @@ impl Simulator {
     pub fn distance_traveled(&self) -> f64 {
         0.0 // Would be accumulated from moves
     }
+
+    /// Replay a program up to a line and report the machine state there
+    ///
+    /// Every line from the start of `src` through `line` (1-based, inclusive)
+    /// is executed, so the result is the state after that line has run.
+    /// Comments and blank lines are skipped but still count as lines.
+    ///
+    /// # Arguments
+    /// * `src` - G-code program text
+    /// * `line` - 1-based line number to stop after
+    ///
+    /// # Returns
+    /// The work position in millimeters and the full modal state
+    pub fn state_at_line(src: &str, line: usize) -> (Position, GcodeState) {
+        let mut parser = GcodeParser::new();
+        let mut position = Position::default();
+
+        for text in src.lines().take(line) {
+            let command = match parser.parse(text) {
+                Ok(command) => command,
+                Err(_) => continue,
+            };
+            let state = parser.get_state();
+            let words = tokenize_words(&command.command);
+
+            // Non-motion commands that take axis words as parameters
+            let skip_axes = words.iter().any(|&(letter, value)| {
+                letter == 'G' && [4.0, 10.0, 28.0, 30.0, 92.0].contains(&value)
+            });
+            if skip_axes {
+                continue;
+            }
+
+            let scale = if state.units_mode == 20 { 25.4 } else { 1.0 };
+            let relative = state.distance_mode == 91;
+            for &(letter, value) in &words {
+                let value = (value * scale) as f32;
+                let axis = match letter {
+                    'X' => &mut position.x,
+                    'Y' => &mut position.y,
+                    'Z' => &mut position.z,
+                    _ => continue,
+                };
+                if relative {
+                    *axis += value;
+                } else {
+                    *axis = value;
+                }
+            }
+        }
+
+        (position, parser.get_state())
+    }
+
+    /// Deepest Z a program reaches, in millimeters
+    ///
+    /// Inch (`G20`) moves are converted, and `G92` offsets are undone so
+    /// the depth is measured from the work zero the program started in.
+    /// Tool length offsets (`G43.1`) only compensate for the tool, so the
+    /// tip still goes to the programmed Z. A program that never moves below
+    /// its start reports 0.
+    pub fn min_z(src: &str) -> f64 {
+        Self::deepest_z(src).0
+    }
+
+    /// Check that a program's deepest Z is the depth it was meant to cut
+    ///
+    /// Catches programs posted in the wrong units or against the wrong
+    /// offsets before they run.
+    ///
+    /// # Arguments
+    /// * `src` - G-code program text
+    /// * `expected` - Intended final depth in millimeters, e.g. -5.0
+    /// * `tolerance` - Allowed deviation in millimeters
+    ///
+    /// # Returns
+    /// A warning at the deepest line when it is off by more than `tolerance`
+    pub fn check_depth(src: &str, expected: f64, tolerance: f64) -> Option<ValidationIssue> {
+        let (depth, line) = Self::deepest_z(src);
+        if (depth - expected).abs() <= tolerance {
+            return None;
+        }
+
+        Some(
+            ValidationIssue::new(
+                line,
+                ValidationSeverity::Warning,
+                format!(
+                    "Deepest Z is {:.3} mm but the expected depth is {:.3} mm",
+                    depth, expected
+                ),
+            )
+            .with_suggestion("Check the program's units (G20/G21) and work offsets"),
+        )
+    }
+
+    /// Deepest Z in millimeters and the 1-based line reaching it (0 for none)
+    fn deepest_z(src: &str) -> (f64, u32) {
+        let mut parser = GcodeParser::new();
+        // Z in the starting work coordinates, and the G92 shift applied to it
+        let mut z = 0.0;
+        let mut shift = 0.0;
+        let mut deepest = (0.0, 0);
+
+        for (index, text) in src.lines().enumerate() {
+            let command = match parser.parse(text) {
+                Ok(command) => command,
+                Err(_) => continue,
+            };
+            let state = parser.get_state();
+            let words = tokenize_words(&command.command);
+            let scale = if state.units_mode == 20 { 25.4 } else { 1.0 };
+            let target = words
+                .iter()
+                .rfind(|&&(letter, _)| letter == 'Z')
+                .map(|&(_, value)| value * scale);
+
+            let mut moves = true;
+            for &(letter, value) in &words {
+                if letter != 'G' {
+                    continue;
+                }
+                match (value * 10.0).round() as i64 {
+                    // The Z word sets the current position, it doesn't move
+                    920 => {
+                        if let Some(target) = target {
+                            shift = z - target;
+                        }
+                        moves = false;
+                    }
+                    921 => shift = 0.0,
+                    // Parameters rather than moves
+                    40 | 100 | 280 | 300 | 431 | 530 => moves = false,
+                    _ => {}
+                }
+            }
+            let Some(target) = target.filter(|_| moves) else {
+                continue;
+            };
+
+            z = if state.distance_mode == 91 {
+                z + target
+            } else {
+                target + shift
+            };
+            if z < deepest.0 {
+                deepest = (z, index as u32 + 1);
+            }
+        }
+
+        deepest
+    }
 }
 
 impl Default for Simulator {
@@ -672,7 +917,7 @@ impl Default for BookmarkManager {
 // ============================================================================
 
 /// Program state for restart
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProgramState {
     /// Current line
     pub current_line: u32,
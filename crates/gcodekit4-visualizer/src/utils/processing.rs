@@ -20,8 +20,11 @@ use std::path::{Path, PathBuf};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
+use crate::gcode::extents::compute_extents;
+use crate::gcode::runtime::{RuntimeEstimate, DEFAULT_RAPID_RATE};
+use crate::gcode::{tokenize_words, GcodeCommand};
 use crate::utils::GcodeFileReader;
-use gcodekit4_core::Position;
+use gcodekit4_core::{OverrideState, Position};
 
 /// File processing statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -52,6 +55,11 @@ pub struct FileStatistics {
     pub feed_rate_stats: FeedRateStats,
     /// Spindle speed statistics
     pub spindle_stats: SpindleStats,
+    /// Move counts and cut distance for each tool, keyed by tool number
+    ///
+    /// Moves before the first tool change are counted against tool 0.
+    #[serde(default)]
+    pub per_tool: HashMap<u16, ToolStats>,
 }
 
 /// 3D Bounding box
@@ -144,6 +152,18 @@ pub struct FeedRateStats {
     pub avg_feed: f64,
     /// Total feed rate changes
     pub changes: u64,
+    /// Estimated time spent at rapid (G0), in seconds
+    #[serde(default)]
+    pub rapid_time: f64,
+    /// Estimated time spent cutting at the feed rate, in seconds
+    #[serde(default)]
+    pub cutting_time: f64,
+    /// Path length of rapid moves
+    #[serde(default)]
+    pub rapid_distance: f64,
+    /// Path length of cutting moves
+    #[serde(default)]
+    pub cutting_distance: f64,
 }
 
 impl FeedRateStats {
@@ -154,9 +174,34 @@ impl FeedRateStats {
             max_feed: 0.0,
             avg_feed: 0.0,
             changes: 0,
+            rapid_time: 0.0,
+            cutting_time: 0.0,
+            rapid_distance: 0.0,
+            cutting_distance: 0.0,
         }
     }
 
+    /// Record the rapid and cutting split of a program's time and path length
+    ///
+    /// Times come from the runtime estimate at 100% overrides, with rapids
+    /// at `rapid_rate` (mm/min), so they ignore acceleration like it does.
+    pub fn record_motion(&mut self, content: &str, rapid_rate: f64) {
+        let commands: Vec<GcodeCommand> = content.lines().map(GcodeCommand::new).collect();
+        let estimate = RuntimeEstimate::new(&commands, rapid_rate);
+        let overrides = OverrideState::default();
+        self.rapid_time = estimate.rapid_time(&overrides).as_secs_f64();
+        self.cutting_time = estimate.feed_time(&overrides).as_secs_f64();
+
+        let extents = compute_extents(&commands);
+        self.rapid_distance = extents.rapid_length;
+        self.cutting_distance = extents.cutting_length;
+    }
+
+    /// Total estimated time of rapids and cuts, in seconds
+    pub fn total_time(&self) -> f64 {
+        self.rapid_time + self.cutting_time
+    }
+
     /// Update feed rate statistics
     pub fn update(&mut self, feed: f64) {
         if feed > 0.0 {
@@ -177,6 +222,26 @@ impl Default for FeedRateStats {
     }
 }
 
+/// Move counts and cut distance for one tool
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToolStats {
+    /// Number of G0 (rapid) moves
+    pub rapid_moves: u64,
+    /// Number of G1 (linear) moves
+    pub linear_moves: u64,
+    /// Number of G2/G3 (arc) moves
+    pub arc_moves: u64,
+    /// Distance traveled by G1/G2/G3 moves (in current units)
+    pub cut_distance: f32,
+}
+
+impl ToolStats {
+    /// Get total motion commands
+    pub fn total_motion_commands(&self) -> u64 {
+        self.rapid_moves + self.linear_moves + self.arc_moves
+    }
+}
+
 /// Spindle speed statistics
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct SpindleStats {
@@ -241,6 +306,7 @@ impl FileStatistics {
             command_counts: HashMap::new(),
             feed_rate_stats: FeedRateStats::new(),
             spindle_stats: SpindleStats::new(),
+            per_tool: HashMap::new(),
         }
     }
 
@@ -361,6 +427,9 @@ impl FileProcessingPipeline {
         let mut processed_lines = Vec::new();
         let mut current_position = Position::default();
         let mut last_position = Position::default();
+        // Tool selected by the last T word, and the tool in the spindle
+        let mut next_tool = 0;
+        let mut current_tool = 0;
 
         reader.read_lines(|line| {
             let trimmed = line.trim();
@@ -380,26 +449,44 @@ impl FileProcessingPipeline {
             // Parse and count commands
             let upper = trimmed.to_uppercase();
 
+            // A T word selects the next tool, which M6 loads
+            for (letter, value) in tokenize_words(&upper) {
+                match letter {
+                    'T' if value >= 0.0 => next_tool = value as u16,
+                    'M' if value == 6.0 => current_tool = next_tool,
+                    _ => {}
+                }
+            }
+            let tool_stats = statistics.per_tool.entry(current_tool).or_default();
+
             // Count motion commands
+            let mut cutting = false;
             if upper.contains("G00") || upper.starts_with("G0 ") {
+                tool_stats.rapid_moves += 1;
                 statistics.rapid_moves += 1;
                 *statistics
                     .command_counts
                     .entry("G0".to_string())
                     .or_insert(0) += 1;
             } else if upper.contains("G01") || upper.starts_with("G1 ") {
+                cutting = true;
+                tool_stats.linear_moves += 1;
                 statistics.linear_moves += 1;
                 *statistics
                     .command_counts
                     .entry("G1".to_string())
                     .or_insert(0) += 1;
             } else if upper.contains("G02") || upper.contains("G2 ") {
+                cutting = true;
+                tool_stats.arc_moves += 1;
                 statistics.arc_moves += 1;
                 *statistics
                     .command_counts
                     .entry("G2".to_string())
                     .or_insert(0) += 1;
             } else if upper.contains("G03") || upper.contains("G3 ") {
+                cutting = true;
+                tool_stats.arc_moves += 1;
                 statistics.arc_moves += 1;
                 *statistics
                     .command_counts
@@ -475,6 +562,11 @@ impl FileProcessingPipeline {
             let dz = current_position.z - last_position.z;
             let distance = (dx * dx + dy * dy + dz * dz).sqrt();
             statistics.total_distance += distance;
+            if cutting {
+                if let Some(tool_stats) = statistics.per_tool.get_mut(&current_tool) {
+                    tool_stats.cut_distance += distance;
+                }
+            }
 
             last_position = current_position;
 
@@ -483,20 +575,18 @@ impl FileProcessingPipeline {
 
             Ok(())
         })?;
-
-        // Estimate time (simplified: assume 60 mm/min for rapid, 20 mm/min for feed)
-        // This is a rough estimate - actual time depends on many factors
-        let rapid_distance = (statistics.rapid_moves as f32) * 10.0; // assume 10mm per rapid move avg
-        let feed_distance = statistics.total_distance - rapid_distance;
-        let rapid_time = (rapid_distance / 60.0) * 60.0; // 60 mm/min -> seconds
-        let feed_time = if statistics.feed_rate_stats.max_feed > 0.0 {
-            (feed_distance / (statistics.feed_rate_stats.max_feed as f32)) * 60.0
-        } else {
-            0.0
-        };
-        statistics.estimated_time = ((rapid_time + feed_time) as f64) as u64;
+        // Only tools that moved, not the setup lines before the first tool
+        statistics
+            .per_tool
+            .retain(|_, tool| tool.total_motion_commands() > 0);
 
         let processed_content = processed_lines.join("\n");
+
+        // Estimate time from the rapid and cutting split of the runtime estimate
+        statistics
+            .feed_rate_stats
+            .record_motion(&processed_content, DEFAULT_RAPID_RATE);
+        statistics.estimated_time = statistics.feed_rate_stats.total_time().round() as u64;
         let processed_result = ProcessedFile {
             source_path: path.to_path_buf(),
             content: processed_content,
@@ -0,0 +1,170 @@
+//! Tool length probing on tool change
+//!
+//! Multi-tool jobs lose their Z zero every time a tool of a different length
+//! goes in. With probing on tool change enabled, each `M6` in the job is
+//! followed by a trip to a fixed tool setter: the tool is probed down onto it
+//! with `G38.2`, and the difference from the first tool's trigger height is
+//! stored in a `ToolOffsetManager` and applied with `G43.1` before the next
+//! line of the job runs.
+
+use super::phase6_extended::{ToolOffset, ToolOffsetManager};
+use crate::gcode::tokenize_words;
+use anyhow::{anyhow, Result};
+
+/// Tool setter position and probing moves
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ToolProbeConfig {
+    /// Probe after every `M6`
+    pub enabled: bool,
+    /// Machine X/Y of the tool setter
+    pub setter: (f64, f64),
+    /// Machine Z to travel at, clear of the work and the setter
+    pub safe_z: f64,
+    /// Furthest the probe moves down looking for the setter
+    pub probe_travel: f64,
+    /// Probing feed rate
+    pub probe_feed: f64,
+}
+
+impl Default for ToolProbeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            setter: (0.0, 0.0),
+            safe_z: -5.0,
+            probe_travel: 50.0,
+            probe_feed: 100.0,
+        }
+    }
+}
+
+/// Probes each new tool against a tool setter during a job
+#[derive(Default)]
+pub struct ToolChangeProber {
+    config: ToolProbeConfig,
+    offsets: ToolOffsetManager,
+    /// Setter trigger height of the first tool probed, in machine Z
+    reference: Option<f64>,
+    /// Tool selected by the last `T` word
+    selected: Option<u32>,
+}
+
+impl ToolChangeProber {
+    /// Create a prober for a tool setter
+    pub fn new(config: ToolProbeConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    /// The probing settings
+    pub fn config(&self) -> &ToolProbeConfig {
+        &self.config
+    }
+
+    /// Turn probing on tool change on or off
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.config.enabled = enabled;
+    }
+
+    /// Length offsets measured so far, relative to the first tool probed
+    pub fn offsets(&self) -> &ToolOffsetManager {
+        &self.offsets
+    }
+
+    /// Forget the reference tool and the selected tool, e.g. for a new job
+    pub fn reset(&mut self) {
+        self.offsets = ToolOffsetManager::new();
+        self.reference = None;
+        self.selected = None;
+    }
+
+    /// G-code that measures the loaded tool on the setter
+    ///
+    /// Travel happens in machine coordinates at `safe_z`, and the probe move
+    /// itself is incremental so it doesn't depend on the active work offset.
+    pub fn probe_routine(&self) -> Vec<String> {
+        let config = &self.config;
+        vec![
+            format!("G53 G0 Z{:.3}", config.safe_z),
+            format!("G53 G0 X{:.3} Y{:.3}", config.setter.0, config.setter.1),
+            format!(
+                "G91 G38.2 Z{:.3} F{:.0}",
+                -config.probe_travel.abs(),
+                config.probe_feed
+            ),
+            "G90".to_string(),
+            format!("G53 G0 Z{:.3}", config.safe_z),
+        ]
+    }
+
+    /// Lines to send for one line of the job
+    ///
+    /// A line with `M6` is followed by the probe routine when probing is
+    /// enabled; every other line is passed through unchanged.
+    pub fn expand(&mut self, line: &str) -> Vec<String> {
+        let words = tokenize_words(line);
+        if let Some(&(_, tool)) = words.iter().find(|(letter, _)| *letter == 'T') {
+            self.selected = Some(tool as u32);
+        }
+
+        let mut lines = vec![line.to_string()];
+        if self.config.enabled && words.contains(&('M', 6.0)) {
+            lines.extend(self.probe_routine());
+        }
+        lines
+    }
+
+    /// Record a line received from the controller
+    ///
+    /// Returns the `G43.1` command applying the measured offset if the line
+    /// was a probe report. The first tool probed becomes the reference with
+    /// no offset. Fails if the probe never touched the setter.
+    pub fn record_response(&mut self, line: &str) -> Result<Option<String>> {
+        let Some(report) = line
+            .trim()
+            .strip_prefix("[PRB:")
+            .and_then(|rest| rest.strip_suffix(']'))
+        else {
+            return Ok(None);
+        };
+
+        let (coords, success) = report.rsplit_once(':').unwrap_or((report, "1"));
+        let z = coords
+            .split(',')
+            .nth(2)
+            .and_then(|z| z.trim().parse::<f64>().ok())
+            .ok_or_else(|| anyhow!("Malformed probe report: {}", line.trim()))?;
+        if success.trim() != "1" {
+            return Err(anyhow!("Tool probe did not touch the tool setter"));
+        }
+
+        let reference = *self.reference.get_or_insert(z);
+        let offset = z - reference;
+        let tool = self.selected.unwrap_or(0);
+        self.offsets.set_offset(ToolOffset::new(tool, offset));
+        Ok(Some(format!("G43.1 Z{:.3}", offset)))
+    }
+
+    /// Send a job, probing each new tool as it is loaded
+    ///
+    /// `send` writes one line to the controller and returns the lines it
+    /// answered with. The offset from each probe is sent before the job's
+    /// next line.
+    pub fn run<F>(&mut self, program: &str, mut send: F) -> Result<()>
+    where
+        F: FnMut(&str) -> Result<Vec<String>>,
+    {
+        for line in program.lines() {
+            for line in self.expand(line) {
+                for response in send(&line)? {
+                    if let Some(apply) = self.record_response(&response)? {
+                        send(&apply)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -2,6 +2,7 @@
 //! Renders G-Code toolpaths as SVG path data for Slint Path elements
 
 use super::visualizer_2d::{GCodeCommand, Visualizer2D};
+use gcodekit4_core::ToolpathLayer;
 
 const GRID_MAJOR_STEP_MM: f32 = 10.0;
 
@@ -244,6 +245,138 @@ pub fn render_origin_to_path(visualizer: &Visualizer2D, width: u32, height: u32)
     path
 }
 
+/// Render the work envelope as a closed rectangle of SVG path commands
+///
+/// Empty when no envelope is set on the visualizer.
+pub fn render_envelope_to_path(visualizer: &Visualizer2D, _width: u32, _height: u32) -> String {
+    let Some(limits) = visualizer.work_envelope() else {
+        return String::new();
+    };
+    let (x0, x1) = (limits.x_min, limits.x_max);
+    let (y0, y1) = (-limits.y_max, -limits.y_min);
+    format!(
+        "M {:.2} {:.2} L {:.2} {:.2} L {:.2} {:.2} L {:.2} {:.2} Z ",
+        x0, y0, x1, y0, x1, y1, x0, y1
+    )
+}
+
+/// Render moves outside the work envelope as SVG path commands
+pub fn render_out_of_bounds_to_path(
+    visualizer: &Visualizer2D,
+    _width: u32,
+    _height: u32,
+) -> String {
+    visualizer.out_of_envelope_svg()
+}
+
+/// Render annotation pins as SVG path commands
+pub fn render_annotations_to_path(
+    visualizer: &Visualizer2D,
+    _width: u32,
+    _height: u32,
+) -> String {
+    visualizer.annotations_svg()
+}
+
+/// Render toolpath direction arrows as SVG chevrons
+///
+/// # Arguments
+/// * `spacing_px` - On-screen distance between arrows
+/// * `size_px` - On-screen length of each arrow
+pub fn render_direction_arrows_to_path(
+    visualizer: &Visualizer2D,
+    spacing_px: f32,
+    size_px: f32,
+) -> String {
+    use std::fmt::Write;
+
+    let half = size_px / (2.0 * visualizer.zoom_scale * visualizer.scale_factor);
+    let mut path = String::new();
+    for arrow in visualizer.render_direction_arrows(spacing_px) {
+        let (p, d) = (arrow.position, arrow.direction);
+        let (tip_x, tip_y) = (p.x + d.x * half, p.y + d.y * half);
+        let (back_x, back_y) = (p.x - d.x * half, p.y - d.y * half);
+        // Wings sit behind the tip, either side of the travel direction
+        let (wing_x, wing_y) = (-d.y * half * 0.6, d.x * half * 0.6);
+        let _ = write!(
+            path,
+            "M {:.2} {:.2} L {:.2} {:.2} L {:.2} {:.2} ",
+            back_x + wing_x,
+            -(back_y + wing_y),
+            tip_x,
+            -tip_y,
+            back_x - wing_x,
+            -(back_y - wing_y)
+        );
+    }
+    path
+}
+
+/// Render one layer of the view as SVG path commands
+pub fn render_layer_to_path(
+    visualizer: &Visualizer2D,
+    layer: ToolpathLayer,
+    width: u32,
+    height: u32,
+) -> String {
+    match layer {
+        ToolpathLayer::Grid => render_grid_to_path(visualizer, width, height).0,
+        ToolpathLayer::Envelope => render_envelope_to_path(visualizer, width, height),
+        ToolpathLayer::Origin => render_origin_to_path(visualizer, width, height),
+        ToolpathLayer::Rapid => render_rapid_moves_to_path(visualizer, width, height),
+        ToolpathLayer::G1 => render_g1_to_path(visualizer, width, height),
+        ToolpathLayer::G2 => render_g2_to_path(visualizer, width, height),
+        ToolpathLayer::G3 => render_g3_to_path(visualizer, width, height),
+        ToolpathLayer::G4 => render_g4_to_path(visualizer, width, height),
+        ToolpathLayer::OutOfBounds => render_out_of_bounds_to_path(visualizer, width, height),
+        ToolpathLayer::Annotations => render_annotations_to_path(visualizer, width, height),
+    }
+}
+
+/// Render the whole view as a standalone SVG document
+///
+/// Each non-empty layer becomes a `<path>` whose `stroke` and `stroke-width`
+/// come from the visualizer's theme, drawn over the theme background.
+pub fn render_svg_document(visualizer: &Visualizer2D, width: u32, height: u32) -> String {
+    use std::fmt::Write;
+
+    let (vb_x, vb_y, vb_w, vb_h) = visualizer.get_viewbox(width as f32, height as f32);
+    let theme = visualizer.theme();
+
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{:.2} {:.2} {:.2} {:.2}">"#,
+        width, height, vb_x, vb_y, vb_w, vb_h
+    );
+    let _ = write!(
+        svg,
+        r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="{}"/>"#,
+        vb_x, vb_y, vb_w, vb_h, theme.background_color
+    );
+
+    for &layer in ToolpathLayer::all() {
+        if layer == ToolpathLayer::Grid && !visualizer.is_grid_visible() {
+            continue;
+        }
+        let data = render_layer_to_path(visualizer, layer, width, height);
+        if data.is_empty() {
+            continue;
+        }
+        let _ = write!(
+            svg,
+            r#"<path class="{}" d="{}" fill="none" stroke="{}" stroke-width="{}" vector-effect="non-scaling-stroke"/>"#,
+            layer.name(),
+            data.trim_end(),
+            theme.color(layer),
+            theme.width(layer)
+        );
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -18,8 +18,11 @@ pub mod visualizer_2d;
 pub use canvas_renderer::{
     render_grid_to_path, render_origin_to_path, render_rapid_moves_to_path, render_toolpath_to_path,
     render_g1_to_path, render_g2_to_path, render_g3_to_path, render_g4_to_path,
-    render_intensity_overlay,
+    render_intensity_overlay, render_layer_to_path, render_svg_document,
+    render_direction_arrows_to_path, render_envelope_to_path,
+    render_out_of_bounds_to_path, render_annotations_to_path,
 };
+pub use gcodekit4_core::{ToolpathLayer, VisualizerTheme};
 pub use controls::{CameraController, ViewPreset, VisualizerControls};
 pub use features::{
     BoundingBox, GridConfig, MachineLimits, SceneFeatures, ToolMarker, WorkCoordinateSystem,
@@ -27,10 +30,14 @@ pub use features::{
 pub use setup::{Camera, CameraType, Color, Light, LightType, Renderer, Scene, Vector3};
 pub use toolpath_cache::ToolpathCache;
 pub use toolpath_rendering::{
-    ArcSegment, LineSegment, MovementType, PathSegment, Toolpath, ToolpathStats,
+    flatten_arc, ArcPlane, ArcSegment, LineSegment, MovementType, PathSegment, Toolpath,
+    ToolpathStats,
 };
 pub use viewport::{Bounds, ViewportTransform};
-pub use visualizer_2d::{GCodeCommand, Point2D, Visualizer2D};
+pub use visualizer_2d::{
+    Annotation, DirectionArrow, GCodeCommand, PlaybackPosition, Point2D, ToolLegendEntry,
+    ToolRendering, Visualizer2D,
+};
 
 /// 3D Visualizer - Task 80-82
 pub struct Visualizer {
@@ -118,38 +118,11 @@ impl ToolpathCache {
                     clockwise,
                     intensity: _,
                 } => {
-                    let radius = ((from.x - center.x).powi(2) + (from.y - center.y).powi(2)).sqrt();
-
                     // Update combined path
                     if last_pos.is_none() || last_pos != Some(*from) {
                         let _ = write!(self.cached_path, "M {:.2} {:.2} ", from.x, -from.y);
                     }
-
-                    let sweep = if *clockwise { 0 } else { 1 };
-
-                    use std::f32::consts::PI;
-                    let start_angle = (from.y - center.y).atan2(from.x - center.x);
-                    let end_angle = (to.y - center.y).atan2(to.x - center.x);
-                    let mut angle_diff = if *clockwise {
-                        start_angle - end_angle
-                    } else {
-                        end_angle - start_angle
-                    };
-
-                    while angle_diff < 0.0 {
-                        angle_diff += 2.0 * PI;
-                    }
-                    while angle_diff >= 2.0 * PI {
-                        angle_diff -= 2.0 * PI;
-                    }
-
-                    let large_arc = if angle_diff > PI { 1 } else { 0 };
-
-                    let _ = write!(
-                        self.cached_path,
-                        "A {:.2} {:.2} 0 {} {} {:.2} {:.2} ",
-                        radius, radius, large_arc, sweep, to.x, -to.y
-                    );
+                    write_arc(&mut self.cached_path, *from, *to, *center, *clockwise);
                     last_pos = Some(*to);
 
                     // Update G2/G3 path
@@ -162,12 +135,7 @@ impl ToolpathCache {
                     if last_target_pos.is_none() || *last_target_pos != Some(*from) {
                         let _ = write!(target_path, "M {:.2} {:.2} ", from.x, -from.y);
                     }
-
-                    let _ = write!(
-                        target_path,
-                        "A {:.2} {:.2} 0 {} {} {:.2} {:.2} ",
-                        radius, radius, large_arc, sweep, to.x, -to.y
-                    );
+                    write_arc(target_path, *from, *to, *center, *clockwise);
                     *last_target_pos = Some(*to);
                 }
                 GCodeCommand::Dwell { pos, duration: _ } => {
@@ -183,3 +151,40 @@ impl ToolpathCache {
         }
     }
 }
+
+/// Append an SVG elliptical arc command from the current point to `to`
+pub(super) fn write_arc(
+    path: &mut String,
+    from: Point2D,
+    to: Point2D,
+    center: Point2D,
+    clockwise: bool,
+) {
+    use std::f32::consts::PI;
+
+    let radius = ((from.x - center.x).powi(2) + (from.y - center.y).powi(2)).sqrt();
+    let sweep = if clockwise { 0 } else { 1 };
+
+    let start_angle = (from.y - center.y).atan2(from.x - center.x);
+    let end_angle = (to.y - center.y).atan2(to.x - center.x);
+    let mut angle_diff = if clockwise {
+        start_angle - end_angle
+    } else {
+        end_angle - start_angle
+    };
+
+    while angle_diff < 0.0 {
+        angle_diff += 2.0 * PI;
+    }
+    while angle_diff >= 2.0 * PI {
+        angle_diff -= 2.0 * PI;
+    }
+
+    let large_arc = if angle_diff > PI { 1 } else { 0 };
+
+    let _ = write!(
+        path,
+        "A {:.2} {:.2} 0 {} {} {:.2} {:.2} ",
+        radius, radius, large_arc, sweep, to.x, -to.y
+    );
+}
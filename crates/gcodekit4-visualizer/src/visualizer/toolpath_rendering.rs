@@ -318,6 +318,92 @@ fn normalize_negative(angle: f32) -> f32 {
     value
 }
 
+/// Plane an arc is interpolated in, selected by G17/G18/G19
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArcPlane {
+    /// G17: arc in XY, helix along Z
+    #[default]
+    XY,
+    /// G18: arc in XZ, helix along Y
+    XZ,
+    /// G19: arc in YZ, helix along X
+    YZ,
+}
+
+impl ArcPlane {
+    /// Plane selected by a G-code number (17, 18 or 19)
+    pub fn from_gcode(code: u32) -> Option<Self> {
+        match code {
+            17 => Some(ArcPlane::XY),
+            18 => Some(ArcPlane::XZ),
+            19 => Some(ArcPlane::YZ),
+            _ => None,
+        }
+    }
+
+    /// Split a point into (first plane axis, second plane axis, linear axis)
+    ///
+    /// Axes are ordered so the linear axis is the plane normal by the right-hand
+    /// rule (Z for XY, Y for ZX, X for YZ), making counter-clockwise (G3) a
+    /// positive angle in every plane.
+    fn split(self, point: Vector3) -> (f32, f32, f32) {
+        match self {
+            ArcPlane::XY => (point.x, point.y, point.z),
+            ArcPlane::XZ => (point.z, point.x, point.y),
+            ArcPlane::YZ => (point.y, point.z, point.x),
+        }
+    }
+
+    /// Inverse of [`ArcPlane::split`]
+    fn join(self, u: f32, v: f32, w: f32) -> Vector3 {
+        match self {
+            ArcPlane::XY => Vector3::new(u, v, w),
+            ArcPlane::XZ => Vector3::new(v, w, u),
+            ArcPlane::YZ => Vector3::new(w, u, v),
+        }
+    }
+}
+
+/// Approximate a (possibly helical) arc by points along it
+///
+/// The arc turns about `center` in `plane`, while the axis normal to the plane
+/// moves linearly from `start` to `end`. A coincident start and end is a full
+/// circle. Returns `segments` points after `start`, the last one at `end`.
+pub fn flatten_arc(
+    start: Vector3,
+    end: Vector3,
+    center: Vector3,
+    clockwise: bool,
+    plane: ArcPlane,
+    segments: u32,
+) -> Vec<Vector3> {
+    let segments = segments.max(1);
+    let (su, sv, sw) = plane.split(start);
+    let (eu, ev, ew) = plane.split(end);
+    let (cu, cv, _) = plane.split(center);
+
+    let radius = (su - cu).hypot(sv - cv);
+    let start_angle = (sv - cv).atan2(su - cu);
+    let mut sweep = (ev - cv).atan2(eu - cu) - start_angle;
+    if clockwise && sweep >= 0.0 {
+        sweep -= std::f32::consts::TAU;
+    } else if !clockwise && sweep <= 0.0 {
+        sweep += std::f32::consts::TAU;
+    }
+
+    (1..=segments)
+        .map(|i| {
+            let t = i as f32 / segments as f32;
+            let angle = start_angle + sweep * t;
+            plane.join(
+                cu + radius * angle.cos(),
+                cv + radius * angle.sin(),
+                sw + (ew - sw) * t,
+            )
+        })
+        .collect()
+}
+
 /// Iterator that lazily emits discretized line segments for an arc
 pub struct ArcLineIterator<'a> {
     arc: &'a ArcSegment,
@@ -117,4 +117,26 @@ impl ViewportTransform {
         let y_offset = (world_y - min_y) * scale + self.padding - (canvas_height - target_screen_y);
         (x_offset, y_offset)
     }
+
+    /// Convert a screen point back to world coordinates.
+    ///
+    /// Inverse of the transform used by `offsets_to_place_world_point`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn screen_to_world(
+        &self,
+        min_x: f32,
+        min_y: f32,
+        zoom_scale: f32,
+        scale_factor: f32,
+        x_offset: f32,
+        y_offset: f32,
+        canvas_height: f32,
+        screen_x: f32,
+        screen_y: f32,
+    ) -> (f32, f32) {
+        let scale = zoom_scale * scale_factor;
+        let world_x = (screen_x - self.padding - x_offset) / scale + min_x;
+        let world_y = (canvas_height - screen_y - self.padding + y_offset) / scale + min_y;
+        (world_x, world_y)
+    }
 }
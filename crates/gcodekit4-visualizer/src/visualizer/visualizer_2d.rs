@@ -1,11 +1,19 @@
 //! 2D G-Code Visualizer
 //! Parses G-Code toolpaths for canvas-based visualization
 
-use super::toolpath_cache::ToolpathCache;
+use super::setup::Vector3;
+use super::toolpath_cache::{write_arc, ToolpathCache};
+use super::toolpath_rendering::{flatten_arc, ArcPlane};
 use super::viewport::{Bounds, ViewportTransform};
+use crate::gcode::runtime::DEFAULT_RAPID_RATE;
+use crate::gcode::tokenize_words;
+use crate::utils::{SoftLimits, ToolLibrary};
+use gcodekit4_core::VisualizerTheme;
+use serde::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
 
 const CANVAS_PADDING: f32 = 20.0;
 const _CANVAS_PADDING_2X: f32 = 40.0;
@@ -16,7 +24,16 @@ const PAN_PERCENTAGE: f32 = 0.1;
 const BOUNDS_PADDING_FACTOR: f32 = 0.1;
 const FIT_MARGIN_FACTOR: f32 = 0.05;
 const _ORIGIN_CROSS_SIZE: i32 = 5;
-const _MARKER_RADIUS: i32 = 4;
+/// Smallest on-screen radius (px) of the position marker, so it stays visible
+const MARKER_RADIUS: f32 = 4.0;
+/// On-screen radius (px) of an annotation pin's head
+const PIN_RADIUS: f32 = 4.0;
+/// On-screen length (px) of an annotation pin's stem
+const PIN_STEM: f32 = 12.0;
+/// Line segments used to draw an arc outside the XY plane (G18/G19)
+const PLANE_ARC_SEGMENTS: u32 = 32;
+/// Points sampled along an arc when checking it against the work envelope
+const ENVELOPE_ARC_SEGMENTS: u32 = 32;
 const _MAX_GRID_ITERATIONS: usize = 500;
 const _MAX_SCALE: f32 = 100.0;
 const _MIN_SCALE: f32 = 0.1;
@@ -61,6 +78,88 @@ pub enum GCodeCommand {
     },
 }
 
+/// Travel direction marker placed along a cutting move
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DirectionArrow {
+    /// Arrow center in machine coordinates (mm)
+    pub position: Point2D,
+    /// Unit vector in the direction of travel
+    pub direction: Point2D,
+}
+
+/// Legend entry for one tool in a [`ToolRendering`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolLegendEntry {
+    /// Tool number from the T word, 0 for moves before the first tool change
+    pub tool: u32,
+    /// Tool name from the tool library, or `T<n>` if it isn't listed
+    pub name: String,
+    /// Stroke color of the tool's moves
+    pub color: String,
+}
+
+/// Cutting moves grouped by the tool that makes them
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ToolRendering {
+    /// SVG path data of each tool's cutting moves, keyed by tool number
+    pub paths: BTreeMap<u32, String>,
+    /// One entry per tool, in the order the program first cuts with them
+    pub legend: Vec<ToolLegendEntry>,
+}
+
+/// Where playback has got to along the toolpath
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlaybackPosition {
+    /// Index of the command being played in [`Visualizer2D::commands`]
+    pub command_index: usize,
+    /// Fraction (0.0-1.0) of that command's time already played
+    pub fraction: f32,
+    /// Tool position in machine coordinates (mm)
+    pub position: Point2D,
+    /// True once the whole program has been played
+    pub finished: bool,
+}
+
+/// Note pinned to a spot on the toolpath, e.g. "check clamp here"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Annotation {
+    /// Identifier, unique among the annotations of one program
+    pub id: u32,
+    /// Machine X of the pinned spot (mm)
+    pub x: f32,
+    /// Machine Y of the pinned spot (mm)
+    pub y: f32,
+    /// Text shown with the pin
+    pub label: String,
+}
+
+/// Straight-line span of a move with its Z at each end, used for Z lookups
+#[derive(Debug, Clone, Copy)]
+struct ZSegment {
+    from: Point2D,
+    to: Point2D,
+    z_from: f32,
+    z_to: f32,
+}
+
+impl ZSegment {
+    /// Distance from a point to this segment and the interpolated Z there
+    fn distance_and_z(&self, x: f32, y: f32) -> (f32, f32) {
+        let dx = self.to.x - self.from.x;
+        let dy = self.to.y - self.from.y;
+        let len_sq = dx * dx + dy * dy;
+        let t = if len_sq > 0.0 {
+            (((x - self.from.x) * dx + (y - self.from.y) * dy) / len_sq).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let px = self.from.x + t * dx;
+        let py = self.from.y + t * dy;
+        let distance = ((x - px).powi(2) + (y - py).powi(2)).sqrt();
+        (distance, self.z_from + t * (self.z_to - self.z_from))
+    }
+}
+
 /// Coordinate transformation helper
 #[allow(dead_code)]
 struct CoordTransform {
@@ -127,8 +226,25 @@ pub struct Visualizer2D {
     pub show_grid: bool,
     /// Scale factor: pixels per mm (default 1.0 = 1px:1mm)
     pub scale_factor: f32,
+    /// Diameter of the current tool in mm (0.0 if unknown)
+    pub tool_diameter: f32,
+    /// Colors and stroke widths used to draw each layer
+    pub theme: VisualizerTheme,
+    /// Machine soft limits drawn as the work envelope, if known
+    work_envelope: Option<SoftLimits>,
     toolpath_cache: ToolpathCache,
     viewport: ViewportTransform,
+    z_segments: Vec<ZSegment>,
+    /// Active tool number of each command, parallel to the commands
+    command_tools: Vec<u32>,
+    /// Modal feed rate of each command, parallel to the commands
+    command_feeds: Vec<f32>,
+    /// Machine rapid rate in mm/min, used to time rapids during playback
+    rapid_rate: f32,
+    /// Notes pinned to the toolpath of the loaded program
+    annotations: Vec<Annotation>,
+    /// Id of the annotation last picked, if any
+    selected_annotation: Option<u32>,
 }
 
 impl Visualizer2D {
@@ -146,8 +262,17 @@ impl Visualizer2D {
             y_offset: 0.0,
             show_grid: true,
             scale_factor: DEFAULT_SCALE_FACTOR,
+            tool_diameter: 0.0,
+            theme: VisualizerTheme::default(),
+            work_envelope: None,
             toolpath_cache: ToolpathCache::new(),
             viewport: ViewportTransform::new(CANVAS_PADDING),
+            z_segments: Vec::new(),
+            command_tools: Vec::new(),
+            command_feeds: Vec::new(),
+            rapid_rate: DEFAULT_RAPID_RATE as f32,
+            annotations: Vec::new(),
+            selected_annotation: None,
         }
     }
 
@@ -193,22 +318,309 @@ impl Visualizer2D {
         self.scale_factor
     }
 
+    /// Set the tool diameter in mm used for the position marker
+    pub fn set_tool_diameter(&mut self, diameter: f32) {
+        self.tool_diameter = diameter.max(0.0);
+    }
+
+    /// Set the colors and stroke widths used to draw each layer
+    pub fn set_theme(&mut self, theme: VisualizerTheme) {
+        self.theme = theme;
+    }
+
+    /// Colors and stroke widths used to draw each layer
+    pub fn theme(&self) -> &VisualizerTheme {
+        &self.theme
+    }
+
+    /// Show the machine soft limits as the work envelope
+    pub fn set_work_envelope(&mut self, limits: &SoftLimits) {
+        self.work_envelope = Some(*limits);
+    }
+
+    /// Stop drawing the work envelope
+    pub fn clear_work_envelope(&mut self) {
+        self.work_envelope = None;
+    }
+
+    /// The soft limits drawn as the work envelope, if any
+    pub fn work_envelope(&self) -> Option<&SoftLimits> {
+        self.work_envelope.as_ref()
+    }
+
+    /// Indices into [`commands`](Self::commands) of moves that leave the work envelope
+    ///
+    /// Only X and Y are checked since the 2D view has no Z. A line is outside
+    /// when either endpoint is, while arcs are sampled along their sweep so a
+    /// bulge past the limits is caught. Nothing is flagged without an
+    /// envelope or when the limits are disabled.
+    pub fn out_of_envelope_commands(&self) -> Vec<usize> {
+        let Some(limits) = self.work_envelope.filter(|l| l.enabled) else {
+            return Vec::new();
+        };
+        let outside = |p: Point2D| {
+            let (x, y) = (p.x as f64, p.y as f64);
+            x < limits.x_min || x > limits.x_max || y < limits.y_min || y > limits.y_max
+        };
+
+        self.commands()
+            .iter()
+            .enumerate()
+            .filter(|(_, command)| match command {
+                GCodeCommand::Move { from, to, .. } => outside(*from) || outside(*to),
+                GCodeCommand::Arc { .. } => Self::arc_points(command).into_iter().any(outside),
+                GCodeCommand::Dwell { .. } => false,
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// SVG path of the moves that leave the work envelope
+    ///
+    /// Arcs are drawn as line segments through their sampled points.
+    pub fn out_of_envelope_svg(&self) -> String {
+        use std::fmt::Write;
+
+        let mut path = String::new();
+        for index in self.out_of_envelope_commands() {
+            let points = match &self.commands()[index] {
+                GCodeCommand::Move { from, to, .. } => vec![*from, *to],
+                command => Self::arc_points(command),
+            };
+            for (i, p) in points.iter().enumerate() {
+                let op = if i == 0 { 'M' } else { 'L' };
+                let _ = write!(path, "{} {:.2} {:.2} ", op, p.x, -p.y);
+            }
+        }
+        path
+    }
+
+    /// Points along an arc command, including both ends
+    fn arc_points(command: &GCodeCommand) -> Vec<Point2D> {
+        let GCodeCommand::Arc {
+            from,
+            to,
+            center,
+            clockwise,
+            ..
+        } = *command
+        else {
+            return Vec::new();
+        };
+        let point = |p: Point2D| Vector3::new(p.x, p.y, 0.0);
+        let mut points = vec![from];
+        points.extend(
+            flatten_arc(
+                point(from),
+                point(to),
+                point(center),
+                clockwise,
+                ArcPlane::XY,
+                ENVELOPE_ARC_SEGMENTS,
+            )
+            .into_iter()
+            .map(|v| Point2D::new(v.x, v.y)),
+        );
+        points
+    }
+
+    /// Pin a labeled note at a machine X/Y, returning its id
+    pub fn add_annotation(&mut self, x: f32, y: f32, label: impl Into<String>) -> u32 {
+        let id = self.annotations.iter().map(|a| a.id).max().unwrap_or(0) + 1;
+        self.annotations.push(Annotation {
+            id,
+            x,
+            y,
+            label: label.into(),
+        });
+        id
+    }
+
+    /// Remove an annotation by id, returning whether it existed
+    pub fn remove_annotation(&mut self, id: u32) -> bool {
+        let count = self.annotations.len();
+        self.annotations.retain(|a| a.id != id);
+        if self.selected_annotation == Some(id) {
+            self.selected_annotation = None;
+        }
+        self.annotations.len() != count
+    }
+
+    /// Annotations in the order they were added
+    pub fn annotations(&self) -> &[Annotation] {
+        &self.annotations
+    }
+
+    /// Annotation nearest to a machine X/Y, if one is within `tolerance` mm
+    ///
+    /// Use [`coords_at`](Self::coords_at) to turn a click into machine X/Y.
+    pub fn annotation_at(&self, x: f32, y: f32, tolerance: f32) -> Option<&Annotation> {
+        self.annotations
+            .iter()
+            .map(|a| ((a.x - x).hypot(a.y - y), a))
+            .filter(|(distance, _)| *distance <= tolerance)
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, annotation)| annotation)
+    }
+
+    /// Select the annotation under a click, or clear the selection on a miss
+    ///
+    /// Returns the id of the newly selected annotation.
+    pub fn select_annotation_at(&mut self, x: f32, y: f32, tolerance: f32) -> Option<u32> {
+        self.selected_annotation = self.annotation_at(x, y, tolerance).map(|a| a.id);
+        self.selected_annotation
+    }
+
+    /// The selected annotation, if any
+    pub fn selected_annotation(&self) -> Option<&Annotation> {
+        let id = self.selected_annotation?;
+        self.annotations.iter().find(|a| a.id == id)
+    }
+
+    /// SVG path of a pin at each annotation
+    ///
+    /// The pin's point sits on the annotated spot with its round head above
+    /// it, both a fixed size on screen whatever the zoom.
+    pub fn annotations_svg(&self) -> String {
+        use std::fmt::Write;
+
+        let pixels_per_mm = self.zoom_scale * self.scale_factor;
+        if pixels_per_mm <= 0.0 {
+            return String::new();
+        }
+        let (radius, stem) = (PIN_RADIUS / pixels_per_mm, PIN_STEM / pixels_per_mm);
+
+        let mut path = String::new();
+        for annotation in &self.annotations {
+            let (x, y) = (annotation.x, -annotation.y);
+            let _ = write!(path, "M {:.2} {:.2} L {:.2} {:.2} ", x, y, x, y - stem);
+            // Head as two half circles, centered above the stem
+            let (left, right, head) = (x - radius, x + radius, y - stem - radius);
+            let _ = write!(path, "M {:.2} {:.2} ", left, head);
+            for end in [right, left] {
+                let _ = write!(path, "A {r:.2} {r:.2} 0 1 0 {:.2} {:.2} ", end, head, r = radius);
+            }
+        }
+        path
+    }
+
+    /// File the annotations of a program are kept in, next to the program
+    ///
+    /// e.g. `part.nc` keeps them in `part.nc.annotations.json`.
+    pub fn annotations_path(program: &Path) -> PathBuf {
+        let mut name = program.as_os_str().to_owned();
+        name.push(".annotations.json");
+        PathBuf::from(name)
+    }
+
+    /// Save the annotations alongside a program
+    ///
+    /// The file is removed when there are no annotations left.
+    pub fn save_annotations(&self, program: &Path) -> std::io::Result<()> {
+        let path = Self::annotations_path(program);
+        if self.annotations.is_empty() {
+            return match std::fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            };
+        }
+        let json =
+            serde_json::to_string_pretty(&self.annotations).map_err(std::io::Error::other)?;
+        std::fs::write(path, json)
+    }
+
+    /// Replace the annotations with those saved alongside a program
+    ///
+    /// A program without saved annotations has none.
+    pub fn load_annotations(&mut self, program: &Path) -> std::io::Result<()> {
+        self.selected_annotation = None;
+        let content = match std::fs::read_to_string(Self::annotations_path(program)) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                self.annotations.clear();
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+        self.annotations = serde_json::from_str(&content).map_err(std::io::Error::other)?;
+        Ok(())
+    }
+
+    /// Radius in pixels of the tool marker drawn at the current position
+    ///
+    /// The marker is the size of the tool at the current zoom, so engagement
+    /// can be judged against the path, but never smaller than a small dot.
+    pub fn tool_marker_screen_radius(&self) -> f32 {
+        let radius = self.tool_diameter / 2.0 * self.zoom_scale * self.scale_factor;
+        radius.max(MARKER_RADIUS)
+    }
+
+    /// Extract the feed rate from an F word (e.g., "G1 X10 F300" -> Some(300.0))
+    fn extract_feed(line: &str) -> Option<f32> {
+        tokenize_words(line)
+            .into_iter()
+            .find_map(|(letter, value)| (letter == 'F').then_some(value as f32))
+    }
+
+    /// Extract the Z word from a G-code line, if present
+    fn extract_z(line: &str) -> Option<f32> {
+        tokenize_words(line)
+            .into_iter()
+            .find_map(|(letter, value)| (letter == 'Z').then_some(value as f32))
+    }
+
+    /// Extract the tool number from a T word (e.g., "T2 M6" or "M6T2" -> Some(2))
+    fn extract_tool(line: &str) -> Option<u32> {
+        tokenize_words(line).into_iter().find_map(|(letter, value)| {
+            (letter == 'T' && value >= 0.0 && value.fract() == 0.0).then_some(value as u32)
+        })
+    }
+
     /// Extract G-code command number from line (e.g., "G01 X10" -> Some(1))
+    ///
+    /// Leading plane selection and arc distance words are skipped, so
+    /// "G18 G02 X10" -> Some(2) and "G90.1G2X10" -> Some(2).
     fn extract_gcode_num(line: &str) -> Option<u32> {
         if !line.starts_with('G') {
             return None;
         }
-        let after_g = &line[1..];
-        // Find end of number
-        let end_idx = after_g
-            .find(|c: char| !c.is_ascii_digit())
-            .unwrap_or(after_g.len());
-
-        if end_idx == 0 {
-            return None;
+        let mut plane_num = None;
+        for (letter, value) in tokenize_words(line) {
+            if letter != 'G' {
+                break;
+            }
+            if matches!((value * 10.0).round() as i64, 901 | 911) {
+                continue;
+            }
+            let num = value.trunc() as u32;
+            if ArcPlane::from_gcode(num).is_none() {
+                return Some(num);
+            }
+            plane_num.get_or_insert(num);
         }
+        plane_num
+    }
+
+    /// Plane selected on a line by G17/G18/G19, if any
+    fn extract_plane(line: &str) -> Option<ArcPlane> {
+        tokenize_words(line)
+            .into_iter()
+            .rev()
+            .filter(|&(letter, value)| letter == 'G' && value.fract() == 0.0)
+            .find_map(|(_, value)| ArcPlane::from_gcode(value as u32))
+    }
 
-        after_g[..end_idx].parse::<u32>().ok()
+    /// Arc distance mode set on a line: `Some(true)` for G90.1 (absolute
+    /// I/J/K), `Some(false)` for G91.1 (offsets from the start)
+    fn extract_arc_center_mode(line: &str) -> Option<bool> {
+        tokenize_words(line)
+            .into_iter()
+            .rev()
+            .find_map(|(letter, value)| match (letter, (value * 10.0).round() as i64) {
+                ('G', 901) => Some(true),
+                ('G', 911) => Some(false),
+                _ => None,
+            })
     }
 
     /// Parse G-Code and extract movement commands
@@ -222,7 +634,15 @@ impl Visualizer2D {
         }
 
         let mut commands = Vec::new();
+        let mut z_segments = Vec::new();
+        let mut command_tools = Vec::new();
+        let mut current_tool = 0;
+        let mut command_feeds = Vec::new();
+        let mut current_feed = 0.0;
+        let mut current_z = 0.0;
         let mut current_pos = Point2D::new(0.0, 0.0);
+        let mut plane = ArcPlane::XY;
+        let mut absolute_centers = false;
         self.current_intensity = 0.0;
         let mut bounds = Bounds::new();
         let mut _g0_count = 0;
@@ -237,8 +657,68 @@ impl Visualizer2D {
                 continue;
             }
 
+            // Commands from earlier lines were made with the tool active then
+            command_tools.resize(commands.len(), current_tool);
+            if let Some(tool) = Self::extract_tool(line) {
+                current_tool = tool;
+            }
+            command_feeds.resize(commands.len(), current_feed);
+            if let Some(feed) = Self::extract_feed(line) {
+                current_feed = feed;
+            }
+
+            if let Some(selected) = Self::extract_plane(line) {
+                plane = selected;
+            }
+            if let Some(absolute) = Self::extract_arc_center_mode(line) {
+                absolute_centers = absolute;
+            }
+
             if let Some(gcode_num) = Self::extract_gcode_num(line) {
+                let command_count = commands.len();
+                let z = Self::extract_z(line).filter(|_| gcode_num <= 3);
+
                 match gcode_num {
+                    2 | 3 if plane != ArcPlane::XY => {
+                        if gcode_num == 2 {
+                            _g2_count += 1;
+                        } else {
+                            _g3_count += 1;
+                        }
+                        // Seen from above, an XZ or YZ arc is drawn as its projected polyline
+                        let start = Vector3::new(current_pos.x, current_pos.y, current_z);
+                        let points = Self::parse_plane_arc_move(
+                            line,
+                            start,
+                            &mut self.current_intensity,
+                            gcode_num == 2,
+                            plane,
+                            absolute_centers,
+                        );
+                        let mut from = start;
+                        for to in points {
+                            let (from_2d, to_2d) =
+                                (Point2D::new(from.x, from.y), Point2D::new(to.x, to.y));
+                            commands.push(GCodeCommand::Move {
+                                from: from_2d,
+                                to: to_2d,
+                                rapid: false,
+                                intensity: Some(self.current_intensity),
+                            });
+                            z_segments.push(ZSegment {
+                                from: from_2d,
+                                to: to_2d,
+                                z_from: from.z,
+                                z_to: to.z,
+                            });
+                            bounds.update(from.x, from.y);
+                            bounds.update(to.x, to.y);
+                            from = to;
+                        }
+                        current_pos = Point2D::new(from.x, from.y);
+                        current_z = from.z;
+                        continue;
+                    }
                     0 => {
                         _g0_count += 1;
                         Self::parse_linear_move(
@@ -270,6 +750,7 @@ impl Visualizer2D {
                             &mut self.current_intensity,
                             &mut bounds,
                             true,
+                            absolute_centers,
                         );
                     }
                     3 => {
@@ -281,6 +762,7 @@ impl Visualizer2D {
                             &mut self.current_intensity,
                             &mut bounds,
                             false,
+                            absolute_centers,
                         );
                     }
                     4 => {
@@ -292,12 +774,33 @@ impl Visualizer2D {
                     }
                     _ => {}
                 }
+
+                let z_to = z.unwrap_or(current_z);
+                if commands.len() > command_count {
+                    if let Some(
+                        GCodeCommand::Move { from, to, .. } | GCodeCommand::Arc { from, to, .. },
+                    ) = commands.last()
+                    {
+                        z_segments.push(ZSegment {
+                            from: *from,
+                            to: *to,
+                            z_from: current_z,
+                            z_to,
+                        });
+                    }
+                }
+                current_z = z_to;
             }
         }
 
         (self.min_x, self.max_x, self.min_y, self.max_y) =
             bounds.finalize_with_padding(BOUNDS_PADDING_FACTOR);
         self.current_pos = current_pos;
+        self.z_segments = z_segments;
+        command_tools.resize(commands.len(), current_tool);
+        self.command_tools = command_tools;
+        command_feeds.resize(commands.len(), current_feed);
+        self.command_feeds = command_feeds;
 
         self.toolpath_cache.update(new_hash, commands);
     }
@@ -316,22 +819,48 @@ impl Visualizer2D {
         )
     }
 
+    /// Convert a screen point to machine X/Y using the current zoom and pan
+    ///
+    /// # Arguments
+    /// * `screen_x` - Horizontal position in pixels from the canvas left edge
+    /// * `screen_y` - Vertical position in pixels from the canvas top edge
+    /// * `canvas_height` - Canvas height in pixels, needed to undo the Y flip
+    pub fn coords_at(&self, screen_x: f32, screen_y: f32, canvas_height: f32) -> (f32, f32) {
+        self.viewport.screen_to_world(
+            self.min_x,
+            self.min_y,
+            self.zoom_scale,
+            self.scale_factor,
+            self.x_offset,
+            self.y_offset,
+            canvas_height,
+            screen_x,
+            screen_y,
+        )
+    }
+
+    /// Interpolated Z of the toolpath segment nearest to a machine X/Y
+    ///
+    /// Arcs are approximated by their chord. Returns `None` if no segment lies
+    /// within `max_distance` mm of the point.
+    pub fn z_at(&self, x: f32, y: f32, max_distance: f32) -> Option<f32> {
+        self.z_segments
+            .iter()
+            .map(|segment| segment.distance_and_z(x, y))
+            .filter(|(distance, _)| *distance <= max_distance)
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, z)| z)
+    }
+
     fn parse_dwell(
         commands: &mut Vec<GCodeCommand>,
         line: &str,
         current_pos: &mut Point2D,
     ) {
         let mut duration = 0.0;
-        for part in line.split_whitespace() {
-            if part.len() < 2 { continue; }
-            let first_char = part.chars().next().unwrap();
-            match first_char {
-                'P' | 'X' => {
-                    if let Ok(val) = part[1..].parse::<f32>() {
-                        duration = val;
-                    }
-                }
-                _ => {}
+        for (letter, value) in tokenize_words(line) {
+            if matches!(letter, 'P' | 'X') {
+                duration = value as f32;
             }
         }
         commands.push(GCodeCommand::Dwell {
@@ -354,29 +883,18 @@ impl Visualizer2D {
         let mut x_found = false;
         let mut y_found = false;
 
-        for part in line.split_whitespace() {
-            if part.len() < 2 {
-                continue;
-            }
-            let first_char = part.chars().next().unwrap();
-            match first_char {
+        for (letter, value) in tokenize_words(line) {
+            let val = value as f32;
+            match letter {
                 'X' => {
-                    if let Ok(val) = part[1..].parse::<f32>() {
-                        new_x = val;
-                        x_found = true;
-                    }
+                    new_x = val;
+                    x_found = true;
                 }
                 'Y' => {
-                    if let Ok(val) = part[1..].parse::<f32>() {
-                        new_y = val;
-                        y_found = true;
-                    }
-                }
-                'S' => {
-                    if let Ok(val) = part[1..].parse::<f32>() {
-                        *current_intensity = val;
-                    }
+                    new_y = val;
+                    y_found = true;
                 }
+                'S' => *current_intensity = val,
                 _ => {}
             }
         }
@@ -404,50 +922,32 @@ impl Visualizer2D {
         current_intensity: &mut f32,
         bounds: &mut Bounds,
         clockwise: bool,
+        absolute_centers: bool,
     ) {
         let mut new_x = None;
         let mut new_y = None;
         let mut offset_i = None;
         let mut offset_j = None;
 
-        for part in line.split_whitespace() {
-            if part.len() < 2 {
-                continue;
-            }
-            let first_char = part.chars().next().unwrap();
-            match first_char {
-                'X' => {
-                    if let Ok(val) = part[1..].parse::<f32>() {
-                        new_x = Some(val);
-                    }
-                }
-                'Y' => {
-                    if let Ok(val) = part[1..].parse::<f32>() {
-                        new_y = Some(val);
-                    }
-                }
-                'I' => {
-                    if let Ok(val) = part[1..].parse::<f32>() {
-                        offset_i = Some(val);
-                    }
-                }
-                'J' => {
-                    if let Ok(val) = part[1..].parse::<f32>() {
-                        offset_j = Some(val);
-                    }
-                }
-                'S' => {
-                    if let Ok(val) = part[1..].parse::<f32>() {
-                        *current_intensity = val;
-                    }
-                }
+        for (letter, value) in tokenize_words(line) {
+            let val = value as f32;
+            match letter {
+                'X' => new_x = Some(val),
+                'Y' => new_y = Some(val),
+                'I' => offset_i = Some(val),
+                'J' => offset_j = Some(val),
+                'S' => *current_intensity = val,
                 _ => {}
             }
         }
 
         if let (Some(x), Some(y), Some(i), Some(j)) = (new_x, new_y, offset_i, offset_j) {
             let to = Point2D::new(x, y);
-            let center = Point2D::new(current_pos.x + i, current_pos.y + j);
+            let center = if absolute_centers {
+                Point2D::new(i, j)
+            } else {
+                Point2D::new(current_pos.x + i, current_pos.y + j)
+            };
 
             commands.push(GCodeCommand::Arc {
                 from: *current_pos,
@@ -463,6 +963,46 @@ impl Visualizer2D {
         }
     }
 
+    /// Parse a G2/G3 in the XZ or YZ plane into points along the arc
+    ///
+    /// Missing axis words keep the start position; missing I/J/K offsets are 0.
+    /// With `absolute_centers` (G90.1) I/J/K are the center coordinates instead.
+    /// Returns no points if the line has no axis words.
+    fn parse_plane_arc_move(
+        line: &str,
+        start: Vector3,
+        current_intensity: &mut f32,
+        clockwise: bool,
+        plane: ArcPlane,
+        absolute_centers: bool,
+    ) -> Vec<Vector3> {
+        let mut end = start;
+        let mut offset = Vector3::new(0.0, 0.0, 0.0);
+        let mut axis_found = false;
+
+        for (letter, value) in tokenize_words(line) {
+            let val = value as f32;
+            match letter {
+                'X' => (end.x, axis_found) = (val, true),
+                'Y' => (end.y, axis_found) = (val, true),
+                'Z' => (end.z, axis_found) = (val, true),
+                'I' if absolute_centers => offset.x = val - start.x,
+                'J' if absolute_centers => offset.y = val - start.y,
+                'K' if absolute_centers => offset.z = val - start.z,
+                'I' => offset.x = val,
+                'J' => offset.y = val,
+                'K' => offset.z = val,
+                'S' => *current_intensity = val,
+                _ => {}
+            }
+        }
+
+        if !axis_found {
+            return Vec::new();
+        }
+        flatten_arc(start, end, start + offset, clockwise, plane, PLANE_ARC_SEGMENTS)
+    }
+
     /// Extract multiple parameters from G-Code line
     // Deprecated: Use direct parsing in parse_linear_move/parse_arc_move instead
     #[allow(dead_code)]
@@ -522,6 +1062,207 @@ impl Visualizer2D {
         self.toolpath_cache.commands()
     }
 
+    /// Tool number active for each command in [`commands`](Self::commands)
+    ///
+    /// Moves before the first T word are given tool 0.
+    pub fn command_tools(&self) -> &[u32] {
+        &self.command_tools
+    }
+
+    /// Set the machine rapid rate (mm/min) used to time rapids during playback
+    pub fn set_rapid_rate(&mut self, rapid_rate: f32) {
+        self.rapid_rate = rapid_rate;
+    }
+
+    /// Estimated seconds each command in [`commands`](Self::commands) takes
+    ///
+    /// Cutting moves run at the modal F rate and rapids at the machine rapid
+    /// rate, over their XY length; moves before any feed rate is set take no
+    /// time. Dwells take their P time. As with the runtime estimate,
+    /// acceleration is ignored.
+    pub fn command_durations(&self) -> Vec<f32> {
+        self.commands()
+            .iter()
+            .enumerate()
+            .map(|(index, command)| {
+                let (length, rate) = match *command {
+                    GCodeCommand::Move {
+                        from, to, rapid, ..
+                    } => {
+                        let rate = if rapid {
+                            self.rapid_rate
+                        } else {
+                            self.command_feeds.get(index).copied().unwrap_or(0.0)
+                        };
+                        ((to.x - from.x).hypot(to.y - from.y), rate)
+                    }
+                    GCodeCommand::Arc { .. } => {
+                        let (_, sweep, radius) = Self::arc_geometry(command);
+                        let rate = self.command_feeds.get(index).copied().unwrap_or(0.0);
+                        (radius * sweep.abs(), rate)
+                    }
+                    GCodeCommand::Dwell { duration, .. } => return duration.max(0.0),
+                };
+                if rate > 0.0 {
+                    length / rate * 60.0
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    }
+
+    /// Estimated seconds the whole program takes, see [`command_durations`](Self::command_durations)
+    pub fn estimated_duration(&self) -> f32 {
+        self.command_durations().iter().sum()
+    }
+
+    /// Move the tool marker to where the job would be after `seconds`
+    ///
+    /// Playback follows the estimated time of each command rather than
+    /// stepping a command at a time, so the preview runs at the pace the job
+    /// will. `speed` scales the elapsed time: 1.0 plays in real time, 2.0
+    /// twice as fast. The position is clamped to the start and end of the
+    /// toolpath.
+    ///
+    /// # Arguments
+    /// * `seconds` - Wall-clock time since playback started
+    /// * `speed` - Playback speed multiplier
+    pub fn set_playback_time(&mut self, seconds: f32, speed: f32) -> PlaybackPosition {
+        let durations = self.command_durations();
+        let elapsed = (seconds * speed).max(0.0);
+
+        let playback = match self.commands().last() {
+            None => PlaybackPosition {
+                command_index: 0,
+                fraction: 0.0,
+                position: self.current_pos,
+                finished: true,
+            },
+            // Compared against the sum so rounding can't stop playback just short
+            Some(last) if elapsed >= durations.iter().sum::<f32>() => PlaybackPosition {
+                command_index: durations.len() - 1,
+                fraction: 1.0,
+                position: Self::point_along(last, 1.0),
+                finished: true,
+            },
+            Some(_) => {
+                let mut remaining = elapsed;
+                let mut index = 0;
+                while index + 1 < durations.len() && remaining >= durations[index] {
+                    remaining -= durations[index];
+                    index += 1;
+                }
+                let fraction = if durations[index] > 0.0 {
+                    (remaining / durations[index]).min(1.0)
+                } else {
+                    1.0
+                };
+                PlaybackPosition {
+                    command_index: index,
+                    fraction,
+                    position: Self::point_along(&self.commands()[index], fraction),
+                    finished: false,
+                }
+            }
+        };
+
+        self.current_pos = playback.position;
+        playback
+    }
+
+    /// Start angle, signed sweep and radius of an arc command
+    fn arc_geometry(command: &GCodeCommand) -> (f32, f32, f32) {
+        let GCodeCommand::Arc {
+            from,
+            to,
+            center,
+            clockwise,
+            ..
+        } = *command
+        else {
+            return (0.0, 0.0, 0.0);
+        };
+        let radius = (from.x - center.x).hypot(from.y - center.y);
+        let start = (from.y - center.y).atan2(from.x - center.x);
+        let mut sweep = (to.y - center.y).atan2(to.x - center.x) - start;
+        if clockwise && sweep >= 0.0 {
+            sweep -= std::f32::consts::TAU;
+        } else if !clockwise && sweep <= 0.0 {
+            sweep += std::f32::consts::TAU;
+        }
+        (start, sweep, radius)
+    }
+
+    /// Point a fraction (0.0-1.0) of the way along a command
+    fn point_along(command: &GCodeCommand, t: f32) -> Point2D {
+        match *command {
+            GCodeCommand::Move { from, to, .. } => {
+                Point2D::new(from.x + (to.x - from.x) * t, from.y + (to.y - from.y) * t)
+            }
+            GCodeCommand::Arc { to, .. } if t >= 1.0 => to,
+            GCodeCommand::Arc { center, .. } => {
+                let (start, sweep, radius) = Self::arc_geometry(command);
+                let (sin, cos) = (start + sweep * t).sin_cos();
+                Point2D::new(center.x + radius * cos, center.y + radius * sin)
+            }
+            GCodeCommand::Dwell { pos, .. } => pos,
+        }
+    }
+
+    /// Cutting moves grouped by tool, with a legend for the tools used
+    ///
+    /// Each tool gets the next theme tool color in the order the program
+    /// first cuts with it. Rapids and dwells are left out since they are
+    /// drawn on their own layers. Tool names come from `library`.
+    pub fn render_by_tool(&self, library: &ToolLibrary) -> ToolRendering {
+        use std::fmt::Write;
+
+        let mut rendering = ToolRendering::default();
+        let mut last: Option<(u32, Point2D)> = None;
+
+        for (command, &tool) in self.commands().iter().zip(&self.command_tools) {
+            let (from, to) = match command {
+                GCodeCommand::Move {
+                    from,
+                    to,
+                    rapid: false,
+                    ..
+                }
+                | GCodeCommand::Arc { from, to, .. } => (*from, *to),
+                GCodeCommand::Move { .. } => {
+                    last = None;
+                    continue;
+                }
+                GCodeCommand::Dwell { .. } => continue,
+            };
+
+            if !rendering.paths.contains_key(&tool) {
+                let name = library
+                    .get_tool(tool)
+                    .map(|info| info.name.clone())
+                    .unwrap_or_else(|| format!("T{}", tool));
+                let color = self.theme.tool_color(rendering.legend.len()).to_string();
+                rendering.legend.push(ToolLegendEntry { tool, name, color });
+            }
+
+            let path = rendering.paths.entry(tool).or_default();
+            if last != Some((tool, from)) {
+                let _ = write!(path, "M {:.2} {:.2} ", from.x, -from.y);
+            }
+            match command {
+                GCodeCommand::Arc {
+                    center, clockwise, ..
+                } => write_arc(path, from, to, *center, *clockwise),
+                _ => {
+                    let _ = write!(path, "L {:.2} {:.2} ", to.x, -to.y);
+                }
+            }
+            last = Some((tool, to));
+        }
+        rendering
+    }
+
     /// Increase zoom by 10%
     pub fn zoom_in(&mut self) {
         self.zoom_scale = (self.zoom_scale * ZOOM_STEP).min(MAX_ZOOM);
@@ -647,6 +1388,84 @@ impl Visualizer2D {
         }
     }
 
+    /// Direction arrows spaced evenly along the cutting moves
+    ///
+    /// `spacing_px` is the on-screen distance between arrows, so zooming in
+    /// adds arrows rather than spreading them apart. Spacing carries over
+    /// between consecutive cuts and restarts after each rapid.
+    pub fn render_direction_arrows(&self, spacing_px: f32) -> Vec<DirectionArrow> {
+        let pixels_per_mm = self.zoom_scale * self.scale_factor;
+        if spacing_px <= 0.0 || pixels_per_mm <= 0.0 {
+            return Vec::new();
+        }
+        let spacing = spacing_px / pixels_per_mm;
+
+        let mut arrows = Vec::new();
+        // Distance along the path to the next arrow
+        let mut next = spacing / 2.0;
+
+        for command in self.commands() {
+            match *command {
+                GCodeCommand::Move {
+                    from,
+                    to,
+                    rapid: false,
+                    ..
+                } => {
+                    let (dx, dy) = (to.x - from.x, to.y - from.y);
+                    let length = dx.hypot(dy);
+                    if length <= f32::EPSILON {
+                        continue;
+                    }
+                    let direction = Point2D::new(dx / length, dy / length);
+                    while next <= length {
+                        let t = next / length;
+                        arrows.push(DirectionArrow {
+                            position: Point2D::new(from.x + dx * t, from.y + dy * t),
+                            direction,
+                        });
+                        next += spacing;
+                    }
+                    next -= length;
+                }
+                GCodeCommand::Arc {
+                    from,
+                    to,
+                    center,
+                    clockwise,
+                    ..
+                } => {
+                    let radius = (from.x - center.x).hypot(from.y - center.y);
+                    let start = (from.y - center.y).atan2(from.x - center.x);
+                    let mut sweep = (to.y - center.y).atan2(to.x - center.x) - start;
+                    if clockwise && sweep >= 0.0 {
+                        sweep -= std::f32::consts::TAU;
+                    } else if !clockwise && sweep <= 0.0 {
+                        sweep += std::f32::consts::TAU;
+                    }
+                    let length = radius * sweep.abs();
+                    if length <= f32::EPSILON {
+                        continue;
+                    }
+                    let turn = sweep.signum();
+                    while next <= length {
+                        let (sin, cos) = (start + sweep * next / length).sin_cos();
+                        arrows.push(DirectionArrow {
+                            position: Point2D::new(center.x + radius * cos, center.y + radius * sin),
+                            direction: Point2D::new(-sin * turn, cos * turn),
+                        });
+                        next += spacing;
+                    }
+                    next -= length;
+                }
+                GCodeCommand::Move { rapid: true, .. } => next = spacing / 2.0,
+                GCodeCommand::Dwell { .. } => {}
+            }
+        }
+
+        arrows
+    }
+
     /// Get the start point of the toolpath (for debugging/testing)
     pub fn get_start_point(&self) -> Option<Point2D> {
         self.toolpath_cache.commands().first().map(|cmd| match cmd {
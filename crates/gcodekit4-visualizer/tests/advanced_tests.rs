@@ -0,0 +1,165 @@
+use gcodekit4_visualizer::{
+    AdvancedProber, BackupEntry, BackupManager, FileComparison, SettingsTarget,
+};
+
+struct MockDevice {
+    settings: Vec<(String, String)>,
+    fail_on: Vec<String>,
+    writes: Vec<(String, String)>,
+}
+
+impl SettingsTarget for MockDevice {
+    fn read_settings(&mut self) -> anyhow::Result<Vec<(String, String)>> {
+        Ok(self.settings.clone())
+    }
+
+    fn write_setting(&mut self, id: &str, value: &str) -> anyhow::Result<()> {
+        self.writes.push((id.to_string(), value.to_string()));
+        if self.fail_on.iter().any(|f| f == id) {
+            anyhow::bail!("error:3");
+        }
+        if let Some(setting) = self.settings.iter_mut().find(|(s, _)| s == id) {
+            setting.1 = value.to_string();
+        }
+        Ok(())
+    }
+}
+
+fn settings_backup(name: &str, content: &str) -> (BackupManager, BackupEntry) {
+    let dir =
+        std::env::temp_dir().join(format!("gcodekit4_backup_{}_{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let source = dir.join("settings.txt");
+    std::fs::write(&source, content).unwrap();
+    let manager = BackupManager::new(dir.join("backups"));
+    let entry = manager.backup(&source, "settings").unwrap();
+    (manager, entry)
+}
+
+fn device(fail_on: &[&str]) -> MockDevice {
+    MockDevice {
+        settings: vec![
+            ("$100".to_string(), "80".to_string()),
+            ("$110".to_string(), "1000".to_string()),
+            ("$120".to_string(), "10".to_string()),
+        ],
+        fail_on: fail_on.iter().map(|s| s.to_string()).collect(),
+        writes: Vec::new(),
+    }
+}
+
+#[test]
+fn test_backup_restore_settings_applies_values() {
+    let (manager, entry) = settings_backup("apply", "; machine\n$100=250\n\n$110=5000\n$120=50\n");
+    let mut target = device(&[]);
+
+    let report = manager.restore_settings(&entry, &mut target).unwrap();
+    assert!(!report.rolled_back);
+    assert_eq!(report.applied, vec!["$100", "$110", "$120"]);
+    assert_eq!(target.settings[1], ("$110".to_string(), "5000".to_string()));
+    std::fs::remove_dir_all(entry.original_path.parent().unwrap()).ok();
+}
+
+#[test]
+fn test_backup_restore_settings_rolls_back_on_failure() {
+    let (manager, entry) = settings_backup("rollback", "$100=250\n$110=5000\n$120=50\n");
+    let mut target = device(&["$110"]);
+
+    let report = manager.restore_settings(&entry, &mut target).unwrap();
+    assert!(report.rolled_back);
+    assert_eq!(report.failed.len(), 1);
+
+    // Restore writes followed by the original values re-sent
+    assert_eq!(
+        target.writes[3..],
+        [
+            ("$100".to_string(), "80".to_string()),
+            ("$120".to_string(), "10".to_string()),
+            ("$110".to_string(), "1000".to_string()),
+        ]
+    );
+    assert_eq!(target.settings, device(&[]).settings);
+    std::fs::remove_dir_all(entry.original_path.parent().unwrap()).ok();
+}
+
+#[test]
+fn test_backup_restore_settings_within_threshold() {
+    let (mut manager, entry) = settings_backup("threshold", "$100=250\n$110=5000\n");
+    manager.set_rollback_threshold(1);
+    let mut target = device(&["$110"]);
+
+    let report = manager.restore_settings(&entry, &mut target).unwrap();
+    assert!(!report.rolled_back);
+    assert_eq!(target.settings[0], ("$100".to_string(), "250".to_string()));
+    std::fs::remove_dir_all(entry.original_path.parent().unwrap()).ok();
+}
+
+#[test]
+fn test_file_comparison_changed_ranges() {
+    let previous = "G21\nG0 X0 Y0\nG1 X10 F500\nG1 Y10\nM30\n";
+    let current = "G21\nG0 X0 Y0\nG1 X12 F500\nG1 Y10\nM30\n";
+    let comparison = FileComparison::new(previous, current);
+    assert_eq!(comparison.changed_ranges(), vec![2..3]);
+
+    let current = "G20\nG0 X0 Y0\nG1 X12 F400\nG1 Y12\nM30\nM2\n";
+    let comparison = FileComparison::new(previous, current);
+    assert_eq!(comparison.changed_ranges(), vec![0..1, 2..4, 5..6]);
+
+    let comparison = FileComparison::new(previous, previous);
+    assert!(comparison.changed_ranges().is_empty());
+}
+
+#[test]
+fn test_grid_routine_visits_points_in_boustrophedon_order() {
+    let mut prober = AdvancedProber::new();
+    let routine = prober.generate_grid_routine(((0.0, 0.0), (20.0, 10.0)), 10.0, 75.0, 3.0);
+    let lines: Vec<&str> = routine
+        .iter()
+        .map(|command| command.line.as_str())
+        .collect();
+
+    let mut visited = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if line.starts_with("G38.2") {
+            assert_eq!(*line, "G38.2 Z-50.000 F75");
+            assert_eq!(lines[i + 1], "G0 Z3.000");
+            visited.push(lines[i - 1]);
+        }
+    }
+    assert_eq!(
+        visited,
+        vec![
+            "G0 X0.000 Y0.000",
+            "G0 X10.000 Y0.000",
+            "G0 X20.000 Y0.000",
+            "G0 X20.000 Y10.000",
+            "G0 X10.000 Y10.000",
+            "G0 X0.000 Y10.000",
+        ]
+    );
+    assert_eq!(lines[..2], ["G90", "G0 Z3.000"]);
+    assert_eq!(prober.probe_points().len(), 6);
+}
+
+#[test]
+fn test_grid_probe_results_fill_mesh() {
+    let mut prober = AdvancedProber::new();
+    prober.add_probe_point(50.0, 50.0);
+    prober.generate_grid_routine(((10.0, 10.0), (0.0, 0.0)), 10.0, 50.0, 5.0);
+    assert_eq!(prober.probe_points().len(), 4);
+
+    for (x, y, z) in [
+        (0.0, 0.0, 0.0),
+        (10.0, 0.0, 0.2),
+        (10.0, 10.0, 0.4),
+        (0.0, 10.0, 0.2),
+    ] {
+        prober.record_probe_result(x, y, z);
+    }
+
+    let mesh = prober.mesh();
+    assert_eq!(mesh.points.len(), 4);
+    assert_eq!((mesh.x_spacing, mesh.y_spacing), (10.0, 10.0));
+    assert!((mesh.get_z_offset(5.0, 5.0).unwrap() - 0.2).abs() < 1e-9);
+    assert_eq!(prober.probe_points()[2].z, 0.4);
+}
@@ -0,0 +1,241 @@
+use gcodekit4_visualizer::{
+    apply_mesh, AutoLevelConfig, AutoLeveler, GcodeCommand, GcodeState, HeightPoint,
+    MeshLevelProcessor, ProbeGrid, ProbeMesh, ProcessorPipeline,
+};
+use std::sync::Arc;
+
+const PROGRAM: &str = "G21 G90\n\
+G0 Z5\n\
+G0 X12 Y8\n\
+G1 Z-1 F200\n\
+G1 X47 Y8 F600\n\
+G1 X47 Y33\n\
+G1 X12 Y33\n\
+G1 X12 Y8\n\
+G0 Z5\n\
+M2\n";
+
+fn config(spacing: f64) -> AutoLevelConfig {
+    AutoLevelConfig {
+        spacing,
+        ..AutoLevelConfig::default()
+    }
+}
+
+fn bounds(points: &[(f64, f64)]) -> (f64, f64, f64, f64) {
+    points.iter().fold(
+        (f64::MAX, f64::MIN, f64::MAX, f64::MIN),
+        |(min_x, max_x, min_y, max_y), &(x, y)| {
+            (min_x.min(x), max_x.max(x), min_y.min(y), max_y.max(y))
+        },
+    )
+}
+
+#[test]
+fn test_probe_grid_covers_program_bounds() {
+    let leveler = AutoLeveler::for_program(PROGRAM, config(10.0)).unwrap();
+    let grid = leveler.grid();
+
+    assert_eq!(bounds(&grid.points()), (12.0, 47.0, 8.0, 33.0));
+    // 35mm and 25mm spans need 4 and 3 gaps of at most 10mm
+    assert_eq!(grid.x.len(), 5);
+    assert_eq!(grid.y.len(), 4);
+    assert!(grid.x.windows(2).all(|w| w[1] - w[0] <= 10.0 + 1e-9));
+    assert!(grid.y.windows(2).all(|w| w[1] - w[0] <= 10.0 + 1e-9));
+}
+
+#[test]
+fn test_probe_grid_density_and_margin() {
+    let dense = AutoLeveler::for_program(PROGRAM, config(5.0)).unwrap();
+    assert_eq!(dense.grid().len(), 8 * 6);
+
+    let margin = AutoLevelConfig {
+        margin: 2.0,
+        ..config(10.0)
+    };
+    let leveler = AutoLeveler::for_program(PROGRAM, margin).unwrap();
+    assert_eq!(bounds(&leveler.grid().points()), (10.0, 49.0, 6.0, 35.0));
+}
+
+#[test]
+fn test_probe_program_visits_every_point() {
+    let leveler = AutoLeveler::for_program(PROGRAM, config(10.0)).unwrap();
+    let program = leveler.probe_program();
+
+    let probes = program.iter().filter(|l| l.starts_with("G38.2")).count();
+    assert_eq!(probes, leveler.grid().len());
+    assert_eq!(program[2], "G0 X12.000 Y8.000");
+    assert_eq!(program[3], "G38.2 Z-5.000 F50");
+    // Rows alternate direction
+    let points = leveler.grid().points();
+    assert_eq!((points[4].0, points[5].0), (47.0, 47.0));
+}
+
+#[test]
+fn test_program_without_cuts_is_rejected() {
+    assert!(AutoLeveler::for_program("G0 X10 Y10\n", config(10.0)).is_err());
+}
+
+#[test]
+fn test_run_builds_mesh_and_levels_program() {
+    let mut leveler = AutoLeveler::for_program(PROGRAM, config(40.0)).unwrap();
+    assert_eq!(leveler.grid().points().len(), 4);
+
+    // The surface rises 0.1mm per 10mm of X, starting from Z -2 in machine coordinates
+    let mut probes = 0;
+    let leveled = leveler
+        .level(PROGRAM, |line| {
+            if !line.starts_with("G38.2") {
+                return Ok(vec!["ok".to_string()]);
+            }
+            let x = [12.0, 47.0, 47.0, 12.0][probes];
+            probes += 1;
+            Ok(vec![
+                format!("[PRB:{:.3},0.000,{:.3}:1]", x, -2.0 + (x - 12.0) * 0.01),
+                "ok".to_string(),
+            ])
+        })
+        .unwrap();
+
+    let lines: Vec<&str> = leveled.lines().collect();
+    assert!(lines.contains(&"G0 X12 Y8 Z5"));
+    assert!(lines.contains(&"G1 Z-1 F200"));
+    // The 35mm cut is split into 5mm pieces that climb with the surface
+    let start = lines.iter().position(|l| l.starts_with("G1 X17")).unwrap();
+    assert_eq!(lines[start], "G1 X17 Y8 Z-0.95 F600");
+    assert_eq!(lines[start + 6], "X47 Y8 Z-0.65");
+    assert_eq!(lines.last(), Some(&"M2"));
+}
+
+#[test]
+fn test_failed_probe_stops_run() {
+    let mut leveler = AutoLeveler::for_program(PROGRAM, config(40.0)).unwrap();
+
+    let err = leveler
+        .run(|line| {
+            if line.starts_with("G38.2") {
+                Ok(vec!["[PRB:12.000,8.000,-5.000:0]".to_string()])
+            } else {
+                Ok(vec!["ok".to_string()])
+            }
+        })
+        .unwrap_err();
+    assert!(err.to_string().contains("X12.000 Y8.000"));
+}
+
+#[test]
+fn test_mesh_requires_every_point() {
+    let mut leveler = AutoLeveler::for_program(PROGRAM, config(40.0)).unwrap();
+    assert!(leveler.record_response("[PRB:0,0,-1.5:1]").unwrap());
+    assert!(!leveler.record_response("ok").unwrap());
+    assert!(!leveler.is_complete());
+    assert!(leveler.mesh().is_err());
+}
+
+#[test]
+fn test_grid_mesh_interpolates_bilinearly() {
+    let grid = ProbeGrid::covering((0.0, 0.0), (10.0, 10.0), 10.0);
+    let mut mesh = ProbeMesh::new(10.0, 10.0);
+    for ((x, y), z) in grid.points().into_iter().zip([0.0, 1.0, 3.0, 2.0]) {
+        mesh.add_point(HeightPoint { x, y, z });
+    }
+
+    assert_eq!(mesh.get_z_offset(0.0, 0.0), Some(0.0));
+    assert_eq!(mesh.get_z_offset(5.0, 0.0), Some(0.5));
+    assert_eq!(mesh.get_z_offset(5.0, 5.0), Some(1.5));
+    // Outside the grid the edge value is used
+    assert_eq!(mesh.get_z_offset(20.0, 10.0), Some(3.0));
+}
+
+#[test]
+fn test_apply_mesh_rejects_incremental_moves() {
+    let mesh = ProbeMesh::new(10.0, 10.0);
+    assert!(apply_mesh("G91\nG1 X10 Z-1\n", &mesh, 5.0).is_err());
+}
+
+/// A 20x20 mesh rising 0.01 in Z per unit of X
+fn tilted_mesh() -> ProbeMesh {
+    let grid = ProbeGrid::covering((0.0, 0.0), (20.0, 20.0), 10.0);
+    let mut mesh = ProbeMesh::new(10.0, 10.0);
+    for (x, y) in grid.points() {
+        mesh.add_point(HeightPoint { x, y, z: 0.01 * x });
+    }
+    mesh
+}
+
+fn level(processor: MeshLevelProcessor, source: &[&str]) -> Vec<String> {
+    let mut pipeline = ProcessorPipeline::new();
+    pipeline.register(Arc::new(processor));
+    let commands: Vec<GcodeCommand> = source.iter().map(|l| GcodeCommand::new(*l)).collect();
+    pipeline
+        .process_commands(&commands, &mut GcodeState::new())
+        .unwrap()
+        .into_iter()
+        .map(|command| command.command)
+        .collect()
+}
+
+#[test]
+fn test_mesh_level_processor_follows_tilted_surface() {
+    let output = level(
+        MeshLevelProcessor::new(tilted_mesh(), 5.0),
+        &["G90 G0 X0 Y5 Z-1", "G1 X20 Y5 F300 ; cut"],
+    );
+
+    assert_eq!(
+        output,
+        vec![
+            "G90 G0 X0 Y5 Z-1",
+            "G1 X5 Y5 Z-0.95 F300 ; cut",
+            "X10 Y5 Z-0.9",
+            "X15 Y5 Z-0.85",
+            "X20 Y5 Z-0.8",
+        ]
+    );
+}
+
+#[test]
+fn test_mesh_level_processor_handles_moves_leaving_the_mesh() {
+    let source = ["G90 G0 X-10 Y5 Z-1", "G1 X10 F300", "G0 X30"];
+    let output = level(MeshLevelProcessor::new(tilted_mesh(), 5.0), &source);
+    assert_eq!(
+        output,
+        vec![
+            "G90 G0 X-10 Y5 Z-1",
+            "G1 X-5 Z-1 F300",
+            "X0 Y5 Z-1",
+            "X5 Y5 Z-0.95",
+            "X10 Y5 Z-0.9",
+            "G0 X15 Z-0.85",
+            "X20 Y5 Z-0.8",
+            "X25 Y5 Z-1",
+            "X30 Y5 Z-1",
+        ]
+    );
+
+    let mesh = tilted_mesh().with_clamp_to_edges(true);
+    let output = level(MeshLevelProcessor::new(mesh, 0.0), &source);
+    assert_eq!(
+        output,
+        vec!["G90 G0 X-10 Y5 Z-1", "G1 X10 Z-0.9 F300", "G0 X30 Z-0.8"]
+    );
+}
+
+#[test]
+fn test_mesh_level_processor_rejects_incremental_moves() {
+    let mut pipeline = ProcessorPipeline::new();
+    pipeline.register(Arc::new(MeshLevelProcessor::new(tilted_mesh(), 5.0)));
+    let commands = [GcodeCommand::new("G91"), GcodeCommand::new("G1 X10 Z-1")];
+    assert!(pipeline
+        .process_commands(&commands, &mut GcodeState::new())
+        .is_err());
+}
+
+#[test]
+fn test_apply_mesh_matches_mesh_level_processor() {
+    let source = ["G90 G0 X-10 Y5 Z-1", "G1 X10 F300 (cut)", "G0 X30", "M5"];
+    let expected = level(MeshLevelProcessor::new(tilted_mesh(), 5.0), &source);
+
+    let leveled = apply_mesh(&source.join("\n"), &tilted_mesh(), 5.0).unwrap();
+    assert_eq!(leveled.lines().collect::<Vec<_>>(), expected);
+}
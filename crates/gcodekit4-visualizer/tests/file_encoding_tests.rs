@@ -0,0 +1,105 @@
+use gcodekit4_visualizer::{FileEncoding, GcodeFileReader};
+use std::path::PathBuf;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+#[test]
+fn test_windows_1252_comments_are_decoded() {
+    let reader = GcodeFileReader::new(fixture("windows1252_comments.nc")).unwrap();
+
+    let (content, encoding) = reader.read_all_with_encoding().unwrap();
+    assert_eq!(encoding, FileEncoding::Windows1252);
+    assert!(content.starts_with("; Probe at 45° – Müller jig\r\n"));
+    assert!(content.contains("(Spindle warm-up ± 5%)"));
+    assert!(content.contains("; 30° chamfer"));
+    assert!(!content.contains('\u{FFFD}'));
+}
+
+#[test]
+fn test_read_lines_records_detected_encoding() {
+    let reader = GcodeFileReader::new(fixture("windows1252_comments.nc")).unwrap();
+
+    let mut lines = Vec::new();
+    let stats = reader
+        .read_lines(|line| {
+            lines.push(line.to_string());
+            Ok(())
+        })
+        .unwrap();
+
+    assert_eq!(stats.encoding, FileEncoding::Windows1252);
+    assert_eq!(stats.lines_read, 5);
+    assert_eq!(stats.bytes_read, reader.file_size());
+    assert_eq!(lines[0], "; Probe at 45° – Müller jig");
+    assert_eq!(lines[4], "G1 X10 Y10 F500 ; 30° chamfer");
+}
+
+#[test]
+fn test_late_non_utf8_line_switches_to_windows_1252() {
+    let path =
+        std::env::temp_dir().join(format!("gcodekit4_late_latin1_{}.nc", std::process::id()));
+    let mut data = "G0 X0 Y0\n".repeat(40_000).into_bytes();
+    data.extend_from_slice(b"; 90\xB0 turn\n");
+    std::fs::write(&path, &data).unwrap();
+
+    let reader = GcodeFileReader::new(&path).unwrap();
+    let mut last = String::new();
+    let stats = reader
+        .read_lines(|line| {
+            last = line.to_string();
+            Ok(())
+        })
+        .unwrap();
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(stats.encoding, FileEncoding::Windows1252);
+    assert_eq!(last, "; 90° turn");
+}
+
+#[test]
+fn test_utf8_bom_is_stripped() {
+    let path = std::env::temp_dir().join(format!("gcodekit4_bom_{}.nc", std::process::id()));
+    std::fs::write(&path, "\u{FEFF}; 45° bevel\nG0 X1\n").unwrap();
+
+    let reader = GcodeFileReader::new(&path).unwrap();
+    let (content, encoding) = reader.read_all_with_encoding().unwrap();
+    let (lines, stats) = reader.read_lines_limited(1).unwrap();
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(encoding, FileEncoding::Utf8);
+    assert_eq!(content, "; 45° bevel\nG0 X1\n");
+    assert_eq!(lines, vec!["; 45° bevel".to_string()]);
+    assert_eq!(stats.encoding, FileEncoding::Utf8);
+}
+
+#[test]
+fn test_binary_file_falls_back_gracefully() {
+    let path = std::env::temp_dir().join(format!("gcodekit4_binary_{}.bin", std::process::id()));
+    std::fs::write(
+        &path,
+        [0x89, b'P', b'N', b'G', 0x00, 0x1A, 0xFF, 0x00, b'\n'],
+    )
+    .unwrap();
+
+    let reader = GcodeFileReader::new(&path).unwrap();
+    let (content, encoding) = reader.read_all_with_encoding().unwrap();
+    let validation = reader.validate().unwrap();
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(encoding, FileEncoding::Binary);
+    assert!(content.contains('\u{FFFD}'));
+    assert!(validation.warnings.iter().any(|w| w.contains("binary")));
+}
+
+#[test]
+fn test_windows_1252_decode() {
+    assert_eq!(
+        FileEncoding::Windows1252.decode(b"\x80 \x93quoted\x94 45\xB0"),
+        "\u{20AC} \u{201C}quoted\u{201D} 45\u{B0}"
+    );
+}
@@ -0,0 +1,79 @@
+use gcodekit4_visualizer::{FeedRateStats, FileProcessingPipeline};
+
+#[test]
+fn test_feed_rate_stats_split_rapid_and_cutting() {
+    let mut stats = FeedRateStats::new();
+    stats.record_motion(
+        "G21 G90\nG0 X100\nG1 Y50 F500\nG1 X0 F1000\nG0 Y0\n",
+        5000.0,
+    );
+
+    // 150 mm of rapids at 5000 mm/min, 50 mm at 500 and 100 mm at 1000
+    assert!((stats.rapid_distance - 150.0).abs() < 1e-9);
+    assert!((stats.cutting_distance - 150.0).abs() < 1e-9);
+    assert!((stats.rapid_time - 1.8).abs() < 1e-6);
+    assert!((stats.cutting_time - 12.0).abs() < 1e-6);
+    assert_eq!(stats.total_time(), stats.rapid_time + stats.cutting_time);
+}
+
+#[test]
+fn test_processed_file_time_is_rapid_plus_cutting() {
+    let path = std::env::temp_dir().join(format!(
+        "gcodekit4_statistics_split_{}.nc",
+        std::process::id()
+    ));
+    std::fs::write(
+        &path,
+        "G21 G90\n; square\nG0 X100\nG1 Y50 F500\nG1 X0 F1000\n",
+    )
+    .unwrap();
+
+    let mut pipeline = FileProcessingPipeline::new();
+    let processed = pipeline.process_file(&path);
+    std::fs::remove_file(&path).ok();
+
+    let stats = processed.unwrap().statistics;
+    let split = stats.feed_rate_stats;
+    assert!((split.rapid_time - 100.0 / 5000.0 * 60.0).abs() < 1e-6);
+    assert!((split.cutting_time - 12.0).abs() < 1e-6);
+    assert_eq!(stats.estimated_time, split.total_time().round() as u64);
+}
+
+#[test]
+fn test_file_statistics_per_tool() {
+    let path = std::env::temp_dir().join(format!(
+        "gcodekit4_statistics_per_tool_{}.nc",
+        std::process::id()
+    ));
+    let program = "G21 G90\nT1 M6\nG0 X0 Y0 Z5\nG1 Z-1 F100\nG1 X10\nG0 Z5\n\
+                   T2\nM6\nG0 X20 Y0\nG1 Z-2\nG2 X30 Y0 I5 J0\nG0 Z5\n";
+    std::fs::write(&path, program).unwrap();
+
+    let mut pipeline = FileProcessingPipeline::new();
+    let processed = pipeline.process_file(&path);
+    std::fs::remove_file(&path).ok();
+    let stats = processed.unwrap().statistics;
+
+    assert_eq!(stats.per_tool.len(), 2);
+    let first = stats.per_tool[&1];
+    assert_eq!(
+        (first.rapid_moves, first.linear_moves, first.arc_moves),
+        (2, 2, 0)
+    );
+    assert!((first.cut_distance - 16.0).abs() < 1e-4);
+
+    // Selected by T2 on one line and loaded by M6 on the next
+    let second = stats.per_tool[&2];
+    assert_eq!(
+        (second.rapid_moves, second.linear_moves, second.arc_moves),
+        (2, 1, 1)
+    );
+    assert!((second.cut_distance - 17.0).abs() < 1e-4);
+
+    let moves: u64 = stats
+        .per_tool
+        .values()
+        .map(|t| t.total_motion_commands())
+        .sum();
+    assert_eq!(moves, stats.total_motion_commands());
+}
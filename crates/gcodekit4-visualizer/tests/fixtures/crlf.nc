@@ -0,0 +1,5 @@
+G21 G90
+G0 X0 Y0 Z5
+G1 Z-1 F100
+G1 X10 Y10 F500
+M30
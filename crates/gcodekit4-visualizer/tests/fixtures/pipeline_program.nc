@@ -0,0 +1,10 @@
+; pipeline fixture
+G21 G90
+
+  G0 X0 Y0 Z5   
+G1 Z-1.0000 F300   ; plunge
+G1 X10.50000 Y0
+
+G1 X10.5 Y10
+G0 Z5
+M30
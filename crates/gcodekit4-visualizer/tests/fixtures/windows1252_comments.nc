@@ -0,0 +1,5 @@
+; Probe at 45  Mller jig
+(Spindle warm-up  5%)
+G21
+G0 X0 Y0
+G1 X10 Y10 F500 ; 30 chamfer
@@ -0,0 +1,518 @@
+use gcodekit4_core::OverrideState;
+use gcodekit4_visualizer::{
+    append_program_end, check_arc_center_mode, check_distance_mode, check_plunge_rates,
+    check_program_end, check_rapid_retracts, check_soft_limits, check_zero_length_moves,
+    compute_extents, insert_distance_mode, merge_arcs, program_end, remove_zero_length_moves,
+    split_by_tool, split_operations, GcodeCommand, Operation, PlungeLimits, ProgramEnd,
+    RuntimeEstimate, SoftLimits, ValidationSeverity, WorkCoordinateSystem, WorkOffset,
+};
+use std::f64::consts::PI;
+use std::time::Duration;
+
+#[test]
+fn test_split_operations_on_tool_change() {
+    let program = "G21\nG90\nT1 M6\nM3 S12000\nG0 X0 Y0\nG1 X10 F500\nT2 M6\nG0 X5\nG1 Y5\nM30";
+    let operations = split_operations(program);
+
+    assert_eq!(
+        operations,
+        vec![
+            Operation {
+                name: "Operation 1".to_string(),
+                line_range: 0..6,
+                tool: Some(1),
+            },
+            Operation {
+                name: "Operation 2".to_string(),
+                line_range: 6..10,
+                tool: Some(2),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_split_operations_uses_operation_comments() {
+    let program = "(Operation: Profile)\nT3\nM6\nG0 X0\nG1 X10\n; operation: Pocket\nG1 Y10\n";
+    let operations = split_operations(program);
+
+    assert_eq!(operations.len(), 2);
+    assert_eq!(operations[0].name, "Profile");
+    assert_eq!(operations[0].line_range, 0..5);
+    assert_eq!(operations[0].tool, Some(3));
+    assert_eq!(operations[1].name, "Pocket");
+    assert_eq!(operations[1].line_range, 5..7);
+    assert_eq!(operations[1].tool, Some(3));
+}
+
+#[test]
+fn test_split_operations_comment_before_tool_change_is_one_boundary() {
+    let program = "G0 X0\n(Operation: Drill)\nT4 M6\nG81 X1 Y1 Z-2 R1\n";
+    let operations = split_operations(program);
+
+    assert_eq!(operations.len(), 2);
+    assert_eq!(operations[1].name, "Drill");
+    assert_eq!(operations[1].line_range, 1..4);
+    assert_eq!(operations[1].tool, Some(4));
+}
+
+#[test]
+fn test_split_operations_empty_program() {
+    assert!(split_operations("").is_empty());
+}
+
+#[test]
+fn test_rapid_at_cut_depth_is_flagged() {
+    let program = "G21 G90\n\
+                   G0 Z5\n\
+                   G0 X0 Y0\n\
+                   G1 Z-2 F200\n\
+                   G1 X20 F800\n\
+                   G0 X40 Y10\n\
+                   G1 X60\n\
+                   G0 Z5\n\
+                   G0 X0 Y0\n";
+
+    let issues = check_rapid_retracts(program, 0.0);
+
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line_number, 6);
+    assert_eq!(issues[0].severity, ValidationSeverity::Error);
+    assert!(issues[0].message.contains("line 4"));
+}
+
+#[test]
+fn test_retract_before_rapid_passes() {
+    let program = "G90\n\
+                   G0 Z5\n\
+                   G0 X0 Y0\n\
+                   G1 Z-2 F200\n\
+                   G1 X20 F800\n\
+                   G0 Z5\n\
+                   G0 X40 Y10\n\
+                   G1 Z-2\n\
+                   G1 X60\n\
+                   G0 Z5\n\
+                   G0 X0 Y0\n";
+
+    assert!(check_rapid_retracts(program, 0.0).is_empty());
+}
+
+#[test]
+fn test_retract_check_tracks_incremental_z_and_stock_top() {
+    // G91 Z3 from Z-2 only reaches Z1, below a stock top of 2
+    let program = "G90 G0 Z5\nG1 Z-2 F200\nG91 G0 Z3\nG0 X10\n";
+
+    assert!(check_rapid_retracts(program, 0.0).is_empty());
+    let issues = check_rapid_retracts(program, 2.0);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line_number, 4);
+}
+
+fn travel() -> SoftLimits {
+    SoftLimits {
+        x_min: 0.0,
+        x_max: 300.0,
+        y_min: 0.0,
+        y_max: 200.0,
+        z_min: -80.0,
+        z_max: 0.0,
+        enabled: true,
+    }
+}
+
+fn offsets() -> WorkCoordinateSystem {
+    let mut wcs = WorkCoordinateSystem::new();
+    wcs.set_offset(1, WorkOffset::new(100.0, 50.0, -20.0));
+    wcs.set_offset(2, WorkOffset::new(0.0, 50.0, -20.0));
+    wcs
+}
+
+#[test]
+fn test_move_beyond_x_travel_is_flagged() {
+    let program = "G21 G90\n\
+                   G0 Z5\n\
+                   G0 X0 Y0\n\
+                   G1 Z-2 F200\n\
+                   G1 X150 F800\n\
+                   G1 X250\n\
+                   G0 Z5\n\
+                   G0 X0 Y0\n";
+
+    let issues = check_soft_limits(program, &travel(), &offsets());
+
+    // X250 in G54 is X350 on the machine
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line_number, 6);
+    assert_eq!(issues[0].severity, ValidationSeverity::Error);
+    assert!(issues[0].message.contains("X350"), "{}", issues[0].message);
+    assert!(issues[0].message.contains("X300"), "{}", issues[0].message);
+}
+
+#[test]
+fn test_soft_limits_follow_work_coordinate_system() {
+    let program = "G0 Z5\nG0 X250 Y100\n";
+    let mut wcs = offsets();
+
+    assert_eq!(check_soft_limits(program, &travel(), &wcs).len(), 1);
+    // The same program fits when started in G55, or switched to it
+    wcs.select_system(2).unwrap();
+    assert!(check_soft_limits(program, &travel(), &wcs).is_empty());
+    assert!(check_soft_limits(&format!("G55\n{}", program), &travel(), &offsets()).is_empty());
+
+    let mut disabled = travel();
+    disabled.enabled = false;
+    assert!(check_soft_limits(program, &disabled, &offsets()).is_empty());
+}
+
+#[test]
+fn test_soft_limits_check_arc_extremes() {
+    // Both ends are inside the travel but the arc bulges out to X302
+    let program = "G55 G0 Z5\nG0 X297 Y100\nG3 X297 Y110 I0 J5 F500\n";
+
+    let issues = check_soft_limits(program, &travel(), &offsets());
+
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line_number, 3);
+    assert!(issues[0].message.contains("X302"), "{}", issues[0].message);
+}
+
+#[test]
+fn test_missing_program_end_is_flagged_and_repaired() {
+    let program = "G21\nM3 S10000\nG0 Z5\nG0 X0 Y0\nG1 Z-1 F200\nG1 X10\nG0 Z5";
+
+    assert_eq!(program_end(program), ProgramEnd::Missing);
+    let issues = check_program_end(program);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].severity, ValidationSeverity::Error);
+    assert_eq!(issues[0].line_number, 7);
+
+    let repaired = append_program_end(program, false).unwrap();
+    assert!(repaired.ends_with("G0 Z5\nM5\nM9\nM30\n"));
+    assert!(check_program_end(&repaired).is_empty());
+    assert_eq!(append_program_end(&repaired, false), None);
+}
+
+#[test]
+fn test_program_ending_mid_cut_only_warns() {
+    let program = "G0 X0 Y0 Z5\nG1 Z-1 F200\nG1 X10\nX20 Y5\n";
+
+    assert_eq!(program_end(program), ProgramEnd::MidOperation);
+    let issues = check_program_end(program);
+    assert_eq!(issues[0].severity, ValidationSeverity::Warning);
+    assert_eq!(append_program_end(program, false), None);
+    assert_eq!(
+        append_program_end(program, true).unwrap(),
+        format!("{}M5\nM9\nM30\n", program)
+    );
+}
+
+#[test]
+fn test_m2_counts_as_program_end() {
+    assert!(check_program_end("G0 X1\nM02 ; done\n").is_empty());
+    assert_eq!(
+        program_end("(comment only, M30 in text)\n"),
+        ProgramEnd::Missing
+    );
+}
+
+#[test]
+fn test_half_arcs_merge_into_full_circle() {
+    let program = "G0 X0 Y0\nG1 Z-1 F300\nG2 X20 Y0 I10 J0\nG2 X0 Y0 I-10 J0\nG0 Z5\n";
+
+    assert_eq!(
+        merge_arcs(program, 0.001),
+        "G0 X0 Y0\nG1 Z-1 F300\nG2 X0 Y0 I10 J0\nG0 Z5\n"
+    );
+}
+
+#[test]
+fn test_quarter_arcs_merge_within_tolerance() {
+    // Four CCW quarters about (10, 0) with rounded centers and a repeated feed
+    let program = "G0 X0 Y0\n\
+G03 X10 Y-10 I10.0004 J0 F500\n\
+G03 X20 Y0 I0 J10.0003 F500\n\
+X10 Y10 I-10 J0\n\
+X0 Y0 I0 J-10\n";
+
+    assert_eq!(
+        merge_arcs(program, 0.001),
+        "G0 X0 Y0\nG03 X0 Y0 I10.0004 J0 F500\n"
+    );
+    // A tighter tolerance rejects the rounded centers but still joins the exact ones
+    assert_eq!(
+        merge_arcs(program, 0.0001),
+        program.replace("X10 Y10 I-10 J0\nX0 Y0 I0 J-10\n", "X0 Y0 I-10 J0\n")
+    );
+}
+
+#[test]
+fn test_arcs_that_do_not_continue_are_kept() {
+    // Opposite direction, different center, a comment and a third half circle
+    let cases = [
+        "G0 X0 Y0\nG2 X20 Y0 I10 J0\nG3 X40 Y0 I10 J0\n",
+        "G0 X0 Y0\nG2 X20 Y0 I10 J0\nG2 X0 Y0 I-9 J0\n",
+        "G0 X0 Y0\nG2 X20 Y0 I10 J0\nG2 X0 Y0 I-10 J0 (finish)\n",
+        "G0 X0 Y0\nG91\nG2 X20 Y0 I10 J0\nG2 X-20 Y0 I-10 J0\n",
+    ];
+    for program in cases {
+        assert_eq!(merge_arcs(program, 0.001), program);
+    }
+
+    let program = "G0 X0 Y0\nG2 X20 Y0 I10 J0\nG2 X0 Y0 I-10 J0\nG2 X20 Y0 I10 J0\n";
+    assert_eq!(
+        merge_arcs(program, 0.001),
+        "G0 X0 Y0\nG2 X0 Y0 I10 J0\nG2 X20 Y0 I10 J0\n"
+    );
+}
+
+#[test]
+fn test_plunge_at_full_feed_is_flagged() {
+    let program = "G90\n\
+                   G0 X0 Y0 Z5\n\
+                   G1 Z-3 F1200\n\
+                   G1 X40 Y0\n\
+                   G0 Z5\n";
+    let issues = check_plunge_rates(program, &PlungeLimits::default());
+
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line_number, 3);
+    assert_eq!(issues[0].severity, ValidationSeverity::Warning);
+    assert!(issues[0].message.contains("1200"));
+    assert!(issues[0].message.contains("600"));
+}
+
+#[test]
+fn test_slow_or_ramped_plunge_passes() {
+    let program = "G90\n\
+                   G0 X0 Y0 Z0\n\
+                   G1 Z-2 F300\n\
+                   G1 X40 F1000\n\
+                   G1 X80 Z-3\n\
+                   G91 G1 X-40 Z-0.5\n";
+    assert!(check_plunge_rates(program, &PlungeLimits::default()).is_empty());
+
+    // An absolute limit catches the slow plunge, but not the shallow ramps
+    let limits = PlungeLimits {
+        max_rate: Some(200.0),
+        ..PlungeLimits::default()
+    };
+    let issues = check_plunge_rates(program, &limits);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line_number, 3);
+}
+
+#[test]
+fn test_repeated_position_is_removed_and_real_move_kept() {
+    let program = "G21 G90\nG0 X0 Y0\nG1 X10 F500\nX10\nX20\nY0.0005 ; same spot\n";
+
+    let issues = check_zero_length_moves(program, 0.001);
+    let lines: Vec<u32> = issues.iter().map(|issue| issue.line_number).collect();
+    assert_eq!(lines, vec![4, 6]);
+    assert_eq!(issues[0].severity, ValidationSeverity::Warning);
+
+    assert_eq!(
+        remove_zero_length_moves(program, 0.001).unwrap(),
+        "G21 G90\nG0 X0 Y0\nG1 X10 F500\nX20\n; same spot\n"
+    );
+}
+
+#[test]
+fn test_zero_length_check_keeps_dwells_and_non_motion_lines() {
+    // Dwells, full-circle arcs, feed changes, mode changes and incremental
+    // moves that go somewhere are all kept
+    let program = "G90 G0 X0 Y0\nG4 P1\nG2 X0 Y0 I5 J0\nG1 X0 F300\nG0 X0 Y0\nM3 S1000\nG91 X5\n";
+    assert!(check_zero_length_moves(program, 0.001).is_empty());
+    assert_eq!(remove_zero_length_moves(program, 0.001), None);
+
+    // An unknown position can't be repeated, and incremental zeros always are
+    assert!(check_zero_length_moves("G0 Z5\nG0 X0\n", 0.001).is_empty());
+    let issues = check_zero_length_moves("G91 G1 X1\nX0 Y0\nX1\n", 0.001);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line_number, 2);
+}
+
+fn commands(src: &str) -> Vec<GcodeCommand> {
+    src.lines().map(GcodeCommand::new).collect()
+}
+
+#[test]
+fn test_extents_span_negative_coordinates() {
+    let extents = compute_extents(&commands(
+        "G21 G90\nG0 X-10 Y-5 Z5\nG1 Z-2 F100\nG1 X20 Y-5\nG91 G1 Y-10\nG90 G0 Z5\n",
+    ));
+
+    assert_eq!((extents.min_x, extents.max_x), (-10.0, 20.0));
+    assert_eq!((extents.min_y, extents.max_y), (-15.0, 0.0));
+    assert_eq!((extents.min_z, extents.max_z), (-2.0, 5.0));
+    assert_eq!(extents.width(), 30.0);
+    assert!((extents.cutting_length - 47.0).abs() < 1e-9);
+    assert!((extents.rapid_length - (150f64.sqrt() + 7.0)).abs() < 1e-9);
+}
+
+#[test]
+fn test_extents_include_the_whole_of_a_full_circle() {
+    let extents = compute_extents(&commands("G0 X10 Y0\nG2 X10 Y0 I-10 J0 F300\n"));
+
+    assert!((extents.min_x + 10.0).abs() < 1e-9);
+    assert!((extents.max_x - 10.0).abs() < 1e-9);
+    assert!((extents.min_y + 10.0).abs() < 1e-9);
+    assert!((extents.max_y - 10.0).abs() < 1e-9);
+    assert!((extents.cutting_length - 20.0 * PI).abs() < 1e-9);
+    assert_eq!(extents.rapid_length, 10.0);
+}
+
+#[test]
+fn test_extents_follow_arc_distance_mode() {
+    // I10 J0 is the offset to (20, 10), or with G90.1 the center (10, 0)
+    let arc = "G0 X10 Y10\nG2 X20 Y0 I10 J0\n";
+
+    let incremental = compute_extents(&commands(arc));
+    assert!((incremental.max_y - 20.0).abs() < 1e-9);
+    let absolute = compute_extents(&commands(&format!("G90.1\n{}", arc)));
+    assert!((absolute.max_y - 10.0).abs() < 1e-9);
+    assert!((absolute.cutting_length - 5.0 * PI).abs() < 1e-9);
+}
+
+#[test]
+fn test_arc_before_arc_distance_mode_is_warned() {
+    let program = "G21 G90\nG0 X10 Y0\nG3 X0 Y10 I-10 J0\nG2 X10 Y0 R10\nG3 X0 Y10 I-10 J0\n";
+
+    let issues = check_arc_center_mode(program);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line_number, 3);
+    assert_eq!(issues[0].severity, ValidationSeverity::Warning);
+
+    // R arcs, or a mode set before the first arc, need no warning
+    assert!(check_arc_center_mode("G0 X10 Y0\nG2 X0 Y10 R10\n").is_empty());
+    assert!(check_arc_center_mode(&format!("G91.1\n{}", program)).is_empty());
+}
+
+#[test]
+fn test_extents_follow_radius_arcs_and_ignore_non_moves() {
+    let extents = compute_extents(&commands("G0 X10 Y0\nG3 X0 Y10 R10\nG53 G0 Z-50\n"));
+
+    assert!((extents.max_x - 10.0).abs() < 1e-9);
+    assert!((extents.max_y - 10.0).abs() < 1e-9);
+    assert_eq!(extents.min_z, 0.0);
+    assert!((extents.cutting_length - 5.0 * PI).abs() < 1e-9);
+
+    assert!(compute_extents(&commands("G21\nM3 S1000\n")).is_empty());
+}
+
+#[test]
+fn test_missing_distance_mode_is_flagged_and_inserted() {
+    let program = "%\n(face)\nG21\nG1 X10 Y10 F500\nG90\nG0 Z5\n";
+
+    let issues = check_distance_mode(program);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line_number, 4);
+    assert_eq!(issues[0].severity, ValidationSeverity::Warning);
+
+    let repaired = insert_distance_mode(program).unwrap();
+    assert_eq!(
+        repaired,
+        "%\n(face)\nG21\nG90\nG1 X10 Y10 F500\nG90\nG0 Z5\n"
+    );
+    assert!(check_distance_mode(&repaired).is_empty());
+    assert_eq!(insert_distance_mode(&repaired), None);
+
+    // CRLF programs keep their line endings
+    assert_eq!(
+        insert_distance_mode("G1 X1 F100\r\nM30\r\n").unwrap(),
+        "G90\r\nG1 X1 F100\r\nM30\r\n"
+    );
+}
+
+#[test]
+fn test_distance_mode_set_before_motion_is_clean() {
+    for program in [
+        "G91 G1 X1 F100\n",
+        "G21 G90\nG0 X0\n",
+        "G21\nM3 S1000\n",
+        "",
+    ] {
+        assert!(check_distance_mode(program).is_empty(), "{program:?}");
+        assert_eq!(insert_distance_mode(program), None);
+    }
+}
+
+#[test]
+fn test_runtime_feed_override_stretches_only_feed_moves() {
+    // 60 mm of rapids at 6000 mm/min and 100 mm of cutting at 1000 mm/min
+    let program = "G21 G90\nG0 X30\nG1 X130 F1000\nG0 X100\n";
+    let estimate = RuntimeEstimate::from_program(program, 6000.0);
+    let full = OverrideState::default();
+    let half_feed = OverrideState {
+        feed_override: 50,
+        ..full
+    };
+
+    assert_eq!(estimate.feed_time(&full), Duration::from_secs(6));
+    assert_eq!(estimate.rapid_time(&full), Duration::from_millis(600));
+    assert_eq!(estimate.feed_time(&half_feed), Duration::from_secs(12));
+    assert_eq!(estimate.rapid_time(&half_feed), Duration::from_millis(600));
+}
+
+#[test]
+fn test_runtime_remaining_time_follows_rapid_override() {
+    let program = "G0 X60\nG1 X160 F1000\nG0 X0\n";
+    let estimate = RuntimeEstimate::from_program(program, 6000.0);
+    let slow_rapids = OverrideState {
+        rapid_override: 25,
+        ..OverrideState::default()
+    };
+
+    assert_eq!(
+        estimate.total_time(&OverrideState::default()),
+        Duration::from_millis(600 + 6000 + 1600)
+    );
+    // Only the final rapid is left after line 2
+    assert_eq!(
+        estimate.remaining_time(2, &slow_rapids),
+        Duration::from_millis(6400)
+    );
+}
+
+#[test]
+fn test_split_by_tool_on_two_tool_changes() {
+    let commands: Vec<GcodeCommand> = [
+        "G21 G90",
+        "T1 M6",
+        "M3 S12000",
+        "G1 X10 F500",
+        "T2",
+        "M6",
+        "G1 Y10",
+        "T3 M6",
+        "G1 X0",
+        "M30",
+    ]
+    .iter()
+    .map(|line| GcodeCommand::new(*line))
+    .collect();
+
+    let segments = split_by_tool(&commands);
+    let summary: Vec<(u16, Vec<&str>)> = segments
+        .iter()
+        .map(|segment| {
+            let lines = segment.commands.iter().map(|c| c.command.as_str());
+            (segment.tool_number, lines.collect())
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (0, vec!["G21 G90"]),
+            (1, vec!["T1 M6", "M3 S12000", "G1 X10 F500", "T2"]),
+            (2, vec!["M6", "G1 Y10"]),
+            (3, vec!["T3 M6", "G1 X0", "M30"]),
+        ]
+    );
+
+    // A program that starts with its tool change has no tool 0 segment
+    let segments = split_by_tool(&commands[1..4]);
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].tool_number, 1);
+    assert!(split_by_tool(&[]).is_empty());
+}
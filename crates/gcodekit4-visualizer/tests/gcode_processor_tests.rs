@@ -0,0 +1,1357 @@
+use gcodekit4_visualizer::gcode::ArcExpander;
+use gcodekit4_visualizer::{
+    ArcNormalizeProcessor, AxisMapProcessor, CommandProcessor, CommentProcessor, DecimalProcessor,
+    DefaultFeedProcessor, EmptyLineRemoverProcessor, ExpressionProcessor,
+    FeedRateOverrideProcessor, GcodeCommand, GcodeState, HeaderFooterProcessor, MirrorProcessor,
+    NRenumberProcessor, ProcessorConfig, ProcessorPipeline, ProcessorRegistry,
+    RapidToFeedProcessor, SafeZProcessor, TrailingZeroProcessor, TransformProcessor,
+    TranslateProcessor, UnitConversionProcessor, WhitespaceProcessor,
+};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+fn program(lines: &[&str]) -> Vec<GcodeCommand> {
+    lines.iter().map(|l| GcodeCommand::new(*l)).collect()
+}
+
+fn lines(commands: &[GcodeCommand]) -> Vec<&str> {
+    commands.iter().map(|c| c.command.as_str()).collect()
+}
+
+fn shop_processor() -> HeaderFooterProcessor {
+    HeaderFooterProcessor::new(
+        vec!["G21".to_string(), "G90".to_string(), "G54".to_string()],
+        vec!["M5".to_string(), "G28".to_string()],
+    )
+}
+
+#[test]
+fn test_header_footer_prepended_once() {
+    let processor = shop_processor();
+    let result = processor.apply(&program(&["G0 X10 Y10", "G1 X20 F500", "M30"]));
+
+    assert_eq!(
+        lines(&result),
+        vec![
+            "G21",
+            "G90",
+            "G54",
+            "G0 X10 Y10",
+            "G1 X20 F500",
+            "M5",
+            "G28",
+            "M30"
+        ]
+    );
+}
+
+#[test]
+fn test_header_footer_skips_existing_header_lines() {
+    let processor = shop_processor();
+    let result = processor.apply(&program(&["G21", "G00 X10", "G1 X20 F500"]));
+
+    assert_eq!(
+        lines(&result),
+        vec!["G90", "G54", "G21", "G00 X10", "G1 X20 F500", "M5", "G28"]
+    );
+    assert_eq!(lines(&result).iter().filter(|l| **l == "G21").count(), 1);
+}
+
+#[test]
+fn test_header_footer_skips_existing_footer_lines() {
+    let processor = shop_processor();
+    let result = processor.apply(&program(&["G0 X1", "M05 (spindle off)", "M2"]));
+
+    assert_eq!(
+        lines(&result),
+        vec![
+            "G21",
+            "G90",
+            "G54",
+            "G0 X1",
+            "M05 (spindle off)",
+            "G28",
+            "M2"
+        ]
+    );
+}
+
+#[test]
+fn test_header_footer_matches_words_within_lines() {
+    let processor = shop_processor();
+    let result = processor.apply(&program(&["G21 G90 G54", "G0 X10", "M30"]));
+
+    assert_eq!(
+        lines(&result),
+        vec!["G21 G90 G54", "G0 X10", "M5", "G28", "M30"]
+    );
+
+    // Only the words the program lacks are kept from a header line
+    let processor = HeaderFooterProcessor::new(vec!["G21 G90 G54".to_string()], Vec::new());
+    let result = processor.apply(&program(&["G90", "G0 X10"]));
+    assert_eq!(lines(&result), vec!["G21 G54", "G90", "G0 X10"]);
+}
+
+#[test]
+fn test_pipeline_applies_header_footer_to_each_program() {
+    let mut pipeline = ProcessorPipeline::new();
+    pipeline
+        .register(Arc::new(WhitespaceProcessor::new()))
+        .set_header_footer(Some(shop_processor()));
+
+    let expected = "G21\nG90\nG54\nG0 X10\nM5\nG28\nM30";
+    assert_eq!(pipeline.process_text("  G0 X10\nM30").unwrap(), expected);
+    assert_eq!(pipeline.process_text("  G0 X10\nM30").unwrap(), expected);
+
+    let (_, report) = pipeline
+        .process_commands_with_report(&program(&["G21 G90", "G0 X10"]), &mut GcodeState::new())
+        .unwrap();
+    assert_eq!(report.counts("header_footer"), Some((2, 5)));
+}
+
+fn evaluate(processor: &ExpressionProcessor, source: &[&str]) -> Result<Vec<String>, String> {
+    let state = GcodeState::new();
+    let mut output = Vec::new();
+    for line in source {
+        for command in processor.process(&GcodeCommand::new(*line), &state)? {
+            output.push(command.command);
+        }
+    }
+    Ok(output)
+}
+
+#[test]
+fn test_expression_substitutes_parameters() {
+    let processor = ExpressionProcessor::new();
+    let result = evaluate(&processor, &["#1=3", "G0 X[#1+1]"]).unwrap();
+
+    assert_eq!(result, vec!["G0 X4"]);
+    assert_eq!(processor.parameter(1), Some(3.0));
+}
+
+#[test]
+fn test_expression_arithmetic_and_comments() {
+    let processor = ExpressionProcessor::new();
+    let result = evaluate(
+        &processor,
+        &[
+            "#1=2.0 #3=1",
+            "#2=[#1*3]",
+            "G1 X[(#1+#2)/4] Y#2 Z[-#1] F500 (depth #1)",
+            "G1 X[1/3] ; third",
+        ],
+    )
+    .unwrap();
+
+    assert_eq!(
+        result,
+        vec!["G1 X2 Y6 Z-2 F500 (depth #1)", "G1 X0.3333 ; third"]
+    );
+}
+
+#[test]
+fn test_expression_errors_report_line_number() {
+    let processor = ExpressionProcessor::new();
+
+    let err = evaluate(&processor, &["G21", "G0 X[#7*2]"]).unwrap_err();
+    assert!(err.contains("Line 2"), "{}", err);
+    assert!(err.contains("#7"), "{}", err);
+
+    let err = evaluate(&processor, &["G0 X[SIN[30]]"]).unwrap_err();
+    assert!(err.contains("Line 3"), "{}", err);
+    assert!(err.contains("unsupported"), "{}", err);
+}
+
+#[test]
+fn test_expression_parameters_do_not_outlive_their_program() {
+    let processor = Arc::new(ExpressionProcessor::new());
+    processor.set_parameter(5, 2.0);
+    let mut pipeline = ProcessorPipeline::new();
+    pipeline.register(processor.clone());
+
+    let output = pipeline.process_text("#1=3\nG0 X[#1+#5]").unwrap();
+    assert_eq!(output, "G0 X5");
+
+    // #1 belonged to the first program, and lines are counted afresh
+    let err = pipeline.process_text("G21\nG0 X[#1+1]").unwrap_err();
+    assert!(err.contains("Line 2"), "{}", err);
+    assert!(err.contains("#1"), "{}", err);
+
+    // Seeded values carry over
+    assert_eq!(pipeline.process_text("G0 X[#5]").unwrap(), "G0 X2");
+}
+
+#[test]
+fn test_pipeline_report_counts_comment_and_arc_expander() {
+    let mut pipeline = ProcessorPipeline::new();
+    pipeline.register(Arc::new(CommentProcessor::new()));
+    pipeline.register(Arc::new(ArcExpander::new()));
+
+    let input = program(&[
+        "(Facing pass)",
+        "G00 X0 Y0",
+        "; rapid to start",
+        "G02 X10 Y0 I5 J0 (arc)",
+        "G01 X20",
+    ]);
+    let mut state = GcodeState::new();
+    let (output, report) = pipeline
+        .process_commands_with_report(&input, &mut state)
+        .unwrap();
+
+    // Comment stripper drops the two comment-only lines
+    assert_eq!(report.counts("comment"), Some((5, 3)));
+    // Arc expander turns the half circle into 25 segments
+    assert_eq!(report.counts("arc_expander"), Some((3, 27)));
+    assert_eq!(output.len(), 27);
+    assert_eq!(
+        report.per_processor,
+        vec![
+            ("comment".to_string(), 5, 3),
+            ("arc_expander".to_string(), 3, 27),
+        ]
+    );
+}
+
+#[test]
+fn test_pipeline_set_enabled_skips_processor_mid_pipeline() {
+    let mut pipeline = ProcessorPipeline::new();
+    pipeline.register(Arc::new(CommentProcessor::new()));
+    pipeline.register(Arc::new(ArcExpander::new()));
+    pipeline.register(Arc::new(TrailingZeroProcessor::new()));
+    let input = program(&["G02 X10.50 Y0 I5.25 J0 (arc)", "G01 X20.0"]);
+
+    assert!(pipeline.set_enabled("arc_expander", false));
+    assert!(!pipeline.set_enabled("no_such_processor", false));
+    assert!(!pipeline.list_processors()[1].2);
+
+    let (output, report) = pipeline
+        .process_commands_with_report(&input, &mut GcodeState::new())
+        .unwrap();
+    // The arc goes through as one line, with both neighbours still applied
+    assert_eq!(lines(&output), vec!["G02 X10.5 Y0 I5.25 J0", "G01 X20"]);
+    assert_eq!(report.counts("arc_expander"), None);
+
+    assert!(pipeline.set_enabled("arc_expander", true));
+    let output = pipeline
+        .process_commands(&input, &mut GcodeState::new())
+        .unwrap();
+    assert!(output.len() > 2);
+}
+
+#[test]
+fn test_pipeline_reorder_changes_processing_order() {
+    let mut pipeline = ProcessorPipeline::new();
+    pipeline.register(Arc::new(FeedRateOverrideProcessor::new(0.4)));
+    pipeline.register(Arc::new(DecimalProcessor::with_precision(0)));
+    pipeline.register(Arc::new(CommentProcessor::new()));
+    let input = program(&["G1 X1 F13 ; feed"]);
+    let run = |pipeline: &ProcessorPipeline| {
+        let output = pipeline
+            .process_commands(&input, &mut GcodeState::new())
+            .unwrap();
+        output[0].command.clone()
+    };
+    let names = |pipeline: &ProcessorPipeline| -> Vec<String> {
+        pipeline
+            .list_processors()
+            .iter()
+            .map(|(name, _, _)| name.to_string())
+            .collect()
+    };
+
+    // Scaling then rounding drops the fraction, rounding first keeps it
+    assert_eq!(run(&pipeline), "G1 X1 F5");
+    assert!(pipeline.swap_processors(0, 1));
+    assert_eq!(run(&pipeline), "G1 X1 F5.2");
+
+    assert!(pipeline.move_processor(2, 0));
+    assert_eq!(
+        names(&pipeline),
+        vec!["comment", "decimal", "feed_override"]
+    );
+    assert!(!pipeline.move_processor(0, 3));
+    assert!(!pipeline.swap_processors(3, 0));
+
+    assert!(pipeline.remove_processor("decimal"));
+    assert!(!pipeline.remove_processor("decimal"));
+    assert_eq!(names(&pipeline), vec!["comment", "feed_override"]);
+    assert_eq!(run(&pipeline), "G1 X1 F5.2");
+}
+
+#[test]
+fn test_pipeline_process_file_preserves_line_numbers() {
+    let mut pipeline = ProcessorPipeline::new();
+    pipeline.register(Arc::new(CommentProcessor::new()));
+    pipeline.register(Arc::new(WhitespaceProcessor::new()));
+    pipeline.register(Arc::new(EmptyLineRemoverProcessor::new()));
+    pipeline.register(Arc::new(TrailingZeroProcessor::new()));
+
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("pipeline_program.nc");
+    let output = pipeline.process_file(&path).unwrap();
+
+    let lines: Vec<(Option<u32>, &str)> = output
+        .iter()
+        .map(|command| (command.line_number, command.command.as_str()))
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            (Some(2), "G21 G90"),
+            (Some(4), "G0 X0 Y0 Z5"),
+            (Some(5), "G1 Z-1 F300"),
+            (Some(6), "G1 X10.5 Y0"),
+            (Some(8), "G1 X10.5 Y10"),
+            (Some(9), "G0 Z5"),
+            (Some(10), "M30"),
+        ]
+    );
+
+    assert!(pipeline
+        .process_file(path.with_extension("missing"))
+        .is_err());
+}
+
+fn expand(processor: &ArcExpander, state: &GcodeState, source: &[&str]) -> Vec<String> {
+    source
+        .iter()
+        .flat_map(|line| processor.process(&GcodeCommand::new(*line), state).unwrap())
+        .map(|command| command.command)
+        .collect()
+}
+
+fn word(line: &str, letter: char) -> f64 {
+    line.split_whitespace()
+        .find_map(|part| part.strip_prefix(letter))
+        .unwrap_or_else(|| panic!("no {} in '{}'", letter, line))
+        .parse()
+        .unwrap()
+}
+
+#[test]
+fn test_arc_expander_expands_consecutive_modal_arcs() {
+    let mut pipeline = ProcessorPipeline::new();
+    pipeline.register(Arc::new(ArcExpander::new()));
+    let input = program(&[
+        "G0 X10 Y0",
+        "G03 X0 Y10 I-10 J0",
+        "X-10 Y0 I0 J-10",
+        "G0 X0",
+    ]);
+    let output = pipeline
+        .process_commands(&input, &mut GcodeState::new())
+        .unwrap();
+    let output = lines(&output);
+
+    // Both quarter circles are expanded, though the first leaves the
+    // pipeline's motion mode at G1
+    let segments = &output[1..output.len() - 1];
+    assert!(segments.len() > 2);
+    assert!(segments.iter().all(|line| line.starts_with("G01 ")));
+    assert!(segments
+        .iter()
+        .all(|line| !line.contains('I') && !line.contains('J')));
+    let end = segments[segments.len() - 1];
+    assert_eq!((word(end, 'X'), word(end, 'Y')), (-10.0, 0.0));
+    assert_eq!(output[output.len() - 1], "G0 X0");
+}
+
+#[test]
+fn test_arc_expander_quarter_circle_ends_at_endpoint() {
+    let processor = ArcExpander::new();
+    let mut state = GcodeState::new();
+    state.feed_rate = 800.0;
+    let output = expand(
+        &processor,
+        &state,
+        &["G0 X10 Y0", "G03 X0 Y10 I-10 J0 ; corner"],
+    );
+
+    assert_eq!(output[0], "G0 X10 Y0");
+    let segments = &output[1..];
+    assert!(segments.len() > 1);
+    assert!(segments.iter().all(|line| line.starts_with("G01 ")));
+    assert_eq!(
+        segments[0],
+        format!(
+            "G01 X{} Y{} F800 ; corner",
+            word(&segments[0], 'X'),
+            word(&segments[0], 'Y')
+        )
+    );
+
+    for line in segments {
+        let radius = word(line, 'X').hypot(word(line, 'Y'));
+        assert!((radius - 10.0).abs() < 1e-3, "{} is off the arc", line);
+    }
+    let last = segments.last().unwrap();
+    assert!(word(last, 'X').abs() < 1e-6);
+    assert!((word(last, 'Y') - 10.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_arc_expander_starts_each_program_from_origin() {
+    let mut pipeline = ProcessorPipeline::new();
+    pipeline.register(Arc::new(ArcExpander::new()));
+
+    // The arc is the first move, so a start point left over from the last
+    // run would put its center elsewhere
+    let program = "G2 X10 Y10 I5 J5 F500";
+    let first = pipeline.process_text(program).unwrap();
+    assert_eq!(pipeline.process_text(program).unwrap(), first);
+
+    let again = pipeline
+        .process_commands(&self::program(&[program]), &mut GcodeState::new())
+        .unwrap();
+    assert_eq!(lines(&again).join("\n"), first);
+}
+
+#[test]
+fn test_arc_expander_chord_tolerance_sets_segment_count() {
+    let count = |tolerance: f64| {
+        let processor = ArcExpander::with_chord_tolerance(tolerance);
+        expand(&processor, &GcodeState::new(), &["G02 X10 Y10 I10 J0"]).len()
+    };
+
+    // A quarter circle of radius 10 needs ceil((PI / 2) / (2 * acos(1 - t / 10))) segments
+    assert_eq!(count(0.1), 6);
+    assert_eq!(count(0.01), 18);
+    assert_eq!(count(100.0), 1);
+}
+
+#[test]
+fn test_arc_expander_incremental_radius_arc() {
+    let processor = ArcExpander::new();
+    let mut state = GcodeState::new();
+    state.distance_mode = 91;
+    let output = expand(&processor, &state, &["G02 X10 Y-10 R10 F300"]);
+
+    let (mut x, mut y) = (0.0, 0.0);
+    for line in &output {
+        x += word(line, 'X');
+        y += word(line, 'Y');
+        // Clockwise about (0, -10), the short way round
+        assert!(
+            (x.hypot(y + 10.0) - 10.0).abs() < 1e-3,
+            "{} is off the arc",
+            line
+        );
+    }
+    assert!((x - 10.0).abs() < 1e-6);
+    assert!((y + 10.0).abs() < 1e-6);
+    assert!(output[0].ends_with(" F300"));
+}
+
+#[test]
+fn test_arc_expander_follows_arc_distance_mode() {
+    let source = ["G0 X10 Y10", "G2 X20 Y0 I10 J0"];
+    let on_circle = |output: &[String], center: (f64, f64)| {
+        output[1..].iter().all(|line| {
+            let radius = (word(line, 'X') - center.0).hypot(word(line, 'Y') - center.1);
+            (radius - 10.0).abs() < 1e-3
+        })
+    };
+
+    // G91.1: I10 J0 is an offset, putting the center at (20, 10)
+    let incremental = expand(&ArcExpander::new(), &GcodeState::new(), &source);
+    assert!(on_circle(&incremental, (20.0, 10.0)));
+
+    // G90.1: the same words are the center itself
+    let mut state = GcodeState::new();
+    state.arc_center_absolute = true;
+    let absolute = expand(&ArcExpander::new(), &state, &source);
+    assert!(on_circle(&absolute, (10.0, 0.0)));
+    assert!(absolute.len() < incremental.len());
+
+    // The mode word on the arc's own line applies to it, and is kept
+    let inline = expand(
+        &ArcExpander::new(),
+        &GcodeState::new(),
+        &["G0 X10 Y10", "G90.1 G2 X20 Y0 I10 J0"],
+    );
+    assert_eq!(inline[1], format!("G90.1 {}", absolute[1]));
+    assert_eq!(inline[2..], absolute[2..]);
+}
+
+fn add_feed(processor: &DefaultFeedProcessor, source: &[&str]) -> Vec<String> {
+    let state = GcodeState::new();
+    source
+        .iter()
+        .flat_map(|line| {
+            processor
+                .process(&GcodeCommand::new(*line), &state)
+                .unwrap()
+        })
+        .map(|command| command.command)
+        .collect()
+}
+
+#[test]
+fn test_default_feed_injected_on_first_feed_move() {
+    let processor = DefaultFeedProcessor::new(500.0);
+    let output = add_feed(
+        &processor,
+        &[
+            "G21 G90",
+            "G0 X0 Y0",
+            "G1 Z-1 ; plunge",
+            "G1 X10",
+            "G2 X20 Y0 I5 J0",
+        ],
+    );
+
+    assert_eq!(
+        output,
+        vec![
+            "G21 G90",
+            "G0 X0 Y0",
+            "G1 Z-1 F500 ; plunge",
+            "G1 X10",
+            "G2 X20 Y0 I5 J0",
+        ]
+    );
+}
+
+#[test]
+fn test_default_feed_injected_in_each_program() {
+    let mut pipeline = ProcessorPipeline::new();
+    pipeline.register(Arc::new(DefaultFeedProcessor::new(500.0)));
+
+    // The feed injected into the first program doesn't carry into the next
+    assert_eq!(pipeline.process_text("G1 X10").unwrap(), "G1 X10 F500");
+    assert_eq!(pipeline.process_text("G1 X10").unwrap(), "G1 X10 F500");
+}
+
+#[test]
+fn test_default_feed_leaves_programs_with_feed_untouched() {
+    let source = ["G0 X0 Y0", "G1 X10 F1200", "G1 Y10"];
+    let processor = DefaultFeedProcessor::new(500.0);
+    assert_eq!(add_feed(&processor, &source), source);
+
+    // A feed set on a line of its own counts too
+    let source = ["F800", "G0 X0", "G1 X10"];
+    processor.reset();
+    assert_eq!(add_feed(&processor, &source), source);
+}
+
+fn convert_rapids(processor: &RapidToFeedProcessor, source: &[&str]) -> Vec<String> {
+    let state = GcodeState::new();
+    source
+        .iter()
+        .flat_map(|line| {
+            processor
+                .process(&GcodeCommand::new(*line), &state)
+                .unwrap()
+        })
+        .map(|command| command.command)
+        .collect()
+}
+
+#[test]
+fn test_rapid_below_safe_z_becomes_feed_move() {
+    let processor = RapidToFeedProcessor::new(2.0, 300.0);
+    let output = convert_rapids(
+        &processor,
+        &["G90 G0 Z5", "G0 X10 Y10", "G0 Z-1", "G1 X20 F800", "G0 Z5"],
+    );
+
+    assert_eq!(
+        output,
+        vec![
+            "G90 G0 Z5",
+            "G0 X10 Y10",
+            "G1 Z-1 F300",
+            "G1 X20 F800",
+            "G0 Z5",
+        ]
+    );
+}
+
+#[test]
+fn test_rapid_to_feed_forgets_z_of_previous_program() {
+    let mut pipeline = ProcessorPipeline::new();
+    pipeline.register(Arc::new(RapidToFeedProcessor::new(2.0, 300.0)));
+
+    assert_eq!(
+        pipeline.process_text("G90 G1 Z-1 F600\nG0 X10").unwrap(),
+        "G90 G1 Z-1 F600\nG1 X10 F300"
+    );
+    // The next program's Z is unknown again, so its rapid is left alone
+    assert_eq!(pipeline.process_text("G0 X10").unwrap(), "G0 X10");
+}
+
+#[test]
+fn test_rapid_to_feed_restores_program_modes() {
+    let processor = RapidToFeedProcessor::new(0.0, 250.0);
+    let output = convert_rapids(
+        &processor,
+        &[
+            "G1 Z-2 F600",
+            "G0 X5 ; traverse in the cut",
+            "G1 X6",
+            "G0Z-0.5",
+            "Z3",
+            "X0 Y0",
+        ],
+    );
+
+    assert_eq!(
+        output,
+        vec![
+            "G1 Z-2 F600",
+            "G1 X5 F250 ; traverse in the cut",
+            "G1 X6 F600",
+            "G1Z-0.5F250",
+            "G0Z3",
+            "X0 Y0",
+        ]
+    );
+}
+
+fn round(processor: &DecimalProcessor, line: &str) -> String {
+    processor
+        .process(&GcodeCommand::new(line), &GcodeState::new())
+        .unwrap()
+        .remove(0)
+        .command
+}
+
+#[test]
+fn test_decimal_rounds_only_axis_and_parameter_words() {
+    let processor = DecimalProcessor::with_precision(5);
+
+    assert_eq!(round(&processor, "G01 X10.123456 N5"), "G01 X10.12346 N5");
+    assert_eq!(
+        round(&processor, "N100 T3 M06 g01 y-2.0000001 f1200.000004"),
+        "N100 T3 M06 g01 y-2 f1200"
+    );
+    assert_eq!(
+        round(&processor, "M03 S1000.1234567 (probe x1.1234567)"),
+        "M03 S1000.1234567 (probe x1.1234567)"
+    );
+    assert_eq!(round(&processor, "G38.2 Z-0.000001"), "G38.2 Z0");
+}
+
+#[test]
+fn test_decimal_preserve_trailing_zeros() {
+    let processor = DecimalProcessor::with_precision(3).with_preserve_trailing_zeros(true);
+
+    assert_eq!(round(&processor, "G1 X10.500 Y2.1234"), "G1 X10.500 Y2.123");
+    assert_eq!(round(&processor, "G1 X1.99999 Y0"), "G1 X2.000 Y0");
+
+    let processor = DecimalProcessor::with_precision(3);
+    assert_eq!(round(&processor, "G1 X10.500 Y2.1234"), "G1 X10.5 Y2.123");
+}
+
+fn override_feed(processor: &FeedRateOverrideProcessor, line: &str) -> String {
+    processor
+        .process(&GcodeCommand::new(line), &GcodeState::new())
+        .unwrap()
+        .remove(0)
+        .command
+}
+
+#[test]
+fn test_feed_override_scales_only_f_words() {
+    let processor = FeedRateOverrideProcessor::new(0.5);
+
+    assert_eq!(override_feed(&processor, "G1 X10 F1500"), "G1 X10 F750");
+    assert_eq!(
+        override_feed(&processor, "G1 X10 F333 ; F1000"),
+        "G1 X10 F166.5 ; F1000"
+    );
+    assert_eq!(override_feed(&processor, "G1 X20 Y5"), "G1 X20 Y5");
+    assert_eq!(override_feed(&processor, "M3 S12000"), "M3 S12000");
+}
+
+#[test]
+fn test_feed_override_clamps() {
+    let config = ProcessorConfig::new()
+        .with_option("scale", "0.5")
+        .with_option("min_feed", "100")
+        .with_option("max_feed", "1000");
+    let processor = FeedRateOverrideProcessor::with_config(config);
+
+    assert_eq!(override_feed(&processor, "G1 X1 F150"), "G1 X1 F100");
+    assert_eq!(override_feed(&processor, "G1 X1 F4000"), "G1 X1 F1000");
+    assert_eq!(override_feed(&processor, "G1 X1 F800"), "G1 X1 F400");
+
+    // Inverse time feeds aren't speeds, so only the scale applies
+    let mut state = GcodeState::new();
+    state.feed_rate_mode = 93;
+    let processor = FeedRateOverrideProcessor::new(2.0).with_max_feed(10.0);
+    let output = processor
+        .process(&GcodeCommand::new("G1 X1 F60"), &state)
+        .unwrap();
+    assert_eq!(output[0].command, "G1 X1 F120");
+}
+
+#[test]
+fn test_feed_override_registered_as_builtin() {
+    let registry = ProcessorRegistry::with_builtins();
+    let processor = registry.create("feed_override").unwrap();
+    assert_eq!(processor.name(), "feed_override");
+    assert!(registry
+        .create_pipeline(&["comment", "feed_override"])
+        .is_ok());
+}
+
+#[test]
+fn test_registry_creates_pipeline_from_recipe() {
+    let registry = ProcessorRegistry::with_builtins();
+    let pipeline = registry
+        .create_recipe("whitespace, comment  trailing_zeros")
+        .unwrap();
+    let names: Vec<&str> = pipeline
+        .list_processors()
+        .into_iter()
+        .map(|(name, _, _)| name)
+        .collect();
+    assert_eq!(names, vec!["whitespace", "comment", "trailing_zeros"]);
+
+    let output = pipeline
+        .process_text("  G1 X1.500 ; cut\n(note)\nG0 Z5.0")
+        .unwrap();
+    assert_eq!(output, "G1 X1.5\nG0 Z5");
+
+    match registry.create_recipe("comment, no_such_step") {
+        Err(err) => assert!(err.contains("no_such_step"), "{}", err),
+        Ok(_) => panic!("unknown processor accepted"),
+    }
+}
+
+fn trim(processor: &TrailingZeroProcessor, line: &str) -> String {
+    processor
+        .process(&GcodeCommand::new(line), &GcodeState::new())
+        .unwrap()
+        .remove(0)
+        .command
+}
+
+#[test]
+fn test_trailing_zeros_trimmed() {
+    let processor = TrailingZeroProcessor::new();
+
+    assert_eq!(
+        trim(&processor, "G1 X10.500 Y2.0 F1500.00"),
+        "G1 X10.5 Y2 F1500"
+    );
+    assert_eq!(trim(&processor, "G0 Y0.0 Z-.500"), "G0 Y0 Z-.5");
+    assert_eq!(trim(&processor, "X.000 Y10"), "X0 Y10");
+}
+
+#[test]
+fn test_trailing_zeros_leaves_g_m_words_and_comments() {
+    let processor = TrailingZeroProcessor::new();
+
+    assert_eq!(trim(&processor, "G01 X1.10 (X2.000)"), "G01 X1.1 (X2.000)");
+    assert_eq!(trim(&processor, "M03 S1000.0 ; S2.50"), "M03 S1000 ; S2.50");
+    assert_eq!(trim(&processor, "N10 G38.2 Z-5.000"), "N10 G38.2 Z-5");
+}
+
+#[test]
+fn test_trailing_zeros_min_precision() {
+    let processor = TrailingZeroProcessor::with_min_precision(1);
+
+    assert_eq!(trim(&processor, "G0 Y0.0"), "G0 Y0.0");
+    assert_eq!(trim(&processor, "X10.500 Y2.000 Z3"), "X10.5 Y2.0 Z3");
+}
+
+fn transform(processor: &TransformProcessor, source: &[&str]) -> Vec<String> {
+    let state = GcodeState::new();
+    source
+        .iter()
+        .flat_map(|line| {
+            processor
+                .process(&GcodeCommand::new(*line), &state)
+                .unwrap()
+        })
+        .map(|command| command.command)
+        .collect()
+}
+
+#[test]
+fn test_transform_translates_square() {
+    let processor = TransformProcessor::new().with_translation(10.0, 10.0, 0.0);
+    let output = transform(
+        &processor,
+        &[
+            "G90 G0 X0 Y0",
+            "G1 X20 Y0 F500",
+            "G1 X20 Y20",
+            "G1 X0 Y20 ; back",
+            "G1 X0 Y0",
+            "G0 Z5",
+        ],
+    );
+
+    assert_eq!(
+        output,
+        vec![
+            "G90 G0 X10 Y10",
+            "G1 X30 Y10 F500",
+            "G1 X30 Y30",
+            "G1 X10 Y30 ; back",
+            "G1 X10 Y10",
+            "G0 Z5",
+        ]
+    );
+}
+
+#[test]
+fn test_transform_rotates_90_degrees() {
+    let processor = TransformProcessor::new().with_rotation(90.0, 0.0, 0.0);
+    let output = transform(&processor, &["G90 G0 X10 Y0", "G1 Y5", "G2 X0 Y10 I-10 J0"]);
+
+    // X maps onto Y and Y onto -X; the single-axis move gains its partner
+    assert_eq!(
+        output,
+        vec!["G90 G0 X0 Y10", "G1 X-5 Y10", "G2 X-10 Y0 I0 J-10"]
+    );
+}
+
+#[test]
+fn test_transform_starts_each_program_from_origin() {
+    let mut pipeline = ProcessorPipeline::new();
+    pipeline.register(Arc::new(TransformProcessor::new().with_rotation(90.0, 0.0, 0.0)));
+
+    assert_eq!(pipeline.process_text("G90 G0 X10 Y0").unwrap(), "G90 G0 X0 Y10");
+    // The single-axis move takes its missing X from the new program's origin
+    assert_eq!(pipeline.process_text("G1 Y5").unwrap(), "G1 X-5 Y0");
+}
+
+#[test]
+fn test_transform_incremental_moves_are_not_translated() {
+    let processor = TransformProcessor::new()
+        .with_translation(100.0, 50.0, 0.0)
+        .with_scale(2.0);
+    let output = transform(&processor, &["G91 G1 X5 Y-2 F300", "G90 X1"]);
+
+    assert_eq!(output, vec!["G91 G1 X10 Y-4 F300", "G90 X102"]);
+}
+
+#[test]
+fn test_transform_scales_about_pivot_and_passes_machine_moves() {
+    let processor = TransformProcessor::new()
+        .with_rotation(0.0, 10.0, 10.0)
+        .with_scale(0.5);
+    let output = transform(
+        &processor,
+        &["G0 X20 Y10 Z4", "G3 X10 Y20 R10", "G53 G0 Z-1"],
+    );
+
+    assert_eq!(output, vec!["G0 X15 Y10 Z2", "G3 X10 Y15 R5", "G53 G0 Z-1"]);
+}
+
+#[test]
+fn test_transform_rejects_rotating_arcs_outside_xy() {
+    let processor = TransformProcessor::new().with_rotation(45.0, 0.0, 0.0);
+    let state = GcodeState::new();
+
+    assert!(processor.process(&GcodeCommand::new("G18"), &state).is_ok());
+    assert!(processor
+        .process(&GcodeCommand::new("G2 X10 Z0 I5 K0"), &state)
+        .is_err());
+}
+
+#[test]
+fn test_translate_moves_square_corners() {
+    let mut pipeline = ProcessorPipeline::new();
+    pipeline.register(Arc::new(TranslateProcessor::new(5.0, -2.5, 1.0)));
+    let input = program(&[
+        "G21 G90",
+        "G0 X0 Y0 Z5",
+        "G1 Z-1 F300",
+        "G1 X10 Y0",
+        "G1 X10 Y10",
+        "G1 X0 Y10",
+        "G1 X0 Y0",
+        "G2 X10 Y0 I5 J0 ; arc center stays relative",
+        "G91 G1 X2 Y2",
+        "G1 Z1",
+        "G90 G0 Z5",
+        "G53 G0 Z0",
+        "M5",
+    ]);
+
+    let output = pipeline
+        .process_commands(&input, &mut GcodeState::new())
+        .unwrap();
+    assert_eq!(
+        lines(&output),
+        vec![
+            "G21 G90",
+            "G0 X5 Y-2.5 Z6",
+            "G1 Z0 F300",
+            "G1 X15 Y-2.5",
+            "G1 X15 Y7.5",
+            "G1 X5 Y7.5",
+            "G1 X5 Y-2.5",
+            "G2 X15 Y-2.5 I5 J0 ; arc center stays relative",
+            "G91 G1 X2 Y2",
+            "G1 Z1",
+            "G90 G0 Z6",
+            "G53 G0 Z0",
+            "M5",
+        ]
+    );
+}
+
+#[test]
+fn test_translate_registered_as_builtin() {
+    let registry = ProcessorRegistry::with_builtins();
+    let processor = registry.create("translate").unwrap();
+    assert_eq!(processor.name(), "translate");
+
+    let config = ProcessorConfig::new().with_option("dx", "1.5");
+    assert_eq!(
+        TranslateProcessor::with_config(config).offset(),
+        [1.5, 0.0, 0.0]
+    );
+}
+
+#[test]
+fn test_mirror_x_reverses_arc() {
+    let mut pipeline = ProcessorPipeline::new();
+    pipeline.register(Arc::new(
+        MirrorProcessor::new(true, false).with_axis_x(50.0),
+    ));
+    let input = program(&[
+        "G21 G90 G17",
+        "G0 X40 Y10 Z5",
+        "G1 Z-1 F300",
+        "G02 X30 Y10 I-5 J0",
+        "X40 Y10 I5 J0 ; modal arc",
+        "G3 X30 Y10 R5",
+        "G91 G1 X-2 Y3",
+        "G90 G53 G0 Z0",
+    ]);
+
+    let mut state = GcodeState::new();
+    let output = pipeline.process_commands(&input, &mut state).unwrap();
+    assert_eq!(
+        lines(&output),
+        vec![
+            "G21 G90 G17",
+            "G0 X60 Y10 Z5",
+            "G1 Z-1 F300",
+            "G03 X70 Y10 I5 J0",
+            "X60 Y10 I-5 J0 ; modal arc",
+            "G2 X70 Y10 R5",
+            "G91 G1 X2 Y3",
+            "G90 G53 G0 Z0",
+        ]
+    );
+    assert_eq!(state.motion_mode, 0);
+}
+
+#[test]
+fn test_mirror_both_axes_keeps_arc_direction() {
+    let config = ProcessorConfig::new()
+        .with_option("mirror_x", "true")
+        .with_option("mirror_y", "true")
+        .with_option("axis_y", "5");
+    let processor = MirrorProcessor::with_config(config);
+
+    let output = processor
+        .process(
+            &GcodeCommand::new("G2 X10 Y0 I5 J2 Z-1"),
+            &GcodeState::new(),
+        )
+        .unwrap();
+    assert_eq!(output[0].command, "G2 X-10 Y10 I-5 J-2 Z-1");
+
+    // In the XZ plane only the X mirror reverses the arc
+    let mut state = GcodeState::new();
+    state.plane_mode = 18;
+    let output = processor
+        .process(&GcodeCommand::new("G2 X10 Z-1 I5 K0"), &state)
+        .unwrap();
+    assert_eq!(output[0].command, "G3 X-10 Z-1 I-5 K0");
+}
+
+#[test]
+fn test_pipeline_tracks_short_motion_words() {
+    let pipeline = ProcessorPipeline::new();
+    let mut state = GcodeState::new();
+    pipeline
+        .process_commands(&program(&["G0 X0", "G3 X1 Y1 R1 ; G1"]), &mut state)
+        .unwrap();
+    assert_eq!(state.motion_mode, 3);
+}
+
+fn map_axes(processor: &AxisMapProcessor, source: &[&str]) -> Vec<String> {
+    let state = GcodeState::new();
+    source
+        .iter()
+        .flat_map(|line| {
+            processor
+                .process(&GcodeCommand::new(*line), &state)
+                .unwrap()
+        })
+        .map(|command| command.command)
+        .collect()
+}
+
+#[test]
+fn test_axis_map_negates_z() {
+    let processor = AxisMapProcessor::new().with_negated('Z');
+    let output = map_axes(
+        &processor,
+        &["G0 Z5", "G1 Z-1.50 F100", "G1 X10 Z+2", "G0 Z0 ; top"],
+    );
+
+    assert_eq!(
+        output,
+        vec!["G0 Z-5", "G1 Z1.50 F100", "G1 X10 Z-2", "G0 Z0 ; top"]
+    );
+}
+
+#[test]
+fn test_axis_map_swaps_x_and_y() {
+    let processor = AxisMapProcessor::new().with_swap('X', 'Y');
+    let output = map_axes(
+        &processor,
+        &["G0 X10 Y20", "G1 X30", "G2 X40 Y10 I5 J-5", "X50 Y0 I5 J0"],
+    );
+
+    // Swapping mirrors the XY plane, so arcs turn the other way
+    assert_eq!(
+        output,
+        vec!["G0 Y10 X20", "G1 Y30", "G3 Y40 X10 J5 I-5", "Y50 X0 J5 I0"]
+    );
+}
+
+#[test]
+fn test_axis_map_forgets_plane_of_previous_program() {
+    let mut pipeline = ProcessorPipeline::new();
+    pipeline.register(Arc::new(AxisMapProcessor::new().with_swap('X', 'Y')));
+
+    assert_eq!(pipeline.process_text("G18 G0 X0").unwrap(), "G18 G0 Y0");
+    // The next program's arc is in the default XY plane, not the last one's XZ
+    assert_eq!(
+        pipeline.process_text("G2 X10 Y0 I5 J0").unwrap(),
+        "G3 Y10 X0 J5 I0"
+    );
+}
+
+#[test]
+fn test_axis_map_moves_axis_to_rotary() {
+    let processor = AxisMapProcessor::new().with_mapping('Y', 'A');
+    let state = GcodeState::new();
+
+    let output = map_axes(&processor, &["G1 X1 Y2 F200"]);
+    assert_eq!(output, vec!["G1 X1 A2 F200"]);
+
+    // Y arcs and lines already using A can't be remapped
+    assert!(processor
+        .process(&GcodeCommand::new("G2 X1 Y1 I1 J0"), &state)
+        .is_err());
+    assert!(processor
+        .process(&GcodeCommand::new("G1 Y1 A90"), &state)
+        .is_err());
+    assert!(processor.description().starts_with("Advanced"));
+}
+
+fn convert_units(processor: UnitConversionProcessor, source: &[&str]) -> Vec<String> {
+    let mut pipeline = ProcessorPipeline::new();
+    pipeline.register(Arc::new(processor));
+    pipeline
+        .process_commands(&program(source), &mut GcodeState::new())
+        .unwrap()
+        .into_iter()
+        .map(|command| command.command)
+        .collect()
+}
+
+#[test]
+fn test_unit_conversion_forgets_units_of_previous_program() {
+    let mut pipeline = ProcessorPipeline::new();
+    pipeline.register(Arc::new(UnitConversionProcessor::new()));
+
+    assert_eq!(pipeline.process_text("G20\nG0 X1").unwrap(), "G21\nG0 X25.4");
+    // A program that never sets units is read in the pipeline's millimeters,
+    // not the inches of the one before
+    assert_eq!(pipeline.process_text("G0 X1").unwrap(), "G0 X1");
+}
+
+#[test]
+fn test_unit_conversion_inch_to_mm_round_trip() {
+    let inch = [
+        "G20 G90",
+        "G0 X1 Y0.5 Z0.1",
+        "G1 Z-0.0625 F10",
+        "G2 X1.5 Y1 I0.25 J0.25 ; arc",
+        "G3 X2 Y1.5 R0.5",
+        "M30",
+    ];
+
+    let mm = convert_units(UnitConversionProcessor::new(), &inch);
+    assert_eq!(
+        mm,
+        vec![
+            "G21 G90",
+            "G0 X25.4 Y12.7 Z2.54",
+            "G1 Z-1.5875 F254",
+            "G2 X38.1 Y25.4 I6.35 J6.35 ; arc",
+            "G3 X50.8 Y38.1 R12.7",
+            "M30",
+        ]
+    );
+
+    let mm: Vec<&str> = mm.iter().map(String::as_str).collect();
+    assert_eq!(
+        convert_units(UnitConversionProcessor::to_inches(), &mm),
+        inch
+    );
+}
+
+#[test]
+fn test_unit_conversion_follows_mid_stream_units_changes() {
+    let output = convert_units(
+        UnitConversionProcessor::new(),
+        &["G0 X10 Y10", "G20", "G1 X1 F20", "G21", "G1 X5 F300"],
+    );
+
+    // No units command means the pipeline's default, millimeters
+    assert_eq!(
+        output,
+        vec!["G0 X10 Y10", "G21", "G1 X25.4 F508", "G21", "G1 X5 F300"]
+    );
+
+    let config = ProcessorConfig::new().with_option("target", "inch");
+    let output = convert_units(
+        UnitConversionProcessor::with_config(config),
+        &["G21 G1 X25.4 A90 F254", "G93 G1 X50.8 F2"],
+    );
+    assert_eq!(output, vec!["G20 G1 X1 A90 F10", "G93 G1 X2 F2"]);
+}
+
+#[test]
+fn test_renumber_start_10_increment_5() {
+    let processor = NRenumberProcessor::new(10, 5);
+    let source = program(&[
+        "G21 G90",
+        "",
+        "; roughing",
+        "N100 G0 X0 Y0",
+        "n7G1 X10 F500 ; cut",
+        "G1 X10 N3 Y10",
+        "M30",
+    ]);
+    let output: Vec<GcodeCommand> = source
+        .iter()
+        .flat_map(|command| processor.process(command, &GcodeState::new()).unwrap())
+        .collect();
+
+    assert_eq!(
+        lines(&output),
+        vec![
+            "N10 G21 G90",
+            "",
+            "; roughing",
+            "N15 G0 X0 Y0",
+            "N20 G1 X10 F500 ; cut",
+            "N25 G1 X10 Y10",
+            "N30 M30",
+        ]
+    );
+
+    processor.reset();
+    let again = processor
+        .process(&GcodeCommand::new("N30 M30"), &GcodeState::new())
+        .unwrap();
+    assert_eq!(again[0].command, "N10 M30");
+}
+
+#[test]
+fn test_renumber_starts_each_program_at_start() {
+    let mut pipeline = ProcessorPipeline::new();
+    pipeline.register(Arc::new(NRenumberProcessor::new(10, 10)));
+
+    let program = "G0 X1\nG1 X2";
+    assert_eq!(pipeline.process_text(program).unwrap(), "N10 G0 X1\nN20 G1 X2");
+    assert_eq!(pipeline.process_text(program).unwrap(), "N10 G0 X1\nN20 G1 X2");
+}
+
+#[test]
+fn test_renumber_overflow_and_blank_lines() {
+    let renumber = |processor: &NRenumberProcessor, source: &[&str]| {
+        let state = GcodeState::new();
+        program(source)
+            .iter()
+            .map(|command| {
+                processor
+                    .process(command, &state)
+                    .map(|out| out[0].command.clone())
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let output = renumber(
+        &NRenumberProcessor::new(99_990, 5),
+        &["G0 Z5", "G0 X0", "G1 X1"],
+    );
+    assert_eq!(output[0].as_deref(), Ok("N99990 G0 Z5"));
+    assert_eq!(output[1].as_deref(), Ok("N99995 G0 X0"));
+    assert!(output[2].is_err());
+
+    let processor = NRenumberProcessor::new(99_990, 5)
+        .with_wrap(true)
+        .with_skip_blank(false);
+    let output = renumber(&processor, &["G0 Z5", "(clear)", "G0 X0"]);
+    assert_eq!(
+        output,
+        vec![
+            Ok("N99990 G0 Z5".to_string()),
+            Ok("N99995 (clear)".to_string()),
+            Ok("N99990 G0 X0".to_string()),
+        ]
+    );
+
+    let registry = ProcessorRegistry::with_builtins();
+    assert_eq!(registry.create("renumber").unwrap().name(), "renumber");
+}
+
+fn with_safe_z(processor: SafeZProcessor, source: &[&str]) -> Vec<String> {
+    let mut pipeline = ProcessorPipeline::new();
+    pipeline.register(Arc::new(processor));
+    pipeline
+        .process_commands(&program(source), &mut GcodeState::new())
+        .unwrap()
+        .into_iter()
+        .map(|command| command.command)
+        .collect()
+}
+
+#[test]
+fn test_safe_z_retracts_before_rapid_after_plunge() {
+    let output = with_safe_z(
+        SafeZProcessor::new(5.0),
+        &[
+            "G90 G0 X0 Y0 Z5",
+            "G1 Z-1 F100",
+            "G1 X10",
+            "G0 X20 Y20",
+            "G1 Z-1",
+            "G0 Z10",
+            "G0 X0 Y0",
+        ],
+    );
+
+    // The retracted rapid at the end is left alone
+    assert_eq!(
+        output,
+        vec![
+            "G90 G0 X0 Y0 Z5",
+            "G1 Z-1 F100",
+            "G1 X10",
+            "G00 Z5",
+            "G0 X20 Y20",
+            "G1 Z-1",
+            "G0 Z10",
+            "G0 X0 Y0",
+        ]
+    );
+}
+
+#[test]
+fn test_safe_z_switches_to_absolute_for_incremental_retract() {
+    let config = ProcessorConfig::new().with_option("safe_z", "2.5");
+    let output = with_safe_z(
+        SafeZProcessor::with_config(config),
+        &["G90 G0 Z1", "G91 G1 Z-2 F100", "G0 X5", "G0 Z10", "G0 X5"],
+    );
+
+    assert_eq!(
+        output,
+        vec![
+            "G90 G0 Z1",
+            "G91 G1 Z-2 F100",
+            "G90 G00 Z2.5",
+            "G91",
+            "G0 X5",
+            "G0 Z10",
+            "G0 X5",
+        ]
+    );
+
+    // Z is unknown until set, so nothing is injected
+    assert_eq!(
+        with_safe_z(SafeZProcessor::default(), &["G0 X1", "G28", "G0 X2"]),
+        vec!["G0 X1", "G28", "G0 X2"]
+    );
+}
+
+#[test]
+fn test_safe_z_forgets_z_of_previous_program() {
+    let mut pipeline = ProcessorPipeline::new();
+    pipeline.register(Arc::new(SafeZProcessor::new(5.0)));
+
+    assert_eq!(
+        pipeline.process_text("G0 Z5\nG1 Z-1 F100\nG0 X10").unwrap(),
+        "G0 Z5\nG1 Z-1 F100\nG00 Z5\nG0 X10"
+    );
+    // The next program's Z is unknown again, so its first rapid is left alone
+    assert_eq!(pipeline.process_text("G0 X10").unwrap(), "G0 X10");
+}
+
+fn normalize(
+    processor: &ArcNormalizeProcessor,
+    state: &GcodeState,
+    source: &[&str],
+) -> Vec<String> {
+    source
+        .iter()
+        .flat_map(|line| processor.process(&GcodeCommand::new(*line), state).unwrap())
+        .map(|command| command.command)
+        .collect()
+}
+
+#[test]
+fn test_arc_normalize_splits_full_circle() {
+    let processor = ArcNormalizeProcessor::new();
+    let output = normalize(
+        &processor,
+        &GcodeState::new(),
+        &["G0 X0 Y0", "G2 X0 Y0 I5 J0 F300 (pocket)"],
+    );
+
+    assert_eq!(
+        output,
+        vec![
+            "G0 X0 Y0",
+            "G2 X10 Y0 I5 J0 F300 (pocket)",
+            "G2 X0 Y0 I-5 J0",
+        ]
+    );
+}
+
+#[test]
+fn test_arc_normalize_starts_each_program_from_origin() {
+    let mut pipeline = ProcessorPipeline::new();
+    pipeline.register(Arc::new(ArcNormalizeProcessor::new()));
+
+    assert_eq!(pipeline.process_text("G0 X10 Y0").unwrap(), "G0 X10 Y0");
+    // Starting from the origin again, the arc is a full circle
+    assert_eq!(
+        pipeline.process_text("G2 X0 Y0 I5 J0").unwrap(),
+        "G2 X10 Y0 I5 J0\nG2 X0 Y0 I-5 J0"
+    );
+}
+
+#[test]
+fn test_arc_normalize_straightens_tiny_arc() {
+    let processor = ArcNormalizeProcessor::new();
+    let mut state = GcodeState::new();
+    let output = normalize(&processor, &state, &["G0 X0 Y0", "G2 X0.002 Y0 I0.001 J0"]);
+    assert_eq!(output, vec!["G0 X0 Y0", "G1 X0.002 Y0"]);
+
+    // The next modal arc needs its G2 back after the G1
+    state.motion_mode = 2;
+    let output = normalize(&processor, &state, &["X10.002 Y0 I5 J0"]);
+    assert_eq!(output, vec!["G2 X10.002 Y0 I5 J0"]);
+}
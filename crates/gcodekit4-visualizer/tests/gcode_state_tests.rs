@@ -0,0 +1,234 @@
+use gcodekit4_visualizer::gcode::tokenize_words;
+use gcodekit4_visualizer::{
+    GcodeCommand, GcodeParser, GcodeState, ParsedWords, ProcessorPipeline, ValidationSeverity,
+};
+
+#[test]
+fn test_modal_conflicts_flags_motion_words() {
+    let issues = GcodeState::check_modal_conflicts("G0 G1 X1", 7);
+
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line_number, 7);
+    assert_eq!(issues[0].severity, ValidationSeverity::Error);
+    assert!(issues[0].message.contains("G0 and G1"));
+}
+
+#[test]
+fn test_modal_conflicts_flags_distance_words() {
+    let issues = GcodeState::check_modal_conflicts("G90 G91", 1);
+
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].severity, ValidationSeverity::Error);
+    assert!(issues[0].message.contains("distance mode"));
+}
+
+#[test]
+fn test_modal_conflicts_allows_different_groups() {
+    assert!(GcodeState::check_modal_conflicts("G90 G1 X1", 1).is_empty());
+    assert!(GcodeState::check_modal_conflicts("G21 G90 G54 G17", 1).is_empty());
+    assert!(GcodeState::check_modal_conflicts("M3 S1000 M8 M7", 1).is_empty());
+    assert!(GcodeState::check_modal_conflicts("G00 G0 X1", 1).is_empty());
+    assert!(GcodeState::check_modal_conflicts("G1 X1 (G0 G2)", 1).is_empty());
+}
+
+#[test]
+fn test_modal_conflicts_reports_each_group() {
+    let issues = GcodeState::check_modal_conflicts("G20 G21 M3 M5", 2);
+
+    assert_eq!(issues.len(), 2);
+}
+
+#[test]
+fn test_tool_change_with_spindle_running_is_flagged() {
+    let program = ["G21 G90", "M3 S12000", "G1 X10 F500", "T2 M6", "M3 S12000"];
+    let issues = GcodeState::check_tool_change_spindle(&program);
+
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line_number, 4);
+    assert_eq!(issues[0].severity, ValidationSeverity::Warning);
+    assert!(issues[0].message.contains("M6"));
+}
+
+#[test]
+fn test_tool_change_after_spindle_stop_is_clean() {
+    let program = ["M3 S12000", "G1 X10 F500", "M5", "T2 M6", "M3 S12000", "M30"];
+    assert!(GcodeState::check_tool_change_spindle(&program).is_empty());
+
+    // No spindle start at all
+    assert!(GcodeState::check_tool_change_spindle(&["T1 M6", "G0 X0"]).is_empty());
+}
+
+#[test]
+fn test_tool_change_on_same_line_as_spindle_stop_is_flagged() {
+    let issues = GcodeState::check_tool_change_spindle(&["M3 S1000", "M5 T2 M6"]);
+
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line_number, 2);
+}
+
+#[test]
+fn test_parse_words_reads_words_without_spaces() {
+    let parser = GcodeParser::new();
+    let words = parser.parse_words("g1x10Y20.5z-1F500 S12000 (rough pass)");
+
+    assert_eq!(
+        words,
+        ParsedWords {
+            x: Some(10.0),
+            y: Some(20.5),
+            z: Some(-1.0),
+            f: Some(500.0),
+            s: Some(12000.0),
+            g_codes: vec![1.0],
+            ..ParsedWords::default()
+        }
+    );
+    // Modal state is left alone
+    assert_eq!(parser.get_state().motion_mode, 0);
+}
+
+#[test]
+fn test_parse_words_keeps_every_g_and_m_code_in_order() {
+    let words = GcodeParser::new().parse_words("G90 G17 G2 X5 Y5 I2.5 J0 M3 M8 G38.2");
+
+    assert_eq!(words.g_codes, vec![90.0, 17.0, 2.0, 38.2]);
+    assert_eq!(words.m_codes, vec![3.0, 8.0]);
+    assert_eq!(
+        (words.i, words.j, words.k, words.r),
+        (Some(2.5), Some(0.0), None, None)
+    );
+}
+
+#[test]
+fn test_parse_words_reads_negative_and_exponent_values() {
+    let words = GcodeParser::new().parse_words("G0X-.5Y1.5e-3Z2E+1 R-1E2");
+
+    assert_eq!(words.x, Some(-0.5));
+    assert_eq!(words.y, Some(0.0015));
+    assert_eq!(words.z, Some(20.0));
+    assert_eq!(words.r, Some(-100.0));
+    assert_eq!(words.g_codes, vec![0.0]);
+}
+
+#[test]
+fn test_tokenize_words_splits_packed_spaced_and_mixed() {
+    let expected = vec![('G', 1.0), ('X', 10.5), ('Y', -3.0), ('F', 200.0)];
+
+    assert_eq!(tokenize_words("G1X10.5Y-3F200"), expected);
+    assert_eq!(tokenize_words("G1 X10.5 Y-3 F200"), expected);
+    assert_eq!(tokenize_words("g1X10.5 Y-3F200 ; done"), expected);
+}
+
+#[test]
+fn test_parser_tracks_modes_from_packed_words() {
+    let mut parser = GcodeParser::new();
+    parser.parse("G21G91G0X10Y5").unwrap();
+    parser.parse("G2X1Y1I1J0F300S12000T2").unwrap();
+
+    let state = parser.get_state();
+    assert_eq!(state.units_mode, 21);
+    assert_eq!(state.distance_mode, 91);
+    assert_eq!(state.motion_mode, 2);
+    assert_eq!(state.feed_rate, 300.0);
+    assert_eq!(state.spindle_speed, 12000.0);
+    assert_eq!(state.tool_number, 2);
+}
+
+#[test]
+fn test_parser_tracks_modes_from_spaced_and_mixed_words() {
+    let mut parser = GcodeParser::new();
+    parser.parse("G90 G0 X10 Y5").unwrap();
+    assert_eq!(parser.get_state().motion_mode, 0);
+
+    parser.parse("G1X20 Y5 F1500.5").unwrap();
+    parser.parse("G55 S800").unwrap();
+
+    let state = parser.get_state();
+    assert_eq!(state.distance_mode, 90);
+    assert_eq!(state.motion_mode, 1);
+    assert_eq!(state.feed_rate, 1500.5);
+    assert_eq!(state.spindle_speed, 800.0);
+    assert_eq!(state.coordinate_system, 55);
+}
+
+#[test]
+fn test_parser_tracks_spindle_and_coolant() {
+    let mut parser = GcodeParser::new();
+    let state = parser.get_state();
+    assert!(!state.spindle_on);
+    assert_eq!(state.spindle_direction, 5);
+    assert_eq!(state.coolant, 9);
+
+    for line in ["G21 G90", "M4 S8000", "M8", "G1 X10 F500"] {
+        parser.parse(line).unwrap();
+    }
+    let state = parser.get_state();
+    assert!(state.spindle_on);
+    assert_eq!(state.spindle_direction, 4);
+    assert_eq!(state.coolant, 8);
+    assert!(state.validate().is_ok());
+
+    parser.parse("M5M9").unwrap();
+    let state = parser.get_state();
+    assert!(!state.spindle_on);
+    assert_eq!(state.spindle_direction, 5);
+    assert_eq!(state.coolant, 9);
+}
+
+#[test]
+fn test_pipeline_tracks_spindle_and_coolant() {
+    let program: Vec<GcodeCommand> = ["M3 S12000", "M7", "G0 X0 Y0", "G1 Z-1 F200"]
+        .into_iter()
+        .map(GcodeCommand::new)
+        .collect();
+    let mut state = GcodeState::new();
+
+    ProcessorPipeline::new()
+        .process_commands(&program, &mut state)
+        .unwrap();
+
+    assert!(state.spindle_on);
+    assert_eq!(state.spindle_direction, 3);
+    assert_eq!(state.coolant, 7);
+}
+
+#[test]
+fn test_spindle_and_coolant_setters_validate() {
+    let mut state = GcodeState::new();
+
+    assert!(state.set_spindle_direction(6).is_err());
+    assert!(state.set_coolant(6).is_err());
+    assert!(!state.spindle_on);
+
+    state.set_spindle_direction(3).unwrap();
+    assert!(state.spindle_on);
+    state.set_coolant(8).unwrap();
+    assert_eq!(state.coolant, 8);
+}
+
+#[test]
+fn test_validate_line_reports_missing_value_at_word_column() {
+    let issues = GcodeParser::validate_line("G1 X");
+
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].col, 3);
+    assert_eq!(issues[0].len, 1);
+    assert!(issues[0].message.contains('X'));
+}
+
+#[test]
+fn test_validate_line_accepts_clean_line() {
+    assert!(GcodeParser::validate_line("G1 X10.5 Y-2 F500 (cut) ; profile").is_empty());
+    assert!(GcodeParser::validate_line("g1x1y2").is_empty());
+    assert!(GcodeParser::validate_line("G1 X[#1*2] #2=5").is_empty());
+    assert!(GcodeParser::validate_line("$H").is_empty());
+}
+
+#[test]
+fn test_validate_line_spans_bad_numbers_and_comments() {
+    let issues = GcodeParser::validate_line("G1 X1.2.3 Y2 (open");
+
+    assert_eq!(issues.len(), 2);
+    assert_eq!((issues[0].col, issues[0].len), (3, 6));
+    assert_eq!((issues[1].col, issues[1].len), (13, 5));
+}
@@ -0,0 +1,606 @@
+use gcodekit4_visualizer::{
+    resume_prelude, CommandState, FileStreamReader, GcodeCommand, GcodeStreamReader, LineEnding,
+    LineFramer, PausableStream, SendAuditLog, SendQueue, SessionDirection, SessionTranscript,
+    StreamProgress, StringStreamReader,
+};
+use std::path::PathBuf;
+
+const PROGRAM: &str = "G21\nG0 X100.125 Y200.5 Z5\nG1 X1\n\nM30\n";
+
+fn drain(reader: &mut dyn GcodeStreamReader) -> Vec<StreamProgress> {
+    let mut positions = vec![reader.position()];
+    while reader.read_line().is_some() {
+        positions.push(reader.position());
+    }
+    positions
+}
+
+fn assert_monotonic(positions: &[StreamProgress]) {
+    for pair in positions.windows(2) {
+        assert!(pair[1].byte_offset > pair[0].byte_offset);
+        assert_eq!(pair[1].line_index, pair[0].line_index + 1);
+        assert!(pair[1].fraction() > pair[0].fraction());
+    }
+}
+
+#[test]
+fn test_string_stream_progress_reaches_eof() {
+    let mut reader = StringStreamReader::new(PROGRAM);
+    let positions = drain(&mut reader);
+
+    assert_eq!(positions.len(), 6);
+    assert_eq!(positions[0].percent(), 0.0);
+    assert_monotonic(&positions);
+
+    let last = positions.last().unwrap();
+    assert_eq!(last.byte_offset, PROGRAM.len() as u64);
+    assert_eq!(last.total_bytes, PROGRAM.len() as u64);
+    assert_eq!(last.line_index, 5);
+    assert_eq!(last.total_lines, 5);
+    assert_eq!(last.percent(), 100.0);
+}
+
+#[test]
+fn test_string_stream_progress_tracks_bytes_not_lines() {
+    let mut reader = StringStreamReader::new(PROGRAM);
+    reader.read_line();
+    reader.read_line();
+
+    // Two of five lines read, but the long second line dominates the bytes
+    let progress = reader.position();
+    assert_eq!(progress.byte_offset, 26);
+    assert!(progress.fraction() > 2.0 / 5.0);
+
+    reader.seek_to_line(1).unwrap();
+    assert_eq!(reader.position().byte_offset, 4);
+}
+
+#[test]
+fn test_file_stream_progress_reaches_eof() {
+    let path = std::env::temp_dir().join(format!(
+        "gcodekit4_stream_progress_{}.nc",
+        std::process::id()
+    ));
+    std::fs::write(&path, PROGRAM).unwrap();
+
+    let mut stream = PausableStream::new(Box::new(FileStreamReader::new(&path).unwrap()));
+    let positions = drain(&mut stream);
+    std::fs::remove_file(&path).ok();
+
+    assert_monotonic(&positions);
+    let last = positions.last().unwrap();
+    assert_eq!(last.byte_offset, PROGRAM.len() as u64);
+    assert_eq!(last.total_lines, 5);
+    assert_eq!(last.percent(), 100.0);
+}
+
+#[test]
+fn test_stream_progress_empty_source_is_complete() {
+    let reader = StringStreamReader::new("");
+
+    assert_eq!(reader.position().total_bytes, 0);
+    assert_eq!(reader.position().percent(), 100.0);
+}
+
+fn assert_counters_reach_eof(reader: &mut dyn GcodeStreamReader, source: &str) {
+    let total = reader.total_bytes().unwrap();
+    assert_eq!(total, source.len() as u64);
+    assert_eq!(reader.bytes_read(), 0);
+    assert_eq!(reader.lines_read(), 0);
+
+    let mut counters = vec![(reader.bytes_read(), reader.lines_read())];
+    while reader.read_line().is_some() {
+        counters.push((reader.bytes_read(), reader.lines_read()));
+    }
+
+    for pair in counters.windows(2) {
+        assert!(pair[1].0 > pair[0].0);
+        assert_eq!(pair[1].1, pair[0].1 + 1);
+    }
+    assert_eq!(reader.bytes_read(), total);
+    assert_eq!(reader.lines_read(), source.lines().count());
+    assert_eq!(reader.bytes_read() as f64 / total as f64 * 100.0, 100.0);
+}
+
+#[test]
+fn test_stream_counters_advance_to_eof_on_fixture() {
+    let path = fixture("pipeline_program.nc");
+    let source = std::fs::read_to_string(&path).unwrap();
+
+    let mut file = PausableStream::new(Box::new(FileStreamReader::new(&path).unwrap()));
+    assert_counters_reach_eof(&mut file, &source);
+
+    let mut string = StringStreamReader::new(&source);
+    assert_counters_reach_eof(&mut string, &source);
+
+    string.reset().unwrap();
+    assert_eq!(string.bytes_read(), 0);
+    assert_eq!(string.lines_read(), 0);
+}
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+fn read_all(reader: &mut dyn GcodeStreamReader) -> Vec<String> {
+    std::iter::from_fn(|| reader.read_line()).collect()
+}
+
+#[test]
+fn test_gzip_file_stream_matches_plain_file() {
+    let mut plain = FileStreamReader::new(fixture("pipeline_program.nc")).unwrap();
+    let mut gzipped = FileStreamReader::new(fixture("pipeline_program.nc.gz")).unwrap();
+
+    assert_eq!(gzipped.total_lines(), plain.total_lines());
+    assert_eq!(gzipped.total_bytes(), plain.total_bytes());
+    let expected = read_all(&mut plain);
+    assert_eq!(read_all(&mut gzipped), expected);
+    assert_eq!(gzipped.position().percent(), 100.0);
+
+    gzipped.seek_to_line(2).unwrap();
+    assert_eq!(gzipped.read_line().as_ref(), expected.get(2));
+}
+
+#[test]
+fn test_gzip_detected_from_content_not_extension() {
+    let path = std::env::temp_dir().join(format!(
+        "gcodekit4_mislabeled_gzip_{}.nc",
+        std::process::id()
+    ));
+    std::fs::copy(fixture("pipeline_program.nc.gz"), &path).unwrap();
+
+    let mut mislabeled = FileStreamReader::new(&path).unwrap();
+    let lines = read_all(&mut mislabeled);
+    std::fs::remove_file(&path).ok();
+
+    let mut plain = FileStreamReader::new(fixture("pipeline_program.nc")).unwrap();
+    assert_eq!(lines, read_all(&mut plain));
+}
+
+const ENDINGS_PROGRAM: [&str; 5] = [
+    "G21 G90",
+    "G0 X0 Y0 Z5",
+    "G1 Z-1 F100",
+    "G1 X10 Y10 F500",
+    "M30",
+];
+
+fn assert_clean_lines(reader: &mut dyn GcodeStreamReader, ending: LineEnding) {
+    assert_eq!(reader.line_ending(), ending);
+    assert_eq!(reader.total_lines(), Some(ENDINGS_PROGRAM.len()));
+
+    let lines = read_all(reader);
+    assert_eq!(lines, ENDINGS_PROGRAM);
+    for line in &lines {
+        let command = GcodeCommand::new(line);
+        assert!(!command.command.contains(['\r', '\n']));
+    }
+    assert_eq!(reader.bytes_read(), reader.total_bytes().unwrap());
+}
+
+#[test]
+fn test_cr_only_lines_are_split_and_clean() {
+    let path = fixture("cr_only.nc");
+    let source = std::fs::read_to_string(&path).unwrap();
+
+    let mut file = FileStreamReader::new(&path).unwrap();
+    assert_clean_lines(&mut file, LineEnding::Cr);
+    assert_clean_lines(&mut StringStreamReader::new(&source), LineEnding::Cr);
+
+    file.seek_to_line(3).unwrap();
+    assert_eq!(file.read_line().unwrap(), "G1 X10 Y10 F500");
+}
+
+#[test]
+fn test_crlf_lines_are_clean() {
+    let path = fixture("crlf.nc");
+    let source = std::fs::read_to_string(&path).unwrap();
+
+    assert_clean_lines(&mut FileStreamReader::new(&path).unwrap(), LineEnding::CrLf);
+    assert_clean_lines(&mut StringStreamReader::new(&source), LineEnding::CrLf);
+    assert_eq!(LineEnding::detect(&source).as_str(), "\r\n");
+    assert_eq!(LineEnding::detect("G0 X1"), LineEnding::Lf);
+}
+
+const SPARSE_PROGRAM: &str =
+    "\n\n; header\nG21\n\n   \n(setup)\nG0 X1 ; rapid\n\n\nG1 X2 F100\n\t\n(done) ; end\n\n";
+
+#[test]
+fn test_send_queue_skips_blank_and_comment_lines() {
+    let mut queue = SendQueue::new(SPARSE_PROGRAM, false);
+
+    assert_eq!(queue.source_lines(), 14);
+    assert_eq!(queue.sendable_lines(), 3);
+    assert_eq!(queue.skipped_lines(), 11);
+
+    let mut sent = Vec::new();
+    let mut progress = vec![queue.percent()];
+    while let Some(line) = queue.pop_front() {
+        sent.push((line.source_line, line.text));
+        progress.push(queue.percent());
+    }
+
+    assert_eq!(
+        sent,
+        vec![
+            (3, "G21".to_string()),
+            (7, "G0 X1 ; rapid".to_string()),
+            (10, "G1 X2 F100".to_string()),
+        ]
+    );
+    assert!(progress.windows(2).all(|w| w[0] <= w[1]));
+    assert_eq!(*progress.last().unwrap(), 100.0);
+}
+
+#[test]
+fn test_send_queue_keeps_comments_when_requested() {
+    let queue = SendQueue::new(SPARSE_PROGRAM, true);
+
+    assert_eq!(queue.sendable_lines(), 6);
+    assert_eq!(queue.front().unwrap().text, "; header");
+    assert_eq!(queue.front().unwrap().source_line, 2);
+}
+
+#[test]
+fn test_send_queue_clear_and_empty_program() {
+    let mut queue = SendQueue::new("G0 X1\nG0 X2\n", false);
+    assert_eq!(queue.percent(), 0.0);
+    queue.clear();
+    assert!(queue.is_empty());
+
+    let queue = SendQueue::new("\n\n", false);
+    assert_eq!(queue.len(), 0);
+    assert_eq!(queue.percent(), 100.0);
+}
+
+#[test]
+fn test_line_framer_numbers_lines_with_checksums() {
+    let mut framer = LineFramer::new();
+    assert_eq!(framer.reset(), "N0 M110 N0*125");
+
+    let framed: Vec<String> = ["G28", "G1 X10 F600 ; first cut", "M114"]
+        .iter()
+        .map(|line| framer.frame(line))
+        .collect();
+
+    assert_eq!(framed[1], "N2 G1 X10 F600*3");
+    for (index, line) in framed.iter().enumerate() {
+        let (body, checksum) = line.split_once('*').unwrap();
+        assert!(body.starts_with(&format!("N{} ", index + 1)), "{}", line);
+        assert_eq!(checksum.parse::<u8>().unwrap(), LineFramer::checksum(body));
+    }
+    assert_eq!(framer.next_line_number(), 4);
+}
+
+#[test]
+fn test_line_framer_resends_from_requested_line() {
+    let mut framer = LineFramer::new();
+    let framed: Vec<String> = (1..=7)
+        .map(|n| framer.frame(&format!("G1 X{}", n)))
+        .collect();
+
+    assert_eq!(framer.handle_response("ok"), None);
+    assert_eq!(
+        framer.handle_response("Resend: 5"),
+        Some(framed[4..].to_vec())
+    );
+    assert_eq!(
+        framer.handle_response("Resend:5"),
+        Some(framed[4..].to_vec())
+    );
+    assert_eq!(framer.handle_response("rs N7"), Some(framed[6..].to_vec()));
+    // Lines never sent, or dropped from the history, can't be resent
+    assert_eq!(framer.handle_response("Resend: 9"), None);
+
+    let mut short = LineFramer::with_history(2);
+    for n in 1..=4 {
+        short.frame(&format!("G1 X{}", n));
+    }
+    assert_eq!(short.resend_from(2), None);
+    assert_eq!(short.resend_from(3).map(|lines| lines.len()), Some(2));
+}
+
+/// Stream a program through a mock controller that answers each line in order
+fn mock_run(program: &str, reply: impl Fn(&str) -> &'static str) -> SendAuditLog {
+    let mut queue = SendQueue::new(program, false);
+    let mut audit = SendAuditLog::new();
+    let mut in_flight = Vec::new();
+
+    while let Some(line) = queue.pop_front() {
+        audit.record_sent(&line.text, Some(line.source_line));
+        in_flight.push(line.text);
+        // Answer two lines at a time, as if the controller buffered them
+        if in_flight.len() == 2 || queue.is_empty() {
+            for text in in_flight.drain(..) {
+                audit.record_response(reply(&text));
+            }
+        }
+    }
+    audit
+}
+
+#[test]
+fn test_audit_log_records_every_sent_line() {
+    let audit = mock_run(SPARSE_PROGRAM, |_| "ok");
+
+    let sent = SendQueue::new(SPARSE_PROGRAM, false).sendable_lines();
+    assert_eq!(audit.entries().len(), sent);
+    assert_eq!(audit.awaiting_response(), 0);
+    assert_eq!(audit.error_count(), 0);
+    for (i, entry) in audit.entries().iter().enumerate() {
+        assert_eq!(entry.sequence_number, i as u32);
+        assert_eq!(entry.state, CommandState::Ok);
+        assert_eq!(entry.response.as_ref().unwrap().message, "ok");
+        assert!(entry.sent_at.is_some());
+        assert!(entry.completed_at >= entry.sent_at);
+    }
+
+    let metrics = audit.metrics();
+    assert_eq!(metrics.commands_sent as usize, sent);
+}
+
+#[test]
+fn test_audit_log_records_errors_and_exports_csv() {
+    let audit = mock_run("G21\nG1 X1, Y2\nM30\n", |text| {
+        if text.contains(',') {
+            "error:20"
+        } else {
+            "ok"
+        }
+    });
+
+    assert_eq!(audit.error_count(), 1);
+    let error = &audit.entries()[1];
+    assert_eq!(error.state, CommandState::Error);
+    assert_eq!(error.response.as_ref().unwrap().error_code, Some(20));
+    assert_eq!(error.line_number, Some(2));
+
+    let csv = audit.to_csv();
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(rows.len(), 4);
+    assert!(rows[0].starts_with("sequence,line_number,command,state,response"));
+    assert!(rows[1].starts_with("0,1,G21,Ok,ok,"));
+    assert!(rows[2].starts_with("1,2,\"G1 X1, Y2\",Error,error:20,"));
+}
+
+#[test]
+fn test_audit_log_saves_csv_by_extension() {
+    let audit = mock_run("G21\nM30\n", |_| "ok");
+    let path = std::env::temp_dir().join(format!("gcodekit4_audit_{}.csv", std::process::id()));
+
+    audit.save(&path).unwrap();
+    let saved = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(saved, audit.to_csv());
+}
+
+#[test]
+fn test_audit_log_ignores_unsolicited_responses() {
+    let mut audit = SendAuditLog::new();
+    assert!(audit.record_response("ok").is_none());
+
+    audit.record_sent("G0 X1", None);
+    audit.clear();
+    assert!(audit.entries().is_empty());
+    assert!(audit.record_response("ok").is_none());
+}
+
+const JOB: &str = "G21 G90 G17\nG55\nM3 S12000\nM8\nG0 X10 Y10 Z5\nG1 Z-1 F300\nG1 X40\nG1 Y30 F600\nG1 X10\nG0 Z5\nM5\nM9\nM30\n";
+
+/// Stream `stream`, acknowledging lines up to and including `acknowledged`,
+/// then drop the connection with the next two lines still unanswered
+fn run_until_disconnect(stream: &mut PausableStream, acknowledged: usize) -> SendAuditLog {
+    let mut audit = SendAuditLog::new();
+    while let Some(line) = stream.read_line() {
+        let source_line = stream.current_line() - 1;
+        audit.record_sent(line.trim_end(), Some(source_line));
+        if source_line <= acknowledged {
+            audit.record_response("ok");
+        }
+        if source_line == acknowledged + 2 {
+            break;
+        }
+    }
+    audit
+}
+
+#[test]
+fn test_resume_after_disconnect_restores_state_and_continues() {
+    let mut stream = PausableStream::new(Box::new(StringStreamReader::new(JOB)));
+    let audit = run_until_disconnect(&mut stream, 6);
+    assert_eq!(audit.awaiting_response(), 2);
+
+    let next_line = audit.last_acknowledged_line().unwrap() + 1;
+    assert_eq!(next_line, 7);
+    let prelude = stream.prepare_resume(next_line).unwrap();
+    assert_eq!(
+        prelude,
+        vec![
+            "G21 G90 G17 G55",
+            "M3 S12000",
+            "M8",
+            "G0 X40 Y10",
+            "G1 Z-1 F300"
+        ]
+    );
+    assert_eq!(stream.read_line().unwrap(), "G1 Y30 F600");
+    assert_eq!(stream.read_line().unwrap(), "G1 X10");
+}
+
+#[test]
+fn test_resume_file_stream_from_next_line() {
+    let path =
+        std::env::temp_dir().join(format!("gcodekit4_stream_resume_{}.nc", std::process::id()));
+    std::fs::write(&path, JOB).unwrap();
+
+    let mut stream = PausableStream::new(Box::new(FileStreamReader::new(&path).unwrap()));
+    let audit = run_until_disconnect(&mut stream, 4);
+    let prelude = stream.prepare_resume(audit.last_acknowledged_line().unwrap() + 1);
+    let next = stream.read_line();
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(
+        prelude.unwrap(),
+        vec![
+            "G21 G90 G17 G55",
+            "M3 S12000",
+            "M8",
+            "G0 X10 Y10",
+            "G0 Z5",
+            "G0"
+        ]
+    );
+    assert_eq!(next.unwrap().trim_end(), "G1 Z-1 F300");
+    assert_eq!(stream.current_line(), 6);
+}
+
+#[test]
+fn test_resume_prelude_restores_incremental_mode_last() {
+    let prelude = resume_prelude(["G20", "G91", "G0 X1 Y1", "G1 X0.5 F20"]);
+    // Unknown absolute position: only modes and feed come back
+    assert_eq!(prelude, vec!["G20 G90", "F20", "G1", "G91"]);
+
+    assert_eq!(resume_prelude(Vec::<String>::new()), vec!["G90"]);
+    assert_eq!(SendAuditLog::new().last_acknowledged_line(), None);
+}
+
+/// Lines a queue would transmit, in order
+fn transmitted(mut queue: SendQueue) -> Vec<String> {
+    let mut sent = Vec::new();
+    while let Some(line) = queue.next_to_send() {
+        sent.push(line.text);
+        queue.pop_front();
+    }
+    sent
+}
+
+#[test]
+fn test_send_queue_cut_factor_halves_feed_and_speed() {
+    let program = "G21\nM3 S12000\nG1 X10 F800 ; F is per minute\nG1 Y5\nG1 X0 F250.5 S9000\n";
+    let mut queue = SendQueue::new(program, false);
+    queue.set_cut_factor(0.5);
+    assert_eq!(queue.cut_factor(), 0.5);
+
+    assert_eq!(
+        transmitted(queue.clone()),
+        vec![
+            "G21",
+            "M3 S6000",
+            "G1 X10 F400 ; F is per minute",
+            "G1 Y5",
+            "G1 X0 F125.25 S4500",
+        ]
+    );
+    // The program itself is untouched
+    assert_eq!(queue.front().unwrap().text, "G21");
+
+    queue.set_cut_factor(0.0);
+    assert_eq!(queue.cut_factor(), 0.5);
+    queue.set_cut_factor(1.0);
+    let full_speed: Vec<&str> = program.lines().collect();
+    assert_eq!(transmitted(queue), full_speed);
+}
+
+#[test]
+fn test_send_queue_cut_factor_changed_mid_program_scales_remaining_lines() {
+    let mut queue = SendQueue::new("G1 X10 F800\nG1 X20 F800\nM3 S10000\n", false);
+    let first = queue.next_to_send().unwrap();
+    queue.pop_front();
+    assert_eq!(first.text, "G1 X10 F800");
+
+    // The cut factor control is changed to 75% while the program runs
+    queue.set_cut_factor(0.75);
+    assert_eq!(transmitted(queue), vec!["G1 X20 F600", "M3 S7500"]);
+}
+
+/// Stream a program through a mock controller, recording the session
+fn record_session(program: &str) -> (SendAuditLog, SessionTranscript) {
+    let mut queue = SendQueue::new(program, false);
+    let mut audit = SendAuditLog::new();
+    let mut transcript = SessionTranscript::new();
+
+    while let Some(line) = queue.pop_front() {
+        audit.record_sent(&line.text, Some(line.source_line));
+        transcript.record_sent(&line.text, Some(line.source_line));
+        transcript.record_received("<Run|MPos:0.000,0.000,0.000|FS:0,0>");
+        let reply = if line.text.starts_with("G5 ") {
+            "error:20"
+        } else {
+            "ok"
+        };
+        audit.record_response(reply);
+        transcript.record_received(reply);
+    }
+    (audit, transcript)
+}
+
+#[test]
+fn test_session_replay_rebuilds_audit_log_and_program_state() {
+    let program = "G21 G90\nT2 M6\nM3 S12000\nG0 X10 Y5 Z2\nG1 Z-1 F300\n\
+                   G5 X1 ; rejected\nG91 G1 X2.5 Y-1\n";
+    let (audit, transcript) = record_session(program);
+
+    let replay = transcript.replay();
+    let entries = replay.audit_log.entries();
+    assert_eq!(entries.len(), audit.entries().len());
+    for (replayed, live) in entries.iter().zip(audit.entries()) {
+        assert_eq!(replayed.command, live.command);
+        assert_eq!(replayed.line_number, live.line_number);
+        assert_eq!(replayed.state, live.state);
+        assert_eq!(
+            replayed.response.as_ref().map(|r| &r.message),
+            live.response.as_ref().map(|r| &r.message)
+        );
+    }
+    assert_eq!(replay.audit_log.error_count(), 1);
+
+    let state = &replay.program_state;
+    assert_eq!(state.current_line, 7);
+    assert_eq!((state.x, state.y, state.z), (12.5, 4.0, -1.0));
+    assert_eq!(state.tool, Some(2));
+    assert_eq!(state.speed, 12000.0);
+    assert_eq!(state.feed_rate, 300.0);
+}
+
+#[test]
+fn test_session_transcript_round_trips_through_json_file() {
+    let (_, transcript) = record_session("G21\nG0 X1 Y2\nG1 X3 F100\nM30\n");
+    assert_eq!(transcript.events()[0].direction, SessionDirection::Sent);
+    let path = std::env::temp_dir().join(format!("gcodekit4_session_{}.json", std::process::id()));
+
+    transcript.save(&path).unwrap();
+    let loaded = SessionTranscript::load(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(loaded, transcript);
+    let (original, replayed) = (transcript.replay(), loaded.replay());
+    assert_eq!(replayed.program_state, original.program_state);
+    assert_eq!(replayed.audit_log.to_csv(), original.audit_log.to_csv());
+}
+
+#[test]
+fn test_pausable_stream_seeks_into_program_with_modal_state() {
+    let program = "G20 G91\nG54\nM3 S12000\nG1 X1 F40\nM8\nG1 Y1\nM5 M9\nM30\n";
+    let mut stream = PausableStream::new(Box::new(StringStreamReader::new(program)));
+
+    stream.seek_to_line(5).unwrap();
+    assert_eq!(stream.current_line(), 5);
+
+    let state = stream.modal_state_at(5).unwrap();
+    assert_eq!(state.units_mode, 20);
+    assert_eq!(state.distance_mode, 91);
+    assert_eq!(state.motion_mode, 1);
+    assert_eq!(state.feed_rate, 40.0);
+    assert_eq!(state.spindle_speed, 12000.0);
+    assert!(state.spindle_on);
+    assert_eq!(state.coolant, 8);
+
+    // Working out the modal state leaves the stream where it was
+    assert_eq!(stream.current_line(), 5);
+    assert_eq!(stream.read_line().as_deref(), Some("G1 Y1"));
+    assert_eq!(stream.current_line(), 6);
+}
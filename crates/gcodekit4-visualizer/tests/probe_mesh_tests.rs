@@ -0,0 +1,49 @@
+use gcodekit4_visualizer::{HeightPoint, ProbeMesh};
+
+fn tilted_plane(x: f64, y: f64) -> f64 {
+    0.02 * x - 0.01 * y + 0.5
+}
+
+fn tilted_mesh() -> ProbeMesh {
+    let mut mesh = ProbeMesh::new(10.0, 5.0);
+    for j in 0..4 {
+        for i in 0..3 {
+            let (x, y) = (i as f64 * 10.0, j as f64 * 5.0);
+            mesh.add_point(HeightPoint {
+                x,
+                y,
+                z: tilted_plane(x, y),
+            });
+        }
+    }
+    mesh
+}
+
+#[test]
+fn test_probe_mesh_height_at_matches_plane() {
+    let mesh = tilted_mesh();
+    for (x, y) in [(5.0, 2.5), (15.0, 7.5), (5.0, 12.5), (15.0, 12.5)] {
+        let z = mesh.height_at(x, y).unwrap();
+        assert!((z - tilted_plane(x, y)).abs() < 1e-9, "at ({x}, {y})");
+    }
+    let z = mesh.height_at(3.0, 11.0).unwrap();
+    assert!((z - tilted_plane(3.0, 11.0)).abs() < 1e-9);
+    assert_eq!(mesh.height_at(20.0, 15.0), Some(tilted_plane(20.0, 15.0)));
+}
+
+#[test]
+fn test_probe_mesh_height_at_outside_region() {
+    let mesh = tilted_mesh();
+    assert_eq!(mesh.height_at(-1.0, 5.0), None);
+    assert_eq!(mesh.height_at(10.0, 16.0), None);
+
+    let mesh = mesh.with_clamp_to_edges(true);
+    let z = mesh.height_at(25.0, -3.0).unwrap();
+    assert!((z - tilted_plane(20.0, 0.0)).abs() < 1e-9);
+
+    let mut sparse = ProbeMesh::new(1.0, 1.0);
+    for (x, y) in [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)] {
+        sparse.add_point(HeightPoint { x, y, z: 0.0 });
+    }
+    assert_eq!(sparse.height_at(0.5, 0.5), None);
+}
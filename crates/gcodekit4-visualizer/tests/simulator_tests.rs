@@ -0,0 +1,69 @@
+use gcodekit4_visualizer::{Simulator, ValidationSeverity};
+
+#[test]
+fn test_simulator_state_at_line_after_wcs_and_units_switch() {
+    let program = "G21 G90\nG0 X10 Y10\nG55\nG20\nG1 X1 Y2 F20\nG54 G21\n";
+
+    let (pos, state) = Simulator::state_at_line(program, 2);
+    assert_eq!(state.coordinate_system, 54);
+    assert_eq!(state.units_mode, 21);
+    assert_eq!((pos.x, pos.y), (10.0, 10.0));
+
+    let (pos, state) = Simulator::state_at_line(program, 5);
+    assert_eq!(state.coordinate_system, 55);
+    assert_eq!(state.units_mode, 20);
+    assert_eq!(state.feed_rate, 20.0);
+    assert!((pos.x - 25.4).abs() < 1e-4);
+    assert!((pos.y - 50.8).abs() < 1e-4);
+
+    let (_, state) = Simulator::state_at_line(program, 100);
+    assert_eq!(state.coordinate_system, 54);
+    assert_eq!(state.units_mode, 21);
+}
+
+#[test]
+fn test_simulator_state_at_line_incremental() {
+    let program = "G91\n(comment)\nG1 X5\nG1 X5 Z-1\nG92 X0\n";
+    let (pos, state) = Simulator::state_at_line(program, 5);
+    assert_eq!(state.distance_mode, 91);
+    assert_eq!((pos.x, pos.z), (10.0, -1.0));
+}
+
+#[test]
+fn test_simulator_modal_summary_follows_units_switch() {
+    let program = "G21 G90 G54\nG0 X10\nG20\nG91 G1 X1 F20\nG55\n";
+
+    let (_, state) = Simulator::state_at_line(program, 2);
+    assert_eq!(state.modal_summary(), "G0 G21 G90 G54 F0");
+
+    let (_, state) = Simulator::state_at_line(program, 4);
+    assert_eq!(state.modal_summary(), "G1 G20 G91 G54 F20");
+
+    let (_, state) = Simulator::state_at_line(program, 5);
+    assert_eq!(state.coordinate_system, 55);
+}
+
+#[test]
+fn test_simulator_min_z_matches_cut_depth() {
+    let program = "G21 G90\nG0 Z5\nG1 Z-2.5 F100\nG1 X10\nG1 Z-5\nG0 Z5\n";
+    assert_eq!(Simulator::min_z(program), -5.0);
+    assert!(Simulator::check_depth(program, -5.0, 0.1).is_none());
+
+    // Inches and offsets are taken into account
+    let inches = "G20\nG43.1 Z-10\nG1 Z-0.25 F4\n";
+    assert!((Simulator::min_z(inches) + 6.35).abs() < 1e-9);
+    let shifted = "G1 Z-1 F100\nG92 Z0\nG91 G1 Z-1\nG90 G1 Z-2\n";
+    assert_eq!(Simulator::min_z(shifted), -3.0);
+    assert_eq!(Simulator::min_z("G0 X10 Y10\n"), 0.0);
+}
+
+#[test]
+fn test_simulator_check_depth_flags_unexpected_depth() {
+    let program = "G21\nG0 Z5\nG1 Z-5 F100\nG1 X20\nG1 Z-50\nG0 Z5\n";
+    assert_eq!(Simulator::min_z(program), -50.0);
+
+    let issue = Simulator::check_depth(program, -5.0, 0.5).unwrap();
+    assert_eq!(issue.line_number, 5);
+    assert_eq!(issue.severity, ValidationSeverity::Warning);
+    assert!(issue.message.contains("-50.000"));
+}
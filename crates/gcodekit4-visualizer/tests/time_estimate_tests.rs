@@ -0,0 +1,67 @@
+use gcodekit4_visualizer::{GcodeCommand, GcodeState, TimeEstimator};
+
+fn program(lines: &[&str]) -> Vec<GcodeCommand> {
+    lines.iter().map(|l| GcodeCommand::new(*l)).collect()
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-6,
+        "expected {expected}, got {actual}"
+    );
+}
+
+#[test]
+fn test_square_at_known_feed() {
+    // 100 mm square at 600 mm/min is 40 s of cutting, and the 50 mm rapids
+    // there and back at 3000 mm/min take 1 s each
+    let commands = program(&[
+        "G21 G90",
+        "G0 X30 Y40",
+        "G1 X130 F600",
+        "Y140",
+        "X30",
+        "Y40",
+        "G4 P2.5",
+        "G0 X0 Y0",
+        "M30",
+    ]);
+    let estimate = TimeEstimator::new(3000.0).estimate(&commands, &mut GcodeState::new());
+
+    assert_close(estimate.cutting_seconds, 40.0);
+    assert_close(estimate.rapid_seconds, 2.0);
+    assert_close(estimate.dwell_seconds, 2.5);
+    assert_close(estimate.total_seconds, 44.5);
+
+    let seconds: Vec<f64> = estimate.per_command.iter().map(|(_, s)| *s).collect();
+    assert_eq!(
+        seconds,
+        vec![0.0, 1.0, 10.0, 10.0, 10.0, 10.0, 2.5, 1.0, 0.0]
+    );
+    assert_eq!(estimate.per_command[2].0, commands[2].id);
+}
+
+#[test]
+fn test_arcs_incremental_and_inverse_time() {
+    let estimate = TimeEstimator::default().estimate(
+        &program(&[
+            "G90 G0 X10 Y0",
+            // Full circle of radius 10 at 100 mm/min
+            "G2 X10 Y0 I-10 J0 F100",
+            // Quarter circle by radius
+            "G3 X0 Y10 R10",
+            // Incremental 30 mm move
+            "G91 G1 X30",
+            // Inverse time: 1/F minutes whatever the length
+            "G93 G1 X5 F12",
+        ]),
+        &mut GcodeState::new(),
+    );
+
+    let seconds: Vec<f64> = estimate.per_command.iter().map(|(_, s)| *s).collect();
+    let circle = std::f64::consts::TAU * 10.0 / 100.0 * 60.0;
+    assert_close(seconds[1], circle);
+    assert_close(seconds[2], circle / 4.0);
+    assert_close(seconds[3], 18.0);
+    assert_close(seconds[4], 5.0);
+}
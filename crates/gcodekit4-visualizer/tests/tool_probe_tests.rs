@@ -0,0 +1,89 @@
+use gcodekit4_visualizer::{ToolChangeProber, ToolProbeConfig};
+
+const PROGRAM: &str = "G21 G90\n\
+T1 M6\n\
+G0 X10 Y10\n\
+G1 Z-1 F200\n\
+T2 M6\n\
+G0 X20 Y10\n\
+M2\n";
+
+fn config(enabled: bool) -> ToolProbeConfig {
+    ToolProbeConfig {
+        enabled,
+        setter: (-10.0, -10.0),
+        safe_z: -2.0,
+        probe_travel: 60.0,
+        probe_feed: 80.0,
+    }
+}
+
+/// Run the program, answering each probe with the next trigger height
+fn run(prober: &mut ToolChangeProber, heights: &[f64]) -> Vec<String> {
+    let mut sent = Vec::new();
+    let mut probes = 0;
+    prober
+        .run(PROGRAM, |line| {
+            sent.push(line.to_string());
+            if !line.contains("G38.2") {
+                return Ok(vec!["ok".to_string()]);
+            }
+            let z = heights[probes];
+            probes += 1;
+            Ok(vec![
+                format!("[PRB:-10.000,-10.000,{:.3}:1]", z),
+                "ok".to_string(),
+            ])
+        })
+        .unwrap();
+    sent
+}
+
+#[test]
+fn test_tool_change_runs_probe_and_applies_offset_before_next_move() {
+    let mut prober = ToolChangeProber::new(config(true));
+    let sent = run(&mut prober, &[-40.0, -43.5]);
+
+    let second = sent.iter().position(|line| line == "T2 M6").unwrap();
+    assert_eq!(
+        sent[second + 1..second + 8],
+        [
+            "G53 G0 Z-2.000",
+            "G53 G0 X-10.000 Y-10.000",
+            "G91 G38.2 Z-60.000 F80",
+            "G43.1 Z-3.500",
+            "G90",
+            "G53 G0 Z-2.000",
+            "G0 X20 Y10",
+        ]
+    );
+    // The first tool is the reference, so its offset is zero
+    assert!(sent.contains(&"G43.1 Z0.000".to_string()));
+    assert_eq!(sent.iter().filter(|line| line.contains("G38.2")).count(), 2);
+    assert_eq!(prober.offsets().get_total_offset(1), 0.0);
+    assert_eq!(prober.offsets().get_total_offset(2), -3.5);
+}
+
+#[test]
+fn test_tool_change_without_probing_sends_program_unchanged() {
+    let mut prober = ToolChangeProber::new(config(false));
+    let sent = run(&mut prober, &[]);
+
+    assert_eq!(sent, PROGRAM.lines().collect::<Vec<_>>());
+    assert!(prober.offsets().get_offset(2).is_none());
+}
+
+#[test]
+fn test_failed_tool_probe_stops_job() {
+    let mut prober = ToolChangeProber::new(config(true));
+    let err = prober
+        .run(PROGRAM, |line| {
+            if line.contains("G38.2") {
+                Ok(vec!["[PRB:-10.000,-10.000,-62.000:0]".to_string()])
+            } else {
+                Ok(vec!["ok".to_string()])
+            }
+        })
+        .unwrap_err();
+    assert!(err.to_string().contains("tool setter"));
+}
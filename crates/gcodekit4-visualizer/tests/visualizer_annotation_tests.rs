@@ -0,0 +1,74 @@
+use gcodekit4_visualizer::{render_annotations_to_path, render_svg_document, Visualizer2D};
+
+fn layer_element<'a>(svg: &'a str, layer: &str) -> Option<&'a str> {
+    let start = svg.find(&format!(r#"<path class="{}""#, layer))?;
+    let end = svg[start..].find("/>").unwrap() + start;
+    Some(&svg[start..end])
+}
+
+#[test]
+fn test_added_annotation_is_drawn_as_pin() {
+    let mut visualizer = Visualizer2D::new();
+    visualizer.parse_gcode("G0 X0 Y0\nG1 X100 Y0 F500\nG1 X100 Y50\n");
+    assert!(layer_element(&render_svg_document(&visualizer, 800, 600), "annotations").is_none());
+
+    let id = visualizer.add_annotation(100.0, 25.0, "check clamp here");
+    assert_eq!(visualizer.annotations()[0].label, "check clamp here");
+
+    // The pin's point is on the annotated spot, SVG Y being flipped
+    let path = render_annotations_to_path(&visualizer, 800, 600);
+    assert!(path.starts_with("M 100.00 -25.00 L 100.00 -37.00 "));
+    assert_eq!(path.matches('A').count(), 2);
+
+    let svg = render_svg_document(&visualizer, 800, 600);
+    let pins = layer_element(&svg, "annotations").unwrap();
+    assert!(pins.contains(&format!(
+        r#"stroke="{}""#,
+        visualizer.theme().annotation_color
+    )));
+    assert!(svg.find(r#"class="annotations""#) > svg.find(r#"class="g1""#));
+
+    assert!(visualizer.remove_annotation(id));
+    assert!(!visualizer.remove_annotation(id));
+    assert!(render_annotations_to_path(&visualizer, 800, 600).is_empty());
+}
+
+#[test]
+fn test_pick_selects_nearest_annotation_within_tolerance() {
+    let mut visualizer = Visualizer2D::new();
+    let clamp = visualizer.add_annotation(10.0, 10.0, "clamp");
+    let tab = visualizer.add_annotation(12.0, 10.0, "tab");
+
+    assert_eq!(visualizer.annotation_at(10.5, 10.5, 1.0).unwrap().id, clamp);
+    assert_eq!(visualizer.annotation_at(11.8, 9.9, 1.0).unwrap().id, tab);
+    assert!(visualizer.annotation_at(30.0, 30.0, 1.0).is_none());
+
+    assert_eq!(visualizer.select_annotation_at(12.2, 10.1, 0.5), Some(tab));
+    assert_eq!(visualizer.selected_annotation().unwrap().label, "tab");
+    assert_eq!(visualizer.select_annotation_at(50.0, 50.0, 0.5), None);
+    assert!(visualizer.selected_annotation().is_none());
+}
+
+#[test]
+fn test_annotations_persist_per_program() {
+    let dir = std::env::temp_dir().join(format!("gcodekit4_annotations_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let (part, other) = (dir.join("part.nc"), dir.join("other.nc"));
+
+    let mut visualizer = Visualizer2D::new();
+    visualizer.add_annotation(5.0, 7.5, "check clamp here");
+    visualizer.save_annotations(&part).unwrap();
+    assert!(Visualizer2D::annotations_path(&part).exists());
+
+    let mut reloaded = Visualizer2D::new();
+    reloaded.load_annotations(&part).unwrap();
+    assert_eq!(reloaded.annotations(), visualizer.annotations());
+
+    // Another program has its own, here none
+    reloaded.load_annotations(&other).unwrap();
+    assert!(reloaded.annotations().is_empty());
+    reloaded.save_annotations(&part).unwrap();
+    assert!(!Visualizer2D::annotations_path(&part).exists());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,119 @@
+use gcodekit4_visualizer::visualizer::{flatten_arc, ArcPlane, GCodeCommand, Vector3};
+use gcodekit4_visualizer::Visualizer2D;
+
+const EPSILON: f32 = 1e-4;
+
+fn assert_close(actual: Vector3, expected: Vector3) {
+    assert!(
+        (actual.x - expected.x).abs() < EPSILON
+            && (actual.y - expected.y).abs() < EPSILON
+            && (actual.z - expected.z).abs() < EPSILON,
+        "expected {:?}, got {:?}",
+        expected,
+        actual
+    );
+}
+
+#[test]
+fn test_g2_in_g18_interpolates_in_xz() {
+    // G18 G2 X10 Z0 I5 K0 from the origin: a half circle about (5, 0, 0)
+    let start = Vector3::new(0.0, 0.0, 0.0);
+    let end = Vector3::new(10.0, 0.0, 0.0);
+    let center = Vector3::new(5.0, 0.0, 0.0);
+    let points = flatten_arc(start, end, center, true, ArcPlane::XZ, 4);
+
+    assert_eq!(points.len(), 4);
+    for point in &points {
+        assert!(point.y.abs() < EPSILON, "left the XZ plane: {:?}", point);
+        let radius = (point.x - 5.0).hypot(point.z);
+        assert!((radius - 5.0).abs() < EPSILON);
+    }
+    // Clockwise about +Y passes through -Z
+    assert_close(points[1], Vector3::new(5.0, 0.0, -5.0));
+    assert_close(points[3], end);
+}
+
+#[test]
+fn test_helical_g3_in_g19_moves_x_linearly() {
+    // G19 G3 X4 Y0 Z10 J0 K5 from the origin, with X as the helix axis
+    let start = Vector3::new(0.0, 0.0, 0.0);
+    let end = Vector3::new(4.0, 0.0, 10.0);
+    let center = Vector3::new(0.0, 0.0, 5.0);
+    let points = flatten_arc(start, end, center, false, ArcPlane::YZ, 2);
+
+    // Counter-clockwise about +X passes through +Y
+    assert_close(points[0], Vector3::new(2.0, 5.0, 5.0));
+    assert_close(points[1], end);
+}
+
+#[test]
+fn test_g17_flatten_matches_xy_arc() {
+    let start = Vector3::new(10.0, 0.0, 0.0);
+    let end = Vector3::new(0.0, 10.0, 0.0);
+    let points = flatten_arc(
+        start,
+        end,
+        Vector3::new(0.0, 0.0, 0.0),
+        false,
+        ArcPlane::XY,
+        2,
+    );
+
+    let diagonal = 10.0 * std::f32::consts::FRAC_1_SQRT_2;
+    assert_close(points[0], Vector3::new(diagonal, diagonal, 0.0));
+    assert_close(points[1], end);
+}
+
+#[test]
+fn test_visualizer_projects_g18_arc_onto_x_axis() {
+    let mut visualizer = Visualizer2D::new();
+    visualizer.parse_gcode("G0 X0 Y0 Z0\nG18 G2 X10 Z0 I5 K0\n");
+
+    let moves: Vec<_> = visualizer
+        .commands()
+        .iter()
+        .filter_map(|command| match command {
+            GCodeCommand::Move {
+                from,
+                to,
+                rapid: false,
+                ..
+            } => Some((*from, *to)),
+            _ => None,
+        })
+        .collect();
+
+    assert!(!moves.is_empty());
+    assert!(moves.iter().all(|(from, to)| from.y == 0.0 && to.y == 0.0));
+    assert!((moves.last().unwrap().1.x - 10.0).abs() < EPSILON);
+    assert!((visualizer.current_pos.x - 10.0).abs() < EPSILON);
+
+    // The arc dips 5mm below Z0 at its midpoint
+    let z = visualizer.z_at(5.0, 0.0, 0.5).unwrap();
+    assert!((z + 5.0).abs() < 0.1, "z at midpoint was {}", z);
+}
+
+#[test]
+fn test_visualizer_arc_centers_follow_arc_distance_mode() {
+    let center = |program: &str| {
+        let mut visualizer = Visualizer2D::new();
+        visualizer.parse_gcode(program);
+        visualizer
+            .commands()
+            .iter()
+            .find_map(|command| match command {
+                GCodeCommand::Arc { center, .. } => Some((center.x, center.y)),
+                _ => None,
+            })
+            .unwrap()
+    };
+
+    let arc = "G0 X10 Y10\nG2 X20 Y0 I10 J0\n";
+    assert_eq!(center(arc), (20.0, 10.0));
+    assert_eq!(center(&format!("G90.1\n{}", arc)), (10.0, 0.0));
+    assert_eq!(center(&format!("G90.1\nG91.1\n{}", arc)), (20.0, 10.0));
+
+    // Packed words without spaces set the mode too
+    assert_eq!(center("G0X10Y10\nG90.1G2X20Y0I10J0\n"), (10.0, 0.0));
+    assert_eq!(center("G90.1\nG0X10Y10\nG91.1G2X20Y0I10J0\n"), (20.0, 10.0));
+}
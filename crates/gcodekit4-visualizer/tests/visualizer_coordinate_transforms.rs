@@ -196,3 +196,84 @@ fn test_set_default_view_positions_origin() {
     assert!(viz.x_offset != 0.0 || viz.min_x == 0.0);
     assert!(viz.y_offset != 0.0 || viz.min_y == 0.0);
 }
+
+#[test]
+fn test_coords_at_maps_screen_point_to_machine_coords() {
+    let mut viz = Visualizer2D::new();
+    viz.parse_gcode("G0 X0 Y0\nG1 X100 Y0\nG1 X100 Y50");
+    let (min_x, _, min_y, _) = viz.get_bounds();
+
+    viz.zoom_scale = 2.0;
+    viz.x_offset = 30.0;
+    viz.y_offset = -10.0;
+
+    // Screen position of (40, 25) with 20px padding and a 600px tall canvas
+    let screen_x = (40.0 - min_x) * 2.0 + 20.0 + 30.0;
+    let screen_y = 600.0 - ((25.0 - min_y) * 2.0 + 20.0 + 10.0);
+
+    let (x, y) = viz.coords_at(screen_x, screen_y, 600.0);
+    assert!((x - 40.0).abs() < 1e-3, "x = {}", x);
+    assert!((y - 25.0).abs() < 1e-3, "y = {}", y);
+}
+
+#[test]
+fn test_coords_at_round_trips_default_view_origin() {
+    let mut viz = Visualizer2D::new();
+    viz.parse_gcode("G0 X0 Y0\nG1 X10 Y10");
+    viz.set_scale_factor(4.0);
+    viz.set_default_view(800.0, 600.0);
+
+    // Default view places the origin 5px in from the bottom-left corner
+    let (x, y) = viz.coords_at(5.0, 595.0, 600.0);
+    assert!(x.abs() < 1e-3 && y.abs() < 1e-3, "({}, {})", x, y);
+
+    let (x, y) = viz.coords_at(45.0, 555.0, 600.0);
+    assert!((x - 10.0).abs() < 1e-3 && (y - 10.0).abs() < 1e-3, "({}, {})", x, y);
+}
+
+#[test]
+fn test_z_at_interpolates_along_nearest_segment() {
+    let mut viz = Visualizer2D::new();
+    viz.parse_gcode("G0 Z5\nG0 X0 Y0\nG1 X10 Y0 Z-2\nG1 X10 Y10");
+
+    // Ramp from Z5 to Z-2 along X
+    let z = viz.z_at(5.0, 0.2, 1.0).unwrap();
+    assert!((z - 1.5).abs() < 1e-3, "z = {}", z);
+
+    // Flat segment at the final depth
+    assert_eq!(viz.z_at(10.0, 7.0, 1.0), Some(-2.0));
+
+    // Too far from any segment
+    assert_eq!(viz.z_at(50.0, 50.0, 1.0), None);
+
+    // Packed words without spaces carry the same depth
+    let mut packed = Visualizer2D::new();
+    packed.parse_gcode("G0Z5\nG0X0Y0\nG1X10Y0Z-2\nG1X10Y10");
+    assert_eq!(packed.z_at(10.0, 7.0, 1.0), Some(-2.0));
+}
+
+#[test]
+fn test_tool_marker_radius_scales_with_zoom() {
+    let mut viz = Visualizer2D::new();
+    viz.set_tool_diameter(6.0);
+    viz.set_scale_factor(2.0);
+
+    viz.reset_zoom();
+    assert_eq!(viz.tool_marker_screen_radius(), 6.0);
+
+    viz.zoom_scale = 4.0;
+    assert_eq!(viz.tool_marker_screen_radius(), 24.0);
+
+    viz.zoom_in();
+    assert!((viz.tool_marker_screen_radius() - 26.4).abs() < 1e-4);
+}
+
+#[test]
+fn test_tool_marker_radius_has_minimum_size() {
+    let mut viz = Visualizer2D::new();
+    assert_eq!(viz.tool_marker_screen_radius(), 4.0);
+
+    viz.set_tool_diameter(6.0);
+    viz.zoom_scale = 0.1;
+    assert_eq!(viz.tool_marker_screen_radius(), 4.0);
+}
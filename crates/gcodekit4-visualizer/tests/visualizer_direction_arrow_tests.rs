@@ -0,0 +1,72 @@
+use gcodekit4_visualizer::{render_direction_arrows_to_path, Visualizer2D};
+
+const EPSILON: f32 = 1e-4;
+
+fn parsed(gcode: &str) -> Visualizer2D {
+    let mut visualizer = Visualizer2D::new();
+    visualizer.parse_gcode(gcode);
+    visualizer
+}
+
+#[test]
+fn test_rightward_segment_arrows_point_positive_x() {
+    let visualizer = parsed("G0 X0 Y0\nG1 X100 Y0 F500\n");
+
+    let arrows = visualizer.render_direction_arrows(20.0);
+    assert_eq!(arrows.len(), 5);
+    for arrow in &arrows {
+        assert!((arrow.direction.x - 1.0).abs() < EPSILON);
+        assert!(arrow.direction.y.abs() < EPSILON);
+        assert!(arrow.position.y.abs() < EPSILON);
+    }
+    assert!((arrows[0].position.x - 10.0).abs() < EPSILON);
+    assert!((arrows[1].position.x - 30.0).abs() < EPSILON);
+}
+
+#[test]
+fn test_arrow_spacing_adapts_to_zoom() {
+    let mut visualizer = parsed("G0 X0 Y0\nG1 X100 Y0 F500\n");
+    let at_fit = visualizer.render_direction_arrows(20.0).len();
+
+    visualizer.zoom_scale = 2.0;
+    let zoomed_in = visualizer.render_direction_arrows(20.0);
+    assert_eq!(zoomed_in.len(), at_fit * 2);
+    // 20px is 10mm at 2x zoom
+    assert!((zoomed_in[1].position.x - zoomed_in[0].position.x - 10.0).abs() < EPSILON);
+
+    visualizer.zoom_scale = 0.5;
+    assert!(visualizer.render_direction_arrows(20.0).len() < at_fit);
+}
+
+#[test]
+fn test_rapids_and_zero_spacing_get_no_arrows() {
+    let visualizer = parsed("G0 X0 Y0\nG0 X100 Y0\n");
+    assert!(visualizer.render_direction_arrows(20.0).is_empty());
+
+    let visualizer = parsed("G0 X0 Y0\nG1 X100 Y0 F500\n");
+    assert!(visualizer.render_direction_arrows(0.0).is_empty());
+}
+
+#[test]
+fn test_clockwise_arc_arrows_follow_tangent() {
+    // Clockwise half circle over the top, from (0,0) to (20,0) about (10,0)
+    let visualizer = parsed("G0 X0 Y0\nG2 X20 Y0 I10 J0\n");
+
+    let arrows = visualizer.render_direction_arrows(std::f32::consts::PI * 10.0);
+    assert_eq!(arrows.len(), 1);
+    let top = arrows[0];
+    assert!((top.position.x - 10.0).abs() < EPSILON);
+    assert!((top.position.y - 10.0).abs() < EPSILON);
+    assert!((top.direction.x - 1.0).abs() < EPSILON);
+    assert!(top.direction.y.abs() < EPSILON);
+}
+
+#[test]
+fn test_arrow_path_has_chevron_per_arrow() {
+    let visualizer = parsed("G0 X0 Y0\nG1 X100 Y0 F500\n");
+
+    let path = render_direction_arrows_to_path(&visualizer, 20.0, 6.0);
+    assert_eq!(path.matches('M').count(), 5);
+    // First chevron tip is 3mm ahead of the arrow at X10
+    assert!(path.starts_with("M 7.00 -1.80 L 13.00 -0.00 L 7.00 1.80"));
+}
@@ -0,0 +1,108 @@
+use gcodekit4_visualizer::visualizer::GCodeCommand;
+use gcodekit4_visualizer::{
+    render_envelope_to_path, render_svg_document, SoftLimits, Visualizer2D,
+};
+
+fn limits() -> SoftLimits {
+    SoftLimits {
+        x_min: -10.0,
+        x_max: 200.0,
+        y_min: -5.0,
+        y_max: 95.0,
+        ..SoftLimits::new()
+    }
+}
+
+fn visualizer(gcode: &str) -> Visualizer2D {
+    let mut visualizer = Visualizer2D::new();
+    visualizer.parse_gcode(gcode);
+    visualizer.set_work_envelope(&limits());
+    visualizer
+}
+
+fn layer_element<'a>(svg: &'a str, layer: &str) -> Option<&'a str> {
+    let start = svg.find(&format!(r#"<path class="{}""#, layer))?;
+    let end = svg[start..].find("/>").unwrap() + start;
+    Some(&svg[start..end])
+}
+
+#[test]
+fn test_envelope_rectangle_matches_limits() {
+    let visualizer = visualizer("G0 X0 Y0\nG1 X10 Y10\n");
+
+    assert_eq!(
+        render_envelope_to_path(&visualizer, 800, 600),
+        "M -10.00 -95.00 L 200.00 -95.00 L 200.00 5.00 L -10.00 5.00 Z "
+    );
+
+    let mut cleared = visualizer.clone();
+    cleared.clear_work_envelope();
+    assert!(cleared.work_envelope().is_none());
+    assert!(render_envelope_to_path(&cleared, 800, 600).is_empty());
+}
+
+#[test]
+fn test_moves_past_envelope_are_flagged() {
+    let visualizer = visualizer("G0 X0 Y0\nG1 X50 Y50\nG1 X250 Y50\nG1 X50 Y50\nG1 X50 Y0\n");
+
+    let flagged = visualizer.out_of_envelope_commands();
+    assert_eq!(flagged.len(), 2);
+    for index in flagged {
+        match &visualizer.commands()[index] {
+            GCodeCommand::Move { from, to, .. } => assert!(from.x == 250.0 || to.x == 250.0),
+            other => panic!("unexpected command {:?}", other),
+        }
+    }
+    assert_eq!(
+        visualizer.out_of_envelope_svg(),
+        "M 50.00 -50.00 L 250.00 -50.00 M 250.00 -50.00 L 50.00 -50.00 "
+    );
+}
+
+#[test]
+fn test_arc_bulging_past_envelope_is_flagged() {
+    // Both ends are inside, but the top of the arc reaches Y100
+    let visualizer = visualizer("G0 X10 Y90\nG2 X30 Y90 I10 J0\n");
+
+    let flagged = visualizer.out_of_envelope_commands();
+    assert_eq!(flagged.len(), 1);
+    assert!(matches!(
+        visualizer.commands()[flagged[0]],
+        GCodeCommand::Arc { .. }
+    ));
+}
+
+#[test]
+fn test_nothing_flagged_inside_or_with_disabled_limits() {
+    let inside = visualizer("G0 X0 Y0\nG1 X100 Y50\nG2 X120 Y50 I10 J0\n");
+    assert!(inside.out_of_envelope_commands().is_empty());
+
+    let mut disabled = visualizer("G0 X0 Y0\nG1 X250 Y50\n");
+    disabled.set_work_envelope(&SoftLimits {
+        enabled: false,
+        ..limits()
+    });
+    assert!(disabled.out_of_envelope_commands().is_empty());
+    assert!(disabled.out_of_envelope_svg().is_empty());
+}
+
+#[test]
+fn test_svg_document_draws_envelope_and_tints_out_of_bounds() {
+    let visualizer = visualizer("G0 X0 Y0\nG1 X250 Y50\n");
+    let theme = visualizer.theme();
+
+    let svg = render_svg_document(&visualizer, 800, 600);
+    let envelope = layer_element(&svg, "envelope").unwrap();
+    assert!(envelope.contains(&format!(r#"stroke="{}""#, theme.envelope_color)));
+    let out_of_bounds = layer_element(&svg, "out_of_bounds").unwrap();
+    assert!(out_of_bounds.contains(&format!(r#"stroke="{}""#, theme.out_of_bounds_color)));
+    assert!(out_of_bounds.contains("L 250.00 -50.00"));
+    // The tint is drawn over the cut it highlights
+    assert!(svg.find(r#"class="out_of_bounds""#) > svg.find(r#"class="g1""#));
+
+    let mut plain = Visualizer2D::new();
+    plain.parse_gcode("G0 X0 Y0\nG1 X250 Y50\n");
+    let svg = render_svg_document(&plain, 800, 600);
+    assert!(layer_element(&svg, "envelope").is_none());
+    assert!(layer_element(&svg, "out_of_bounds").is_none());
+}
@@ -0,0 +1,63 @@
+use gcodekit4_visualizer::Visualizer2D;
+
+const EPSILON: f32 = 1e-3;
+
+/// A slow 60 mm cut taking 60 s, then ten quick 1 mm cuts taking 0.1 s each
+fn slow_then_fast() -> Visualizer2D {
+    let mut program = String::from("G0 X0 Y0\nG1 X60 F60\n");
+    for x in 61..=70 {
+        program.push_str(&format!("G1 X{} F600\n", x));
+    }
+    let mut visualizer = Visualizer2D::new();
+    visualizer.parse_gcode(&program);
+    visualizer
+}
+
+#[test]
+fn test_playback_reaches_end_at_estimated_time() {
+    let mut visualizer = slow_then_fast();
+    let total = visualizer.estimated_duration();
+    assert!((total - 61.0).abs() < EPSILON);
+
+    let playback = visualizer.set_playback_time(total, 1.0);
+    assert!(playback.finished);
+    assert_eq!(playback.command_index, visualizer.get_command_count() - 1);
+    assert!((playback.position.x - 70.0).abs() < EPSILON);
+    assert_eq!(visualizer.current_pos, playback.position);
+
+    let start = visualizer.set_playback_time(0.0, 1.0);
+    assert!(!start.finished);
+    assert!(start.position.x.abs() < EPSILON);
+}
+
+#[test]
+fn test_playback_half_time_is_halfway_in_time_not_index() {
+    let mut visualizer = slow_then_fast();
+    let half = visualizer.estimated_duration() / 2.0;
+
+    // Halfway by index would be among the quick cuts; by time it's mid slow cut
+    let playback = visualizer.set_playback_time(half, 1.0);
+    assert_eq!(playback.command_index, 1);
+    assert!((playback.fraction - 30.5 / 60.0).abs() < EPSILON);
+    assert!((playback.position.x - 30.5).abs() < EPSILON);
+
+    // Double speed gets there in half the wall-clock time
+    let doubled = visualizer.set_playback_time(half / 2.0, 2.0);
+    assert_eq!(doubled, playback);
+}
+
+#[test]
+fn test_playback_follows_arcs_and_dwells() {
+    let mut visualizer = Visualizer2D::new();
+    visualizer.parse_gcode("G0 X10 Y0\nG3 X-10 Y0 I-10 J0 F600\nG4 P2\n");
+
+    let durations = visualizer.command_durations();
+    let arc_seconds = std::f32::consts::PI * 10.0 / 600.0 * 60.0;
+    assert!((durations[1] - arc_seconds).abs() < EPSILON);
+    assert!((durations[2] - 2.0).abs() < EPSILON);
+
+    // Halfway round the counter-clockwise arc is the top of the circle
+    let playback = visualizer.set_playback_time(durations[0] + arc_seconds / 2.0, 1.0);
+    assert!(playback.position.x.abs() < EPSILON);
+    assert!((playback.position.y - 10.0).abs() < EPSILON);
+}
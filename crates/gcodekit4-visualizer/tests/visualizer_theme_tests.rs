@@ -0,0 +1,53 @@
+use gcodekit4_visualizer::{render_svg_document, Visualizer2D, VisualizerTheme};
+
+fn layer_element<'a>(svg: &'a str, layer: &str) -> &'a str {
+    let start = svg
+        .find(&format!(r#"<path class="{}""#, layer))
+        .unwrap_or_else(|| panic!("no {} layer in {}", layer, svg));
+    let end = svg[start..].find("/>").unwrap() + start;
+    &svg[start..end]
+}
+
+fn visualizer() -> Visualizer2D {
+    let mut visualizer = Visualizer2D::new();
+    visualizer.parse_gcode("G0 X0 Y0\nG1 X10 Y10\nG2 X20 Y0 I5 J-5\n");
+    visualizer
+}
+
+#[test]
+fn test_default_theme_strokes() {
+    let svg = render_svg_document(&visualizer(), 800, 600);
+
+    assert!(layer_element(&svg, "g1").contains(r##"stroke="#FFFF00""##));
+    assert!(layer_element(&svg, "g2").contains(r##"stroke="#00FF00""##));
+    assert!(svg.contains(r##"fill="#34495E""##));
+}
+
+#[test]
+fn test_g1_color_change_reflected_in_stroke() {
+    let mut visualizer = visualizer();
+    visualizer.set_theme(VisualizerTheme {
+        g1_color: "#123456".to_string(),
+        cut_width: 3.0,
+        ..VisualizerTheme::default()
+    });
+
+    let svg = render_svg_document(&visualizer, 800, 600);
+    let g1 = layer_element(&svg, "g1");
+    assert!(g1.contains(r##"stroke="#123456""##));
+    assert!(g1.contains(r#"stroke-width="3""#));
+    // Other layers keep their colors
+    assert!(layer_element(&svg, "g2").contains(r##"stroke="#00FF00""##));
+}
+
+#[test]
+fn test_high_contrast_background_and_hidden_grid() {
+    let mut visualizer = visualizer();
+    visualizer.set_theme(VisualizerTheme::high_contrast());
+    visualizer.set_grid_visible(false);
+
+    let svg = render_svg_document(&visualizer, 800, 600);
+    assert!(svg.contains(r##"fill="#000000""##));
+    assert!(!svg.contains(r#"class="grid""#));
+    assert!(layer_element(&svg, "g1").contains(r##"stroke="#FFFFFF""##));
+}
@@ -0,0 +1,63 @@
+use gcodekit4_visualizer::{ToolInfo, ToolLibrary, Visualizer2D, VisualizerTheme};
+
+const TWO_TOOLS: &str = "T1 M6\nG0 X0 Y0\nG1 X10 Y0 F500\nG1 X10 Y10\nG0 Z5\nT2 M6\nG0 X20 Y0\nG1 X30 Y0\nG2 X40 Y0 I5 J0\n";
+
+fn library() -> ToolLibrary {
+    let mut library = ToolLibrary::new();
+    library.add_tool(ToolInfo::new(1, "6mm end mill", 6.0));
+    library.add_tool(ToolInfo::new(2, "V-bit", 3.0));
+    library
+}
+
+#[test]
+fn test_two_tool_program_yields_two_path_groups() {
+    let mut visualizer = Visualizer2D::new();
+    visualizer.parse_gcode(TWO_TOOLS);
+
+    let rendering = visualizer.render_by_tool(&library());
+    assert_eq!(
+        rendering.paths.keys().copied().collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+    assert_eq!(
+        rendering.paths[&1],
+        "M 0.00 -0.00 L 10.00 -0.00 L 10.00 -10.00 "
+    );
+    assert!(rendering.paths[&2].starts_with("M 20.00 -0.00 L 30.00 -0.00 A 5.00 5.00 "));
+
+    let theme = VisualizerTheme::default();
+    let legend: Vec<_> = rendering
+        .legend
+        .iter()
+        .map(|entry| (entry.tool, entry.name.as_str(), entry.color.as_str()))
+        .collect();
+    assert_eq!(
+        legend,
+        vec![
+            (1, "6mm end mill", theme.tool_color(0)),
+            (2, "V-bit", theme.tool_color(1)),
+        ]
+    );
+}
+
+#[test]
+fn test_moves_before_tool_change_use_tool_zero() {
+    let mut visualizer = Visualizer2D::new();
+    visualizer.parse_gcode("G0 X0 Y0\nG1 X5 Y0\nT3\nG1 X5 Y5\n");
+
+    assert_eq!(visualizer.command_tools(), &[0, 0, 3]);
+    let rendering = visualizer.render_by_tool(&ToolLibrary::new());
+    assert_eq!(
+        rendering.paths.keys().copied().collect::<Vec<_>>(),
+        vec![0, 3]
+    );
+    assert_eq!(rendering.legend[1].name, "T3");
+}
+
+#[test]
+fn test_packed_tool_change_switches_tool() {
+    let mut visualizer = Visualizer2D::new();
+    visualizer.parse_gcode("G0 X0 Y0\nG1 X5 Y0\nM6T2\nG1 X5 Y5\n");
+
+    assert_eq!(visualizer.command_tools(), &[0, 0, 2]);
+}
@@ -0,0 +1,14 @@
+use gcodekit4_visualizer::{WorkCoordinateSystem, WorkOffset};
+
+#[test]
+fn test_work_coordinate_system_reference_as_zero() {
+    let mut wcs = WorkCoordinateSystem::new();
+    wcs.select_system(2).unwrap();
+
+    let cmd = wcs.set_reference_as_zero(WorkOffset::new(-120.5, -80.0, -3.25));
+
+    assert_eq!(cmd, "G10 L2 P2 X-120.500 Y-80.000 Z-3.250");
+    let offset = wcs.current_offset();
+    assert_eq!((offset.x, offset.y, offset.z), (-120.5, -80.0, -3.25));
+    assert_eq!(wcs.get_offset(1).unwrap().x, 0.0);
+}
@@ -351,6 +351,28 @@ pub fn register_callbacks(
         }
     });
 
+    // Ctrl+/ toggles the comment on the cursor line
+    let window_weak = main_window.as_weak();
+    let editor_bridge_comment = editor_bridge.clone();
+    main_window.on_toggle_comment_requested(move || {
+        let (line, _col) = editor_bridge_comment.cursor_position();
+        if editor_bridge_comment.toggle_comment(line..line + 1) {
+            if let Some(window) = window_weak.upgrade() {
+                window.set_can_undo(editor_bridge_comment.can_undo());
+                window.set_can_redo(editor_bridge_comment.can_redo());
+
+                super::super::helpers::update_visible_lines(&window, &editor_bridge_comment);
+
+                let content = editor_bridge_comment.get_text();
+                window.set_gcode_content(slint::SharedString::from(content));
+
+                let (line, col) = editor_bridge_comment.cursor_position();
+                window.set_cursor_line((line + 1) as i32);
+                window.set_cursor_column((col + 1) as i32);
+            }
+        }
+    });
+
     // Set up scroll callback for custom editor
     let window_weak = main_window.as_weak();
     let editor_bridge_scroll = editor_bridge.clone();
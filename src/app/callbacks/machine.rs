@@ -3,11 +3,13 @@ use std::sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}};
 use slint::{ComponentHandle, VecModel};
 use crate::slint_generatedMainWindow::MainWindow;
 use crate::ErrorDialog;
-use gcodekit4::{SerialCommunicator, ConnectionParams, ConnectionDriver, SerialParity};
+use gcodekit4::SerialCommunicator;
+use gcodekit4::connection::{command_dialect, panel_connection_params};
 use gcodekit4::{DeviceConsoleManager as ConsoleManager, DeviceMessageType, ConsoleListener, CapabilityManager, Communicator};
 use crate::app::types::GcodeSendState;
-use crate::app::helpers::{update_device_info_panel, sync_capabilities_to_ui, get_available_ports};
+use crate::app::helpers::{update_device_info_panel, update_position_text, sync_capabilities_to_ui, get_available_ports};
 use gcodekit4_communication::firmware::grbl::error_decoder::format_error;
+use gcodekit4_devicedb::DeviceManager;
 use tracing::warn;
 
 pub fn register_callbacks(
@@ -20,15 +22,19 @@ pub fn register_callbacks(
     capability_manager: Rc<CapabilityManager>,
     gcode_send_state: Arc<Mutex<GcodeSendState>>,
     detected_firmware: Arc<Mutex<Option<gcodekit4::firmware::firmware_detector::FirmwareDetectionResult>>>,
+    device_manager: Arc<DeviceManager>,
 ) {
     // Set up refresh-ports callback
     let ports_model_clone = ports_model.clone();
     let window_weak = main_window.as_weak();
     main_window.on_refresh_ports(move || {
-        if let Ok(ports) = get_available_ports() {
+        if let Ok((ports, port_details)) = get_available_ports() {
             ports_model_clone.set_vec(ports.clone());
             
             if let Some(window) = window_weak.upgrade() {
+                window.set_available_port_details(slint::ModelRc::new(VecModel::from(
+                    port_details,
+                )));
                 let current_selection = window.get_selected_port();
                 // If no port is selected or the placeholder is selected, select the first available port
                 if !ports.is_empty() && (current_selection.is_empty() || current_selection == "No ports available") {
@@ -38,6 +44,25 @@ pub fn register_callbacks(
         }
     });
 
+    // Set up no-reset-on-open callback; the choice is saved on the active device profile
+    let window_weak = main_window.as_weak();
+    let console_manager_clone = console_manager.clone();
+    let device_manager_no_reset = device_manager.clone();
+    main_window.on_machine_no_reset_on_open_toggled(move |no_reset: bool| {
+        if let Err(e) = device_manager_no_reset.set_no_reset_on_open(no_reset) {
+            warn!("Failed to save DTR/RTS setting: {}", e);
+            console_manager_clone.add_message(
+                DeviceMessageType::Error,
+                format!("✗ Failed to save DTR/RTS setting: {}", e),
+            );
+            if let Some(window) = window_weak.upgrade() {
+                window.set_no_reset_on_open(!no_reset);
+                let console_output = console_manager_clone.get_output();
+                window.set_console_output(slint::SharedString::from(console_output));
+            }
+        }
+    });
+
     // Set up connect callback
     let window_weak = main_window.as_weak();
     let communicator_clone = communicator.clone();
@@ -46,8 +71,18 @@ pub fn register_callbacks(
     let capability_manager_clone = capability_manager.clone();
     let gcode_send_state_connect = gcode_send_state.clone();
     let detected_firmware_connect = detected_firmware.clone();
+    let device_manager_connect = device_manager.clone();
     main_window.on_connect(move |port: slint::SharedString, baud: i32| {
-        let port_str = port.to_string();
+        // The active device's connection profile decides how to connect;
+        // the panel's port only fills in for a profile set to `Auto`
+        let active_connection = device_manager_connect.active_connection();
+        let mut params =
+            panel_connection_params(active_connection.as_ref(), &port, baud as u32);
+        // Short reads so the polling loop never holds the port lock for long
+        params.timeout_ms = 50;
+
+        let port_str = params.port.clone();
+        let baud = params.baud_rate;
 
         // Add connection attempt to console
         console_manager_clone.add_message(
@@ -64,21 +99,6 @@ pub fn register_callbacks(
             window.set_console_output(slint::SharedString::from(console_output));
         }
 
-        // Create connection parameters
-        let params = ConnectionParams {
-            driver: ConnectionDriver::Serial,
-            port: port_str.clone(),
-            network_port: 8888,
-            baud_rate: baud as u32,
-            timeout_ms: 50,
-            flow_control: false,
-            data_bits: 8,
-            stop_bits: 1,
-            parity: SerialParity::None,
-            auto_reconnect: true,
-            max_retries: 3,
-        };
-
         // Try to connect
         let mut comm = communicator_clone.lock().unwrap();
         match comm.connect(&params) {
@@ -134,6 +154,8 @@ pub fn register_callbacks(
                 let communicator_poll = communicator_clone.clone();
                 let console_manager_poll = console_manager_clone.clone();
                 let gcode_state_poll = gcode_send_state_connect.clone();
+                let detected_firmware_poll = detected_firmware_connect.clone();
+                let startup_commands = device_manager_connect.sender_config().startup_commands;
 
                 std::thread::spawn(move || {
                     polling_active.store(true, Ordering::Relaxed);
@@ -151,6 +173,16 @@ pub fn register_callbacks(
                     // The UI timer will update Device Info panel automatically
                     std::thread::sleep(std::time::Duration::from_millis(1000));
 
+                    if !startup_commands.is_empty() {
+                        run_startup_block(
+                            &communicator_poll,
+                            &detected_firmware_poll,
+                            &startup_commands,
+                            &console_manager_poll,
+                            &window_weak_poll,
+                        );
+                    }
+
                     // GRBL buffer is 128 bytes, but we use 127 for safety
                     const GRBL_RX_BUFFER_SIZE: usize = 127;
                     let mut response_buffer = String::new();
@@ -184,6 +216,31 @@ pub fn register_callbacks(
                                         
                                         if line.is_empty() { continue; }
 
+                                        // Marlin asks for a corrupted line again by its number
+                                        let resend = {
+                                            let gstate = gcode_state_poll.lock().unwrap();
+                                            gstate.framer.as_ref().and_then(|framer| framer.handle_response(&line))
+                                        };
+                                        if let Some(resend) = resend {
+                                            let mut gstate = gcode_state_poll.lock().unwrap();
+                                            for framed in resend {
+                                                let send_result = {
+                                                    let mut comm = communicator_poll.lock().unwrap();
+                                                    comm.send(format!("{}\n", framed).as_bytes())
+                                                };
+                                                if let Err(e) = send_result {
+                                                    warn!("Failed to resend {}: {}", framed, e);
+                                                    break;
+                                                }
+                                                gstate.record_sent_line(&framed);
+                                            }
+                                            drop(gstate);
+                                            console_manager_poll.add_message(
+                                                DeviceMessageType::Output,
+                                                format!("Controller asked for a resend: {}", line),
+                                            );
+                                        }
+
                                         // Count "ok" and "error" responses for buffer management
                                         let is_ok = line.contains("ok") || line.contains("OK");
                                         let is_error = line.contains("error:");
@@ -195,6 +252,7 @@ pub fn register_callbacks(
                                             }
 
                                             if let Some(sent_cmd) = gstate.sent_lines.pop_front() {
+                                                gstate.audit.record_response(&line);
                                                 let log_msg = format!("{} => {}", sent_cmd, line);
                                                 // Release lock before logging to avoid potential deadlocks (though unlikely here)
                                                 drop(gstate);
@@ -203,7 +261,15 @@ pub fn register_callbacks(
                                                     log_msg
                                                 );
                                             } else {
+                                                // Not a program line, so the reply to a jog
+                                                let held_jog = gstate.jog_queue.response(!is_error);
                                                 drop(gstate);
+                                                if let Some(jog) = held_jog {
+                                                    let mut comm = communicator_poll.lock().unwrap();
+                                                    if let Err(e) = comm.send(format!("{}\n", jog).as_bytes()) {
+                                                        warn!("Failed to send held jog: {}", e);
+                                                    }
+                                                }
                                             }
                                         }
 
@@ -253,6 +319,40 @@ pub fn register_callbacks(
                                             // Parse full status from response
                                             use gcodekit4::firmware::grbl::status_parser::StatusParser;
                                             let full_status = StatusParser::parse_full(&line);
+                                            let held_jog = {
+                                                let mut gstate = gcode_state_poll.lock().unwrap();
+                                                gstate.machine_state = full_status.machine_state.clone();
+                                                full_status
+                                                    .machine_state
+                                                    .as_deref()
+                                                    .and_then(|state| gstate.jog_queue.machine_state(state))
+                                            };
+                                            // A jog held back while others ran goes out once they finish
+                                            if let Some(jog) = held_jog {
+                                                let mut comm = communicator_poll.lock().unwrap();
+                                                if let Err(e) = comm.send(format!("{}\n", jog).as_bytes()) {
+                                                    warn!("Failed to send held jog: {}", e);
+                                                }
+                                            }
+
+                                            // Refresh the remaining time when the overrides change mid-job
+                                            let reported_overrides = gcodekit4::firmware::device_status::DeviceStatus::parse_grbl_status(&line)
+                                                .and_then(|status| status.overrides);
+                                            let estimated_str = reported_overrides.and_then(|ov| {
+                                                let overrides = gcodekit4::OverrideState {
+                                                    feed_override: ov.feed_rate,
+                                                    rapid_override: u8::try_from(ov.rapid).unwrap_or(u8::MAX),
+                                                    spindle_override: ov.spindle_speed,
+                                                };
+                                                let mut gstate = gcode_state_poll.lock().unwrap();
+                                                if gstate.overrides == overrides {
+                                                    return None;
+                                                }
+                                                gstate.overrides = overrides;
+                                                gstate.start_time?;
+                                                let remaining_secs = gstate.estimated_remaining()?.as_secs();
+                                                Some(format!("{:02}:{:02}:{:02}", remaining_secs / 3600, (remaining_secs % 3600) / 60, remaining_secs % 60))
+                                            });
 
                                             let window_handle = window_weak_poll.clone();
                                             let raw_response = line.clone();
@@ -294,6 +394,8 @@ pub fn register_callbacks(
                                                         window.set_work_position_z(mpos.z as f32);
                                                     }
 
+                                                    update_position_text(&window);
+
                                                     // Update machine state
                                                     if let Some(state) = full_status.machine_state {
                                                         window.set_machine_state(slint::SharedString::from(state));
@@ -308,6 +410,10 @@ pub fn register_callbacks(
                                                     if let Some(spindle) = full_status.spindle_speed {
                                                         window.set_spindle_speed(spindle as f32);
                                                     }
+
+                                                    if let Some(estimated) = estimated_str {
+                                                        window.set_job_estimated_time(slint::SharedString::from(estimated));
+                                                    }
                                                 }
                                             })
                                             .ok();
@@ -323,22 +429,30 @@ pub fn register_callbacks(
                                 let mut lines_sent_this_cycle = 0;
 
                                 while !gstate.lines.is_empty() && lines_sent_this_cycle < 10 {
-                                    let line = gstate.lines.front().cloned().unwrap_or_default();
-                                    let trimmed = line.trim();
-
-                                    // Skip empty lines and comments quickly
-                                    if trimmed.is_empty() || trimmed.starts_with(';') {
-                                        gstate.lines.pop_front();
-                                        continue; // Don't count skipped lines
-                                    }
+                                    // Blank and comment-only lines were dropped when the queue was built
+                                    let Some(line) = gstate.lines.next_to_send() else {
+                                        break;
+                                    };
+                                    let trimmed = line.text.as_str();
+
+                                    // Check buffer space before sending. Framed (Marlin) lines go one
+                                    // at a time, so a resend never races lines already in flight
+                                    let has_room = if gstate.framer.is_some() {
+                                        gstate.line_lengths.is_empty()
+                                    } else {
+                                        gstate.pending_bytes + trimmed.len() + 1 <= GRBL_RX_BUFFER_SIZE
+                                    };
+                                    if has_room {
+                                        let outgoing = match gstate.framer.as_mut() {
+                                            Some(framer) => framer.frame(trimmed),
+                                            None => trimmed.to_string(),
+                                        };
+                                        let line_len = outgoing.len() + 1;
 
-                                    // Check buffer space before sending
-                                    let line_len = trimmed.len() + 1;
-                                    if gstate.pending_bytes + line_len <= GRBL_RX_BUFFER_SIZE {
                                         // Acquire lock only for the actual send operation
                                         let send_result = {
                                             let mut comm = communicator_poll.lock().unwrap();
-                                            comm.send(format!("{}\n", trimmed).as_bytes())
+                                            comm.send(format!("{}\n", outgoing).as_bytes())
                                         }; // Lock released immediately
 
                                         match send_result {
@@ -346,7 +460,8 @@ pub fn register_callbacks(
                                                 gstate.lines.pop_front();
                                                 gstate.pending_bytes += line_len;
                                                 gstate.line_lengths.push_back(line_len);
-                                                gstate.sent_lines.push_back(trimmed.to_string());
+                                                gstate.sent_lines.push_back(outgoing);
+                                                gstate.audit.record_sent(trimmed, Some(line.source_line));
                                                 gstate.total_sent += 1;
                                                 lines_sent_this_cycle += 1;
 
@@ -354,7 +469,7 @@ pub fn register_callbacks(
                                                 if gstate.total_sent.is_multiple_of(10) || gstate.lines.is_empty() {
                                                     let sent = gstate.total_sent;
                                                     let total = gstate.total_lines;
-                                                    let progress = if total > 0 { (sent as f32 / total as f32) * 100.0 } else { 0.0 };
+                                                    let progress = gstate.lines.percent() as f32;
                                                     
                                                     // Calculate times
                                                     let (elapsed_str, estimated_str) = if let Some(start) = gstate.start_time {
@@ -362,7 +477,11 @@ pub fn register_callbacks(
                                                         let elapsed_secs = elapsed.as_secs();
                                                         let elapsed_formatted = format!("{:02}:{:02}:{:02}", elapsed_secs / 3600, (elapsed_secs % 3600) / 60, elapsed_secs % 60);
                                                         
-                                                        let estimated_formatted = if progress > 0.0 {
+                                                        let estimated_formatted = if let Some(remaining) = gstate.estimated_remaining() {
+                                                            // Move times scaled by the current feed and rapid overrides
+                                                            let remaining_secs = remaining.as_secs();
+                                                            format!("{:02}:{:02}:{:02}", remaining_secs / 3600, (remaining_secs % 3600) / 60, remaining_secs % 60)
+                                                        } else if progress > 0.0 {
                                                             let total_secs = (elapsed_secs as f32 / (progress / 100.0)) as u64;
                                                             let remaining_secs = total_secs.saturating_sub(elapsed_secs);
                                                             format!("{:02}:{:02}:{:02}", remaining_secs / 3600, (remaining_secs % 3600) / 60, remaining_secs % 60)
@@ -389,7 +508,7 @@ pub fn register_callbacks(
                                                 }
                                             }
                                             Err(e) => {
-                                                let error_msg = format!("✗ Send failed at line {}: {}", gstate.total_sent + 1, e);
+                                                let error_msg = format!("✗ Send failed at line {}: {}", line.source_line + 1, e);
                                                 console_manager_poll.add_message(
                                                     DeviceMessageType::Error,
                                                     error_msg.clone()
@@ -534,6 +653,7 @@ pub fn register_callbacks(
                     window.set_position_x(0.0);
                     window.set_position_y(0.0);
                     window.set_position_z(0.0);
+                    update_position_text(&window);
                     let console_output = console_manager_clone.get_output();
                     window.set_console_output(slint::SharedString::from(console_output));
 
@@ -616,10 +736,19 @@ pub fn register_callbacks(
         }
     });
 
+    // Set up dro-units-toggled callback
+    let window_weak = main_window.as_weak();
+    main_window.on_dro_units_toggled(move || {
+        if let Some(window) = window_weak.upgrade() {
+            update_position_text(&window);
+        }
+    });
+
     // Set up machine-emergency-stop callback
     let window_weak = main_window.as_weak();
     let communicator_clone = communicator.clone();
     let console_manager_clone = console_manager.clone();
+    let device_manager_estop = device_manager.clone();
     main_window.on_machine_emergency_stop(move || {
         if let Some(window) = window_weak.upgrade() {
             let mut comm = communicator_clone.lock().unwrap();
@@ -633,12 +762,13 @@ pub fn register_callbacks(
                     "!!! EMERGENCY STOP TRIGGERED !!!",
                 );
                 
-                // Send Soft Reset (Ctrl-X / 0x18)
-                match comm.send(&[0x18]) {
+                // Soft reset (0x18) for GRBL, M112 for Marlin
+                let dialect = command_dialect(device_manager_estop.get_active_profile().as_ref());
+                match dialect.send_emergency_stop(&mut *comm) {
                     Ok(_) => {
                          console_manager_clone.add_message(
                             DeviceMessageType::Success,
-                            "✓ Soft Reset sent",
+                            "✓ Emergency stop sent",
                         );
                     }
                     Err(e) => {
@@ -709,256 +839,76 @@ pub fn register_callbacks(
             window.set_console_output(slint::SharedString::from(console_output));
         }
     });
+}
 
-    // Set up machine-jog-x-positive callback
-    let window_weak = main_window.as_weak();
-    let communicator_clone = communicator.clone();
-    let console_manager_clone = console_manager.clone();
-    let gcode_send_state_xp = gcode_send_state.clone();
-    main_window.on_machine_jog_x_positive(move |step_size: f32| {
-        if let Some(window) = window_weak.upgrade() {
-            let mut comm = communicator_clone.lock().unwrap();
-            if !comm.is_connected() {
-                warn!("Jog X+ failed: Device not connected");
-                console_manager_clone
-                    .add_message(DeviceMessageType::Error, "✗ Device not connected.");
-            } else {
-                // Send jog command in relative mode (G91) for incremental movement
-                let jog_cmd = format!("$J=G91 X{} F2000", step_size);
-                console_manager_clone.add_message(
-                    DeviceMessageType::Output,
-                    format!("Jogging X+ ({} mm)...", step_size),
-                );
-
-                match comm.send(format!("{}\n", jog_cmd).as_bytes()) {
-                    Ok(_) => {
-                        // Add to sent_lines for logging
-                        if let Ok(mut gstate) = gcode_send_state_xp.lock() {
-                            gstate.sent_lines.push_back(jog_cmd);
-                        }
-                    }
-                    Err(e) => {
-                        warn!("Failed to send Jog X+ command: {}", e);
-                        console_manager_clone.add_message(
-                            DeviceMessageType::Error,
-                            format!("✗ Jog X+ failed: {}", e),
-                        );
-                    }
-                }
-            }
-
-            let console_output = console_manager_clone.get_output();
-            window.set_console_output(slint::SharedString::from(console_output));
-        }
-    });
-
-    // Set up machine-jog-x-negative callback
-    let window_weak = main_window.as_weak();
-    let communicator_clone = communicator.clone();
-    let console_manager_clone = console_manager.clone();
-    let gcode_send_state_xn = gcode_send_state.clone();
-    main_window.on_machine_jog_x_negative(move |step_size: f32| {
-        if let Some(window) = window_weak.upgrade() {
-            let mut comm = communicator_clone.lock().unwrap();
-            if !comm.is_connected() {
-                warn!("Jog X- failed: Device not connected");
-                console_manager_clone
-                    .add_message(DeviceMessageType::Error, "✗ Device not connected.");
-            } else {
-                // Send jog command in relative mode (G91) for incremental movement
-                let jog_cmd = format!("$J=G91 X-{} F2000", step_size);
-                console_manager_clone.add_message(
-                    DeviceMessageType::Output,
-                    format!("Jogging X- ({} mm)...", step_size),
-                );
-
-                match comm.send(format!("{}\n", jog_cmd).as_bytes()) {
-                    Ok(_) => {
-                        // Add to sent_lines for logging
-                        if let Ok(mut gstate) = gcode_send_state_xn.lock() {
-                            gstate.sent_lines.push_back(jog_cmd);
-                        }
-                    }
-                    Err(e) => {
-                        warn!("Failed to send Jog X- command: {}", e);
-                        console_manager_clone.add_message(
-                            DeviceMessageType::Error,
-                            format!("✗ Jog X- failed: {}", e),
-                        );
-                    }
-                }
-            }
-
-            let console_output = console_manager_clone.get_output();
-            window.set_console_output(slint::SharedString::from(console_output));
-        }
-    });
-
-    // Set up machine-jog-y-positive callback
-    let window_weak = main_window.as_weak();
-    let communicator_clone = communicator.clone();
-    let console_manager_clone = console_manager.clone();
-    let gcode_send_state_yp = gcode_send_state.clone();
-    main_window.on_machine_jog_y_positive(move |step_size: f32| {
-        if let Some(window) = window_weak.upgrade() {
-            let mut comm = communicator_clone.lock().unwrap();
-            if !comm.is_connected() {
-                warn!("Jog Y+ failed: Device not connected");
-                console_manager_clone
-                    .add_message(DeviceMessageType::Error, "✗ Device not connected.");
-            } else {
-                // Send jog command in relative mode (G91) for incremental movement
-                let jog_cmd = format!("$J=G91 Y{} F2000", step_size);
-                console_manager_clone.add_message(
-                    DeviceMessageType::Output,
-                    format!("Jogging Y+ ({} mm)...", step_size),
-                );
-
-                match comm.send(format!("{}\n", jog_cmd).as_bytes()) {
-                    Ok(_) => {
-                        // Add to sent_lines for logging
-                        if let Ok(mut gstate) = gcode_send_state_yp.lock() {
-                            gstate.sent_lines.push_back(jog_cmd);
-                        }
-                    }
-                    Err(e) => {
-                        warn!("Failed to send Jog Y+ command: {}", e);
-                        console_manager_clone.add_message(
-                            DeviceMessageType::Error,
-                            format!("✗ Jog Y+ failed: {}", e),
-                        );
-                    }
-                }
-            }
-
-            let console_output = console_manager_clone.get_output();
-            window.set_console_output(slint::SharedString::from(console_output));
-        }
-    });
-
-    // Set up machine-jog-y-negative callback
-    let window_weak = main_window.as_weak();
-    let communicator_clone = communicator.clone();
-    let console_manager_clone = console_manager.clone();
-    let gcode_send_state_yn = gcode_send_state.clone();
-    main_window.on_machine_jog_y_negative(move |step_size: f32| {
-        if let Some(window) = window_weak.upgrade() {
-            let mut comm = communicator_clone.lock().unwrap();
-            if !comm.is_connected() {
-                warn!("Jog Y- failed: Device not connected");
-                console_manager_clone
-                    .add_message(DeviceMessageType::Error, "✗ Device not connected.");
-            } else {
-                // Send jog command in relative mode (G91) for incremental movement
-                let jog_cmd = format!("$J=G91 Y-{} F2000", step_size);
-                console_manager_clone.add_message(
-                    DeviceMessageType::Output,
-                    format!("Jogging Y- ({} mm)...", step_size),
-                );
-
-                match comm.send(format!("{}\n", jog_cmd).as_bytes()) {
-                    Ok(_) => {
-                        // Add to sent_lines for logging
-                        if let Ok(mut gstate) = gcode_send_state_yn.lock() {
-                            gstate.sent_lines.push_back(jog_cmd);
-                        }
-                    }
-                    Err(e) => {
-                        warn!("Failed to send Jog Y- command: {}", e);
-                        console_manager_clone.add_message(
-                            DeviceMessageType::Error,
-                            format!("✗ Jog Y- failed: {}", e),
-                        );
-                    }
-                }
-            }
-
-            let console_output = console_manager_clone.get_output();
-            window.set_console_output(slint::SharedString::from(console_output));
-        }
-    });
-
-    // Set up machine-jog-z-positive callback
-    let window_weak = main_window.as_weak();
-    let communicator_clone = communicator.clone();
-    let console_manager_clone = console_manager.clone();
-    let gcode_send_state_zp = gcode_send_state.clone();
-    main_window.on_machine_jog_z_positive(move |step_size: f32| {
-        if let Some(window) = window_weak.upgrade() {
-            let mut comm = communicator_clone.lock().unwrap();
-            if !comm.is_connected() {
-                warn!("Jog Z+ failed: Device not connected");
-                console_manager_clone
-                    .add_message(DeviceMessageType::Error, "✗ Device not connected.");
-            } else {
-                // Send jog command in relative mode (G91) for incremental movement
-                let jog_cmd = format!("$J=G91 Z{} F2000", step_size);
-                console_manager_clone.add_message(
-                    DeviceMessageType::Output,
-                    format!("Jogging Z+ ({} mm)...", step_size),
-                );
-
-                match comm.send(format!("{}\n", jog_cmd).as_bytes()) {
-                    Ok(_) => {
-                        // Add to sent_lines for logging
-                        if let Ok(mut gstate) = gcode_send_state_zp.lock() {
-                            gstate.sent_lines.push_back(jog_cmd);
-                        }
-                    }
-                    Err(e) => {
-                        warn!("Failed to send Jog Z+ command: {}", e);
-                        console_manager_clone.add_message(
-                            DeviceMessageType::Error,
-                            format!("✗ Jog Z+ failed: {}", e),
-                        );
-                    }
-                }
-            }
-
-            let console_output = console_manager_clone.get_output();
-            window.set_console_output(slint::SharedString::from(console_output));
-        }
-    });
-
-    // Set up machine-jog-z-negative callback
-    let window_weak = main_window.as_weak();
-    let communicator_clone = communicator.clone();
-    let console_manager_clone = console_manager.clone();
-    let gcode_send_state_zn = gcode_send_state.clone();
-    main_window.on_machine_jog_z_negative(move |step_size: f32| {
-        if let Some(window) = window_weak.upgrade() {
-            let mut comm = communicator_clone.lock().unwrap();
-            if !comm.is_connected() {
-                warn!("Jog Z- failed: Device not connected");
-                console_manager_clone
-                    .add_message(DeviceMessageType::Error, "✗ Device not connected.");
-            } else {
-                // Send jog command in relative mode (G91) for incremental movement
-                let jog_cmd = format!("$J=G91 Z-{} F2000", step_size);
-                console_manager_clone.add_message(
-                    DeviceMessageType::Output,
-                    format!("Jogging Z- ({} mm)...", step_size),
-                );
-
-                match comm.send(format!("{}\n", jog_cmd).as_bytes()) {
-                    Ok(_) => {
-                        // Add to sent_lines for logging
-                        if let Ok(mut gstate) = gcode_send_state_zn.lock() {
-                            gstate.sent_lines.push_back(jog_cmd);
-                        }
-                    }
-                    Err(e) => {
-                        warn!("Failed to send Jog Z- command: {}", e);
-                        console_manager_clone.add_message(
-                            DeviceMessageType::Error,
-                            format!("✗ Jog Z- failed: {}", e),
-                        );
-                    }
+/// Send the active device's startup commands once firmware detection is done
+///
+/// Each command waits for its `ok` before the next is sent. Responses are
+/// logged to the console, and the first failure stops the block and is
+/// shown in an error dialog.
+fn run_startup_block(
+    communicator: &Arc<Mutex<SerialCommunicator>>,
+    detected_firmware: &Arc<Mutex<Option<gcodekit4::firmware::firmware_detector::FirmwareDetectionResult>>>,
+    commands: &[String],
+    console_manager: &ConsoleManager,
+    window_weak: &slint::Weak<MainWindow>,
+) {
+    // Reading lets the console listener see the $I answer and detect the firmware
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(3);
+    while detected_firmware.lock().unwrap().is_none() && std::time::Instant::now() < deadline {
+        let _ = communicator.lock().unwrap().receive();
+        std::thread::sleep(std::time::Duration::from_millis(35));
+    }
+
+    console_manager.add_message(
+        DeviceMessageType::Output,
+        format!("Running {} startup command(s)", commands.len()),
+    );
+    let responses = {
+        let mut comm = communicator.lock().unwrap();
+        // Drop the rest of the $I answer so its ok isn't taken for ours
+        let _ = comm.receive();
+        gcodekit4_communication::run_startup_commands(&mut *comm, commands, std::time::Duration::from_secs(5))
+    };
+
+    for response in responses.iter().filter(|response| response.is_ok()) {
+        console_manager.add_message(DeviceMessageType::Output, format!("{} => ok", response.command));
+    }
+
+    let Some(failed) = responses.iter().find(|response| !response.is_ok()) else {
+        console_manager.add_message(DeviceMessageType::Success, "Startup commands complete");
+        return;
+    };
+    let error = failed.error.clone().unwrap_or_default();
+    let reason = error
+        .strip_prefix("error:")
+        .and_then(|code| code.trim().parse::<u8>().ok())
+        .map(format_error)
+        .unwrap_or(error);
+    let message = format!(
+        "Startup command '{}' failed: {}. The remaining startup commands were not sent.",
+        failed.command, reason
+    );
+    console_manager.add_message(DeviceMessageType::Error, message.clone());
+
+    let window_weak = window_weak.clone();
+    slint::invoke_from_event_loop(move || {
+        if window_weak.upgrade().is_some() {
+            let error_dialog = ErrorDialog::new().unwrap();
+            error_dialog.set_error_message(slint::SharedString::from(format!(
+                "Startup Commands Failed\n\n{}",
+                message
+            )));
+
+            let error_dialog_weak = error_dialog.as_weak();
+            error_dialog.on_close_dialog(move || {
+                if let Some(dlg) = error_dialog_weak.upgrade() {
+                    dlg.hide().ok();
                 }
-            }
+            });
 
-            let console_output = console_manager_clone.get_output();
-            window.set_console_output(slint::SharedString::from(console_output));
+            error_dialog.show().ok();
         }
-    });
+    })
+    .ok();
 }
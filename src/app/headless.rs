@@ -0,0 +1,98 @@
+//! Headless sending from the command line
+//!
+//! `gcodekit4 --port /dev/ttyUSB0 --send -` streams G-code piped on stdin to
+//! the controller without opening the window, so CAM post-processors can
+//! feed the machine directly. A file path can be given instead of `-`.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::time::Duration;
+
+use gcodekit4::{Communicator, ConnectionParams, SerialCommunicator};
+use gcodekit4_communication::communication::{
+    stream_lines, wait_for_ready, BufferedCommunicatorConfig, BufferedCommunicatorWrapper,
+};
+
+/// How long the controller may go without answering a streamed line
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long a board reset by opening the port gets to print its banner
+const BANNER_WAIT: Duration = Duration::from_secs(2);
+
+/// How long the controller may take to become ready after connecting
+const READY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Options for a headless send
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeadlessSend {
+    /// Program to send, `-` for stdin
+    pub source: String,
+    /// Serial port the controller is on
+    pub port: String,
+    /// Serial baud rate
+    pub baud_rate: u32,
+}
+
+/// Parse the command line for a headless send
+///
+/// Returns `None` when `--send` isn't given and the window should open.
+pub fn parse_args(args: &[String]) -> Option<anyhow::Result<HeadlessSend>> {
+    args.iter().any(|arg| arg == "--send").then(|| {
+        let source = flag_value(args, "--send")
+            .ok_or_else(|| anyhow::anyhow!("--send needs a file or '-'"))?;
+        let port = flag_value(args, "--port")
+            .ok_or_else(|| anyhow::anyhow!("--send needs a --port to send to"))?;
+        let baud_rate = match flag_value(args, "--baud") {
+            Some(baud) => baud
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Invalid baud rate '{}'", baud))?,
+            None => ConnectionParams::default().baud_rate,
+        };
+        Ok(HeadlessSend {
+            source: source.clone(),
+            port: port.clone(),
+            baud_rate,
+        })
+    })
+}
+
+/// The argument following a flag
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a String> {
+    let index = args.iter().position(|arg| arg == flag)?;
+    args.get(index + 1)
+}
+
+/// Connect to the controller and stream the program to it
+pub fn run(options: &HeadlessSend) -> anyhow::Result<()> {
+    let params = ConnectionParams {
+        port: options.port.clone(),
+        baud_rate: options.baud_rate,
+        // Short reads so waiting for the controller keeps to its deadlines
+        timeout_ms: 50,
+        ..ConnectionParams::default()
+    };
+    let mut communicator = SerialCommunicator::new();
+    communicator
+        .connect(&params)
+        .map_err(|e| anyhow::anyhow!("Failed to connect to {}: {}", options.port, e))?;
+    if let Err(e) = wait_for_ready(&mut communicator, BANNER_WAIT, READY_TIMEOUT) {
+        let _ = communicator.disconnect();
+        return Err(anyhow::anyhow!("{} on {}", e, options.port));
+    }
+
+    let mut sender = BufferedCommunicatorWrapper::new(
+        Box::new(communicator),
+        BufferedCommunicatorConfig::default(),
+    );
+    let reader: Box<dyn BufRead> = if options.source == "-" {
+        Box::new(std::io::stdin().lock())
+    } else {
+        Box::new(BufReader::new(File::open(&options.source)?))
+    };
+
+    let result = stream_lines(reader, &mut sender, RESPONSE_TIMEOUT);
+    let _ = sender.communicator_mut().disconnect();
+    let sent = result.map_err(|e| anyhow::anyhow!("{}", e))?;
+    tracing::info!("Sent {} lines to {}", sent, options.port);
+    Ok(())
+}
@@ -1,24 +1,40 @@
 use crate::{CapabilityItem, ConfigSetting, MainWindow};
-use gcodekit4::{CapabilityManager, list_ports};
+use gcodekit4::{CapabilityManager, JogPresets, Simulator, Units, list_ports};
+use gcodekit4_core::units::DroFormatter;
+use gcodekit4_core::VisualizerTheme;
+use gcodekit4_devicedb::DeviceManager;
 use gcodekit4_ui::EditorBridge;
 use crate::TextLine;
 
 /// Get list of available serial ports
-pub fn get_available_ports() -> anyhow::Result<Vec<slint::SharedString>> {
+///
+/// Returns the port names and, in the same order, a details line with the
+/// manufacturer, product and USB VID:PID of each port.
+pub fn get_available_ports() -> anyhow::Result<(Vec<slint::SharedString>, Vec<slint::SharedString>)>
+{
     match list_ports() {
         Ok(ports) => {
-            let port_names: Vec<slint::SharedString> = ports
-                .iter()
-                .map(|p| slint::SharedString::from(p.port_name.clone()))
-                .collect();
-
-            if port_names.is_empty() {
-                Ok(vec![slint::SharedString::from("No ports available")])
-            } else {
-                Ok(port_names)
+            if ports.is_empty() {
+                return Ok((
+                    vec![slint::SharedString::from("No ports available")],
+                    vec![slint::SharedString::new()],
+                ));
             }
+
+            Ok(ports
+                .iter()
+                .map(|p| {
+                    (
+                        slint::SharedString::from(p.port_name.clone()),
+                        slint::SharedString::from(p.details()),
+                    )
+                })
+                .unzip())
         }
-        Err(_) => Ok(vec![slint::SharedString::from("Error reading ports")]),
+        Err(_) => Ok((
+            vec![slint::SharedString::from("Error reading ports")],
+            vec![slint::SharedString::new()],
+        )),
     }
 }
 
@@ -67,6 +83,72 @@ pub fn snap_to_mm(value: f64) -> f64 {
     (value + 0.5).floor()
 }
 
+/// Describe the selected jog step for console messages, e.g. "1 mm" or "continuous"
+pub fn jog_step_label(presets: &JogPresets, unit: &str) -> String {
+    if presets.is_continuous() {
+        "continuous".to_string()
+    } else {
+        format!("{} {}", presets.distance(), unit)
+    }
+}
+
+/// Show whether the active device's port is opened without resetting the board
+pub fn show_no_reset_on_open(window: &MainWindow, device_manager: &DeviceManager) {
+    let no_reset = device_manager
+        .get_active_profile()
+        .is_some_and(|profile| profile.no_reset_on_open());
+    window.set_no_reset_on_open(no_reset);
+}
+
+/// Show the jog step presets and the selected one
+pub fn show_jog_presets(window: &MainWindow, presets: &JogPresets) {
+    let labels: Vec<slint::SharedString> = presets
+        .labels()
+        .into_iter()
+        .map(slint::SharedString::from)
+        .collect();
+    window.set_jog_preset_labels(slint::ModelRc::new(slint::VecModel::from(labels)));
+    window.set_jog_preset_index(presets.selected as i32);
+}
+
+/// Push the visualizer theme colors and stroke widths to the UI
+///
+/// Colors that fail to parse keep the UI's current value.
+pub fn apply_visualizer_theme(window: &MainWindow, theme: &VisualizerTheme) {
+    let color = |hex: &str| {
+        VisualizerTheme::parse_color(hex).map(|(r, g, b, a)| slint::Color::from_argb_u8(a, r, g, b))
+    };
+
+    if let Some(c) = color(&theme.background_color) {
+        window.set_visualizer_background_color(c);
+    }
+    if let Some(c) = color(&theme.grid_color) {
+        window.set_visualizer_grid_color(c);
+    }
+    if let Some(c) = color(&theme.origin_color) {
+        window.set_visualizer_origin_color(c);
+    }
+    if let Some(c) = color(&theme.rapid_color) {
+        window.set_visualizer_rapid_color(c);
+    }
+    if let Some(c) = color(&theme.g1_color) {
+        window.set_visualizer_g1_color(c);
+    }
+    if let Some(c) = color(&theme.g2_color) {
+        window.set_visualizer_g2_color(c);
+    }
+    if let Some(c) = color(&theme.g3_color) {
+        window.set_visualizer_g3_color(c);
+    }
+    if let Some(c) = color(&theme.g4_color) {
+        window.set_visualizer_g4_color(c);
+    }
+    window.set_visualizer_grid_width(theme.grid_width);
+    window.set_visualizer_origin_width(theme.origin_width);
+    window.set_visualizer_rapid_width(theme.rapid_width);
+    window.set_visualizer_cut_width(theme.cut_width);
+}
+
 /// Parse a GRBL setting line from $$ response
 /// Format: $100=80.000
 pub fn parse_grbl_setting_line(line: &str) -> Option<ConfigSetting> {
@@ -245,6 +327,28 @@ pub fn sync_capabilities_to_ui(window: &MainWindow, capability_manager: &Capabil
     window.set_cap_coordinate_systems(state.coordinate_systems as i32);
 }
 
+/// Refresh the formatted DRO position text from the raw positions
+///
+/// GRBL reports positions in mm; the inch/mm toggle only changes the display.
+/// Rotary axes are always shown in degrees.
+pub fn update_position_text(window: &MainWindow) {
+    let display_units = if window.get_dro_inches() { Units::INCH } else { Units::MM };
+    let dro = DroFormatter::new(Units::MM).with_display_units(display_units);
+    let format = |value: f32| slint::SharedString::from(dro.format(value as f64));
+
+    window.set_position_x_text(format(window.get_position_x()));
+    window.set_position_y_text(format(window.get_position_y()));
+    window.set_position_z_text(format(window.get_position_z()));
+    window.set_work_position_x_text(format(window.get_work_position_x()));
+    window.set_work_position_y_text(format(window.get_work_position_y()));
+    window.set_work_position_z_text(format(window.get_work_position_z()));
+
+    let rotary = |degrees: f32| slint::SharedString::from(dro.format_rotary(degrees as f64));
+    window.set_position_a_text(rotary(window.get_position_a()));
+    window.set_position_b_text(rotary(window.get_position_b()));
+    window.set_position_c_text(rotary(window.get_position_c()));
+}
+
 /// Update device info panel with firmware and capabilities
 pub fn update_device_info_panel(
     window: &MainWindow,
@@ -345,5 +449,33 @@ pub fn update_visible_lines(window: &MainWindow, editor_bridge: &EditorBridge) {
     }
     let model = std::rc::Rc::new(slint::VecModel::from(visible_lines));
     window.set_visible_lines(slint::ModelRc::new(model));
+    show_paren_match(window, editor_bridge);
+    show_modal_status(window, editor_bridge);
+}
+
+/// Show the modal state in effect at the editor cursor line
+///
+/// The program is replayed from the top through the cursor line, so a
+/// `G20` on the cursor line already reports inches.
+pub fn show_modal_status(window: &MainWindow, editor_bridge: &EditorBridge) {
+    let (line, _col) = editor_bridge.cursor_position();
+    let (_, state) = Simulator::state_at_line(&editor_bridge.get_text(), line + 1);
+    window.set_modal_status(slint::SharedString::from(state.modal_summary()));
+}
+
+/// Highlight the parenthesis pair at the editor cursor (1-based, 0 = none)
+pub fn show_paren_match(window: &MainWindow, editor_bridge: &EditorBridge) {
+    match editor_bridge.paren_match() {
+        Some(paren) => {
+            window.set_paren_line((paren.line + 1) as i32);
+            window.set_paren_open_column((paren.open_col + 1) as i32);
+            window.set_paren_close_column(paren.close_col.map_or(0, |col| (col + 1) as i32));
+        }
+        None => {
+            window.set_paren_line(0);
+            window.set_paren_open_column(0);
+            window.set_paren_close_column(0);
+        }
+    }
 }
 
@@ -1,4 +1,5 @@
 pub mod helpers;
 pub mod designer;
 pub mod callbacks;
+pub mod headless;
 pub mod types;
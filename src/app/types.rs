@@ -1,31 +1,92 @@
 use std::collections::VecDeque;
 use serde::{Serialize, Deserialize};
+use gcodekit4::firmware::grbl::JogQueue;
+use gcodekit4::{LineFramer, OverrideState, RuntimeEstimate, SendAuditLog, SendQueue};
 
 #[derive(Debug)]
 pub struct GcodeSendState {
-    pub lines: VecDeque<String>,
+    pub lines: SendQueue,
     pub pending_bytes: usize,
     pub line_lengths: VecDeque<usize>,
     pub sent_lines: VecDeque<String>,
     pub total_sent: usize,
     pub total_lines: usize,
     pub start_time: Option<std::time::Instant>,
+    pub audit: SendAuditLog,
+    /// Feed and spindle speed multiplier for test cuts, applied as lines are sent
+    pub cut_factor: f64,
+    /// Move times of the program being sent, for the remaining-time display
+    pub runtime: RuntimeEstimate,
+    /// Overrides last reported by the controller
+    pub overrides: OverrideState,
+    /// Machine state from the last status report, e.g. `Hold:0`
+    pub machine_state: Option<String>,
+    /// Jog button commands sent or held back
+    pub jog_queue: JogQueue,
+    /// Line numbers and checksums for the program, when the firmware checks them
+    pub framer: Option<LineFramer>,
 }
 
 impl Default for GcodeSendState {
     fn default() -> Self {
         Self {
-            lines: VecDeque::new(),
+            lines: SendQueue::default(),
             pending_bytes: 0,
             line_lengths: VecDeque::new(),
             sent_lines: VecDeque::new(),
             total_sent: 0,
             total_lines: 0,
             start_time: None,
+            audit: SendAuditLog::new(),
+            cut_factor: 1.0,
+            runtime: RuntimeEstimate::default(),
+            overrides: OverrideState::default(),
+            machine_state: None,
+            jog_queue: JogQueue::default(),
+            framer: None,
         }
     }
 }
 
+impl GcodeSendState {
+    /// Time left in the job at the current overrides
+    ///
+    /// Counts from the line after the last one the controller acknowledged.
+    /// Returns `None` when the program has no timed moves.
+    pub fn estimated_remaining(&self) -> Option<std::time::Duration> {
+        if self.runtime.total_time(&self.overrides).is_zero() {
+            return None;
+        }
+        let next_line = self
+            .audit
+            .last_acknowledged_line()
+            .map_or(0, |line| line + 1);
+        Some(self.runtime.remaining_time(next_line, &self.overrides))
+    }
+
+    /// Set the test cut factor, e.g. 0.5 for half feed and spindle speed
+    ///
+    /// Also applies to the rest of a program already being sent. Factors that
+    /// aren't positive are ignored.
+    pub fn set_cut_factor(&mut self, factor: f64) {
+        self.lines.set_cut_factor(factor);
+        if factor.is_finite() && factor > 0.0 {
+            self.cut_factor = factor;
+        }
+    }
+
+    /// Account for a line sent outside the program queue, e.g. a resend
+    ///
+    /// Its reply is then matched to it rather than to the next program line.
+    pub fn record_sent_line(&mut self, line: &str) {
+        let line_len = line.len() + 1;
+        self.pending_bytes += line_len;
+        self.line_lengths.push_back(line_len);
+        self.sent_lines.push_back(line.to_string());
+        self.audit.record_sent(line, None);
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct VectorEngravingParams {
     pub feed_rate: f32,
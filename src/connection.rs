@@ -0,0 +1,66 @@
+//! Connection parameters from device database connection profiles
+
+use gcodekit4_communication::firmware::CommandDialect;
+use gcodekit4_communication::{ConnectionDriver, ConnectionParams, ControllerType};
+use gcodekit4_devicedb::{ConnectionProfile, DeviceProfile};
+
+/// Build connection parameters from a connection profile
+///
+/// Network profiles use `port` as the host name and `tcp_port` as its port.
+/// Settings the profile doesn't cover keep the `ConnectionParams` defaults.
+pub fn connection_params(profile: &ConnectionProfile) -> ConnectionParams {
+    let driver = match profile.connection_type.to_ascii_lowercase().as_str() {
+        "tcp" => ConnectionDriver::Tcp,
+        "websocket" => ConnectionDriver::WebSocket,
+        _ => ConnectionDriver::Serial,
+    };
+    ConnectionParams {
+        driver,
+        port: profile.port.clone(),
+        network_port: profile.tcp_port,
+        baud_rate: profile.baud_rate,
+        timeout_ms: profile.timeout_ms,
+        auto_reconnect: profile.auto_reconnect,
+        ..ConnectionParams::default()
+    }
+    .with_control_lines(profile.dtr_on_open, profile.rts_on_open)
+}
+
+/// Connection parameters for a connect from the connection panel
+///
+/// The active connection profile decides the driver, port and settings. The
+/// panel's port and baud rate are only used when there's no profile; a
+/// serial profile whose port is `Auto` (or empty) takes the panel's port.
+pub fn panel_connection_params(
+    profile: Option<&ConnectionProfile>,
+    panel_port: &str,
+    panel_baud: u32,
+) -> ConnectionParams {
+    let Some(profile) = profile else {
+        return ConnectionParams::serial(panel_port, panel_baud);
+    };
+    let mut params = connection_params(profile);
+    let auto_port = params.port.is_empty() || params.port.eq_ignore_ascii_case("auto");
+    if params.driver == ConnectionDriver::Serial && auto_port {
+        params.port = panel_port.to_string();
+    }
+    params
+}
+
+/// Command dialect of a device profile's controller
+///
+/// Without an active profile the machine is taken to speak GRBL.
+pub fn command_dialect(profile: Option<&DeviceProfile>) -> CommandDialect {
+    let Some(profile) = profile else {
+        return CommandDialect::default();
+    };
+    let controller_type = match profile.controller_type {
+        gcodekit4_devicedb::ControllerType::Grbl => ControllerType::Grbl,
+        gcodekit4_devicedb::ControllerType::TinyG => ControllerType::TinyG,
+        gcodekit4_devicedb::ControllerType::G2Core => ControllerType::G2Core,
+        gcodekit4_devicedb::ControllerType::Smoothieware => ControllerType::Smoothieware,
+        gcodekit4_devicedb::ControllerType::FluidNC => ControllerType::FluidNC,
+        gcodekit4_devicedb::ControllerType::Marlin => ControllerType::Marlin,
+    };
+    CommandDialect::for_controller(controller_type)
+}
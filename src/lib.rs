@@ -28,7 +28,9 @@
 
 #![allow(dead_code)]
 
+pub mod connection;
 pub mod platform;
+pub mod sender;
 
 // Re-export modules for main.rs
 pub use gcodekit4_communication::firmware;
@@ -46,21 +48,30 @@ pub use gcodekit4_core::{
 };
 
 pub use gcodekit4_visualizer::{
-    AdvancedProber, Alarm, AlarmManager, AlarmType, AutoConnectConfig, BackupEntry, BackupManager,
-    BasicProber, Bookmark, BookmarkManager, CommandHistory, CommandId, CommandLengthProcessor,
-    CommandListener, CommandListenerHandle, CommandNumberGenerator, CommandProcessor,
-    CommandResponse, CommandState, CommentProcessor, CustomAction, CustomMacro, DataLogger,
-    DecimalProcessor, DropEvent, DropFileType, DropIndicatorState, DropTarget, DropZone,
-    EmptyLineRemoverProcessor, ExportOptions, FeedRateStats, FileComparison, FileEncoding,
-    FileExporter, FileFormat, FileProcessingPipeline, FileReadStats, FileStatistics,
-    FileStreamReader, FileValidation, GcodeCommand, GcodeFileReader, GcodeParser, GcodeState,
-    GcodeStreamReader, GcodeTemplate, HeightPoint, HistoryEntry, LogEntry, ModalState,
-    NetworkConfig, PausableStream, PendantButton, PendantConfig, PerformanceMetrics, ProbeMesh,
-    ProbePoint, ProcessedFile, ProcessorConfig, ProcessorHandle, ProcessorPipeline,
-    ProcessorRegistry, ProgramState, RecentFileEntry, RecentFilesManager, SimulationPosition,
-    Simulator, SoftLimits, SpindleStats, Stepper, StringStreamReader, TemplateLibrary,
-    TemplateVariable, ToolInfo, ToolLibrary, ToolOffset, ToolOffsetManager, ValidationIssue,
-    ValidationResult, ValidationSeverity, WhitespaceProcessor, WorkCoordinateSystem, WorkOffset,
+    AdvancedProber, Alarm, AlarmManager, AlarmType, ArcNormalizeProcessor, AutoConnectConfig,
+    AutoLevelConfig, AutoLeveler, AxisMapProcessor, BackupEntry, BackupManager, BasicProber,
+    Bookmark, BookmarkManager, CommandHistory, CommandId, CommandLengthProcessor, CommandListener,
+    CommandListenerHandle, CommandNumberGenerator, CommandProcessor, CommandResponse, CommandState,
+    CommentProcessor, CustomAction, CustomMacro, DataLogger, DecimalProcessor,
+    DefaultFeedProcessor, DropEvent, DropFileType, DropIndicatorState, DropTarget, DropZone,
+    EmptyLineRemoverProcessor, ExportOptions, ExpressionProcessor, Extents,
+    FeedRateOverrideProcessor, FeedRateStats, FileComparison, FileEncoding, FileExporter,
+    FileFormat, FileProcessingPipeline, FileReadStats, FileStatistics, FileStreamReader,
+    FileValidation, GcodeCommand, GcodeFileReader, GcodeParser, GcodeState, GcodeStreamReader,
+    GcodeTemplate, HeaderFooterProcessor, HeightPoint, HistoryEntry, LineEnding, LineFramer,
+    LineIssue, LogEntry, MeshLevelProcessor, MirrorProcessor, ModalState, NRenumberProcessor,
+    NetworkConfig, Operation, ParsedWords, PausableStream, PendantButton, PendantConfig,
+    PerformanceMetrics, PipelineReport, PlungeLimits, ProbeGrid, ProbeMesh, ProbePoint,
+    ProcessedFile, ProcessorConfig, ProcessorHandle, ProcessorPipeline, ProcessorRegistry,
+    ProgramEnd, ProgramState, QueuedLine, RapidToFeedProcessor, RecentFileEntry,
+    RecentFilesManager, RestoreReport, RuntimeEstimate, SafeZProcessor, SendAuditLog, SendQueue,
+    SessionDirection, SessionEvent, SessionReplay, SessionTranscript, SettingsTarget,
+    SimulationPosition, Simulator, SoftLimits, SpindleStats, Stepper, StreamProgress,
+    StringStreamReader, TemplateLibrary, TemplateVariable, TimeEstimate, TimeEstimator,
+    ToolChangeProber, ToolInfo, ToolLibrary, ToolOffset, ToolOffsetManager, ToolProbeConfig,
+    ToolSegment, ToolStats, TrailingZeroProcessor, TransformProcessor, TranslateProcessor,
+    UnitConversionProcessor, ValidationIssue, ValidationResult, ValidationSeverity,
+    WhitespaceProcessor, WorkCoordinateSystem, WorkOffset,
 };
 
 pub use gcodekit4_designer::{
@@ -85,9 +96,9 @@ pub use gcodekit4_communication::{
 pub use gcodekit4_ui::{
     Config, ConnectionSettings, ConnectionType, ConsoleEvent, ConsoleListener, DeviceConsoleManager,
     DeviceMessageType, FileProcessingSettings, FirmwareSettings, FirmwareSettingsIntegration,
-    GcodeEditor, GcodeLine, KeyboardShortcut, MachineSettings, Setting, SettingUiModel,
-    SettingValue, SettingsCategory, SettingsController, SettingsDialog, SettingsManager,
-    SettingsPersistence, Token, TokenType, UiSettings,
+    GcodeEditor, GcodeLine, JogPresets, JogStep, KeyboardShortcut, MachineSettings, Setting,
+    SettingUiModel, SettingValue, SettingsCategory, SettingsController, SettingsDialog,
+    SettingsManager, SettingsPersistence, Token, TokenType, UiSettings,
 };
 
 /// Library version
@@ -6,7 +6,7 @@
 
 use std::sync::{Arc, Mutex};
 use gcodekit4::{
-    init_logging, list_ports, CapabilityManager, Communicator,
+    init_logging, CapabilityManager, Communicator,
     ConsoleEvent, ConsoleListener, DeviceConsoleManager, DeviceMessageType,
     FirmwareSettingsIntegration, SerialCommunicator,
     SettingsController, SettingsDialog, SettingsManager, SettingsPersistence,
@@ -56,12 +56,77 @@ fn update_visible_lines(window: &MainWindow, bridge: &EditorBridge) {
     let _ = !lines.is_empty();
 
     window.set_visible_lines(slint::ModelRc::new(VecModel::from(lines)));
+    app::helpers::show_paren_match(window, bridge);
+    app::helpers::show_modal_status(window, bridge);
+}
+
+/// Shared state the jog buttons send their commands through
+#[derive(Clone)]
+struct JogButtons {
+    window_weak: slint::Weak<MainWindow>,
+    communicator: Arc<Mutex<SerialCommunicator>>,
+    console_manager: Arc<DeviceConsoleManager>,
+    device_manager: Arc<DeviceManager>,
+    settings_persistence: Rc<RefCell<SettingsPersistence>>,
+    gcode_send_state: Arc<Mutex<app::types::GcodeSendState>>,
+}
+
+impl JogButtons {
+    /// Jog one axis by the selected step in the given direction
+    fn jog(&self, axis: char, positive: bool) {
+        let Some(window) = self.window_weak.upgrade() else {
+            return;
+        };
+        let name = format!("{}{}", axis, if positive { '+' } else { '-' });
+        let mut comm = self.communicator.lock().unwrap();
+        if !comm.is_connected() {
+            warn!("Jog {} failed: Device not connected", name);
+            self.console_manager
+                .add_message(DeviceMessageType::Error, "✗ Device not connected.");
+        } else {
+            // Send jog command in relative mode (G91) for incremental movement
+            let jog_feed = self.device_manager.sender_config().jog_feed_rate;
+            let presets = self
+                .settings_persistence
+                .borrow()
+                .config()
+                .machine
+                .jog_presets
+                .clone();
+            let jog_cmd = presets.jog_command(axis, positive, jog_feed);
+            let unit = if matches!(axis, 'A' | 'B' | 'C') { "deg" } else { "mm" };
+            self.console_manager.add_message(
+                DeviceMessageType::Output,
+                format!("Jogging {} ({})...", name, jog_step_label(&presets, unit)),
+            );
+
+            // Bursts of clicks are held back until the outstanding jogs finish
+            let ready = self.gcode_send_state.lock().unwrap().jog_queue.request(jog_cmd);
+            let sent = ready.map_or(Ok(0), |cmd| comm.send(format!("{}\n", cmd).as_bytes()));
+            if let Err(e) = sent {
+                warn!("Failed to send Jog {} command: {}", name, e);
+                self.console_manager.add_message(
+                    DeviceMessageType::Error,
+                    format!("✗ Jog {} failed: {}", name, e),
+                );
+            }
+        }
+
+        let console_output = self.console_manager.get_output();
+        window.set_console_output(slint::SharedString::from(console_output));
+    }
 }
 
 fn main() -> anyhow::Result<()> {
     // Initialize logging
     init_logging()?;
 
+    // `--send` streams a program without opening the window
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(options) = app::headless::parse_args(&args) {
+        return app::headless::run(&options?);
+    }
+
     let main_window = MainWindow::new().map_err(|e| anyhow::anyhow!("UI Error: {}", e))?;
 
     // Initialize about dialog properties
@@ -69,9 +134,10 @@ fn main() -> anyhow::Result<()> {
     main_window.set_app_build_date(slint::SharedString::from(BUILD_DATE));
 
     // Get initial list of ports
-    let ports = get_available_ports()?;
+    let (ports, port_details) = get_available_ports()?;
     let ports_model = Rc::new(VecModel::from(ports.clone()));
     main_window.set_available_ports(slint::ModelRc::from(ports_model.clone()));
+    main_window.set_available_port_details(slint::ModelRc::new(VecModel::from(port_details)));
 
     // Initialize selected port if we have ports
     if !ports.is_empty() {
@@ -109,13 +175,20 @@ fn main() -> anyhow::Result<()> {
     // Shared G-code send state (for polling thread to process)
     use crate::app::types::GcodeSendState;
     let gcode_send_state = Arc::new(Mutex::new(GcodeSendState {
-        lines: std::collections::VecDeque::new(),
+        lines: gcodekit4::SendQueue::default(),
         pending_bytes: 0,
         line_lengths: std::collections::VecDeque::new(),
         sent_lines: std::collections::VecDeque::new(),
         total_sent: 0,
         total_lines: 0,
         start_time: None,
+        audit: gcodekit4::SendAuditLog::new(),
+        cut_factor: 1.0,
+        runtime: gcodekit4::RuntimeEstimate::default(),
+        overrides: gcodekit4::OverrideState::default(),
+        machine_state: None,
+        jog_queue: gcodekit4::firmware::grbl::JogQueue::default(),
+        framer: None,
     }));
 
     // Initialize device console manager early to register listeners
@@ -358,6 +431,12 @@ fn main() -> anyhow::Result<()> {
         // main_window.set_show_menu_shortcuts(persistence.config().ui.show_menu_shortcuts);
     }
 
+    // Show jog step presets from settings
+    show_jog_presets(&main_window, &settings_persistence.borrow().config().machine.jog_presets);
+
+    // Apply the visualizer theme from settings
+    apply_visualizer_theme(&main_window, &settings_persistence.borrow().config().ui.visualizer_theme);
+
     // Initialize Settings Controller
     let settings_controller = Rc::new(SettingsController::new(
         settings_dialog.clone(),
@@ -371,10 +450,22 @@ fn main() -> anyhow::Result<()> {
         warn!("Failed to load device profiles: {}", e);
     }
     let device_ui_controller = Rc::new(DeviceUiController::new(device_manager.clone()));
+    show_no_reset_on_open(&main_window, &device_manager);
+
+    // Flag designs that won't fit the active machine
+    if let Some(profile) = device_manager.get_active_profile() {
+        designer_mgr.borrow_mut().set_work_area(
+            profile.x_axis.min,
+            profile.y_axis.min,
+            profile.x_axis.max,
+            profile.y_axis.max,
+        );
+    }
 
     // Bind Device Manager callbacks
     {
         let controller = device_ui_controller.clone();
+        let device_manager = device_manager.clone();
         let window_weak = main_window.as_weak();
         main_window.on_load_device_profiles(move || {
             let profiles = controller.get_ui_profiles();
@@ -409,6 +500,7 @@ fn main() -> anyhow::Result<()> {
 
             if let Some(window) = window_weak.upgrade() {
                 window.set_device_profiles(slint::ModelRc::new(VecModel::from(slint_profiles)));
+                show_no_reset_on_open(&window, &device_manager);
             }
         });
     }
@@ -524,12 +616,33 @@ fn main() -> anyhow::Result<()> {
 
     {
         let controller = device_ui_controller.clone();
+        let device_manager = device_manager.clone();
+        let settings_persistence = settings_persistence.clone();
+        let console_manager = console_manager.clone();
         let window_weak = main_window.as_weak();
         main_window.on_set_active_device_profile(move |id| {
             if let Err(e) = controller.set_active_profile(&id) {
                 warn!("Failed to set active profile: {}", e);
             }
             if let Some(window) = window_weak.upgrade() {
+                // Jog by the step the newly active profile defaults to
+                {
+                    let step = device_manager.sender_config().jog_step_size;
+                    let mut persistence = settings_persistence.borrow_mut();
+                    let presets = &mut persistence.config_mut().machine.jog_presets;
+                    presets.select_distance(step);
+                    if presets.distance() != step {
+                        console_manager.add_message(
+                            DeviceMessageType::Output,
+                            format!(
+                                "No {} mm jog preset for this profile, jogging by {} mm",
+                                step,
+                                presets.distance()
+                            ),
+                        );
+                    }
+                    show_jog_presets(&window, presets);
+                }
                 window.invoke_load_device_profiles();
             }
         });
@@ -547,6 +660,7 @@ fn main() -> anyhow::Result<()> {
         capability_manager.clone(),
         gcode_send_state.clone(),
         detected_firmware.clone(),
+        device_manager.clone(),
     );
     app::callbacks::editor::register_callbacks(
         &main_window,
@@ -576,6 +690,7 @@ fn main() -> anyhow::Result<()> {
     let console_manager_clone = console_manager.clone();
     let gcode_editor_clone = gcode_editor.clone();
     let gcode_send_state_clone = gcode_send_state.clone();
+    let device_manager_send = device_manager.clone();
     main_window.on_menu_send_to_device(move || {
         if let Some(window) = window_weak.upgrade() {
             // Get the current content from the UI TextEdit
@@ -642,6 +757,24 @@ fn main() -> anyhow::Result<()> {
                 return;
             }
 
+            // Run the program through the active profile's preprocessing recipe
+            let sender_config = device_manager_send.sender_config();
+            let processed = gcodekit4::sender::preprocess_program(&sender_config, &current_content);
+            let current_content = match processed {
+                Ok(content) => content,
+                Err(e) => {
+                    warn!("Send failed: preprocessing recipe: {}", e);
+                    console_manager_clone.add_message(
+                        DeviceMessageType::Error,
+                        format!("✗ Preprocessing recipe failed: {}", e),
+                    );
+                    window.set_connection_status(slint::SharedString::from(
+                        "Error: Preprocessing recipe failed",
+                    ));
+                    return;
+                }
+            };
+
             // Queue G-Code for the polling thread to send using GRBL Character-Counting Protocol
             console_manager_clone.add_message(
                 DeviceMessageType::Output,
@@ -651,19 +784,58 @@ fn main() -> anyhow::Result<()> {
                 ),
             );
 
-            // Queue the lines for the polling thread
-            let lines: Vec<String> = current_content.lines().map(|s| s.to_string()).collect();
-            let line_count = lines.len();
+            // Queue the lines for the polling thread, skipping blank and comment-only lines
+            let send_comments = sender_config.send_comments;
+            let queue = gcodekit4::SendQueue::new(&current_content, send_comments);
+            let line_count = queue.sendable_lines();
+            if queue.skipped_lines() > 0 {
+                console_manager_clone.add_message(
+                    DeviceMessageType::Output,
+                    format!("Skipping {} blank or comment-only lines", queue.skipped_lines()),
+                );
+            }
 
             {
                 let mut gstate = gcode_send_state_clone.lock().unwrap();
-                gstate.lines = lines.into();
+                let cut_factor = gstate.cut_factor;
+                gstate.lines = queue;
+                gstate.lines.set_cut_factor(cut_factor);
+                if gstate.lines.cut_factor() != 1.0 {
+                    console_manager_clone.add_message(
+                        DeviceMessageType::Output,
+                        format!(
+                            "Cut factor {:.0}%: feed and spindle words scaled as sent, file unchanged",
+                            gstate.lines.cut_factor() * 100.0
+                        ),
+                    );
+                }
                 gstate.total_lines = line_count;
                 gstate.total_sent = 0;
                 gstate.pending_bytes = 0;
                 gstate.line_lengths.clear();
                 gstate.sent_lines.clear();
+                gstate.audit.clear();
+                gstate.runtime = gcodekit4::RuntimeEstimate::from_program(
+                    &current_content,
+                    gcodekit4_visualizer::DEFAULT_RAPID_RATE,
+                );
                 gstate.start_time = Some(std::time::Instant::now());
+
+                // Marlin checks line numbers and checksums; number each program from 1
+                let dialect = gcodekit4::connection::command_dialect(
+                    device_manager_send.get_active_profile().as_ref(),
+                );
+                gstate.framer = dialect.line_framer();
+                if let Some(reset) = gstate.framer.as_mut().map(|framer| framer.reset()) {
+                    let send_result = communicator_clone
+                        .lock()
+                        .unwrap()
+                        .send(format!("{}\n", reset).as_bytes());
+                    match send_result {
+                        Ok(_) => gstate.record_sent_line(&reset),
+                        Err(e) => warn!("Failed to reset line numbers: {}", e),
+                    }
+                }
             }
 
             // Update UI
@@ -692,7 +864,9 @@ fn main() -> anyhow::Result<()> {
     // Stop transmission callback
     let window_weak = main_window.as_weak();
     let gcode_send_state_stop = gcode_send_state.clone();
+    let communicator_stop = communicator.clone();
     let console_manager_stop = console_manager.clone();
+    let device_manager_stop = device_manager.clone();
     main_window.on_menu_stop_transmission(move || {
         if let Some(window) = window_weak.upgrade() {
             // Clear the send queue to stop transmission
@@ -704,11 +878,34 @@ fn main() -> anyhow::Result<()> {
                 gstate.pending_bytes = 0;
                 gstate.line_lengths.clear();
                 gstate.sent_lines.clear();
+                // Only status reports after the feed hold can say it has stopped
+                gstate.machine_state = None;
+            }
+
+            // The controller may still be running buffered moves. GRBL holds to stop
+            // them, waits for the hold to finish decelerating, then soft resets to
+            // flush the planner and queries the machine's real state; Marlin quick
+            // stops with M410
+            let connected = communicator_stop.lock().unwrap().is_connected();
+            if connected {
+                let dialect = gcodekit4::connection::command_dialect(
+                    device_manager_stop.get_active_profile().as_ref(),
+                );
+                let communicator_abort = communicator_stop.clone();
+                let send_state_abort = gcode_send_state_stop.clone();
+                std::thread::spawn(move || {
+                    let machine_state = || send_state_abort.lock().unwrap().machine_state.clone();
+                    if let Err(e) =
+                        dialect.abort_communicator(&*communicator_abort, machine_state)
+                    {
+                        warn!("Failed to abort controller after stop: {}", e);
+                    }
+                });
             }
 
             console_manager_stop
-                .add_message(DeviceMessageType::Output, "⏹ G-Code transmission stopped");
-            window.set_connection_status("G-Code transmission stopped".into());
+                .add_message(DeviceMessageType::Output, "⏹ G-Code transmission aborted");
+            window.set_connection_status("G-Code transmission aborted".into());
             window.set_progress_value(0.0);
 
             let console_output = console_manager_stop.get_output();
@@ -1142,364 +1339,93 @@ fn main() -> anyhow::Result<()> {
         }
     });
 
-    // Set up machine-jog-x-positive callback
-    let window_weak = main_window.as_weak();
-    let communicator_clone = communicator.clone();
-    let console_manager_clone = console_manager.clone();
-    main_window.on_machine_jog_x_positive(move |step_size: f32| {
-        if let Some(window) = window_weak.upgrade() {
-            let mut comm = communicator_clone.lock().unwrap();
-            if !comm.is_connected() {
-                warn!("Jog X+ failed: Device not connected");
-                console_manager_clone
-                    .add_message(DeviceMessageType::Error, "✗ Device not connected.");
-            } else {
-                // Send jog command in relative mode (G91) for incremental movement
-                let jog_cmd = format!("$J=G91 X{} F2000", step_size);
-                console_manager_clone.add_message(
-                    DeviceMessageType::Output,
-                    format!("Jogging X+ ({} mm)...", step_size),
-                );
-
-                match comm.send(format!("{}\n", jog_cmd).as_bytes()) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        warn!("Failed to send Jog X+ command: {}", e);
-                        console_manager_clone.add_message(
-                            DeviceMessageType::Error,
-                            format!("✗ Jog X+ failed: {}", e),
-                        );
-                    }
-                }
-            }
-
-            let console_output = console_manager_clone.get_output();
-            window.set_console_output(slint::SharedString::from(console_output));
-        }
-    });
-
-    // Set up machine-jog-x-negative callback
-    let window_weak = main_window.as_weak();
-    let communicator_clone = communicator.clone();
-    let console_manager_clone = console_manager.clone();
-    main_window.on_machine_jog_x_negative(move |step_size: f32| {
-        if let Some(window) = window_weak.upgrade() {
-            let mut comm = communicator_clone.lock().unwrap();
-            if !comm.is_connected() {
-                warn!("Jog X- failed: Device not connected");
-                console_manager_clone
-                    .add_message(DeviceMessageType::Error, "✗ Device not connected.");
-            } else {
-                // Send jog command in relative mode (G91) for incremental movement
-                let jog_cmd = format!("$J=G91 X-{} F2000", step_size);
-                console_manager_clone.add_message(
-                    DeviceMessageType::Output,
-                    format!("Jogging X- ({} mm)...", step_size),
-                );
-
-                match comm.send(format!("{}\n", jog_cmd).as_bytes()) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        warn!("Failed to send Jog X- command: {}", e);
-                        console_manager_clone.add_message(
-                            DeviceMessageType::Error,
-                            format!("✗ Jog X- failed: {}", e),
-                        );
-                    }
-                }
-            }
-
-            let console_output = console_manager_clone.get_output();
-            window.set_console_output(slint::SharedString::from(console_output));
-        }
-    });
-
-    // Set up machine-jog-y-positive callback
-    let window_weak = main_window.as_weak();
-    let communicator_clone = communicator.clone();
-    let console_manager_clone = console_manager.clone();
-    main_window.on_machine_jog_y_positive(move |step_size: f32| {
-        if let Some(window) = window_weak.upgrade() {
-            let mut comm = communicator_clone.lock().unwrap();
-            if !comm.is_connected() {
-                warn!("Jog Y+ failed: Device not connected");
-                console_manager_clone
-                    .add_message(DeviceMessageType::Error, "✗ Device not connected.");
-            } else {
-                // Send jog command in relative mode (G91) for incremental movement
-                let jog_cmd = format!("$J=G91 Y{} F2000", step_size);
-                console_manager_clone.add_message(
-                    DeviceMessageType::Output,
-                    format!("Jogging Y+ ({} mm)...", step_size),
-                );
-
-                match comm.send(format!("{}\n", jog_cmd).as_bytes()) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        warn!("Failed to send Jog Y+ command: {}", e);
-                        console_manager_clone.add_message(
-                            DeviceMessageType::Error,
-                            format!("✗ Jog Y+ failed: {}", e),
-                        );
-                    }
-                }
-            }
-
-            let console_output = console_manager_clone.get_output();
-            window.set_console_output(slint::SharedString::from(console_output));
-        }
-    });
-
-    // Set up machine-jog-y-negative callback
-    let window_weak = main_window.as_weak();
-    let communicator_clone = communicator.clone();
-    let console_manager_clone = console_manager.clone();
-    main_window.on_machine_jog_y_negative(move |step_size: f32| {
-        if let Some(window) = window_weak.upgrade() {
-            let mut comm = communicator_clone.lock().unwrap();
-            if !comm.is_connected() {
-                warn!("Jog Y- failed: Device not connected");
-                console_manager_clone
-                    .add_message(DeviceMessageType::Error, "✗ Device not connected.");
-            } else {
-                // Send jog command in relative mode (G91) for incremental movement
-                let jog_cmd = format!("$J=G91 Y-{} F2000", step_size);
-                console_manager_clone.add_message(
-                    DeviceMessageType::Output,
-                    format!("Jogging Y- ({} mm)...", step_size),
-                );
-
-                match comm.send(format!("{}\n", jog_cmd).as_bytes()) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        warn!("Failed to send Jog Y- command: {}", e);
-                        console_manager_clone.add_message(
-                            DeviceMessageType::Error,
-                            format!("✗ Jog Y- failed: {}", e),
-                        );
-                    }
-                }
-            }
-
-            let console_output = console_manager_clone.get_output();
-            window.set_console_output(slint::SharedString::from(console_output));
-        }
-    });
-
-    // Set up machine-jog-z-positive callback
-    let window_weak = main_window.as_weak();
-    let communicator_clone = communicator.clone();
-    let console_manager_clone = console_manager.clone();
-    main_window.on_machine_jog_z_positive(move |step_size: f32| {
-        if let Some(window) = window_weak.upgrade() {
-            let mut comm = communicator_clone.lock().unwrap();
-            if !comm.is_connected() {
-                warn!("Jog Z+ failed: Device not connected");
-                console_manager_clone
-                    .add_message(DeviceMessageType::Error, "✗ Device not connected.");
-            } else {
-                // Send jog command in relative mode (G91) for incremental movement
-                let jog_cmd = format!("$J=G91 Z{} F2000", step_size);
-                console_manager_clone.add_message(
-                    DeviceMessageType::Output,
-                    format!("Jogging Z+ ({} mm)...", step_size),
-                );
-
-                match comm.send(format!("{}\n", jog_cmd).as_bytes()) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        warn!("Failed to send Jog Z+ command: {}", e);
-                        console_manager_clone.add_message(
-                            DeviceMessageType::Error,
-                            format!("✗ Jog Z+ failed: {}", e),
-                        );
-                    }
-                }
-            }
-
-            let console_output = console_manager_clone.get_output();
-            window.set_console_output(slint::SharedString::from(console_output));
-        }
-    });
-
-    // Set up machine-jog-z-negative callback
-    let window_weak = main_window.as_weak();
+    // Set up the jog button callbacks
+    let jog_buttons = JogButtons {
+        window_weak: main_window.as_weak(),
+        communicator: communicator.clone(),
+        console_manager: console_manager.clone(),
+        device_manager: device_manager.clone(),
+        settings_persistence: settings_persistence.clone(),
+        gcode_send_state: gcode_send_state.clone(),
+    };
+    let jog = jog_buttons.clone();
+    main_window.on_machine_jog_x_positive(move || jog.jog('X', true));
+    let jog = jog_buttons.clone();
+    main_window.on_machine_jog_x_negative(move || jog.jog('X', false));
+    let jog = jog_buttons.clone();
+    main_window.on_machine_jog_y_positive(move || jog.jog('Y', true));
+    let jog = jog_buttons.clone();
+    main_window.on_machine_jog_y_negative(move || jog.jog('Y', false));
+    let jog = jog_buttons.clone();
+    main_window.on_machine_jog_z_positive(move || jog.jog('Z', true));
+    let jog = jog_buttons.clone();
+    main_window.on_machine_jog_z_negative(move || jog.jog('Z', false));
+    let jog = jog_buttons.clone();
+    main_window.on_machine_jog_a_positive(move || jog.jog('A', true));
+    let jog = jog_buttons.clone();
+    main_window.on_machine_jog_a_negative(move || jog.jog('A', false));
+    let jog = jog_buttons.clone();
+    main_window.on_machine_jog_b_positive(move || jog.jog('B', true));
+    main_window.on_machine_jog_b_negative(move || jog_buttons.jog('B', false));
+
+    // Set up machine-jog-stop callback
     let communicator_clone = communicator.clone();
-    let console_manager_clone = console_manager.clone();
-    main_window.on_machine_jog_z_negative(move |step_size: f32| {
-        if let Some(window) = window_weak.upgrade() {
-            let mut comm = communicator_clone.lock().unwrap();
-            if !comm.is_connected() {
-                warn!("Jog Z- failed: Device not connected");
-                console_manager_clone
-                    .add_message(DeviceMessageType::Error, "✗ Device not connected.");
-            } else {
-                // Send jog command in relative mode (G91) for incremental movement
-                let jog_cmd = format!("$J=G91 Z-{} F2000", step_size);
-                console_manager_clone.add_message(
-                    DeviceMessageType::Output,
-                    format!("Jogging Z- ({} mm)...", step_size),
-                );
-
-                match comm.send(format!("{}\n", jog_cmd).as_bytes()) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        warn!("Failed to send Jog Z- command: {}", e);
-                        console_manager_clone.add_message(
-                            DeviceMessageType::Error,
-                            format!("✗ Jog Z- failed: {}", e),
-                        );
-                    }
-                }
+    let settings_persistence_clone = settings_persistence.clone();
+    let gcode_send_state_clone = gcode_send_state.clone();
+    main_window.on_machine_jog_stop(move || {
+        // Only continuous jogs keep moving after the button is released
+        if !settings_persistence_clone
+            .borrow()
+            .config()
+            .machine
+            .jog_presets
+            .is_continuous()
+        {
+            return;
+        }
+
+        gcode_send_state_clone.lock().unwrap().jog_queue.cancel();
+        let mut comm = communicator_clone.lock().unwrap();
+        if comm.is_connected() {
+            // Jog Cancel realtime command
+            if let Err(e) = comm.send(&[0x85]) {
+                warn!("Failed to send jog cancel: {}", e);
             }
-
-            let console_output = console_manager_clone.get_output();
-            window.set_console_output(slint::SharedString::from(console_output));
         }
     });
 
-    // Set up machine-jog-a-positive callback
+    // Set up machine-jog-preset-selected callback
     let window_weak = main_window.as_weak();
-    let communicator_clone = communicator.clone();
-    let console_manager_clone = console_manager.clone();
-    main_window.on_machine_jog_a_positive(move |step_size: f32| {
-        if let Some(window) = window_weak.upgrade() {
-            let mut comm = communicator_clone.lock().unwrap();
-            if !comm.is_connected() {
-                warn!("Jog A+ failed: Device not connected");
-                console_manager_clone
-                    .add_message(DeviceMessageType::Error, "✗ Device not connected.");
-            } else {
-                // Send jog command in relative mode (G91) for incremental movement
-                let jog_cmd = format!("$J=G91 A{} F2000", step_size);
-                console_manager_clone.add_message(
-                    DeviceMessageType::Output,
-                    format!("Jogging A+ ({} deg)...", step_size),
-                );
-
-                match comm.send(format!("{}\n", jog_cmd).as_bytes()) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        warn!("Failed to send Jog A+ command: {}", e);
-                        console_manager_clone.add_message(
-                            DeviceMessageType::Error,
-                            format!("✗ Jog A+ failed: {}", e),
-                        );
-                    }
-                }
-            }
-
-            let console_output = console_manager_clone.get_output();
-            window.set_console_output(slint::SharedString::from(console_output));
+    let settings_persistence_clone = settings_persistence.clone();
+    main_window.on_machine_jog_preset_selected(move |index: i32| {
+        let mut persistence = settings_persistence_clone.borrow_mut();
+        let Ok(index) = usize::try_from(index) else {
+            return;
+        };
+        if !persistence.config_mut().machine.jog_presets.select(index) {
+            return;
         }
-    });
 
-    // Set up machine-jog-a-negative callback
-    let window_weak = main_window.as_weak();
-    let communicator_clone = communicator.clone();
-    let console_manager_clone = console_manager.clone();
-    main_window.on_machine_jog_a_negative(move |step_size: f32| {
         if let Some(window) = window_weak.upgrade() {
-            let mut comm = communicator_clone.lock().unwrap();
-            if !comm.is_connected() {
-                warn!("Jog A- failed: Device not connected");
-                console_manager_clone
-                    .add_message(DeviceMessageType::Error, "✗ Device not connected.");
-            } else {
-                // Send jog command in relative mode (G91) for incremental movement
-                let jog_cmd = format!("$J=G91 A-{} F2000", step_size);
-                console_manager_clone.add_message(
-                    DeviceMessageType::Output,
-                    format!("Jogging A- ({} deg)...", step_size),
-                );
-
-                match comm.send(format!("{}\n", jog_cmd).as_bytes()) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        warn!("Failed to send Jog A- command: {}", e);
-                        console_manager_clone.add_message(
-                            DeviceMessageType::Error,
-                            format!("✗ Jog A- failed: {}", e),
-                        );
-                    }
-                }
-            }
-
-            let console_output = console_manager_clone.get_output();
-            window.set_console_output(slint::SharedString::from(console_output));
+            window.set_jog_preset_index(index as i32);
         }
-    });
-
-    // Set up machine-jog-b-positive callback
-    let window_weak = main_window.as_weak();
-    let communicator_clone = communicator.clone();
-    let console_manager_clone = console_manager.clone();
-    main_window.on_machine_jog_b_positive(move |step_size: f32| {
-        if let Some(window) = window_weak.upgrade() {
-            let mut comm = communicator_clone.lock().unwrap();
-            if !comm.is_connected() {
-                warn!("Jog B+ failed: Device not connected");
-                console_manager_clone
-                    .add_message(DeviceMessageType::Error, "✗ Device not connected.");
-            } else {
-                // Send jog command in relative mode (G91) for incremental movement
-                let jog_cmd = format!("$J=G91 B{} F2000", step_size);
-                console_manager_clone.add_message(
-                    DeviceMessageType::Output,
-                    format!("Jogging B+ ({} deg)...", step_size),
-                );
-
-                match comm.send(format!("{}\n", jog_cmd).as_bytes()) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        warn!("Failed to send Jog B+ command: {}", e);
-                        console_manager_clone.add_message(
-                            DeviceMessageType::Error,
-                            format!("✗ Jog B+ failed: {}", e),
-                        );
-                    }
-                }
-            }
 
-            let console_output = console_manager_clone.get_output();
-            window.set_console_output(slint::SharedString::from(console_output));
+        let saved = SettingsManager::config_file_path().and_then(|config_path| {
+            SettingsManager::ensure_config_dir()?;
+            persistence.save_to_file(&config_path)
+        });
+        if let Err(e) = saved {
+            warn!("Failed to save jog preset: {}", e);
         }
     });
 
-    // Set up machine-jog-b-negative callback
-    let window_weak = main_window.as_weak();
-    let communicator_clone = communicator.clone();
-    let console_manager_clone = console_manager.clone();
-    main_window.on_machine_jog_b_negative(move |step_size: f32| {
-        if let Some(window) = window_weak.upgrade() {
-            let mut comm = communicator_clone.lock().unwrap();
-            if !comm.is_connected() {
-                warn!("Jog B- failed: Device not connected");
-                console_manager_clone
-                    .add_message(DeviceMessageType::Error, "✗ Device not connected.");
-            } else {
-                // Send jog command in relative mode (G91) for incremental movement
-                let jog_cmd = format!("$J=G91 B-{} F2000", step_size);
-                console_manager_clone.add_message(
-                    DeviceMessageType::Output,
-                    format!("Jogging B- ({} deg)...", step_size),
-                );
-
-                match comm.send(format!("{}\n", jog_cmd).as_bytes()) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        warn!("Failed to send Jog B- command: {}", e);
-                        console_manager_clone.add_message(
-                            DeviceMessageType::Error,
-                            format!("✗ Jog B- failed: {}", e),
-                        );
-                    }
-                }
-            }
-
-            let console_output = console_manager_clone.get_output();
-            window.set_console_output(slint::SharedString::from(console_output));
-        }
+    // Set up machine-cut-factor-changed callback
+    let gcode_send_state_clone = gcode_send_state.clone();
+    main_window.on_machine_cut_factor_changed(move |percent: i32| {
+        gcode_send_state_clone
+            .lock()
+            .unwrap()
+            .set_cut_factor(f64::from(percent) / 100.0);
     });
 
     // Set up machine-unlock callback
@@ -2037,6 +1963,7 @@ fn main() -> anyhow::Result<()> {
     // Set up refresh-visualization callback
     let window_weak = main_window.as_weak();
     let visualizer_refresh = visualizer.clone();
+    let designer_mgr_refresh = designer_mgr.clone();
     main_window.on_refresh_visualization(move |width, height, max_intensity| {
         if let Some(window) = window_weak.upgrade() {
             let gcode = window.get_gcode_content();
@@ -2044,6 +1971,10 @@ fn main() -> anyhow::Result<()> {
             
             // Parse G-code
             vis.parse_gcode(&gcode);
+
+            // Size the position marker to the designer's tool
+            let tool_diameter = designer_mgr_refresh.borrow().toolpath_generator.tool_diameter();
+            vis.set_tool_diameter(tool_diameter as f32);
             
             // Update toolpath data
             window.set_visualization_path_data(vis.toolpath_svg().into());
@@ -2076,6 +2007,7 @@ fn main() -> anyhow::Result<()> {
             // Update status
             window.set_visualizer_status("Ready".into());
             window.set_visualizer_zoom_scale(vis.zoom_scale);
+            window.set_visualizer_tool_marker_radius(vis.tool_marker_screen_radius());
             window.set_visualizer_x_offset(vis.x_offset);
             window.set_visualizer_y_offset(vis.y_offset);
             
@@ -2109,6 +2041,7 @@ fn main() -> anyhow::Result<()> {
             window.set_visualization_origin_data(origin.into());
             
             window.set_visualizer_zoom_scale(vis.zoom_scale);
+            window.set_visualizer_tool_marker_radius(vis.tool_marker_screen_radius());
         }
     });
 
@@ -2136,6 +2069,7 @@ fn main() -> anyhow::Result<()> {
             window.set_visualization_origin_data(origin.into());
             
             window.set_visualizer_zoom_scale(vis.zoom_scale);
+            window.set_visualizer_tool_marker_radius(vis.tool_marker_screen_radius());
         }
     });
 
@@ -2165,6 +2099,7 @@ fn main() -> anyhow::Result<()> {
             window.set_visualization_origin_data(origin.into());
             
             window.set_visualizer_zoom_scale(vis.zoom_scale);
+            window.set_visualizer_tool_marker_radius(vis.tool_marker_screen_radius());
             window.set_visualizer_x_offset(vis.x_offset);
             window.set_visualizer_y_offset(vis.y_offset);
         }
@@ -2194,6 +2129,7 @@ fn main() -> anyhow::Result<()> {
             window.set_visualization_origin_data(origin.into());
             
             window.set_visualizer_zoom_scale(vis.zoom_scale);
+            window.set_visualizer_tool_marker_radius(vis.tool_marker_screen_radius());
             window.set_visualizer_x_offset(vis.x_offset);
             window.set_visualizer_y_offset(vis.y_offset);
         }
@@ -3065,25 +3001,6 @@ fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
-/// Get list of available serial ports
-fn get_available_ports() -> anyhow::Result<Vec<slint::SharedString>> {
-    match list_ports() {
-        Ok(ports) => {
-            let port_names: Vec<slint::SharedString> = ports
-                .iter()
-                .map(|p| slint::SharedString::from(p.port_name.clone()))
-                .collect();
-
-            if port_names.is_empty() {
-                Ok(vec![slint::SharedString::from("No ports available")])
-            } else {
-                Ok(port_names)
-            }
-        }
-        Err(_) => Ok(vec![slint::SharedString::from("Error reading ports")]),
-    }
-}
-
 /// Render G-code visualization in background thread using message passing
 #[allow(dead_code)]
 fn render_gcode_visualization_background_channel(
@@ -0,0 +1,26 @@
+//! Program preprocessing from device database sender settings
+
+use gcodekit4_devicedb::SenderConfig;
+use gcodekit4_visualizer::{ProcessorPipeline, ProcessorRegistry};
+
+/// Build the preprocessing pipeline for the active profile's recipe
+///
+/// Returns `None` when the profile has no recipe, or an error naming an
+/// unknown processor.
+pub fn recipe_pipeline(config: &SenderConfig) -> Result<Option<ProcessorPipeline>, String> {
+    config
+        .recipe
+        .as_deref()
+        .map(|recipe| ProcessorRegistry::with_builtins().create_recipe(recipe))
+        .transpose()
+}
+
+/// Run a program through the active profile's preprocessing recipe
+///
+/// The program is returned unchanged when the profile has no recipe.
+pub fn preprocess_program(config: &SenderConfig, program: &str) -> Result<String, String> {
+    match recipe_pipeline(config)? {
+        Some(pipeline) => pipeline.process_text(program),
+        None => Ok(program.to_string()),
+    }
+}
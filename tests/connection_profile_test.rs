@@ -0,0 +1,169 @@
+use gcodekit4::connection::{command_dialect, connection_params, panel_connection_params};
+use gcodekit4::{Communicator, CommunicatorListenerHandle, ConnectionDriver, ConnectionParams};
+use gcodekit4_devicedb::{ConnectionProfile, ControllerType, DeviceManager, DeviceProfile};
+use tempfile::tempdir;
+
+#[test]
+fn test_connection_params_from_network_profile() {
+    let profile = ConnectionProfile {
+        connection_type: "TCP".to_string(),
+        port: "cnc.local".to_string(),
+        tcp_port: 2323,
+        dtr_on_open: Some(false),
+        ..Default::default()
+    };
+
+    let params = connection_params(&profile);
+    assert_eq!(params.driver, ConnectionDriver::Tcp);
+    assert_eq!(params.port, "cnc.local");
+    assert_eq!(params.network_port, 2323);
+    assert_eq!(params.dtr_on_open, Some(false));
+}
+
+#[test]
+fn test_connection_params_follow_active_profile() {
+    let dir = tempdir().unwrap();
+    let manager = DeviceManager::new(dir.path().join("devices.json"));
+    manager.load().unwrap();
+
+    let router = DeviceProfile {
+        name: "Router".to_string(),
+        connection_type: "Serial".to_string(),
+        port: "/dev/ttyACM0".to_string(),
+        baud_rate: 250000,
+        ..Default::default()
+    };
+    manager.save_profile(router.clone()).unwrap();
+
+    let laser = DeviceProfile {
+        name: "Laser".to_string(),
+        connection_type: "TCP".to_string(),
+        port: "laser.local".to_string(),
+        ..Default::default()
+    };
+    manager.save_profile(laser.clone()).unwrap();
+
+    let connect = |manager: &DeviceManager| {
+        panel_connection_params(manager.active_connection().as_ref(), "/dev/ttyUSB0", 115200)
+    };
+
+    manager.set_active_profile(&router.id).unwrap();
+    let params = connect(&manager);
+    assert_eq!(params.driver, ConnectionDriver::Serial);
+    assert_eq!(params.port, "/dev/ttyACM0");
+    assert_eq!(params.baud_rate, 250000);
+
+    manager.set_active_profile(&laser.id).unwrap();
+    let params = connect(&manager);
+    assert_eq!(params.driver, ConnectionDriver::Tcp);
+    assert_eq!(params.port, "laser.local");
+}
+
+#[test]
+fn test_panel_port_fills_in_for_auto_profile() {
+    let profile = ConnectionProfile::default();
+    assert_eq!(profile.port, "Auto");
+    let params = panel_connection_params(Some(&profile), "COM4", 9600);
+    assert_eq!(params.port, "COM4");
+    assert_eq!(params.baud_rate, profile.baud_rate);
+
+    let params = panel_connection_params(None, "COM4", 9600);
+    assert_eq!(params.driver, ConnectionDriver::Serial);
+    assert_eq!(params.port, "COM4");
+    assert_eq!(params.baud_rate, 9600);
+}
+
+#[test]
+fn test_panel_connect_holds_control_lines_for_no_reset_device() {
+    let dir = tempdir().unwrap();
+    let manager = DeviceManager::new(dir.path().join("devices.json"));
+    manager.load().unwrap();
+
+    manager.set_no_reset_on_open(true).unwrap();
+    let params = panel_connection_params(manager.active_connection().as_ref(), "COM4", 9600);
+    assert!(params.is_no_reset());
+
+    manager.set_no_reset_on_open(false).unwrap();
+    let params = panel_connection_params(manager.active_connection().as_ref(), "COM4", 9600);
+    assert!(!params.is_no_reset());
+}
+
+/// Communicator that records every byte written to it
+#[derive(Default)]
+struct RecordingCommunicator {
+    sent: Vec<u8>,
+}
+
+impl Communicator for RecordingCommunicator {
+    fn connect(&mut self, _params: &ConnectionParams) -> gcodekit4::Result<()> {
+        Ok(())
+    }
+
+    fn disconnect(&mut self) -> gcodekit4::Result<()> {
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        true
+    }
+
+    fn send(&mut self, data: &[u8]) -> gcodekit4::Result<usize> {
+        self.sent.extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    fn receive(&mut self) -> gcodekit4::Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn add_listener(&mut self, _listener: CommunicatorListenerHandle) {}
+
+    fn remove_listener(&mut self, _listener: &CommunicatorListenerHandle) {}
+
+    fn connection_params(&self) -> Option<&ConnectionParams> {
+        None
+    }
+
+    fn set_connection_params(&mut self, _params: ConnectionParams) -> gcodekit4::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_emergency_stop_follows_active_profile_controller() {
+    let dir = tempdir().unwrap();
+    let manager = DeviceManager::new(dir.path().join("devices.json"));
+    manager.load().unwrap();
+
+    let printer = DeviceProfile {
+        name: "Printer".to_string(),
+        controller_type: ControllerType::Marlin,
+        ..Default::default()
+    };
+    manager.save_profile(printer.clone()).unwrap();
+
+    let router = DeviceProfile {
+        name: "Router".to_string(),
+        controller_type: ControllerType::Grbl,
+        ..Default::default()
+    };
+    manager.save_profile(router.clone()).unwrap();
+
+    let estop = |manager: &DeviceManager| {
+        let mut communicator = RecordingCommunicator::default();
+        command_dialect(manager.get_active_profile().as_ref())
+            .send_emergency_stop(&mut communicator)
+            .unwrap();
+        communicator.sent
+    };
+
+    manager.set_active_profile(&printer.id).unwrap();
+    assert_eq!(estop(&manager), b"M112\n");
+
+    manager.set_active_profile(&router.id).unwrap();
+    assert_eq!(estop(&manager), vec![0x18]);
+
+    let mut communicator = RecordingCommunicator::default();
+    command_dialect(None).send_emergency_stop(&mut communicator).unwrap();
+    assert_eq!(communicator.sent, vec![0x18]);
+}
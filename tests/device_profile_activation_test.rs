@@ -0,0 +1,71 @@
+use gcodekit4::sender::preprocess_program;
+use gcodekit4::JogPresets;
+use gcodekit4_devicedb::{DeviceManager, DeviceProfile};
+use tempfile::tempdir;
+
+fn process(manager: &DeviceManager, program: &str) -> String {
+    preprocess_program(&manager.sender_config(), program).unwrap()
+}
+
+#[test]
+fn test_profile_activation_changes_pipeline_and_jog_step() {
+    let dir = tempdir().unwrap();
+    let manager = DeviceManager::new(dir.path().join("devices.json"));
+    manager.load().unwrap();
+
+    let router = DeviceProfile {
+        name: "Router".to_string(),
+        default_recipe: "whitespace, comment, trailing_zeros".to_string(),
+        jog_step_size: 10.0,
+        ..Default::default()
+    };
+    manager.save_profile(router.clone()).unwrap();
+
+    let laser = DeviceProfile {
+        name: "Laser".to_string(),
+        jog_step_size: 0.1,
+        ..Default::default()
+    };
+    manager.save_profile(laser.clone()).unwrap();
+
+    let program = "G1 X1.500 ; cut\nG0 Z5.0";
+    let mut presets = JogPresets::default();
+
+    manager.set_active_profile(&router.id).unwrap();
+    assert_eq!(process(&manager, program), "G1 X1.5\nG0 Z5");
+    presets.select_distance(manager.sender_config().jog_step_size);
+    assert_eq!(presets.jog_command('X', true, 1000.0), "$J=G91 X10 F1000");
+
+    manager.set_active_profile(&laser.id).unwrap();
+    assert_eq!(process(&manager, program), program);
+    presets.select_distance(manager.sender_config().jog_step_size);
+    assert_eq!(presets.jog_command('X', true, 1000.0), "$J=G91 X0.1 F1000");
+
+    // Profile defaults pick from the existing presets without adding to them
+    let laser_fine = DeviceProfile {
+        jog_step_size: 0.2,
+        ..Default::default()
+    };
+    manager.save_profile(laser_fine.clone()).unwrap();
+    manager.set_active_profile(&laser_fine.id).unwrap();
+    presets.select_distance(manager.sender_config().jog_step_size);
+    assert_eq!(presets.jog_command('X', true, 1000.0), "$J=G91 X0.1 F1000");
+    assert_eq!(presets.steps, JogPresets::default().steps);
+}
+
+#[test]
+fn test_unknown_recipe_fails_preprocessing() {
+    let dir = tempdir().unwrap();
+    let manager = DeviceManager::new(dir.path().join("devices.json"));
+    manager.load().unwrap();
+
+    let broken = DeviceProfile {
+        default_recipe: "whitespace, no_such_processor".to_string(),
+        ..Default::default()
+    };
+    manager.save_profile(broken.clone()).unwrap();
+    manager.set_active_profile(&broken.id).unwrap();
+
+    let err = preprocess_program(&manager.sender_config(), "G0 X1").unwrap_err();
+    assert!(err.contains("no_such_processor"), "{}", err);
+}